@@ -0,0 +1,127 @@
+//! Times lexing and parsing about 1 MB of PHP, made of the fixtures that parse,
+//! with `cargo bench --bench parse`. `skip` parses it with
+//! [`ParserConfig::skip_bodies`], which only saves the time of the code in functions,
+//! methods and closures.
+//!
+//! Borrowing token values from the source, rather than copying each of them, took the
+//! best of `tokenize` from 49 ms to 28 ms and of `parse` from 285 ms to 206 ms, on a
+//! single machine with the fixtures of the time.
+
+use std::env;
+use std::fs::read_dir;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+use pxp_parser::lexer::Lexer;
+use pxp_parser::ParserConfig;
+
+const SIZE: usize = 1024 * 1024;
+const RUNS: u32 = 20;
+
+/// Each fixture in a namespace of its own, after a single opening tag, so that the
+/// names they declare don't clash.
+fn namespaced(fixtures: &[&[u8]]) -> Vec<u8> {
+    let mut source = b"<?php".to_vec();
+    for (namespace, fixture) in fixtures.iter().enumerate() {
+        source.extend_from_slice(format!("\nnamespace N{} {{", namespace).as_bytes());
+        source.extend_from_slice(fixture);
+        source.extend_from_slice(b"\n}\n");
+    }
+
+    source
+}
+
+/// The fixtures, repeated until there is about [`SIZE`] of code. A fixture is left out
+/// when it doesn't parse after the ones that are kept, e.g. as it declares a namespace
+/// of its own or a label that is already defined, or when it ends the code early.
+fn source() -> Vec<u8> {
+    let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let mut entries = read_dir(manifest.join("tests/fixtures"))
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.path().join("code.php"))
+        .filter(|code| code.with_file_name("ast.txt").exists())
+        .collect::<Vec<PathBuf>>();
+
+    entries.sort();
+
+    let codes = entries
+        .iter()
+        .filter_map(|code| std::fs::read(code).ok())
+        .collect::<Vec<Vec<u8>>>();
+
+    let mut fixtures: Vec<&[u8]> = vec![];
+    for code in &codes {
+        let Some(body) = code.strip_prefix(b"<?php") else {
+            continue;
+        };
+
+        if [&b"__halt_compiler"[..], b"?>"]
+            .iter()
+            .any(|word| body.windows(word.len()).any(|window| window == *word))
+        {
+            continue;
+        }
+
+        // Twice, as the fixtures are repeated.
+        fixtures.push(body);
+        let twice = [&fixtures[..], &fixtures[..]].concat();
+        if pxp_parser::parse(&namespaced(&twice)).is_err() {
+            fixtures.pop();
+        }
+    }
+
+    let mut source = vec![];
+    let mut repeated = vec![];
+    while source.len() < SIZE {
+        repeated.extend_from_slice(&fixtures);
+        source = namespaced(&repeated);
+    }
+
+    source
+}
+
+fn time<T>(name: &str, size: usize, mut run: impl FnMut() -> T) {
+    let mut best = Duration::MAX;
+    let mut total = Duration::ZERO;
+
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        black_box(run());
+        let elapsed = start.elapsed();
+
+        best = best.min(elapsed);
+        total += elapsed;
+    }
+
+    println!(
+        "{:<10} best {:>8.2?}, mean {:>8.2?}, {:>7.1} MB/s",
+        name,
+        best,
+        total / RUNS,
+        size as f64 / best.as_secs_f64() / 1_000_000.0
+    );
+}
+
+fn main() {
+    let source = source();
+    let tokens = Lexer::new().tokenize(&source).unwrap();
+    if let Err(stack) = pxp_parser::construct(&tokens) {
+        panic!("the benchmark source doesn't parse: {:?}", stack.errors[0]);
+    }
+
+    println!("{} bytes, {} tokens", source.len(), tokens.len());
+
+    time("tokenize", source.len(), || {
+        Lexer::new().tokenize(&source).unwrap()
+    });
+    time("construct", source.len(), || {
+        pxp_parser::construct(&tokens).map(|program| program.len())
+    });
+    time("parse", source.len(), || pxp_parser::parse(&source).is_ok());
+    time("skip", source.len(), || {
+        pxp_parser::parse_with_config(&source, ParserConfig::new().skip_bodies(true)).is_ok()
+    });
+}
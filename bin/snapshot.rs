@@ -4,6 +4,7 @@ use std::io;
 use std::path::PathBuf;
 
 use pxp_parser::parse;
+use pxp_parser::sexpr::ToSExpr;
 
 fn main() -> io::Result<()> {
     let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
@@ -18,6 +19,7 @@ fn main() -> io::Result<()> {
     for entry in entries {
         let mut code_filename = entry.join("code.php");
         let ast_filename = entry.join("ast.txt");
+        let sexpr_filename = entry.join("ast.sexpr");
         let error_filename = entry.join("error.txt");
 
         if !code_filename.exists() {
@@ -28,17 +30,28 @@ fn main() -> io::Result<()> {
             continue;
         }
 
+        // Fixtures using the s-expression format keep using it.
+        let sexpr = sexpr_filename.exists();
+
         if ast_filename.exists() {
             std::fs::remove_file(&ast_filename)?;
         }
 
+        if sexpr {
+            std::fs::remove_file(&sexpr_filename)?;
+        }
+
         if error_filename.exists() {
             std::fs::remove_file(&error_filename)?;
         }
 
-        let code = std::fs::read_to_string(&code_filename)?;
+        let code = std::fs::read(&code_filename)?;
 
         match parse(&code) {
+            Ok(ast) if sexpr => {
+                std::fs::write(sexpr_filename, format!("{}\n", ast.to_sexpr()))?;
+                println!("✅ generated `ast.sexpr` for `{}`", entry.to_string_lossy());
+            }
             Ok(ast) => {
                 std::fs::write(ast_filename, format!("{:#?}\n", ast))?;
                 println!("✅ generated `ast.txt` for `{}`", entry.to_string_lossy());
@@ -46,7 +59,15 @@ fn main() -> io::Result<()> {
             Err(error) => {
                 std::fs::write(
                     error_filename,
-                    format!("{}\n", error.report(&code, Some("code.php"), false, true)?),
+                    format!(
+                        "{}\n",
+                        error.report(
+                            &String::from_utf8_lossy(&code),
+                            Some("code.php"),
+                            false,
+                            true
+                        )?
+                    ),
                 )?;
 
                 println!("✅ generated `error.txt` for `{}`", entry.to_string_lossy());
@@ -0,0 +1,69 @@
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use pxp_parser::InlineHtmlMode;
+use pxp_parser::ParserConfig;
+
+/// Counts the bytes that are allocated and not yet freed.
+struct Counter;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counter = Counter;
+
+/// A template with a lot more HTML than PHP.
+fn template(rows: usize) -> String {
+    let mut code = String::from("<!DOCTYPE html>\n<html>\n<body>\n<table>\n");
+
+    for row in 0..rows {
+        code.push_str(&format!(
+            "    <tr class=\"row row-{}\">\n        <td class=\"name\"><?= $rows[{}]['name'] ?></td>\n        <td class=\"description\">A description that takes up some room.</td>\n    </tr>\n",
+            row, row
+        ));
+    }
+
+    code.push_str("</table>\n</body>\n</html>\n");
+
+    code
+}
+
+fn main() {
+    let code = template(10_000);
+    println!("template: {} bytes", code.len());
+
+    for mode in [
+        InlineHtmlMode::Retain,
+        InlineHtmlMode::SpanOnly,
+        InlineHtmlMode::Hash,
+    ] {
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        let program =
+            pxp_parser::parse_with_config(&code, ParserConfig::new().inline_html(mode)).unwrap();
+        let retained = ALLOCATED.load(Ordering::Relaxed) - before;
+
+        println!(
+            "{:?}: {} statements, {} bytes retained",
+            mode,
+            program.len(),
+            retained
+        );
+    }
+}
@@ -0,0 +1,651 @@
+//! The values of the arguments of attributes, see [`evaluate_attribute_args`].
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::arguments::Argument;
+use crate::parser::ast::arguments::ArgumentList;
+use crate::parser::ast::attributes::Attribute;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::literals::Literal;
+use crate::parser::ast::operators::ArithmeticOperationExpression;
+use crate::parser::ast::operators::BitwiseOperationExpression;
+use crate::parser::ast::ArrayItem;
+use crate::parser::ast::Expression;
+use crate::parser::ast::MagicConstantExpression;
+use crate::parser::symbols::SymbolKind;
+use crate::spans::bounds;
+
+use super::integer_string;
+use super::names::ResolvedNames;
+
+/// The value of a constant expression, as far as it can be known without running code.
+///
+/// Enum cases and class constants are left symbolic, as their values are declared
+/// elsewhere, and so are the objects created with `new`.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub enum AttrValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(ByteString),
+    /// The entries of an array, as pairs of keys and values in order.
+    Array(Vec<(AttrValue, AttrValue)>),
+    /// `Suit::Hearts`, where `Suit` is known to be an enum.
+    EnumCase {
+        class: ByteString,
+        case: ByteString,
+    },
+    /// `Foo::BAR`, where `Foo` isn't known to be an enum.
+    ClassConst {
+        class: ByteString,
+        constant: ByteString,
+    },
+    /// `new Foo(...)`, with the values of its arguments.
+    New {
+        class: ByteString,
+        args: Vec<(Option<ByteString>, AttrValue)>,
+    },
+    /// An expression whose value can't be known, starting at `span`.
+    Unevaluable {
+        span: Span,
+    },
+}
+
+/// Evaluate the arguments of an attribute, in order, with their names if they are
+/// named.
+///
+/// Literals, arrays and the operators of arithmetic, bitwise operations and
+/// concatenation are evaluated the way PHP does, with arrays numbered as of PHP 8.3.
+/// Class names are resolved with `names`, which tells enums from other classes.
+/// `Foo::class` is the name of the class, `Foo::BAR` is an [`AttrValue::EnumCase`] if
+/// `names` knows that `Foo` is an enum, as constants of enums are fetched the same way,
+/// and an [`AttrValue::ClassConst`] otherwise. An expression that uses anything else,
+/// such as a global constant, or that would fail, such as a division by zero, is
+/// [`AttrValue::Unevaluable`].
+pub fn evaluate_attribute_args(
+    attribute: &Attribute,
+    names: &ResolvedNames,
+) -> Vec<(Option<ByteString>, AttrValue)> {
+    match &attribute.arguments {
+        Some(arguments) => Evaluator { names, file: None }.arguments(arguments),
+        None => vec![],
+    }
+}
+
+/// The value of a constant expression, evaluated like the arguments of attributes.
+pub(super) fn evaluate(expression: &Expression, names: &ResolvedNames) -> AttrValue {
+    Evaluator { names, file: None }.value(expression)
+}
+
+/// The value of a constant expression in the file named `file`, which `__FILE__` and
+/// `__DIR__` evaluate to the name and the directory of.
+pub(super) fn evaluate_in(
+    expression: &Expression,
+    names: &ResolvedNames,
+    file: Option<&Path>,
+) -> AttrValue {
+    Evaluator { names, file }.value(expression)
+}
+
+struct Evaluator<'a> {
+    names: &'a ResolvedNames,
+    /// The name of the file, if it is known.
+    file: Option<&'a Path>,
+}
+
+impl Evaluator<'_> {
+    fn arguments(&self, arguments: &ArgumentList) -> Vec<(Option<ByteString>, AttrValue)> {
+        arguments
+            .iter()
+            .map(|argument| match argument {
+                Argument::Positional(argument) => (
+                    None,
+                    match argument.ellipsis {
+                        Some(ellipsis) => AttrValue::Unevaluable { span: ellipsis },
+                        None => self.value(&argument.value),
+                    },
+                ),
+                Argument::Named(argument) => (
+                    Some(argument.name.value.clone()),
+                    match argument.ellipsis {
+                        Some(ellipsis) => AttrValue::Unevaluable { span: ellipsis },
+                        None => self.value(&argument.value),
+                    },
+                ),
+            })
+            .collect()
+    }
+
+    fn value(&self, expression: &Expression) -> AttrValue {
+        self.evaluate(expression)
+            .unwrap_or_else(|| AttrValue::Unevaluable {
+                span: bounds(&[expression]).0,
+            })
+    }
+
+    fn evaluate(&self, expression: &Expression) -> Option<AttrValue> {
+        Some(match expression {
+            Expression::Null(_) => AttrValue::Null,
+            Expression::Bool(bool) => AttrValue::Bool(bool.value),
+            Expression::Literal(Literal::Integer(integer)) => AttrValue::Int(integer.to_i64()?),
+            Expression::Literal(Literal::Float(float)) => {
+                let digits = float.value.iter().filter(|byte| **byte != b'_');
+
+                AttrValue::Float(
+                    String::from_utf8(digits.copied().collect())
+                        .ok()?
+                        .parse()
+                        .ok()?,
+                )
+            }
+            Expression::Literal(Literal::String(string)) => {
+                AttrValue::Str(string.value[1..string.value.len() - 1].into())
+            }
+            Expression::Nowdoc(nowdoc) => AttrValue::Str(nowdoc.value.clone()),
+            Expression::MagicConstant(MagicConstantExpression::File(_)) => {
+                AttrValue::Str(self.file?.to_string_lossy().as_bytes().into())
+            }
+            Expression::MagicConstant(MagicConstantExpression::Directory(_)) => {
+                AttrValue::Str(directory(self.file?).as_bytes().into())
+            }
+            Expression::Parenthesized(parenthesized) => self.evaluate(&parenthesized.expr)?,
+            Expression::ShortArray(array) => self.array(array.items.iter())?,
+            Expression::Array(array) => self.array(array.items.iter())?,
+            Expression::ConstantFetch(fetch) => {
+                let Identifier::SimpleIdentifier(constant) = &fetch.constant else {
+                    return None;
+                };
+                let (class, resolved) = self.class(&fetch.target)?;
+
+                if constant.value.eq_ignore_ascii_case(b"class") {
+                    // `static::class` is only known at runtime.
+                    return resolved.then_some(AttrValue::Str(class));
+                }
+
+                let is_enum = resolved
+                    && self
+                        .names
+                        .declaration(&class)
+                        .is_some_and(|(_, kind)| kind == SymbolKind::Enum);
+
+                if is_enum {
+                    AttrValue::EnumCase {
+                        class,
+                        case: constant.value.clone(),
+                    }
+                } else {
+                    AttrValue::ClassConst {
+                        class,
+                        constant: constant.value.clone(),
+                    }
+                }
+            }
+            Expression::New(new) => {
+                let (class, true) = self.class(&new.target)? else {
+                    return None;
+                };
+
+                AttrValue::New {
+                    class,
+                    args: new
+                        .arguments
+                        .as_ref()
+                        .map(|arguments| self.arguments(arguments))
+                        .unwrap_or_default(),
+                }
+            }
+            Expression::ArithmeticOperation(operation) => self.arithmetic(operation)?,
+            Expression::BitwiseOperation(operation) => self.bitwise(operation)?,
+            Expression::Concat(concat) => {
+                let mut string = self.string(&concat.left)?;
+                string.extend(self.string(&concat.right)?);
+
+                AttrValue::Str(string.into())
+            }
+            Expression::ConcatMany(concat) => {
+                let mut string = vec![];
+                for part in &concat.parts {
+                    string.extend(self.string(part)?);
+                }
+
+                AttrValue::Str(string.into())
+            }
+            _ => return None,
+        })
+    }
+
+    /// The name of the class on the left of `::` or after `new`, and whether it is
+    /// resolved, which `self`, `static` and `parent` aren't.
+    fn class(&self, target: &Expression) -> Option<(ByteString, bool)> {
+        match target {
+            Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => {
+                match self.names.resolve(identifier.span) {
+                    Some(name) => Some((name.clone(), true)),
+                    None => Some((identifier.value.clone(), false)),
+                }
+            }
+            Expression::Self_(_) => Some(("self".into(), false)),
+            Expression::Static(_) => Some(("static".into(), false)),
+            Expression::Parent(_) => Some(("parent".into(), false)),
+            _ => None,
+        }
+    }
+
+    fn array<'a>(&self, items: impl Iterator<Item = &'a ArrayItem>) -> Option<AttrValue> {
+        let mut array = Array::default();
+
+        for item in items {
+            match item {
+                ArrayItem::Skipped => {}
+                ArrayItem::Value { value } => array.push(None, self.value(value)),
+                ArrayItem::KeyValue { key, value, .. } => {
+                    array.push(Some(self.evaluate(key)?), self.value(value))
+                }
+                ArrayItem::SpreadValue { value, .. } => {
+                    let AttrValue::Array(entries) = self.evaluate(value)? else {
+                        return None;
+                    };
+
+                    // Integer keys are numbered again, string keys are kept.
+                    for (key, value) in entries {
+                        match key {
+                            AttrValue::Int(_) => array.push(None, value),
+                            key => array.push(Some(key), value),
+                        }
+                    }
+                }
+                ArrayItem::ReferencedValue { .. } | ArrayItem::ReferencedKeyValue { .. } => {
+                    return None
+                }
+            }
+        }
+
+        Some(AttrValue::Array(array.entries))
+    }
+
+    fn arithmetic(&self, operation: &ArithmeticOperationExpression) -> Option<AttrValue> {
+        let (left, right) = match operation {
+            ArithmeticOperationExpression::Negative { right, .. } => {
+                return match self.evaluate(right)? {
+                    AttrValue::Int(value) => Some(match value.checked_neg() {
+                        Some(value) => AttrValue::Int(value),
+                        None => AttrValue::Float(-(value as f64)),
+                    }),
+                    AttrValue::Float(value) => Some(AttrValue::Float(-value)),
+                    _ => None,
+                }
+            }
+            ArithmeticOperationExpression::Positive { right, .. } => {
+                return match self.evaluate(right)? {
+                    value @ (AttrValue::Int(_) | AttrValue::Float(_)) => Some(value),
+                    _ => None,
+                }
+            }
+            ArithmeticOperationExpression::Addition { left, right, .. }
+            | ArithmeticOperationExpression::Subtraction { left, right, .. }
+            | ArithmeticOperationExpression::Multiplication { left, right, .. }
+            | ArithmeticOperationExpression::Division { left, right, .. }
+            | ArithmeticOperationExpression::Modulo { left, right, .. }
+            | ArithmeticOperationExpression::Exponentiation { left, right, .. } => {
+                (self.evaluate(left)?, self.evaluate(right)?)
+            }
+            _ => return None,
+        };
+
+        let integers = match (&left, &right) {
+            (AttrValue::Int(left), AttrValue::Int(right)) => Some((*left, *right)),
+            _ => None,
+        };
+        let (left, right) = (number(&left)?, number(&right)?);
+
+        // Integers that overflow become floats.
+        Some(match operation {
+            ArithmeticOperationExpression::Addition { .. } => {
+                integer_or_float(integers.and_then(|(a, b)| a.checked_add(b)), left + right)
+            }
+            ArithmeticOperationExpression::Subtraction { .. } => {
+                integer_or_float(integers.and_then(|(a, b)| a.checked_sub(b)), left - right)
+            }
+            ArithmeticOperationExpression::Multiplication { .. } => {
+                integer_or_float(integers.and_then(|(a, b)| a.checked_mul(b)), left * right)
+            }
+            ArithmeticOperationExpression::Division { .. } => {
+                if right == 0.0 {
+                    return None;
+                }
+
+                let exact = integers
+                    .filter(|(a, b)| a.checked_rem(*b) == Some(0))
+                    .and_then(|(a, b)| a.checked_div(b));
+
+                integer_or_float(exact, left / right)
+            }
+            ArithmeticOperationExpression::Modulo { .. } => {
+                let (a, b) = integers?;
+                if b == 0 {
+                    return None;
+                }
+
+                // `PHP_INT_MIN % -1` is 0.
+                AttrValue::Int(a.checked_rem(b).unwrap_or(0))
+            }
+            _ => {
+                let power = integers.and_then(|(a, b)| a.checked_pow(u32::try_from(b).ok()?));
+
+                integer_or_float(power, left.powf(right))
+            }
+        })
+    }
+
+    fn bitwise(&self, operation: &BitwiseOperationExpression) -> Option<AttrValue> {
+        let (left, right) = match operation {
+            BitwiseOperationExpression::Not { right, .. } => {
+                let AttrValue::Int(value) = self.evaluate(right)? else {
+                    return None;
+                };
+
+                return Some(AttrValue::Int(!value));
+            }
+            BitwiseOperationExpression::And { left, right, .. }
+            | BitwiseOperationExpression::Or { left, right, .. }
+            | BitwiseOperationExpression::Xor { left, right, .. }
+            | BitwiseOperationExpression::LeftShift { left, right, .. }
+            | BitwiseOperationExpression::RightShift { left, right, .. } => {
+                (self.evaluate(left)?, self.evaluate(right)?)
+            }
+        };
+
+        let (AttrValue::Int(left), AttrValue::Int(right)) = (left, right) else {
+            return None;
+        };
+
+        Some(AttrValue::Int(match operation {
+            BitwiseOperationExpression::And { .. } => left & right,
+            BitwiseOperationExpression::Or { .. } => left | right,
+            BitwiseOperationExpression::Xor { .. } => left ^ right,
+            // Shifting by a negative number throws an `ArithmeticError`.
+            BitwiseOperationExpression::LeftShift { .. } => match u32::try_from(right).ok()? {
+                shift @ 0..=63 => left << shift,
+                _ => 0,
+            },
+            _ => match u32::try_from(right).ok()? {
+                shift @ 0..=63 => left >> shift,
+                _ => left >> 63,
+            },
+        }))
+    }
+
+    /// The value of an operand of `.`, as a string.
+    fn string(&self, expression: &Expression) -> Option<Vec<u8>> {
+        Some(match self.evaluate(expression)? {
+            AttrValue::Str(string) => string.to_vec(),
+            AttrValue::Int(value) => value.to_string().into_bytes(),
+            AttrValue::Bool(true) => b"1".to_vec(),
+            AttrValue::Bool(false) | AttrValue::Null => vec![],
+            // Floats are printed with a precision that depends on the configuration.
+            _ => return None,
+        })
+    }
+}
+
+/// The directory of a file, `.` for a file named without one.
+pub(super) fn directory(file: &Path) -> String {
+    match file.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => {
+            directory.to_string_lossy().into_owned()
+        }
+        _ => ".".to_string(),
+    }
+}
+
+/// The entries of an array being built, and the next integer key.
+#[derive(Default)]
+struct Array {
+    entries: Vec<(AttrValue, AttrValue)>,
+    next: Option<i64>,
+}
+
+impl Array {
+    fn push(&mut self, key: Option<AttrValue>, value: AttrValue) {
+        let key = match key.map(array_key) {
+            Some(key) => key,
+            None => AttrValue::Int(self.next.unwrap_or(0)),
+        };
+
+        if let AttrValue::Int(key) = key {
+            let after = key.saturating_add(1);
+            self.next = Some(self.next.map_or(after, |next| next.max(after)));
+        }
+
+        // A key that is already in the array keeps its place, with the new value.
+        let known = !matches!(
+            key,
+            AttrValue::Unevaluable { .. }
+                | AttrValue::EnumCase { .. }
+                | AttrValue::ClassConst { .. }
+        );
+        match self
+            .entries
+            .iter_mut()
+            .find(|(other, _)| known && *other == key)
+        {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key, value)),
+        }
+    }
+}
+
+/// The key that a value is converted to when it is used as one.
+fn array_key(key: AttrValue) -> AttrValue {
+    match key {
+        AttrValue::Str(string) => match integer_string(&string) {
+            Some(integer) => AttrValue::Int(integer),
+            None => AttrValue::Str(string),
+        },
+        AttrValue::Bool(bool) => AttrValue::Int(bool as i64),
+        AttrValue::Null => AttrValue::Str(ByteString::default()),
+        AttrValue::Float(float) if float.is_finite() => AttrValue::Int(float.trunc() as i64),
+        key => key,
+    }
+}
+
+fn number(value: &AttrValue) -> Option<f64> {
+    match value {
+        AttrValue::Int(value) => Some(*value as f64),
+        AttrValue::Float(value) => Some(*value),
+        _ => None,
+    }
+}
+
+fn integer_or_float(integer: Option<i64>, float: f64) -> AttrValue {
+    match integer {
+        Some(integer) => AttrValue::Int(integer),
+        None => AttrValue::Float(float),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::resolve_names;
+    use crate::parser::ast::namespaces::NamespaceStatement;
+    use crate::parser::ast::Statement;
+
+    /// The arguments of the first attribute of the class that `code` ends with.
+    fn evaluate(code: &str) -> Vec<(Option<ByteString>, AttrValue)> {
+        let program = crate::parse(code).unwrap();
+        let names = resolve_names(&program);
+        let statements = match program.last() {
+            Some(Statement::Namespace(NamespaceStatement::Unbraced(namespace))) => {
+                &namespace.statements
+            }
+            _ => &program,
+        };
+        let Some(Statement::Class(class)) = statements.last() else {
+            panic!("expected a class");
+        };
+
+        evaluate_attribute_args(&class.attributes[0].members[0], &names)
+    }
+
+    fn value(expression: &str) -> AttrValue {
+        evaluate(&format!("<?php #[A({})] class C {{}}", expression))
+            .remove(0)
+            .1
+    }
+
+    fn str(value: &str) -> AttrValue {
+        AttrValue::Str(value.into())
+    }
+
+    #[test]
+    fn test_route() {
+        let code = "<?php
+namespace App\\Http;
+
+enum Method { case GET; case POST; }
+
+#[Route(path: '/x', methods: [Method::GET, Method::POST], priority: 2 * 10)]
+class Controller {}";
+
+        let method = |case: &str| AttrValue::EnumCase {
+            class: "App\\Http\\Method".into(),
+            case: case.into(),
+        };
+
+        assert_eq!(
+            evaluate(code),
+            vec![
+                (Some("path".into()), str("/x")),
+                (
+                    Some("methods".into()),
+                    AttrValue::Array(vec![
+                        (AttrValue::Int(0), method("GET")),
+                        (AttrValue::Int(1), method("POST")),
+                    ])
+                ),
+                (Some("priority".into()), AttrValue::Int(20)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_positional_arguments() {
+        let code = "<?php
+use Symfony\\Validator as V;
+
+#[V\\Length(1, max: 0x10, message: \"too \" . 'long', strict: true, default: null)]
+class C {}";
+
+        assert_eq!(
+            evaluate(code),
+            vec![
+                (None, AttrValue::Int(1)),
+                (Some("max".into()), AttrValue::Int(16)),
+                (Some("message".into()), str("too long")),
+                (Some("strict".into()), AttrValue::Bool(true)),
+                (Some("default".into()), AttrValue::Null),
+            ]
+        );
+        assert_eq!(evaluate("<?php #[A] class C {}"), vec![]);
+    }
+
+    #[test]
+    fn test_classes() {
+        let code = "<?php
+namespace App;
+
+use Other\\Limit;
+
+#[A(new Limit(10, per: Limit::MINUTE), Limit::class, self::NAME)]
+class C {}";
+
+        assert_eq!(
+            evaluate(code),
+            vec![
+                (
+                    None,
+                    AttrValue::New {
+                        class: "Other\\Limit".into(),
+                        args: vec![
+                            (None, AttrValue::Int(10)),
+                            (
+                                Some("per".into()),
+                                AttrValue::ClassConst {
+                                    class: "Other\\Limit".into(),
+                                    constant: "MINUTE".into(),
+                                }
+                            ),
+                        ],
+                    }
+                ),
+                (None, str("Other\\Limit")),
+                (
+                    None,
+                    AttrValue::ClassConst {
+                        class: "self".into(),
+                        constant: "NAME".into(),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_array_keys() {
+        assert_eq!(
+            value("['a', '5' => 'b', 'c', 1.7 => 'd', true => 'e', null => 'f', ...['g', 'x' => 'h']]"),
+            AttrValue::Array(vec![
+                (AttrValue::Int(0), str("a")),
+                (AttrValue::Int(5), str("b")),
+                (AttrValue::Int(6), str("c")),
+                (AttrValue::Int(1), str("e")),
+                (str(""), str("f")),
+                (AttrValue::Int(7), str("g")),
+                (str("x"), str("h")),
+            ])
+        );
+        // Negative keys are followed by the next one, as of PHP 8.3.
+        assert_eq!(
+            value("[-5 => 'a', 'b']"),
+            AttrValue::Array(vec![
+                (AttrValue::Int(-5), str("a")),
+                (AttrValue::Int(-4), str("b")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(value("7 / 2"), AttrValue::Float(3.5));
+        assert_eq!(value("8 / 2"), AttrValue::Int(4));
+        assert_eq!(value("-(2 ** 3) % 5"), AttrValue::Int(-3));
+        assert_eq!(value("1 << 4 | 1"), AttrValue::Int(17));
+        assert_eq!(
+            value("9223372036854775807 + 1"),
+            AttrValue::Float(9223372036854775808.0)
+        );
+        assert_eq!(value("1_000.5"), AttrValue::Float(1000.5));
+    }
+
+    #[test]
+    fn test_unevaluable() {
+        let code = "<?php #[A(FOO, 1 / 0, ...$args)] class C {}";
+
+        assert_eq!(
+            evaluate(code)
+                .into_iter()
+                .map(|(_, value)| match value {
+                    AttrValue::Unevaluable { span } => span.column,
+                    _ => 0,
+                })
+                .collect::<Vec<_>>(),
+            vec![11, 16, 23]
+        );
+    }
+}
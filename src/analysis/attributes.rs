@@ -0,0 +1,588 @@
+//! The attribute classes of a program and where their attributes are used, see
+//! [`attribute_definitions`] and [`validate_attribute_targets`].
+
+use std::any::Any;
+use std::collections::BTreeMap;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::arguments::Argument;
+use crate::parser::ast::attributes::Attribute;
+use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::classes::AnonymousClassExpression;
+use crate::parser::ast::classes::ClassStatement;
+use crate::parser::ast::constant::ClassishConstant;
+use crate::parser::ast::enums::BackedEnumCase;
+use crate::parser::ast::enums::BackedEnumStatement;
+use crate::parser::ast::enums::UnitEnumCase;
+use crate::parser::ast::enums::UnitEnumStatement;
+use crate::parser::ast::functions::AbstractConstructor;
+use crate::parser::ast::functions::AbstractMethod;
+use crate::parser::ast::functions::ArrowFunctionExpression;
+use crate::parser::ast::functions::ClosureExpression;
+use crate::parser::ast::functions::ConcreteConstructor;
+use crate::parser::ast::functions::ConcreteMethod;
+use crate::parser::ast::functions::ConstructorParameter;
+use crate::parser::ast::functions::FunctionParameter;
+use crate::parser::ast::functions::FunctionStatement;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::interfaces::InterfaceStatement;
+use crate::parser::ast::literals::Literal;
+use crate::parser::ast::operators::BitwiseOperationExpression;
+use crate::parser::ast::properties::Property;
+use crate::parser::ast::properties::VariableProperty;
+use crate::parser::ast::traits::TraitStatement;
+use crate::parser::ast::Expression;
+use crate::parser::ast::Program;
+use crate::parser::error;
+use crate::parser::error::ParseError;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+use super::names::resolve_names;
+use super::names::ResolvedNames;
+
+/// The kind of declaration that an attribute is attached to, one of the `TARGET_*`
+/// constants of the `Attribute` class.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AttributeTarget {
+    /// A class, interface, trait or enum.
+    Class,
+    /// A function, closure or arrow function.
+    Function,
+    Method,
+    Property,
+    /// A constant of a class, interface, trait or enum, or a case of an enum.
+    ClassConstant,
+    Parameter,
+}
+
+impl AttributeTarget {
+    const ALL: [AttributeTarget; 6] = [
+        AttributeTarget::Class,
+        AttributeTarget::Function,
+        AttributeTarget::Method,
+        AttributeTarget::Property,
+        AttributeTarget::ClassConstant,
+        AttributeTarget::Parameter,
+    ];
+
+    /// The value of the `Attribute::TARGET_*` constant.
+    pub const fn flag(self) -> u8 {
+        match self {
+            AttributeTarget::Class => AttributeDefinition::TARGET_CLASS,
+            AttributeTarget::Function => AttributeDefinition::TARGET_FUNCTION,
+            AttributeTarget::Method => AttributeDefinition::TARGET_METHOD,
+            AttributeTarget::Property => AttributeDefinition::TARGET_PROPERTY,
+            AttributeTarget::ClassConstant => AttributeDefinition::TARGET_CLASS_CONSTANT,
+            AttributeTarget::Parameter => AttributeDefinition::TARGET_PARAMETER,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            AttributeTarget::Class => "class",
+            AttributeTarget::Function => "function",
+            AttributeTarget::Method => "method",
+            AttributeTarget::Property => "property",
+            AttributeTarget::ClassConstant => "class constant",
+            AttributeTarget::Parameter => "parameter",
+        }
+    }
+}
+
+/// An attribute class, declared with `#[Attribute]`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AttributeDefinition {
+    /// The fully qualified name of the class, without a leading `\`.
+    pub name: ByteString,
+    /// The flags given to `#[Attribute]`, `TARGET_ALL` if there are none.
+    pub flags: u8,
+}
+
+impl AttributeDefinition {
+    pub const TARGET_CLASS: u8 = 1;
+    pub const TARGET_FUNCTION: u8 = 2;
+    pub const TARGET_METHOD: u8 = 4;
+    pub const TARGET_PROPERTY: u8 = 8;
+    pub const TARGET_CLASS_CONSTANT: u8 = 16;
+    pub const TARGET_PARAMETER: u8 = 32;
+    pub const TARGET_ALL: u8 = 63;
+    pub const IS_REPEATABLE: u8 = 64;
+
+    pub const fn allows(&self, target: AttributeTarget) -> bool {
+        self.flags & target.flag() != 0
+    }
+
+    pub const fn is_repeatable(&self) -> bool {
+        self.flags & Self::IS_REPEATABLE != 0
+    }
+}
+
+/// The attribute classes that attributes are checked against, by name.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct AttributeRegistry {
+    /// The definitions, by their name in lowercase.
+    definitions: BTreeMap<Vec<u8>, AttributeDefinition>,
+}
+
+impl AttributeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The attribute classes of PHP itself, such as `Attribute` and `Override`.
+    pub fn php() -> Self {
+        let mut registry = Self::new();
+
+        registry.declare("Attribute", AttributeDefinition::TARGET_CLASS);
+        registry.declare("AllowDynamicProperties", AttributeDefinition::TARGET_CLASS);
+        registry.declare("ReturnTypeWillChange", AttributeDefinition::TARGET_METHOD);
+        registry.declare("SensitiveParameter", AttributeDefinition::TARGET_PARAMETER);
+        registry.declare("Override", AttributeDefinition::TARGET_METHOD);
+        registry.declare(
+            "Deprecated",
+            AttributeDefinition::TARGET_FUNCTION
+                | AttributeDefinition::TARGET_METHOD
+                | AttributeDefinition::TARGET_CLASS_CONSTANT,
+        );
+
+        registry
+    }
+
+    /// The attribute class with a fully qualified name, which is compared
+    /// case-insensitively.
+    pub fn get(&self, name: &[u8]) -> Option<&AttributeDefinition> {
+        let name = name.strip_prefix(b"\\").unwrap_or(name);
+
+        self.definitions.get(&name.to_ascii_lowercase())
+    }
+
+    /// The attribute classes, ordered by name.
+    pub fn definitions(&self) -> impl Iterator<Item = &AttributeDefinition> {
+        self.definitions.values()
+    }
+
+    /// Add an attribute class declared outside of the program, e.g. in another file or
+    /// in an extension, with the flags given to its `#[Attribute]`.
+    pub fn declare<T: Into<ByteString>>(&mut self, name: T, flags: u8) {
+        let name = name.into();
+        let name = ByteString::from(name.strip_prefix(b"\\").unwrap_or(&name));
+
+        self.definitions.insert(
+            name.to_ascii_lowercase(),
+            AttributeDefinition { name, flags },
+        );
+    }
+}
+
+/// Find the attribute classes that a program declares, i.e. the classes with an
+/// `#[Attribute]` attribute, and the flags given to it, along with those of PHP itself.
+///
+/// The flags are worked out from integers and the constants of `Attribute`, combined
+/// with `|`. Classes whose flags are written any other way, or aren't valid, are left
+/// out, as their targets can't be known without running the code.
+pub fn attribute_definitions(program: &Program) -> AttributeRegistry {
+    let names = resolve_names(program);
+    let mut registry = AttributeRegistry::php();
+
+    let mut collector = DefinitionCollector {
+        names: &names,
+        registry: &mut registry,
+    };
+    program.visit_spans(&mut collector);
+
+    registry
+}
+
+/// Find the attributes that are used on a kind of declaration that their class doesn't
+/// allow, and the attributes that are repeated on a declaration although their class
+/// isn't repeatable, as errors in source order.
+///
+/// Attribute names are resolved like other class names, and looked up in `registry`.
+/// The attributes of a promoted constructor parameter are also those of its property,
+/// so they can target either. Attributes whose class isn't in `registry` can't be
+/// checked: they are left out, or reported as [`Info`] when `strict` is set.
+///
+/// [`Info`]: crate::parser::error::ErrorCategory::Info
+pub fn validate_attribute_targets(
+    program: &Program,
+    registry: &AttributeRegistry,
+    strict: bool,
+) -> Vec<ParseError> {
+    let names = resolve_names(program);
+
+    let mut validator = TargetValidator {
+        names: &names,
+        registry,
+        strict,
+        errors: vec![],
+    };
+    program.visit_spans(&mut validator);
+
+    let mut errors = validator.errors;
+    errors.sort_by_key(|error| error.span.position);
+
+    errors
+}
+
+/// The attributes of a declaration.
+struct Attached<'a> {
+    pub target: AttributeTarget,
+    /// Whether the declaration is a promoted constructor parameter, which is also a
+    /// property.
+    pub promoted: bool,
+    pub groups: &'a [AttributeGroup],
+}
+
+/// The attributes of a node, if it is a declaration that can have any.
+fn attached(node: &dyn Any) -> Option<Attached<'_>> {
+    let (target, groups, promoted) = if let Some(class) = node.downcast_ref::<ClassStatement>() {
+        (AttributeTarget::Class, &class.attributes, false)
+    } else if let Some(class) = node.downcast_ref::<AnonymousClassExpression>() {
+        (AttributeTarget::Class, &class.attributes, false)
+    } else if let Some(interface) = node.downcast_ref::<InterfaceStatement>() {
+        (AttributeTarget::Class, &interface.attributes, false)
+    } else if let Some(r#trait) = node.downcast_ref::<TraitStatement>() {
+        (AttributeTarget::Class, &r#trait.attributes, false)
+    } else if let Some(r#enum) = node.downcast_ref::<UnitEnumStatement>() {
+        (AttributeTarget::Class, &r#enum.attributes, false)
+    } else if let Some(r#enum) = node.downcast_ref::<BackedEnumStatement>() {
+        (AttributeTarget::Class, &r#enum.attributes, false)
+    } else if let Some(function) = node.downcast_ref::<FunctionStatement>() {
+        (AttributeTarget::Function, &function.attributes, false)
+    } else if let Some(closure) = node.downcast_ref::<ClosureExpression>() {
+        (AttributeTarget::Function, &closure.attributes, false)
+    } else if let Some(function) = node.downcast_ref::<ArrowFunctionExpression>() {
+        (AttributeTarget::Function, &function.attributes, false)
+    } else if let Some(method) = node.downcast_ref::<ConcreteMethod>() {
+        (AttributeTarget::Method, &method.attributes, false)
+    } else if let Some(method) = node.downcast_ref::<AbstractMethod>() {
+        (AttributeTarget::Method, &method.attributes, false)
+    } else if let Some(constructor) = node.downcast_ref::<ConcreteConstructor>() {
+        (AttributeTarget::Method, &constructor.attributes, false)
+    } else if let Some(constructor) = node.downcast_ref::<AbstractConstructor>() {
+        (AttributeTarget::Method, &constructor.attributes, false)
+    } else if let Some(property) = node.downcast_ref::<Property>() {
+        (AttributeTarget::Property, &property.attributes, false)
+    } else if let Some(property) = node.downcast_ref::<VariableProperty>() {
+        (AttributeTarget::Property, &property.attributes, false)
+    } else if let Some(constant) = node.downcast_ref::<ClassishConstant>() {
+        (AttributeTarget::ClassConstant, &constant.attributes, false)
+    } else if let Some(case) = node.downcast_ref::<UnitEnumCase>() {
+        (AttributeTarget::ClassConstant, &case.attributes, false)
+    } else if let Some(case) = node.downcast_ref::<BackedEnumCase>() {
+        (AttributeTarget::ClassConstant, &case.attributes, false)
+    } else if let Some(parameter) = node.downcast_ref::<FunctionParameter>() {
+        (AttributeTarget::Parameter, &parameter.attributes, false)
+    } else if let Some(parameter) = node.downcast_ref::<ConstructorParameter>() {
+        let promoted = !parameter.modifiers.is_empty();
+
+        (AttributeTarget::Parameter, &parameter.attributes, promoted)
+    } else {
+        return None;
+    };
+
+    Some(Attached {
+        target,
+        promoted,
+        groups,
+    })
+}
+
+fn attributes(groups: &[AttributeGroup]) -> impl Iterator<Item = &Attribute> {
+    groups.iter().flat_map(|group| group.iter())
+}
+
+struct DefinitionCollector<'a> {
+    names: &'a ResolvedNames,
+    registry: &'a mut AttributeRegistry,
+}
+
+impl DefinitionCollector<'_> {
+    /// The value of the flags given to `#[Attribute]`.
+    fn flags(&self, expression: &Expression) -> Option<i64> {
+        match expression {
+            Expression::Literal(Literal::Integer(integer)) => integer.to_i64(),
+            Expression::ConstantFetch(fetch) => {
+                let Expression::Identifier(Identifier::SimpleIdentifier(class)) =
+                    fetch.target.as_ref()
+                else {
+                    return None;
+                };
+                let Identifier::SimpleIdentifier(constant) = &fetch.constant else {
+                    return None;
+                };
+
+                if !self.is_attribute(class) {
+                    return None;
+                }
+
+                let flag = match &constant.value[..] {
+                    b"TARGET_CLASS" => AttributeDefinition::TARGET_CLASS,
+                    b"TARGET_FUNCTION" => AttributeDefinition::TARGET_FUNCTION,
+                    b"TARGET_METHOD" => AttributeDefinition::TARGET_METHOD,
+                    b"TARGET_PROPERTY" => AttributeDefinition::TARGET_PROPERTY,
+                    b"TARGET_CLASS_CONSTANT" => AttributeDefinition::TARGET_CLASS_CONSTANT,
+                    b"TARGET_PARAMETER" => AttributeDefinition::TARGET_PARAMETER,
+                    b"TARGET_ALL" => AttributeDefinition::TARGET_ALL,
+                    b"IS_REPEATABLE" => AttributeDefinition::IS_REPEATABLE,
+                    _ => return None,
+                };
+
+                Some(flag as i64)
+            }
+            Expression::BitwiseOperation(BitwiseOperationExpression::Or {
+                left, right, ..
+            }) => Some(self.flags(left)? | self.flags(right)?),
+            Expression::Parenthesized(parenthesized) => self.flags(&parenthesized.expr),
+            _ => None,
+        }
+    }
+
+    /// Whether a name refers to the `Attribute` class.
+    fn is_attribute(&self, name: &SimpleIdentifier) -> bool {
+        self.names
+            .resolve(name.span)
+            .is_some_and(|name| name.eq_ignore_ascii_case(b"Attribute"))
+    }
+
+    fn definition(&self, class: &ClassStatement) -> Option<(ByteString, u8)> {
+        let attribute =
+            attributes(&class.attributes).find(|attribute| self.is_attribute(&attribute.name))?;
+
+        let flags = match attribute
+            .arguments
+            .as_ref()
+            .and_then(|list| list.arguments.first())
+        {
+            None => AttributeDefinition::TARGET_ALL as i64,
+            Some(Argument::Positional(argument)) if argument.ellipsis.is_none() => {
+                self.flags(&argument.value)?
+            }
+            Some(Argument::Named(argument))
+                if argument.ellipsis.is_none() && argument.name.value == b"flags" =>
+            {
+                self.flags(&argument.value)?
+            }
+            Some(_) => return None,
+        };
+
+        let flags = u8::try_from(flags).ok()?;
+        if flags > AttributeDefinition::TARGET_ALL | AttributeDefinition::IS_REPEATABLE {
+            return None;
+        }
+
+        Some((self.names.resolve(class.name.span)?.clone(), flags))
+    }
+}
+
+impl<'a> SpanVisitor<'a> for DefinitionCollector<'_> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(class) = node.downcast_ref::<ClassStatement>() {
+            if let Some((name, flags)) = self.definition(class) {
+                self.registry.declare(name, flags);
+            }
+        }
+    }
+}
+
+struct TargetValidator<'a> {
+    names: &'a ResolvedNames,
+    registry: &'a AttributeRegistry,
+    strict: bool,
+    errors: Vec<ParseError>,
+}
+
+impl TargetValidator<'_> {
+    fn validate(&mut self, attached: Attached) {
+        let (names, registry) = (self.names, self.registry);
+
+        // The non-repeatable attributes seen so far, with where they were used.
+        let mut seen: Vec<(&AttributeDefinition, &Attribute)> = vec![];
+
+        for attribute in attributes(attached.groups) {
+            let Some(name) = names.resolve(attribute.name.span) else {
+                continue;
+            };
+            let length = attribute.end.position - attribute.start.position;
+
+            let Some(definition) = registry.get(name) else {
+                if self.strict {
+                    self.errors
+                        .push(error::unknown_attribute(name, attribute.start, length));
+                }
+
+                continue;
+            };
+
+            let allowed = definition.allows(attached.target)
+                || attached.promoted && definition.allows(AttributeTarget::Property);
+
+            if !allowed {
+                let targets = AttributeTarget::ALL
+                    .into_iter()
+                    .filter(|target| definition.allows(*target))
+                    .map(AttributeTarget::name)
+                    .collect::<Vec<_>>();
+
+                self.errors.push(error::attribute_target_not_allowed(
+                    &definition.name,
+                    attached.target.name(),
+                    &targets,
+                    attribute.start,
+                    length,
+                ));
+            }
+
+            if definition.is_repeatable() {
+                continue;
+            }
+
+            match seen.iter().find(|(seen, _)| *seen == definition) {
+                Some((_, first)) => self.errors.push(error::repeated_attribute(
+                    &definition.name,
+                    first.start,
+                    first.end.position - first.start.position,
+                    attribute.start,
+                    length,
+                )),
+                None => seen.push((definition, attribute)),
+            }
+        }
+    }
+}
+
+impl<'a> SpanVisitor<'a> for TargetValidator<'_> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(attached) = attached(node) {
+            self.validate(attached);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0414-attribute-targets/code.php");
+
+    fn validate(code: &str, registry: &AttributeRegistry, strict: bool) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        validate_attribute_targets(&program, registry, strict)
+            .iter()
+            .map(|error| {
+                format!(
+                    "{}:{} {} {}",
+                    error.span.line, error.span.column, error.id, error.message
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_attribute_definitions() {
+        let registry = attribute_definitions(&crate::parse(SOURCE).unwrap());
+
+        assert_eq!(
+            registry
+                .definitions()
+                .filter(|definition| definition.name.starts_with(b"App\\"))
+                .map(|definition| format!("{} {}", definition.name, definition.flags))
+                .collect::<Vec<_>>(),
+            vec![
+                "App\\Routing\\Inject 96",
+                "App\\Routing\\Marker 63",
+                "App\\Routing\\Route 4",
+            ]
+        );
+        assert!(registry.get(b"\\app\\routing\\route").is_some());
+        assert!(registry.get(b"Override").is_some());
+    }
+
+    #[test]
+    fn test_attribute_flags() {
+        let flags = |code: &str| {
+            let registry = attribute_definitions(&crate::parse(code).unwrap());
+
+            registry.get(b"A\\Foo").map(|definition| definition.flags)
+        };
+
+        assert_eq!(
+            flags("<?php namespace A; #[\\Attribute(flags: (\\Attribute::TARGET_CLASS | 32))] class Foo {}"),
+            Some(33)
+        );
+        assert_eq!(
+            flags("<?php namespace A; use Attribute; #[attribute(0)] class Foo {}"),
+            Some(0)
+        );
+        // Not the `Attribute` class, or flags that can't be worked out.
+        assert_eq!(flags("<?php namespace A; #[Attribute] class Foo {}"), None);
+        assert_eq!(
+            flags("<?php namespace A; #[\\Attribute(FLAGS)] class Foo {}"),
+            None
+        );
+        assert_eq!(
+            flags("<?php namespace A; #[\\Attribute(128)] class Foo {}"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_attribute_targets() {
+        let registry = attribute_definitions(&crate::parse(SOURCE).unwrap());
+
+        assert_eq!(
+            validate(SOURCE, &registry, false),
+            vec![
+                "25:3 E083 attribute `App\\Routing\\Route` cannot target a class",
+                "28:24 E084 attribute `App\\Routing\\Route` must not be repeated",
+                "36:11 E083 attribute `App\\Routing\\Route` cannot target a parameter",
+                "41:7 E084 attribute `App\\Routing\\Marker` must not be repeated",
+            ]
+        );
+
+        let strict = validate(SOURCE, &registry, true);
+        assert_eq!(strict.len(), 5);
+        assert_eq!(
+            strict[4],
+            "44:7 E085 attribute `App\\Routing\\Cache` is not known"
+        );
+    }
+
+    #[test]
+    fn test_promoted_parameters() {
+        let mut registry = AttributeRegistry::new();
+        registry.declare("\\Lib\\Column", AttributeDefinition::TARGET_PROPERTY);
+        registry.declare("Lib\\Argument", AttributeDefinition::TARGET_PARAMETER);
+
+        let code = "<?php use Lib\\{Column, Argument};
+            class A { function __construct(#[Column] public $a, #[Argument] public $b) {} }
+            class B { function __construct(#[Column] $a, #[Argument] $b) {} }";
+
+        assert_eq!(
+            validate(code, &registry, false),
+            vec!["3:46 E083 attribute `Lib\\Column` cannot target a parameter"]
+        );
+
+        let program = crate::parse(code).unwrap();
+        let error = &validate_attribute_targets(&program, &registry, false)[0];
+        assert_eq!(error.note.as_deref(), Some("allowed targets: property"));
+        assert_eq!(error.annotations[0].length, 6);
+    }
+}
@@ -0,0 +1,866 @@
+//! Detection of duplicated code, see [`find_clones`].
+//!
+//! Statements are compared by their shape: the AST without spans, with every literal
+//! replaced by the same placeholder and every name by a placeholder numbered in order
+//! of first use. Renaming variables or changing constants doesn't hide a clone, while
+//! reusing one name where the other fragment uses two does, e.g. `$a + $a` and
+//! `$x + $y` have different shapes but `$a + $b` and `$x + $y` have the same.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use serde::ser;
+use serde::Serialize;
+
+use crate::lexer::token::Span;
+use crate::parser::ast::functions::ArrowFunctionBlockBody;
+use crate::parser::ast::functions::FunctionBody;
+use crate::parser::ast::functions::MethodBody;
+use crate::parser::ast::namespaces::BracedNamespaceBody;
+use crate::parser::ast::namespaces::UnbracedNamespace;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+use crate::parser::cancellation::CancellationToken;
+use crate::spans::bounds;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+/// The base of the rolling hash over the shapes of statements.
+const BASE: u64 = 1_000_003;
+
+/// A group of statement sequences that have the same shape.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CloneClass {
+    /// The occurrences in source order, none of which overlap.
+    pub occurrences: Vec<CloneOccurrence>,
+    /// The number of statements in each occurrence.
+    pub statements: usize,
+    /// The number of nodes in the shape the occurrences share.
+    pub nodes: usize,
+}
+
+/// A sequence of statements that is part of a [`CloneClass`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CloneOccurrence {
+    /// The first span of the first statement.
+    pub start: Span,
+    /// The last span of the last statement, usually its `;` or `}`.
+    pub end: Span,
+}
+
+/// Find sequences of at least `min_statements` statements that have the same shape,
+/// made of at least `min_nodes` nodes, e.g. code that was copied and had its variables
+/// renamed.
+///
+/// Sequences are taken from the top level of the program and of its namespaces, and
+/// from the bodies of functions, methods and closures, but don't cross from one to
+/// another. Occurrences are grown to the longest sequence that all of them share, and
+/// classes whose occurrences all lie within those of a longer class aren't reported.
+/// Comments and opening and closing tags are left out of the comparison.
+///
+/// Classes are ordered by their first occurrence, then by their length, so the result
+/// is the same from one run to the next.
+pub fn find_clones(program: &Program, min_statements: usize, min_nodes: usize) -> Vec<CloneClass> {
+    detect_clones(program, min_statements, min_nodes, None).unwrap_or_default()
+}
+
+/// Find clones like [`find_clones`], giving up with `None` once `cancellation` is
+/// cancelled, as comparing the statements of a large program can take a while.
+pub fn find_clones_with_cancellation(
+    program: &Program,
+    min_statements: usize,
+    min_nodes: usize,
+    cancellation: &CancellationToken,
+) -> Option<Vec<CloneClass>> {
+    detect_clones(program, min_statements, min_nodes, Some(cancellation))
+}
+
+fn detect_clones(
+    program: &Program,
+    min_statements: usize,
+    min_nodes: usize,
+    cancellation: Option<&CancellationToken>,
+) -> Option<Vec<CloneClass>> {
+    let cancelled = || cancellation.is_some_and(|token| token.is_cancelled());
+    let min_statements = min_statements.max(1);
+
+    let mut collector = BodyCollector { bodies: vec![] };
+    program.visit_spans(&mut collector);
+
+    let mut bodies: Vec<&[Statement]> = vec![program];
+    bodies.append(&mut collector.bodies);
+
+    let sequences: Vec<Vec<&Statement>> = bodies
+        .iter()
+        .map(|body| body.iter().filter(|s| is_compared(s)).collect())
+        .collect();
+
+    let mut candidates: Vec<Vec<Window>> = Vec::new();
+    let mut positions: HashMap<u64, usize> = HashMap::new();
+    for (sequence, statements) in sequences.iter().enumerate() {
+        for (start, hash) in rolling_hashes(statements, min_statements) {
+            let window = Window {
+                sequence,
+                start,
+                length: min_statements,
+            };
+
+            match positions.get(&hash) {
+                Some(index) => candidates[*index].push(window),
+                None => {
+                    positions.insert(hash, candidates.len());
+                    candidates.push(vec![window]);
+                }
+            }
+        }
+    }
+
+    let mut classes: Vec<(Vec<Window>, usize)> = Vec::new();
+    for windows in candidates {
+        if windows.len() < 2 {
+            continue;
+        }
+
+        if cancelled() {
+            return None;
+        }
+
+        // Equal hashes only suggest equal shapes, and the names of a sequence are only
+        // numbered consistently when the sequence is normalized as a whole.
+        let mut groups: Vec<(Shape, Vec<Window>)> = Vec::new();
+        for window in windows {
+            let shape = normalize(&sequences[window.sequence][window.range()]);
+
+            match groups.iter_mut().find(|(other, _)| *other == shape) {
+                Some((_, group)) => group.push(window),
+                None => groups.push((shape, vec![window])),
+            }
+        }
+
+        for (shape, group) in groups {
+            let mut group = without_overlaps(group);
+            if group.len() < 2 {
+                continue;
+            }
+
+            let mut nodes = shape.nodes;
+            while let Some(extended) = extend(&sequences, &group) {
+                group = extended.0;
+                nodes = extended.1;
+            }
+
+            if nodes >= min_nodes {
+                classes.push((group, nodes));
+            }
+        }
+    }
+
+    let mut clones: Vec<CloneClass> = classes
+        .iter()
+        .filter(|(group, _)| {
+            !classes.iter().any(|(other, _)| {
+                other != group
+                    && group
+                        .iter()
+                        .all(|window| other.iter().any(|o| o.contains(window)))
+            })
+        })
+        .map(|(group, nodes)| CloneClass {
+            occurrences: group
+                .iter()
+                .map(|window| {
+                    let statements = &sequences[window.sequence][window.range()];
+
+                    CloneOccurrence {
+                        start: bounds(&statements[..1]).0,
+                        end: bounds(&statements[statements.len() - 1..]).1,
+                    }
+                })
+                .collect(),
+            statements: group[0].length,
+            nodes: *nodes,
+        })
+        .collect();
+
+    for class in &mut clones {
+        class.occurrences.sort_by_key(|o| o.start.position);
+    }
+    clones.sort_by_key(|class| (class.occurrences[0].start.position, class.statements));
+
+    Some(clones)
+}
+
+/// A sequence of `length` statements of one of the bodies, starting at `start`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Window {
+    sequence: usize,
+    start: usize,
+    length: usize,
+}
+
+impl Window {
+    fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.start + self.length
+    }
+
+    fn overlaps(&self, other: &Window) -> bool {
+        self.sequence == other.sequence
+            && self.start < other.start + other.length
+            && other.start < self.start + self.length
+    }
+
+    fn contains(&self, other: &Window) -> bool {
+        self.sequence == other.sequence
+            && self.start <= other.start
+            && other.start + other.length <= self.start + self.length
+    }
+}
+
+/// Drop the windows that overlap an earlier one, e.g. in a run of equal statements.
+fn without_overlaps(windows: Vec<Window>) -> Vec<Window> {
+    let mut kept: Vec<Window> = Vec::with_capacity(windows.len());
+    for window in windows {
+        if !kept.iter().any(|other| other.overlaps(&window)) {
+            kept.push(window);
+        }
+    }
+
+    kept
+}
+
+/// Grow every window by the statement that follows it, if they all still have the
+/// same shape and don't overlap, returning the grown windows and the size of their
+/// shape.
+fn extend(sequences: &[Vec<&Statement>], group: &[Window]) -> Option<(Vec<Window>, usize)> {
+    let grown: Vec<Window> = group
+        .iter()
+        .map(|window| Window {
+            length: window.length + 1,
+            ..*window
+        })
+        .collect();
+
+    if grown
+        .iter()
+        .any(|window| window.start + window.length > sequences[window.sequence].len())
+        || without_overlaps(grown.clone()).len() != grown.len()
+    {
+        return None;
+    }
+
+    let first = normalize(&sequences[grown[0].sequence][grown[0].range()]);
+    let same = grown[1..]
+        .iter()
+        .all(|window| normalize(&sequences[window.sequence][window.range()]) == first);
+
+    same.then_some((grown, first.nodes))
+}
+
+/// The rolling hash of every window of `length` statements, with the index of the
+/// window's first statement.
+fn rolling_hashes(statements: &[&Statement], length: usize) -> Vec<(usize, u64)> {
+    if statements.len() < length {
+        return vec![];
+    }
+
+    let hashes: Vec<u64> = statements
+        .iter()
+        .map(|statement| {
+            let mut hasher = DefaultHasher::new();
+            normalize(&[statement]).atoms.hash(&mut hasher);
+
+            hasher.finish()
+        })
+        .collect();
+
+    // The factor of the statement that leaves the window.
+    let leaving = (1..length).fold(1u64, |power, _| power.wrapping_mul(BASE));
+
+    let mut hash = hashes[..length]
+        .iter()
+        .fold(0u64, |hash, h| hash.wrapping_mul(BASE).wrapping_add(*h));
+
+    let mut windows = vec![(0, hash)];
+    for start in 1..=statements.len() - length {
+        hash = hash
+            .wrapping_sub(hashes[start - 1].wrapping_mul(leaving))
+            .wrapping_mul(BASE)
+            .wrapping_add(hashes[start + length - 1]);
+
+        windows.push((start, hash));
+    }
+
+    windows
+}
+
+fn is_compared(statement: &Statement) -> bool {
+    !matches!(
+        statement,
+        Statement::Comment(_)
+            | Statement::FullOpeningTag(_)
+            | Statement::ShortOpeningTag(_)
+            | Statement::EchoOpeningTag(_)
+            | Statement::ClosingTag(_)
+    )
+}
+
+/// Collects the statements of every function-like body and of every namespace.
+struct BodyCollector<'a> {
+    bodies: Vec<&'a [Statement]>,
+}
+
+impl<'a> SpanVisitor<'a> for BodyCollector<'a> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(body) = node.downcast_ref::<FunctionBody>() {
+            self.bodies.push(&body.statements);
+        } else if let Some(body) = node.downcast_ref::<MethodBody>() {
+            self.bodies.push(&body.statements);
+        } else if let Some(body) = node.downcast_ref::<ArrowFunctionBlockBody>() {
+            self.bodies.push(&body.statements);
+        } else if let Some(namespace) = node.downcast_ref::<UnbracedNamespace>() {
+            self.bodies.push(&namespace.statements);
+        } else if let Some(body) = node.downcast_ref::<BracedNamespaceBody>() {
+            self.bodies.push(&body.statements);
+        }
+    }
+}
+
+/// The shape of a sequence of statements.
+#[derive(Debug, Default)]
+struct Shape {
+    atoms: Vec<Atom>,
+    nodes: usize,
+    /// The placeholders of the names, in order of first use.
+    names: HashMap<String, usize>,
+}
+
+/// Shapes are equal when their atoms are, wherever they are and whatever their names.
+impl PartialEq for Shape {
+    fn eq(&self, other: &Self) -> bool {
+        self.atoms == other.atoms
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+enum Atom {
+    /// The start of a node, or a variant without any fields.
+    Node(&'static str),
+    Field(&'static str),
+    /// The end of a node or of a list.
+    End,
+    List,
+    None,
+    /// The name of a node, kept as is.
+    Tag(String),
+    Value(String),
+    Name(usize),
+    Literal,
+}
+
+fn normalize<T: Serialize>(statements: &[&T]) -> Shape {
+    let mut shape = Shape::default();
+
+    for statement in statements {
+        // The AST doesn't contain any values that can fail to serialize.
+        let _ = statement.serialize(Normalizer {
+            shape: &mut shape,
+            context: Context::Literal,
+        });
+    }
+
+    shape
+}
+
+/// What the strings being serialized stand for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    /// The name of a variable, function, class and so on.
+    Name,
+    /// The name of a node, in the `type` field that `serde` adds.
+    Tag,
+    Literal,
+    Span,
+}
+
+#[derive(Debug)]
+struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+struct Normalizer<'a> {
+    shape: &'a mut Shape,
+    context: Context,
+}
+
+impl<'a> Normalizer<'a> {
+    fn push(self, atom: Atom) -> Result<()> {
+        self.shape.atoms.push(atom);
+
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for Normalizer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = SeqNormalizer<'a>;
+    type SerializeTuple = SeqNormalizer<'a>;
+    type SerializeTupleStruct = SeqNormalizer<'a>;
+    type SerializeTupleVariant = SeqNormalizer<'a>;
+    type SerializeMap = SeqNormalizer<'a>;
+    type SerializeStruct = StructNormalizer<'a>;
+    type SerializeStructVariant = StructNormalizer<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.push(Atom::Value(v.to_string()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.push(Atom::Value(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        if self.context == Context::Span {
+            return Ok(());
+        }
+
+        self.push(Atom::Value(v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.push(Atom::Value(v.to_string()))
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        let atom = match self.context {
+            Context::Name => {
+                let next = self.shape.names.len();
+
+                Atom::Name(*self.shape.names.entry(v.to_string()).or_insert(next))
+            }
+            Context::Tag => Atom::Tag(v.to_string()),
+            Context::Literal | Context::Span => Atom::Literal,
+        };
+
+        self.push(atom)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<()> {
+        self.push(Atom::Literal)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.push(Atom::None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.push(Atom::None)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        self.shape.nodes += 1;
+
+        self.push(Atom::Node(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.shape.nodes += 1;
+
+        self.push(Atom::Node(variant))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.shape.atoms.push(Atom::Node(variant));
+        value.serialize(Normalizer {
+            shape: &mut *self.shape,
+            context: self.context,
+        })?;
+
+        self.push(Atom::End)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SeqNormalizer<'a>> {
+        self.shape.atoms.push(Atom::List);
+
+        Ok(SeqNormalizer {
+            shape: self.shape,
+            context: self.context,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqNormalizer<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<SeqNormalizer<'a>> {
+        self.shape.nodes += 1;
+        self.shape.atoms.push(Atom::Node(name));
+
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqNormalizer<'a>> {
+        self.shape.nodes += 1;
+        self.shape.atoms.push(Atom::Node(variant));
+
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SeqNormalizer<'a>> {
+        self.serialize_seq(len)
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<StructNormalizer<'a>> {
+        Ok(StructNormalizer::new(self.shape, name))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<StructNormalizer<'a>> {
+        Ok(StructNormalizer::new(self.shape, variant))
+    }
+}
+
+struct SeqNormalizer<'a> {
+    shape: &'a mut Shape,
+    context: Context,
+}
+
+impl<'a> SeqNormalizer<'a> {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T, context: Context) -> Result<()> {
+        value.serialize(Normalizer {
+            shape: &mut *self.shape,
+            context,
+        })
+    }
+
+    fn finish(self) -> Result<()> {
+        self.shape.atoms.push(Atom::End);
+
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeSeq for SeqNormalizer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value, self.context)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTuple for SeqNormalizer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value, self.context)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SeqNormalizer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value, self.context)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for SeqNormalizer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value, self.context)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+/// Nodes containing a `#[serde(flatten)]` field are serialized as maps, whose keys
+/// are the names of their fields.
+impl<'a> ser::SerializeMap for SeqNormalizer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        self.push(key, Context::Tag)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value, Context::Literal)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+/// Spans are left out of the shape, and only used to find where the sequence starts
+/// and ends.
+struct StructNormalizer<'a> {
+    shape: &'a mut Shape,
+    name: &'static str,
+}
+
+impl<'a> StructNormalizer<'a> {
+    fn new(shape: &'a mut Shape, name: &'static str) -> Self {
+        if name != "Span" {
+            shape.nodes += 1;
+            shape.atoms.push(Atom::Node(name));
+        }
+
+        Self { shape, name }
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        let context = match (self.name, key) {
+            ("Span", _) => Context::Span,
+            (_, "type") => Context::Tag,
+            ("SimpleIdentifier", _) | ("SimpleVariable", _) => Context::Name,
+            _ => Context::Literal,
+        };
+
+        if context != Context::Span {
+            self.shape.atoms.push(Atom::Field(key));
+        }
+
+        value.serialize(Normalizer {
+            shape: &mut *self.shape,
+            context,
+        })
+    }
+
+    fn finish(self) -> Result<()> {
+        if self.name != "Span" {
+            self.shape.atoms.push(Atom::End);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStruct for StructNormalizer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for StructNormalizer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0397-clone-detection/code.php");
+
+    fn clones(code: &str, min_statements: usize, min_nodes: usize) -> Vec<CloneClass> {
+        find_clones(&crate::parse(code).unwrap(), min_statements, min_nodes)
+    }
+
+    fn lines(class: &CloneClass) -> Vec<(usize, usize)> {
+        class
+            .occurrences
+            .iter()
+            .map(|occurrence| (occurrence.start.line, occurrence.end.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_three_way_clone() {
+        let clones = clones(SOURCE, 3, 10);
+
+        assert_eq!(clones.len(), 1);
+        assert_eq!(clones[0].statements, 4);
+        assert_eq!(lines(&clones[0]), vec![(5, 11), (17, 23), (30, 36)]);
+    }
+
+    #[test]
+    fn test_names_are_numbered_in_order_of_first_use() {
+        let code = "<?php
+            function a() { $a = $b + $c; echo $a; }
+            function b() { $x = $y + $z; echo $x; }
+            function c() { $x = $x + $z; echo $x; }
+            function d() { $x = $y + $z; echo $y; }
+        ";
+
+        let clones = clones(code, 2, 0);
+
+        assert_eq!(clones.len(), 1);
+        assert_eq!(lines(&clones[0]), vec![(2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn test_literals_are_abstracted() {
+        let clones = clones("<?php foo(1, 'a'); bar(); foo(2, 'b'); bar();", 2, 0);
+
+        assert_eq!(clones.len(), 1);
+        assert_eq!(clones[0].occurrences.len(), 2);
+    }
+
+    #[test]
+    fn test_min_nodes() {
+        let code = "<?php foo(); bar(); foo(); bar();";
+
+        let nodes = clones(code, 2, 0)[0].nodes;
+
+        assert_eq!(clones(code, 2, nodes).len(), 1);
+        assert!(clones(code, 2, nodes + 1).is_empty());
+    }
+
+    #[test]
+    fn test_occurrences_do_not_overlap() {
+        let clones = clones("<?php foo(); foo(); foo(); foo(); foo();", 2, 0);
+
+        assert_eq!(clones.len(), 1);
+        let occurrences = &clones[0].occurrences;
+        assert_eq!(occurrences.len(), 2);
+        assert!(occurrences[0].end.position < occurrences[1].start.position);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let program = crate::parse(SOURCE).unwrap();
+        let token = CancellationToken::new();
+
+        assert_eq!(
+            find_clones_with_cancellation(&program, 3, 10, &token),
+            Some(find_clones(&program, 3, 10))
+        );
+
+        token.cancel();
+        assert_eq!(find_clones_with_cancellation(&program, 3, 10, &token), None);
+    }
+}
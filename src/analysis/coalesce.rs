@@ -0,0 +1,141 @@
+//! Accesses that `??` and `??=` guard, see [`coalesce_guarded_accesses`].
+
+use crate::lexer::token::Span;
+use crate::parser::ast::operators::AssignmentOperationExpression;
+use crate::parser::ast::Expression;
+use crate::parser::ast::Program;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+/// Find the array indexes and property fetches that are guarded by being on the left of
+/// `??` or `??=`, where PHP doesn't warn about an undefined index or property.
+///
+/// The guard applies to the whole chain of accesses on the left, so in
+/// `$a['b']->c ?? null` both `['b']` and `->c` are guarded, but not the accesses in an
+/// index or in the arguments of a call, as in `$a[$b['c']] ?? null`. Each access is
+/// given by its operator: the `[` of an index, or the `->`, `?->` or `::` of a property
+/// fetch, in source order.
+pub fn coalesce_guarded_accesses(program: &Program) -> Vec<Span> {
+    let mut collector = GuardCollector { accesses: vec![] };
+
+    program.visit_spans(&mut collector);
+
+    let mut accesses = collector.accesses;
+    accesses.sort_by_key(|span| span.position);
+    accesses.dedup();
+
+    accesses
+}
+
+struct GuardCollector {
+    accesses: Vec<Span>,
+}
+
+impl GuardCollector {
+    fn guard(&mut self, expression: &Expression) {
+        match expression {
+            Expression::ArrayIndex(index) => {
+                self.accesses.push(index.left_bracket);
+                self.guard(&index.array);
+            }
+            Expression::PropertyFetch(fetch) => {
+                self.accesses.push(fetch.arrow);
+                self.guard(&fetch.target);
+            }
+            Expression::NullsafePropertyFetch(fetch) => {
+                self.accesses.push(fetch.question_arrow);
+                self.guard(&fetch.target);
+            }
+            Expression::StaticPropertyFetch(fetch) => self.accesses.push(fetch.double_colon),
+            Expression::Parenthesized(parenthesized) => self.guard(&parenthesized.expr),
+            _ => {}
+        }
+    }
+}
+
+impl<'a> SpanVisitor<'a> for GuardCollector {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        match node.downcast_ref::<Expression>() {
+            Some(Expression::Coalesce(coalesce)) => self.guard(&coalesce.lhs),
+            Some(Expression::AssignmentOperation(AssignmentOperationExpression::Coalesce {
+                left,
+                ..
+            })) => self.guard(left),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::Statement;
+
+    fn guarded(code: &str) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        coalesce_guarded_accesses(&program)
+            .iter()
+            .map(|span| format!("{}:{}", span.line, span.column))
+            .collect()
+    }
+
+    fn expression(code: &str) -> Expression {
+        match &crate::parse(code).unwrap()[1] {
+            Statement::Expression(statement) => statement.expression.clone(),
+            statement => panic!("expected an expression, found {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_coalesce_is_right_associative() {
+        let Expression::Coalesce(outer) = expression("<?php $a['a'] ?? $a['b'] ?? 'default';")
+        else {
+            panic!("expected `??`");
+        };
+
+        assert!(matches!(outer.lhs.as_ref(), Expression::ArrayIndex(_)));
+        let Expression::Coalesce(inner) = outer.rhs.as_ref() else {
+            panic!("expected `??` on the right");
+        };
+        assert!(matches!(inner.lhs.as_ref(), Expression::ArrayIndex(_)));
+        assert!(matches!(inner.rhs.as_ref(), Expression::Literal(_)));
+
+        let Expression::Coalesce(coalesce) = expression("<?php $x ?? throw new E();") else {
+            panic!("expected `??`");
+        };
+        assert!(matches!(coalesce.rhs.as_ref(), Expression::Throw(_)));
+    }
+
+    #[test]
+    fn test_three_link_chain() {
+        assert_eq!(
+            guarded("<?php $config['a'] ?? $this->config->b ?? $c?->d['e'] ?? 'default';"),
+            vec!["1:14", "1:28", "1:36", "1:45", "1:49"]
+        );
+    }
+
+    #[test]
+    fn test_coalesce_assign_on_nested_index() {
+        assert_eq!(
+            guarded("<?php $x['a'][$y['b']]['c'] ??= [];"),
+            vec!["1:9", "1:14", "1:23"]
+        );
+    }
+
+    #[test]
+    fn test_unguarded_accesses() {
+        assert_eq!(
+            guarded("<?php f($a['b'])['c'] ?? $d['e']; $f['g'] = $h->i ?: A::$j;"),
+            vec!["1:17"]
+        );
+        assert_eq!(guarded("<?php ($a['b']) ?? A::$c['d'];"), vec!["1:10"]);
+    }
+}
@@ -0,0 +1,336 @@
+//! Whether declarations are always made, see [`declaration_conditions`].
+
+use std::any::Any;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::classes::ClassStatement;
+use crate::parser::ast::constant::ConstantStatement;
+use crate::parser::ast::control_flow::IfStatement;
+use crate::parser::ast::enums::BackedEnumStatement;
+use crate::parser::ast::enums::UnitEnumStatement;
+use crate::parser::ast::functions::ArrowFunctionExpression;
+use crate::parser::ast::functions::ClosureExpression;
+use crate::parser::ast::functions::ConcreteConstructor;
+use crate::parser::ast::functions::ConcreteMethod;
+use crate::parser::ast::functions::FunctionStatement;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::interfaces::InterfaceStatement;
+use crate::parser::ast::loops::DoWhileStatement;
+use crate::parser::ast::loops::ForStatement;
+use crate::parser::ast::loops::ForeachStatement;
+use crate::parser::ast::loops::WhileStatement;
+use crate::parser::ast::namespaces::BracedNamespace;
+use crate::parser::ast::namespaces::UnbracedNamespace;
+use crate::parser::ast::traits::TraitStatement;
+use crate::parser::ast::try_block::TryStatement;
+use crate::parser::ast::Program;
+use crate::parser::ast::SwitchStatement;
+use crate::parser::symbols::SymbolKind;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+/// A declaration of a class, interface, trait, enum, function or global constant.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SymbolRef {
+    pub kind: SymbolKind,
+    /// The fully qualified name, e.g. `App\Models\User`.
+    pub name: ByteString,
+    /// The span of the name as written.
+    pub span: Span,
+}
+
+/// When a declaration is made.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Condition {
+    /// At the top level of the file or of a namespace, the declaration is made when
+    /// the file is included.
+    Unconditional,
+    /// In the body of a function, method or closure, whose `function` or `fn` keyword
+    /// is given: the declaration is made when it runs.
+    InsideFunction(Span),
+    /// In an `if`, `switch`, loop or `try` statement, whose keyword is given: the
+    /// declaration is made if the statement gets to it.
+    InsideConditional(Span),
+    /// Both in a function and in a conditional statement.
+    Both { function: Span, conditional: Span },
+}
+
+/// Find the declarations of a program that PHP makes in the global symbol table, and
+/// when it makes them, in source order.
+///
+/// A function declared in another function only exists once the other function has
+/// been called, and a class declared in an `if` only if its branch is taken, as in
+/// `if (!class_exists('A')) { class A {} }`. The enclosing function and conditional
+/// statement are the innermost ones, and only the conditional statements in the
+/// enclosing function count, as the declaration is made when it is called. Blocks and
+/// `declare` statements don't make a declaration conditional, but the `else`, `elseif`
+/// and `catch` clauses of a statement do, like its main body. Methods, closures and
+/// anonymous classes aren't global symbols, so they aren't reported.
+pub fn declaration_conditions(program: &Program) -> Vec<(SymbolRef, Condition)> {
+    let mut collector = ConditionCollector::default();
+
+    program.visit_spans(&mut collector);
+
+    collector.declarations
+}
+
+#[derive(Default)]
+struct ConditionCollector {
+    declarations: Vec<(SymbolRef, Condition)>,
+    namespace: Option<ByteString>,
+    function: Option<Span>,
+    conditional: Option<Span>,
+    /// A frame for each segment that was entered.
+    frames: Vec<Option<Scope>>,
+}
+
+impl ConditionCollector {
+    fn declare(&mut self, kind: SymbolKind, name: &SimpleIdentifier) {
+        let qualified = match &self.namespace {
+            Some(namespace) => [&namespace[..], b"\\", &name.value[..]].concat().into(),
+            None => name.value.clone(),
+        };
+
+        let condition = match (self.function, self.conditional) {
+            (None, None) => Condition::Unconditional,
+            (Some(function), None) => Condition::InsideFunction(function),
+            (None, Some(conditional)) => Condition::InsideConditional(conditional),
+            (Some(function), Some(conditional)) => Condition::Both {
+                function,
+                conditional,
+            },
+        };
+
+        self.declarations.push((
+            SymbolRef {
+                kind,
+                name: qualified,
+                span: name.span,
+            },
+            condition,
+        ));
+    }
+}
+
+/// What to restore when a segment is left.
+enum Scope {
+    /// The end of a braced namespace.
+    Namespace,
+    /// The function and conditional statement around a function.
+    Function(Option<Span>, Option<Span>),
+    /// The conditional statement around a conditional statement.
+    Conditional(Option<Span>),
+}
+
+impl<'a> SpanVisitor<'a> for ConditionCollector {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        self.frames.push(None);
+
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(function) = node.downcast_ref::<FunctionStatement>() {
+            self.declare(SymbolKind::Function, &function.name);
+        } else if let Some(class) = node.downcast_ref::<ClassStatement>() {
+            self.declare(SymbolKind::Class, &class.name);
+        } else if let Some(interface) = node.downcast_ref::<InterfaceStatement>() {
+            self.declare(SymbolKind::Interface, &interface.name);
+        } else if let Some(r#trait) = node.downcast_ref::<TraitStatement>() {
+            self.declare(SymbolKind::Trait, &r#trait.name);
+        } else if let Some(r#enum) = node.downcast_ref::<UnitEnumStatement>() {
+            self.declare(SymbolKind::Enum, &r#enum.name);
+        } else if let Some(r#enum) = node.downcast_ref::<BackedEnumStatement>() {
+            self.declare(SymbolKind::Enum, &r#enum.name);
+        } else if let Some(constant) = node.downcast_ref::<ConstantStatement>() {
+            for entry in &constant.entries {
+                self.declare(SymbolKind::Constant, &entry.name);
+            }
+        }
+
+        let scope = if let Some(namespace) = node.downcast_ref::<BracedNamespace>() {
+            self.namespace = namespace.name.as_ref().map(|name| name.value.clone());
+
+            Scope::Namespace
+        } else if let Some(namespace) = node.downcast_ref::<UnbracedNamespace>() {
+            self.namespace = Some(namespace.name.value.clone());
+
+            return;
+        } else if let Some(function) = function(node) {
+            Scope::Function(self.function.replace(function), self.conditional.take())
+        } else if let Some(conditional) = conditional(node) {
+            Scope::Conditional(self.conditional.replace(conditional))
+        } else {
+            return;
+        };
+
+        if let Some(frame) = self.frames.last_mut() {
+            *frame = Some(scope);
+        }
+    }
+
+    fn leave(&mut self) {
+        match self.frames.pop().flatten() {
+            Some(Scope::Namespace) => self.namespace = None,
+            Some(Scope::Function(function, conditional)) => {
+                self.function = function;
+                self.conditional = conditional;
+            }
+            Some(Scope::Conditional(conditional)) => self.conditional = conditional,
+            None => {}
+        }
+    }
+}
+
+/// The keyword of a function, method or closure.
+fn function(node: &dyn Any) -> Option<Span> {
+    if let Some(function) = node.downcast_ref::<FunctionStatement>() {
+        Some(function.function)
+    } else if let Some(method) = node.downcast_ref::<ConcreteMethod>() {
+        Some(method.function)
+    } else if let Some(constructor) = node.downcast_ref::<ConcreteConstructor>() {
+        Some(constructor.function)
+    } else if let Some(closure) = node.downcast_ref::<ClosureExpression>() {
+        Some(closure.function)
+    } else {
+        node.downcast_ref::<ArrowFunctionExpression>()
+            .map(|function| function.r#fn)
+    }
+}
+
+/// The keyword of a statement that may not run its body.
+fn conditional(node: &dyn Any) -> Option<Span> {
+    if let Some(r#if) = node.downcast_ref::<IfStatement>() {
+        Some(r#if.r#if)
+    } else if let Some(switch) = node.downcast_ref::<SwitchStatement>() {
+        Some(switch.switch)
+    } else if let Some(r#while) = node.downcast_ref::<WhileStatement>() {
+        Some(r#while.r#while)
+    } else if let Some(r#do) = node.downcast_ref::<DoWhileStatement>() {
+        Some(r#do.r#do)
+    } else if let Some(r#for) = node.downcast_ref::<ForStatement>() {
+        Some(r#for.r#for)
+    } else if let Some(foreach) = node.downcast_ref::<ForeachStatement>() {
+        Some(foreach.foreach)
+    } else {
+        node.downcast_ref::<TryStatement>().map(|r#try| r#try.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(code: &str) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        declaration_conditions(&program)
+            .iter()
+            .map(|(symbol, condition)| {
+                let condition = match condition {
+                    Condition::Unconditional => "unconditional".to_string(),
+                    Condition::InsideFunction(function) => {
+                        format!("in function at {}", function.column)
+                    }
+                    Condition::InsideConditional(conditional) => {
+                        format!("in conditional at {}", conditional.column)
+                    }
+                    Condition::Both {
+                        function,
+                        conditional,
+                    } => format!(
+                        "in function at {} and conditional at {}",
+                        function.column, conditional.column
+                    ),
+                };
+
+                format!("{:?} {} {}", symbol.kind, symbol.name, condition)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unconditional_declarations() {
+        assert_eq!(
+            conditions(
+                "<?php
+namespace App;
+const A = 1, B = 2;
+class C { public function m() {} }
+{ interface I {} }
+declare(ticks=1) { enum E {} }"
+            ),
+            vec![
+                "Constant App\\A unconditional",
+                "Constant App\\B unconditional",
+                "Class App\\C unconditional",
+                "Interface App\\I unconditional",
+                "Enum App\\E unconditional",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_functions() {
+        assert_eq!(
+            conditions(
+                "<?php function outer() { function inner() { trait T {} } }
+class C { public function m() { function f() {} } }
+$f = fn () => function () { class D {} };"
+            ),
+            vec![
+                "Function outer unconditional",
+                "Function inner in function at 7",
+                "Trait T in function at 26",
+                "Class C unconditional",
+                "Function f in function at 18",
+                "Class D in function at 15",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conditional_declarations() {
+        assert_eq!(
+            conditions(
+                "<?php
+if ($flag) { class A {} } else { class A {} }
+if (!class_exists('B')) { class B {} }
+try { function f() {} } catch (Exception $e) { foreach ($a as $b) { function g() {} } }
+switch ($a) { case 1: interface I {} }"
+            ),
+            vec![
+                "Class A in conditional at 1",
+                "Class A in conditional at 1",
+                "Class B in conditional at 1",
+                "Function f in conditional at 1",
+                "Function g in conditional at 48",
+                "Interface I in conditional at 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_functions_and_conditionals() {
+        assert_eq!(
+            conditions(
+                "<?php
+function a() { if (true) { function b() {} } }
+while (true) { function c() { function d() {} } }
+for (;;) { function e() { while (1) { class F {} } } }"
+            ),
+            vec![
+                "Function a unconditional",
+                "Function b in function at 1 and conditional at 16",
+                "Function c in conditional at 1",
+                "Function d in function at 16",
+                "Function e in conditional at 1",
+                "Class F in function at 12 and conditional at 27",
+            ]
+        );
+    }
+}
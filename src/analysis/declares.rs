@@ -0,0 +1,201 @@
+//! The parts of a program that `declare` directives apply to, see [`declare_regions`].
+
+use crate::lexer::token::Span;
+use crate::parser::ast::declares::DeclareBody;
+use crate::parser::ast::declares::DeclareEntry;
+use crate::parser::ast::namespaces::NamespaceStatement;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+
+/// How far a `declare` directive reaches.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DeclareScope {
+    /// `declare(ticks=1);` applies to the rest of the file.
+    File,
+    /// `declare(ticks=1) { ... }` or `declare(ticks=1): ... enddeclare;`
+    Block,
+    /// `declare(ticks=1) tick();`
+    Statement,
+}
+
+/// The part of a program that a `declare` directive applies to.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DeclareRegion {
+    /// The directive, e.g. `ticks=1`.
+    pub directive: DeclareEntry,
+    pub scope: DeclareScope,
+    /// The `{` or `:` of a block, or else the `declare` keyword.
+    pub start: Span,
+    /// The `}` or `enddeclare` of a block, the `;` of a statement, or the `declare`
+    /// keyword of the directive that overrides a file-scoped one. `None` when the
+    /// directive applies up to the end of the file.
+    pub end: Option<Span>,
+}
+
+/// Find the region of every `declare` directive, in source order.
+///
+/// Directives are found at the top level, in namespaces and in the bodies of other
+/// `declare` statements. A file-scoped directive lasts until the next file-scoped
+/// directive with the same name, or else until the end of the file. Inside a block, it
+/// lasts until the end of the block at most. A directive nested in a block doesn't
+/// end the region of an outer one, so regions can overlap, the innermost applying.
+pub fn declare_regions(program: &Program) -> Vec<DeclareRegion> {
+    let mut regions = vec![];
+    collect(program, None, &mut regions);
+
+    regions
+}
+
+fn collect(statements: &[Statement], until: Option<Span>, regions: &mut Vec<DeclareRegion>) {
+    let mut sequence = vec![];
+    flatten(statements, &mut sequence);
+
+    for (index, statement) in sequence.iter().enumerate() {
+        let Statement::Declare(declare) = statement else {
+            continue;
+        };
+
+        let (scope, start, end, body) = match &declare.body {
+            DeclareBody::Noop { .. } => (DeclareScope::File, declare.declare, until, None),
+            DeclareBody::Braced {
+                left_brace,
+                statements,
+                right_brace,
+            } => (
+                DeclareScope::Block,
+                *left_brace,
+                Some(*right_brace),
+                Some(statements),
+            ),
+            DeclareBody::Block {
+                colon,
+                statements,
+                end: (enddeclare, _),
+            } => (
+                DeclareScope::Block,
+                *colon,
+                Some(*enddeclare),
+                Some(statements),
+            ),
+            DeclareBody::Expression { semicolon, .. } => (
+                DeclareScope::Statement,
+                declare.declare,
+                Some(*semicolon),
+                None,
+            ),
+        };
+
+        for entry in &declare.entries.entries {
+            let end = match scope {
+                DeclareScope::File => overridden_at(&sequence[index + 1..], entry).or(end),
+                _ => end,
+            };
+
+            regions.push(DeclareRegion {
+                directive: entry.clone(),
+                scope,
+                start,
+                end,
+            });
+        }
+
+        if let Some(body) = body {
+            collect(body, end, regions);
+        }
+    }
+}
+
+/// Add the statements to `sequence`, replacing namespaces with their statements, as
+/// directives reach from one namespace into the next.
+fn flatten<'a>(statements: &'a [Statement], sequence: &mut Vec<&'a Statement>) {
+    for statement in statements {
+        match statement {
+            Statement::Namespace(NamespaceStatement::Unbraced(namespace)) => {
+                flatten(&namespace.statements, sequence)
+            }
+            Statement::Namespace(NamespaceStatement::Braced(namespace)) => {
+                flatten(&namespace.body.statements, sequence)
+            }
+            statement => sequence.push(statement),
+        }
+    }
+}
+
+/// The `declare` keyword of the first file-scoped directive with the same name.
+fn overridden_at(statements: &[&Statement], entry: &DeclareEntry) -> Option<Span> {
+    statements.iter().find_map(|statement| match statement {
+        Statement::Declare(declare)
+            if declare.body.is_file_scoped()
+                && declare.entries.entries.iter().any(|other| {
+                    other.key.value[..].eq_ignore_ascii_case(&entry.key.value[..])
+                }) =>
+        {
+            Some(declare.declare)
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::literals::Literal;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0405-declare-regions/code.php");
+
+    /// The regions as `name=value scope lines`, with `$` for the end of the file.
+    fn outline(code: &str) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        declare_regions(&program)
+            .iter()
+            .map(|region| {
+                let value = match &region.directive.value {
+                    Literal::String(literal) => &literal.value,
+                    Literal::Integer(literal) => &literal.value,
+                    Literal::Float(literal) => &literal.value,
+                };
+                let end = region
+                    .end
+                    .map_or("$".to_string(), |end| end.line.to_string());
+
+                format!(
+                    "{}={} {:?} {}-{}",
+                    region.directive.key, value, region.scope, region.start.line, end
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_file_and_block_regions() {
+        assert_eq!(
+            outline(SOURCE),
+            vec![
+                "strict_types=1 File 3-$",
+                "ticks=1 File 5-18",
+                "ticks=5 Block 9-13",
+                "encoding='UTF-8' Block 9-13",
+                "ticks=10 File 11-13",
+                "ticks=2 File 18-$",
+                "ticks=3 Statement 20-20",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directives_reach_across_namespaces() {
+        assert_eq!(
+            outline("<?php namespace A; declare(ticks=1); namespace B;\ndeclare(ticks=2);"),
+            vec!["ticks=1 File 1-2", "ticks=2 File 2-$"]
+        );
+    }
+
+    #[test]
+    fn test_alternative_syntax_block() {
+        assert_eq!(
+            outline("<?php declare(ticks=1):\n\ttick();\nenddeclare;"),
+            vec!["ticks=1 Block 1-3"]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Defaults whose type doesn't fit the declared type, see [`default_type_mismatches`].
+
+use std::fmt::Display;
+
+use crate::lexer::token::Span;
+use crate::parser::ast::data_type::Type;
+use crate::parser::ast::functions::ConstructorParameter;
+use crate::parser::ast::functions::FunctionParameter;
+use crate::parser::ast::literals::Literal;
+use crate::parser::ast::operators::ArithmeticOperationExpression;
+use crate::parser::ast::properties::Property;
+use crate::parser::ast::properties::PropertyEntry;
+use crate::parser::ast::properties::VariableProperty;
+use crate::parser::ast::variables::SimpleVariable;
+use crate::parser::ast::Expression;
+use crate::parser::ast::Program;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+/// The type of a literal default value.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DefaultType {
+    Int,
+    Float,
+    String,
+    True,
+    False,
+    Null,
+    Array,
+}
+
+impl Display for DefaultType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DefaultType::Int => "int",
+            DefaultType::Float => "float",
+            DefaultType::String => "string",
+            DefaultType::True => "true",
+            DefaultType::False => "false",
+            DefaultType::Null => "null",
+            DefaultType::Array => "array",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A parameter or property whose default value doesn't fit its declared type.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DefaultTypeMismatch {
+    /// The parameter or property, e.g. `$count` in `int $count = '1'`.
+    pub name: SimpleVariable,
+    pub declared: Type,
+    pub default: DefaultType,
+}
+
+/// Find the parameters and properties whose literal default value has a type that
+/// their declared type doesn't accept, in source order.
+///
+/// Defaults are checked when PHP compiles them, whether or not `strict_types` is on,
+/// and aren't coerced: the only conversion allowed is from an `int` to a `float`. A
+/// union type accepts a default that one of its members accepts. A `null` default is
+/// accepted by a nullable type, and by the type of a parameter that isn't promoted to
+/// a property, which it makes nullable. A `null` default of a property that isn't
+/// nullable is already reported while parsing. Defaults that aren't literals, such as
+/// constants, are skipped.
+pub fn default_type_mismatches(program: &Program) -> Vec<DefaultTypeMismatch> {
+    let mut collector = DefaultCollector { mismatches: vec![] };
+    program.visit_spans(&mut collector);
+
+    collector.mismatches
+}
+
+struct DefaultCollector {
+    mismatches: Vec<DefaultTypeMismatch>,
+}
+
+impl DefaultCollector {
+    fn check(
+        &mut self,
+        name: &SimpleVariable,
+        declared: &Option<Type>,
+        default: Option<&Expression>,
+        implicitly_nullable: bool,
+    ) {
+        let (Some(declared), Some(default)) = (declared, default) else {
+            return;
+        };
+        let Some(default) = default_type(default) else {
+            return;
+        };
+
+        if default == DefaultType::Null && implicitly_nullable {
+            return;
+        }
+
+        if !accepts(declared, default) {
+            self.mismatches.push(DefaultTypeMismatch {
+                name: name.clone(),
+                declared: declared.clone(),
+                default,
+            });
+        }
+    }
+
+    fn check_entries(&mut self, declared: &Option<Type>, entries: &[PropertyEntry]) {
+        for entry in entries {
+            if let PropertyEntry::Initialized {
+                variable, value, ..
+            } = entry
+            {
+                self.check(variable, declared, Some(value), false);
+            }
+        }
+    }
+}
+
+impl<'a> SpanVisitor<'a> for DefaultCollector {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(parameter) = node.downcast_ref::<FunctionParameter>() {
+            self.check(
+                &parameter.name,
+                &parameter.data_type,
+                parameter.default.as_ref(),
+                true,
+            );
+        } else if let Some(parameter) = node.downcast_ref::<ConstructorParameter>() {
+            self.check(
+                &parameter.name,
+                &parameter.data_type,
+                parameter.default.as_ref(),
+                parameter.modifiers.is_empty(),
+            );
+        } else if let Some(property) = node.downcast_ref::<Property>() {
+            self.check_entries(&property.r#type, &property.entries);
+        } else if let Some(property) = node.downcast_ref::<VariableProperty>() {
+            self.check_entries(&property.r#type, &property.entries);
+        }
+    }
+}
+
+/// The type of a default value, if it is a literal.
+fn default_type(default: &Expression) -> Option<DefaultType> {
+    Some(match default {
+        Expression::Literal(Literal::Integer(_)) => DefaultType::Int,
+        Expression::Literal(Literal::Float(_)) => DefaultType::Float,
+        Expression::Literal(Literal::String(_)) | Expression::Nowdoc(_) => DefaultType::String,
+        Expression::Bool(bool) if bool.value => DefaultType::True,
+        Expression::Bool(_) => DefaultType::False,
+        Expression::Null(_) => DefaultType::Null,
+        Expression::ShortArray(_) | Expression::Array(_) => DefaultType::Array,
+        Expression::ArithmeticOperation(
+            ArithmeticOperationExpression::Negative { right, .. }
+            | ArithmeticOperationExpression::Positive { right, .. },
+        ) => match default_type(right)? {
+            number @ (DefaultType::Int | DefaultType::Float) => number,
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+fn accepts(declared: &Type, default: DefaultType) -> bool {
+    match declared {
+        Type::Mixed(_) => true,
+        Type::Nullable(_, inner) => default == DefaultType::Null || accepts(inner, default),
+        Type::Union(types) => types.iter().any(|inner| accepts(inner, default)),
+        Type::Null(_) => default == DefaultType::Null,
+        Type::True(_) => default == DefaultType::True,
+        Type::False(_) => default == DefaultType::False,
+        Type::Boolean(_) => matches!(default, DefaultType::True | DefaultType::False),
+        Type::Integer(_) => default == DefaultType::Int,
+        Type::Float(_) => matches!(default, DefaultType::Int | DefaultType::Float),
+        Type::String(_) => default == DefaultType::String,
+        Type::Array(_) | Type::Iterable(_) => default == DefaultType::Array,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0408-default-type-mismatches/code.php");
+
+    fn mismatches(code: &str) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        default_type_mismatches(&program)
+            .iter()
+            .map(|mismatch| {
+                format!(
+                    "{} {}: {}",
+                    mismatch.declared, mismatch.name.name, mismatch.default
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_type_mismatches() {
+        assert_eq!(
+            mismatches(SOURCE),
+            vec![
+                "int $retries: string",
+                "int|bool $limit: float",
+                "string $label: null",
+                "callable $callback: string",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accepted_defaults() {
+        for code in [
+            "<?php function f(float $a = 1, float $b = -1.5, ?string $c = null) {}",
+            "<?php function f(array $a = [1, 2], iterable $b = array(), mixed $c = 'x') {}",
+            "<?php function f(bool $a = false, true $b = true, int|string $c = '1') {}",
+            "<?php function f(string $a = null, int $b = PHP_INT_MAX, string $c = <<<'EOT'\nx\nEOT) {}",
+            "<?php class A { public ?int $a = null; public float $b = 0; var $c = 'x'; }",
+            "<?php $f = fn (int $a = -1, $b = '') => $a;",
+        ] {
+            assert_eq!(mismatches(code), Vec::<String>::new(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_rejected_defaults() {
+        assert_eq!(
+            mismatches("<?php function f(int $a = 1.0, false $b = true, Foo $c = []) {}"),
+            vec!["int $a: float", "false $b: true", "Foo $c: array"]
+        );
+        assert_eq!(
+            mismatches("<?php class A { function __construct(public string $a = null, public int $b = -'1') {} }"),
+            vec!["string $a: null"]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! The places where a program uses enums, see [`enum_usages`].
+
+use std::collections::BTreeMap;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::data_type::Type;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::ConstantFetchExpression;
+use crate::parser::ast::Expression;
+use crate::parser::ast::InstanceofExpression;
+use crate::parser::ast::Program;
+use crate::parser::ast::StaticMethodCallExpression;
+use crate::parser::symbols::SymbolKind;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+use super::names::ResolvedNames;
+
+/// How an enum is used.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum EnumUsageKind {
+    /// `Suit::Hearts`, with the name of the case. Constants of an enum are fetched the
+    /// same way, so they are included.
+    Case(ByteString),
+    /// `Suit::from($value)` or `Suit::tryFrom($value)`, with the name of the method as
+    /// written.
+    Factory(ByteString),
+    /// `Suit::cases()`
+    Cases,
+    /// `Suit $suit`, the type of a parameter, property, constant or return value.
+    Type,
+    /// `$value instanceof Suit`
+    Instanceof,
+}
+
+/// A place where an enum is used.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EnumUsage {
+    pub kind: EnumUsageKind,
+    /// The fully qualified name of the enum, or else the name as written.
+    pub name: ByteString,
+    /// The name of the enum, e.g. `Suit` in `Suit::Hearts`.
+    pub span: Span,
+}
+
+/// The usages of the enums of a program, see [`enum_usages`].
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct EnumUsageIndex {
+    /// The usages of every enum, by fully qualified name, in source order.
+    pub enums: BTreeMap<ByteString, Vec<EnumUsage>>,
+    /// The usages of names that aren't known to be enums or other classes, in source
+    /// order.
+    pub unresolved: Vec<EnumUsage>,
+}
+
+/// Find every place where a program could use an enum: fetching a case, calling
+/// `from`, `tryFrom` or `cases`, naming it as a type, or checking it with `instanceof`.
+///
+/// Names are resolved with `names`, which tells enums from other classes. Every enum
+/// that `names` knows of is in the index, including those declared elsewhere, and
+/// usages of the other classes that it knows of are left out. Usages of names that it
+/// doesn't know of, or can't resolve, are put apart as unresolved rather than guessed.
+/// Fetches and calls on `self`, `static` and `parent` are left out, as these have no
+/// span.
+pub fn enum_usages(program: &Program, names: &ResolvedNames) -> EnumUsageIndex {
+    let mut collector = EnumUsageCollector {
+        names,
+        index: EnumUsageIndex::default(),
+    };
+
+    for (name, kind) in names.declarations() {
+        if kind == SymbolKind::Enum {
+            collector.index.enums.insert(name.clone(), vec![]);
+        }
+    }
+
+    program.visit_spans(&mut collector);
+
+    let mut index = collector.index;
+    for usages in index.enums.values_mut() {
+        usages.sort_by_key(|usage| usage.span.position);
+    }
+    index.unresolved.sort_by_key(|usage| usage.span.position);
+
+    index
+}
+
+struct EnumUsageCollector<'a> {
+    names: &'a ResolvedNames,
+    index: EnumUsageIndex,
+}
+
+impl EnumUsageCollector<'_> {
+    fn usage(&mut self, kind: EnumUsageKind, span: Span, written: &ByteString) {
+        let declaration = self
+            .names
+            .resolve(span)
+            .map(|name| (name, self.names.declaration(name)));
+
+        match declaration {
+            Some((_, Some((name, SymbolKind::Enum)))) => {
+                self.index
+                    .enums
+                    .entry(name.clone())
+                    .or_default()
+                    .push(EnumUsage {
+                        kind,
+                        name: name.clone(),
+                        span,
+                    });
+            }
+            Some((_, Some(_))) => {}
+            Some((name, None)) => self.index.unresolved.push(EnumUsage {
+                kind,
+                name: name.clone(),
+                span,
+            }),
+            None => self.index.unresolved.push(EnumUsage {
+                kind,
+                name: written.clone(),
+                span,
+            }),
+        }
+    }
+}
+
+impl<'a> SpanVisitor<'a> for EnumUsageCollector<'_> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(fetch) = node.downcast_ref::<ConstantFetchExpression>() {
+            if let (Some(target), Identifier::SimpleIdentifier(constant)) =
+                (target(&fetch.target), &fetch.constant)
+            {
+                if !constant.value.eq_ignore_ascii_case(b"class") {
+                    let kind = EnumUsageKind::Case(constant.value.clone());
+
+                    self.usage(kind, target.span, &target.value);
+                }
+            }
+        } else if let Some(call) = node.downcast_ref::<StaticMethodCallExpression>() {
+            if let (Some(target), Identifier::SimpleIdentifier(method)) =
+                (target(&call.target), &call.method)
+            {
+                let kind = if method.value.eq_ignore_ascii_case(b"from")
+                    || method.value.eq_ignore_ascii_case(b"tryFrom")
+                {
+                    EnumUsageKind::Factory(method.value.clone())
+                } else if method.value.eq_ignore_ascii_case(b"cases") {
+                    EnumUsageKind::Cases
+                } else {
+                    return;
+                };
+
+                self.usage(kind, target.span, &target.value);
+            }
+        } else if let Some(instanceof) = node.downcast_ref::<InstanceofExpression>() {
+            if let Some(target) = target(&instanceof.right) {
+                self.usage(EnumUsageKind::Instanceof, target.span, &target.value);
+            }
+        } else if let Some(r#type) = node.downcast_ref::<Type>() {
+            match r#type {
+                Type::Named(span, name) => self.usage(EnumUsageKind::Type, *span, name),
+                // `self` in a trait or an anonymous class can't be resolved, but it
+                // doesn't refer to an enum either.
+                Type::SelfReference(span) if self.names.resolve(*span).is_some() => {
+                    self.usage(EnumUsageKind::Type, *span, &"self".into())
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The name of the class on the left of `::` or the right of `instanceof`.
+fn target(expression: &Expression) -> Option<&SimpleIdentifier> {
+    match expression {
+        Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => Some(identifier),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::resolve_names;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0409-enum-usages/code.php");
+
+    fn outline(usages: &[EnumUsage]) -> Vec<String> {
+        usages
+            .iter()
+            .map(|usage| format!("{}:{} {:?}", usage.span.line, usage.span.column, usage.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_enum_usages() {
+        let program = crate::parse(SOURCE).unwrap();
+        let index = enum_usages(&program, &resolve_names(&program));
+
+        assert_eq!(
+            index
+                .enums
+                .keys()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>(),
+            vec!["App\\Cards\\Suit"]
+        );
+        assert_eq!(
+            outline(&index.enums[&ByteString::from("App\\Cards\\Suit")]),
+            vec![
+                "9:56 Type",
+                "27:19 Type",
+                "27:48 Type",
+                "29:30 Instanceof",
+                "29:48 Case(\"Hearts\")",
+                "30:20 Factory(\"tryFrom\")",
+                "35:18 Cases",
+                "39:16 Factory(\"from\")",
+            ]
+        );
+
+        // `Legacy\Suit::Hearts` is a constant of a class, but `Deck` is unknown.
+        assert_eq!(outline(&index.unresolved), vec!["36:18 Case(\"Hearts\")"]);
+        assert_eq!(index.unresolved[0].name, b"App\\Game\\Deck");
+    }
+
+    #[test]
+    fn test_enums_declared_elsewhere() {
+        let program = crate::parse(
+            "<?php use Vendor\\Status; use Vendor\\Client;
+            Status::Active; Client::VERSION; static::Active; Other::cases();",
+        )
+        .unwrap();
+
+        let mut names = resolve_names(&program);
+        names.declare("\\Vendor\\Status", SymbolKind::Enum);
+        names.declare("Vendor\\Client", SymbolKind::Class);
+
+        let index = enum_usages(&program, &names);
+
+        assert_eq!(
+            outline(&index.enums[&ByteString::from("Vendor\\Status")]),
+            vec!["2:13 Case(\"Active\")"]
+        );
+        assert_eq!(
+            index
+                .unresolved
+                .iter()
+                .map(|usage| usage.name.to_string())
+                .collect::<Vec<_>>(),
+            vec!["Other"]
+        );
+    }
+}
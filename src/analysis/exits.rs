@@ -0,0 +1,303 @@
+//! The places where a program exits, see [`exit_points`].
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::arguments::Argument;
+use crate::parser::ast::arguments::SingleArgument;
+use crate::parser::ast::classes::AnonymousClassExpression;
+use crate::parser::ast::classes::ClassStatement;
+use crate::parser::ast::enums::BackedEnumStatement;
+use crate::parser::ast::enums::UnitEnumStatement;
+use crate::parser::ast::functions::ArrowFunctionExpression;
+use crate::parser::ast::functions::ClosureExpression;
+use crate::parser::ast::functions::ConcreteConstructor;
+use crate::parser::ast::functions::ConcreteMethod;
+use crate::parser::ast::functions::FunctionStatement;
+use crate::parser::ast::interfaces::InterfaceStatement;
+use crate::parser::ast::namespaces::BracedNamespace;
+use crate::parser::ast::namespaces::UnbracedNamespace;
+use crate::parser::ast::traits::TraitStatement;
+use crate::parser::ast::DieExpression;
+use crate::parser::ast::ExitExpression;
+use crate::parser::ast::Program;
+use crate::spans::bounds;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+use super::arguments::evaluate;
+use super::arguments::AttrValue;
+use super::names::ResolvedNames;
+
+/// What an `exit` or `die` is given, which decides what it does.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ExitArgument {
+    /// `exit(1)`, which exits with the status.
+    IntStatus(i64),
+    /// `exit('failed')`, which prints the message and exits with the status 0.
+    Message(ByteString),
+    /// An argument whose value can't be known without running the code, starting at
+    /// the span.
+    Dynamic(Span),
+    /// `exit`, or `exit()`, which exits with the status 0.
+    None,
+}
+
+/// An `exit` or `die`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExitPoint {
+    /// The `exit` or `die` keyword.
+    pub span: Span,
+    /// Whether it is a `die`, which is the same as `exit`.
+    pub die: bool,
+    pub argument: ExitArgument,
+    /// The innermost function that contains it, as in `App\run` or `App\Command::run`,
+    /// or `{closure}` for a closure or an arrow function. `None` at the top level of
+    /// the file.
+    pub function: Option<ByteString>,
+}
+
+/// Find every `exit` and `die` of a program, in source order, with what their argument
+/// evaluates to.
+///
+/// Arguments are evaluated as constant expressions, so `exit(1 + 1)` has the status 2
+/// and `die('no ' . 'input')` the message `no input`. Any other value is dynamic, even
+/// if PHP would convert it, like `exit(true)`.
+pub fn exit_points(program: &Program) -> Vec<ExitPoint> {
+    let mut collector = ExitCollector::default();
+
+    program.visit_spans(&mut collector);
+
+    collector.exits
+}
+
+#[derive(Default)]
+struct ExitCollector {
+    exits: Vec<ExitPoint>,
+    names: ResolvedNames,
+    namespace: Option<ByteString>,
+    class: Option<ByteString>,
+    function: Option<ByteString>,
+    /// A frame for each segment that was entered.
+    frames: Vec<Option<Scope>>,
+}
+
+impl ExitCollector {
+    fn exit(&mut self, span: Span, die: bool, argument: &Option<Box<SingleArgument>>) {
+        let argument = match argument.as_deref().map(|argument| &argument.argument) {
+            Some(Argument::Positional(argument)) => match evaluate(&argument.value, &self.names) {
+                AttrValue::Int(status) => ExitArgument::IntStatus(status),
+                AttrValue::Str(message) => ExitArgument::Message(message),
+                _ => ExitArgument::Dynamic(bounds(&[&argument.value]).0),
+            },
+            // Named arguments aren't accepted, nor are spread ones, which start with
+            // their `...`.
+            Some(Argument::Named(argument)) => ExitArgument::Dynamic(argument.name.span),
+            None => ExitArgument::None,
+        };
+
+        self.exits.push(ExitPoint {
+            span,
+            die,
+            argument,
+            function: self.function.clone(),
+        });
+    }
+
+    fn qualified(&self, name: &[u8]) -> ByteString {
+        match &self.namespace {
+            Some(namespace) => [&namespace[..], b"\\", name].concat().into(),
+            None => name.into(),
+        }
+    }
+
+    /// Visit the node that was entered last in `class` or `function`, until it is left.
+    fn within(&mut self, class: Option<ByteString>, function: Option<ByteString>) {
+        let outer_class = match class {
+            Some(class) => self.class.replace(class),
+            None => self.class.clone(),
+        };
+        let outer_function = match function {
+            Some(function) => self.function.replace(function),
+            None => self.function.clone(),
+        };
+
+        if let Some(frame) = self.frames.last_mut() {
+            *frame = Some(Scope::Within(outer_class, outer_function));
+        }
+    }
+
+    fn method(&self, name: &[u8]) -> ByteString {
+        match &self.class {
+            Some(class) => [&class[..], b"::", name].concat().into(),
+            None => name.into(),
+        }
+    }
+}
+
+/// What to restore when a segment is left.
+enum Scope {
+    /// The end of a braced namespace.
+    Namespace,
+    /// The class and function around a class or function.
+    Within(Option<ByteString>, Option<ByteString>),
+}
+
+impl<'a> SpanVisitor<'a> for ExitCollector {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        self.frames.push(None);
+
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        let (class, function) = if let Some(exit) = node.downcast_ref::<ExitExpression>() {
+            self.exit(exit.exit, false, &exit.argument);
+
+            return;
+        } else if let Some(die) = node.downcast_ref::<DieExpression>() {
+            self.exit(die.die, true, &die.argument);
+
+            return;
+        } else if let Some(namespace) = node.downcast_ref::<BracedNamespace>() {
+            self.namespace = namespace.name.as_ref().map(|name| name.value.clone());
+
+            if let Some(frame) = self.frames.last_mut() {
+                *frame = Some(Scope::Namespace);
+            }
+
+            return;
+        } else if let Some(namespace) = node.downcast_ref::<UnbracedNamespace>() {
+            self.namespace = Some(namespace.name.value.clone());
+
+            return;
+        } else if let Some(class) = node.downcast_ref::<ClassStatement>() {
+            (Some(self.qualified(&class.name.value)), None)
+        } else if let Some(interface) = node.downcast_ref::<InterfaceStatement>() {
+            (Some(self.qualified(&interface.name.value)), None)
+        } else if let Some(r#trait) = node.downcast_ref::<TraitStatement>() {
+            (Some(self.qualified(&r#trait.name.value)), None)
+        } else if let Some(r#enum) = node.downcast_ref::<UnitEnumStatement>() {
+            (Some(self.qualified(&r#enum.name.value)), None)
+        } else if let Some(r#enum) = node.downcast_ref::<BackedEnumStatement>() {
+            (Some(self.qualified(&r#enum.name.value)), None)
+        } else if node.is::<AnonymousClassExpression>() {
+            (Some("class@anonymous".into()), None)
+        } else if let Some(function) = node.downcast_ref::<FunctionStatement>() {
+            (None, Some(self.qualified(&function.name.value)))
+        } else if let Some(method) = node.downcast_ref::<ConcreteMethod>() {
+            (None, Some(self.method(&method.name.value)))
+        } else if let Some(constructor) = node.downcast_ref::<ConcreteConstructor>() {
+            (None, Some(self.method(&constructor.name.value)))
+        } else if node.is::<ClosureExpression>() || node.is::<ArrowFunctionExpression>() {
+            (None, Some("{closure}".into()))
+        } else {
+            return;
+        };
+
+        self.within(class, function);
+    }
+
+    fn leave(&mut self) {
+        match self.frames.pop().flatten() {
+            Some(Scope::Namespace) => self.namespace = None,
+            Some(Scope::Within(class, function)) => {
+                self.class = class;
+                self.function = function;
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exits(code: &str) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        exit_points(&program)
+            .iter()
+            .map(|exit| {
+                let argument = match &exit.argument {
+                    ExitArgument::IntStatus(status) => format!("status {}", status),
+                    ExitArgument::Message(message) => format!("message {}", message),
+                    ExitArgument::Dynamic(span) => format!("dynamic at {}", span.column),
+                    ExitArgument::None => "none".to_string(),
+                };
+
+                format!(
+                    "{}:{} {} {}{}",
+                    exit.span.line,
+                    exit.span.column,
+                    if exit.die { "die" } else { "exit" },
+                    argument,
+                    exit.function
+                        .as_ref()
+                        .map(|function| format!(" in {}", function))
+                        .unwrap_or_default()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_exit_arguments() {
+        assert_eq!(
+            exits(
+                "<?php
+exit(2);
+exit(-1 << 2);
+die('bye');
+die(\"no \" . 'input');
+exit($status);
+exit(PHP_EOL);
+exit;
+die();"
+            ),
+            vec![
+                "2:1 exit status 2",
+                "3:1 exit status -4",
+                "4:1 die message bye",
+                "5:1 die message no input",
+                "6:1 exit dynamic at 6",
+                "7:1 exit dynamic at 6",
+                "8:1 exit none",
+                "9:1 die none",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_containing_functions() {
+        assert_eq!(
+            exits(
+                "<?php
+namespace App;
+function run() { $f = fn () => exit(1); exit(0); }
+class Command {
+    public function handle() { function nested() { die; } exit; }
+}
+exit;"
+            ),
+            vec![
+                "3:32 exit status 1 in {closure}",
+                "3:41 exit status 0 in App\\run",
+                "5:52 die none in App\\nested",
+                "5:59 exit none in App\\Command::handle",
+                "7:1 exit none",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exit_before_closing_tag() {
+        assert_eq!(
+            exits("<?php if ($done) { exit ?>\n<p>Done</p>\n<?php }\nexit?>"),
+            vec!["1:20 exit none", "4:1 exit none"]
+        );
+    }
+}
@@ -0,0 +1,457 @@
+//! The `use` statements at the top of a file or namespace, see [`import_block`].
+
+use std::cmp::Ordering;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::lexer::token::SpanRange;
+use crate::parser::ast::comments::Comment;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::namespaces::NamespaceStatement;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+use crate::parser::ast::UseKind;
+
+/// A run of `use` statements, with nothing but comments between them.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ImportBlock {
+    /// From the `use` keyword of the first statement up to and including the `;` of
+    /// the last, e.g. to replace the whole block with [`Rewriter::replace_span`].
+    ///
+    /// [`Rewriter::replace_span`]: crate::rewrite::Rewriter::replace_span
+    pub span: SpanRange,
+    pub statements: Vec<ImportStatement>,
+    /// The imports of the statements, in source order.
+    pub entries: Vec<ImportEntry>,
+    /// The comments between the statements, but not those before the first one.
+    pub comments: Vec<Comment>,
+}
+
+/// A `use` statement of an [`ImportBlock`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ImportStatement {
+    /// From the `use` keyword up to and including the `;`.
+    pub span: SpanRange,
+    /// Whether the statement is a group use, as in `use Foo\{Bar, Baz};`.
+    pub grouped: bool,
+    /// The index of the part of the block the statement is in, where the parts are
+    /// separated by blank lines.
+    pub section: usize,
+}
+
+/// An import of an [`ImportBlock`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ImportEntry {
+    pub kind: UseKind,
+    /// The imported name, including the prefix of a group use, e.g. `Foo\Bar` in
+    /// `use Foo\{Bar};`, without a leading `\`.
+    pub name: ByteString,
+    pub alias: Option<ByteString>,
+    /// The prefix of the group use that the import is part of, e.g. `Foo\`.
+    pub group: Option<ByteString>,
+    /// The index of the statement of the import in [`ImportBlock::statements`].
+    pub statement: usize,
+    /// From the start of the name up to the end of the alias, if any, or else of the
+    /// name.
+    pub span: SpanRange,
+}
+
+/// How the names of imports are compared.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum NameOrder {
+    /// Byte by byte, so that `Foo\Zed` comes before `Foo\bar`.
+    CaseSensitive,
+    #[default]
+    CaseInsensitive,
+    /// Case-insensitively, with runs of digits compared as numbers, so that
+    /// `Version2` comes before `Version10`.
+    Natural,
+}
+
+/// The order that the imports of an [`ImportBlock`] are expected to be in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ImportOrder {
+    pub names: NameOrder,
+    /// Whether classes come first, then functions, then constants, as PSR-12 requires.
+    pub kinds: bool,
+}
+
+impl Default for ImportOrder {
+    fn default() -> Self {
+        Self {
+            names: NameOrder::CaseInsensitive,
+            kinds: true,
+        }
+    }
+}
+
+impl ImportOrder {
+    pub fn compare(&self, a: &ImportEntry, b: &ImportEntry) -> Ordering {
+        let kinds = if self.kinds {
+            rank(&a.kind).cmp(&rank(&b.kind))
+        } else {
+            Ordering::Equal
+        };
+
+        kinds.then_with(|| match self.names {
+            NameOrder::CaseSensitive => a.name.cmp(&b.name),
+            NameOrder::CaseInsensitive => a
+                .name
+                .iter()
+                .map(u8::to_ascii_lowercase)
+                .cmp(b.name.iter().map(u8::to_ascii_lowercase)),
+            NameOrder::Natural => natural(&a.name, &b.name),
+        })
+    }
+}
+
+impl ImportBlock {
+    /// The indexes of the first two adjacent imports that are out of `order`, if any.
+    pub fn out_of_order(&self, order: &ImportOrder) -> Option<(usize, usize)> {
+        self.entries
+            .windows(2)
+            .position(|pair| order.compare(&pair[0], &pair[1]) == Ordering::Greater)
+            .map(|index| (index, index + 1))
+    }
+
+    pub fn is_sorted(&self, order: &ImportOrder) -> bool {
+        self.out_of_order(order).is_none()
+    }
+}
+
+/// Find the first run of `use` statements of the first namespace, or of the file when
+/// it has no namespace, or `None` if there is no `use` statement there.
+pub fn import_block(program: &Program) -> Option<ImportBlock> {
+    let statements = program
+        .iter()
+        .find_map(|statement| match statement {
+            Statement::Namespace(NamespaceStatement::Unbraced(namespace)) => {
+                Some(&namespace.statements[..])
+            }
+            Statement::Namespace(NamespaceStatement::Braced(namespace)) => {
+                Some(&namespace.body.statements[..])
+            }
+            _ => None,
+        })
+        .unwrap_or(program);
+
+    let first = statements
+        .iter()
+        .position(|statement| matches!(statement, Statement::Use(_) | Statement::GroupUse(_)))?;
+
+    let mut imports: Vec<ImportStatement> = vec![];
+    let mut entries = vec![];
+    let mut comments = vec![];
+    let mut previous: Option<Span> = None;
+
+    for statement in &statements[first..] {
+        let index = imports.len();
+        let (leading, r#use, semicolon, grouped) = match statement {
+            Statement::Use(statement) => {
+                for import in &statement.uses {
+                    entries.push(entry(
+                        statement.kind.clone(),
+                        None,
+                        &import.name,
+                        import.alias.as_ref(),
+                        index,
+                    ));
+                }
+
+                (
+                    &statement.comments,
+                    statement.r#use,
+                    statement.semicolon,
+                    false,
+                )
+            }
+            Statement::GroupUse(statement) => {
+                for import in &statement.uses {
+                    let kind = import.kind.as_ref().unwrap_or(&statement.kind);
+                    entries.push(entry(
+                        kind.clone(),
+                        Some(&statement.prefix),
+                        &import.name,
+                        import.alias.as_ref(),
+                        index,
+                    ));
+                }
+
+                (
+                    &statement.comments,
+                    statement.r#use,
+                    statement.semicolon,
+                    true,
+                )
+            }
+            _ => break,
+        };
+
+        let section = match previous {
+            Some(previous) => {
+                comments.extend(leading.iter().cloned());
+
+                let last = imports[index - 1].section;
+                last + has_blank_line(previous, leading.iter(), r#use) as usize
+            }
+            None => 0,
+        };
+
+        imports.push(ImportStatement {
+            span: r#use.to(after(semicolon, 1)),
+            grouped,
+            section,
+        });
+        previous = Some(semicolon);
+    }
+
+    let start = imports[0].span.start;
+    let end = imports[imports.len() - 1].span.end;
+
+    Some(ImportBlock {
+        span: start.to(end),
+        statements: imports,
+        entries,
+        comments,
+    })
+}
+
+fn entry(
+    kind: UseKind,
+    prefix: Option<&SimpleIdentifier>,
+    name: &SimpleIdentifier,
+    alias: Option<&SimpleIdentifier>,
+    statement: usize,
+) -> ImportEntry {
+    let full = match prefix {
+        Some(prefix) => [&prefix.value[..], &name.value[..]].concat(),
+        None => name.value.to_vec(),
+    };
+    let last = alias.unwrap_or(name);
+
+    ImportEntry {
+        kind,
+        name: full.strip_prefix(b"\\").unwrap_or(&full).into(),
+        alias: alias.map(|alias| alias.value.clone()),
+        group: prefix.map(|prefix| prefix.value.clone()),
+        statement,
+        span: name.span.to(after(last.span, last.value.len())),
+    }
+}
+
+/// Whether there is an empty line between the `;` of a statement and the `use` keyword
+/// of the next one, which the comments in between don't take up.
+fn has_blank_line<'a>(
+    semicolon: Span,
+    comments: impl Iterator<Item = &'a Comment>,
+    r#use: Span,
+) -> bool {
+    let mut line = semicolon.line;
+
+    for comment in comments {
+        if comment.span.line > line + 1 {
+            return true;
+        }
+
+        let lines = comment
+            .content
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count();
+        line = comment.span.line + lines;
+    }
+
+    r#use.line > line + 1
+}
+
+/// Classes, then functions, then constants.
+fn rank(kind: &UseKind) -> u8 {
+    match kind {
+        UseKind::Normal => 0,
+        UseKind::Function => 1,
+        UseKind::Const => 2,
+    }
+}
+
+/// Compare case-insensitively, with runs of digits compared by their value.
+fn natural(a: &[u8], b: &[u8]) -> Ordering {
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        if a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+            let (x, next_i) = digits(a, i);
+            let (y, next_j) = digits(b, j);
+
+            let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(y));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+
+            (i, j) = (next_i, next_j);
+            continue;
+        }
+
+        let ordering = a[i].to_ascii_lowercase().cmp(&b[j].to_ascii_lowercase());
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+
+        i += 1;
+        j += 1;
+    }
+
+    (a.len() - i).cmp(&(b.len() - j))
+}
+
+/// The digits of the number that starts at `start`, without leading zeros, and the
+/// index after it.
+fn digits(bytes: &[u8], start: usize) -> (&[u8], usize) {
+    let end = bytes[start..]
+        .iter()
+        .position(|byte| !byte.is_ascii_digit())
+        .map_or(bytes.len(), |length| start + length);
+    let zeros = bytes[start..end]
+        .iter()
+        .take_while(|byte| **byte == b'0')
+        .count();
+
+    (&bytes[start + zeros..end], end)
+}
+
+/// The point just after a token of `length` bytes that starts at `span`.
+fn after(span: Span, length: usize) -> Span {
+    Span::new(span.line, span.column + length, span.position + length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rewrite::Rewriter;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0424-import-block/code.php");
+
+    /// The entries as `kind name [as alias] [in group] #statement/section`.
+    fn entries(block: &ImportBlock) -> Vec<String> {
+        block
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut outline = format!("{:?} {}", entry.kind, entry.name);
+                if let Some(alias) = &entry.alias {
+                    outline.push_str(&format!(" as {}", alias));
+                }
+                if let Some(group) = &entry.group {
+                    outline.push_str(&format!(" in {}", group));
+                }
+
+                let statement = &block.statements[entry.statement];
+                format!("{} #{}/{}", outline, entry.statement, statement.section)
+            })
+            .collect()
+    }
+
+    fn text(range: SpanRange) -> &'static str {
+        &SOURCE[range.start.position..range.end.position]
+    }
+
+    #[test]
+    fn test_messy_block() {
+        let program = crate::parse(SOURCE).unwrap();
+        let block = import_block(&program).unwrap();
+
+        assert_eq!((block.span.start.line, block.span.end.line), (8, 16));
+        assert!(text(block.span).starts_with("use Illuminate\\Support\\Str;"));
+        assert!(text(block.span).ends_with("use App\\Models\\{Team, function team_name};"));
+
+        assert_eq!(
+            entries(&block),
+            vec![
+                "Normal Illuminate\\Support\\Str #0/0",
+                "Normal Illuminate\\Http\\Request in Illuminate\\Http\\ #1/0",
+                "Normal Illuminate\\Http\\Response as HttpResponse in Illuminate\\Http\\ #1/0",
+                "Normal App\\Models\\User #2/0",
+                "Function App\\Support\\version10 #3/1",
+                "Function App\\Support\\version2 #4/1",
+                "Const App\\Support\\DEBUG #5/1",
+                "Normal App\\Models\\Team in App\\Models\\ #6/1",
+                "Function App\\Models\\team_name in App\\Models\\ #6/1",
+            ]
+        );
+        assert_eq!(text(block.entries[2].span), "Response as HttpResponse");
+        assert_eq!(
+            block
+                .statements
+                .iter()
+                .map(|statement| statement.grouped)
+                .collect::<Vec<_>>(),
+            vec![false, true, false, false, false, false, true]
+        );
+
+        // The comment before the block isn't part of it.
+        let comments: Vec<_> = block
+            .comments
+            .iter()
+            .map(|comment| comment.content.to_string())
+            .collect();
+        assert_eq!(comments, vec!["// the current user", "/* Helpers */"]);
+
+        let order = ImportOrder::default();
+        assert!(!block.is_sorted(&order));
+        assert_eq!(block.out_of_order(&order), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_name_orders() {
+        let block = |code: &str| import_block(&crate::parse(code).unwrap()).unwrap();
+        let order = |names| ImportOrder {
+            names,
+            kinds: false,
+        };
+
+        let versions = block("<?php use A\\Version2; use A\\version10;");
+        assert_eq!(
+            versions.out_of_order(&order(NameOrder::CaseSensitive)),
+            None
+        );
+        assert_eq!(
+            versions.out_of_order(&order(NameOrder::CaseInsensitive)),
+            Some((0, 1))
+        );
+        assert!(versions.is_sorted(&order(NameOrder::Natural)));
+
+        let kinds = block("<?php use const A\\B; use function A\\c; use A\\D;");
+        assert!(kinds.is_sorted(&order(NameOrder::CaseInsensitive)));
+        assert_eq!(kinds.out_of_order(&ImportOrder::default()), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_replace_block() {
+        let code = "<?php\nuse B;\n\nuse A;\n\nclass C {}\n";
+        let program = crate::parse(code).unwrap();
+        let block = import_block(&program).unwrap();
+        assert_eq!(
+            block
+                .statements
+                .iter()
+                .map(|statement| statement.section)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        let mut rewriter = Rewriter::new(code.as_bytes());
+        rewriter.replace_span(block.span, "use A;\nuse B;");
+        assert_eq!(
+            rewriter.apply().unwrap(),
+            b"<?php\nuse A;\nuse B;\n\nclass C {}\n"
+        );
+    }
+
+    #[test]
+    fn test_no_imports() {
+        assert_eq!(import_block(&crate::parse("<?php echo 1;").unwrap()), None);
+        assert_eq!(
+            import_block(&crate::parse("<?php namespace A; echo 1;").unwrap()),
+            None
+        );
+    }
+}
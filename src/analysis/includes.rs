@@ -0,0 +1,228 @@
+//! The files that a program includes, see [`includes`].
+
+use std::path::Path;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::Expression;
+use crate::parser::ast::MagicConstantExpression;
+use crate::parser::ast::Program;
+use crate::spans::bounds;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+use super::arguments::evaluate;
+use super::arguments::evaluate_in;
+use super::arguments::AttrValue;
+use super::names::ResolvedNames;
+
+/// Which of `include`, `include_once`, `require` and `require_once` includes a file.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IncludeKind {
+    Include,
+    IncludeOnce,
+    Require,
+    RequireOnce,
+}
+
+/// The path of an included file, as far as it can be known without running code.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IncludePath {
+    /// A path that is known, such as `'vendor/autoload.php'`, or `__DIR__ . '/a.php'`
+    /// in a file whose name is known.
+    Path(ByteString),
+    /// `__DIR__ . '/a.php'` in a file whose name isn't known, with what follows
+    /// `__DIR__`, as in `/a.php`.
+    RelativeToDir(ByteString),
+    /// A path that can't be known without running the code, starting at the span.
+    Dynamic(Span),
+}
+
+/// An `include`, `include_once`, `require` or `require_once`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Include {
+    /// The keyword.
+    pub span: Span,
+    pub kind: IncludeKind,
+    pub path: IncludePath,
+}
+
+/// Find the files that a program includes, in source order, for the edges of a graph
+/// of the files of a project.
+///
+/// Paths are evaluated as constant expressions, with `__FILE__` and `__DIR__` resolved
+/// against `source_name`, the name of the file, as given to
+/// [`ParserConfig::source_name`]. Without it, a path that starts with `__DIR__` is kept
+/// relative to the directory of the file, and any other use of `__FILE__` or `__DIR__`
+/// makes the path dynamic. Paths aren't resolved against the include path of PHP, nor
+/// normalized, so `__DIR__ . '/../a.php'` keeps its `..`.
+///
+/// [`ParserConfig::source_name`]: crate::parser::config::ParserConfig::source_name
+pub fn includes(program: &Program, source_name: Option<&Path>) -> Vec<Include> {
+    let mut collector = IncludeCollector {
+        includes: vec![],
+        names: ResolvedNames::default(),
+        source_name,
+    };
+
+    program.visit_spans(&mut collector);
+
+    collector.includes
+}
+
+struct IncludeCollector<'a> {
+    includes: Vec<Include>,
+    names: ResolvedNames,
+    source_name: Option<&'a Path>,
+}
+
+impl IncludeCollector<'_> {
+    fn path(&self, path: &Expression) -> IncludePath {
+        if let AttrValue::Str(path) = evaluate_in(path, &self.names, self.source_name) {
+            return IncludePath::Path(path);
+        }
+
+        if self.source_name.is_none() {
+            if let Some(rest) = self.relative_to_dir(path) {
+                return IncludePath::RelativeToDir(rest);
+            }
+        }
+
+        IncludePath::Dynamic(bounds(&[path]).0)
+    }
+
+    /// What follows `__DIR__` in a concatenation that starts with it.
+    fn relative_to_dir(&self, path: &Expression) -> Option<ByteString> {
+        let (first, rest) = match path {
+            Expression::Concat(concat) => (concat.left.as_ref(), vec![concat.right.as_ref()]),
+            Expression::ConcatMany(concat) => {
+                let (first, rest) = concat.parts.split_first()?;
+
+                (first, rest.iter().collect())
+            }
+            _ => return None,
+        };
+
+        if !matches!(
+            first,
+            Expression::MagicConstant(MagicConstantExpression::Directory(_))
+        ) {
+            return None;
+        }
+
+        let mut relative = vec![];
+        for part in rest {
+            match evaluate(part, &self.names) {
+                AttrValue::Str(string) => relative.extend_from_slice(&string),
+                AttrValue::Int(value) => relative.extend(value.to_string().into_bytes()),
+                _ => return None,
+            }
+        }
+
+        Some(relative.into())
+    }
+}
+
+impl<'a> SpanVisitor<'a> for IncludeCollector<'_> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        let (span, kind, path) = match node.downcast_ref::<Expression>() {
+            Some(Expression::Include(include)) => {
+                (include.include, IncludeKind::Include, &include.path)
+            }
+            Some(Expression::IncludeOnce(include)) => (
+                include.include_once,
+                IncludeKind::IncludeOnce,
+                &include.path,
+            ),
+            Some(Expression::Require(require)) => {
+                (require.require, IncludeKind::Require, &require.path)
+            }
+            Some(Expression::RequireOnce(require)) => (
+                require.require_once,
+                IncludeKind::RequireOnce,
+                &require.path,
+            ),
+            _ => return,
+        };
+
+        let path = self.path(path);
+        self.includes.push(Include { span, kind, path });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(code: &str, source_name: Option<&str>) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        includes(&program, source_name.map(Path::new))
+            .iter()
+            .map(|include| {
+                let path = match &include.path {
+                    IncludePath::Path(path) => path.to_string(),
+                    IncludePath::RelativeToDir(path) => format!("__DIR__{}", path),
+                    IncludePath::Dynamic(span) => format!("dynamic at {}", span.column),
+                };
+
+                format!(
+                    "{}:{} {:?} {}",
+                    include.span.line, include.span.column, include.kind, path
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_include_paths() {
+        let code = "<?php
+require_once 'vendor/autoload.php';
+include __DIR__ . '/config.php';
+require __DIR__ . '/../lib/' . 'a.php';
+include_once dirname(__FILE__) . '/b.php';
+require $path;
+if (true) { $x = fn () => include __FILE__; }";
+
+        assert_eq!(
+            paths(code, None),
+            vec![
+                "2:1 RequireOnce vendor/autoload.php",
+                "3:1 Include __DIR__/config.php",
+                "4:1 Require __DIR__/../lib/a.php",
+                "5:1 IncludeOnce dynamic at 14",
+                "6:1 Require dynamic at 9",
+                "7:27 Include dynamic at 35",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_include_paths_with_source_name() {
+        let code = "<?php
+include __DIR__ . '/config.php';
+require __DIR__ . '/../lib/' . 'a.php';
+include __FILE__;";
+
+        assert_eq!(
+            paths(code, Some("app/src/index.php")),
+            vec![
+                "2:1 Include app/src/config.php",
+                "3:1 Require app/src/../lib/a.php",
+                "4:1 Include app/src/index.php",
+            ]
+        );
+
+        assert_eq!(
+            paths("<?php include __DIR__ . '/config.php';", Some("index.php")),
+            vec!["1:7 Include ./config.php"]
+        );
+    }
+}
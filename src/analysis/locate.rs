@@ -0,0 +1,368 @@
+//! Finding the nodes at a position of the source, see [`FindNode`].
+
+use crate::lexer::token::Span;
+use crate::lexer::token::SpanRange;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::variables::SimpleVariable;
+use crate::parser::ast::Expression;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+use crate::spans::extent;
+use crate::spans::range;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+pub trait FindNode {
+    /// Find the statements, expressions, identifiers and variables that contain the
+    /// byte `offset`, e.g. the position of the cursor in an editor, or `None` if it is
+    /// outside of every statement.
+    ///
+    /// A node contains the offsets from its start to its end, both included, so that
+    /// an offset at the boundary of two tokens, as when the cursor is right after a
+    /// name, is in both of the nodes on either side of it. The node to the left is
+    /// preferred then.
+    ///
+    /// Only the top-level statement that contains the offset is searched, which is
+    /// found with a binary search, and its nodes are visited once.
+    fn find_node_at_offset(&self, offset: usize) -> Option<NodePath<'_>>;
+
+    /// Find the identifier or variable that contains the byte `offset`, if any.
+    fn find_identifier_at(&self, offset: usize) -> Option<PathNode<'_>>;
+}
+
+impl FindNode for Program {
+    fn find_node_at_offset(&self, offset: usize) -> Option<NodePath<'_>> {
+        let index =
+            self.partition_point(|statement| extent(statement).is_none_or(|(_, end)| end < offset));
+        let statement = self.get(index)?;
+
+        let mut locator = Locator {
+            offset,
+            frames: vec![],
+            found: vec![],
+        };
+        statement.visit_spans(&mut locator);
+
+        // The statement is left last, and each node is followed by the node around it,
+        // so the path is found from the statement inwards.
+        let mut current = locator.found.len().checked_sub(1)?;
+        let mut nodes = vec![locator.found[current].node];
+        while let Some(inner) = locator
+            .found
+            .iter()
+            .enumerate()
+            .filter(|(_, found)| found.outer == Some(current))
+            .min_by_key(|(_, found)| found.start)
+        {
+            nodes.push(inner.1.node);
+            current = inner.0;
+        }
+
+        Some(NodePath { nodes })
+    }
+
+    fn find_identifier_at(&self, offset: usize) -> Option<PathNode<'_>> {
+        match self.find_node_at_offset(offset)?.nodes.pop()? {
+            node @ (PathNode::Identifier(_) | PathNode::Variable(_)) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+/// The range of the source that a node covers, from the start of its first token to
+/// the end of its last, e.g. the closing parenthesis of a call or the closing bracket
+/// of an array access.
+///
+/// Nodes keep the spans of their tokens, each with the length of the token, so the
+/// range is worked out from those.
+pub trait NodeRange {
+    /// The range of the node, or `None` if it has no tokens at all, like the missing
+    /// middle of `$a ?: $b`.
+    fn range(&self) -> Option<SpanRange>;
+}
+
+impl NodeRange for Expression {
+    fn range(&self) -> Option<SpanRange> {
+        range(self)
+    }
+}
+
+impl NodeRange for Statement {
+    fn range(&self) -> Option<SpanRange> {
+        range(self)
+    }
+}
+
+/// The nodes that contain an offset, from the top-level statement down to the
+/// innermost node.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NodePath<'a> {
+    nodes: Vec<PathNode<'a>>,
+}
+
+impl<'a> NodePath<'a> {
+    pub fn nodes(&self) -> &[PathNode<'a>] {
+        &self.nodes
+    }
+
+    pub fn innermost(&self) -> &PathNode<'a> {
+        &self.nodes[self.nodes.len() - 1]
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PathNode<'a>> {
+        self.nodes.iter()
+    }
+}
+
+/// A node of a [`NodePath`]. Names that are only known when the code runs, like
+/// `$$a` or `$a->{$b}`, aren't nodes of a path, but the variables and expressions in
+/// them are.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PathNode<'a> {
+    Statement(&'a Statement),
+    Expression(&'a Expression),
+    Identifier(&'a SimpleIdentifier),
+    Variable(&'a SimpleVariable),
+}
+
+impl<'a> PathNode<'a> {
+    fn from_segment(segment: Segment<'a>) -> Option<Self> {
+        let Segment::Node(node) = segment else {
+            return None;
+        };
+
+        if let Some(statement) = node.downcast_ref::<Statement>() {
+            Some(PathNode::Statement(statement))
+        } else if let Some(expression) = node.downcast_ref::<Expression>() {
+            Some(PathNode::Expression(expression))
+        } else if let Some(identifier) = node.downcast_ref::<SimpleIdentifier>() {
+            Some(PathNode::Identifier(identifier))
+        } else {
+            node.downcast_ref::<SimpleVariable>()
+                .map(PathNode::Variable)
+        }
+    }
+}
+
+/// Finds the nodes of a path that contain an offset, in a single visit of the nodes of
+/// a statement.
+struct Locator<'a> {
+    offset: usize,
+    /// A frame for each segment that was entered.
+    frames: Vec<Frame<'a>>,
+    /// The nodes of a path that contain the offset, each after the nodes within it.
+    found: Vec<Found<'a>>,
+}
+
+struct Frame<'a> {
+    node: Option<PathNode<'a>>,
+    /// Where the spans within the segment start and end.
+    extent: Option<(usize, usize)>,
+    /// The number of nodes that were found before the segment was entered.
+    found: usize,
+}
+
+struct Found<'a> {
+    node: PathNode<'a>,
+    start: usize,
+    /// The index of the innermost of the nodes found around this one.
+    outer: Option<usize>,
+}
+
+fn join(extent: Option<(usize, usize)>, start: usize, end: usize) -> Option<(usize, usize)> {
+    Some(extent.map_or((start, end), |(first, last)| {
+        (first.min(start), last.max(end))
+    }))
+}
+
+impl<'a> SpanVisitor<'a> for Locator<'a> {
+    fn span(&mut self, span: &'a Span) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.extent = join(frame.extent, span.position, span.end());
+        }
+    }
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        self.frames.push(Frame {
+            node: PathNode::from_segment(segment),
+            extent: None,
+            found: self.found.len(),
+        });
+    }
+
+    fn leave(&mut self) {
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        let Some((start, end)) = frame.extent else {
+            return;
+        };
+
+        if let Some(parent) = self.frames.last_mut() {
+            parent.extent = join(parent.extent, start, end);
+        }
+
+        let Some(node) = frame.node else {
+            return;
+        };
+        if self.offset < start || self.offset > end {
+            return;
+        }
+
+        let index = self.found.len();
+        for inner in &mut self.found[frame.found..] {
+            inner.outer.get_or_insert(index);
+        }
+
+        self.found.push(Found {
+            node,
+            start,
+            outer: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The kinds of the nodes at the offset of the `|` in `code`, which is removed.
+    fn path(code: &str) -> Vec<String> {
+        let offset = code.find('|').unwrap();
+        let code = code.replacen('|', "", 1);
+        let program = crate::parse(&code).unwrap();
+
+        program
+            .find_node_at_offset(offset)
+            .map(|path| path.iter().map(kind).collect())
+            .unwrap_or_default()
+    }
+
+    /// The variant of the node and the variant or type of its value, e.g.
+    /// `Statement(Echo`.
+    fn kind(node: &PathNode) -> String {
+        let debug = format!("{:?}", node);
+        let value = debug.find('(').map_or(debug.len(), |start| start + 1);
+        let end = debug[value..]
+            .find(['(', ' '])
+            .map_or(debug.len(), |end| value + end);
+
+        debug[..end].to_string()
+    }
+
+    #[test]
+    fn test_method_name() {
+        let code = "<?php\nclass A {\n    public function ha|ndle() {}\n}";
+        assert_eq!(
+            path(code),
+            vec!["Statement(Class", "Identifier(SimpleIdentifier"]
+        );
+
+        let offset = code.find('|').unwrap();
+        let program = crate::parse(&code.replacen('|', "", 1)).unwrap();
+        assert!(matches!(
+            program.find_identifier_at(offset),
+            Some(PathNode::Identifier(name)) if name.value == b"handle"
+        ));
+    }
+
+    #[test]
+    fn test_path_borrows_the_program() {
+        let program = crate::parse("<?php echo 1;\n$a = 2;").unwrap();
+        let path = program.find_node_at_offset(15).unwrap();
+
+        assert!(matches!(
+            path.nodes()[0],
+            PathNode::Statement(statement) if std::ptr::eq(statement, &program[2])
+        ));
+        assert!(matches!(
+            path.innermost(),
+            PathNode::Variable(variable) if variable.name == b"$a"
+        ));
+    }
+
+    #[test]
+    fn test_string_interpolation() {
+        assert_eq!(
+            path("<?php echo \"Hello {$user->na|me}!\";"),
+            vec![
+                "Statement(Echo",
+                "Expression(InterpolatedString",
+                "Expression(PropertyFetch",
+                "Expression(Identifier",
+                "Identifier(SimpleIdentifier"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_between_statements() {
+        assert_eq!(
+            path("<?php if ($a) { $b = 1; | $c = 2; }"),
+            vec!["Statement(If", "Statement(Block"]
+        );
+    }
+
+    /// The source that the expression of the first statement of `code` covers.
+    fn covered(code: &str) -> String {
+        let program = crate::parse(code).unwrap();
+        let Statement::Expression(statement) = &program[1] else {
+            panic!("expected an expression statement, found {:?}", program[1]);
+        };
+
+        let range = statement.expression.range().unwrap();
+        code[range.start.position..range.end.position].to_string()
+    }
+
+    #[test]
+    fn test_expression_range() {
+        assert_eq!(covered("<?php foo($a, 1);"), "foo($a, 1)");
+        assert_eq!(covered("<?php $a['b'][0];"), "$a['b'][0]");
+        assert_eq!(covered("<?php $a->b(...) + $c;"), "$a->b(...) + $c");
+        assert_eq!(covered("<?php $a = 10 ** $b;"), "$a = 10 ** $b");
+        assert_eq!(covered("<?php ($a);"), "($a)");
+        assert_eq!(covered("<?php new Foo();"), "new Foo()");
+        assert_eq!(covered("<?php \"a{$b}c\";"), "\"a{$b}c\"");
+    }
+
+    #[test]
+    fn test_expression_range_ending_in_keyword() {
+        assert_eq!(covered("<?php $a = null;"), "$a = null");
+        assert_eq!(covered("<?php $a = true;"), "$a = true");
+        assert_eq!(covered("<?php $a = FALSE;"), "$a = FALSE");
+        assert_eq!(covered("<?php new static;"), "new static");
+        assert_eq!(covered("<?php $a instanceof self;"), "$a instanceof self");
+        assert_eq!(
+            covered("<?php $a instanceof parent;"),
+            "$a instanceof parent"
+        );
+        assert_eq!(covered("<?php $a = __LINE__;"), "$a = __LINE__");
+        assert_eq!(covered("<?php exit;"), "exit");
+    }
+
+    #[test]
+    fn test_expression_range_ending_in_literal() {
+        assert_eq!(covered("<?php $a = 1_000;"), "$a = 1_000");
+        assert_eq!(covered("<?php $a = 'b\\'c';"), "$a = 'b\\'c'");
+        assert_eq!(covered("<?php $a = <<<EOT\nb\nEOT;"), "$a = <<<EOT\nb\nEOT");
+    }
+
+    #[test]
+    fn test_statement_range() {
+        let code = "<?php\nif ($a) {\n    echo 'b';\n}\n";
+        let program = crate::parse(code).unwrap();
+
+        let range = program[1].range().unwrap();
+        assert_eq!((range.start.line, range.start.column), (2, 1));
+        assert_eq!((range.end.line, range.end.column), (4, 2));
+        assert!(Expression::Noop.range().is_none());
+    }
+
+    #[test]
+    fn test_end_of_file() {
+        // The end of the file is right after the last statement.
+        assert_eq!(path("<?php echo $a;|"), vec!["Statement(Echo"]);
+        assert_eq!(path("<?php echo $a;\n|"), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,555 @@
+//! Accesses to members that a class doesn't declare, see [`undefined_member_access`].
+
+use std::any::Any;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::arguments::Argument;
+use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::classes::AnonymousClassExpression;
+use crate::parser::ast::classes::AnonymousClassMember;
+use crate::parser::ast::classes::ClassMember;
+use crate::parser::ast::classes::ClassStatement;
+use crate::parser::ast::functions::AbstractConstructor;
+use crate::parser::ast::functions::AbstractMethod;
+use crate::parser::ast::functions::ArrowFunctionExpression;
+use crate::parser::ast::functions::ClosureExpression;
+use crate::parser::ast::functions::ConcreteConstructor;
+use crate::parser::ast::functions::ConcreteMethod;
+use crate::parser::ast::functions::FunctionStatement;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::literals::Literal;
+use crate::parser::ast::properties::Property;
+use crate::parser::ast::properties::VariableProperty;
+use crate::parser::ast::traits::TraitUsage;
+use crate::parser::ast::variables::SimpleVariable;
+use crate::parser::ast::variables::Variable;
+use crate::parser::ast::Expression;
+use crate::parser::ast::FunctionCallExpression;
+use crate::parser::ast::MethodCallExpression;
+use crate::parser::ast::NullsafeMethodCallExpression;
+use crate::parser::ast::NullsafePropertyFetchExpression;
+use crate::parser::ast::Program;
+use crate::parser::ast::PropertyFetchExpression;
+use crate::parser::error;
+use crate::parser::error::ParseError;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+use super::string_value;
+
+/// Find the `$this->method()` calls and `$this->property` fetches in the methods and
+/// closures of a class that don't name a method or property that the class declares,
+/// as warnings in source order.
+///
+/// Only names written as identifiers are checked. Properties include those promoted
+/// by the constructor. As the members of parent classes and traits aren't known, a
+/// class that extends another one or uses a trait is left out. So are the methods of
+/// a class that declares `__call`, and the properties of one that declares `__get` or
+/// `__set` or allows dynamic properties with `#[AllowDynamicProperties]`. A name that
+/// the class checks with `method_exists` or `property_exists` counts as declared.
+///
+/// The warnings suggest the declared member whose name is the closest to the one
+/// accessed, if it is close enough to be a typo.
+pub fn undefined_member_access(program: &Program) -> Vec<ParseError> {
+    let mut collector = ClassCollector { warnings: vec![] };
+
+    program.visit_spans(&mut collector);
+
+    let mut warnings = collector.warnings;
+    warnings.sort_by_key(|warning| warning.span.position);
+
+    warnings
+}
+
+struct ClassCollector {
+    warnings: Vec<ParseError>,
+}
+
+impl ClassCollector {
+    fn check(&mut self, name: &[u8], attributes: &[AttributeGroup], members: Vec<&dyn Any>) {
+        let mut class = Class {
+            magic_properties: allows_dynamic_properties(attributes),
+            ..Class::default()
+        };
+        let mut accesses = AccessCollector::default();
+
+        for member in members {
+            class.declare(member);
+
+            if let Some(method) = member.downcast_ref::<ConcreteMethod>() {
+                method.visit_spans(&mut accesses);
+            } else if let Some(constructor) = member.downcast_ref::<ConcreteConstructor>() {
+                constructor.visit_spans(&mut accesses);
+            }
+        }
+
+        if class.uses_traits {
+            return;
+        }
+
+        for (method, guarded) in accesses.guarded {
+            if method {
+                class.methods.push(guarded);
+            } else {
+                class.properties.push(guarded);
+            }
+        }
+
+        for access in accesses.accesses {
+            let (kind, declared) = if access.method {
+                if class.magic_methods {
+                    continue;
+                }
+
+                ("method", &class.methods)
+            } else {
+                if class.magic_properties {
+                    continue;
+                }
+
+                ("property", &class.properties)
+            };
+
+            let known = |candidate: &ByteString| {
+                if access.method {
+                    candidate.eq_ignore_ascii_case(&access.name.value)
+                } else {
+                    candidate[..] == access.name.value[..]
+                }
+            };
+            if declared.iter().any(known) {
+                continue;
+            }
+
+            self.warnings.push(error::undefined_member(
+                kind,
+                &access.name.value,
+                name,
+                suggestion(&access.name.value, declared, access.method).map(|name| &name[..]),
+                access.name.span,
+                access.name.value.len(),
+            ));
+        }
+    }
+}
+
+impl<'a> SpanVisitor<'a> for ClassCollector {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(class) = node.downcast_ref::<ClassStatement>() {
+            if class.extends.is_none() {
+                let members = class.body.members.iter().map(class_member).collect();
+
+                self.check(&class.name.value, &class.attributes, members);
+            }
+        } else if let Some(class) = node.downcast_ref::<AnonymousClassExpression>() {
+            if class.extends.is_none() {
+                let members = class
+                    .body
+                    .members
+                    .iter()
+                    .map(anonymous_class_member)
+                    .collect();
+
+                self.check(b"class@anonymous", &class.attributes, members);
+            }
+        }
+    }
+}
+
+/// The declaration of a member, to downcast.
+fn class_member(member: &ClassMember) -> &dyn Any {
+    match member {
+        ClassMember::Constant(constant) => constant,
+        ClassMember::TraitUsage(usage) => usage,
+        ClassMember::Property(property) => property,
+        ClassMember::VariableProperty(property) => property,
+        ClassMember::AbstractMethod(method) => method,
+        ClassMember::AbstractConstructor(constructor) => constructor,
+        ClassMember::ConcreteMethod(method) => method,
+        ClassMember::ConcreteConstructor(constructor) => constructor,
+    }
+}
+
+fn anonymous_class_member(member: &AnonymousClassMember) -> &dyn Any {
+    match member {
+        AnonymousClassMember::Constant(constant) => constant,
+        AnonymousClassMember::TraitUsage(usage) => usage,
+        AnonymousClassMember::Property(property) => property,
+        AnonymousClassMember::VariableProperty(property) => property,
+        AnonymousClassMember::ConcreteMethod(method) => method,
+        AnonymousClassMember::ConcreteConstructor(constructor) => constructor,
+    }
+}
+
+/// The members that a class declares.
+#[derive(Default)]
+struct Class {
+    methods: Vec<ByteString>,
+    /// The names of the properties, without their `$`.
+    properties: Vec<ByteString>,
+    uses_traits: bool,
+    /// Whether the class declares `__call`.
+    magic_methods: bool,
+    /// Whether the class declares `__get` or `__set`, or allows dynamic properties.
+    magic_properties: bool,
+}
+
+impl Class {
+    fn declare(&mut self, member: &dyn Any) {
+        if let Some(method) = member.downcast_ref::<ConcreteMethod>() {
+            self.method(&method.name);
+        } else if let Some(method) = member.downcast_ref::<AbstractMethod>() {
+            self.method(&method.name);
+        } else if let Some(constructor) = member.downcast_ref::<AbstractConstructor>() {
+            self.method(&constructor.name);
+        } else if let Some(constructor) = member.downcast_ref::<ConcreteConstructor>() {
+            self.method(&constructor.name);
+
+            for parameter in constructor.parameters.parameters.iter() {
+                if !parameter.modifiers.is_empty() {
+                    self.property(&parameter.name);
+                }
+            }
+        } else if let Some(property) = member.downcast_ref::<Property>() {
+            for entry in &property.entries {
+                self.property(entry.variable());
+            }
+        } else if let Some(property) = member.downcast_ref::<VariableProperty>() {
+            for entry in &property.entries {
+                self.property(entry.variable());
+            }
+        } else if member.is::<TraitUsage>() {
+            self.uses_traits = true;
+        }
+    }
+
+    fn method(&mut self, name: &SimpleIdentifier) {
+        if name.value.eq_ignore_ascii_case(b"__call") {
+            self.magic_methods = true;
+        }
+        if name.value.eq_ignore_ascii_case(b"__get") || name.value.eq_ignore_ascii_case(b"__set") {
+            self.magic_properties = true;
+        }
+
+        self.methods.push(name.value.clone());
+    }
+
+    fn property(&mut self, variable: &SimpleVariable) {
+        self.properties.push(variable.name[1..].into());
+    }
+}
+
+struct Access {
+    method: bool,
+    name: SimpleIdentifier,
+}
+
+/// The accesses to members of `$this` in a method, and the names checked with
+/// `method_exists` or `property_exists`.
+#[derive(Default)]
+struct AccessCollector {
+    accesses: Vec<Access>,
+    /// Whether the name is a method's, and the name.
+    guarded: Vec<(bool, ByteString)>,
+    /// Whether each segment that was entered is one that `$this` isn't visited in.
+    frames: Vec<bool>,
+    /// The number of those segments that the visitor is in.
+    skipped: usize,
+}
+
+impl<'a> SpanVisitor<'a> for AccessCollector {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            self.frames.push(false);
+
+            return;
+        };
+
+        // `$this` is another object, or none, in these.
+        let is_static = |r#static: &Option<Span>| r#static.is_some();
+        let skipped = node.is::<AnonymousClassExpression>()
+            || node.is::<ClassStatement>()
+            || node.is::<FunctionStatement>()
+            || node
+                .downcast_ref::<ClosureExpression>()
+                .is_some_and(|c| is_static(&c.r#static))
+            || node
+                .downcast_ref::<ArrowFunctionExpression>()
+                .is_some_and(|f| is_static(&f.r#static));
+
+        if self.skipped == 0 {
+            self.visit(node);
+        }
+
+        self.frames.push(skipped);
+        if skipped {
+            self.skipped += 1;
+        }
+    }
+
+    fn leave(&mut self) {
+        if self.frames.pop() == Some(true) {
+            self.skipped -= 1;
+        }
+    }
+}
+
+impl AccessCollector {
+    fn visit(&mut self, node: &dyn Any) {
+        let (method, target, name) = if let Some(call) = node.downcast_ref::<MethodCallExpression>()
+        {
+            (true, &call.target, &call.method)
+        } else if let Some(call) = node.downcast_ref::<NullsafeMethodCallExpression>() {
+            (true, &call.target, &call.method)
+        } else if let Some(fetch) = node.downcast_ref::<PropertyFetchExpression>() {
+            (false, &fetch.target, &fetch.property)
+        } else if let Some(fetch) = node.downcast_ref::<NullsafePropertyFetchExpression>() {
+            (false, &fetch.target, &fetch.property)
+        } else {
+            if let Some(guarded) = node
+                .downcast_ref::<FunctionCallExpression>()
+                .and_then(guard)
+            {
+                self.guarded.push(guarded);
+            }
+
+            return;
+        };
+
+        if let (
+            Expression::Variable(Variable::SimpleVariable(this)),
+            Expression::Identifier(Identifier::SimpleIdentifier(name)),
+        ) = (target.as_ref(), name.as_ref())
+        {
+            if this.name.eq_ignore_ascii_case(b"$this") {
+                self.accesses.push(Access {
+                    method,
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn allows_dynamic_properties(attributes: &[AttributeGroup]) -> bool {
+    attributes
+        .iter()
+        .flat_map(|group| group.iter())
+        .any(|attribute| {
+            let name = &attribute.name.value;
+            let name = name.strip_prefix(b"\\").unwrap_or(name);
+
+            name.eq_ignore_ascii_case(b"AllowDynamicProperties")
+        })
+}
+
+/// The name checked by `method_exists($this, 'name')` or `property_exists(...)`, and
+/// whether it is a method's.
+fn guard(call: &FunctionCallExpression) -> Option<(bool, ByteString)> {
+    let Expression::Identifier(Identifier::SimpleIdentifier(function)) = call.target.as_ref()
+    else {
+        return None;
+    };
+
+    let function = function
+        .value
+        .strip_prefix(b"\\")
+        .unwrap_or(&function.value);
+    let method = if function.eq_ignore_ascii_case(b"method_exists") {
+        true
+    } else if function.eq_ignore_ascii_case(b"property_exists") {
+        false
+    } else {
+        return None;
+    };
+
+    match call.arguments.arguments.get(1)? {
+        Argument::Positional(argument) => match &argument.value {
+            Expression::Literal(Literal::String(name)) => {
+                Some((method, string_value(name)?.into()))
+            }
+            _ => None,
+        },
+        Argument::Named(_) => None,
+    }
+}
+
+/// The declared name that is the closest to `name`, if it is at most a third of its
+/// length away, and at least one byte is left as it is.
+fn suggestion<'a>(
+    name: &[u8],
+    declared: &'a [ByteString],
+    ignore_case: bool,
+) -> Option<&'a ByteString> {
+    let fold = |name: &[u8]| {
+        if ignore_case {
+            name.to_ascii_lowercase()
+        } else {
+            name.to_vec()
+        }
+    };
+    let name = fold(name);
+    let limit = name.len().max(3) / 3;
+
+    declared
+        .iter()
+        .map(|candidate| (edit_distance(&name, &fold(candidate)), candidate))
+        .filter(|(distance, _)| *distance <= limit && *distance < name.len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between two names, where swapping two adjacent bytes
+/// counts as a single edit, as it is a common typo.
+fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+    // `distances[i][j]` is the distance between the first `i` bytes of `a` and the
+    // first `j` bytes of `b`.
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn undefined(code: &str) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        undefined_member_access(&program)
+            .iter()
+            .map(|warning| {
+                format!(
+                    "{}:{} {}{}",
+                    warning.span.line,
+                    warning.span.column,
+                    warning.message,
+                    warning
+                        .note
+                        .as_ref()
+                        .map(|note| format!(", {}", note))
+                        .unwrap_or_default()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_undefined_members() {
+        let code = "<?php
+class Counter {
+    private int $counter = 0;
+
+    public function __construct(private string $label) {}
+
+    public function increment(): void {
+        $this->counte++;
+        $this->counter++;
+        $this->label;
+        $this->Increment();
+        $this->reset();
+        $this?->incremnt();
+        $f = function () { return $this->lable; };
+        $g = static fn () => $this->nope;
+        $h = new class { public function a() { $this->b(); } };
+        $this->$name;
+    }
+}";
+
+        assert_eq!(
+            undefined(code),
+            vec![
+                "8:16 property `counte` is not declared in `Counter`, did you mean `counter`?",
+                "12:16 method `reset` is not declared in `Counter`",
+                "13:17 method `incremnt` is not declared in `Counter`, did you mean `increment`?",
+                "14:42 property `lable` is not declared in `Counter`, did you mean `label`?",
+                "16:55 method `b` is not declared in `class@anonymous`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_magic_members() {
+        let code = "<?php
+class Magic {
+    public function __get($name) {}
+
+    public function run() {
+        $this->anything;
+        $this->missing();
+    }
+}
+class Call {
+    public function __call($name, $arguments) {}
+
+    public function run() {
+        $this->anything();
+        $this->missing;
+    }
+}";
+
+        assert_eq!(
+            undefined(code),
+            vec![
+                "7:16 method `missing` is not declared in `Magic`",
+                "15:16 property `missing` is not declared in `Call`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_members() {
+        let code = "<?php
+class Child extends Base { public function a() { $this->b(); } }
+class WithTrait { use T; public function a() { $this->b(); } }
+class Guarded {
+    public function a() {
+        if (method_exists($this, 'b')) { $this->b(); }
+        if (\\property_exists($this, \"c\")) { $this->c; }
+    }
+}";
+
+        assert_eq!(undefined(code), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance(b"", b"abc"), 3);
+        assert_eq!(edit_distance(b"kitten", b"sitting"), 3);
+        assert_eq!(edit_distance(b"name", b"naem"), 1);
+        assert_eq!(edit_distance(b"ab", b"ba"), 1);
+        assert_eq!(edit_distance(b"same", b"same"), 0);
+    }
+}
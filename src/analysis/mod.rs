@@ -0,0 +1,881 @@
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::lexer::token::Token;
+use crate::parser::ast::classes::ClassMember;
+use crate::parser::ast::data_type::Type;
+use crate::parser::ast::enums::BackedEnumMember;
+use crate::parser::ast::enums::UnitEnumMember;
+use crate::parser::ast::functions::ReturnType;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::literals::Literal;
+use crate::parser::ast::literals::LiteralString;
+use crate::parser::ast::namespaces::NamespaceStatement;
+use crate::parser::ast::operators::ArithmeticOperationExpression;
+use crate::parser::ast::traits::TraitMember;
+use crate::parser::ast::utils::CommaSeparated;
+use crate::parser::ast::variables::Variable;
+use crate::parser::ast::ArrayItem;
+use crate::parser::ast::ConstantFetchExpression;
+use crate::parser::ast::Expression;
+use crate::parser::ast::FunctionCallExpression;
+use crate::parser::ast::MethodCallExpression;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+use crate::parser::ast::StaticMethodCallExpression;
+
+pub use self::arguments::{evaluate_attribute_args, AttrValue};
+pub use self::attributes::{
+    attribute_definitions, validate_attribute_targets, AttributeDefinition, AttributeRegistry,
+    AttributeTarget,
+};
+pub use self::clones::{find_clones, find_clones_with_cancellation, CloneClass, CloneOccurrence};
+pub use self::coalesce::coalesce_guarded_accesses;
+pub use self::conditions::{declaration_conditions, Condition, SymbolRef};
+pub use self::declares::{declare_regions, DeclareRegion, DeclareScope};
+pub use self::defaults::{default_type_mismatches, DefaultType, DefaultTypeMismatch};
+pub use self::enums::{enum_usages, EnumUsage, EnumUsageIndex, EnumUsageKind};
+pub use self::exits::{exit_points, ExitArgument, ExitPoint};
+pub use self::imports::{
+    import_block, ImportBlock, ImportEntry, ImportOrder, ImportStatement, NameOrder,
+};
+pub use self::includes::{includes, Include, IncludeKind, IncludePath};
+pub use self::locate::{FindNode, NodePath, NodeRange, PathNode};
+pub use self::members::undefined_member_access;
+pub use self::names::{resolve_names, ResolvedNames};
+pub use self::sinks::{output_sinks, OutputSink, OutputSinkKind, OutputSinks, PRINTF_FUNCTIONS};
+pub use self::statics::static_var_issues;
+pub use self::suppressions::{apply_suppressions, UnusedSuppression, SUPPRESSION_PREFIX};
+pub use self::unreachable::unreachable_code;
+pub use self::versions::{required_version, VersionReport};
+
+mod arguments;
+mod attributes;
+mod clones;
+mod coalesce;
+mod conditions;
+mod declares;
+mod defaults;
+mod enums;
+mod exits;
+mod imports;
+mod includes;
+mod locate;
+mod members;
+mod names;
+mod sinks;
+mod statics;
+mod suppressions;
+mod unreachable;
+mod versions;
+
+/// A callable, as spelled out in source code.
+///
+/// Names are taken verbatim from the source; no attempt is made to resolve them
+/// against imports or the current namespace.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CallableRef<'a> {
+    /// `'Foo::bar'`, `['Foo', 'bar']` or `[Foo::class, 'bar']`
+    StaticMethod {
+        class: ByteString,
+        method: ByteString,
+    },
+    /// `[$this, 'bar']`
+    Method {
+        target: &'a Expression,
+        method: ByteString,
+    },
+    /// `'strlen'`
+    Function(ByteString),
+    /// `strlen(...)`, `$foo->bar(...)` or `Foo::bar(...)`
+    FirstClassCallable(&'a Expression),
+    /// `function () {}` or `fn () => 1`
+    Closure(&'a Expression),
+}
+
+/// Interpret an expression as a callable, if its shape is that of one.
+///
+/// Only the syntax is considered, so `'foo'` is a callable whether or not a function
+/// named `foo` exists, while `[$foo, $bar]` is not, as the method name isn't known
+/// until runtime.
+pub fn as_callable(expression: &Expression) -> Option<CallableRef<'_>> {
+    match expression {
+        Expression::Literal(Literal::String(string)) => {
+            let value = string_value(string)?;
+
+            match value.windows(2).position(|window| window == b"::") {
+                Some(position) => {
+                    let (class, method) = (&value[..position], &value[position + 2..]);
+
+                    if class.is_empty() || method.is_empty() {
+                        return None;
+                    }
+
+                    Some(CallableRef::StaticMethod {
+                        class: class.into(),
+                        method: method.into(),
+                    })
+                }
+                None => Some(CallableRef::Function(value.into())),
+            }
+        }
+        Expression::ShortArray(array) => array_callable(&array.items),
+        Expression::Array(array) => array_callable(&array.items),
+        Expression::FunctionClosureCreation(_)
+        | Expression::MethodClosureCreation(_)
+        | Expression::StaticMethodClosureCreation(_)
+        | Expression::StaticVariableMethodClosureCreation(_) => {
+            Some(CallableRef::FirstClassCallable(expression))
+        }
+        Expression::Closure(_) | Expression::ArrowFunction(_) => {
+            Some(CallableRef::Closure(expression))
+        }
+        Expression::Parenthesized(parenthesized) => as_callable(&parenthesized.expr),
+        _ => None,
+    }
+}
+
+/// The encoding named by a `declare(encoding='...')` statement at the top level of the
+/// program, without its quotes.
+///
+/// Values that aren't a string literal are reported as an error while parsing, and are
+/// ignored here.
+pub fn declared_encoding(program: &Program) -> Option<ByteString> {
+    program.iter().find_map(|statement| match statement {
+        Statement::Declare(declare) => {
+            declare
+                .entries
+                .entries
+                .iter()
+                .find_map(|entry| match &entry.value {
+                    Literal::String(string)
+                        if entry.key.value[..].eq_ignore_ascii_case(b"encoding") =>
+                    {
+                        string_value(string).map(ByteString::from)
+                    }
+                    _ => None,
+                })
+        }
+        _ => None,
+    })
+}
+
+fn array_callable(items: &CommaSeparated<ArrayItem>) -> Option<CallableRef<'_>> {
+    let (target, method) = match &items.inner[..] {
+        [ArrayItem::Value { value: target }, ArrayItem::Value { value: method }] => {
+            (target, method)
+        }
+        _ => return None,
+    };
+
+    let method: ByteString = match method {
+        Expression::Literal(Literal::String(string)) => string_value(string)?.into(),
+        _ => return None,
+    };
+
+    match target {
+        Expression::Literal(Literal::String(string)) => Some(CallableRef::StaticMethod {
+            class: string_value(string)?.into(),
+            method,
+        }),
+        Expression::ConstantFetch(ConstantFetchExpression {
+            target: class,
+            constant: Identifier::SimpleIdentifier(constant),
+            ..
+        }) if constant.value[..].eq_ignore_ascii_case(b"class") => match class.as_ref() {
+            Expression::Identifier(Identifier::SimpleIdentifier(class)) => {
+                Some(CallableRef::StaticMethod {
+                    class: class.value.clone(),
+                    method,
+                })
+            }
+            _ => None,
+        },
+        Expression::Literal(_) => None,
+        _ => Some(CallableRef::Method { target, method }),
+    }
+}
+
+/// A key of an array.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ArrayKey {
+    Integer(i64),
+    String(ByteString),
+}
+
+/// The keys of an array literal, as far as they can be known without running the code.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ArrayShape {
+    /// Every key is known. Keys are listed in the order the array holds them, once each.
+    Known(Vec<ArrayKey>),
+    /// At least one key, or the number of items spread into the array, depends on the
+    /// value of a variable, a call or a constant.
+    Dynamic,
+}
+
+/// Compute the keys of an array literal, following PHP's rules:
+///
+/// - items without a key are given the next integer after the largest integer key so far,
+/// - string keys that are decimal integers, such as `'5'`, and float keys are converted
+///   to integers,
+/// - spread arrays append their integer keys and overwrite their string keys,
+/// - a repeated key keeps the position it was first given.
+///
+/// Returns `None` if the expression isn't an array literal.
+pub fn array_shape(expression: &Expression) -> Option<ArrayShape> {
+    let items = match expression {
+        Expression::ShortArray(array) => &array.items,
+        Expression::Array(array) => &array.items,
+        _ => return None,
+    };
+
+    Some(match array_keys(items) {
+        Some(keys) => ArrayShape::Known(keys),
+        None => ArrayShape::Dynamic,
+    })
+}
+
+fn array_keys(items: &CommaSeparated<ArrayItem>) -> Option<Vec<ArrayKey>> {
+    let mut keys = Vec::new();
+    let mut next: Option<i64> = None;
+
+    for item in items.iter() {
+        match item {
+            ArrayItem::Value { .. } | ArrayItem::ReferencedValue { .. } => {
+                let key = next.unwrap_or(0);
+                next = Some(key.checked_add(1)?);
+
+                insert_key(&mut keys, ArrayKey::Integer(key));
+            }
+            ArrayItem::KeyValue { key, .. } | ArrayItem::ReferencedKeyValue { key, .. } => {
+                let key = array_key(key)?;
+
+                if let ArrayKey::Integer(key) = key {
+                    if next.is_none_or(|next| key >= next) {
+                        next = Some(key.checked_add(1)?);
+                    }
+                }
+
+                insert_key(&mut keys, key);
+            }
+            ArrayItem::SpreadValue { value, .. } => {
+                let spread = match value {
+                    Expression::ShortArray(array) => array_keys(&array.items)?,
+                    Expression::Array(array) => array_keys(&array.items)?,
+                    _ => return None,
+                };
+
+                for key in spread {
+                    match key {
+                        ArrayKey::Integer(_) => {
+                            let key = next.unwrap_or(0);
+                            next = Some(key.checked_add(1)?);
+
+                            insert_key(&mut keys, ArrayKey::Integer(key));
+                        }
+                        key => insert_key(&mut keys, key),
+                    }
+                }
+            }
+            ArrayItem::Skipped => return None,
+        }
+    }
+
+    Some(keys)
+}
+
+fn insert_key(keys: &mut Vec<ArrayKey>, key: ArrayKey) {
+    if !keys.contains(&key) {
+        keys.push(key);
+    }
+}
+
+fn array_key(key: &Expression) -> Option<ArrayKey> {
+    match key {
+        Expression::Literal(Literal::Integer(integer)) => integer.to_i64().map(ArrayKey::Integer),
+        Expression::Literal(Literal::Float(float)) => float_key(&float.value, false),
+        Expression::Literal(Literal::String(string)) => {
+            // The lexer has already processed escape sequences, leaving only the quotes.
+            let value = string.value.get(1..string.value.len().checked_sub(1)?)?;
+
+            Some(match integer_string(value) {
+                Some(integer) => ArrayKey::Integer(integer),
+                None => ArrayKey::String(value.into()),
+            })
+        }
+        Expression::ArithmeticOperation(ArithmeticOperationExpression::Negative {
+            right, ..
+        }) => match right.as_ref() {
+            Expression::Literal(Literal::Integer(integer)) => {
+                integer.to_i64()?.checked_neg().map(ArrayKey::Integer)
+            }
+            Expression::Literal(Literal::Float(float)) => float_key(&float.value, true),
+            _ => None,
+        },
+        Expression::Parenthesized(parenthesized) => array_key(&parenthesized.expr),
+        _ => None,
+    }
+}
+
+/// Float keys are truncated towards zero.
+fn float_key(value: &[u8], negative: bool) -> Option<ArrayKey> {
+    let value: f64 = std::str::from_utf8(value).ok()?.parse().ok()?;
+    let value = if negative { -value } else { value }.trunc();
+
+    if !value.is_finite() || value < i64::MIN as f64 || value >= i64::MAX as f64 {
+        return None;
+    }
+
+    Some(ArrayKey::Integer(value as i64))
+}
+
+/// The integer that a string key is converted to, if it is written the way PHP would
+/// write the integer, e.g. `'5'` or `'-5'`, but not `'05'` or `'+5'`.
+fn integer_string(value: &[u8]) -> Option<i64> {
+    let digits = value.strip_prefix(b"-").unwrap_or(value);
+
+    match digits {
+        [b'0'] if digits.len() == value.len() => Some(0),
+        [b'1'..=b'9', rest @ ..] if rest.iter().all(u8::is_ascii_digit) => {
+            std::str::from_utf8(value).ok()?.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+/// The contents of a string literal, without its quotes and with backslashes and
+/// quotes unescaped. Names never contain any other escape sequence.
+fn string_value(string: &LiteralString) -> Option<Vec<u8>> {
+    let quote = *string.value.first()?;
+    let inner = &string.value[1..string.value.len() - 1];
+
+    let mut value = Vec::with_capacity(inner.len());
+    let mut bytes = inner.iter();
+    while let Some(&byte) = bytes.next() {
+        match (byte, bytes.as_slice().first()) {
+            (b'\\', Some(&next)) if next == b'\\' || next == quote => {
+                value.push(next);
+                bytes.next();
+            }
+            _ => value.push(byte),
+        }
+    }
+
+    if value.is_empty() {
+        return None;
+    }
+
+    Some(value)
+}
+
+/// The expressions that never hand control back to the code that follows them.
+///
+/// `exit`, `die` and `throw` are always terminators. Functions and methods that are
+/// declared with a `never` return type can be picked up from a program with
+/// [`Terminators::for_program`], and functions that are known to never return, such as
+/// a framework's `abort()` or `dd()`, added with [`Terminators::with_known`].
+///
+/// Names are compared case-insensitively and without their namespace, as they aren't
+/// resolved against imports. Methods are only recognized when called on `$this`,
+/// `self`, `static` or `parent`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Terminators {
+    functions: Vec<ByteString>,
+    methods: Vec<ByteString>,
+}
+
+impl Terminators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The terminators of a program, including the functions and methods it declares
+    /// with a `never` return type.
+    ///
+    /// Functions are found at the top level and in namespaces, and methods in classes,
+    /// traits and enums declared there.
+    pub fn for_program(program: &Program) -> Self {
+        let mut terminators = Self::default();
+        terminators.collect(program);
+
+        terminators
+    }
+
+    /// Treat calls to the functions with the given names as terminators.
+    pub fn with_known<I, T>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ByteString>,
+    {
+        self.functions.extend(names.into_iter().map(Into::into));
+
+        self
+    }
+
+    pub fn terminates(&self, expression: &Expression) -> bool {
+        match expression {
+            Expression::Exit(_) | Expression::Die(_) | Expression::Throw(_) => true,
+            Expression::Parenthesized(parenthesized) => self.terminates(&parenthesized.expr),
+            Expression::FunctionCall(FunctionCallExpression { target, .. }) => {
+                match target.as_ref() {
+                    Expression::Identifier(Identifier::SimpleIdentifier(name)) => {
+                        contains(&self.functions, &name.value)
+                    }
+                    _ => false,
+                }
+            }
+            Expression::MethodCall(MethodCallExpression { target, method, .. }) => {
+                match (target.as_ref(), method.as_ref()) {
+                    (
+                        Expression::Variable(Variable::SimpleVariable(variable)),
+                        Expression::Identifier(Identifier::SimpleIdentifier(method)),
+                    ) if variable.name[..] == b"$this"[..] => {
+                        contains(&self.methods, &method.value)
+                    }
+                    _ => false,
+                }
+            }
+            Expression::StaticMethodCall(StaticMethodCallExpression {
+                target,
+                method: Identifier::SimpleIdentifier(method),
+                ..
+            }) => match target.as_ref() {
+                Expression::Self_(_) | Expression::Static(_) | Expression::Parent(_) => {
+                    contains(&self.methods, &method.value)
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the statement is an expression statement whose expression terminates.
+    pub fn terminates_statement(&self, statement: &Statement) -> bool {
+        match statement {
+            Statement::Expression(statement) => self.terminates(&statement.expression),
+            _ => false,
+        }
+    }
+
+    fn collect(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            match statement {
+                Statement::Function(function) if returns_never(&function.return_type) => {
+                    self.functions.push(function.name.value.clone());
+                }
+                Statement::Namespace(NamespaceStatement::Unbraced(namespace)) => {
+                    self.collect(&namespace.statements)
+                }
+                Statement::Namespace(NamespaceStatement::Braced(namespace)) => {
+                    self.collect(&namespace.body.statements)
+                }
+                Statement::Class(class) => {
+                    for member in &class.body.members {
+                        match member {
+                            ClassMember::ConcreteMethod(method) => {
+                                self.method(&method.name, &method.return_type)
+                            }
+                            ClassMember::AbstractMethod(method) => {
+                                self.method(&method.name, &method.return_type)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Statement::Trait(r#trait) => {
+                    for member in &r#trait.body.members {
+                        match member {
+                            TraitMember::ConcreteMethod(method) => {
+                                self.method(&method.name, &method.return_type)
+                            }
+                            TraitMember::AbstractMethod(method) => {
+                                self.method(&method.name, &method.return_type)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Statement::UnitEnum(r#enum) => {
+                    for member in &r#enum.body.members {
+                        if let UnitEnumMember::Method(method) = member {
+                            self.method(&method.name, &method.return_type)
+                        }
+                    }
+                }
+                Statement::BackedEnum(r#enum) => {
+                    for member in &r#enum.body.members {
+                        if let BackedEnumMember::Method(method) = member {
+                            self.method(&method.name, &method.return_type)
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn method(&mut self, name: &SimpleIdentifier, return_type: &Option<ReturnType>) {
+        if returns_never(return_type) {
+            self.methods.push(name.value.clone());
+        }
+    }
+}
+
+fn returns_never(return_type: &Option<ReturnType>) -> bool {
+    matches!(
+        return_type,
+        Some(ReturnType {
+            data_type: Type::Never(_),
+            ..
+        })
+    )
+}
+
+fn contains(names: &[ByteString], name: &[u8]) -> bool {
+    let name = match name.iter().rposition(|byte| *byte == b'\\') {
+        Some(separator) => &name[separator + 1..],
+        None => name,
+    };
+
+    names.iter().any(|known| {
+        let known = match known.iter().rposition(|byte| *byte == b'\\') {
+            Some(separator) => &known[separator + 1..],
+            None => &known[..],
+        };
+
+        known.eq_ignore_ascii_case(name)
+    })
+}
+
+/// A link of a method or property chain, such as the `->where(...)` in
+/// `$query->where(...)`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ChainLink {
+    /// The `->` or `?->` that starts the link.
+    pub arrow: Span,
+    /// Whether the arrow is the first token on its line.
+    pub line_break: bool,
+}
+
+/// How a method or property chain is laid out over lines, see [`chain_layout`].
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ChainLayout {
+    /// The links of the chain, from the one closest to its root to the outermost one.
+    pub links: Vec<ChainLink>,
+}
+
+impl ChainLayout {
+    /// Whether either every link after the first starts a new line, or none does.
+    ///
+    /// The first link is left out, as it is usually kept on the line of the chain's
+    /// root, e.g. `$query->where(...)`.
+    pub fn is_consistent(&self) -> bool {
+        let mut rest = self.links.iter().skip(1).map(|link| link.line_break);
+
+        match rest.next() {
+            Some(first) => rest.all(|line_break| line_break == first),
+            None => true,
+        }
+    }
+}
+
+/// The layout of the chain that ends with `chain`, such as
+/// `$query->where(...)->orderBy(...)`, given the tokens the chain was parsed from; see
+/// [`crate::parse_with_tokens`].
+///
+/// The chain is followed through method calls and property fetches, including their
+/// nullsafe forms, and ends at the first expression that is neither.
+pub fn chain_layout(tokens: &[Token], chain: &Expression) -> ChainLayout {
+    let mut arrows = Vec::new();
+
+    let mut expression = chain;
+    loop {
+        let (target, arrow) = match expression {
+            Expression::MethodCall(call) => (&call.target, call.arrow),
+            Expression::MethodClosureCreation(creation) => (&creation.target, creation.arrow),
+            Expression::NullsafeMethodCall(call) => (&call.target, call.question_arrow),
+            Expression::PropertyFetch(fetch) => (&fetch.target, fetch.arrow),
+            Expression::NullsafePropertyFetch(fetch) => (&fetch.target, fetch.question_arrow),
+            _ => break,
+        };
+
+        arrows.push(arrow);
+        expression = target;
+    }
+
+    ChainLayout {
+        links: arrows
+            .into_iter()
+            .rev()
+            .map(|arrow| ChainLink {
+                arrow,
+                line_break: line_break_before(tokens, arrow),
+            })
+            .collect(),
+    }
+}
+
+/// Whether the token at `span` is the first on its line, i.e. the token before it ends
+/// on an earlier line.
+fn line_break_before(tokens: &[Token], span: Span) -> bool {
+    let index = match tokens.binary_search_by_key(&span.position, |token| token.span.position) {
+        Ok(index) if index > 0 => index,
+        _ => return false,
+    };
+
+    let previous = &tokens[index - 1];
+    let end_line =
+        previous.span.line + previous.value.iter().filter(|byte| **byte == b'\n').count();
+
+    end_line < span.line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::Statement;
+
+    fn expression(code: &str) -> Expression {
+        let program = crate::parse(&format!("<?php {};", code)).unwrap();
+
+        match program.into_iter().nth(1) {
+            Some(Statement::Expression(statement)) => statement.expression,
+            statement => panic!("expected an expression, found {:?}", statement),
+        }
+    }
+
+    fn static_method(class: &str, method: &str) -> Option<CallableRef<'static>> {
+        Some(CallableRef::StaticMethod {
+            class: class.into(),
+            method: method.into(),
+        })
+    }
+
+    #[test]
+    fn test_string_callables() {
+        assert_eq!(
+            as_callable(&expression("'strlen'")),
+            Some(CallableRef::Function("strlen".into()))
+        );
+        assert_eq!(
+            as_callable(&expression(r#""Foo::bar""#)),
+            static_method("Foo", "bar")
+        );
+        assert_eq!(
+            as_callable(&expression(r"'App\\Foo::bar'")),
+            static_method("App\\Foo", "bar")
+        );
+        assert_eq!(as_callable(&expression("'Foo::'")), None);
+        assert_eq!(as_callable(&expression("''")), None);
+    }
+
+    #[test]
+    fn test_array_callables() {
+        assert_eq!(
+            as_callable(&expression("['Foo', 'bar']")),
+            static_method("Foo", "bar")
+        );
+        assert_eq!(
+            as_callable(&expression("array(Foo::class, 'bar')")),
+            static_method("Foo", "bar")
+        );
+
+        let this = expression("[$this, 'bar']");
+        let Expression::ShortArray(array) = &this else {
+            unreachable!()
+        };
+        let ArrayItem::Value { value: target } = &array.items.inner[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            as_callable(&this),
+            Some(CallableRef::Method {
+                target,
+                method: "bar".into()
+            })
+        );
+
+        assert_eq!(as_callable(&expression("[$foo, $bar]")), None);
+        assert_eq!(as_callable(&expression("['Foo', 'bar', 'baz']")), None);
+        assert_eq!(as_callable(&expression("[1, 'bar']")), None);
+    }
+
+    #[test]
+    fn test_closure_callables() {
+        let first_class = expression("strlen(...)");
+        assert_eq!(
+            as_callable(&first_class),
+            Some(CallableRef::FirstClassCallable(&first_class))
+        );
+
+        let method = expression("$foo->bar(...)");
+        assert_eq!(
+            as_callable(&method),
+            Some(CallableRef::FirstClassCallable(&method))
+        );
+
+        let closure = expression("fn ($x) => $x");
+        assert_eq!(as_callable(&closure), Some(CallableRef::Closure(&closure)));
+
+        assert_eq!(as_callable(&expression("$foo")), None);
+    }
+
+    fn statement(code: &str) -> Statement {
+        let program = crate::parse(&format!("<?php {}", code)).unwrap();
+
+        program.into_iter().last().unwrap()
+    }
+
+    #[test]
+    fn test_builtin_terminators() {
+        let terminators = Terminators::new();
+
+        assert!(terminators.terminates(&expression("exit(1)")));
+        assert!(terminators.terminates(&expression("die")));
+        assert!(terminators.terminates(&expression("throw new Exception()")));
+        assert!(terminators.terminates_statement(&statement("exit;")));
+        assert!(!terminators.terminates(&expression("dd($x)")));
+    }
+
+    #[test]
+    fn test_known_terminators() {
+        let terminators = Terminators::new().with_known(["dd", "abort"]);
+
+        assert!(terminators.terminates_statement(&statement("dd($x);")));
+        assert!(terminators.terminates(&expression("\\DD($x)")));
+        assert!(terminators.terminates(&expression("abort(404)")));
+        assert!(!terminators.terminates(&expression("dump($x)")));
+        assert!(!terminators.terminates(&expression("$foo->dd($x)")));
+    }
+
+    #[test]
+    fn test_never_returning_declarations() {
+        let program = crate::parse(
+            "<?php
+namespace App;
+
+function fail(): never { throw new \\Exception(); }
+function succeed(): void {}
+
+class Controller {
+    public function redirect(): never { exit; }
+    public static function abort(): never { exit; }
+}",
+        )
+        .unwrap();
+        let terminators = Terminators::for_program(&program);
+
+        assert!(terminators.terminates(&expression("fail()")));
+        assert!(terminators.terminates(&expression("\\App\\fail()")));
+        assert!(!terminators.terminates(&expression("succeed()")));
+        assert!(terminators.terminates(&expression("$this->redirect()")));
+        assert!(terminators.terminates(&expression("static::abort()")));
+        assert!(!terminators.terminates(&expression("$other->redirect()")));
+        assert!(!terminators.terminates(&expression("redirect()")));
+    }
+
+    fn shape(code: &str) -> ArrayShape {
+        array_shape(&expression(code)).unwrap()
+    }
+
+    fn known(keys: &[ArrayKey]) -> ArrayShape {
+        ArrayShape::Known(keys.to_vec())
+    }
+
+    fn int(key: i64) -> ArrayKey {
+        ArrayKey::Integer(key)
+    }
+
+    fn string(key: &str) -> ArrayKey {
+        ArrayKey::String(key.into())
+    }
+
+    #[test]
+    fn test_array_shape_auto_index() {
+        assert_eq!(
+            shape("['a', 5 => 'b', 'c']"),
+            known(&[int(0), int(5), int(6)])
+        );
+        assert_eq!(
+            shape("array(5 => 'a', 2 => 'b', 'c')"),
+            known(&[int(5), int(2), int(6)])
+        );
+        assert_eq!(shape("[-5 => 'a', 'b']"), known(&[int(-5), int(-4)]));
+        assert_eq!(shape("[]"), known(&[]));
+    }
+
+    #[test]
+    fn test_array_shape_key_casts() {
+        assert_eq!(
+            shape("['x' => 1, '7' => 2, 'y', '07' => 3, '' => 4]"),
+            known(&[string("x"), int(7), int(8), string("07"), string("")])
+        );
+        assert_eq!(
+            shape("[1.7 => 'a', \"1\" => 'b', 'c']"),
+            known(&[int(1), int(2)])
+        );
+        assert_eq!(shape("[\"a\\n\" => 1]"), known(&[string("a\n")]));
+    }
+
+    #[test]
+    fn test_array_shape_spreads() {
+        assert_eq!(shape("[...[1, 2], 3]"), known(&[int(0), int(1), int(2)]));
+        assert_eq!(
+            shape("[5 => 'a', ...['x' => 1, 9 => 2], 'x' => 3]"),
+            known(&[int(5), string("x"), int(6)])
+        );
+        assert_eq!(
+            shape("[...getDefaults(), 'override' => 1, ...$extra]"),
+            ArrayShape::Dynamic
+        );
+        assert_eq!(shape("[$key => 1]"), ArrayShape::Dynamic);
+        assert_eq!(array_shape(&expression("$foo")), None);
+    }
+
+    #[test]
+    fn test_chain_layout() {
+        let (program, tokens) = crate::parse_with_tokens(
+            "<?php
+$query->where('a', 1)
+    ->orderBy('b')->limit(10)
+    // the first one
+    ?->first();
+",
+        )
+        .unwrap();
+
+        let Some(Statement::Expression(statement)) = program.get(1) else {
+            unreachable!()
+        };
+        let layout = chain_layout(&tokens, &statement.expression);
+
+        assert_eq!(
+            layout
+                .links
+                .iter()
+                .map(|link| (link.arrow.line, link.line_break))
+                .collect::<Vec<_>>(),
+            vec![(2, false), (3, true), (3, false), (5, true)]
+        );
+        assert!(!layout.is_consistent());
+    }
+
+    #[test]
+    fn test_consistent_chain_layout() {
+        let layout = |code: &str| {
+            let (program, tokens) = crate::parse_with_tokens(code).unwrap();
+
+            match program.get(1) {
+                Some(Statement::Expression(statement)) => {
+                    chain_layout(&tokens, &statement.expression)
+                }
+                statement => panic!("expected an expression, found {:?}", statement),
+            }
+        };
+
+        assert!(layout("<?php $a->b()->c->d();").is_consistent());
+        assert!(layout("<?php $a->b()\n  ->c\n  ->d();").is_consistent());
+        assert!(layout("<?php $a\n  ->b()\n  ->c();").is_consistent());
+        assert_eq!(layout("<?php foo();"), ChainLayout::default());
+    }
+}
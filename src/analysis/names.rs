@@ -0,0 +1,482 @@
+//! The fully qualified names of the classes a program refers to, see [`resolve_names`].
+
+use std::collections::BTreeMap;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::attributes::Attribute;
+use crate::parser::ast::classes::AnonymousClassExpression;
+use crate::parser::ast::classes::ClassExtends;
+use crate::parser::ast::classes::ClassImplements;
+use crate::parser::ast::classes::ClassStatement;
+use crate::parser::ast::data_type::Type;
+use crate::parser::ast::enums::BackedEnumStatement;
+use crate::parser::ast::enums::UnitEnumStatement;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::interfaces::InterfaceExtends;
+use crate::parser::ast::interfaces::InterfaceStatement;
+use crate::parser::ast::namespaces::BracedNamespace;
+use crate::parser::ast::namespaces::UnbracedNamespace;
+use crate::parser::ast::traits::TraitStatement;
+use crate::parser::ast::traits::TraitUsage;
+use crate::parser::ast::traits::TraitUsageAdaptation;
+use crate::parser::ast::try_block::CatchType;
+use crate::parser::ast::ConstantFetchExpression;
+use crate::parser::ast::Expression;
+use crate::parser::ast::GroupUseStatement;
+use crate::parser::ast::InstanceofExpression;
+use crate::parser::ast::NewExpression;
+use crate::parser::ast::Program;
+use crate::parser::ast::StaticMethodCallExpression;
+use crate::parser::ast::StaticMethodClosureCreationExpression;
+use crate::parser::ast::StaticPropertyFetchExpression;
+use crate::parser::ast::StaticVariableMethodCallExpression;
+use crate::parser::ast::StaticVariableMethodClosureCreationExpression;
+use crate::parser::ast::Use;
+use crate::parser::ast::UseKind;
+use crate::parser::ast::UseStatement;
+use crate::parser::symbols::SymbolKind;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+/// The classes, interfaces, traits and enums that a program declares and refers to,
+/// with their fully qualified names, without a leading `\`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ResolvedNames {
+    /// The names referred to, by the position where they are written.
+    references: BTreeMap<usize, ByteString>,
+    /// The declared names and their kind, by their name in lowercase.
+    declarations: BTreeMap<Vec<u8>, (ByteString, SymbolKind)>,
+}
+
+impl ResolvedNames {
+    /// The fully qualified name of the class, interface, trait or enum named at `span`,
+    /// e.g. at `Bar` in `new Bar` or `function (Bar $bar)`, or at the name of a
+    /// declaration.
+    pub fn resolve(&self, span: Span) -> Option<&ByteString> {
+        self.references.get(&span.position)
+    }
+
+    /// The declaration of a fully qualified name, with the name as declared, as names
+    /// are compared case-insensitively.
+    pub fn declaration(&self, name: &[u8]) -> Option<(&ByteString, SymbolKind)> {
+        self.declarations
+            .get(&name.to_ascii_lowercase())
+            .map(|(name, kind)| (name, *kind))
+    }
+
+    /// The declarations, ordered by name.
+    pub fn declarations(&self) -> impl Iterator<Item = (&ByteString, SymbolKind)> {
+        self.declarations.values().map(|(name, kind)| (name, *kind))
+    }
+
+    /// Add a declaration made outside of the program, e.g. in another file or in an
+    /// extension.
+    pub fn declare<T: Into<ByteString>>(&mut self, name: T, kind: SymbolKind) {
+        let name = name.into();
+        let name = ByteString::from(name.strip_prefix(b"\\").unwrap_or(&name));
+
+        self.declarations
+            .insert(name.to_ascii_lowercase(), (name, kind));
+    }
+}
+
+/// Resolve the names of the classes, interfaces, traits and enums that a program refers
+/// to: in `new`, `instanceof`, `extends`, `implements`, trait uses, `catch` clauses,
+/// types, and the targets of `::`.
+///
+/// Names are resolved the way PHP does, against the imports of the namespace and the
+/// namespace itself. `self` and `parent` are resolved in types, but not in expressions,
+/// where they have no span, and `static` isn't, as it depends on the class that is
+/// called at runtime. The names of attributes, and those in their arguments, are
+/// resolved too.
+pub fn resolve_names(program: &Program) -> ResolvedNames {
+    let mut resolver = Resolver::default();
+    program.visit_spans(&mut resolver);
+
+    resolver.names
+}
+
+#[derive(Default)]
+struct Resolver {
+    names: ResolvedNames,
+    namespace: Option<ByteString>,
+    /// The classes imported in the namespace, by alias.
+    imports: Vec<(ByteString, ByteString)>,
+    /// The classes that `self` and `parent` refer to.
+    class: Option<ByteString>,
+    parent: Option<ByteString>,
+    /// A frame for each segment that was entered.
+    frames: Vec<Option<Scope>>,
+}
+
+impl Resolver {
+    /// The fully qualified name of a name as written.
+    fn qualify(&self, name: &[u8]) -> Option<ByteString> {
+        if let Some(name) = name.strip_prefix(b"\\") {
+            return Some(name.into());
+        }
+
+        if name.eq_ignore_ascii_case(b"self") {
+            return self.class.clone();
+        }
+        if name.eq_ignore_ascii_case(b"parent") {
+            return self.parent.clone();
+        }
+        if name.eq_ignore_ascii_case(b"static") {
+            return None;
+        }
+
+        if name.len() > 10 && name[..10].eq_ignore_ascii_case(b"namespace\\") {
+            return Some(self.prefixed(&name[10..]));
+        }
+
+        let (first, rest) = match name.iter().position(|byte| *byte == b'\\') {
+            Some(separator) => name.split_at(separator),
+            None => (name, &b""[..]),
+        };
+
+        let imported = self
+            .imports
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(first));
+
+        Some(match imported {
+            Some((_, imported)) => [&imported[..], rest].concat().into(),
+            None => self.prefixed(name),
+        })
+    }
+
+    /// The name in the current namespace.
+    fn prefixed(&self, name: &[u8]) -> ByteString {
+        match &self.namespace {
+            Some(namespace) => [&namespace[..], b"\\", name].concat().into(),
+            None => name.into(),
+        }
+    }
+
+    fn refer(&mut self, identifier: &SimpleIdentifier) {
+        if let Some(name) = self.qualify(&identifier.value) {
+            self.names.references.insert(identifier.span.position, name);
+        }
+    }
+
+    fn refer_to_target(&mut self, target: &Expression) {
+        if let Expression::Identifier(Identifier::SimpleIdentifier(identifier)) = target {
+            self.refer(identifier);
+        }
+    }
+
+    fn import(&mut self, prefix: &[u8], statement_kind: &UseKind, r#use: &Use) {
+        if r#use.kind.as_ref().unwrap_or(statement_kind) != &UseKind::Normal {
+            return;
+        }
+
+        let name = [prefix, &r#use.name.value[..]].concat();
+        let name = name.strip_prefix(b"\\").unwrap_or(&name);
+        let alias = match &r#use.alias {
+            Some(alias) => alias.value.clone(),
+            None => name
+                .rsplit(|byte| *byte == b'\\')
+                .next()
+                .unwrap_or(name)
+                .into(),
+        };
+
+        self.imports.push((alias, name.into()));
+    }
+
+    /// Declare a class-like, and return the class that `self` refers to while its
+    /// members are resolved.
+    fn declare(&mut self, name: &SimpleIdentifier, kind: SymbolKind) -> Option<ByteString> {
+        let qualified = self.prefixed(&name.value);
+        self.names.declare(qualified.clone(), kind);
+        self.names
+            .references
+            .insert(name.span.position, qualified.clone());
+
+        // `self` refers to the class that uses a trait.
+        (kind != SymbolKind::Trait).then_some(qualified)
+    }
+
+    /// Resolve the node that was entered last with `self` and `parent` referring to
+    /// other classes, until it is left.
+    fn enclose(&mut self, class: Option<ByteString>, parent: Option<ByteString>) {
+        let class = std::mem::replace(&mut self.class, class);
+        let parent = std::mem::replace(&mut self.parent, parent);
+
+        if let Some(frame) = self.frames.last_mut() {
+            *frame = Some(Scope::Class(class, parent));
+        }
+    }
+}
+
+/// What to restore when a segment is left.
+enum Scope {
+    /// The end of a braced namespace.
+    Namespace,
+    /// The classes that `self` and `parent` referred to around a class.
+    Class(Option<ByteString>, Option<ByteString>),
+}
+
+impl<'a> SpanVisitor<'a> for Resolver {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        self.frames.push(None);
+
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(namespace) = node.downcast_ref::<BracedNamespace>() {
+            self.namespace = namespace.name.as_ref().map(|name| name.value.clone());
+            self.imports.clear();
+
+            if let Some(frame) = self.frames.last_mut() {
+                *frame = Some(Scope::Namespace);
+            }
+        } else if let Some(namespace) = node.downcast_ref::<UnbracedNamespace>() {
+            self.namespace = Some(namespace.name.value.clone());
+            self.imports.clear();
+        } else if let Some(class) = node.downcast_ref::<ClassStatement>() {
+            let parent = class
+                .extends
+                .as_ref()
+                .and_then(|extends| self.qualify(&extends.parent.value));
+            let class = self.declare(&class.name, SymbolKind::Class);
+
+            self.enclose(class, parent);
+        } else if let Some(class) = node.downcast_ref::<AnonymousClassExpression>() {
+            let parent = class
+                .extends
+                .as_ref()
+                .and_then(|extends| self.qualify(&extends.parent.value));
+
+            self.enclose(None, parent);
+        } else if let Some(interface) = node.downcast_ref::<InterfaceStatement>() {
+            let class = self.declare(&interface.name, SymbolKind::Interface);
+            self.enclose(class, None);
+        } else if let Some(r#trait) = node.downcast_ref::<TraitStatement>() {
+            let class = self.declare(&r#trait.name, SymbolKind::Trait);
+            self.enclose(class, None);
+        } else if let Some(r#enum) = node.downcast_ref::<UnitEnumStatement>() {
+            for interface in &r#enum.implements {
+                self.refer(interface);
+            }
+
+            let class = self.declare(&r#enum.name, SymbolKind::Enum);
+            self.enclose(class, None);
+        } else if let Some(r#enum) = node.downcast_ref::<BackedEnumStatement>() {
+            for interface in &r#enum.implements {
+                self.refer(interface);
+            }
+
+            let class = self.declare(&r#enum.name, SymbolKind::Enum);
+            self.enclose(class, None);
+        } else if let Some(attribute) = node.downcast_ref::<Attribute>() {
+            self.refer(&attribute.name);
+        } else if let Some(statement) = node.downcast_ref::<UseStatement>() {
+            for r#use in &statement.uses {
+                self.import(b"", &statement.kind, r#use);
+            }
+        } else if let Some(statement) = node.downcast_ref::<GroupUseStatement>() {
+            for r#use in &statement.uses {
+                self.import(&statement.prefix.value, &statement.kind, r#use);
+            }
+        } else if let Some(r#type) = node.downcast_ref::<Type>() {
+            let (span, name) = match r#type {
+                Type::Named(span, name) => (*span, self.qualify(name)),
+                Type::SelfReference(span) => (*span, self.class.clone()),
+                Type::ParentReference(span) => (*span, self.parent.clone()),
+                _ => return,
+            };
+
+            if let Some(name) = name {
+                self.names.references.insert(span.position, name);
+            }
+        } else if let Some(extends) = node.downcast_ref::<ClassExtends>() {
+            self.refer(&extends.parent);
+        } else if let Some(implements) = node.downcast_ref::<ClassImplements>() {
+            for interface in implements.interfaces.iter() {
+                self.refer(interface);
+            }
+        } else if let Some(extends) = node.downcast_ref::<InterfaceExtends>() {
+            for parent in extends.parents.iter() {
+                self.refer(parent);
+            }
+        } else if let Some(usage) = node.downcast_ref::<TraitUsage>() {
+            for r#trait in &usage.traits {
+                self.refer(r#trait);
+            }
+
+            for adaptation in &usage.adaptations {
+                match adaptation {
+                    TraitUsageAdaptation::Alias { r#trait, .. }
+                    | TraitUsageAdaptation::Visibility { r#trait, .. } => {
+                        if let Some(r#trait) = r#trait {
+                            self.refer(r#trait);
+                        }
+                    }
+                    TraitUsageAdaptation::Precedence {
+                        r#trait, insteadof, ..
+                    } => {
+                        if let Some(r#trait) = r#trait {
+                            self.refer(r#trait);
+                        }
+
+                        for other in insteadof {
+                            self.refer(other);
+                        }
+                    }
+                }
+            }
+        } else if let Some(catch) = node.downcast_ref::<CatchType>() {
+            match catch {
+                CatchType::Identifier { identifier } => self.refer(identifier),
+                CatchType::Union { identifiers } => {
+                    for identifier in identifiers.iter() {
+                        self.refer(identifier);
+                    }
+                }
+            }
+        } else if let Some(new) = node.downcast_ref::<NewExpression>() {
+            self.refer_to_target(&new.target);
+        } else if let Some(instanceof) = node.downcast_ref::<InstanceofExpression>() {
+            self.refer_to_target(&instanceof.right);
+        } else if let Some(fetch) = node.downcast_ref::<ConstantFetchExpression>() {
+            self.refer_to_target(&fetch.target);
+        } else if let Some(fetch) = node.downcast_ref::<StaticPropertyFetchExpression>() {
+            self.refer_to_target(&fetch.target);
+        } else if let Some(call) = node.downcast_ref::<StaticMethodCallExpression>() {
+            self.refer_to_target(&call.target);
+        } else if let Some(call) = node.downcast_ref::<StaticVariableMethodCallExpression>() {
+            self.refer_to_target(&call.target);
+        } else if let Some(call) = node.downcast_ref::<StaticMethodClosureCreationExpression>() {
+            self.refer_to_target(&call.target);
+        } else if let Some(call) =
+            node.downcast_ref::<StaticVariableMethodClosureCreationExpression>()
+        {
+            self.refer_to_target(&call.target);
+        }
+    }
+
+    fn leave(&mut self) {
+        match self.frames.pop().flatten() {
+            Some(Scope::Namespace) => {
+                self.namespace = None;
+                self.imports.clear();
+            }
+            Some(Scope::Class(class, parent)) => {
+                self.class = class;
+                self.parent = parent;
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The resolved name that starts right after the first occurrence of `prefix`.
+    fn resolved_after(names: &ResolvedNames, code: &str, prefix: &str) -> Option<String> {
+        let position = code.find(prefix).unwrap() + prefix.len();
+
+        names
+            .resolve(Span::new(0, 0, position))
+            .map(|name| name.to_string())
+    }
+
+    #[test]
+    fn test_names_are_resolved_against_imports_and_the_namespace() {
+        let code = "<?php namespace App;
+            use Lib\\Http;
+            use Lib\\Model as Base;
+            use function Lib\\helper;
+            use Lib\\{Cache, Queue\\Job as Task};
+
+            new Http\\Request; new base; new \\Exception; new namespace\\Local;
+            new helper; new Cache; new Task; new Other;";
+        let names = resolve_names(&crate::parse(code).unwrap());
+        let resolved = |prefix| resolved_after(&names, code, prefix);
+
+        assert_eq!(resolved("new H").as_deref(), None);
+        assert_eq!(resolved("new ").as_deref(), Some("Lib\\Http\\Request"));
+        assert_eq!(resolved("Request; new ").as_deref(), Some("Lib\\Model"));
+        assert_eq!(resolved("base; new ").as_deref(), Some("Exception"));
+        assert_eq!(resolved("Exception; new ").as_deref(), Some("App\\Local"));
+        assert_eq!(
+            resolved("Local;\n            new ").as_deref(),
+            Some("App\\helper")
+        );
+        assert_eq!(resolved("helper; new ").as_deref(), Some("Lib\\Cache"));
+        assert_eq!(resolved("Cache; new ").as_deref(), Some("Lib\\Queue\\Job"));
+        assert_eq!(resolved("Task; new ").as_deref(), Some("App\\Other"));
+    }
+
+    #[test]
+    fn test_declarations_and_class_references() {
+        let code = "<?php
+            namespace A { use B\\C; class D extends C { function f(self $a, parent $b): static {} } }
+            namespace { new C; try {} catch (D|\\A\\D $e) {} interface I {} enum E: string implements I {} }";
+        let names = resolve_names(&crate::parse(code).unwrap());
+        let resolved = |prefix| resolved_after(&names, code, prefix);
+
+        assert_eq!(resolved("class ").as_deref(), Some("A\\D"));
+        assert_eq!(resolved("extends ").as_deref(), Some("B\\C"));
+        assert_eq!(resolved("f(").as_deref(), Some("A\\D"));
+        assert_eq!(resolved("$a, ").as_deref(), Some("B\\C"));
+        assert_eq!(resolved("): ").as_deref(), None);
+        assert_eq!(resolved("new ").as_deref(), Some("C"));
+        assert_eq!(resolved("catch (").as_deref(), Some("D"));
+        assert_eq!(resolved("D|").as_deref(), Some("A\\D"));
+        assert_eq!(resolved("implements ").as_deref(), Some("I"));
+
+        assert_eq!(
+            names
+                .declarations()
+                .map(|(name, kind)| format!("{:?} {}", kind, name))
+                .collect::<Vec<_>>(),
+            vec!["Class A\\D", "Enum E", "Interface I"]
+        );
+        assert_eq!(
+            names.declaration(b"a\\d"),
+            Some((&ByteString::from("A\\D"), SymbolKind::Class))
+        );
+    }
+
+    #[test]
+    fn test_trait_adaptations() {
+        let code = "<?php namespace App;
+            use Vendor\\SomeTrait as ST;
+            class A {
+                use \\Vendor\\Package\\SomeTrait, ST, Other {
+                    \\Vendor\\Package\\SomeTrait::method insteadof Other, ST;
+                    ST::method as renamed;
+                    Other::list as protected print;
+                }
+            }";
+        let names = resolve_names(&crate::parse(code).unwrap());
+        let resolved = |prefix| resolved_after(&names, code, prefix);
+
+        assert_eq!(
+            resolved("{\n                    ").as_deref(),
+            Some("Vendor\\Package\\SomeTrait")
+        );
+        assert_eq!(resolved("insteadof ").as_deref(), Some("App\\Other"));
+        assert_eq!(resolved("Other, ").as_deref(), Some("Vendor\\SomeTrait"));
+        assert_eq!(
+            resolved(";\n                    ").as_deref(),
+            Some("Vendor\\SomeTrait")
+        );
+        assert_eq!(
+            resolved("renamed;\n                    ").as_deref(),
+            Some("App\\Other")
+        );
+        assert_eq!(resolved("::").as_deref(), None);
+    }
+}
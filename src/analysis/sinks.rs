@@ -0,0 +1,309 @@
+//! The places where a program writes output, see [`output_sinks`].
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::arguments::Argument;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::Expression;
+use crate::parser::ast::FunctionCallExpression;
+use crate::parser::ast::PrintExpression;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+use crate::parser::ast::StringPart;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+use super::contains;
+
+/// The functions of the `printf` family that write to the output.
+pub const PRINTF_FUNCTIONS: &[&str] = &["printf", "vprintf"];
+
+/// The construct that writes to the output.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OutputSinkKind {
+    /// `echo $a, $b;`
+    Echo,
+    /// `<?= $a ?>`
+    EchoTag,
+    /// `print $a`
+    Print,
+    /// A call to one of the `printf` functions, with the name as written.
+    Printf(ByteString),
+}
+
+/// A construct that writes to the output, with the parts of its output that aren't
+/// constant.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OutputSink {
+    pub kind: OutputSinkKind,
+    /// The `echo`, `<?=` or `print` keyword, or the name of the function.
+    pub span: Span,
+    /// The expressions that make up the output, in order. Interpolated strings,
+    /// heredocs and concatenations are broken up into the expressions they embed, and
+    /// literals, constant strings and magic constants are left out.
+    pub values: Vec<Expression>,
+}
+
+impl OutputSink {
+    /// Whether the output is made of constants only, so it doesn't need escaping.
+    pub fn is_safe(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Find the output sinks of a program, in source order, treating calls to the
+/// [`PRINTF_FUNCTIONS`] as sinks.
+pub fn output_sinks(program: &Program) -> Vec<OutputSink> {
+    OutputSinks::new().find(program)
+}
+
+/// The search for output sinks, with the names of the functions that write their
+/// arguments to the output.
+///
+/// Function names are compared case-insensitively and without their namespace, as they
+/// aren't resolved against imports.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OutputSinks {
+    functions: Vec<ByteString>,
+}
+
+impl Default for OutputSinks {
+    fn default() -> Self {
+        Self {
+            functions: PRINTF_FUNCTIONS.iter().map(|name| (*name).into()).collect(),
+        }
+    }
+}
+
+impl OutputSinks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treat calls to the functions with the given names as sinks, instead of the
+    /// [`PRINTF_FUNCTIONS`].
+    pub fn with_functions<I, T>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ByteString>,
+    {
+        self.functions = names.into_iter().map(Into::into).collect();
+
+        self
+    }
+
+    pub fn find(&self, program: &Program) -> Vec<OutputSink> {
+        let mut collector = SinkCollector {
+            functions: &self.functions,
+            sinks: vec![],
+            tag: None,
+        };
+        program.visit_spans(&mut collector);
+
+        collector.sinks
+    }
+}
+
+struct SinkCollector<'a> {
+    functions: &'a [ByteString],
+    sinks: Vec<OutputSink>,
+    /// The `<?=` tag that the statement visited next belongs to.
+    tag: Option<Span>,
+}
+
+impl SinkCollector<'_> {
+    fn push<'e>(
+        &mut self,
+        kind: OutputSinkKind,
+        span: Span,
+        outputs: impl IntoIterator<Item = &'e Expression>,
+    ) {
+        let mut values = vec![];
+        for output in outputs {
+            flatten(output, &mut values);
+        }
+
+        self.sinks.push(OutputSink { kind, span, values });
+    }
+}
+
+impl<'a> SpanVisitor<'a> for SinkCollector<'_> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(statement) = node.downcast_ref::<Statement>() {
+            // The value of `<?= $a ?>` is parsed as the expression statement that
+            // follows the tag.
+            match (self.tag.take(), statement) {
+                (_, Statement::EchoOpeningTag(tag)) => self.tag = Some(tag.span),
+                (Some(tag), Statement::Expression(statement)) => {
+                    self.push(OutputSinkKind::EchoTag, tag, [&statement.expression])
+                }
+                (_, Statement::Echo(echo)) => {
+                    self.push(OutputSinkKind::Echo, echo.echo, &echo.values)
+                }
+                _ => {}
+            }
+        } else if let Some(print) = node.downcast_ref::<PrintExpression>() {
+            let value = match (&print.value, &print.argument) {
+                (Some(value), _) => Some(value.as_ref()),
+                (_, Some(argument)) => Some(argument_value(&argument.argument)),
+                _ => None,
+            };
+
+            self.push(OutputSinkKind::Print, print.print, value);
+        } else if let Some(call) = node.downcast_ref::<FunctionCallExpression>() {
+            if let Expression::Identifier(Identifier::SimpleIdentifier(name)) = call.target.as_ref()
+            {
+                if contains(self.functions, &name.value) {
+                    self.push(
+                        OutputSinkKind::Printf(name.value.clone()),
+                        name.span,
+                        call.arguments.iter().map(argument_value),
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn argument_value(argument: &Argument) -> &Expression {
+    match argument {
+        Argument::Positional(argument) => &argument.value,
+        Argument::Named(argument) => &argument.value,
+    }
+}
+
+/// Add the parts of `expression` that aren't constant to `values`.
+fn flatten(expression: &Expression, values: &mut Vec<Expression>) {
+    match expression {
+        Expression::Literal(_)
+        | Expression::Bool(_)
+        | Expression::Null(_)
+        | Expression::MagicConstant(_)
+        | Expression::Nowdoc(_) => {}
+        Expression::InterpolatedString(string) => flatten_parts(&string.parts, values),
+        Expression::Heredoc(heredoc) => flatten_parts(&heredoc.parts, values),
+        Expression::Parenthesized(parenthesized) => flatten(&parenthesized.expr, values),
+        Expression::Concat(concat) => {
+            flatten(&concat.left, values);
+            flatten(&concat.right, values);
+        }
+        Expression::ConcatMany(concat) => {
+            for part in &concat.parts {
+                flatten(part, values);
+            }
+        }
+        expression => values.push(expression.clone()),
+    }
+}
+
+fn flatten_parts(parts: &[StringPart], values: &mut Vec<Expression>) {
+    for part in parts {
+        if let StringPart::Expression(part) = part {
+            flatten(&part.expression, values);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::variables::Variable;
+
+    const TEMPLATE: &str = include_str!("../../tests/fixtures/0403-output-sinks/code.php");
+
+    /// The sinks as `kind: value, value`, with variables and property fetches spelled
+    /// out and other values by their kind.
+    fn outline(sinks: &[OutputSink]) -> Vec<String> {
+        sinks
+            .iter()
+            .map(|sink| {
+                let values: Vec<String> = sink.values.iter().map(describe).collect();
+
+                format!("{:?}: {}", sink.kind, values.join(", "))
+            })
+            .collect()
+    }
+
+    fn describe(expression: &Expression) -> String {
+        match expression {
+            Expression::Variable(Variable::SimpleVariable(variable)) => variable.name.to_string(),
+            Expression::PropertyFetch(fetch) => {
+                format!("{}->{}", describe(&fetch.target), describe(&fetch.property))
+            }
+            Expression::Identifier(Identifier::SimpleIdentifier(name)) => name.value.to_string(),
+            expression => format!("{:?}", expression)
+                .split('(')
+                .next()
+                .unwrap()
+                .to_string(),
+        }
+    }
+
+    #[test]
+    fn test_template_sinks() {
+        let program = crate::parse(TEMPLATE).unwrap();
+        let sinks = output_sinks(&program);
+
+        assert_eq!(
+            outline(&sinks),
+            vec![
+                "Echo: ",
+                "Echo: $user->name, $user->email",
+                "EchoTag: $title",
+                "Print: $footer",
+                "Printf(\"printf\"): $count",
+                "EchoTag: ",
+            ]
+        );
+        assert!(sinks[0].is_safe());
+        assert!(!sinks[1].is_safe());
+        assert_eq!(sinks[2].span.line, 6);
+    }
+
+    #[test]
+    fn test_flattened_outputs() {
+        let program = crate::parse(
+            "<?php echo 'a' . $b . ('c' . $d), <<<EOT\n{$e} f\nEOT, __LINE__, true, null, $f ?: 'g';",
+        )
+        .unwrap();
+        let sinks = output_sinks(&program);
+
+        assert_eq!(outline(&sinks), vec!["Echo: $b, $d, $e, ShortTernary"]);
+    }
+
+    #[test]
+    fn test_printf_functions() {
+        let code =
+            "<?php printf('%s', $a); \\vprintf('%s', [$b]); sprintf('%s', $c); Log\\write($d);";
+        let program = crate::parse(code).unwrap();
+
+        assert_eq!(
+            outline(&output_sinks(&program)),
+            vec![
+                "Printf(\"printf\"): $a",
+                "Printf(\"\\vprintf\"): ShortArray"
+            ]
+        );
+        assert_eq!(
+            outline(&OutputSinks::new().with_functions(["write"]).find(&program)),
+            vec!["Printf(\"Log\\write\"): $d"]
+        );
+    }
+
+    #[test]
+    fn test_print_with_parentheses() {
+        let program = crate::parse("<?php $ok = print('ok') && print($message);").unwrap();
+
+        assert_eq!(
+            outline(&output_sinks(&program)),
+            vec!["Print: ", "Print: $message"]
+        );
+    }
+}
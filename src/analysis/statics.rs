@@ -0,0 +1,276 @@
+//! `static` declarations that are likely to be mistakes, see [`static_var_issues`].
+
+use crate::lexer::token::Span;
+use crate::parser::ast::functions::ArrowFunctionExpression;
+use crate::parser::ast::functions::ClosureExpression;
+use crate::parser::ast::functions::ConcreteConstructor;
+use crate::parser::ast::functions::ConcreteMethod;
+use crate::parser::ast::functions::FunctionStatement;
+use crate::parser::ast::variables::SimpleVariable;
+use crate::parser::ast::variables::Variable;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+use crate::parser::error;
+use crate::parser::error::ParseError;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+/// Find the `static` declarations in functions, methods and closures that shadow a
+/// parameter, or a variable that a closure captures with `use`, and those of a
+/// variable that the same function already declared `static`, as warnings in source
+/// order.
+///
+/// Every `static` declaration in a body is of a variable of the function, including
+/// those in blocks, loops and branches. The bodies of nested functions, closures and
+/// arrow functions are checked on their own. A variable declared more than once is
+/// only checked for shadowing at its first declaration.
+pub fn static_var_issues(program: &Program) -> Vec<ParseError> {
+    let mut collector = FunctionCollector { warnings: vec![] };
+
+    program.visit_spans(&mut collector);
+
+    let mut warnings = collector.warnings;
+    warnings.sort_by_key(|warning| warning.span.position);
+
+    warnings
+}
+
+struct FunctionCollector {
+    warnings: Vec<ParseError>,
+}
+
+impl FunctionCollector {
+    /// Check the `static` declarations of a body, given the variables they can shadow
+    /// and what those are.
+    fn check(&mut self, shadowable: Vec<(&str, &SimpleVariable)>, body: &impl VisitSpans) {
+        let mut statics = StaticCollector::default();
+        body.visit_spans(&mut statics);
+
+        let mut declared: Vec<&SimpleVariable> = vec![];
+        for variable in statics.variables {
+            if let Some(first) = declared.iter().find(|first| first.name == variable.name) {
+                self.warnings
+                    .push(error::duplicate_static_variable(first, variable));
+
+                continue;
+            }
+
+            if let Some((kind, shadowed)) = shadowable
+                .iter()
+                .find(|(_, shadowed)| shadowed.name == variable.name)
+            {
+                self.warnings
+                    .push(error::static_variable_shadows(variable, kind, shadowed));
+            }
+
+            declared.push(variable);
+        }
+    }
+}
+
+impl<'a> SpanVisitor<'a> for FunctionCollector {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(function) = node.downcast_ref::<FunctionStatement>() {
+            let parameters = function
+                .parameters
+                .parameters
+                .iter()
+                .map(|parameter| ("parameter", &parameter.name))
+                .collect();
+
+            self.check(parameters, &function.body);
+        } else if let Some(method) = node.downcast_ref::<ConcreteMethod>() {
+            let parameters = method
+                .parameters
+                .parameters
+                .iter()
+                .map(|parameter| ("parameter", &parameter.name))
+                .collect();
+
+            self.check(parameters, &method.body);
+        } else if let Some(constructor) = node.downcast_ref::<ConcreteConstructor>() {
+            let parameters = constructor
+                .parameters
+                .parameters
+                .iter()
+                .map(|parameter| ("parameter", &parameter.name))
+                .collect();
+
+            self.check(parameters, &constructor.body);
+        } else if let Some(closure) = node.downcast_ref::<ClosureExpression>() {
+            let mut shadowable: Vec<(&str, &SimpleVariable)> = closure
+                .parameters
+                .parameters
+                .iter()
+                .map(|parameter| ("parameter", &parameter.name))
+                .collect();
+
+            if let Some(uses) = &closure.uses {
+                shadowable.extend(
+                    uses.variables
+                        .iter()
+                        .map(|variable| ("variable captured with `use`", &variable.variable)),
+                );
+            }
+
+            self.check(shadowable, &closure.body);
+        }
+    }
+}
+
+/// The variables declared `static` in a body, in source order.
+#[derive(Default)]
+struct StaticCollector<'a> {
+    variables: Vec<&'a SimpleVariable>,
+    /// Whether each segment that was entered has variables of its own.
+    frames: Vec<bool>,
+    /// The number of those segments that the visitor is in.
+    skipped: usize,
+}
+
+impl<'a> SpanVisitor<'a> for StaticCollector<'a> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            self.frames.push(false);
+
+            return;
+        };
+
+        // These have variables of their own, which includes the methods of a class
+        // declared in the body.
+        let skipped = node.is::<FunctionStatement>()
+            || node.is::<ClosureExpression>()
+            || node.is::<ArrowFunctionExpression>()
+            || node.is::<ConcreteMethod>()
+            || node.is::<ConcreteConstructor>();
+
+        self.frames.push(skipped);
+        if skipped {
+            self.skipped += 1;
+        }
+
+        if self.skipped > 0 {
+            return;
+        }
+
+        if let Some(Statement::Static(r#static)) = node.downcast_ref::<Statement>() {
+            for var in &r#static.vars {
+                if let Variable::SimpleVariable(variable) = &var.var {
+                    self.variables.push(variable);
+                }
+            }
+        }
+    }
+
+    fn leave(&mut self) {
+        if self.frames.pop() == Some(true) {
+            self.skipped -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(code: &str) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        static_var_issues(&program)
+            .iter()
+            .map(|warning| {
+                format!(
+                    "{}:{} {}",
+                    warning.span.line, warning.span.column, warning.message
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_static_shadows_parameter() {
+        let code = "<?php
+function f($x, $y) {
+    static $x = 1;
+}
+class A {
+    public function __construct(private $a) { static $a; }
+    public function m($b) { if ($b) { static $b = []; } }
+}";
+
+        assert_eq!(
+            issues(code),
+            vec![
+                "3:12 static variable `$x` shadows a parameter",
+                "6:54 static variable `$a` shadows a parameter",
+                "7:46 static variable `$b` shadows a parameter",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_static() {
+        let code = "<?php
+function f($x) {
+    static $count = 0;
+    foreach ($x as $item) {
+        static $count, $x;
+    }
+    static $count;
+}";
+
+        assert_eq!(
+            issues(code),
+            vec![
+                "5:16 static variable `$count` is declared more than once",
+                "5:24 static variable `$x` shadows a parameter",
+                "7:12 static variable `$count` is declared more than once",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_closure_scopes() {
+        let code = "<?php
+function f($a) {
+    static $b;
+    $g = function ($c) use ($a, &$b) {
+        static $a, $b, $d;
+    };
+    $h = fn ($d) => function () { static $d; };
+    static $d;
+}";
+
+        assert_eq!(
+            issues(code),
+            vec![
+                "5:16 static variable `$a` shadows a variable captured with `use`",
+                "5:20 static variable `$b` shadows a variable captured with `use`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clean_function() {
+        let code = "<?php
+function counter(int $step) {
+    static $count = 0;
+    static $calls;
+    $count += $step;
+    return function () use ($count) { static $calls = 0; return ++$calls + $count; };
+}
+static $count;
+static $count;";
+
+        assert!(issues(code).is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+//! Comments that suppress diagnostics, see [`apply_suppressions`].
+
+use crate::lexer::token::Span;
+use crate::parser::ast::comments::Comment;
+use crate::parser::error::ParseError;
+
+/// The prefix of the suppression comments that [`apply_suppressions`] is usually given,
+/// as in `// parser-ignore-next-line E082`.
+pub const SUPPRESSION_PREFIX: &str = "parser";
+
+/// A code in a suppression comment that didn't suppress any diagnostic.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnusedSuppression {
+    /// The code as written, or `all`.
+    pub code: String,
+    /// The line that the comment suppresses diagnostics on.
+    pub line: usize,
+    /// The comment.
+    pub span: Span,
+}
+
+/// Drop the diagnostics that comments ask to suppress, and find the codes in these
+/// comments that didn't suppress anything, so that they can be cleaned up.
+///
+/// With `prefix` set to `parser`, a comment that is only `parser-ignore-next-line`
+/// followed by codes suppresses diagnostics on the line after the comment, and one that
+/// is `parser-ignore` followed by codes suppresses them on the line the comment starts
+/// on, e.g. at the end of the line. Codes are separated by commas, like `E082, E083`,
+/// and `all` suppresses any diagnostic. A diagnostic is on the line where its span
+/// starts.
+///
+/// `comments` are all the comments of `source`, e.g. collected from its tokens with
+/// [`Comment::from_token`]. The comments and diagnostics are left in their order.
+pub fn apply_suppressions(
+    diagnostics: Vec<ParseError>,
+    comments: &[Comment],
+    source: &[u8],
+    prefix: &str,
+) -> (Vec<ParseError>, Vec<UnusedSuppression>) {
+    let lines = LineIndex::new(source);
+    let mut suppressions = comments
+        .iter()
+        .filter_map(|comment| Suppression::parse(comment, prefix, &lines))
+        .collect::<Vec<_>>();
+
+    let diagnostics = diagnostics
+        .into_iter()
+        .filter(|diagnostic| {
+            let mut suppressed = false;
+
+            for suppression in &mut suppressions {
+                if suppression.line != diagnostic.span.line {
+                    continue;
+                }
+
+                for (code, used) in &mut suppression.codes {
+                    if code.eq_ignore_ascii_case("all") || code.eq_ignore_ascii_case(&diagnostic.id)
+                    {
+                        *used = true;
+                        suppressed = true;
+                    }
+                }
+            }
+
+            !suppressed
+        })
+        .collect();
+
+    let unused = suppressions
+        .into_iter()
+        .flat_map(|suppression| {
+            suppression
+                .codes
+                .into_iter()
+                .filter(|(_, used)| !used)
+                .map(move |(code, _)| UnusedSuppression {
+                    code,
+                    line: suppression.line,
+                    span: suppression.span,
+                })
+        })
+        .collect();
+
+    (diagnostics, unused)
+}
+
+struct Suppression {
+    span: Span,
+    line: usize,
+    /// The codes, and whether they suppressed a diagnostic.
+    codes: Vec<(String, bool)>,
+}
+
+impl Suppression {
+    fn parse(comment: &Comment, prefix: &str, lines: &LineIndex) -> Option<Self> {
+        let content = std::str::from_utf8(&comment.content).ok()?;
+        let text = if let Some(text) = content.strip_prefix("/*") {
+            // The directive can be on a line of its own, after a `*`.
+            let text = text.strip_suffix("*/").unwrap_or(text).trim_start();
+            text.trim_start_matches('*')
+        } else if let Some(text) = content.strip_prefix("//") {
+            text
+        } else {
+            content.strip_prefix('#')?
+        };
+
+        let directive = text.trim().strip_prefix(prefix)?;
+        let (next_line, codes) = if let Some(codes) = directive.strip_prefix("-ignore-next-line") {
+            (true, codes)
+        } else {
+            (false, directive.strip_prefix("-ignore")?)
+        };
+
+        // The directive has to end at a space, e.g. `parser-ignored` isn't one.
+        if !codes.starts_with(char::is_whitespace) {
+            return None;
+        }
+
+        let codes = codes
+            .split(',')
+            .map(str::trim)
+            .filter(|code| !code.is_empty())
+            .map(|code| (code.to_string(), false))
+            .collect::<Vec<_>>();
+        if codes.is_empty() {
+            return None;
+        }
+
+        let line = if next_line {
+            lines.line(comment.span.position + comment.content.len()) + 1
+        } else {
+            comment.span.line
+        };
+
+        Some(Self {
+            span: comment.span,
+            line,
+            codes,
+        })
+    }
+}
+
+/// The positions where the lines of a source start.
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(source: &[u8]) -> Self {
+        let starts = std::iter::once(0)
+            .chain(
+                source
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, byte)| **byte == b'\n')
+                    .map(|(position, _)| position + 1),
+            )
+            .collect();
+
+        Self { starts }
+    }
+
+    /// The line of the last byte before `end`, counted from 1.
+    fn line(&self, end: usize) -> usize {
+        self.starts.partition_point(|start| *start < end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::unreachable_code;
+
+    /// The diagnostics left and the unused suppressions, for unreachable code.
+    fn suppress(code: &str) -> (Vec<usize>, Vec<String>) {
+        let (program, tokens) = crate::parse_with_tokens(code).unwrap();
+        let comments = tokens
+            .iter()
+            .filter_map(Comment::from_token)
+            .collect::<Vec<_>>();
+
+        let (diagnostics, unused) = apply_suppressions(
+            unreachable_code(&program),
+            &comments,
+            code.as_bytes(),
+            SUPPRESSION_PREFIX,
+        );
+
+        (
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.span.line)
+                .collect(),
+            unused
+                .iter()
+                .map(|unused| format!("{}:{} {}", unused.span.line, unused.line, unused.code))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_next_line_suppression() {
+        let code = "<?php
+function a() {
+    return;
+    // parser-ignore-next-line E082
+    echo 1;
+}
+function b() {
+    return;
+    /*
+     * parser-ignore-next-line all
+     */
+    echo 2;
+}
+function c() {
+    return;
+    echo 3;
+}";
+
+        assert_eq!(suppress(code), (vec![16], vec![]));
+    }
+
+    #[test]
+    fn test_same_line_suppression() {
+        let code = "<?php
+function a() { return; echo 1; } # parser-ignore E001, E082
+function b() { return; /* parser-ignore E082 */ echo 2; }";
+
+        assert_eq!(suppress(code), (vec![], vec!["2:2 E001".to_string()]));
+    }
+
+    #[test]
+    fn test_code_mismatch() {
+        let code = "<?php
+function a() {
+    return;
+    // parser-ignore-next-line E083
+    echo 1;
+    // parser-ignore-next-line E082
+}
+// parser-ignored E082
+// parser-ignore
+// other-ignore-next-line E082";
+
+        assert_eq!(
+            suppress(code),
+            (
+                vec![5],
+                vec!["4:5 E083".to_string(), "6:7 E082".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_line_index() {
+        let lines = LineIndex::new(b"a\nbc\n\nd");
+
+        assert_eq!(lines.line(1), 1);
+        assert_eq!(lines.line(2), 1);
+        assert_eq!(lines.line(3), 2);
+        assert_eq!(lines.line(5), 2);
+        assert_eq!(lines.line(6), 3);
+        assert_eq!(lines.line(8), 4);
+    }
+}
@@ -0,0 +1,329 @@
+//! Statements that can never run, see [`unreachable_code`].
+
+use crate::lexer::token::Span;
+use crate::parser::ast::control_flow::IfStatementBody;
+use crate::parser::ast::control_flow::IfStatementElseBlock;
+use crate::parser::ast::control_flow::IfStatementElseIfBlock;
+use crate::parser::ast::declares::DeclareBody;
+use crate::parser::ast::functions::ArrowFunctionBlockBody;
+use crate::parser::ast::functions::FunctionBody;
+use crate::parser::ast::functions::MethodBody;
+use crate::parser::ast::loops::ForStatementBody;
+use crate::parser::ast::loops::ForeachStatementBody;
+use crate::parser::ast::loops::WhileStatementBody;
+use crate::parser::ast::namespaces::BracedNamespaceBody;
+use crate::parser::ast::namespaces::UnbracedNamespace;
+use crate::parser::ast::try_block::CatchBlock;
+use crate::parser::ast::try_block::FinallyBlock;
+use crate::parser::ast::try_block::TryStatement;
+use crate::parser::ast::BlockStatement;
+use crate::parser::ast::Case;
+use crate::parser::ast::Expression;
+use crate::parser::ast::MatchArmBody;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+use crate::parser::error;
+use crate::parser::error::ParseError;
+use crate::spans::bounds;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+use super::Terminators;
+
+/// Find the statements that follow a terminator in the same list of statements, as
+/// warnings in source order.
+///
+/// A terminator is a `return`, `break`, `continue` or `goto`, or an expression that
+/// [`Terminators::for_program`] knows never hands control back, such as `exit` or
+/// `throw`. Every list of statements is checked on its own: the bodies of functions,
+/// methods and closures, blocks, the branches of an `if`, the bodies of loops, `case`s,
+/// `try`, `catch` and `finally` blocks, and the top level of the program and of its
+/// namespaces. A nested function doesn't end the list that declares it.
+///
+/// Only one warning is reported for a run of dead statements. A label ends the run, as
+/// it can be reached with `goto`, and so do declarations of functions, classes,
+/// interfaces, traits and enums at the top level, which PHP declares before running the
+/// file. Comments, opening and closing tags, empty statements and inline HTML that is
+/// only whitespace are left out.
+pub fn unreachable_code(program: &Program) -> Vec<ParseError> {
+    let mut collector = UnreachableCollector {
+        terminators: Terminators::for_program(program),
+        warnings: vec![],
+    };
+
+    collector.check(program, true);
+
+    program.visit_spans(&mut collector);
+
+    let mut warnings = collector.warnings;
+    warnings.sort_by_key(|warning| warning.span.position);
+
+    warnings
+}
+
+struct UnreachableCollector {
+    terminators: Terminators,
+    warnings: Vec<ParseError>,
+}
+
+impl UnreachableCollector {
+    fn check(&mut self, statements: &[Statement], top_level: bool) {
+        let mut terminator = None;
+        let mut dead = vec![];
+
+        for statement in statements {
+            let hoisted = top_level && is_hoisted(statement);
+
+            if matches!(statement, Statement::Label(_)) || hoisted {
+                self.report(terminator, &dead);
+                dead.clear();
+
+                if !hoisted {
+                    terminator = None;
+                }
+            } else if terminator.is_some() {
+                if !is_ignored(statement) {
+                    dead.push(statement);
+                }
+            } else if let Some(kind) = self.terminator(statement) {
+                terminator = Some((kind, statement));
+            }
+        }
+
+        self.report(terminator, &dead);
+    }
+
+    fn report(&mut self, terminator: Option<(&str, &Statement)>, dead: &[&Statement]) {
+        let Some((kind, terminator)) = terminator else {
+            return;
+        };
+        if dead.is_empty() {
+            return;
+        }
+
+        let (terminated, last) = bounds(&[terminator]);
+        let (start, end) = bounds(dead);
+
+        self.warnings.push(error::unreachable_code(
+            kind,
+            terminated,
+            last.end() - terminated.position,
+            start,
+            end,
+        ));
+    }
+
+    /// What the statement is, if it never hands control to the next one.
+    fn terminator(&self, statement: &Statement) -> Option<&'static str> {
+        Some(match statement {
+            Statement::Return(_) => "`return`",
+            Statement::Break(_) => "`break`",
+            Statement::Continue(_) => "`continue`",
+            Statement::Goto(_) => "`goto`",
+            Statement::Expression(expression)
+                if self.terminators.terminates_statement(statement) =>
+            {
+                match unparenthesized(&expression.expression) {
+                    Expression::Exit(_) => "`exit`",
+                    Expression::Die(_) => "`die`",
+                    Expression::Throw(_) => "`throw`",
+                    _ => "call that never returns",
+                }
+            }
+            _ => return None,
+        })
+    }
+}
+
+impl<'a> SpanVisitor<'a> for UnreachableCollector {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        if let Some(r#try) = node.downcast_ref::<TryStatement>() {
+            self.check(&r#try.body, false);
+        } else if let Some(catch) = node.downcast_ref::<CatchBlock>() {
+            self.check(&catch.body, false);
+        } else if let Some(finally) = node.downcast_ref::<FinallyBlock>() {
+            self.check(&finally.body, false);
+        } else if let Some(MatchArmBody::Block { statements, .. }) =
+            node.downcast_ref::<MatchArmBody>()
+        {
+            self.check(statements, false);
+        } else if let Some(block) = node.downcast_ref::<BlockStatement>() {
+            self.check(&block.statements, false);
+        } else if let Some(body) = node.downcast_ref::<FunctionBody>() {
+            self.check(&body.statements, false);
+        } else if let Some(body) = node.downcast_ref::<MethodBody>() {
+            self.check(&body.statements, false);
+        } else if let Some(body) = node.downcast_ref::<ArrowFunctionBlockBody>() {
+            self.check(&body.statements, false);
+        } else if let Some(IfStatementBody::Block { statements, .. }) =
+            node.downcast_ref::<IfStatementBody>()
+        {
+            self.check(statements, false);
+        } else if let Some(elseif) = node.downcast_ref::<IfStatementElseIfBlock>() {
+            self.check(&elseif.statements, false);
+        } else if let Some(r#else) = node.downcast_ref::<IfStatementElseBlock>() {
+            self.check(&r#else.statements, false);
+        } else if let Some(case) = node.downcast_ref::<Case>() {
+            self.check(&case.body, false);
+        } else if let Some(
+            DeclareBody::Braced { statements, .. } | DeclareBody::Block { statements, .. },
+        ) = node.downcast_ref::<DeclareBody>()
+        {
+            self.check(statements, false);
+        } else if let Some(ForeachStatementBody::Block { statements, .. }) =
+            node.downcast_ref::<ForeachStatementBody>()
+        {
+            self.check(statements, false);
+        } else if let Some(ForStatementBody::Block { statements, .. }) =
+            node.downcast_ref::<ForStatementBody>()
+        {
+            self.check(statements, false);
+        } else if let Some(WhileStatementBody::Block { statements, .. }) =
+            node.downcast_ref::<WhileStatementBody>()
+        {
+            self.check(statements, false);
+        } else if let Some(namespace) = node.downcast_ref::<UnbracedNamespace>() {
+            self.check(&namespace.statements, true);
+        } else if let Some(body) = node.downcast_ref::<BracedNamespaceBody>() {
+            self.check(&body.statements, true);
+        }
+    }
+}
+
+/// Declarations that PHP makes when it compiles the file, if they are at its top level.
+fn is_hoisted(statement: &Statement) -> bool {
+    matches!(
+        statement,
+        Statement::Function(_)
+            | Statement::Class(_)
+            | Statement::Interface(_)
+            | Statement::Trait(_)
+            | Statement::UnitEnum(_)
+            | Statement::BackedEnum(_)
+    )
+}
+
+/// Statements that don't run any code.
+fn is_ignored(statement: &Statement) -> bool {
+    match statement {
+        Statement::Comment(_)
+        | Statement::FullOpeningTag(_)
+        | Statement::ShortOpeningTag(_)
+        | Statement::EchoOpeningTag(_)
+        | Statement::ClosingTag(_)
+        | Statement::Noop(_) => true,
+        Statement::InlineHtml(inline) => inline
+            .html
+            .bytes()
+            .is_ok_and(|html| html.iter().all(u8::is_ascii_whitespace)),
+        _ => false,
+    }
+}
+
+fn unparenthesized(expression: &Expression) -> &Expression {
+    match expression {
+        Expression::Parenthesized(parenthesized) => unparenthesized(&parenthesized.expr),
+        expression => expression,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0413-unreachable-code/code.php");
+
+    fn unreachable(code: &str) -> Vec<String> {
+        let program = crate::parse(code).unwrap();
+
+        unreachable_code(&program)
+            .iter()
+            .map(|warning| {
+                format!(
+                    "{}:{} {}",
+                    warning.span.line,
+                    warning.span.column,
+                    warning.note.as_deref().unwrap_or_default()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unreachable_code() {
+        assert_eq!(
+            unreachable(SOURCE),
+            vec![
+                "6:5 execution never gets past the `return` on line 5",
+                "21:5 execution never gets past the `throw` on line 20",
+                "27:9 execution never gets past the `continue` on line 26",
+                "33:13 execution never gets past the `break` on line 32",
+                "41:5 execution never gets past the `exit` on line 40",
+                "46:1 execution never gets past the `return` on line 44",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_one_warning_per_dead_region() {
+        let program =
+            crate::parse("<?php function f() { return; echo 1; echo 2; $a = 3; }").unwrap();
+        let warnings = unreachable_code(&program);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].id, "E082");
+        // From `echo 1;` to `$a = 3;`.
+        assert_eq!(warnings[0].annotations[0].position, 29);
+        assert_eq!(warnings[0].annotations[0].length, 23);
+    }
+
+    #[test]
+    fn test_labels_are_reachable() {
+        assert_eq!(
+            unreachable("<?php goto end; end: echo 1; return; echo 2; done: echo 3;"),
+            vec!["1:38 execution never gets past the `return` on line 1"]
+        );
+    }
+
+    #[test]
+    fn test_hoisted_declarations() {
+        assert_eq!(
+            unreachable("<?php return; function f() {} interface I {} ?>\n\n"),
+            Vec::<String>::new()
+        );
+        // Declarations in a function are only made when it runs.
+        assert_eq!(
+            unreachable("<?php function f() { return; function g() {} }"),
+            vec!["1:30 execution never gets past the `return` on line 1"]
+        );
+    }
+
+    #[test]
+    fn test_never_returning_functions() {
+        assert_eq!(
+            unreachable("<?php function fail(): never {} fail(); echo 1;"),
+            vec!["1:41 execution never gets past the call that never returns on line 1"]
+        );
+    }
+
+    #[test]
+    fn test_try_blocks() {
+        assert_eq!(
+            unreachable(
+                "<?php try { return; echo 1; } catch (E $e) { throw $e; echo 2; } finally { exit; echo 3; }"
+            ),
+            vec![
+                "1:21 execution never gets past the `return` on line 1",
+                "1:56 execution never gets past the `throw` on line 1",
+                "1:82 execution never gets past the `exit` on line 1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,535 @@
+//! The version of PHP that a program needs, see [`required_version`].
+
+use std::any::Any;
+
+use crate::lexer::token::Span;
+use crate::parser::ast::arguments::NamedArgument;
+use crate::parser::ast::classes::ClassStatement;
+use crate::parser::ast::constant::ClassishConstant;
+use crate::parser::ast::data_type::Type;
+use crate::parser::ast::enums::BackedEnumStatement;
+use crate::parser::ast::enums::UnitEnumStatement;
+use crate::parser::ast::functions::ArrowFunctionExpression;
+use crate::parser::ast::functions::ClosureUse;
+use crate::parser::ast::functions::ConstructorParameter;
+use crate::parser::ast::functions::ConstructorParameterList;
+use crate::parser::ast::functions::FunctionParameter;
+use crate::parser::ast::functions::FunctionParameterList;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::literals::LiteralInteger;
+use crate::parser::ast::loops::ForeachStatementIterator;
+use crate::parser::ast::modifiers::ClassModifier;
+use crate::parser::ast::modifiers::ConstantModifier;
+use crate::parser::ast::modifiers::PromotedPropertyModifier;
+use crate::parser::ast::modifiers::PropertyModifier;
+use crate::parser::ast::operators::AssignmentOperationExpression;
+use crate::parser::ast::properties::Property;
+use crate::parser::ast::properties::VariableProperty;
+use crate::parser::ast::traits::TraitMember;
+use crate::parser::ast::try_block::CatchBlock;
+use crate::parser::ast::try_block::CatchType;
+use crate::parser::ast::utils::CommaSeparated;
+use crate::parser::ast::ArrayItem;
+use crate::parser::ast::ConstantFetchExpression;
+use crate::parser::ast::Expression;
+use crate::parser::ast::ExpressionStatement;
+use crate::parser::ast::FunctionClosureCreationExpression;
+use crate::parser::ast::ListEntry;
+use crate::parser::ast::ListExpression;
+use crate::parser::ast::MatchExpression;
+use crate::parser::ast::MethodClosureCreationExpression;
+use crate::parser::ast::NullsafeMethodCallExpression;
+use crate::parser::ast::NullsafePropertyFetchExpression;
+use crate::parser::ast::Program;
+use crate::parser::ast::StaticMethodClosureCreationExpression;
+use crate::parser::ast::StaticVariableMethodClosureCreationExpression;
+use crate::parser::ast::ThrowExpression;
+use crate::parser::version::Feature;
+use crate::parser::version::PhpVersion;
+use crate::spans::bounds;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+/// The oldest version of PHP that supports the syntax of a program, see
+/// [`required_version`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VersionReport {
+    pub version: PhpVersion,
+    /// The features that need a newer version than [`PhpVersion::OLDEST`], in source
+    /// order, each with where it is used.
+    pub evidence: Vec<(Feature, Span)>,
+}
+
+impl VersionReport {
+    /// The uses of the features that need [`VersionReport::version`], which are the
+    /// ones to change to support an older version.
+    pub fn deciding(&self) -> impl Iterator<Item = &(Feature, Span)> {
+        self.evidence
+            .iter()
+            .filter(|(feature, _)| feature.since() == self.version)
+    }
+}
+
+/// Find the oldest version of PHP that supports the syntax of a program, which is the
+/// oldest version the parser can target without reporting an unsupported feature, or
+/// [`PhpVersion::OLDEST`] if it uses none of the features that came later.
+///
+/// The features are the ones of [`ParserConfig::php_version`], with the versions of
+/// [`Feature::since`]. Trailing commas in argument lists and in grouped `use`
+/// statements aren't found, as the program doesn't keep them, and neither is the
+/// `throw` keyword, so `throw` expressions are found at the value they throw.
+///
+/// [`ParserConfig::php_version`]: crate::parser::config::ParserConfig::php_version
+pub fn required_version(program: &Program) -> VersionReport {
+    let mut collector = FeatureCollector::default();
+
+    program.visit_spans(&mut collector);
+
+    let mut evidence = collector.evidence;
+    evidence.sort_by_key(|(_, span)| span.position);
+
+    VersionReport {
+        version: evidence
+            .iter()
+            .map(|(feature, _)| feature.since())
+            .max()
+            .unwrap_or(PhpVersion::OLDEST),
+        evidence,
+    }
+}
+
+#[derive(Default)]
+struct FeatureCollector<'a> {
+    evidence: Vec<(Feature, Span)>,
+    /// The `throw` of the statement that was entered last, if it is a `throw`
+    /// statement.
+    statement: Option<&'a ThrowExpression>,
+    /// Whether each segment that was entered is a type.
+    frames: Vec<bool>,
+    /// The number of types that the visitor is in.
+    types: usize,
+}
+
+impl FeatureCollector<'_> {
+    fn used(&mut self, feature: Feature, span: Span) {
+        self.evidence.push((feature, span));
+    }
+
+    /// The features of a type as a whole, which is the type of a parameter, a property
+    /// or a return type, and of the types it is made of.
+    fn data_type(&mut self, ty: &Type) {
+        match ty {
+            Type::Null(span) | Type::False(span) => self.used(Feature::LiteralTypes, *span),
+            _ => self.inner_type(ty),
+        }
+    }
+
+    fn inner_type(&mut self, ty: &Type) {
+        match ty {
+            Type::Nullable(span, inner) => {
+                self.used(Feature::NullableTypes, *span);
+                self.inner_type(inner);
+            }
+            Type::Union(types) | Type::Intersection(types) => {
+                let feature = if types
+                    .iter()
+                    .any(|inner| matches!(inner, Type::Union(_) | Type::Intersection(_)))
+                {
+                    Feature::DisjunctiveNormalFormTypes
+                } else if matches!(ty, Type::Union(_)) {
+                    Feature::UnionTypes
+                } else {
+                    Feature::IntersectionTypes
+                };
+
+                self.used(feature, ty.first_span());
+                for inner in types {
+                    self.inner_type(inner);
+                }
+            }
+            Type::True(span) => self.used(Feature::LiteralTypes, *span),
+            Type::StaticReference(span) => self.used(Feature::StaticReturnType, *span),
+            Type::Never(span) => self.used(Feature::NeverType, *span),
+            _ => {}
+        }
+    }
+
+    /// The references taken by the short arrays of a destructuring pattern, which are
+    /// only references to the array items elsewhere. Those of `list()` are found with
+    /// the list.
+    fn pattern(&mut self, pattern: &Expression) {
+        match pattern {
+            Expression::ShortArray(array) => {
+                for item in array.items.iter() {
+                    match item {
+                        ArrayItem::ReferencedValue { ampersand, value }
+                        | ArrayItem::ReferencedKeyValue {
+                            ampersand, value, ..
+                        } => {
+                            self.used(Feature::ReferenceAssignmentsInLists, *ampersand);
+                            self.pattern(value);
+                        }
+                        ArrayItem::Value { value } | ArrayItem::KeyValue { value, .. } => {
+                            self.pattern(value)
+                        }
+                        ArrayItem::SpreadValue { .. } | ArrayItem::Skipped => {}
+                    }
+                }
+            }
+            Expression::List(list) => {
+                for entry in list.items.iter() {
+                    match entry {
+                        ListEntry::Value { value }
+                        | ListEntry::ReferencedValue { value, .. }
+                        | ListEntry::KeyValue { value, .. }
+                        | ListEntry::ReferencedKeyValue { value, .. } => self.pattern(value),
+                        ListEntry::Skipped => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn trailing_comma<T>(&mut self, list: &CommaSeparated<T>) {
+        if list.has_trailing_comma() {
+            if let Some(comma) = list.commas.last() {
+                self.used(Feature::TrailingCommaInParameters, *comma);
+            }
+        }
+    }
+}
+
+impl<'a> SpanVisitor<'a> for FeatureCollector<'a> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            self.frames.push(false);
+
+            return;
+        };
+
+        // The types a type is made of are part of it.
+        if let Some(ty) = node.downcast_ref::<Type>() {
+            if self.types == 0 {
+                self.data_type(ty);
+            }
+
+            self.frames.push(true);
+            self.types += 1;
+
+            return;
+        }
+
+        self.frames.push(false);
+
+        // A `throw` statement, as opposed to a `throw` expression.
+        if let Some(statement) = node.downcast_ref::<ExpressionStatement>() {
+            if let Expression::Throw(throw) = &statement.expression {
+                self.statement = Some(throw);
+            }
+        } else if let Some(throw) = node.downcast_ref::<ThrowExpression>() {
+            if self
+                .statement
+                .is_some_and(|statement| std::ptr::eq(statement, throw))
+            {
+                return;
+            }
+        }
+
+        self.visit(node);
+    }
+
+    fn leave(&mut self) {
+        if self.frames.pop() == Some(true) {
+            self.types -= 1;
+        }
+    }
+}
+
+impl FeatureCollector<'_> {
+    fn visit(&mut self, node: &dyn Any) {
+        if let Some(r#enum) = node.downcast_ref::<UnitEnumStatement>() {
+            self.used(Feature::Enums, r#enum.r#enum);
+        } else if let Some(r#enum) = node.downcast_ref::<BackedEnumStatement>() {
+            self.used(Feature::Enums, r#enum.r#enum);
+        } else if let Some(class) = node.downcast_ref::<ClassStatement>() {
+            for modifier in &class.modifiers.modifiers {
+                if let ClassModifier::Readonly(span) = modifier {
+                    self.used(Feature::ReadonlyClasses, *span);
+                }
+            }
+        } else if let Some(property) = node.downcast_ref::<Property>() {
+            for modifier in &property.modifiers.modifiers {
+                if let PropertyModifier::Readonly(span) = modifier {
+                    self.used(Feature::ReadonlyProperties, *span);
+                }
+            }
+
+            if let Some(ty) = &property.r#type {
+                self.used(Feature::TypedProperties, ty.first_span());
+            }
+        } else if let Some(property) = node.downcast_ref::<VariableProperty>() {
+            if let Some(ty) = &property.r#type {
+                self.used(Feature::TypedProperties, ty.first_span());
+            }
+        } else if let Some(parameter) = node.downcast_ref::<ConstructorParameter>() {
+            if let Some(modifier) = parameter.modifiers.modifiers.first() {
+                self.used(Feature::ConstructorPromotion, modifier.span());
+            }
+
+            for modifier in &parameter.modifiers.modifiers {
+                if let PromotedPropertyModifier::Readonly(span) = modifier {
+                    self.used(Feature::ReadonlyProperties, *span);
+                }
+            }
+
+            if let Some(Expression::New(new)) = &parameter.default {
+                self.used(Feature::NewInInitializers, new.new);
+            }
+        } else if let Some(parameter) = node.downcast_ref::<FunctionParameter>() {
+            if let Some(Expression::New(new)) = &parameter.default {
+                self.used(Feature::NewInInitializers, new.new);
+            }
+        } else if let Some(parameters) = node.downcast_ref::<FunctionParameterList>() {
+            self.trailing_comma(&parameters.parameters);
+        } else if let Some(parameters) = node.downcast_ref::<ConstructorParameterList>() {
+            self.trailing_comma(&parameters.parameters);
+        } else if let Some(uses) = node.downcast_ref::<ClosureUse>() {
+            self.trailing_comma(&uses.variables);
+        } else if let Some(constant) = node.downcast_ref::<ClassishConstant>() {
+            for modifier in &constant.modifiers.modifiers {
+                let feature = match modifier {
+                    ConstantModifier::Final(_) => Feature::FinalClassConstants,
+                    _ => Feature::ClassConstantVisibility,
+                };
+
+                self.used(feature, modifier.span());
+            }
+
+            if let Some(data_type) = &constant.data_type {
+                self.used(Feature::TypedClassConstants, data_type.first_span());
+            }
+        } else if let Some(TraitMember::Constant(constant)) = node.downcast_ref::<TraitMember>() {
+            self.used(Feature::ConstantsInTraits, constant.r#const);
+        } else if let Some(catch) = node.downcast_ref::<CatchBlock>() {
+            if let CatchType::Union { identifiers } = &catch.types {
+                self.used(Feature::MultiCatch, identifiers[0].span);
+            }
+
+            if catch.var.is_none() {
+                self.used(Feature::CatchWithoutVariable, catch.start);
+            }
+        } else if let Some(argument) = node.downcast_ref::<NamedArgument>() {
+            self.used(Feature::NamedArguments, argument.name.span);
+        } else if let Some(item) = node.downcast_ref::<ArrayItem>() {
+            if let ArrayItem::SpreadValue { ellipsis, .. } = item {
+                self.used(Feature::SpreadInArrays, *ellipsis);
+            }
+        } else if let Some(list) = node.downcast_ref::<ListExpression>() {
+            let double_arrow = list.items.iter().find_map(|entry| match entry {
+                ListEntry::KeyValue { double_arrow, .. }
+                | ListEntry::ReferencedKeyValue { double_arrow, .. } => Some(*double_arrow),
+                _ => None,
+            });
+
+            if let Some(double_arrow) = double_arrow {
+                self.used(Feature::KeyedListAssignments, double_arrow);
+            }
+
+            for entry in list.items.iter() {
+                if let ListEntry::ReferencedValue { ampersand, .. }
+                | ListEntry::ReferencedKeyValue { ampersand, .. } = entry
+                {
+                    self.used(Feature::ReferenceAssignmentsInLists, *ampersand);
+                }
+            }
+        } else if let Some(iterator) = node.downcast_ref::<ForeachStatementIterator>() {
+            let value = match iterator {
+                ForeachStatementIterator::Value { value, .. }
+                | ForeachStatementIterator::KeyAndValue { value, .. } => value,
+            };
+
+            if let Expression::ShortArray(array) = value {
+                self.used(Feature::ShortListAssignments, array.start);
+            }
+
+            self.pattern(value);
+        } else if let Some(assignment) = node.downcast_ref::<AssignmentOperationExpression>() {
+            match assignment {
+                AssignmentOperationExpression::Assign { left, .. } => {
+                    if let Expression::ShortArray(array) = left.as_ref() {
+                        self.used(Feature::ShortListAssignments, array.start);
+                    }
+
+                    self.pattern(left);
+                }
+                AssignmentOperationExpression::Coalesce {
+                    coalesce_equals, ..
+                } => self.used(Feature::NullCoalescingAssignment, *coalesce_equals),
+                _ => {}
+            }
+        } else if let Some(arrow_function) = node.downcast_ref::<ArrowFunctionExpression>() {
+            self.used(Feature::ArrowFunctions, arrow_function.r#fn);
+        } else if let Some(r#match) = node.downcast_ref::<MatchExpression>() {
+            self.used(Feature::Match, r#match.keyword);
+        } else if let Some(call) = node.downcast_ref::<NullsafeMethodCallExpression>() {
+            self.used(Feature::NullsafeOperator, call.question_arrow);
+        } else if let Some(fetch) = node.downcast_ref::<NullsafePropertyFetchExpression>() {
+            self.used(Feature::NullsafeOperator, fetch.question_arrow);
+        } else if let Some(throw) = node.downcast_ref::<ThrowExpression>() {
+            self.used(Feature::ThrowExpressions, bounds(&[throw.value.as_ref()]).0);
+        } else if let Some(fetch) = node.downcast_ref::<ConstantFetchExpression>() {
+            match &fetch.constant {
+                Identifier::DynamicIdentifier(dynamic) => {
+                    self.used(Feature::DynamicClassConstantFetch, dynamic.start);
+                }
+                Identifier::SimpleIdentifier(constant)
+                    if constant.value.eq_ignore_ascii_case(b"class")
+                        && !matches!(
+                            fetch.target.as_ref(),
+                            Expression::Identifier(_)
+                                | Expression::Self_(_)
+                                | Expression::Static(_)
+                                | Expression::Parent(_)
+                        ) =>
+                {
+                    self.used(Feature::ClassNameOfObjects, constant.span);
+                }
+                _ => {}
+            }
+        } else if let Some(literal) = node.downcast_ref::<LiteralInteger>() {
+            if literal.value.len() > 1 && matches!(literal.value[1], b'o' | b'O') {
+                self.used(Feature::ExplicitOctalLiterals, literal.span);
+            }
+        } else if let Some(creation) = node.downcast_ref::<FunctionClosureCreationExpression>() {
+            self.used(Feature::FirstClassCallables, creation.placeholder.ellipsis);
+        } else if let Some(creation) = node.downcast_ref::<MethodClosureCreationExpression>() {
+            self.used(Feature::FirstClassCallables, creation.placeholder.ellipsis);
+        } else if let Some(creation) = node.downcast_ref::<StaticMethodClosureCreationExpression>()
+        {
+            self.used(Feature::FirstClassCallables, creation.placeholder.ellipsis);
+        } else if let Some(creation) =
+            node.downcast_ref::<StaticVariableMethodClosureCreationExpression>()
+        {
+            self.used(Feature::FirstClassCallables, creation.placeholder.ellipsis);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The required version of `code`, and the features that decide it with the lines
+    /// and columns where they are used.
+    fn required(code: &str) -> (PhpVersion, Vec<String>) {
+        let program = crate::parse(code).unwrap();
+        let report = required_version(&program);
+
+        let deciding = report
+            .deciding()
+            .map(|(feature, span)| format!("{} at {}:{}", feature, span.line, span.column))
+            .collect();
+
+        (report.version, deciding)
+    }
+
+    #[test]
+    fn test_oldest_version() {
+        let (version, deciding) =
+            required("<?php\nfunction add(int $a, int $b): int { return $a + $b; }");
+
+        assert_eq!(version, PhpVersion::Php70);
+        assert!(deciding.is_empty());
+    }
+
+    #[test]
+    fn test_php_80() {
+        assert_eq!(
+            required(
+                "<?php
+class Point {
+    public function __construct(private ?int $x = null) {}
+    public function label(): string|int {
+        return match ($this->x) { null => 'origin', default => $this?->x };
+    }
+}"
+            ),
+            (
+                PhpVersion::Php80,
+                vec![
+                    "constructor property promotion at 3:33".to_string(),
+                    "union types at 4:30".to_string(),
+                    "`match` expressions at 5:16".to_string(),
+                    "the `?->` operator at 5:69".to_string(),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_php_81() {
+        assert_eq!(
+            required(
+                "<?php
+enum Suit: string {
+    case Hearts = 'H';
+}
+$from = Suit::from(...);"
+            ),
+            (
+                PhpVersion::Php81,
+                vec![
+                    "enums at 2:1".to_string(),
+                    "first-class callable syntax at 5:20".to_string(),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_php_82() {
+        assert_eq!(
+            required(
+                "<?php
+readonly class Money {
+    public function __construct(public (Countable&Iterator)|null $items) {}
+}"
+            ),
+            (
+                PhpVersion::Php82,
+                vec![
+                    "readonly classes at 2:1".to_string(),
+                    "disjunctive normal form types at 3:41".to_string(),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_php_83() {
+        let program = crate::parse(
+            "<?php\n$value = Status::{$name}->value ?? throw new Error();\nclass A { const int|string B = 1; }",
+        )
+        .unwrap();
+        let report = required_version(&program);
+
+        assert_eq!(report.version, PhpVersion::Php83);
+        assert_eq!(
+            report
+                .evidence
+                .iter()
+                .map(|(feature, span)| (*feature, span.line, span.column))
+                .collect::<Vec<_>>(),
+            vec![
+                (Feature::DynamicClassConstantFetch, 2, 18),
+                (Feature::ThrowExpressions, 2, 42),
+                (Feature::TypedClassConstants, 3, 17),
+                (Feature::UnionTypes, 3, 17),
+            ]
+        );
+    }
+}
@@ -1,4 +1,8 @@
 use schemars::JsonSchema;
+use serde::de;
+use serde::de::MapAccess;
+use serde::de::Visitor;
+use serde::ser::SerializeMap;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -11,7 +15,7 @@ use std::str::from_utf8;
 ///
 /// The Trunk lexer and parser work mainly with byte strings because
 /// valid PHP code is not required to be valid UTF-8.
-#[derive(PartialOrd, PartialEq, Eq, Clone, Hash)]
+#[derive(PartialOrd, Ord, PartialEq, Eq, Clone, Hash)]
 pub struct ByteString {
     pub bytes: Vec<u8>,
 }
@@ -28,18 +32,23 @@ impl Default for ByteString {
     }
 }
 
-impl std::fmt::Display for ByteString {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for &b in &self.bytes {
-            match b {
-                0 => write!(f, "\\0")?,
-                b'\n' | b'\r' | b'\t' => write!(f, "{}", b.escape_ascii())?,
-                0x01..=0x19 | 0x7f..=0xff => write!(f, "\\x{:02x}", b)?,
-                _ => write!(f, "{}", b as char)?,
-            }
+/// Write `bytes` as text, escaping those that aren't printable ASCII.
+pub(crate) fn write_escaped(f: &mut std::fmt::Formatter<'_>, bytes: &[u8]) -> std::fmt::Result {
+    for &b in bytes {
+        match b {
+            0 => write!(f, "\\0")?,
+            b'\n' | b'\r' | b'\t' => write!(f, "{}", b.escape_ascii())?,
+            0x01..=0x19 | 0x7f..=0xff => write!(f, "\\x{:02x}", b)?,
+            _ => write!(f, "{}", b as char)?,
         }
+    }
+
+    Ok(())
+}
 
-        Ok(())
+impl std::fmt::Display for ByteString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_escaped(f, &self.bytes)
     }
 }
 
@@ -54,25 +63,35 @@ impl std::str::FromStr for ByteString {
 impl std::fmt::Debug for ByteString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "\"")?;
-        for &b in &self.bytes {
-            match b {
-                0 => write!(f, "\\0")?,
-                b'\n' | b'\r' | b'\t' => write!(f, "{}", b.escape_ascii())?,
-                0x01..=0x19 | 0x7f..=0xff => write!(f, "\\x{:02x}", b)?,
-                _ => write!(f, "{}", b as char)?,
-            }
-        }
-        write!(f, "\"")?;
-        Ok(())
+        write_escaped(f, &self.bytes)?;
+        write!(f, "\"")
     }
 }
 
+/// Byte strings that are valid UTF-8 serialize as strings, and others as
+/// `{"bytes": [...]}`, so that every byte string deserializes to the bytes it was
+/// serialized from.
 impl Serialize for ByteString {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serialize_bytes(&self.bytes, serializer)
+    }
+}
+
+/// Serialize `bytes` like a [`ByteString`], for the other types that hold them.
+pub(crate) fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match from_utf8(bytes) {
+        Ok(string) => serializer.serialize_str(string),
+        Err(_) => {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry("bytes", bytes)?;
+            map.end()
+        }
     }
 }
 
@@ -81,8 +100,40 @@ impl<'de> Deserialize<'de> for ByteString {
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Ok(ByteString::new(s.into_bytes()))
+        deserializer.deserialize_any(ByteStringVisitor)
+    }
+}
+
+struct ByteStringVisitor;
+
+impl<'de> Visitor<'de> for ByteStringVisitor {
+    type Value = ByteString;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a string or a map of `bytes` to an array of bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<ByteString, E> {
+        Ok(ByteString::new(value.as_bytes().to_vec()))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<ByteString, E> {
+        Ok(ByteString::new(value.to_vec()))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ByteString, A::Error> {
+        let mut bytes = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if key != "bytes" {
+                return Err(de::Error::unknown_field(&key, &["bytes"]));
+            }
+
+            bytes = Some(map.next_value::<Vec<u8>>()?);
+        }
+
+        bytes
+            .map(ByteString::new)
+            .ok_or_else(|| de::Error::missing_field("bytes"))
     }
 }
 
@@ -91,11 +142,29 @@ impl JsonSchema for ByteString {
         "ByteString".to_string()
     }
 
-    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
-        schemars::schema::SchemaObject {
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let string = schemars::schema::SchemaObject {
             instance_type: Some(schemars::schema::InstanceType::String.into()),
             format: Some("byte-string".to_string()),
             ..Default::default()
+        };
+
+        let mut bytes = schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::Object.into()),
+            ..Default::default()
+        };
+        let object = bytes.object();
+        object
+            .properties
+            .insert("bytes".to_string(), gen.subschema_for::<Vec<u8>>());
+        object.required.insert("bytes".to_string());
+
+        schemars::schema::SchemaObject {
+            subschemas: Some(Box::new(schemars::schema::SubschemaValidation {
+                any_of: Some(vec![string.into(), bytes.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
         }
         .into()
     }
@@ -185,4 +254,19 @@ mod tests {
             r#""\x01\x10\x7f\xff""#
         );
     }
+
+    #[test]
+    fn test_byte_string_json() {
+        let text = ByteString::from("é\n");
+        let json = serde_json::to_string(&text).unwrap();
+        assert_eq!(json, r#""é\n""#);
+        assert_eq!(serde_json::from_str::<ByteString>(&json).unwrap(), text);
+
+        let latin1 = ByteString::from(b"caf\xe9");
+        let json = serde_json::to_string(&latin1).unwrap();
+        assert_eq!(json, r#"{"bytes":[99,97,102,233]}"#);
+        assert_eq!(serde_json::from_str::<ByteString>(&json).unwrap(), latin1);
+
+        assert!(serde_json::from_str::<ByteString>(r#"{"text":"a"}"#).is_err());
+    }
 }
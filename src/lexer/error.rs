@@ -4,6 +4,66 @@ use crate::lexer::token::Span;
 
 pub type SyntaxResult<T> = Result<T, SyntaxError>;
 
+/// A non-ASCII character that is easily mistaken for an ASCII one, usually found in
+/// code that has been pasted from a word processor or chat application.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct LookalikeCharacter {
+    pub bytes: &'static [u8],
+    pub name: &'static str,
+    pub replacement: &'static str,
+}
+
+const LOOKALIKE_CHARACTERS: [LookalikeCharacter; 7] = [
+    LookalikeCharacter {
+        bytes: b"\xc2\xa0",
+        name: "non-breaking space (U+00A0)",
+        replacement: " ",
+    },
+    LookalikeCharacter {
+        bytes: b"\xe2\x80\x8b",
+        name: "zero-width space (U+200B)",
+        replacement: "",
+    },
+    LookalikeCharacter {
+        bytes: b"\xef\xbb\xbf",
+        name: "zero-width no-break space (U+FEFF)",
+        replacement: "",
+    },
+    LookalikeCharacter {
+        bytes: b"\xe2\x80\x98",
+        name: "left single quotation mark (U+2018)",
+        replacement: "'",
+    },
+    LookalikeCharacter {
+        bytes: b"\xe2\x80\x99",
+        name: "right single quotation mark (U+2019)",
+        replacement: "'",
+    },
+    LookalikeCharacter {
+        bytes: b"\xe2\x80\x9c",
+        name: "left double quotation mark (U+201C)",
+        replacement: "\"",
+    },
+    LookalikeCharacter {
+        bytes: b"\xe2\x80\x9d",
+        name: "right double quotation mark (U+201D)",
+        replacement: "\"",
+    },
+];
+
+impl LookalikeCharacter {
+    /// Find the lookalike character at the start of `input`, if there is one.
+    pub fn find(input: &[u8]) -> Option<Self> {
+        LOOKALIKE_CHARACTERS
+            .into_iter()
+            .find(|character| input.starts_with(character.bytes))
+    }
+
+    pub fn length(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum SyntaxError {
     UnexpectedEndOfFile(Span),
@@ -17,6 +77,9 @@ pub enum SyntaxError {
     InvalidDocIndentation(Span),
     InvalidDocBodyIndentationLevel(usize, Span),
     UnrecognisedToken(u8, Span),
+    UnexpectedLookalikeCharacter(LookalikeCharacter, Span),
+    UnclosedString(Span),
+    UnfinishedDocStringStart(Span),
 }
 
 impl SyntaxError {
@@ -33,6 +96,9 @@ impl SyntaxError {
             Self::InvalidDocIndentation(span) => *span,
             Self::InvalidDocBodyIndentationLevel(_, span) => *span,
             Self::UnrecognisedToken(_, span) => *span,
+            Self::UnexpectedLookalikeCharacter(_, span) => *span,
+            Self::UnclosedString(span) => *span,
+            Self::UnfinishedDocStringStart(span) => *span,
         }
     }
 }
@@ -97,7 +163,22 @@ impl Display for SyntaxError {
                 token,
                 span.line,
                 span.column
-            )
+            ),
+            Self::UnexpectedLookalikeCharacter(character, span) => write!(
+                f,
+                "Syntax Error: unexpected {} on line {} column {}",
+                character.name, span.line, span.column
+            ),
+            Self::UnclosedString(span) => write!(
+                f,
+                "Syntax Error: unclosed string starting on line {} column {}",
+                span.line, span.column
+            ),
+            Self::UnfinishedDocStringStart(span) => write!(
+                f,
+                "Syntax Error: expected a line break after the heredoc label on line {} column {}",
+                span.line, span.column
+            ),
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::ident;
 use crate::ident_start;
 use crate::lexer::byte_string::ByteString;
+use crate::lexer::error::LookalikeCharacter;
 use crate::lexer::error::SyntaxError;
 use crate::lexer::error::SyntaxResult;
 use crate::lexer::state::source::Source;
@@ -9,8 +10,10 @@ use crate::lexer::state::State;
 use crate::lexer::token::DocStringIndentationKind;
 use crate::lexer::token::DocStringKind;
 use crate::lexer::token::OpenTagKind;
+use crate::lexer::token::Span;
 use crate::lexer::token::Token;
 use crate::lexer::token::TokenKind;
+use crate::lexer::token::TokenValue;
 
 pub mod byte_string;
 pub mod error;
@@ -29,9 +32,43 @@ impl Lexer {
         Self {}
     }
 
-    pub fn tokenize<B: ?Sized + AsRef<[u8]>>(&self, input: &B) -> SyntaxResult<Vec<Token>> {
+    pub fn tokenize<'a, B: ?Sized + AsRef<[u8]>>(
+        &self,
+        input: &'a B,
+    ) -> SyntaxResult<Vec<Token<'a>>> {
+        let state = State::new(Source::new(input.as_ref()));
+
+        self.tokenize_from(state)
+    }
+
+    /// Tokenize a snippet of PHP code that isn't preceded by an opening tag, such as
+    /// a single expression.
+    pub fn tokenize_scripting<'a, B: ?Sized + AsRef<[u8]>>(
+        &self,
+        input: &'a B,
+    ) -> SyntaxResult<Vec<Token<'a>>> {
         let mut state = State::new(Source::new(input.as_ref()));
+        state.replace(StackFrame::Scripting);
+
+        self.tokenize_from(state)
+    }
+
+    /// Tokenize PHP code that isn't preceded by an opening tag, and that starts at
+    /// `start` in a file, such as the end of a file after `__halt_compiler();`.
+    pub fn tokenize_scripting_at<'a, B: ?Sized + AsRef<[u8]>>(
+        &self,
+        input: &'a B,
+        start: Span,
+    ) -> SyntaxResult<Vec<Token<'a>>> {
+        let mut state = State::new(Source::starting_at(input.as_ref(), start));
+        state.replace(StackFrame::Scripting);
+
+        self.tokenize_from(state)
+    }
+
+    fn tokenize_from<'a>(&self, mut state: State<'a>) -> SyntaxResult<Vec<Token<'a>>> {
         let mut tokens = Vec::new();
+        let mut measured = 0;
 
         while !state.source.eof() {
             match state.frame()? {
@@ -98,12 +135,17 @@ impl Lexer {
                     tokens.push(self.var_offset(&mut state)?);
                 }
             }
+
+            measure(&mut tokens[measured..], state.source.span().position);
+            measured = tokens.len();
         }
 
+        measure(&mut tokens[measured..], state.source.span().position);
+
         tokens.push(Token {
             kind: TokenKind::Eof,
             span: state.source.span(),
-            value: ByteString::default(),
+            value: TokenValue::default(),
         });
 
         Ok(tokens)
@@ -115,21 +157,12 @@ impl Lexer {
         }
     }
 
-    fn read_and_skip_whitespace(&self, state: &mut State) -> Vec<u8> {
-        let mut buffer = Vec::new();
-        while let Some(true) = state.source.current().map(|u: &u8| u.is_ascii_whitespace()) {
-            buffer.push(*state.source.current().unwrap());
-            state.source.next();
-        }
-        buffer
-    }
-
-    fn initial(&self, state: &mut State, tokens: &mut Vec<Token>) -> SyntaxResult<()> {
+    fn initial<'a>(&self, state: &mut State<'a>, tokens: &mut Vec<Token<'a>>) -> SyntaxResult<()> {
         let inline_span = state.source.span();
-        let mut buffer = Vec::new();
-        while let Some(char) = state.source.current() {
+        while state.source.current().is_some() {
             if state.source.at_case_insensitive(b"<?php", 5) {
                 let tag_span = state.source.span();
+                let buffer = state.source.read_since(inline_span);
 
                 let tag = state.source.read_and_skip(5);
                 state.replace(StackFrame::Scripting);
@@ -151,6 +184,7 @@ impl Lexer {
                 return Ok(());
             } else if state.source.at_case_insensitive(b"<?=", 3) {
                 let tag_span = state.source.span();
+                let buffer = state.source.read_since(inline_span);
 
                 state.source.skip(3);
                 state.replace(StackFrame::Scripting);
@@ -172,6 +206,7 @@ impl Lexer {
                 return Ok(());
             } else if state.source.at_case_insensitive(b"<?", 2) {
                 let tag_span = state.source.span();
+                let buffer = state.source.read_since(inline_span);
 
                 state.source.skip(2);
                 state.replace(StackFrame::Scripting);
@@ -194,21 +229,25 @@ impl Lexer {
             }
 
             state.source.next();
-            buffer.push(*char);
         }
 
         tokens.push(Token {
             kind: TokenKind::InlineHtml,
             span: inline_span,
-            value: buffer.into(),
+            value: state.source.read_since(inline_span).into(),
         });
 
         Ok(())
     }
 
-    fn scripting(&self, state: &mut State) -> SyntaxResult<Token> {
+    fn scripting<'a>(&self, state: &mut State<'a>) -> SyntaxResult<Token<'a>> {
         let span = state.source.span();
-        let (kind, value): (TokenKind, ByteString) = match state.source.read(3) {
+
+        if let Some(character) = LookalikeCharacter::find(state.source.read(3)) {
+            return Err(SyntaxError::UnexpectedLookalikeCharacter(character, span));
+        }
+
+        let (kind, value): (TokenKind, TokenValue) = match state.source.read(3) {
             [b'!', b'=', b'='] => {
                 state.source.skip(3);
 
@@ -301,22 +340,33 @@ impl Lexer {
             }
             // Single quoted string.
             [b'\'', ..] => {
-                let opening = state.source.read_and_skip(1);
-                self.tokenize_single_quote_string(state, opening)?
+                state.source.next();
+                self.tokenize_single_quote_string(state, span)?
             }
             [b'b' | b'B', b'\'', ..] => {
-                let opening = state.source.read_and_skip(2);
-                self.tokenize_single_quote_string(state, opening)?
+                state.source.skip(2);
+                self.tokenize_single_quote_string(state, span)?
             }
             [b'"', ..] => {
-                let opening = state.source.read_and_skip(1);
-                self.tokenize_double_quote_string(state, opening)?
+                state.source.next();
+                self.tokenize_double_quote_string(state, span)?
             }
             [b'b' | b'B', b'"', ..] => {
-                let opening = state.source.read_and_skip(2);
-                self.tokenize_double_quote_string(state, opening)?
+                state.source.skip(2);
+                self.tokenize_double_quote_string(state, span)?
+            }
+            [b'$', ident_start!(), ..] => {
+                state.source.next();
+                while let Some(ident!()) = state.source.current() {
+                    if LookalikeCharacter::find(state.source.read(3)).is_some() {
+                        break;
+                    }
+
+                    state.source.next();
+                }
+
+                (TokenKind::Variable, state.source.read_since(span).into())
             }
-            [b'$', ident_start!(), ..] => self.tokenize_variable(state),
             [b'$', ..] => {
                 state.source.next();
                 (TokenKind::Dollar, b"$".into())
@@ -335,16 +385,6 @@ impl Lexer {
                 state.source.next();
 
                 match self.scripting(state)? {
-                    Token {
-                        kind: TokenKind::Identifier | TokenKind::QualifiedIdentifier,
-                        value,
-                        ..
-                    } => {
-                        let mut bytes = value;
-                        bytes.insert(0, b'\\');
-
-                        (TokenKind::FullyQualifiedIdentifier, bytes)
-                    }
                     Token {
                         kind: TokenKind::True,
                         ..
@@ -357,7 +397,14 @@ impl Lexer {
                         kind: TokenKind::Null,
                         ..
                     } => (TokenKind::FullyQualifiedIdentifier, b"\\null".into()),
-                    s => unreachable!("{:?}", s),
+                    // Names, and keywords, e.g. `\isset`, which are names once they are
+                    // qualified. Only the name is read, e.g. not the `();` that follows
+                    // `__halt_compiler`.
+                    Token { value, .. } => {
+                        let name = &state.source.read_since(span)[..value.len() + 1];
+
+                        (TokenKind::FullyQualifiedIdentifier, name.into())
+                    }
                 }
             }
             [b'\\', ..] => {
@@ -366,18 +413,15 @@ impl Lexer {
             }
             [b'/', b'*', ..] => {
                 state.source.next();
-                let mut buffer = vec![b'/'];
 
                 loop {
                     match state.source.read(2) {
                         [b'*', b'/'] => {
                             state.source.skip(2);
-                            buffer.extend_from_slice(b"*/");
                             break;
                         }
-                        &[t, ..] => {
+                        [_, ..] => {
                             state.source.next();
-                            buffer.push(t);
                         }
                         _ => {
                             break;
@@ -385,6 +429,7 @@ impl Lexer {
                     }
                 }
 
+                let buffer = state.source.read_since(span);
                 if buffer.starts_with(b"/**") {
                     (TokenKind::DocumentComment, buffer.into())
                 } else {
@@ -396,25 +441,23 @@ impl Lexer {
                 (TokenKind::Attribute, b"#[".into())
             }
             [ch @ b'/', b'/', ..] | [ch @ b'#', ..] => {
-                let mut buffer = if *ch == b'/' {
+                if *ch == b'/' {
                     state.source.skip(2);
-                    b"//".to_vec()
                 } else {
                     state.source.next();
-                    b"#".to_vec()
-                };
+                }
 
                 while let Some(c) = state.source.current() {
-                    if *c == b'\n' {
-                        state.source.next();
+                    if *c == b'\n' || state.source.read(2) == [b'?', b'>'] {
                         break;
                     }
 
-                    if state.source.read(2) == [b'?', b'>'] {
-                        break;
-                    }
+                    state.source.next();
+                }
 
-                    buffer.push(*c);
+                // The line break ends the comment, but isn't part of it.
+                let buffer = state.source.read_since(span);
+                if state.source.current() == Some(&b'\n') {
                     state.source.next();
                 }
 
@@ -451,17 +494,14 @@ impl Lexer {
             }
             [b'<', b'<', b'<'] => {
                 state.source.skip(3);
-                let mut buffer = b"<<<".to_vec();
-                buffer.extend(self.read_and_skip_whitespace(state));
+                self.skip_whitespace(state);
 
                 let doc_string_kind = match state.source.read(1) {
                     [b'\''] => {
-                        buffer.push(b'\'');
                         state.source.next();
                         DocStringKind::Nowdoc
                     }
                     [b'"'] => {
-                        buffer.push(b'"');
                         state.source.next();
                         DocStringKind::Heredoc
                     }
@@ -483,35 +523,30 @@ impl Lexer {
                     }
                 };
 
-                buffer.extend_from_slice(&label);
-
                 if doc_string_kind == DocStringKind::Nowdoc {
                     match state.source.current() {
                         Some(b'\'') => {
-                            buffer.push(b'\'');
                             state.source.next();
                         }
-                        _ => {
-                            // TODO(azjezz) this is most likely a bug, what if current is none?
-                            return Err(SyntaxError::UnexpectedCharacter(
-                                *state.source.current().unwrap(),
-                                state.source.span(),
-                            ));
+                        Some(c) => {
+                            return Err(SyntaxError::UnexpectedCharacter(*c, state.source.span()));
+                        }
+                        None => {
+                            return Err(SyntaxError::UnexpectedEndOfFile(state.source.span()));
                         }
                     };
                 } else if let Some(b'"') = state.source.current() {
-                    buffer.push(b'"');
                     state.source.next();
                 }
 
+                // Without a line break after the label this isn't a doc string, and
+                // `$a <<<EOT` is most likely a mistyped left shift.
                 if !matches!(state.source.current(), Some(b'\n')) {
-                    return Err(SyntaxError::UnexpectedCharacter(
-                        *state.source.current().unwrap(),
-                        state.source.span(),
-                    ));
+                    return Err(SyntaxError::UnfinishedDocStringStart(span));
                 }
 
-                state.source.next();
+                // The line break is left in the source so that the doc string's body
+                // can check for the closing label on the very first line.
                 state.replace(StackFrame::DocString(
                     doc_string_kind.clone(),
                     label.clone(),
@@ -519,7 +554,10 @@ impl Lexer {
                     0,
                 ));
 
-                (TokenKind::StartDocString(doc_string_kind), buffer.into())
+                (
+                    TokenKind::StartDocString(doc_string_kind),
+                    state.source.read_since(span).into(),
+                )
             }
             [b'*', b'*', ..] => {
                 state.source.skip(2);
@@ -560,138 +598,134 @@ impl Lexer {
             }
             [b'}', ..] => {
                 state.source.next();
-                state.exit();
+                // A stray `}` is left to the parser, which reports it.
+                if state.stack.len() > 1 {
+                    state.exit();
+                }
                 (TokenKind::RightBrace, b"}".into())
             }
             [b'(', ..] => {
                 state.source.next();
-                let mut buffer = b"(".to_vec();
-
-                // Inlined so we can add whitespace to the buffer.
-                while let Some(true) = state.source.current().map(|u: &u8| u.is_ascii_whitespace())
-                {
-                    buffer.push(*state.source.current().unwrap());
-                    state.source.next();
-                }
+                self.skip_whitespace(state);
 
                 if state.source.at_case_insensitive(b"int", 3) {
                     if state.source.at_case_insensitive(b"integer", 7)
                         && state.source.peek_ignoring_whitespace(7, 1) == [b')']
                     {
-                        buffer.extend(state.source.read_and_skip(7));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(7);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::IntegerCast, buffer.into())
+                        (TokenKind::IntegerCast, state.source.read_since(span).into())
                     } else if state.source.peek_ignoring_whitespace(3, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(3));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(3);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::IntCast, buffer.into())
+                        (TokenKind::IntCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"bool", 4) {
                     if state.source.at_case_insensitive(b"boolean", 7)
                         && state.source.peek_ignoring_whitespace(7, 1) == [b')']
                     {
-                        buffer.extend(state.source.read_and_skip(7));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(7);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::BooleanCast, buffer.into())
+                        (TokenKind::BooleanCast, state.source.read_since(span).into())
                     } else if state.source.peek_ignoring_whitespace(4, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(4));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(4);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::BoolCast, buffer.into())
+                        (TokenKind::BoolCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"float", 5) {
                     if state.source.peek_ignoring_whitespace(5, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(5));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(5);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::FloatCast, buffer.into())
+                        (TokenKind::FloatCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"double", 6) {
                     if state.source.peek_ignoring_whitespace(6, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(6));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(6);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::DoubleCast, buffer.into())
+                        (TokenKind::DoubleCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"real", 4) {
                     if state.source.peek_ignoring_whitespace(4, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(4));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(4);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::RealCast, buffer.into())
+                        (TokenKind::RealCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"string", 6) {
                     if state.source.peek_ignoring_whitespace(6, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(6));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(6);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::StringCast, buffer.into())
+                        (TokenKind::StringCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"binary", 6) {
                     if state.source.peek_ignoring_whitespace(6, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(6));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(6);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::BinaryCast, buffer.into())
+                        (TokenKind::BinaryCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"array", 5) {
                     if state.source.peek_ignoring_whitespace(5, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(5));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(5);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::ArrayCast, buffer.into())
+                        (TokenKind::ArrayCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"object", 6) {
                     if state.source.peek_ignoring_whitespace(6, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(6));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(6);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::ObjectCast, buffer.into())
+                        (TokenKind::ObjectCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else if state.source.at_case_insensitive(b"unset", 5) {
                     if state.source.peek_ignoring_whitespace(5, 1) == [b')'] {
-                        buffer.extend(state.source.read_and_skip(5));
-                        buffer.extend(self.read_and_skip_whitespace(state));
-                        buffer.extend(state.source.read_and_skip(1));
+                        state.source.skip(5);
+                        self.skip_whitespace(state);
+                        state.source.next();
 
-                        (TokenKind::UnsetCast, buffer.into())
+                        (TokenKind::UnsetCast, state.source.read_since(span).into())
                     } else {
-                        (TokenKind::LeftParen, buffer.into())
+                        (TokenKind::LeftParen, state.source.read_since(span).into())
                     }
                 } else {
-                    (TokenKind::LeftParen, buffer.into())
+                    (TokenKind::LeftParen, state.source.read_since(span).into())
                 }
             }
             [b')', ..] => {
@@ -790,15 +824,19 @@ impl Lexer {
                 state.source.next();
                 (TokenKind::BitwiseNot, b"~".into())
             }
-            [b @ ident_start!(), ..] => {
+            [ident_start!(), ..] => {
                 state.source.next();
                 let mut qualified = false;
                 let mut last_was_slash = false;
 
-                let mut buffer = vec![*b];
                 while let Some(next @ ident!() | next @ b'\\') = state.source.current() {
+                    // `foo\u{a0}bar` is almost certainly a pasted non-breaking space rather than
+                    // a single identifier, so stop here and let it be reported on its own.
+                    if LookalikeCharacter::find(state.source.read(3)).is_some() {
+                        break;
+                    }
+
                     if matches!(next, ident!()) {
-                        buffer.push(*next);
                         state.source.next();
                         last_was_slash = false;
                         continue;
@@ -807,7 +845,6 @@ impl Lexer {
                     if *next == b'\\' && !last_was_slash {
                         qualified = true;
                         last_was_slash = true;
-                        buffer.push(*next);
                         state.source.next();
                         continue;
                     }
@@ -815,10 +852,11 @@ impl Lexer {
                     break;
                 }
 
+                let buffer = state.source.read_since(span);
                 if qualified {
                     (TokenKind::QualifiedIdentifier, buffer.into())
                 } else {
-                    let kind = identifier_to_keyword(&buffer).unwrap_or(TokenKind::Identifier);
+                    let kind = identifier_to_keyword(buffer).unwrap_or(TokenKind::Identifier);
 
                     if kind == TokenKind::HaltCompiler {
                         match state.source.read(3) {
@@ -847,10 +885,17 @@ impl Lexer {
         Ok(Token { kind, span, value })
     }
 
-    fn double_quote(&self, state: &mut State, tokens: &mut Vec<Token>) -> SyntaxResult<()> {
+    fn double_quote<'a>(
+        &self,
+        state: &mut State<'a>,
+        tokens: &mut Vec<Token<'a>>,
+    ) -> SyntaxResult<()> {
         let span = state.source.span();
         let mut buffer = Vec::new();
+        let mut start;
         let (kind, value) = loop {
+            start = state.source.span();
+
             match state.source.read(3) {
                 [b'$', b'{', ..] => {
                     state.source.skip(2);
@@ -989,14 +1034,25 @@ impl Lexer {
             })
         }
 
-        tokens.push(Token { kind, span, value });
+        tokens.push(Token {
+            kind,
+            span: start,
+            value,
+        });
         Ok(())
     }
 
-    fn shell_exec(&self, state: &mut State, tokens: &mut Vec<Token>) -> SyntaxResult<()> {
+    fn shell_exec<'a>(
+        &self,
+        state: &mut State<'a>,
+        tokens: &mut Vec<Token<'a>>,
+    ) -> SyntaxResult<()> {
         let span = state.source.span();
         let mut buffer = Vec::new();
+        let mut start;
         let (kind, value) = loop {
+            start = state.source.span();
+
             match state.source.read(2) {
                 [b'$', b'{'] => {
                     state.source.skip(2);
@@ -1044,21 +1100,28 @@ impl Lexer {
             })
         }
 
-        tokens.push(Token { kind, span, value });
+        tokens.push(Token {
+            kind,
+            span: start,
+            value,
+        });
 
         Ok(())
     }
 
-    fn heredoc(
+    fn heredoc<'a>(
         &self,
-        state: &mut State,
-        tokens: &mut Vec<Token>,
+        state: &mut State<'a>,
+        tokens: &mut Vec<Token<'a>>,
         label: ByteString,
     ) -> SyntaxResult<()> {
         let span = state.source.span();
         let mut buffer: Vec<u8> = Vec::new();
 
+        let mut start;
         let (kind, value) = loop {
+            start = state.source.span();
+
             match state.source.read(3) {
                 [b'$', b'{', ..] => {
                     state.source.skip(2);
@@ -1183,11 +1246,12 @@ impl Lexer {
 
                     // Check if we can see the closing label right here.
                     if state.source.at(&label, label.len()) {
+                        start = state.source.span();
                         state.source.skip(label.len());
                         state.replace(StackFrame::Scripting);
                         break (
                             TokenKind::EndDocString(DocStringIndentationKind::None, 0),
-                            label,
+                            label.into(),
                         );
                     }
 
@@ -1235,11 +1299,12 @@ impl Lexer {
                         // If we get here, only 1 type of indentation was found. We can move
                         // the process along by reading over the label and breaking out
                         // with the EndHeredoc token, storing the kind and amount of whitespace.
+                        start = state.source.span();
                         state.source.skip(label.len());
                         state.replace(StackFrame::Scripting);
                         break (
                             TokenKind::EndDocString(whitespace_kind, whitespace_amount),
-                            label,
+                            label.into(),
                         );
                     } else {
                         // We didn't find the label. The buffer still needs to know about
@@ -1263,6 +1328,18 @@ impl Lexer {
             }
         };
 
+        // The line break following the opening label is not part of the doc string.
+        if matches!(
+            tokens.last(),
+            Some(Token {
+                kind: TokenKind::StartDocString(_),
+                ..
+            })
+        ) && buffer.first() == Some(&b'\n')
+        {
+            buffer.remove(0);
+        }
+
         // Any trailing line breaks should be removed from the final heredoc.
         if buffer.last() == Some(&b'\n') {
             buffer.pop();
@@ -1276,20 +1353,25 @@ impl Lexer {
             })
         }
 
-        tokens.push(Token { kind, span, value });
+        tokens.push(Token {
+            kind,
+            span: start,
+            value,
+        });
 
         Ok(())
     }
 
-    fn nowdoc(
+    fn nowdoc<'a>(
         &self,
-        state: &mut State,
-        tokens: &mut Vec<Token>,
+        state: &mut State<'a>,
+        tokens: &mut Vec<Token<'a>>,
         label: ByteString,
     ) -> SyntaxResult<()> {
         let span = state.source.span();
         let mut buffer: Vec<u8> = Vec::new();
 
+        let start;
         let (kind, value) = loop {
             match state.source.read(3) {
                 // If we find a new-line, we can start to check if we can see the EndHeredoc token.
@@ -1299,11 +1381,12 @@ impl Lexer {
 
                     // Check if we can see the closing label right here.
                     if state.source.at(&label, label.len()) {
+                        start = state.source.span();
                         state.source.skip(label.len());
                         state.replace(StackFrame::Scripting);
                         break (
                             TokenKind::EndDocString(DocStringIndentationKind::None, 0),
-                            label,
+                            label.into(),
                         );
                     }
 
@@ -1351,11 +1434,12 @@ impl Lexer {
                         // If we get here, only 1 type of indentation was found. We can move
                         // the process along by reading over the label and breaking out
                         // with the EndHeredoc token, storing the kind and amount of whitespace.
+                        start = state.source.span();
                         state.source.skip(label.len());
                         state.replace(StackFrame::Scripting);
                         break (
                             TokenKind::EndDocString(whitespace_kind, whitespace_amount),
-                            label,
+                            label.into(),
                         );
                     } else {
                         // We didn't find the label. The buffer still needs to know about
@@ -1379,6 +1463,18 @@ impl Lexer {
             }
         };
 
+        // The line break following the opening label is not part of the doc string.
+        if matches!(
+            tokens.last(),
+            Some(Token {
+                kind: TokenKind::StartDocString(_),
+                ..
+            })
+        ) && buffer.first() == Some(&b'\n')
+        {
+            buffer.remove(0);
+        }
+
         // Any trailing line breaks should be removed from the final heredoc.
         if buffer.last() == Some(&b'\n') {
             buffer.pop();
@@ -1392,17 +1488,20 @@ impl Lexer {
             })
         }
 
-        tokens.push(Token { kind, span, value });
+        tokens.push(Token {
+            kind,
+            span: start,
+            value,
+        });
 
         Ok(())
     }
 
-    fn looking_for_varname(&self, state: &mut State) -> SyntaxResult<Option<Token>> {
+    fn looking_for_varname<'a>(&self, state: &mut State<'a>) -> SyntaxResult<Option<Token<'a>>> {
         let identifier = self.peek_identifier(state);
 
         if let Some(ident) = identifier {
             if let [b'[' | b'}'] = state.source.peek(ident.len(), 1) {
-                let ident = ident.to_vec();
                 let span = state.source.span();
                 state.source.skip(ident.len());
                 state.replace(StackFrame::Scripting);
@@ -1419,7 +1518,7 @@ impl Lexer {
         Ok(None)
     }
 
-    fn looking_for_property(&self, state: &mut State) -> SyntaxResult<Token> {
+    fn looking_for_property<'a>(&self, state: &mut State<'a>) -> SyntaxResult<Token<'a>> {
         let span = state.source.span();
         let (kind, value) = match state.source.read(3) {
             [b'?', b'-', b'>'] => {
@@ -1442,7 +1541,7 @@ impl Lexer {
         Ok(Token { kind, span, value })
     }
 
-    fn var_offset(&self, state: &mut State) -> SyntaxResult<Token> {
+    fn var_offset<'a>(&self, state: &mut State<'a>) -> SyntaxResult<Token<'a>> {
         let span = state.source.span();
         let (kind, value) = match state.source.read(2) {
             [b'$', ident_start!()] => self.tokenize_variable(state),
@@ -1475,47 +1574,68 @@ impl Lexer {
         Ok(Token { kind, span, value })
     }
 
-    fn tokenize_single_quote_string(
+    fn tokenize_single_quote_string<'a>(
         &self,
-        state: &mut State,
-        opening: &[u8],
-    ) -> SyntaxResult<(TokenKind, ByteString)> {
-        let mut buffer = opening.to_vec();
+        state: &mut State<'a>,
+        span: Span,
+    ) -> SyntaxResult<(TokenKind, TokenValue<'a>)> {
+        // The string is only copied once an escape is found in it.
+        let mut unescaped: Option<Vec<u8>> = None;
 
         loop {
             match state.source.read(2) {
                 [b'\'', ..] => {
-                    buffer.push(b'\'');
                     state.source.next();
                     break;
                 }
                 &[b'\\', b @ b'\'' | b @ b'\\'] => {
+                    let buffer =
+                        unescaped.get_or_insert_with(|| state.source.read_since(span).to_vec());
                     state.source.skip(2);
                     buffer.push(b);
                 }
                 &[b, ..] => {
                     state.source.next();
-                    buffer.push(b);
+                    if let Some(buffer) = &mut unescaped {
+                        buffer.push(b);
+                    }
                 }
-                [] => return Err(SyntaxError::UnexpectedEndOfFile(state.source.span())),
+                [] => return Err(SyntaxError::UnclosedString(span)),
             }
         }
 
-        Ok((TokenKind::LiteralString, buffer.into()))
+        let value = match unescaped {
+            Some(mut buffer) => {
+                buffer.push(b'\'');
+                buffer.into()
+            }
+            None => state.source.read_since(span).into(),
+        };
+
+        Ok((TokenKind::LiteralString, value))
     }
 
-    fn tokenize_double_quote_string(
+    fn tokenize_double_quote_string<'a>(
         &self,
-        state: &mut State,
-        opening: &[u8],
-    ) -> SyntaxResult<(TokenKind, ByteString)> {
-        let mut buffer = opening.to_vec();
+        state: &mut State<'a>,
+        span: Span,
+    ) -> SyntaxResult<(TokenKind, TokenValue<'a>)> {
+        // The string is only copied once an escape may be found in it.
+        let mut buffer = Vec::new();
+        let mut unescaped = false;
 
         let constant = loop {
+            if !unescaped && state.source.current() == Some(&b'\\') {
+                unescaped = true;
+                buffer.extend_from_slice(state.source.read_since(span));
+            }
+
             match state.source.read(3) {
                 [b'"', ..] => {
-                    buffer.push(b'"');
                     state.source.next();
+                    if unescaped {
+                        buffer.push(b'"');
+                    }
                     break true;
                 }
                 &[b'\\', b @ (b'"' | b'\\' | b'$'), ..] => {
@@ -1615,21 +1735,29 @@ impl Lexer {
                 }
                 &[b, ..] => {
                     state.source.next();
-                    buffer.push(b);
+                    if unescaped {
+                        buffer.push(b);
+                    }
                 }
-                [] => return Err(SyntaxError::UnexpectedEndOfFile(state.source.span())),
+                [] => return Err(SyntaxError::UnclosedString(span)),
             }
         };
 
+        let value = if unescaped {
+            buffer.into()
+        } else {
+            state.source.read_since(span).into()
+        };
+
         Ok(if constant {
-            (TokenKind::LiteralString, buffer.into())
+            (TokenKind::LiteralString, value)
         } else {
             state.replace(StackFrame::DoubleQuote);
-            (TokenKind::StringPart, buffer.into())
+            (TokenKind::StringPart, value)
         })
     }
 
-    fn peek_identifier<'a>(&'a self, state: &'a State) -> Option<&'a [u8]> {
+    fn peek_identifier<'a>(&self, state: &State<'a>) -> Option<&'a [u8]> {
         let mut size = 0;
 
         if let [ident_start!()] = state.source.read(1) {
@@ -1644,20 +1772,24 @@ impl Lexer {
         }
     }
 
-    fn consume_identifier(&self, state: &mut State) -> Vec<u8> {
-        let ident = self.peek_identifier(state).unwrap().to_vec();
+    fn consume_identifier<'a>(&self, state: &mut State<'a>) -> &'a [u8] {
+        let ident = self.peek_identifier(state).unwrap();
         state.source.skip(ident.len());
 
         ident
     }
 
-    fn tokenize_variable(&self, state: &mut State) -> (TokenKind, ByteString) {
-        let mut var = state.source.read_and_skip(1).to_vec();
-        var.extend(self.consume_identifier(state));
-        (TokenKind::Variable, var.into())
+    fn tokenize_variable<'a>(&self, state: &mut State<'a>) -> (TokenKind, TokenValue<'a>) {
+        let span = state.source.span();
+        state.source.next();
+        self.consume_identifier(state);
+        (TokenKind::Variable, state.source.read_since(span).into())
     }
 
-    fn tokenize_number(&self, state: &mut State) -> SyntaxResult<(TokenKind, ByteString)> {
+    fn tokenize_number<'a>(
+        &self,
+        state: &mut State<'a>,
+    ) -> SyntaxResult<(TokenKind, TokenValue<'a>)> {
         let mut buffer = Vec::new();
 
         let (base, kind) = match state.source.read(2) {
@@ -1769,10 +1901,22 @@ impl Lexer {
     }
 }
 
+/// Give the tokens that were read in one go their lengths: they follow one another,
+/// and the last of them ends at `end`.
+fn measure(tokens: &mut [Token], end: usize) {
+    for index in 0..tokens.len() {
+        let next = tokens
+            .get(index + 1)
+            .map_or(end, |token| token.span.position);
+
+        tokens[index].span.length = next.saturating_sub(tokens[index].span.position);
+    }
+}
+
 // Parses an integer literal in the given base and converts errors to SyntaxError.
 // It returns a float token instead on overflow.
-fn parse_int(buffer: &[u8]) -> SyntaxResult<(TokenKind, ByteString)> {
-    Ok((TokenKind::LiteralInteger, buffer.into()))
+fn parse_int(buffer: &[u8]) -> SyntaxResult<(TokenKind, TokenValue<'static>)> {
+    Ok((TokenKind::LiteralInteger, buffer.to_vec().into()))
 }
 
 #[inline(always)]
@@ -1875,3 +2019,69 @@ enum NumberKind {
     IntOrFloat,
     OctalOrFloat,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The kinds and values of the tokens of `code`, up to the end of the file.
+    fn tokens(code: &str) -> Vec<(TokenKind, String)> {
+        Lexer::new()
+            .tokenize(code)
+            .unwrap()
+            .into_iter()
+            .take_while(|token| token.kind != TokenKind::Eof)
+            .map(|token| (token.kind, token.value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_close_tag_ends_single_line_comments() {
+        for (comment, kind) in [
+            ("// note ", TokenKind::SingleLineComment),
+            ("# note ", TokenKind::HashMarkComment),
+        ] {
+            assert_eq!(
+                tokens(&format!("<?php {}?><p>{}</p>", comment, comment)),
+                vec![
+                    (TokenKind::OpenTag(OpenTagKind::Full), "<?php".to_string()),
+                    (kind, comment.to_string()),
+                    (TokenKind::CloseTag, "?>".to_string()),
+                    (TokenKind::InlineHtml, format!("<p>{}</p>", comment)),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_lengths() {
+        let code = "<?php $a = 1_000 . 'b\\'c' . \"d{$e}\" ?>\n<p>";
+        let tokens = Lexer::new().tokenize(code).unwrap();
+
+        let texts: Vec<&str> = tokens
+            .iter()
+            .map(|token| &code[token.span.position..token.span.end()])
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "<?php", "$a", "=", "1_000", ".", "'b\\'c'", ".", "\"d", "{", "$e", "}", "\"",
+                "?>", "\n<p>", ""
+            ]
+        );
+    }
+
+    #[test]
+    fn test_close_tag_is_text_in_block_comments() {
+        assert_eq!(
+            tokens("<?php /* ?><p> */ echo 1;"),
+            vec![
+                (TokenKind::OpenTag(OpenTagKind::Full), "<?php".to_string()),
+                (TokenKind::MultiLineComment, "/* ?><p> */".to_string()),
+                (TokenKind::Echo, "echo".to_string()),
+                (TokenKind::LiteralInteger, "1".to_string()),
+                (TokenKind::SemiColon, ";".to_string()),
+            ]
+        );
+    }
+}
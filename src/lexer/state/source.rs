@@ -5,6 +5,8 @@ pub struct Source<'a> {
     input: &'a [u8],
     length: usize,
     span: Span,
+    /// Where the input starts, when it is only the end of a file.
+    origin: Span,
 }
 
 impl<'a> Source<'a> {
@@ -16,11 +18,30 @@ impl<'a> Source<'a> {
             input,
             length,
             span: Span::new(1, 1, 0),
+            origin: Span::new(1, 1, 0),
+        }
+    }
+
+    /// A source for the end of a file, that starts at `origin` in the file.
+    pub fn starting_at(input: &'a [u8], origin: Span) -> Self {
+        Self {
+            origin,
+            ..Self::new(input)
         }
     }
 
     pub const fn span(&self) -> Span {
-        self.span
+        let column = if self.span.line == 1 {
+            self.span.column + self.origin.column - 1
+        } else {
+            self.span.column
+        };
+
+        Span::new(
+            self.span.line + self.origin.line - 1,
+            column,
+            self.span.position + self.origin.position,
+        )
     }
 
     pub const fn eof(&self) -> bool {
@@ -78,6 +99,14 @@ impl<'a> Source<'a> {
         })..]
     }
 
+    /// The input from `start`, a span that was read earlier, up to the current one.
+    pub fn read_since(&self, start: Span) -> &'a [u8] {
+        let from = start.position - self.origin.position;
+        let until = self.span.position.min(self.length);
+
+        &self.input[from.min(until)..until]
+    }
+
     pub fn at(&self, search: &[u8], len: usize) -> bool {
         self.read(len) == search
     }
@@ -2,8 +2,8 @@ use crate::lexer::token::Token;
 use crate::lexer::token::TokenKind;
 
 use crate::parser::ast::comments::Comment;
-use crate::parser::ast::comments::CommentFormat;
 use crate::parser::ast::comments::CommentGroup;
+use crate::parser::ast::comments::DocRef;
 
 /// Token stream.
 ///
@@ -57,15 +57,15 @@ use crate::parser::ast::comments::CommentGroup;
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TokenStream<'a> {
-    tokens: &'a [Token],
+    tokens: &'a [Token<'a>],
     length: usize,
-    comments: Vec<&'a Token>,
+    comments: Vec<&'a Token<'a>>,
     cursor: usize,
 }
 
 /// Token stream.
 impl<'a> TokenStream<'a> {
-    pub fn new(tokens: &'a [Token]) -> TokenStream {
+    pub fn new(tokens: &'a [Token<'a>]) -> TokenStream<'a> {
         let length = tokens.len();
 
         let mut stream = TokenStream {
@@ -89,7 +89,7 @@ impl<'a> TokenStream<'a> {
     }
 
     /// Get current token.
-    pub const fn current(&self) -> &'a Token {
+    pub const fn current(&self) -> &'a Token<'a> {
         let position = if self.cursor >= self.length {
             self.length - 1
         } else {
@@ -100,7 +100,7 @@ impl<'a> TokenStream<'a> {
     }
 
     /// Get previous token.
-    pub const fn previous(&self) -> &'a Token {
+    pub const fn previous(&self) -> &'a Token<'a> {
         let position = if self.cursor == 0 { 0 } else { self.cursor - 1 };
 
         &self.tokens[position]
@@ -109,14 +109,14 @@ impl<'a> TokenStream<'a> {
     /// Peek next token.
     ///
     /// All comments are skipped.
-    pub const fn peek(&self) -> &'a Token {
+    pub const fn peek(&self) -> &'a Token<'a> {
         self.peek_nth(1)
     }
 
     /// Peek nth+1 token.
     ///
     /// All comments are skipped.
-    pub const fn lookahead(&self, n: usize) -> &'a Token {
+    pub const fn lookahead(&self, n: usize) -> &'a Token<'a> {
         self.peek_nth(n + 1)
     }
 
@@ -124,7 +124,7 @@ impl<'a> TokenStream<'a> {
     ///
     /// All comments are skipped.
     #[inline(always)]
-    const fn peek_nth(&self, n: usize) -> &'a Token {
+    const fn peek_nth(&self, n: usize) -> &'a Token<'a> {
         let mut cursor = self.cursor + 1;
         let mut target = 1;
         loop {
@@ -173,49 +173,77 @@ impl<'a> TokenStream<'a> {
         CommentGroup {
             comments: comments
                 .iter()
-                .map(|token| match token {
-                    Token {
-                        kind: TokenKind::SingleLineComment,
-                        span,
-                        value,
-                    } => Comment {
-                        span: *span,
-                        format: CommentFormat::SingleLine,
-                        content: value.clone(),
-                    },
-                    Token {
-                        kind: TokenKind::MultiLineComment,
-                        span,
-                        value,
-                    } => Comment {
-                        span: *span,
-                        format: CommentFormat::MultiLine,
-                        content: value.clone(),
-                    },
-                    Token {
-                        kind: TokenKind::HashMarkComment,
-                        span,
-                        value,
-                    } => Comment {
-                        span: *span,
-                        format: CommentFormat::HashMark,
-                        content: value.clone(),
-                    },
-                    Token {
-                        kind: TokenKind::DocumentComment,
-                        span,
-                        value,
-                    } => Comment {
-                        span: *span,
-                        format: CommentFormat::Document,
-                        content: value.clone(),
-                    },
-                    _ => unreachable!(),
-                })
+                .filter_map(|token| Comment::from_token(token))
                 .collect(),
         }
     }
 
+    /// Get the tokens that haven't been consumed yet, including comments.
+    pub fn upcoming(&self) -> &'a [Token<'a>] {
+        &self.tokens[self.cursor.min(self.length)..self.length]
+    }
+
+    /// Get the last token that was consumed, skipping comments.
+    pub fn last_consumed(&self) -> Option<&'a Token<'a>> {
+        self.tokens[..self.cursor.min(self.length)]
+            .iter()
+            .rev()
+            .find(|token| {
+                !matches!(
+                    token.kind,
+                    TokenKind::SingleLineComment
+                        | TokenKind::MultiLineComment
+                        | TokenKind::HashMarkComment
+                        | TokenKind::DocumentComment
+                )
+            })
+    }
+
+    /// Get the doc comment of a declaration that starts at `position`.
+    pub fn doc_comment(&self, position: usize) -> Option<DocRef> {
+        let tokens = &self.tokens[..self.length];
+        let start = tokens.partition_point(|token| token.span.position < position);
+
+        tokens[..start]
+            .iter()
+            .rev()
+            .take_while(|token| {
+                matches!(
+                    token.kind,
+                    TokenKind::SingleLineComment
+                        | TokenKind::MultiLineComment
+                        | TokenKind::HashMarkComment
+                        | TokenKind::DocumentComment
+                )
+            })
+            .find(|token| {
+                matches!(
+                    token.kind,
+                    TokenKind::MultiLineComment | TokenKind::DocumentComment
+                )
+            })
+            .map(|token| DocRef::new(token.span, token.value.to_byte_string()))
+    }
+
+    /// Get the tokens consumed since `position`, including comments.
+    pub fn consumed_since(&self, position: usize) -> &'a [Token<'a>] {
+        let consumed = &self.tokens[..self.cursor.min(self.length)];
+        let start = consumed.partition_point(|token| token.span.position < position);
+
+        &consumed[start..]
+    }
+
+    /// Drop the comments collected before the last token that was consumed.
+    ///
+    /// These were inside a construct that doesn't keep its comments, e.g. a `use`
+    /// statement, and would otherwise end up in the comments of the next construct.
+    pub fn forget_enclosed_comments(&mut self) {
+        if let Some(last) = self.last_consumed() {
+            self.comments
+                .retain(|comment| comment.span.position > last.span.position);
+        }
+    }
+
     fn collect_comments(&mut self) {
         loop {
             if self.cursor >= self.length {
@@ -246,8 +274,8 @@ impl<'a> Default for TokenStream<'a> {
     }
 }
 
-impl<'a> From<&'a Vec<Token>> for TokenStream<'a> {
-    fn from(tokens: &'a Vec<Token>) -> Self {
+impl<'a> From<&'a Vec<Token<'a>>> for TokenStream<'a> {
+    fn from(tokens: &'a Vec<Token<'a>>) -> Self {
         Self::new(tokens.as_slice())
     }
 }
@@ -2,8 +2,12 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
+use std::borrow::Cow;
 use std::fmt::Display;
+use std::ops::Deref;
 
+use crate::lexer::byte_string::serialize_bytes;
+use crate::lexer::byte_string::write_escaped;
 use crate::lexer::byte_string::ByteString;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize, JsonSchema, Default)]
@@ -11,16 +15,98 @@ pub struct Span {
     pub line: usize,
     pub column: usize,
     pub position: usize,
+    /// The number of bytes of the token that starts here, or 0 for a span that the
+    /// lexer didn't read, e.g. one made with [`Span::new`].
+    #[serde(default)]
+    pub length: usize,
 }
 
 impl Span {
-    pub fn new(line: usize, column: usize, position: usize) -> Self {
+    pub const fn new(line: usize, column: usize, position: usize) -> Self {
         Self {
             line,
             column,
             position,
+            length: 0,
         }
     }
+
+    /// The same span, for a token of `length` bytes.
+    pub const fn with_length(self, length: usize) -> Self {
+        Self { length, ..self }
+    }
+
+    /// The position right after the token that starts here.
+    pub const fn end(&self) -> usize {
+        self.position + self.length
+    }
+
+    /// A placeholder for a span that doesn't point into the source, e.g. the span of the
+    /// default token.
+    pub const fn dummy() -> Self {
+        Self::new(0, 0, 0)
+    }
+
+    pub const fn is_dummy(&self) -> bool {
+        self.line == 0
+    }
+
+    /// The range from this span up to, but not including, `end`.
+    pub fn to(self, end: Span) -> SpanRange {
+        SpanRange::new(self, end)
+    }
+}
+
+/// A range of the source, from the start of one span up to, but not including, the
+/// start of another.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+pub struct SpanRange {
+    pub start: Span,
+    pub end: Span,
+}
+
+impl SpanRange {
+    pub fn new(start: Span, end: Span) -> Self {
+        debug_assert!(
+            start.position <= end.position,
+            "a range can't end before it starts"
+        );
+
+        Self { start, end }
+    }
+
+    /// The number of bytes in the range.
+    pub fn len(&self) -> usize {
+        self.end.position - self.start.position
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The smallest range that covers both ranges.
+    pub fn join(&self, other: &SpanRange) -> SpanRange {
+        let start = if other.start.position < self.start.position {
+            other.start
+        } else {
+            self.start
+        };
+        let end = if other.end.position > self.end.position {
+            other.end
+        } else {
+            self.end
+        };
+
+        SpanRange { start, end }
+    }
+
+    pub fn contains(&self, other: &SpanRange) -> bool {
+        self.start.position <= other.start.position && other.end.position <= self.end.position
+    }
+
+    pub fn contains_offset(&self, offset: usize) -> bool {
+        self.start.position <= offset && offset < self.end.position
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -264,25 +350,544 @@ pub enum TokenKind {
     Type,
 }
 
+/// The variant of a [`TokenKind`] without its data, which the parser compares on hot
+/// paths instead of the whole [`TokenKind`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(u8)]
+pub enum KindId {
+    Die,
+    Self_,
+    Parent,
+    Backtick,
+    StartDocString,
+    EndDocString,
+    From,
+    Print,
+    Dollar,
+    HaltCompiler,
+    Readonly,
+    Global,
+    Abstract,
+    Ampersand,
+    AmpersandEquals,
+    And,
+    AndEquals,
+    Array,
+    ArrayCast,
+    Arrow,
+    QuestionArrow,
+    At,
+    As,
+    Asterisk,
+    Attribute,
+    Bang,
+    BangEquals,
+    AngledLeftRight,
+    BangDoubleEquals,
+    Spaceship,
+    BoolCast,
+    BooleanCast,
+    BooleanAnd,
+    BooleanOr,
+    Break,
+    Callable,
+    Caret,
+    CaretEquals,
+    Case,
+    Catch,
+    Class,
+    ClassConstant,
+    TraitConstant,
+    FunctionConstant,
+    MethodConstant,
+    LineConstant,
+    FileConstant,
+    Clone,
+    MinusEquals,
+    CloseTag,
+    DoubleQuestion,
+    DoubleQuestionEquals,
+    AsteriskEquals,
+    Colon,
+    Comma,
+    SingleLineComment,
+    HashMarkComment,
+    MultiLineComment,
+    DocumentComment,
+    Const,
+    LiteralString,
+    Continue,
+    CurlyOpen,
+    Declare,
+    Decrement,
+    Default,
+    DirConstant,
+    DivEquals,
+    Do,
+    DollarLeftBrace,
+    Dot,
+    DotEquals,
+    DoubleArrow,
+    DoubleCast,
+    RealCast,
+    FloatCast,
+    DoubleColon,
+    DoubleEquals,
+    DoubleQuote,
+    Echo,
+    Ellipsis,
+    Else,
+    ElseIf,
+    Empty,
+    EndDeclare,
+    EndFor,
+    EndForeach,
+    EndIf,
+    EndSwitch,
+    EndWhile,
+    Enum,
+    Eof,
+    Equals,
+    Extends,
+    False,
+    Final,
+    Finally,
+    LiteralFloat,
+    Fn,
+    For,
+    Foreach,
+    FullyQualifiedIdentifier,
+    Function,
+    Goto,
+    GreaterThan,
+    GreaterThanEquals,
+    Identifier,
+    If,
+    Implements,
+    Include,
+    IncludeOnce,
+    Increment,
+    InlineHtml,
+    Instanceof,
+    Insteadof,
+    Eval,
+    Exit,
+    Unset,
+    Isset,
+    List,
+    LiteralInteger,
+    IntCast,
+    IntegerCast,
+    Interface,
+    LeftBrace,
+    LeftBracket,
+    LeftParen,
+    LeftShift,
+    LeftShiftEquals,
+    RightShift,
+    RightShiftEquals,
+    LessThan,
+    LessThanEquals,
+    Match,
+    Minus,
+    Namespace,
+    NamespaceSeparator,
+    NamespaceConstant,
+    CompilerHaltOffsetConstant,
+    New,
+    Null,
+    ObjectCast,
+    UnsetCast,
+    OpenTag,
+    Percent,
+    PercentEquals,
+    Pipe,
+    PipeEquals,
+    Plus,
+    PlusEquals,
+    Pow,
+    PowEquals,
+    Private,
+    Protected,
+    Public,
+    QualifiedIdentifier,
+    Question,
+    QuestionColon,
+    Require,
+    RequireOnce,
+    Return,
+    RightBrace,
+    RightBracket,
+    RightParen,
+    SemiColon,
+    Slash,
+    SlashEquals,
+    Static,
+    StringCast,
+    BinaryCast,
+    StringPart,
+    Switch,
+    Throw,
+    Trait,
+    TripleEquals,
+    True,
+    Try,
+    Use,
+    Var,
+    Variable,
+    Yield,
+    While,
+    BitwiseNot,
+    LogicalAnd,
+    LogicalOr,
+    LogicalXor,
+    Type,
+}
+
+impl TokenKind {
+    pub const fn kind_id(&self) -> KindId {
+        match self {
+            Self::Die => KindId::Die,
+            Self::Self_ => KindId::Self_,
+            Self::Parent => KindId::Parent,
+            Self::Backtick => KindId::Backtick,
+            Self::StartDocString(_) => KindId::StartDocString,
+            Self::EndDocString(..) => KindId::EndDocString,
+            Self::From => KindId::From,
+            Self::Print => KindId::Print,
+            Self::Dollar => KindId::Dollar,
+            Self::HaltCompiler => KindId::HaltCompiler,
+            Self::Readonly => KindId::Readonly,
+            Self::Global => KindId::Global,
+            Self::Abstract => KindId::Abstract,
+            Self::Ampersand => KindId::Ampersand,
+            Self::AmpersandEquals => KindId::AmpersandEquals,
+            Self::And => KindId::And,
+            Self::AndEquals => KindId::AndEquals,
+            Self::Array => KindId::Array,
+            Self::ArrayCast => KindId::ArrayCast,
+            Self::Arrow => KindId::Arrow,
+            Self::QuestionArrow => KindId::QuestionArrow,
+            Self::At => KindId::At,
+            Self::As => KindId::As,
+            Self::Asterisk => KindId::Asterisk,
+            Self::Attribute => KindId::Attribute,
+            Self::Bang => KindId::Bang,
+            Self::BangEquals => KindId::BangEquals,
+            Self::AngledLeftRight => KindId::AngledLeftRight,
+            Self::BangDoubleEquals => KindId::BangDoubleEquals,
+            Self::Spaceship => KindId::Spaceship,
+            Self::BoolCast => KindId::BoolCast,
+            Self::BooleanCast => KindId::BooleanCast,
+            Self::BooleanAnd => KindId::BooleanAnd,
+            Self::BooleanOr => KindId::BooleanOr,
+            Self::Break => KindId::Break,
+            Self::Callable => KindId::Callable,
+            Self::Caret => KindId::Caret,
+            Self::CaretEquals => KindId::CaretEquals,
+            Self::Case => KindId::Case,
+            Self::Catch => KindId::Catch,
+            Self::Class => KindId::Class,
+            Self::ClassConstant => KindId::ClassConstant,
+            Self::TraitConstant => KindId::TraitConstant,
+            Self::FunctionConstant => KindId::FunctionConstant,
+            Self::MethodConstant => KindId::MethodConstant,
+            Self::LineConstant => KindId::LineConstant,
+            Self::FileConstant => KindId::FileConstant,
+            Self::Clone => KindId::Clone,
+            Self::MinusEquals => KindId::MinusEquals,
+            Self::CloseTag => KindId::CloseTag,
+            Self::DoubleQuestion => KindId::DoubleQuestion,
+            Self::DoubleQuestionEquals => KindId::DoubleQuestionEquals,
+            Self::AsteriskEquals => KindId::AsteriskEquals,
+            Self::Colon => KindId::Colon,
+            Self::Comma => KindId::Comma,
+            Self::SingleLineComment => KindId::SingleLineComment,
+            Self::HashMarkComment => KindId::HashMarkComment,
+            Self::MultiLineComment => KindId::MultiLineComment,
+            Self::DocumentComment => KindId::DocumentComment,
+            Self::Const => KindId::Const,
+            Self::LiteralString => KindId::LiteralString,
+            Self::Continue => KindId::Continue,
+            Self::CurlyOpen => KindId::CurlyOpen,
+            Self::Declare => KindId::Declare,
+            Self::Decrement => KindId::Decrement,
+            Self::Default => KindId::Default,
+            Self::DirConstant => KindId::DirConstant,
+            Self::DivEquals => KindId::DivEquals,
+            Self::Do => KindId::Do,
+            Self::DollarLeftBrace => KindId::DollarLeftBrace,
+            Self::Dot => KindId::Dot,
+            Self::DotEquals => KindId::DotEquals,
+            Self::DoubleArrow => KindId::DoubleArrow,
+            Self::DoubleCast => KindId::DoubleCast,
+            Self::RealCast => KindId::RealCast,
+            Self::FloatCast => KindId::FloatCast,
+            Self::DoubleColon => KindId::DoubleColon,
+            Self::DoubleEquals => KindId::DoubleEquals,
+            Self::DoubleQuote => KindId::DoubleQuote,
+            Self::Echo => KindId::Echo,
+            Self::Ellipsis => KindId::Ellipsis,
+            Self::Else => KindId::Else,
+            Self::ElseIf => KindId::ElseIf,
+            Self::Empty => KindId::Empty,
+            Self::EndDeclare => KindId::EndDeclare,
+            Self::EndFor => KindId::EndFor,
+            Self::EndForeach => KindId::EndForeach,
+            Self::EndIf => KindId::EndIf,
+            Self::EndSwitch => KindId::EndSwitch,
+            Self::EndWhile => KindId::EndWhile,
+            Self::Enum => KindId::Enum,
+            Self::Eof => KindId::Eof,
+            Self::Equals => KindId::Equals,
+            Self::Extends => KindId::Extends,
+            Self::False => KindId::False,
+            Self::Final => KindId::Final,
+            Self::Finally => KindId::Finally,
+            Self::LiteralFloat => KindId::LiteralFloat,
+            Self::Fn => KindId::Fn,
+            Self::For => KindId::For,
+            Self::Foreach => KindId::Foreach,
+            Self::FullyQualifiedIdentifier => KindId::FullyQualifiedIdentifier,
+            Self::Function => KindId::Function,
+            Self::Goto => KindId::Goto,
+            Self::GreaterThan => KindId::GreaterThan,
+            Self::GreaterThanEquals => KindId::GreaterThanEquals,
+            Self::Identifier => KindId::Identifier,
+            Self::If => KindId::If,
+            Self::Implements => KindId::Implements,
+            Self::Include => KindId::Include,
+            Self::IncludeOnce => KindId::IncludeOnce,
+            Self::Increment => KindId::Increment,
+            Self::InlineHtml => KindId::InlineHtml,
+            Self::Instanceof => KindId::Instanceof,
+            Self::Insteadof => KindId::Insteadof,
+            Self::Eval => KindId::Eval,
+            Self::Exit => KindId::Exit,
+            Self::Unset => KindId::Unset,
+            Self::Isset => KindId::Isset,
+            Self::List => KindId::List,
+            Self::LiteralInteger => KindId::LiteralInteger,
+            Self::IntCast => KindId::IntCast,
+            Self::IntegerCast => KindId::IntegerCast,
+            Self::Interface => KindId::Interface,
+            Self::LeftBrace => KindId::LeftBrace,
+            Self::LeftBracket => KindId::LeftBracket,
+            Self::LeftParen => KindId::LeftParen,
+            Self::LeftShift => KindId::LeftShift,
+            Self::LeftShiftEquals => KindId::LeftShiftEquals,
+            Self::RightShift => KindId::RightShift,
+            Self::RightShiftEquals => KindId::RightShiftEquals,
+            Self::LessThan => KindId::LessThan,
+            Self::LessThanEquals => KindId::LessThanEquals,
+            Self::Match => KindId::Match,
+            Self::Minus => KindId::Minus,
+            Self::Namespace => KindId::Namespace,
+            Self::NamespaceSeparator => KindId::NamespaceSeparator,
+            Self::NamespaceConstant => KindId::NamespaceConstant,
+            Self::CompilerHaltOffsetConstant => KindId::CompilerHaltOffsetConstant,
+            Self::New => KindId::New,
+            Self::Null => KindId::Null,
+            Self::ObjectCast => KindId::ObjectCast,
+            Self::UnsetCast => KindId::UnsetCast,
+            Self::OpenTag(_) => KindId::OpenTag,
+            Self::Percent => KindId::Percent,
+            Self::PercentEquals => KindId::PercentEquals,
+            Self::Pipe => KindId::Pipe,
+            Self::PipeEquals => KindId::PipeEquals,
+            Self::Plus => KindId::Plus,
+            Self::PlusEquals => KindId::PlusEquals,
+            Self::Pow => KindId::Pow,
+            Self::PowEquals => KindId::PowEquals,
+            Self::Private => KindId::Private,
+            Self::Protected => KindId::Protected,
+            Self::Public => KindId::Public,
+            Self::QualifiedIdentifier => KindId::QualifiedIdentifier,
+            Self::Question => KindId::Question,
+            Self::QuestionColon => KindId::QuestionColon,
+            Self::Require => KindId::Require,
+            Self::RequireOnce => KindId::RequireOnce,
+            Self::Return => KindId::Return,
+            Self::RightBrace => KindId::RightBrace,
+            Self::RightBracket => KindId::RightBracket,
+            Self::RightParen => KindId::RightParen,
+            Self::SemiColon => KindId::SemiColon,
+            Self::Slash => KindId::Slash,
+            Self::SlashEquals => KindId::SlashEquals,
+            Self::Static => KindId::Static,
+            Self::StringCast => KindId::StringCast,
+            Self::BinaryCast => KindId::BinaryCast,
+            Self::StringPart => KindId::StringPart,
+            Self::Switch => KindId::Switch,
+            Self::Throw => KindId::Throw,
+            Self::Trait => KindId::Trait,
+            Self::TripleEquals => KindId::TripleEquals,
+            Self::True => KindId::True,
+            Self::Try => KindId::Try,
+            Self::Use => KindId::Use,
+            Self::Var => KindId::Var,
+            Self::Variable => KindId::Variable,
+            Self::Yield => KindId::Yield,
+            Self::While => KindId::While,
+            Self::BitwiseNot => KindId::BitwiseNot,
+            Self::LogicalAnd => KindId::LogicalAnd,
+            Self::LogicalOr => KindId::LogicalOr,
+            Self::LogicalXor => KindId::LogicalXor,
+            Self::Type => KindId::Type,
+        }
+    }
+}
+
+/// The bytes of a token. Those that are found as is in the code, like names, operators
+/// and comments, are borrowed from it, and only those that aren't, like the unescaped
+/// contents of a double-quoted string, are owned.
+///
+/// It is shown, serialized and described like a [`ByteString`].
+#[derive(PartialEq, Eq, Clone, Default, Hash)]
+pub struct TokenValue<'a>(Cow<'a, [u8]>);
+
+impl<'a> TokenValue<'a> {
+    /// The value, owning its bytes, so that it outlives the code it was found in.
+    pub fn into_owned(self) -> TokenValue<'static> {
+        TokenValue(Cow::Owned(self.0.into_owned()))
+    }
+
+    /// A copy of the bytes, e.g. for the node of the syntax tree that the token is in.
+    pub fn to_byte_string(&self) -> ByteString {
+        ByteString::new(self.0.to_vec())
+    }
+}
+
+impl Deref for TokenValue<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for TokenValue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_escaped(f, &self.0)
+    }
+}
+
+impl std::fmt::Debug for TokenValue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+        write_escaped(f, &self.0)?;
+        write!(f, "\"")
+    }
+}
+
+/// Like a [`ByteString`], so that a value that isn't UTF-8 keeps its bytes.
+impl Serialize for TokenValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_bytes(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenValue<'_> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let string = ByteString::deserialize(deserializer)?;
+        Ok(TokenValue(Cow::Owned(string.bytes)))
+    }
+}
+
+impl JsonSchema for TokenValue<'_> {
+    fn schema_name() -> String {
+        ByteString::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        ByteString::json_schema(gen)
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for TokenValue<'_> {
+    fn eq(&self, other: &&[u8; N]) -> bool {
+        *self.0 == other[..]
+    }
+}
+
+impl<'a> From<&'a [u8]> for TokenValue<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        TokenValue(Cow::Borrowed(bytes))
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for TokenValue<'a> {
+    fn from(bytes: &'a [u8; N]) -> Self {
+        TokenValue(Cow::Borrowed(bytes))
+    }
+}
+
+impl From<u8> for TokenValue<'_> {
+    fn from(byte: u8) -> Self {
+        TokenValue(Cow::Owned(vec![byte]))
+    }
+}
+
+impl From<Vec<u8>> for TokenValue<'_> {
+    fn from(bytes: Vec<u8>) -> Self {
+        TokenValue(Cow::Owned(bytes))
+    }
+}
+
+impl From<ByteString> for TokenValue<'_> {
+    fn from(bytes: ByteString) -> Self {
+        TokenValue(Cow::Owned(bytes.bytes))
+    }
+}
+
+impl From<&str> for TokenValue<'_> {
+    fn from(bytes: &str) -> Self {
+        TokenValue(Cow::Owned(bytes.as_bytes().to_vec()))
+    }
+}
+
+impl From<TokenValue<'_>> for ByteString {
+    fn from(value: TokenValue<'_>) -> Self {
+        ByteString::new(value.0.into_owned())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
-pub struct Token {
+pub struct Token<'a> {
     pub kind: TokenKind,
     pub span: Span,
-    pub value: ByteString,
+    pub value: TokenValue<'a>,
 }
 
-impl Default for Token {
+impl Token<'_> {
+    /// The token, owning its value, so that it outlives the code it was found in.
+    pub fn into_owned(self) -> Token<'static> {
+        Token {
+            kind: self.kind,
+            span: self.span,
+            value: self.value.into_owned(),
+        }
+    }
+}
+
+impl Default for Token<'_> {
     fn default() -> Self {
         Self {
             kind: TokenKind::Eof,
-            span: Span::new(0, 0, 0),
-            value: ByteString::default(),
+            span: Span::dummy(),
+            value: TokenValue::default(),
         }
     }
 }
 
-impl Display for Token {
+impl Display for Token<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.value)
     }
@@ -491,3 +1096,83 @@ impl Display for TokenKind {
         write!(f, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// The name of every variant of [`TokenKind`], taken from its schema.
+    fn variants() -> Vec<String> {
+        let schema = serde_json::to_value(schemars::schema_for!(TokenKind)).unwrap();
+
+        let mut names = vec![];
+        for variant in schema["oneOf"].as_array().unwrap() {
+            match &variant["enum"] {
+                Value::Array(units) => names.extend(units.iter().map(|unit| unit.to_string())),
+                _ => names.extend(
+                    variant["required"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|name| name.to_string()),
+                ),
+            }
+        }
+
+        names
+            .into_iter()
+            .map(|name| name.trim_matches('"').to_string())
+            .collect()
+    }
+
+    fn kind(name: &str) -> TokenKind {
+        let value = match name {
+            "StartDocString" => json!({ name: "Heredoc" }),
+            "EndDocString" => json!({ name: ["Space", 4] }),
+            "OpenTag" => json!({ name: "Full" }),
+            _ => json!(name),
+        };
+
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_every_kind_has_a_unique_kind_id() {
+        let variants = variants();
+        let mut ids = HashSet::new();
+
+        for name in &variants {
+            let id = kind(name).kind_id();
+
+            assert_eq!(format!("{:?}", id), *name);
+            assert!(ids.insert(id), "{} shares its kind id", name);
+        }
+
+        assert_eq!(ids.len(), variants.len());
+    }
+
+    #[test]
+    fn test_kind_id_ignores_data() {
+        assert_eq!(
+            TokenKind::OpenTag(OpenTagKind::Full).kind_id(),
+            TokenKind::OpenTag(OpenTagKind::Echo).kind_id()
+        );
+        assert_ne!(TokenKind::Comma.kind_id(), TokenKind::SemiColon.kind_id());
+    }
+
+    #[test]
+    fn test_token_value_json() {
+        let latin1 = TokenValue::from(&b"caf\xe9"[..]);
+        let json = serde_json::to_string(&latin1).unwrap();
+        assert_eq!(json, r#"{"bytes":[99,97,102,233]}"#);
+        assert_eq!(serde_json::from_str::<TokenValue>(&json).unwrap(), latin1);
+
+        let text = TokenValue::from(&b"\n"[..]);
+        assert_eq!(serde_json::to_string(&text).unwrap(), r#""\n""#);
+    }
+}
@@ -1,9 +1,34 @@
+pub mod analysis;
 pub mod downcast;
 pub mod lexer;
+pub mod lowering;
 pub mod node;
+pub mod normalize;
 pub mod parser;
 pub mod printer;
+pub mod remap;
+pub mod rewrite;
+pub mod sexpr;
+pub mod spans;
 pub mod traverser;
+pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use lexer::stream::TokenStream;
-pub use parser::{construct, parse};
+pub use parser::config::AfterHaltMode;
+pub use parser::config::InlineHtmlMode;
+pub use parser::config::ParserConfig;
+pub use parser::encoding::{SourceEncoding, TranscodedSource};
+pub use parser::error::Error;
+pub use parser::lsp::diagnostics_to_json;
+pub use parser::policy::{CallPolicy, ExpressionPolicy};
+pub use parser::report::{render_report, RenderOptions};
+pub use parser::source_map::{PositionEncoding, SourceMap};
+pub use parser::symbols::{SymbolContainer, SymbolEvent, SymbolKind};
+pub use parser::version::PhpVersion;
+pub use parser::{
+    construct, construct_with_config, construct_with_recovery, parse, parse_file,
+    parse_file_with_config, parse_restricted_expression, parse_with_config, parse_with_recovery,
+    parse_with_tokens, parse_with_warnings,
+};
@@ -0,0 +1,1281 @@
+//! A flat, postfix encoding of expressions, see [`Expression::to_postfix`].
+//!
+//! The encoding is meant for evaluators of small, mostly constant expressions, which
+//! are a lot faster running over a single vector of ops than walking the boxed tree.
+//! Every op pops its operands off a stack and pushes its result, so an expression
+//! always leaves exactly one value on the stack. Branches are encoded with forward
+//! jumps, whose offset counts the ops to skip after the jump.
+
+use std::fmt::Display;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::parser::ast::arguments::Argument;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::literals::Literal;
+use crate::parser::ast::literals::LiteralFloat;
+use crate::parser::ast::literals::LiteralInteger;
+use crate::parser::ast::literals::LiteralString;
+use crate::parser::ast::operators::ArithmeticOperationExpression;
+use crate::parser::ast::operators::BitwiseOperationExpression;
+use crate::parser::ast::operators::ComparisonOperationExpression;
+use crate::parser::ast::operators::LogicalOperationExpression;
+use crate::parser::ast::operators::RangeOperationExpression;
+use crate::parser::ast::utils::CommaSeparated;
+use crate::parser::ast::variables::Variable;
+use crate::parser::ast::ArrayItem;
+use crate::parser::ast::Expression;
+use crate::parser::ast::MagicConstantExpression;
+use crate::parser::ast::StringPart;
+
+pub type LoweringResult<T> = Result<T, LoweringError>;
+
+/// An operator that takes two operands, the left one being pushed first.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InfixOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Power,
+    Concat,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    LeftShift,
+    RightShift,
+    Equal,
+    /// `!=` or `<>`
+    NotEqual,
+    Identical,
+    NotIdentical,
+    LessThan,
+    GreaterThan,
+    LessThanOrEqual,
+    GreaterThanOrEqual,
+    Spaceship,
+    /// `xor`, the only logical operator that always evaluates both operands.
+    LogicalXor,
+}
+
+/// An operator that takes a single operand.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PrefixOp {
+    /// `-$a`
+    Negative,
+    /// `+$a`
+    Positive,
+    /// `!$a`
+    Not,
+    /// `~$a`
+    BitwiseNot,
+}
+
+/// A single step of a lowered expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExprOp {
+    PushInt(i64),
+    /// A float literal, or an integer literal too large for an `i64`.
+    PushFloat(f64),
+    /// The contents of a string literal, without its quotes.
+    PushString(ByteString),
+    PushBool(bool),
+    PushNull,
+    /// The value of a variable, by name without the `$`.
+    LoadVar(ByteString),
+    /// The value of a constant, by name as written.
+    LoadConst(ByteString),
+    UnaryOp(PrefixOp),
+    BinaryOp(InfixOp),
+    /// Pop `argc` arguments and then the function to call, which is pushed as a string
+    /// when it is called by name.
+    Call {
+        argc: usize,
+    },
+    /// Pop the items of an array literal and push the array. There is a flag for every
+    /// item, in order, telling whether it has a key, which is pushed before its value.
+    MakeArray {
+        keyed: Vec<bool>,
+    },
+    /// Pop an index and then an array, and push the element. A missing element is
+    /// `null`, without a warning when the fetch is the left operand of `??`.
+    FetchIndex,
+    /// Pop an object and push one of its properties.
+    FetchProp {
+        name: ByteString,
+    },
+    /// Push the value on top of the stack again.
+    Dup,
+    /// Drop the value on top of the stack.
+    Pop,
+    /// Skip the next `offset` ops.
+    Jump {
+        offset: usize,
+    },
+    /// Pop a value and skip the next `offset` ops if it is truthy.
+    JumpIf {
+        offset: usize,
+    },
+    /// Pop a value and skip the next `offset` ops if it is falsy.
+    JumpUnless {
+        offset: usize,
+    },
+}
+
+/// A node that can't be lowered, such as a closure, a `match` or a `yield`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LoweringError {
+    /// What the node is, e.g. `a closure`.
+    pub node: &'static str,
+    /// Where the node starts, unless neither it nor its first operand has a span, as
+    /// for `static` or a heredoc.
+    pub span: Option<Span>,
+}
+
+impl Display for LoweringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.span {
+            Some(span) => write!(
+                f,
+                "Lowering Error: cannot lower {} on line {} column {}",
+                self.node, span.line, span.column
+            ),
+            None => write!(f, "Lowering Error: cannot lower {}", self.node),
+        }
+    }
+}
+
+impl std::error::Error for LoweringError {}
+
+impl Expression {
+    /// Flatten the expression into a sequence of postfix ops, see the [`lowering`]
+    /// module.
+    ///
+    /// Literals, `true`, `false` and `null`, simple variables, constants, arithmetic,
+    /// bitwise, comparison and logical operators, concatenation, ternaries, `??`, calls
+    /// with positional arguments, array literals, and fetches of elements and named
+    /// properties are supported. `&&`, `||`, `?:`, `??` and ternaries only evaluate the
+    /// operands that PHP does. Anything else, e.g. an assignment, a closure, a `match`
+    /// or a `yield`, is an error naming the first such node.
+    ///
+    /// [`lowering`]: crate::lowering
+    pub fn to_postfix(&self) -> LoweringResult<Vec<ExprOp>> {
+        let mut lowering = Lowering { ops: vec![] };
+        lowering.expression(self)?;
+
+        Ok(lowering.ops)
+    }
+}
+
+struct Lowering {
+    ops: Vec<ExprOp>,
+}
+
+impl Lowering {
+    fn expression(&mut self, expression: &Expression) -> LoweringResult<()> {
+        match expression {
+            Expression::Literal(Literal::Integer(integer)) => {
+                self.ops.push(match integer.to_i64() {
+                    Some(value) => ExprOp::PushInt(value),
+                    None => ExprOp::PushFloat(float(&integer.value)),
+                })
+            }
+            Expression::Literal(Literal::Float(value)) => {
+                self.ops.push(ExprOp::PushFloat(float(&value.value)))
+            }
+            Expression::Literal(Literal::String(string)) => {
+                let value = &string.value;
+                let inner = &value[1.min(value.len())..value.len().saturating_sub(1)];
+
+                self.ops.push(ExprOp::PushString(inner.into()))
+            }
+            Expression::Bool(bool) => self.ops.push(ExprOp::PushBool(bool.value)),
+            Expression::Null(_) => self.ops.push(ExprOp::PushNull),
+            Expression::Variable(Variable::SimpleVariable(variable)) => {
+                let name = variable.name.strip_prefix(b"$").unwrap_or(&variable.name);
+
+                self.ops.push(ExprOp::LoadVar(name.into()))
+            }
+            Expression::Identifier(Identifier::SimpleIdentifier(constant)) => {
+                self.ops.push(ExprOp::LoadConst(constant.value.clone()))
+            }
+            Expression::Parenthesized(parenthesized) => self.expression(&parenthesized.expr)?,
+            Expression::ArithmeticOperation(operation) => self.arithmetic(operation)?,
+            Expression::BitwiseOperation(operation) => match operation {
+                BitwiseOperationExpression::And { left, right, .. } => {
+                    self.binary(left, InfixOp::BitwiseAnd, right)?
+                }
+                BitwiseOperationExpression::Or { left, right, .. } => {
+                    self.binary(left, InfixOp::BitwiseOr, right)?
+                }
+                BitwiseOperationExpression::Xor { left, right, .. } => {
+                    self.binary(left, InfixOp::BitwiseXor, right)?
+                }
+                BitwiseOperationExpression::LeftShift { left, right, .. } => {
+                    self.binary(left, InfixOp::LeftShift, right)?
+                }
+                BitwiseOperationExpression::RightShift { left, right, .. } => {
+                    self.binary(left, InfixOp::RightShift, right)?
+                }
+                BitwiseOperationExpression::Not { right, .. } => {
+                    self.unary(PrefixOp::BitwiseNot, right)?
+                }
+            },
+            Expression::ComparisonOperation(operation) => {
+                let (left, op, right) = match operation {
+                    ComparisonOperationExpression::Equal { left, right, .. } => {
+                        (left, InfixOp::Equal, right)
+                    }
+                    ComparisonOperationExpression::Identical { left, right, .. } => {
+                        (left, InfixOp::Identical, right)
+                    }
+                    ComparisonOperationExpression::NotEqual { left, right, .. }
+                    | ComparisonOperationExpression::AngledNotEqual { left, right, .. } => {
+                        (left, InfixOp::NotEqual, right)
+                    }
+                    ComparisonOperationExpression::NotIdentical { left, right, .. } => {
+                        (left, InfixOp::NotIdentical, right)
+                    }
+                    ComparisonOperationExpression::LessThan { left, right, .. } => {
+                        (left, InfixOp::LessThan, right)
+                    }
+                    ComparisonOperationExpression::GreaterThan { left, right, .. } => {
+                        (left, InfixOp::GreaterThan, right)
+                    }
+                    ComparisonOperationExpression::LessThanOrEqual { left, right, .. } => {
+                        (left, InfixOp::LessThanOrEqual, right)
+                    }
+                    ComparisonOperationExpression::GreaterThanOrEqual { left, right, .. } => {
+                        (left, InfixOp::GreaterThanOrEqual, right)
+                    }
+                    ComparisonOperationExpression::Spaceship { left, right, .. } => {
+                        (left, InfixOp::Spaceship, right)
+                    }
+                };
+
+                self.binary(left, op, right)?
+            }
+            Expression::LogicalOperation(operation) => match operation {
+                LogicalOperationExpression::And { left, right, .. }
+                | LogicalOperationExpression::LogicalAnd { left, right, .. } => {
+                    self.short_circuit(left, right, false)?
+                }
+                LogicalOperationExpression::Or { left, right, .. }
+                | LogicalOperationExpression::LogicalOr { left, right, .. } => {
+                    self.short_circuit(left, right, true)?
+                }
+                LogicalOperationExpression::LogicalXor { left, right, .. } => {
+                    self.binary(left, InfixOp::LogicalXor, right)?
+                }
+                LogicalOperationExpression::Not { right, .. } => {
+                    self.unary(PrefixOp::Not, right)?
+                }
+            },
+            Expression::Concat(concat) => {
+                self.binary(&concat.left, InfixOp::Concat, &concat.right)?
+            }
+            Expression::ConcatMany(concat) => {
+                for (index, part) in concat.parts.iter().enumerate() {
+                    self.expression(part)?;
+
+                    if index > 0 {
+                        self.ops.push(ExprOp::BinaryOp(InfixOp::Concat));
+                    }
+                }
+            }
+            // `$a ?: $b` is sometimes kept as a ternary without a `then` branch.
+            Expression::Ternary(ternary) if matches!(ternary.then.as_ref(), Expression::Noop) => {
+                self.short_ternary(&ternary.condition, &ternary.r#else)?
+            }
+            Expression::Ternary(ternary) => {
+                self.expression(&ternary.condition)?;
+                let unless = self.jump(ExprOp::JumpUnless { offset: 0 });
+                self.expression(&ternary.then)?;
+                let end = self.jump(ExprOp::Jump { offset: 0 });
+                self.land(unless);
+                self.expression(&ternary.r#else)?;
+                self.land(end);
+            }
+            Expression::ShortTernary(ternary) => {
+                self.short_ternary(&ternary.condition, &ternary.r#else)?
+            }
+            Expression::Coalesce(coalesce) => {
+                self.expression(&coalesce.lhs)?;
+                self.ops.push(ExprOp::Dup);
+                self.ops.push(ExprOp::PushNull);
+                self.ops.push(ExprOp::BinaryOp(InfixOp::Identical));
+                let end = self.jump(ExprOp::JumpUnless { offset: 0 });
+                self.ops.push(ExprOp::Pop);
+                self.expression(&coalesce.rhs)?;
+                self.land(end);
+            }
+            Expression::FunctionCall(call) => {
+                match call.target.as_ref() {
+                    Expression::Identifier(Identifier::SimpleIdentifier(name)) => {
+                        self.ops.push(ExprOp::PushString(name.value.clone()))
+                    }
+                    target => self.expression(target)?,
+                }
+
+                for argument in &call.arguments.arguments {
+                    match argument {
+                        Argument::Positional(argument) if argument.ellipsis.is_none() => {
+                            self.expression(&argument.value)?
+                        }
+                        Argument::Positional(argument) => {
+                            return Err(LoweringError {
+                                node: "an unpacked argument",
+                                span: argument.ellipsis,
+                            })
+                        }
+                        Argument::Named(argument) => {
+                            return Err(LoweringError {
+                                node: "a named argument",
+                                span: Some(argument.name.span),
+                            })
+                        }
+                    }
+                }
+
+                self.ops.push(ExprOp::Call {
+                    argc: call.arguments.arguments.len(),
+                });
+            }
+            Expression::ShortArray(array) => self.array(&array.items)?,
+            Expression::Array(array) => self.array(&array.items)?,
+            Expression::ArrayIndex(index) if index.index.is_some() => {
+                self.expression(&index.array)?;
+                self.expression(index.index.as_ref().unwrap())?;
+                self.ops.push(ExprOp::FetchIndex);
+            }
+            Expression::PropertyFetch(fetch) => match fetch.property.as_ref() {
+                Expression::Identifier(Identifier::SimpleIdentifier(name)) => {
+                    self.expression(&fetch.target)?;
+                    self.ops.push(ExprOp::FetchProp {
+                        name: name.value.clone(),
+                    });
+                }
+                property => return Err(unsupported(property)),
+            },
+            expression => return Err(unsupported(expression)),
+        }
+
+        Ok(())
+    }
+
+    fn arithmetic(&mut self, operation: &ArithmeticOperationExpression) -> LoweringResult<()> {
+        let (left, op, right) = match operation {
+            ArithmeticOperationExpression::Addition { left, right, .. } => {
+                (left, InfixOp::Add, right)
+            }
+            ArithmeticOperationExpression::Subtraction { left, right, .. } => {
+                (left, InfixOp::Subtract, right)
+            }
+            ArithmeticOperationExpression::Multiplication { left, right, .. } => {
+                (left, InfixOp::Multiply, right)
+            }
+            ArithmeticOperationExpression::Division { left, right, .. } => {
+                (left, InfixOp::Divide, right)
+            }
+            ArithmeticOperationExpression::Modulo { left, right, .. } => {
+                (left, InfixOp::Modulo, right)
+            }
+            ArithmeticOperationExpression::Exponentiation { left, right, .. } => {
+                (left, InfixOp::Power, right)
+            }
+            ArithmeticOperationExpression::Negative { right, .. } => {
+                return self.unary(PrefixOp::Negative, right)
+            }
+            ArithmeticOperationExpression::Positive { right, .. } => {
+                return self.unary(PrefixOp::Positive, right)
+            }
+            ArithmeticOperationExpression::PreIncrement { increment, .. }
+            | ArithmeticOperationExpression::PreDecrement {
+                decrement: increment,
+                ..
+            } => {
+                return Err(LoweringError {
+                    node: "an increment or decrement",
+                    span: Some(*increment),
+                })
+            }
+            ArithmeticOperationExpression::PostIncrement { left, .. }
+            | ArithmeticOperationExpression::PostDecrement { left, .. } => {
+                return Err(LoweringError {
+                    node: "an increment or decrement",
+                    span: start(left),
+                })
+            }
+        };
+
+        self.binary(left, op, right)
+    }
+
+    fn binary(&mut self, left: &Expression, op: InfixOp, right: &Expression) -> LoweringResult<()> {
+        self.expression(left)?;
+        self.expression(right)?;
+        self.ops.push(ExprOp::BinaryOp(op));
+
+        Ok(())
+    }
+
+    fn unary(&mut self, op: PrefixOp, right: &Expression) -> LoweringResult<()> {
+        self.expression(right)?;
+        self.ops.push(ExprOp::UnaryOp(op));
+
+        Ok(())
+    }
+
+    /// `&&` and `and` when `or` is false, `||` and `or` when it is true. The result is
+    /// always a bool.
+    fn short_circuit(
+        &mut self,
+        left: &Expression,
+        right: &Expression,
+        or: bool,
+    ) -> LoweringResult<()> {
+        let decided = |offset| {
+            if or {
+                ExprOp::JumpIf { offset }
+            } else {
+                ExprOp::JumpUnless { offset }
+            }
+        };
+
+        self.expression(left)?;
+        let left = self.jump(decided(0));
+        self.expression(right)?;
+        let right = self.jump(decided(0));
+        self.ops.push(ExprOp::PushBool(!or));
+        let end = self.jump(ExprOp::Jump { offset: 0 });
+        self.land(left);
+        self.land(right);
+        self.ops.push(ExprOp::PushBool(or));
+        self.land(end);
+
+        Ok(())
+    }
+
+    fn short_ternary(&mut self, condition: &Expression, r#else: &Expression) -> LoweringResult<()> {
+        self.expression(condition)?;
+        self.ops.push(ExprOp::Dup);
+        let end = self.jump(ExprOp::JumpIf { offset: 0 });
+        self.ops.push(ExprOp::Pop);
+        self.expression(r#else)?;
+        self.land(end);
+
+        Ok(())
+    }
+
+    fn array(&mut self, items: &CommaSeparated<ArrayItem>) -> LoweringResult<()> {
+        let mut keyed = vec![];
+        for item in items.iter() {
+            match item {
+                ArrayItem::Value { value } => {
+                    self.expression(value)?;
+                    keyed.push(false);
+                }
+                ArrayItem::KeyValue { key, value, .. } => {
+                    self.expression(key)?;
+                    self.expression(value)?;
+                    keyed.push(true);
+                }
+                ArrayItem::ReferencedValue { ampersand, .. }
+                | ArrayItem::ReferencedKeyValue { ampersand, .. } => {
+                    return Err(LoweringError {
+                        node: "an item taken by reference",
+                        span: Some(*ampersand),
+                    })
+                }
+                ArrayItem::SpreadValue { ellipsis, .. } => {
+                    return Err(LoweringError {
+                        node: "an unpacked item",
+                        span: Some(*ellipsis),
+                    })
+                }
+                ArrayItem::Skipped => {
+                    return Err(LoweringError {
+                        node: "a skipped item",
+                        span: None,
+                    })
+                }
+            }
+        }
+
+        self.ops.push(ExprOp::MakeArray { keyed });
+
+        Ok(())
+    }
+
+    /// Push a jump whose offset is set by [`Lowering::land`], returning its index.
+    fn jump(&mut self, op: ExprOp) -> usize {
+        self.ops.push(op);
+
+        self.ops.len() - 1
+    }
+
+    /// Make the jump at `index` land on the next op.
+    fn land(&mut self, index: usize) {
+        let target = self.ops.len() - index - 1;
+
+        match &mut self.ops[index] {
+            ExprOp::Jump { offset } | ExprOp::JumpIf { offset } | ExprOp::JumpUnless { offset } => {
+                *offset = target
+            }
+            op => unreachable!("{:?} is not a jump", op),
+        }
+    }
+}
+
+fn float(value: &[u8]) -> f64 {
+    std::str::from_utf8(value)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(f64::NAN)
+}
+
+fn unsupported(expression: &Expression) -> LoweringError {
+    let node = match expression {
+        Expression::Eval(_) => "`eval`",
+        Expression::Empty(_) => "`empty`",
+        Expression::Die(_) | Expression::Exit(_) => "`exit`",
+        Expression::Isset(_) => "`isset`",
+        Expression::Unset(_) => "`unset`",
+        Expression::Print(_) => "`print`",
+        Expression::AssignmentOperation(_) => "an assignment",
+        Expression::RangeOperation(_) => "a range",
+        Expression::Instanceof(_) => "`instanceof`",
+        Expression::Reference(_) => "a reference",
+        Expression::ErrorSuppress(_) => "the `@` operator",
+        Expression::Identifier(_) => "a dynamic name",
+        Expression::Variable(_) => "a variable variable",
+        Expression::Include(_)
+        | Expression::IncludeOnce(_)
+        | Expression::Require(_)
+        | Expression::RequireOnce(_) => "an include",
+        Expression::FunctionClosureCreation(_)
+        | Expression::MethodClosureCreation(_)
+        | Expression::StaticMethodClosureCreation(_)
+        | Expression::StaticVariableMethodClosureCreation(_) => "a first-class callable",
+        Expression::MethodCall(_)
+        | Expression::NullsafeMethodCall(_)
+        | Expression::StaticMethodCall(_)
+        | Expression::StaticVariableMethodCall(_) => "a method call",
+        Expression::NullsafePropertyFetch(_) => "a nullsafe property fetch",
+        Expression::StaticPropertyFetch(_) => "a static property fetch",
+        Expression::ConstantFetch(_) => "a class constant",
+        Expression::Static(_) | Expression::Self_(_) | Expression::Parent(_) => "a class reference",
+        Expression::List(_) => "`list`",
+        Expression::Closure(_) => "a closure",
+        Expression::ArrowFunction(_) => "an arrow function",
+        Expression::New(_) | Expression::AnonymousClass(_) => "`new`",
+        Expression::InterpolatedString(_) => "an interpolated string",
+        Expression::Heredoc(_) | Expression::Nowdoc(_) => "a heredoc or nowdoc",
+        Expression::ShellExec(_) => "a shell command",
+        Expression::ArrayIndex(_) => "an append to an array",
+        Expression::MagicConstant(_) => "a magic constant",
+        Expression::Clone(_) => "`clone`",
+        Expression::Match(_) | Expression::ShortMatch(_) => "a `match`",
+        Expression::Throw(_) => "`throw`",
+        Expression::Yield(_) | Expression::YieldFrom(_) => "a `yield`",
+        Expression::Cast(_) => "a cast",
+        Expression::Noop => "an empty expression",
+        Expression::Error(_) => "an expression that failed to parse",
+        _ => "an expression",
+    };
+
+    LoweringError {
+        node,
+        span: start(expression),
+    }
+}
+
+/// Where an expression starts: the span of its keyword or operator, or else the start
+/// of its first operand.
+fn start(expression: &Expression) -> Option<Span> {
+    match expression {
+        Expression::Eval(eval) => Some(eval.eval),
+        Expression::Empty(empty) => Some(empty.empty),
+        Expression::Die(die) => Some(die.die),
+        Expression::Exit(exit) => Some(exit.exit),
+        Expression::Isset(isset) => Some(isset.isset),
+        Expression::Unset(unset) => Some(unset.unset),
+        Expression::Print(print) => Some(print.print),
+        Expression::Literal(
+            Literal::Integer(LiteralInteger { span, .. })
+            | Literal::Float(LiteralFloat { span, .. })
+            | Literal::String(LiteralString { span, .. }),
+        ) => Some(*span),
+        Expression::ArithmeticOperation(operation) => match operation {
+            ArithmeticOperationExpression::Negative { minus: span, .. }
+            | ArithmeticOperationExpression::Positive { plus: span, .. }
+            | ArithmeticOperationExpression::PreIncrement {
+                increment: span, ..
+            }
+            | ArithmeticOperationExpression::PreDecrement {
+                decrement: span, ..
+            } => Some(*span),
+            ArithmeticOperationExpression::Addition { left, .. }
+            | ArithmeticOperationExpression::Subtraction { left, .. }
+            | ArithmeticOperationExpression::Multiplication { left, .. }
+            | ArithmeticOperationExpression::Division { left, .. }
+            | ArithmeticOperationExpression::Modulo { left, .. }
+            | ArithmeticOperationExpression::Exponentiation { left, .. }
+            | ArithmeticOperationExpression::PostIncrement { left, .. }
+            | ArithmeticOperationExpression::PostDecrement { left, .. } => start(left),
+        },
+        Expression::AssignmentOperation(operation) => start(operation.left()),
+        Expression::BitwiseOperation(operation) => match operation {
+            BitwiseOperationExpression::Not { not, .. } => Some(*not),
+            BitwiseOperationExpression::And { left, .. }
+            | BitwiseOperationExpression::Or { left, .. }
+            | BitwiseOperationExpression::Xor { left, .. }
+            | BitwiseOperationExpression::LeftShift { left, .. }
+            | BitwiseOperationExpression::RightShift { left, .. } => start(left),
+        },
+        Expression::ComparisonOperation(operation) => match operation {
+            ComparisonOperationExpression::Equal { left, .. }
+            | ComparisonOperationExpression::Identical { left, .. }
+            | ComparisonOperationExpression::NotEqual { left, .. }
+            | ComparisonOperationExpression::AngledNotEqual { left, .. }
+            | ComparisonOperationExpression::NotIdentical { left, .. }
+            | ComparisonOperationExpression::LessThan { left, .. }
+            | ComparisonOperationExpression::GreaterThan { left, .. }
+            | ComparisonOperationExpression::LessThanOrEqual { left, .. }
+            | ComparisonOperationExpression::GreaterThanOrEqual { left, .. }
+            | ComparisonOperationExpression::Spaceship { left, .. } => start(left),
+        },
+        Expression::LogicalOperation(operation) => match operation {
+            LogicalOperationExpression::Not { bang, .. } => Some(*bang),
+            LogicalOperationExpression::And { left, .. }
+            | LogicalOperationExpression::Or { left, .. }
+            | LogicalOperationExpression::LogicalAnd { left, .. }
+            | LogicalOperationExpression::LogicalOr { left, .. }
+            | LogicalOperationExpression::LogicalXor { left, .. } => start(left),
+        },
+        Expression::RangeOperation(
+            RangeOperationExpression::Exclusive { lower_bound, .. }
+            | RangeOperationExpression::Inclusive { lower_bound, .. }
+            | RangeOperationExpression::Endless { lower_bound, .. },
+        ) => start(lower_bound),
+        Expression::Concat(concat) => start(&concat.left),
+        Expression::ConcatMany(concat) => concat.parts.first().and_then(start),
+        Expression::Instanceof(instanceof) => start(&instanceof.left),
+        Expression::Reference(reference) => Some(reference.ampersand),
+        Expression::Parenthesized(parenthesized) => Some(parenthesized.start),
+        Expression::ErrorSuppress(suppress) => Some(suppress.at),
+        Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => Some(identifier.span),
+        Expression::Identifier(Identifier::DynamicIdentifier(identifier)) => Some(identifier.start),
+        Expression::Variable(Variable::SimpleVariable(variable)) => Some(variable.span),
+        Expression::Variable(Variable::VariableVariable(variable)) => Some(variable.span),
+        Expression::Variable(Variable::BracedVariableVariable(variable)) => Some(variable.start),
+        Expression::Include(include) => Some(include.include),
+        Expression::IncludeOnce(include) => Some(include.include_once),
+        Expression::Require(require) => Some(require.require),
+        Expression::RequireOnce(require) => Some(require.require_once),
+        Expression::FunctionCall(call) => start(&call.target),
+        Expression::FunctionClosureCreation(creation) => start(&creation.target),
+        Expression::MethodCall(call) => start(&call.target),
+        Expression::MethodClosureCreation(creation) => start(&creation.target),
+        Expression::NullsafeMethodCall(call) => start(&call.target),
+        Expression::StaticMethodCall(call) => start(&call.target),
+        Expression::StaticVariableMethodCall(call) => start(&call.target),
+        Expression::StaticMethodClosureCreation(creation) => start(&creation.target),
+        Expression::StaticVariableMethodClosureCreation(creation) => start(&creation.target),
+        Expression::PropertyFetch(fetch) => start(&fetch.target),
+        Expression::NullsafePropertyFetch(fetch) => start(&fetch.target),
+        Expression::StaticPropertyFetch(fetch) => start(&fetch.target),
+        Expression::ConstantFetch(fetch) => start(&fetch.target),
+        Expression::ShortArray(array) => Some(array.start),
+        Expression::Array(array) => Some(array.array),
+        Expression::List(list) => Some(list.list),
+        Expression::Closure(closure) => Some(closure.r#static.unwrap_or(closure.function)),
+        Expression::ArrowFunction(function) => Some(function.r#static.unwrap_or(function.r#fn)),
+        Expression::New(new) => Some(new.new),
+        Expression::AnonymousClass(class) => Some(class.class),
+        Expression::InterpolatedString(string) => string.parts.iter().find_map(|part| match part {
+            StringPart::Expression(part) => start(&part.expression),
+            StringPart::Literal(_) => None,
+        }),
+        Expression::ArrayIndex(index) => start(&index.array),
+        Expression::MagicConstant(
+            MagicConstantExpression::Directory(span)
+            | MagicConstantExpression::File(span)
+            | MagicConstantExpression::Line(span)
+            | MagicConstantExpression::Class(span)
+            | MagicConstantExpression::Function(span)
+            | MagicConstantExpression::Method(span)
+            | MagicConstantExpression::Namespace(span)
+            | MagicConstantExpression::Trait(span)
+            | MagicConstantExpression::CompilerHaltOffset(span),
+        ) => Some(*span),
+        Expression::ShortTernary(ternary) => start(&ternary.condition),
+        Expression::Ternary(ternary) => start(&ternary.condition),
+        Expression::Coalesce(coalesce) => start(&coalesce.lhs),
+        Expression::Clone(clone) => Some(clone.clone),
+        Expression::Match(r#match) => Some(r#match.keyword),
+        Expression::ShortMatch(r#match) => Some(r#match.keyword),
+        Expression::Throw(throw) => Some(throw.throw),
+        Expression::Yield(r#yield) => Some(r#yield.r#yield),
+        Expression::YieldFrom(r#yield) => Some(r#yield.r#yield),
+        Expression::Cast(cast) => Some(cast.cast),
+        Expression::Bool(bool) => Some(bool.span),
+        Expression::Null(span)
+        | Expression::Static(span)
+        | Expression::Self_(span)
+        | Expression::Parent(span)
+        | Expression::Error(span) => Some(*span),
+        Expression::Heredoc(_)
+        | Expression::Nowdoc(_)
+        | Expression::ShellExec(_)
+        | Expression::Noop => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::ArrayExpression;
+    use crate::parser::ast::ReturnStatement;
+    use crate::parser::ast::ShortArrayExpression;
+    use crate::parser::ast::Statement;
+
+    /// A value of the test interpreter, with arrays and objects as ordered pairs.
+    #[derive(Debug, PartialEq, Clone)]
+    enum Value {
+        Null,
+        Bool(bool),
+        Int(i64),
+        Float(f64),
+        String(Vec<u8>),
+        Array(Vec<(Value, Value)>),
+        Object(Vec<(Vec<u8>, Value)>),
+    }
+
+    impl Value {
+        fn truthy(&self) -> bool {
+            match self {
+                Value::Null => false,
+                Value::Bool(value) => *value,
+                Value::Int(value) => *value != 0,
+                Value::Float(value) => *value != 0.0,
+                Value::String(value) => !value.is_empty() && value != b"0",
+                Value::Array(items) => !items.is_empty(),
+                Value::Object(_) => true,
+            }
+        }
+
+        fn number(&self) -> f64 {
+            match self {
+                Value::Int(value) => *value as f64,
+                Value::Float(value) => *value,
+                Value::Bool(value) => f64::from(u8::from(*value)),
+                Value::String(value) => float(value),
+                _ => 0.0,
+            }
+        }
+
+        fn int(&self) -> i64 {
+            match self {
+                Value::Int(value) => *value,
+                value => value.number() as i64,
+            }
+        }
+
+        fn string(&self) -> Vec<u8> {
+            match self {
+                Value::Null | Value::Bool(false) => vec![],
+                Value::Bool(true) => b"1".to_vec(),
+                Value::Int(value) => value.to_string().into_bytes(),
+                Value::Float(value) => value.to_string().into_bytes(),
+                Value::String(value) => value.clone(),
+                value => panic!("can't convert {:?} to a string", value),
+            }
+        }
+    }
+
+    /// The variables, constants and functions known to both evaluators.
+    fn variable(name: &[u8]) -> Value {
+        match name {
+            b"name" => Value::String(b"pxp".to_vec()),
+            b"count" => Value::Int(3),
+            b"list" => Value::Array(vec![
+                (Value::Int(0), Value::Int(10)),
+                (Value::Int(1), Value::Int(20)),
+            ]),
+            b"point" => Value::Object(vec![(b"x".to_vec(), Value::Float(1.5))]),
+            _ => Value::Null,
+        }
+    }
+
+    fn constant(name: &[u8]) -> Value {
+        match name {
+            b"PHP_INT_SIZE" => Value::Int(8),
+            name => panic!("undefined constant {}", String::from_utf8_lossy(name)),
+        }
+    }
+
+    fn call(function: &Value, arguments: Vec<Value>) -> Value {
+        match (function.string().as_slice(), arguments.as_slice()) {
+            (b"strlen", [value]) => Value::Int(value.string().len() as i64),
+            (b"max", [first, rest @ ..]) => rest.iter().fold(first.clone(), |max, value| {
+                if value.number() > max.number() {
+                    value.clone()
+                } else {
+                    max
+                }
+            }),
+            (name, _) => panic!("undefined function {}", String::from_utf8_lossy(name)),
+        }
+    }
+
+    fn array(items: Vec<(Option<Value>, Value)>) -> Value {
+        let mut array: Vec<(Value, Value)> = vec![];
+        let mut next = 0;
+        for (key, value) in items {
+            let key = match key {
+                Some(Value::String(key)) => Value::String(key),
+                Some(key) => Value::Int(key.int()),
+                None => Value::Int(next),
+            };
+            if let Value::Int(key) = key {
+                next = next.max(key + 1);
+            }
+
+            match array.iter_mut().find(|(existing, _)| *existing == key) {
+                Some(item) => item.1 = value,
+                None => array.push((key, value)),
+            }
+        }
+
+        Value::Array(array)
+    }
+
+    fn fetch_index(array: &Value, index: &Value) -> Value {
+        let Value::Array(items) = array else {
+            return Value::Null;
+        };
+
+        items
+            .iter()
+            .find(|(key, _)| key == index || key.string() == index.string())
+            .map_or(Value::Null, |(_, value)| value.clone())
+    }
+
+    fn fetch_prop(object: &Value, name: &[u8]) -> Value {
+        let Value::Object(properties) = object else {
+            return Value::Null;
+        };
+
+        properties
+            .iter()
+            .find(|(property, _)| property == name)
+            .map_or(Value::Null, |(_, value)| value.clone())
+    }
+
+    fn unary(op: PrefixOp, value: Value) -> Value {
+        match (op, value) {
+            (PrefixOp::Negative, Value::Int(value)) => Value::Int(-value),
+            (PrefixOp::Negative, value) => Value::Float(-value.number()),
+            (PrefixOp::Positive, value @ (Value::Int(_) | Value::Float(_))) => value,
+            (PrefixOp::Positive, value) => Value::Float(value.number()),
+            (PrefixOp::Not, value) => Value::Bool(!value.truthy()),
+            (PrefixOp::BitwiseNot, value) => Value::Int(!value.int()),
+        }
+    }
+
+    fn binary(op: InfixOp, left: Value, right: Value) -> Value {
+        let ints = matches!((&left, &right), (Value::Int(_), Value::Int(_)));
+        let arithmetic = |int: fn(i64, i64) -> Option<i64>, float: fn(f64, f64) -> f64| match int(
+            left.int(),
+            right.int(),
+        ) {
+            Some(value) if ints => Value::Int(value),
+            _ => Value::Float(float(left.number(), right.number())),
+        };
+        let compare = || match (&left, &right) {
+            (Value::String(left), Value::String(right)) => left.cmp(right),
+            _ => left.number().total_cmp(&right.number()),
+        };
+
+        match op {
+            InfixOp::Add => arithmetic(i64::checked_add, |a, b| a + b),
+            InfixOp::Subtract => arithmetic(i64::checked_sub, |a, b| a - b),
+            InfixOp::Multiply => arithmetic(i64::checked_mul, |a, b| a * b),
+            InfixOp::Divide => {
+                arithmetic(|a, b| (b != 0 && a % b == 0).then(|| a / b), |a, b| a / b)
+            }
+            InfixOp::Modulo => Value::Int(left.int() % right.int()),
+            InfixOp::Power => arithmetic(
+                |a, b| u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+                f64::powf,
+            ),
+            InfixOp::Concat => Value::String([left.string(), right.string()].concat()),
+            InfixOp::BitwiseAnd => Value::Int(left.int() & right.int()),
+            InfixOp::BitwiseOr => Value::Int(left.int() | right.int()),
+            InfixOp::BitwiseXor => Value::Int(left.int() ^ right.int()),
+            InfixOp::LeftShift => Value::Int(left.int() << right.int()),
+            InfixOp::RightShift => Value::Int(left.int() >> right.int()),
+            InfixOp::Equal => Value::Bool(compare().is_eq()),
+            InfixOp::NotEqual => Value::Bool(compare().is_ne()),
+            InfixOp::Identical => Value::Bool(left == right),
+            InfixOp::NotIdentical => Value::Bool(left != right),
+            InfixOp::LessThan => Value::Bool(compare().is_lt()),
+            InfixOp::GreaterThan => Value::Bool(compare().is_gt()),
+            InfixOp::LessThanOrEqual => Value::Bool(compare().is_le()),
+            InfixOp::GreaterThanOrEqual => Value::Bool(compare().is_ge()),
+            InfixOp::Spaceship => Value::Int(compare() as i64),
+            InfixOp::LogicalXor => Value::Bool(left.truthy() != right.truthy()),
+        }
+    }
+
+    /// Run the ops of a lowered expression.
+    fn run(ops: &[ExprOp]) -> Value {
+        let mut stack = vec![];
+        let mut pc = 0;
+        while let Some(op) = ops.get(pc) {
+            pc += 1;
+
+            match op {
+                ExprOp::PushInt(value) => stack.push(Value::Int(*value)),
+                ExprOp::PushFloat(value) => stack.push(Value::Float(*value)),
+                ExprOp::PushString(value) => stack.push(Value::String(value.to_vec())),
+                ExprOp::PushBool(value) => stack.push(Value::Bool(*value)),
+                ExprOp::PushNull => stack.push(Value::Null),
+                ExprOp::LoadVar(name) => stack.push(variable(name)),
+                ExprOp::LoadConst(name) => stack.push(constant(name)),
+                ExprOp::UnaryOp(op) => {
+                    let value = stack.pop().unwrap();
+                    stack.push(unary(*op, value));
+                }
+                ExprOp::BinaryOp(op) => {
+                    let right = stack.pop().unwrap();
+                    let left = stack.pop().unwrap();
+                    stack.push(binary(*op, left, right));
+                }
+                ExprOp::Call { argc } => {
+                    let arguments = stack.split_off(stack.len() - argc);
+                    let function = stack.pop().unwrap();
+                    stack.push(call(&function, arguments));
+                }
+                ExprOp::MakeArray { keyed } => {
+                    let mut items = vec![];
+                    for keyed in keyed.iter().rev() {
+                        let value = stack.pop().unwrap();
+                        let key = keyed.then(|| stack.pop().unwrap());
+                        items.push((key, value));
+                    }
+                    items.reverse();
+                    stack.push(array(items));
+                }
+                ExprOp::FetchIndex => {
+                    let index = stack.pop().unwrap();
+                    let array = stack.pop().unwrap();
+                    stack.push(fetch_index(&array, &index));
+                }
+                ExprOp::FetchProp { name } => {
+                    let object = stack.pop().unwrap();
+                    stack.push(fetch_prop(&object, name));
+                }
+                ExprOp::Dup => stack.push(stack.last().unwrap().clone()),
+                ExprOp::Pop => {
+                    stack.pop().unwrap();
+                }
+                ExprOp::Jump { offset } => pc += offset,
+                ExprOp::JumpIf { offset } => {
+                    if stack.pop().unwrap().truthy() {
+                        pc += offset;
+                    }
+                }
+                ExprOp::JumpUnless { offset } => {
+                    if !stack.pop().unwrap().truthy() {
+                        pc += offset;
+                    }
+                }
+            }
+        }
+
+        assert_eq!(stack.len(), 1, "{:?}", ops);
+
+        stack.pop().unwrap()
+    }
+
+    /// Evaluate the same subset by walking the tree, as a reference for [`run`].
+    fn evaluate(expression: &Expression) -> Value {
+        match expression {
+            Expression::Literal(Literal::Integer(integer)) => Value::Int(integer.to_i64().unwrap()),
+            Expression::Literal(Literal::Float(value)) => Value::Float(float(&value.value)),
+            Expression::Literal(Literal::String(string)) => {
+                Value::String(string.value[1..string.value.len() - 1].to_vec())
+            }
+            Expression::Bool(bool) => Value::Bool(bool.value),
+            Expression::Null(_) => Value::Null,
+            Expression::Variable(Variable::SimpleVariable(simple)) => variable(&simple.name[1..]),
+            Expression::Identifier(Identifier::SimpleIdentifier(name)) => constant(&name.value),
+            Expression::Parenthesized(parenthesized) => evaluate(&parenthesized.expr),
+            Expression::ArithmeticOperation(operation) => match operation {
+                ArithmeticOperationExpression::Addition { left, right, .. } => {
+                    binary(InfixOp::Add, evaluate(left), evaluate(right))
+                }
+                ArithmeticOperationExpression::Subtraction { left, right, .. } => {
+                    binary(InfixOp::Subtract, evaluate(left), evaluate(right))
+                }
+                ArithmeticOperationExpression::Multiplication { left, right, .. } => {
+                    binary(InfixOp::Multiply, evaluate(left), evaluate(right))
+                }
+                ArithmeticOperationExpression::Division { left, right, .. } => {
+                    binary(InfixOp::Divide, evaluate(left), evaluate(right))
+                }
+                ArithmeticOperationExpression::Modulo { left, right, .. } => {
+                    binary(InfixOp::Modulo, evaluate(left), evaluate(right))
+                }
+                ArithmeticOperationExpression::Exponentiation { left, right, .. } => {
+                    binary(InfixOp::Power, evaluate(left), evaluate(right))
+                }
+                ArithmeticOperationExpression::Negative { right, .. } => {
+                    unary(PrefixOp::Negative, evaluate(right))
+                }
+                operation => panic!("no reference for {:?}", operation),
+            },
+            Expression::BitwiseOperation(operation) => match operation {
+                BitwiseOperationExpression::And { left, right, .. } => {
+                    binary(InfixOp::BitwiseAnd, evaluate(left), evaluate(right))
+                }
+                BitwiseOperationExpression::Or { left, right, .. } => {
+                    binary(InfixOp::BitwiseOr, evaluate(left), evaluate(right))
+                }
+                BitwiseOperationExpression::Xor { left, right, .. } => {
+                    binary(InfixOp::BitwiseXor, evaluate(left), evaluate(right))
+                }
+                BitwiseOperationExpression::LeftShift { left, right, .. } => {
+                    binary(InfixOp::LeftShift, evaluate(left), evaluate(right))
+                }
+                BitwiseOperationExpression::RightShift { left, right, .. } => {
+                    binary(InfixOp::RightShift, evaluate(left), evaluate(right))
+                }
+                BitwiseOperationExpression::Not { right, .. } => {
+                    unary(PrefixOp::BitwiseNot, evaluate(right))
+                }
+            },
+            Expression::ComparisonOperation(operation) => match operation {
+                ComparisonOperationExpression::Equal { left, right, .. } => {
+                    binary(InfixOp::Equal, evaluate(left), evaluate(right))
+                }
+                ComparisonOperationExpression::NotIdentical { left, right, .. } => {
+                    binary(InfixOp::NotIdentical, evaluate(left), evaluate(right))
+                }
+                ComparisonOperationExpression::LessThan { left, right, .. } => {
+                    binary(InfixOp::LessThan, evaluate(left), evaluate(right))
+                }
+                ComparisonOperationExpression::GreaterThan { left, right, .. } => {
+                    binary(InfixOp::GreaterThan, evaluate(left), evaluate(right))
+                }
+                ComparisonOperationExpression::GreaterThanOrEqual { left, right, .. } => {
+                    binary(InfixOp::GreaterThanOrEqual, evaluate(left), evaluate(right))
+                }
+                ComparisonOperationExpression::Spaceship { left, right, .. } => {
+                    binary(InfixOp::Spaceship, evaluate(left), evaluate(right))
+                }
+                operation => panic!("no reference for {:?}", operation),
+            },
+            Expression::LogicalOperation(operation) => match operation {
+                LogicalOperationExpression::And { left, right, .. }
+                | LogicalOperationExpression::LogicalAnd { left, right, .. } => {
+                    Value::Bool(evaluate(left).truthy() && evaluate(right).truthy())
+                }
+                LogicalOperationExpression::Or { left, right, .. }
+                | LogicalOperationExpression::LogicalOr { left, right, .. } => {
+                    Value::Bool(evaluate(left).truthy() || evaluate(right).truthy())
+                }
+                LogicalOperationExpression::LogicalXor { left, right, .. } => {
+                    binary(InfixOp::LogicalXor, evaluate(left), evaluate(right))
+                }
+                LogicalOperationExpression::Not { right, .. } => {
+                    unary(PrefixOp::Not, evaluate(right))
+                }
+            },
+            Expression::Concat(concat) => binary(
+                InfixOp::Concat,
+                evaluate(&concat.left),
+                evaluate(&concat.right),
+            ),
+            Expression::ConcatMany(concat) => Value::String(
+                concat
+                    .parts
+                    .iter()
+                    .flat_map(|part| evaluate(part).string())
+                    .collect(),
+            ),
+            Expression::Ternary(ternary) => {
+                if evaluate(&ternary.condition).truthy() {
+                    evaluate(&ternary.then)
+                } else {
+                    evaluate(&ternary.r#else)
+                }
+            }
+            Expression::ShortTernary(ternary) => {
+                let condition = evaluate(&ternary.condition);
+                if condition.truthy() {
+                    condition
+                } else {
+                    evaluate(&ternary.r#else)
+                }
+            }
+            Expression::Coalesce(coalesce) => match evaluate(&coalesce.lhs) {
+                Value::Null => evaluate(&coalesce.rhs),
+                value => value,
+            },
+            Expression::FunctionCall(call_expression) => {
+                let function = match call_expression.target.as_ref() {
+                    Expression::Identifier(Identifier::SimpleIdentifier(name)) => {
+                        Value::String(name.value.to_vec())
+                    }
+                    target => evaluate(target),
+                };
+                let arguments = call_expression
+                    .arguments
+                    .arguments
+                    .iter()
+                    .map(|argument| match argument {
+                        Argument::Positional(argument) => evaluate(&argument.value),
+                        Argument::Named(_) => unreachable!(),
+                    })
+                    .collect();
+
+                call(&function, arguments)
+            }
+            Expression::ShortArray(ShortArrayExpression { items, .. })
+            | Expression::Array(ArrayExpression { items, .. }) => array(
+                items
+                    .iter()
+                    .map(|item| match item {
+                        ArrayItem::Value { value } => (None, evaluate(value)),
+                        ArrayItem::KeyValue { key, value, .. } => {
+                            (Some(evaluate(key)), evaluate(value))
+                        }
+                        item => panic!("no reference for {:?}", item),
+                    })
+                    .collect(),
+            ),
+            Expression::ArrayIndex(index) => fetch_index(
+                &evaluate(&index.array),
+                &evaluate(index.index.as_ref().unwrap()),
+            ),
+            Expression::PropertyFetch(fetch) => match fetch.property.as_ref() {
+                Expression::Identifier(Identifier::SimpleIdentifier(name)) => {
+                    fetch_prop(&evaluate(&fetch.target), &name.value)
+                }
+                property => panic!("no reference for {:?}", property),
+            },
+            expression => panic!("no reference for {:?}", expression),
+        }
+    }
+
+    fn expression(code: &str) -> Expression {
+        let program = crate::parse(&format!("<?php {};", code)).unwrap();
+
+        match &program[1] {
+            Statement::Expression(statement) => statement.expression.clone(),
+            statement => panic!("{:?} is not an expression", statement),
+        }
+    }
+
+    fn string(value: &str) -> Value {
+        Value::String(value.as_bytes().to_vec())
+    }
+
+    /// Expressions whose lowering is run and compared with the tree-walking reference,
+    /// with the value that PHP gives them. `missing()` is never called.
+    fn expressions() -> Vec<(&'static str, Value)> {
+        vec![
+            ("1 + 2 * 3", Value::Int(7)),
+            ("(1 + 2) * 3 - -4", Value::Int(13)),
+            ("2 ** 10 % 1000", Value::Int(24)),
+            ("7 / 2", Value::Float(3.5)),
+            ("0x10 | 0b1 ^ ~0 & 6 << 1", Value::Int(29)),
+            ("1.5 + 1", Value::Float(2.5)),
+            ("'a' . \"b\\n\" . 1 . PHP_INT_SIZE", string("ab\n18")),
+            ("1 <=> 2", Value::Int(-1)),
+            ("$count >= 3 and $count !== '3'", Value::Bool(true)),
+            ("!true || false xor true", Value::Bool(true)),
+            ("false && missing()", Value::Bool(false)),
+            ("true || missing()", Value::Bool(true)),
+            ("$name ?: missing()", string("pxp")),
+            ("$undefined ?: 'fallback'", string("fallback")),
+            ("$undefined ?? $list[5] ?? 'default'", string("default")),
+            ("$list[1] ?? missing()", Value::Int(20)),
+            ("$count > 2 ? 'many' : missing()", string("many")),
+            (
+                "$count < 2 ? missing() : ($count == 3 ? 'three' : 'other')",
+                string("three"),
+            ),
+            ("strlen($name . 'x') + max(1, $count, 2)", Value::Int(7)),
+            ("[1, 'a' => 2, 3][1]", Value::Int(3)),
+            ("array(5 => 'five', 'six')[6]", string("six")),
+            ("$point->x * 2", Value::Float(3.0)),
+        ]
+    }
+
+    #[test]
+    fn test_lowering_matches_the_tree() {
+        for (code, value) in expressions() {
+            let expression = expression(code);
+            let ops = expression.to_postfix().unwrap();
+
+            assert_eq!(run(&ops), evaluate(&expression), "{}", code);
+            assert_eq!(run(&ops), value, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_ternary_jumps() {
+        assert_eq!(
+            expression("$a ? 1 : 2").to_postfix().unwrap(),
+            vec![
+                ExprOp::LoadVar("a".into()),
+                ExprOp::JumpUnless { offset: 2 },
+                ExprOp::PushInt(1),
+                ExprOp::Jump { offset: 1 },
+                ExprOp::PushInt(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_nodes() {
+        for (code, node, column) in [
+            ("$f = function () {}", "an assignment", 7),
+            ("1 + function () {}", "a closure", 11),
+            ("[fn () => 1]", "an arrow function", 8),
+            ("match ($a) { default => 1 }", "a `match`", 7),
+            ("f(...$args)", "an unpacked argument", 9),
+            ("f(name: 1)", "a named argument", 9),
+            ("$a->b()", "a method call", 7),
+        ] {
+            let error = expression(code).to_postfix().unwrap_err();
+
+            assert_eq!(
+                (error.node, error.span.unwrap().column),
+                (node, column),
+                "{}",
+                code
+            );
+        }
+
+        let program = crate::parse("<?php function g() { return 1 + yield $y; }").unwrap();
+        let Statement::Function(function) = &program[1] else {
+            panic!("{:?} is not a function", program[1]);
+        };
+        let Statement::Return(ReturnStatement {
+            value: Some(value), ..
+        }) = &function.body.statements[0]
+        else {
+            panic!("{:?} is not a return", function.body.statements[0]);
+        };
+        let error = value.to_postfix().unwrap_err();
+
+        assert_eq!(error.node, "a `yield`");
+        assert_eq!(error.span.unwrap().column, 33);
+    }
+}
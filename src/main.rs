@@ -1,5 +1,18 @@
-use clap::Parser;
-use std::io::Result;
+use clap::{Parser, ValueEnum};
+use pxp_parser::{
+    diagnostics_to_json, render_report, ParserConfig, PositionEncoding, RenderOptions,
+};
+use std::io::{IsTerminal, Result};
+use std::path::Path;
+
+#[derive(ValueEnum, Clone, Copy, Default, Debug)]
+enum Format {
+    /// A report for people to read
+    #[default]
+    Text,
+    /// A JSON object with the diagnostics of the file, in the shape of the Language Server Protocol
+    Json,
+}
 
 #[derive(Parser, Default, Debug)]
 #[clap(version, about = "A PHP Parser")]
@@ -11,6 +24,15 @@ struct Arguments {
     #[clap(short, long)]
     /// Print as json
     json: bool,
+    #[clap(short, long)]
+    /// Only report the errors and warnings of the file
+    check: bool,
+    #[clap(long, value_enum, default_value_t)]
+    /// How `--check` reports the errors and warnings
+    format: Format,
+    #[clap(long)]
+    /// Count the columns of `--format json` in bytes instead of UTF-16 code units
+    byte_columns: bool,
 }
 
 fn main() -> Result<()> {
@@ -21,7 +43,17 @@ fn main() -> Result<()> {
     let silent = args.silent;
     let print_json = args.json;
 
-    match pxp_parser::parse(&contents) {
+    if args.check {
+        let encoding = if args.byte_columns {
+            PositionEncoding::Utf8
+        } else {
+            PositionEncoding::Utf16
+        };
+
+        return check(&file, &contents, args.format, encoding);
+    }
+
+    match pxp_parser::parse_with_config(&contents, ParserConfig::new().source_name(&file)) {
         Ok(ast) => {
             // if --silent is passed, don't print anything
             if silent {
@@ -44,7 +76,7 @@ fn main() -> Result<()> {
             }
         }
         Err(error) => {
-            println!("{}", error.report(&contents, Some(&file), true, false)?);
+            println!("{}", error.report(&contents, None, true, false)?);
 
             std::process::exit(1);
         }
@@ -52,3 +84,57 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+fn check(file: &str, contents: &str, format: Format, encoding: PositionEncoding) -> Result<()> {
+    let result = pxp_parser::parse_with_warnings(contents, ParserConfig::new().source_name(file));
+    let (diagnostics, failed) = match result {
+        Ok((_, warnings)) => (warnings, false),
+        Err(stack) => (stack.errors, true),
+    };
+
+    match format {
+        Format::Text => {
+            let options = RenderOptions::new().colored(std::io::stdout().is_terminal());
+
+            print!(
+                "{}",
+                render_report(contents.as_bytes(), file, &diagnostics, options)
+            );
+        }
+        Format::Json => println!(
+            "{}",
+            diagnostics_to_json(&file_uri(file), contents.as_bytes(), &diagnostics, encoding)
+        ),
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// The `file://` URI of a file, with the bytes that can't be in a path of a URI
+/// percent-encoded.
+fn file_uri(file: &str) -> String {
+    let path = Path::new(file)
+        .canonicalize()
+        .unwrap_or_else(|_| Path::new(file).to_path_buf());
+
+    let mut uri = "file://".to_string();
+    if !path.starts_with("/") {
+        uri.push('/');
+    }
+
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            b'\\' => uri.push('/'),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
@@ -11,3 +11,154 @@ impl<N: Node> Node for Vec<N> {
         self.iter_mut().map(|a| a as &mut dyn Node).collect()
     }
 }
+
+/// Define an enum of nodes along with its [`Node`] and [`VisitSpans`] implementations,
+/// so that adding a variant can't leave it out of either traversal.
+///
+/// Each tuple variant has its value as its only child, and unit variants have none. A
+/// tuple variant marked `#[leaf]` has no children either, for values that aren't nodes,
+/// like a [`Span`](crate::lexer::token::Span), but its spans are still visited. Every
+/// variant needs a trailing comma.
+///
+/// [`VisitSpans`]: crate::spans::VisitSpans
+///
+/// ```ignore
+/// node_enum! {
+///     #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+///     pub enum Literal {
+///         String(LiteralString),
+///         Integer(LiteralInteger),
+///         Float(LiteralFloat),
+///     }
+/// }
+/// ```
+macro_rules! node_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($variants:tt)*
+        }
+    ) => {
+        $crate::node::node_enum!(@munch [$(#[$meta])* $vis enum $name] [] [] $($variants)*);
+    };
+    (@munch [$($enum:tt)*] [$($variants:tt)*] [$($arms:tt)*]
+        #[leaf] $(#[$meta:meta])* $variant:ident($type:ty), $($rest:tt)*
+    ) => {
+        $crate::node::node_enum!(@munch [$($enum)*]
+            [$($variants)* $(#[$meta])* $variant($type),]
+            [$($arms)* $variant [value] vec![],]
+            $($rest)*);
+    };
+    (@munch [$($enum:tt)*] [$($variants:tt)*] [$($arms:tt)*]
+        $(#[$meta:meta])* $variant:ident($type:ty), $($rest:tt)*
+    ) => {
+        $crate::node::node_enum!(@munch [$($enum)*]
+            [$($variants)* $(#[$meta])* $variant($type),]
+            [$($arms)* $variant [value] vec![value],]
+            $($rest)*);
+    };
+    (@munch [$($enum:tt)*] [$($variants:tt)*] [$($arms:tt)*]
+        $(#[$meta:meta])* $variant:ident, $($rest:tt)*
+    ) => {
+        $crate::node::node_enum!(@munch [$($enum)*]
+            [$($variants)* $(#[$meta])* $variant,]
+            [$($arms)* $variant [] vec![],]
+            $($rest)*);
+    };
+    (@munch [$($enum:tt)* ] [$($variants:tt)*] [$($arms:tt)*]) => {
+        $crate::node::node_enum!(@emit [$($enum)*] [$($variants)*] [$($arms)*]);
+    };
+    (@emit [$(#[$meta:meta])* $vis:vis enum $name:ident]
+        [$($variants:tt)*]
+        [$($variant:ident [$($value:ident)?] $children:expr,)*]
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variants)*
+        }
+
+        impl $crate::node::Node for $name {
+            #[allow(unused_variables)]
+            fn children(&mut self) -> Vec<&mut dyn $crate::node::Node> {
+                match self {
+                    $($name::$variant $(($value))? => $children,)*
+                }
+            }
+        }
+
+        impl $crate::spans::VisitSpans for $name {
+            fn visit_spans<'a>(&'a self, visitor: &mut dyn $crate::spans::SpanVisitor<'a>) {
+                visitor.enter($crate::spans::Segment::Node(self));
+                match self {
+                    $($name::$variant $(($value))? => {
+                        visitor.enter($crate::spans::Segment::Variant(stringify!($variant)));
+                        $($crate::spans::VisitSpans::visit_spans($value, visitor);)?
+                        visitor.leave();
+                    })*
+                }
+                visitor.leave();
+            }
+
+            fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut $crate::lexer::token::Span)) {
+                match self {
+                    $($name::$variant $(($value))? => {
+                        $($crate::spans::VisitSpans::visit_spans_mut($value, f);)?
+                    })*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use node_enum;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::Span;
+    use crate::spans::VisitSpans;
+
+    struct Leaf;
+
+    impl Node for Leaf {}
+
+    impl VisitSpans for Leaf {}
+
+    node_enum! {
+        enum Dummy {
+            /// A node.
+            Node(Leaf),
+            #[leaf]
+            Noop(Span),
+            Nothing,
+        }
+    }
+
+    #[test]
+    fn test_node_enum_children() {
+        assert_eq!(Dummy::Node(Leaf).children().len(), 1);
+        let mut noop = Dummy::Noop(Span::new(1, 1, 0));
+        assert!(noop.children().is_empty());
+        assert!(matches!(noop, Dummy::Noop(_)));
+        assert!(Dummy::Nothing.children().is_empty());
+
+        let mut node = Dummy::Node(Leaf);
+        let child = node.children().remove(0);
+        assert!(crate::downcast::downcast::<Leaf>(child).is_some());
+    }
+
+    #[test]
+    fn test_node_enum_spans() {
+        let mut noop = Dummy::Noop(Span::new(1, 1, 0));
+        noop.visit_spans_mut(&mut |span| span.position += 1);
+
+        let mut positions = vec![];
+        noop.visit_spans(&mut |span: &Span| positions.push(span.position));
+        assert_eq!(positions, [1]);
+
+        let mut count = 0;
+        Dummy::Node(Leaf).visit_spans(&mut |_: &Span| count += 1);
+        Dummy::Nothing.visit_spans(&mut |_: &Span| count += 1);
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::downcast::downcast_mut;
+use crate::lexer::token::Span;
+use crate::node::Node;
+use crate::parser::ast::classes::AnonymousClassBody;
+use crate::parser::ast::classes::AnonymousClassMember;
+use crate::parser::ast::classes::ClassBody;
+use crate::parser::ast::classes::ClassMember;
+use crate::parser::ast::enums::BackedEnumBody;
+use crate::parser::ast::enums::BackedEnumMember;
+use crate::parser::ast::enums::UnitEnumBody;
+use crate::parser::ast::enums::UnitEnumMember;
+use crate::parser::ast::interfaces::InterfaceBody;
+use crate::parser::ast::interfaces::InterfaceMember;
+use crate::parser::ast::modifiers::ConstantModifier;
+use crate::parser::ast::modifiers::ConstantModifierGroup;
+use crate::parser::ast::modifiers::MethodModifier;
+use crate::parser::ast::modifiers::MethodModifierGroup;
+use crate::parser::ast::modifiers::PropertyModifier;
+use crate::parser::ast::properties::Property;
+use crate::parser::ast::properties::PropertyEntry;
+use crate::parser::ast::traits::TraitBody;
+use crate::parser::ast::traits::TraitMember;
+use crate::parser::ast::Program;
+use crate::traverser::Visitor;
+
+/// Fill in the modifiers that PHP implies on class-like members, so consumers don't
+/// have to re-implement the defaulting rules:
+///
+/// - `public` on methods, constants and properties without a visibility,
+/// - `public` and `abstract` on interface methods,
+/// - `public` on interface constants.
+///
+/// Implied modifiers are added to the `synthetic` list of the member's modifier group,
+/// using the span of the `function` or `const` keyword, or of the first property, as
+/// they have no span of their own. Running the pass more than once has no further effect.
+///
+/// Properties declared with `var` have no modifier group, and are left as they are.
+pub fn normalize_modifiers(program: &mut Program) {
+    ModifierNormalizer.visit_node(program).unwrap();
+}
+
+struct ModifierNormalizer;
+
+impl Visitor<()> for ModifierNormalizer {
+    fn visit(&mut self, node: &mut dyn Node) -> Result<(), ()> {
+        if let Some(body) = downcast_mut::<ClassBody>(node) {
+            for member in &mut body.members {
+                match member {
+                    ClassMember::Constant(constant) => {
+                        constant_modifiers(&mut constant.modifiers, constant.r#const)
+                    }
+                    ClassMember::Property(property) => property_modifiers(property),
+                    ClassMember::AbstractMethod(method) => {
+                        method_modifiers(&mut method.modifiers, method.function, false)
+                    }
+                    ClassMember::AbstractConstructor(constructor) => {
+                        method_modifiers(&mut constructor.modifiers, constructor.function, false)
+                    }
+                    ClassMember::ConcreteMethod(method) => {
+                        method_modifiers(&mut method.modifiers, method.function, false)
+                    }
+                    ClassMember::ConcreteConstructor(constructor) => {
+                        method_modifiers(&mut constructor.modifiers, constructor.function, false)
+                    }
+                    ClassMember::TraitUsage(_) | ClassMember::VariableProperty(_) => {}
+                }
+            }
+        } else if let Some(body) = downcast_mut::<AnonymousClassBody>(node) {
+            for member in &mut body.members {
+                match member {
+                    AnonymousClassMember::Constant(constant) => {
+                        constant_modifiers(&mut constant.modifiers, constant.r#const)
+                    }
+                    AnonymousClassMember::Property(property) => property_modifiers(property),
+                    AnonymousClassMember::ConcreteMethod(method) => {
+                        method_modifiers(&mut method.modifiers, method.function, false)
+                    }
+                    AnonymousClassMember::ConcreteConstructor(constructor) => {
+                        method_modifiers(&mut constructor.modifiers, constructor.function, false)
+                    }
+                    AnonymousClassMember::TraitUsage(_)
+                    | AnonymousClassMember::VariableProperty(_) => {}
+                }
+            }
+        } else if let Some(body) = downcast_mut::<TraitBody>(node) {
+            for member in &mut body.members {
+                match member {
+                    TraitMember::Constant(constant) => {
+                        constant_modifiers(&mut constant.modifiers, constant.r#const)
+                    }
+                    TraitMember::Property(property) => property_modifiers(property),
+                    TraitMember::AbstractMethod(method) => {
+                        method_modifiers(&mut method.modifiers, method.function, false)
+                    }
+                    TraitMember::AbstractConstructor(constructor) => {
+                        method_modifiers(&mut constructor.modifiers, constructor.function, false)
+                    }
+                    TraitMember::ConcreteMethod(method) => {
+                        method_modifiers(&mut method.modifiers, method.function, false)
+                    }
+                    TraitMember::ConcreteConstructor(constructor) => {
+                        method_modifiers(&mut constructor.modifiers, constructor.function, false)
+                    }
+                    TraitMember::TraitUsage(_) | TraitMember::VariableProperty(_) => {}
+                }
+            }
+        } else if let Some(body) = downcast_mut::<UnitEnumBody>(node) {
+            for member in &mut body.members {
+                match member {
+                    UnitEnumMember::Constant(constant) => {
+                        constant_modifiers(&mut constant.modifiers, constant.r#const)
+                    }
+                    UnitEnumMember::Method(method) => {
+                        method_modifiers(&mut method.modifiers, method.function, false)
+                    }
+                    UnitEnumMember::Case(_) | UnitEnumMember::TraitUsage(_) => {}
+                }
+            }
+        } else if let Some(body) = downcast_mut::<BackedEnumBody>(node) {
+            for member in &mut body.members {
+                match member {
+                    BackedEnumMember::Constant(constant) => {
+                        constant_modifiers(&mut constant.modifiers, constant.r#const)
+                    }
+                    BackedEnumMember::Method(method) => {
+                        method_modifiers(&mut method.modifiers, method.function, false)
+                    }
+                    BackedEnumMember::Case(_) | BackedEnumMember::TraitUsage(_) => {}
+                }
+            }
+        } else if let Some(body) = downcast_mut::<InterfaceBody>(node) {
+            for member in &mut body.members {
+                match member {
+                    InterfaceMember::Constant(constant) => {
+                        constant_modifiers(&mut constant.modifiers, constant.r#const)
+                    }
+                    InterfaceMember::Constructor(constructor) => {
+                        method_modifiers(&mut constructor.modifiers, constructor.function, true)
+                    }
+                    InterfaceMember::Method(method) => {
+                        method_modifiers(&mut method.modifiers, method.function, true)
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn method_modifiers(modifiers: &mut MethodModifierGroup, function: Span, interface: bool) {
+    let has_visibility = modifiers.resolved().any(|resolved| {
+        matches!(
+            resolved.modifier,
+            MethodModifier::Public(_) | MethodModifier::Protected(_) | MethodModifier::Private(_)
+        )
+    });
+
+    if !has_visibility {
+        modifiers.synthetic.push(MethodModifier::Public(function));
+    }
+
+    if interface && !modifiers.has_abstract() {
+        modifiers.synthetic.push(MethodModifier::Abstract(function));
+    }
+}
+
+fn constant_modifiers(modifiers: &mut ConstantModifierGroup, r#const: Span) {
+    let has_visibility = modifiers.resolved().any(|resolved| {
+        matches!(
+            resolved.modifier,
+            ConstantModifier::Public(_)
+                | ConstantModifier::Protected(_)
+                | ConstantModifier::Private(_)
+        )
+    });
+
+    if !has_visibility {
+        modifiers.synthetic.push(ConstantModifier::Public(r#const));
+    }
+}
+
+fn property_modifiers(property: &mut Property) {
+    let has_visibility = property.modifiers.resolved().any(|resolved| {
+        matches!(
+            resolved.modifier,
+            PropertyModifier::Public(_)
+                | PropertyModifier::Protected(_)
+                | PropertyModifier::Private(_)
+        )
+    });
+
+    let span = match property.entries.first() {
+        Some(PropertyEntry::Uninitialized { variable })
+        | Some(PropertyEntry::Initialized { variable, .. }) => variable.span,
+        None => property.end,
+    };
+
+    if !has_visibility {
+        property
+            .modifiers
+            .synthetic
+            .push(PropertyModifier::Public(span));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::modifiers::ResolvedModifier;
+    use crate::parser::ast::Statement;
+
+    fn normalize(code: &str) -> Program {
+        let mut program = crate::parse(code).unwrap();
+        normalize_modifiers(&mut program);
+
+        program
+    }
+
+    fn describe<'a, T: std::fmt::Debug + 'a>(
+        modifiers: impl Iterator<Item = ResolvedModifier<'a, T>>,
+    ) -> Vec<(String, bool)> {
+        modifiers
+            .map(|resolved| {
+                let modifier = format!("{:?}", resolved.modifier);
+                let name = modifier.split('(').next().unwrap().to_string();
+
+                (name, resolved.synthetic)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_interface_methods_are_public_and_abstract() {
+        let program = normalize(
+            "<?php interface Foo { function bar(); public function baz(); const QUX = 1; }",
+        );
+
+        let Some(Statement::Interface(interface)) = program.get(1) else {
+            unreachable!()
+        };
+        let members = &interface.body.members;
+
+        let (InterfaceMember::Method(bar), InterfaceMember::Method(baz)) =
+            (&members[0], &members[1])
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            describe(bar.modifiers.resolved()),
+            vec![("Public".to_string(), true), ("Abstract".to_string(), true)]
+        );
+        assert_eq!(
+            bar.modifiers.synthetic[0],
+            MethodModifier::Public(bar.function)
+        );
+        assert_eq!(
+            describe(baz.modifiers.resolved()),
+            vec![
+                ("Public".to_string(), false),
+                ("Abstract".to_string(), true)
+            ]
+        );
+
+        let InterfaceMember::Constant(qux) = &members[2] else {
+            unreachable!()
+        };
+        assert_eq!(
+            describe(qux.modifiers.resolved()),
+            vec![("Public".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn test_class_members_default_to_public() {
+        let program = normalize(
+            "<?php class Foo { function a() {} private function b() {} static $c; const D = 1; var $e; }",
+        );
+
+        let Some(Statement::Class(class)) = program.get(1) else {
+            unreachable!()
+        };
+        let members = &class.body.members;
+
+        let ClassMember::ConcreteMethod(a) = &members[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            describe(a.modifiers.resolved()),
+            vec![("Public".to_string(), true)]
+        );
+
+        let ClassMember::ConcreteMethod(b) = &members[1] else {
+            unreachable!()
+        };
+        assert_eq!(
+            describe(b.modifiers.resolved()),
+            vec![("Private".to_string(), false)]
+        );
+
+        let ClassMember::Property(c) = &members[2] else {
+            unreachable!()
+        };
+        assert_eq!(
+            describe(c.modifiers.resolved()),
+            vec![("Static".to_string(), false), ("Public".to_string(), true)]
+        );
+
+        let ClassMember::Constant(d) = &members[3] else {
+            unreachable!()
+        };
+        assert_eq!(
+            describe(d.modifiers.resolved()),
+            vec![("Public".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn test_normalize_is_idempotent() {
+        let mut program = normalize("<?php enum Foo { case Bar; function baz() {} }");
+        let once = program.clone();
+
+        normalize_modifiers(&mut program);
+
+        assert_eq!(program, once);
+    }
+}
@@ -5,10 +5,12 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::token::Span;
+use crate::node::node_enum;
 use crate::node::Node;
 use crate::parser::ast::comments::CommentGroup;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::Expression;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct PositionalArgument {
@@ -38,19 +40,12 @@ impl Node for NamedArgument {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum Argument {
-    Positional(PositionalArgument),
-    Named(NamedArgument),
-}
-
-impl Node for Argument {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            Argument::Positional(argument) => vec![argument],
-            Argument::Named(argument) => vec![argument],
-        }
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum Argument {
+        Positional(PositionalArgument),
+        Named(NamedArgument),
     }
 }
 
@@ -107,3 +102,11 @@ pub struct ArgumentPlaceholder {
     pub ellipsis: Span,          // `...`
     pub right_parenthesis: Span, // `)`
 }
+
+visit_spans! {
+    struct PositionalArgument { comments, ellipsis, value }
+    struct NamedArgument { comments, name, colon, ellipsis, value }
+    struct ArgumentList { comments, left_parenthesis, arguments, right_parenthesis }
+    struct SingleArgument { comments, left_parenthesis, argument, right_parenthesis }
+    struct ArgumentPlaceholder { comments, left_parenthesis, ellipsis, right_parenthesis }
+}
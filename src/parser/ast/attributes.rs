@@ -7,6 +7,7 @@ use serde::Serialize;
 use crate::lexer::token::Span;
 use crate::parser::ast::arguments::ArgumentList;
 use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -39,3 +40,8 @@ impl IntoIterator for AttributeGroup {
         self.members.into_iter()
     }
 }
+
+visit_spans! {
+    struct Attribute { start, end, name, arguments }
+    struct AttributeGroup { start, end, members }
+}
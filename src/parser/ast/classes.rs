@@ -5,8 +5,10 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::token::Span;
+use crate::node::node_enum;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::comments::DocRef;
 use crate::parser::ast::constant::ClassishConstant;
 use crate::parser::ast::functions::AbstractConstructor;
 use crate::parser::ast::functions::AbstractMethod;
@@ -18,6 +20,7 @@ use crate::parser::ast::properties::Property;
 use crate::parser::ast::properties::VariableProperty;
 use crate::parser::ast::traits::TraitUsage;
 use crate::parser::ast::utils::CommaSeparated;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -51,6 +54,7 @@ impl Node for ClassBody {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct ClassStatement {
+    pub doc: Option<DocRef>,             // `/** ... */`
     pub attributes: Vec<AttributeGroup>, // `#[Qux]`
     #[serde(flatten)]
     pub modifiers: ClassModifierGroup, // `abstract`, `final`
@@ -169,54 +173,39 @@ impl Node for ClassImplements {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum ClassMember {
-    Constant(ClassishConstant),
-    TraitUsage(TraitUsage),
-    Property(Property),
-    VariableProperty(VariableProperty),
-    AbstractMethod(AbstractMethod),
-    AbstractConstructor(AbstractConstructor),
-    ConcreteMethod(ConcreteMethod),
-    ConcreteConstructor(ConcreteConstructor),
-}
-
-impl Node for ClassMember {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            ClassMember::Constant(constant) => vec![constant],
-            ClassMember::TraitUsage(usage) => vec![usage],
-            ClassMember::Property(property) => vec![property],
-            ClassMember::VariableProperty(property) => vec![property],
-            ClassMember::AbstractMethod(method) => vec![method],
-            ClassMember::AbstractConstructor(method) => vec![method],
-            ClassMember::ConcreteMethod(method) => vec![method],
-            ClassMember::ConcreteConstructor(method) => vec![method],
-        }
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum ClassMember {
+        Constant(ClassishConstant),
+        TraitUsage(TraitUsage),
+        Property(Property),
+        VariableProperty(VariableProperty),
+        AbstractMethod(AbstractMethod),
+        AbstractConstructor(AbstractConstructor),
+        ConcreteMethod(ConcreteMethod),
+        ConcreteConstructor(ConcreteConstructor),
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum AnonymousClassMember {
-    Constant(ClassishConstant),
-    TraitUsage(TraitUsage),
-    Property(Property),
-    VariableProperty(VariableProperty),
-    ConcreteMethod(ConcreteMethod),
-    ConcreteConstructor(ConcreteConstructor),
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum AnonymousClassMember {
+        Constant(ClassishConstant),
+        TraitUsage(TraitUsage),
+        Property(Property),
+        VariableProperty(VariableProperty),
+        ConcreteMethod(ConcreteMethod),
+        ConcreteConstructor(ConcreteConstructor),
+    }
 }
 
-impl Node for AnonymousClassMember {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            AnonymousClassMember::Constant(constant) => vec![constant],
-            AnonymousClassMember::TraitUsage(usage) => vec![usage],
-            AnonymousClassMember::Property(property) => vec![property],
-            AnonymousClassMember::VariableProperty(property) => vec![property],
-            AnonymousClassMember::ConcreteMethod(method) => vec![method],
-            AnonymousClassMember::ConcreteConstructor(method) => vec![method],
-        }
-    }
+visit_spans! {
+    struct ClassBody { left_brace, members, right_brace }
+    struct ClassStatement { doc, attributes, modifiers, class, name, extends, implements, body }
+    struct AnonymousClassBody { left_brace, members, right_brace }
+    struct AnonymousClassExpression { attributes, class, extends, implements, body }
+    struct ClassExtends { extends, parent }
+    struct ClassImplements { implements, interfaces }
 }
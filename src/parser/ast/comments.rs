@@ -1,4 +1,6 @@
+use std::fmt::Debug;
 use std::slice::Iter;
+use std::sync::OnceLock;
 
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -6,7 +8,10 @@ use serde::Serialize;
 
 use crate::lexer::byte_string::ByteString;
 use crate::lexer::token::Span;
+use crate::lexer::token::Token;
+use crate::lexer::token::TokenKind;
 use crate::node::Node;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type")]
@@ -25,6 +30,28 @@ pub struct Comment {
     pub content: ByteString,
 }
 
+impl Comment {
+    /// The comment of a comment token, e.g. to collect all the comments of a file from
+    /// the tokens returned by [`parse_with_tokens`].
+    ///
+    /// [`parse_with_tokens`]: crate::parser::parse_with_tokens
+    pub fn from_token(token: &Token) -> Option<Self> {
+        let format = match token.kind {
+            TokenKind::SingleLineComment => CommentFormat::SingleLine,
+            TokenKind::MultiLineComment => CommentFormat::MultiLine,
+            TokenKind::HashMarkComment => CommentFormat::HashMark,
+            TokenKind::DocumentComment => CommentFormat::Document,
+            _ => return None,
+        };
+
+        Some(Self {
+            span: token.span,
+            format,
+            content: token.value.to_byte_string(),
+        })
+    }
+}
+
 impl Node for Comment {}
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -39,6 +66,95 @@ impl CommentGroup {
     }
 }
 
+/// The doc comment of a declaration: the `/** */` or `/* */` comment right before it, or
+/// before its attributes, with only other comments in between.
+#[derive(Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DocRef {
+    pub span: Span,
+    pub content: ByteString,
+    #[serde(skip)]
+    #[schemars(skip)]
+    tags: OnceLock<Vec<DocTag>>,
+}
+
+/// A tag at the start of a line of a doc comment, e.g. `@param` or `@phpstan-assert`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DocTag {
+    /// The name of the tag, without the `@`.
+    pub name: ByteString,
+    /// The line of the tag, counted from the first line of the comment.
+    pub line: usize,
+}
+
+impl DocRef {
+    pub fn new(span: Span, content: ByteString) -> Self {
+        Self {
+            span,
+            content,
+            tags: OnceLock::new(),
+        }
+    }
+
+    /// The tags of the comment, in order, scanned the first time they are asked for.
+    ///
+    /// Only the names are scanned, what follows them isn't parsed.
+    pub fn tags(&self) -> &[DocTag] {
+        self.tags.get_or_init(|| scan_tags(&self.content))
+    }
+}
+
+impl PartialEq for DocRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.span == other.span && self.content == other.content
+    }
+}
+
+impl Eq for DocRef {}
+
+impl Debug for DocRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DocRef")
+            .field("span", &self.span)
+            .field("content", &self.content)
+            .finish()
+    }
+}
+
+impl Node for DocRef {}
+
+fn scan_tags(content: &[u8]) -> Vec<DocTag> {
+    let content = content.strip_suffix(b"*/").unwrap_or(content);
+    let content = content
+        .strip_prefix(b"/**")
+        .or_else(|| content.strip_prefix(b"/*"))
+        .unwrap_or(content);
+
+    content
+        .split(|byte| *byte == b'\n')
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let text = text.trim_ascii_start();
+            // The gutter, e.g. ` * @param`, or `** @param` in a row of stars.
+            let stars = text.iter().take_while(|byte| **byte == b'*').count();
+            let text = text[stars..].trim_ascii_start();
+            let name = text.strip_prefix(b"@")?;
+            let length = name
+                .iter()
+                .position(|byte| {
+                    !(byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'\\'))
+                })
+                .unwrap_or(name.len());
+
+            name.first()
+                .is_some_and(u8::is_ascii_alphabetic)
+                .then(|| DocTag {
+                    name: name[..length].into(),
+                    line,
+                })
+        })
+        .collect()
+}
+
 impl IntoIterator for CommentGroup {
     type Item = Comment;
     type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -47,3 +163,95 @@ impl IntoIterator for CommentGroup {
         self.comments.into_iter()
     }
 }
+
+visit_spans! {
+    enum CommentFormat { SingleLine, MultiLine, HashMark, Document }
+    struct Comment { span, format, content }
+    struct CommentGroup { comments }
+    struct DocRef { span, content, .. }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::classes::ClassMember;
+    use crate::parser::ast::Statement;
+
+    const SOURCE: &str = include_str!("../../../tests/fixtures/0412-doc-comments/code.php");
+
+    fn tags(doc: &Option<DocRef>) -> Vec<(String, usize)> {
+        doc.as_ref()
+            .unwrap()
+            .tags()
+            .iter()
+            .map(|tag| (tag.name.to_string(), tag.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_doc_tags() {
+        let program = crate::parse(SOURCE).unwrap();
+
+        let Statement::Function(function) = &program[2] else {
+            panic!("expected a function, found {:?}", program[2]);
+        };
+        assert_eq!(function.doc.as_ref().unwrap().span.line, 6);
+        assert_eq!(
+            tags(&function.doc),
+            vec![
+                ("template".to_string(), 1),
+                ("param".to_string(), 2),
+                ("return".to_string(), 3),
+                ("throws".to_string(), 4),
+                ("phpstan-assert".to_string(), 5),
+            ]
+        );
+
+        let Statement::Class(class) = &program[3] else {
+            panic!("expected a class, found {:?}", program[3]);
+        };
+        assert_eq!(tags(&class.doc), vec![("phpstan-type".to_string(), 1)]);
+
+        let docs = class
+            .body
+            .members
+            .iter()
+            .map(|member| match member {
+                ClassMember::Constant(constant) => &constant.doc,
+                ClassMember::Property(property) => &property.doc,
+                ClassMember::ConcreteConstructor(constructor) => &constructor.doc,
+                ClassMember::ConcreteMethod(method) => &method.doc,
+                member => panic!("unexpected member {:?}", member),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(tags(docs[0]), vec![("var".to_string(), 0)]);
+        assert_eq!(tags(docs[1]), vec![("deprecated".to_string(), 0)]);
+        assert_eq!(docs[2], &None);
+        assert_eq!(tags(docs[3]), vec![("param".to_string(), 1)]);
+        // An attribute separates the comment from the method.
+        assert_eq!(docs[4], &None);
+
+        let Statement::BackedEnum(status) = &program[4] else {
+            panic!("expected an enum, found {:?}", program[4]);
+        };
+        assert_eq!(tags(&status.doc), vec![("psalm-immutable".to_string(), 0)]);
+    }
+
+    #[test]
+    fn test_text_that_isnt_a_tag() {
+        let doc = DocRef::new(
+            Span::new(1, 1, 0),
+            "/** Mail me@example.com, or @ someone.\n * @1 {@inheritDoc}\n * @ORM\\Column */"
+                .into(),
+        );
+
+        assert_eq!(
+            doc.tags(),
+            &[DocTag {
+                name: "ORM\\Column".into(),
+                line: 2
+            }]
+        );
+    }
+}
@@ -8,9 +8,13 @@ use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
 use crate::parser::ast::comments::CommentGroup;
+use crate::parser::ast::comments::DocRef;
+use crate::parser::ast::data_type::Type;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::modifiers::ConstantModifierGroup;
+use crate::parser::ast::modifiers::Visibility;
 use crate::parser::ast::Expression;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -30,6 +34,7 @@ impl Node for ConstantEntry {
 
 pub struct ConstantStatement {
     pub comments: CommentGroup,
+    pub doc: Option<DocRef>,         // `/** ... */`
     pub r#const: Span,               // `const`
     pub entries: Vec<ConstantEntry>, // `FOO = 123`
     pub semicolon: Span,             // `;`
@@ -56,15 +61,20 @@ impl Node for ConstantStatement {
     }
 }
 
+/// A class, interface, trait or enum constant declaration.
+///
+/// Modifiers are stored once for the whole declaration and apply to every entry,
+/// so `private const A = 1, B = 2;` declares both `A` and `B` as private.
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct ClassishConstant {
     pub comments: CommentGroup,
+    pub doc: Option<DocRef>,              // `/** ... */`
     pub attributes: Vec<AttributeGroup>,  // `#[Foo]`
     pub modifiers: ConstantModifierGroup, // `public`
     pub r#const: Span,                    // `const`
-    #[serde(flatten)]
-    pub entries: Vec<ConstantEntry>, // `FOO = 123`
+    pub data_type: Option<Type>,          // `int`
+    pub entries: Vec<ConstantEntry>,      // `FOO = 123`
     pub semicolon: Span,                  // `;`
 }
 
@@ -72,6 +82,16 @@ impl ClassishConstant {
     pub fn iter(&self) -> Iter<'_, ConstantEntry> {
         self.entries.iter()
     }
+
+    /// The visibility shared by every entry in this declaration.
+    pub fn visibility(&self) -> Visibility {
+        self.modifiers.visibility()
+    }
+
+    /// Whether every entry in this declaration is final.
+    pub fn is_final(&self) -> bool {
+        self.modifiers.has_final()
+    }
 }
 
 impl IntoIterator for ClassishConstant {
@@ -85,6 +105,73 @@ impl IntoIterator for ClassishConstant {
 
 impl Node for ClassishConstant {
     fn children(&mut self) -> Vec<&mut dyn Node> {
-        self.entries.children()
+        let mut children: Vec<&mut dyn Node> = vec![];
+        if let Some(data_type) = &mut self.data_type {
+            children.push(data_type);
+        }
+        children.extend(self.entries.children());
+        children
+    }
+}
+
+visit_spans! {
+    struct ConstantEntry { name, equals, value }
+    struct ConstantStatement { comments, doc, r#const, entries, semicolon }
+    struct ClassishConstant {
+        comments, doc, attributes, modifiers, r#const, data_type, entries, semicolon,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::classes::ClassMember;
+    use crate::parser::ast::Statement;
+
+    fn class_constants(code: &str) -> Vec<ClassishConstant> {
+        let program = crate::parse(code).unwrap();
+
+        match program.into_iter().nth(1) {
+            Some(Statement::Class(class)) => class
+                .body
+                .members
+                .into_iter()
+                .filter_map(|member| match member {
+                    ClassMember::Constant(constant) => Some(constant),
+                    _ => None,
+                })
+                .collect(),
+            statement => panic!("expected a class, found {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_grouped_constants_share_modifiers() {
+        let constants = class_constants(
+            "<?php class Foo { private const A = 1, B = 2; final public const C = 3; protected const D = 4; }",
+        );
+
+        let entries = constants
+            .iter()
+            .flat_map(|constant| {
+                constant.iter().map(|entry| {
+                    (
+                        entry.name.value.to_string(),
+                        constant.visibility(),
+                        constant.is_final(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("A".to_string(), Visibility::Private, false),
+                ("B".to_string(), Visibility::Private, false),
+                ("C".to_string(), Visibility::Public, true),
+                ("D".to_string(), Visibility::Protected, false),
+            ]
+        );
     }
 }
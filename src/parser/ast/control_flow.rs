@@ -4,9 +4,11 @@ use serde::Serialize;
 
 use crate::lexer::token::Span;
 use crate::node::Node;
+use crate::parser::ast::BodySyntax;
 use crate::parser::ast::Ending;
 use crate::parser::ast::Expression;
 use crate::parser::ast::Statement;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -42,6 +44,15 @@ pub enum IfStatementBody {
     },
 }
 
+impl IfStatementBody {
+    pub fn body_syntax(&self) -> BodySyntax {
+        match self {
+            IfStatementBody::Statement { .. } => BodySyntax::Brace,
+            IfStatementBody::Block { .. } => BodySyntax::Alternative,
+        }
+    }
+}
+
 impl Node for IfStatementBody {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         match self {
@@ -142,3 +153,17 @@ impl Node for IfStatementElseBlock {
         self.statements.children()
     }
 }
+
+visit_spans! {
+    struct IfStatement { r#if, left_parenthesis, condition, right_parenthesis, body }
+    enum IfStatementBody {
+        Statement { statement, elseifs, r#else },
+        Block { colon, statements, elseifs, r#else, endif, ending },
+    }
+    struct IfStatementElseIf { elseif, left_parenthesis, condition, right_parenthesis, statement }
+    struct IfStatementElse { r#else, statement }
+    struct IfStatementElseIfBlock {
+        elseif, left_parenthesis, condition, right_parenthesis, colon, statements,
+    }
+    struct IfStatementElseBlock { r#else, colon, statements }
+}
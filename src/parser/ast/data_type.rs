@@ -7,6 +7,7 @@ use std::fmt::Display;
 use crate::lexer::byte_string::ByteString;
 use crate::lexer::token::Span;
 use crate::node::Node;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "value")]
@@ -56,6 +57,17 @@ impl Type {
         }
     }
 
+    pub fn includes_static(&self) -> bool {
+        match &self {
+            Self::StaticReference(_) => true,
+            Self::Nullable(_, inner) => inner.includes_static(),
+            Self::Union(types) | Self::Intersection(types) => {
+                types.iter().any(|x| x.includes_static())
+            }
+            _ => false,
+        }
+    }
+
     pub fn includes_class_scoped(&self) -> bool {
         match &self {
             Self::StaticReference(_) | Self::SelfReference(_) | Self::ParentReference(_) => true,
@@ -151,3 +163,29 @@ impl Node for Type {
         }
     }
 }
+
+visit_spans! {
+    enum Type {
+        Named(span, name),
+        Nullable(question, inner),
+        Union(value),
+        Intersection(value),
+        Void(value),
+        Null(value),
+        True(value),
+        False(value),
+        Never(value),
+        Float(value),
+        Boolean(value),
+        Integer(value),
+        String(value),
+        Array(value),
+        Object(value),
+        Mixed(value),
+        Callable(value),
+        Iterable(value),
+        StaticReference(value),
+        SelfReference(value),
+        ParentReference(value),
+    }
+}
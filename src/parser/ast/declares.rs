@@ -6,14 +6,17 @@ use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::literals::Literal;
+use crate::parser::ast::BodySyntax;
 use crate::parser::ast::Expression;
 use crate::parser::ast::Statement;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct DeclareEntry {
     pub key: SimpleIdentifier, // `strict_types`
     pub equals: Span,          // `=`
+    pub minus: Option<Span>,   // `-`
     pub value: Literal,        // `1`
 }
 
@@ -63,6 +66,21 @@ pub enum DeclareBody {
     },
 }
 
+impl DeclareBody {
+    /// Whether the declaration has no body, so it applies to the rest of the file
+    /// rather than to a block or a single statement.
+    pub fn is_file_scoped(&self) -> bool {
+        matches!(self, DeclareBody::Noop { .. })
+    }
+
+    pub fn body_syntax(&self) -> BodySyntax {
+        match self {
+            DeclareBody::Block { .. } => BodySyntax::Alternative,
+            _ => BodySyntax::Brace,
+        }
+    }
+}
+
 impl Node for DeclareBody {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         match self {
@@ -91,3 +109,15 @@ impl Node for DeclareStatement {
         vec![&mut self.entries, &mut self.body]
     }
 }
+
+visit_spans! {
+    struct DeclareEntry { key, equals, minus, value }
+    struct DeclareEntryGroup { left_parenthesis, right_parenthesis, entries }
+    enum DeclareBody {
+        Noop { semicolon },
+        Braced { left_brace, statements, right_brace },
+        Expression { expression, semicolon },
+        Block { colon, statements, end },
+    }
+    struct DeclareStatement { declare, entries, body }
+}
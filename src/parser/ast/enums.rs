@@ -3,18 +3,22 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::token::Span;
+use crate::node::node_enum;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::comments::DocRef;
 use crate::parser::ast::constant::ClassishConstant;
 use crate::parser::ast::functions::ConcreteMethod;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::Expression;
+use crate::spans::visit_spans;
 
 use super::traits::TraitUsage;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct UnitEnumCase {
+    pub doc: Option<DocRef>,             // `/** ... */`
     pub attributes: Vec<AttributeGroup>, // `#[Foo]`
     pub start: Span,                     // `case`
     pub name: SimpleIdentifier,          // `Bar`
@@ -27,23 +31,14 @@ impl Node for UnitEnumCase {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum UnitEnumMember {
-    Case(UnitEnumCase),         // `case Bar;`
-    Method(ConcreteMethod),     // `public function foo(): void { ... }`
-    Constant(ClassishConstant), // `public const FOO = 123;`
-    TraitUsage(TraitUsage),
-}
-
-impl Node for UnitEnumMember {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            UnitEnumMember::Case(case) => vec![case],
-            UnitEnumMember::Method(method) => vec![method],
-            UnitEnumMember::Constant(constant) => vec![constant],
-            UnitEnumMember::TraitUsage(trait_usage) => vec![trait_usage],
-        }
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum UnitEnumMember {
+        Case(UnitEnumCase),         // `case Bar;`
+        Method(ConcreteMethod),     // `public function foo(): void { ... }`
+        Constant(ClassishConstant), // `public const FOO = 123;`
+        TraitUsage(TraitUsage),
     }
 }
 
@@ -64,6 +59,7 @@ impl Node for UnitEnumBody {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct UnitEnumStatement {
+    pub doc: Option<DocRef>,               // `/** ... */`
     pub attributes: Vec<AttributeGroup>,   // `#[Foo]`
     pub r#enum: Span,                      // `enum`
     pub name: SimpleIdentifier,            // `Foo`
@@ -96,6 +92,7 @@ impl Node for BackedEnumType {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct BackedEnumCase {
+    pub doc: Option<DocRef>,             // `/** ... */`
     pub attributes: Vec<AttributeGroup>, // `#[Foo]`
     pub case: Span,                      // `case`
     pub name: SimpleIdentifier,          // `Bar`
@@ -110,23 +107,14 @@ impl Node for BackedEnumCase {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum BackedEnumMember {
-    Case(BackedEnumCase),
-    Method(ConcreteMethod),
-    Constant(ClassishConstant),
-    TraitUsage(TraitUsage),
-}
-
-impl Node for BackedEnumMember {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            BackedEnumMember::Case(case) => vec![case],
-            BackedEnumMember::Method(method) => vec![method],
-            BackedEnumMember::Constant(constant) => vec![constant],
-            BackedEnumMember::TraitUsage(trait_usage) => vec![trait_usage],
-        }
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum BackedEnumMember {
+        Case(BackedEnumCase),
+        Method(ConcreteMethod),
+        Constant(ClassishConstant),
+        TraitUsage(TraitUsage),
     }
 }
 
@@ -147,6 +135,7 @@ impl Node for BackedEnumBody {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct BackedEnumStatement {
+    pub doc: Option<DocRef>,               // `/** ... */`
     pub attributes: Vec<AttributeGroup>,   // `#[Foo]`
     pub r#enum: Span,                      // `enum`
     pub name: SimpleIdentifier,            // `Foo`
@@ -165,3 +154,13 @@ impl Node for BackedEnumStatement {
         children
     }
 }
+
+visit_spans! {
+    struct UnitEnumCase { doc, attributes, start, name, end }
+    struct UnitEnumBody { left_brace, members, right_brace }
+    struct UnitEnumStatement { doc, attributes, r#enum, name, implements, body }
+    enum BackedEnumType { String(colon, string), Int(colon, int) }
+    struct BackedEnumCase { doc, attributes, case, name, equals, value, semicolon }
+    struct BackedEnumBody { left_brace, members, right_brace }
+    struct BackedEnumStatement { doc, attributes, r#enum, name, backed_type, implements, body }
+}
@@ -5,9 +5,11 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::token::Span;
+use crate::node::node_enum;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
 use crate::parser::ast::comments::CommentGroup;
+use crate::parser::ast::comments::DocRef;
 use crate::parser::ast::data_type::Type;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::modifiers::MethodModifierGroup;
@@ -16,6 +18,7 @@ use crate::parser::ast::utils::CommaSeparated;
 use crate::parser::ast::variables::SimpleVariable;
 use crate::parser::ast::Expression;
 use crate::parser::ast::Statement;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -91,6 +94,12 @@ pub struct FunctionBody {
     pub comments: CommentGroup,
     pub left_brace: Span,
     pub statements: Vec<Statement>,
+    /// Whether the statements were passed over without being parsed, see
+    /// [`ParserConfig::skip_bodies`], rather than there being none.
+    ///
+    /// [`ParserConfig::skip_bodies`]: crate::parser::config::ParserConfig::skip_bodies
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
     pub right_brace: Span,
 }
 
@@ -104,6 +113,7 @@ impl Node for FunctionBody {
 
 pub struct FunctionStatement {
     pub comments: CommentGroup,
+    pub doc: Option<DocRef>,
     pub attributes: Vec<AttributeGroup>,
     pub function: Span,
     pub ampersand: Option<Span>,
@@ -199,6 +209,12 @@ pub struct ArrowFunctionExpression {
 pub struct ArrowFunctionBlockBody {
     pub left_brace: Span,
     pub statements: Vec<Statement>,
+    /// Whether the statements were passed over without being parsed, see
+    /// [`ParserConfig::skip_bodies`], rather than there being none.
+    ///
+    /// [`ParserConfig::skip_bodies`]: crate::parser::config::ParserConfig::skip_bodies
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
     pub right_brace: Span,
 }
 
@@ -220,19 +236,12 @@ impl Node for ArrowFunctionExpressionBody {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum ArrowFunctionBody {
-    Block(ArrowFunctionBlockBody),
-    Expression(ArrowFunctionExpressionBody),
-}
-
-impl Node for ArrowFunctionBody {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            ArrowFunctionBody::Block(body) => vec![body],
-            ArrowFunctionBody::Expression(body) => vec![body],
-        }
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum ArrowFunctionBody {
+        Block(ArrowFunctionBlockBody),
+        Expression(ArrowFunctionExpressionBody),
     }
 }
 
@@ -293,6 +302,7 @@ impl Node for ConstructorParameterList {
 
 pub struct AbstractConstructor {
     pub comments: CommentGroup,
+    pub doc: Option<DocRef>,
     pub attributes: Vec<AttributeGroup>,
     #[serde(flatten)]
     pub modifiers: MethodModifierGroup,
@@ -315,6 +325,7 @@ impl Node for AbstractConstructor {
 
 pub struct ConcreteConstructor {
     pub comments: CommentGroup,
+    pub doc: Option<DocRef>,
     pub attributes: Vec<AttributeGroup>,
     #[serde(flatten)]
     pub modifiers: MethodModifierGroup,
@@ -355,6 +366,7 @@ impl ConcreteConstructor {
 
 pub struct AbstractMethod {
     pub comments: CommentGroup,
+    pub doc: Option<DocRef>,
     pub attributes: Vec<AttributeGroup>,
     #[serde(flatten)]
     pub modifiers: MethodModifierGroup,
@@ -380,6 +392,7 @@ impl Node for AbstractMethod {
 
 pub struct ConcreteMethod {
     pub comments: CommentGroup,
+    pub doc: Option<DocRef>,
     pub attributes: Vec<AttributeGroup>,
     #[serde(flatten)]
     pub modifiers: MethodModifierGroup,
@@ -408,6 +421,12 @@ pub struct MethodBody {
     pub comments: CommentGroup,
     pub left_brace: Span, // `{`
     pub statements: Vec<Statement>,
+    /// Whether the statements were passed over without being parsed, see
+    /// [`ParserConfig::skip_bodies`], rather than there being none.
+    ///
+    /// [`ParserConfig::skip_bodies`]: crate::parser::config::ParserConfig::skip_bodies
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
     pub right_brace: Span, // `}`
 }
 
@@ -416,3 +435,42 @@ impl Node for MethodBody {
         self.statements.children()
     }
 }
+
+visit_spans! {
+    struct ReturnType { colon, data_type }
+    struct FunctionParameter { comments, name, attributes, data_type, ellipsis, default, ampersand }
+    struct FunctionParameterList { comments, left_parenthesis, parameters, right_parenthesis }
+    struct FunctionBody { comments, left_brace, statements, skipped, right_brace }
+    struct FunctionStatement {
+        comments, doc, attributes, function, ampersand, name, parameters, return_type, body,
+    }
+    struct ClosureUseVariable { comments, ampersand, variable }
+    struct ClosureUse { comments, r#use, left_parenthesis, variables, right_parenthesis }
+    struct ClosureExpression {
+        comments, attributes, r#static, function, ampersand, parameters, uses, return_type, body,
+    }
+    struct ArrowFunctionExpression {
+        comments, r#static, ampersand, r#fn, attributes, parameters, return_type, body,
+    }
+    struct ArrowFunctionBlockBody { left_brace, statements, skipped, right_brace }
+    struct ArrowFunctionExpressionBody { double_arrow, expression }
+    struct ConstructorParameter {
+        attributes, comments, ampersand, name, data_type, ellipsis, default, modifiers,
+    }
+    struct ConstructorParameterList { comments, left_parenthesis, parameters, right_parenthesis }
+    struct AbstractConstructor {
+        comments, doc, attributes, modifiers, function, ampersand, name, parameters, semicolon,
+    }
+    struct ConcreteConstructor {
+        comments, doc, attributes, modifiers, function, ampersand, name, parameters, body,
+    }
+    struct AbstractMethod {
+        comments, doc, attributes, modifiers, function, ampersand, name, parameters, return_type,
+        semicolon,
+    }
+    struct ConcreteMethod {
+        comments, doc, attributes, modifiers, function, ampersand, name, parameters, return_type,
+        body,
+    }
+    struct MethodBody { comments, left_brace, statements, skipped, right_brace }
+}
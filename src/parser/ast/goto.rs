@@ -6,6 +6,7 @@ use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::comments::CommentGroup;
 use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -35,3 +36,8 @@ impl Node for GotoStatement {
         vec![&mut self.label]
     }
 }
+
+visit_spans! {
+    struct LabelStatement { comments, label, colon }
+    struct GotoStatement { comments, keyword, label, semicolon }
+}
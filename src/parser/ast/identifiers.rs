@@ -8,6 +8,7 @@ use crate::lexer::byte_string::ByteString;
 use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::Expression;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "value")]
@@ -55,3 +56,9 @@ impl Node for DynamicIdentifier {
         vec![self.expr.as_mut()]
     }
 }
+
+visit_spans! {
+    enum Identifier { SimpleIdentifier(value), DynamicIdentifier(value) }
+    struct SimpleIdentifier { span, value }
+    struct DynamicIdentifier { start, expr, end }
+}
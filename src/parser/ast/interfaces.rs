@@ -3,29 +3,24 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::token::Span;
+use crate::node::node_enum;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::comments::DocRef;
 use crate::parser::ast::constant::ClassishConstant;
 use crate::parser::ast::functions::AbstractConstructor;
 use crate::parser::ast::functions::AbstractMethod;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::utils::CommaSeparated;
+use crate::spans::visit_spans;
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum InterfaceMember {
-    Constant(ClassishConstant),       // `public const FOO = 123;`
-    Constructor(AbstractConstructor), // `public function __construct(): void;`
-    Method(AbstractMethod),           // `public function foo(): void;`
-}
-
-impl Node for InterfaceMember {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            InterfaceMember::Constant(constant) => vec![constant],
-            InterfaceMember::Constructor(constructor) => vec![constructor],
-            InterfaceMember::Method(method) => vec![method],
-        }
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum InterfaceMember {
+        Constant(ClassishConstant),       // `public const FOO = 123;`
+        Constructor(AbstractConstructor), // `public function __construct(): void;`
+        Method(AbstractMethod),           // `public function foo(): void;`
     }
 }
 
@@ -59,6 +54,7 @@ impl Node for InterfaceBody {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct InterfaceStatement {
+    pub doc: Option<DocRef>,               // `/** ... */`
     pub attributes: Vec<AttributeGroup>,   // `#[Foo]`
     pub interface: Span,                   // `interface`
     pub name: SimpleIdentifier,            // `Foo`
@@ -72,6 +68,13 @@ impl Node for InterfaceStatement {
         if let Some(extends) = &mut self.extends {
             children.push(extends);
         }
+        children.push(&mut self.body);
         children
     }
 }
+
+visit_spans! {
+    struct InterfaceExtends { extends, parents }
+    struct InterfaceBody { left_brace, members, right_brace }
+    struct InterfaceStatement { doc, attributes, interface, name, extends, body }
+}
@@ -4,23 +4,17 @@ use serde::Serialize;
 
 use crate::lexer::byte_string::ByteString;
 use crate::lexer::token::Span;
+use crate::node::node_enum;
 use crate::node::Node;
+use crate::spans::visit_spans;
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum Literal {
-    String(LiteralString),
-    Integer(LiteralInteger),
-    Float(LiteralFloat),
-}
-
-impl Node for Literal {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            Literal::String(literal) => vec![literal],
-            Literal::Integer(literal) => vec![literal],
-            Literal::Float(literal) => vec![literal],
-        }
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum Literal {
+        String(LiteralString),
+        Integer(LiteralInteger),
+        Float(LiteralFloat),
     }
 }
 
@@ -42,6 +36,26 @@ pub struct LiteralInteger {
     pub span: Span,
 }
 
+impl LiteralInteger {
+    /// Returns the value of the literal, or `None` if it does not fit in an `i64`.
+    ///
+    /// Binary (`0b`), octal (`0o` / leading `0`), and hexadecimal (`0x`) notations
+    /// are supported. Numeric separators are already removed by the lexer.
+    pub fn to_i64(&self) -> Option<i64> {
+        let value = std::str::from_utf8(&self.value).ok()?;
+
+        let (digits, radix) = match value.get(..2) {
+            Some("0b" | "0B") => (&value[2..], 2),
+            Some("0o" | "0O") => (&value[2..], 8),
+            Some("0x" | "0X") => (&value[2..], 16),
+            _ if value.len() > 1 && value.starts_with('0') => (&value[1..], 8),
+            _ => (value, 10),
+        };
+
+        i64::from_str_radix(digits, radix).ok()
+    }
+}
+
 impl Node for LiteralInteger {
     //
 }
@@ -56,3 +70,9 @@ pub struct LiteralFloat {
 impl Node for LiteralFloat {
     //
 }
+
+visit_spans! {
+    struct LiteralString { value, span }
+    struct LiteralInteger { value, span }
+    struct LiteralFloat { value, span }
+}
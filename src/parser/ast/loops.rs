@@ -6,9 +6,11 @@ use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::literals::LiteralInteger;
 use crate::parser::ast::utils::CommaSeparated;
+use crate::parser::ast::BodySyntax;
 use crate::parser::ast::Ending;
 use crate::parser::ast::Expression;
 use crate::parser::ast::Statement;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -36,7 +38,7 @@ pub enum ForeachStatementIterator {
         ampersand: Option<Span>, // `&`
         value: Expression,       // `$var`
     },
-    // `*expression* as &$key => $value`
+    // `*expression* as $key => &$value`
     KeyAndValue {
         expression: Expression,  // `*expression*`
         r#as: Span,              // `as`
@@ -79,6 +81,15 @@ pub enum ForeachStatementBody {
     },
 }
 
+impl ForeachStatementBody {
+    pub fn body_syntax(&self) -> BodySyntax {
+        match self {
+            ForeachStatementBody::Statement { .. } => BodySyntax::Brace,
+            ForeachStatementBody::Block { .. } => BodySyntax::Alternative,
+        }
+    }
+}
+
 impl Node for ForeachStatementBody {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         match self {
@@ -114,6 +125,17 @@ pub struct ForStatementIterator {
     pub r#loop: CommaSeparated<Expression>,          // `*expression*`
 }
 
+impl ForStatementIterator {
+    /// The condition that decides whether the loop goes on, if any.
+    ///
+    /// Every expression of the conditions section is evaluated before each iteration,
+    /// in order, but only the value of the last one is tested, so `for (;; $i++)` and
+    /// `for (; f(), $i < 10;)` loop on `true` and on `$i < 10` respectively.
+    pub fn condition(&self) -> Option<&Expression> {
+        self.conditions.inner.last()
+    }
+}
+
 impl Node for ForStatementIterator {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         let mut children = vec![];
@@ -142,6 +164,15 @@ pub enum ForStatementBody {
     },
 }
 
+impl ForStatementBody {
+    pub fn body_syntax(&self) -> BodySyntax {
+        match self {
+            ForStatementBody::Statement { .. } => BodySyntax::Brace,
+            ForStatementBody::Block { .. } => BodySyntax::Alternative,
+        }
+    }
+}
+
 impl Node for ForStatementBody {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         match self {
@@ -199,6 +230,15 @@ pub enum WhileStatementBody {
     },
 }
 
+impl WhileStatementBody {
+    pub fn body_syntax(&self) -> BodySyntax {
+        match self {
+            WhileStatementBody::Statement { .. } => BodySyntax::Brace,
+            WhileStatementBody::Block { .. } => BodySyntax::Alternative,
+        }
+    }
+}
+
 impl Node for WhileStatementBody {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         match self {
@@ -219,6 +259,16 @@ pub enum Level {
     },
 }
 
+impl Level {
+    /// Returns the number of levels, or `None` if it does not fit in an `i64`.
+    pub fn value(&self) -> Option<i64> {
+        match self {
+            Level::Literal(literal) => literal.to_i64(),
+            Level::Parenthesized { level, .. } => level.value(),
+        }
+    }
+}
+
 impl Node for Level {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         match self {
@@ -261,3 +311,100 @@ impl Node for ContinueStatement {
         }
     }
 }
+
+visit_spans! {
+    struct ForeachStatement { foreach, left_parenthesis, iterator, right_parenthesis, body }
+    enum ForeachStatementIterator {
+        Value { expression, r#as, ampersand, value },
+        KeyAndValue { expression, r#as, ampersand, key, double_arrow, value },
+    }
+    enum ForeachStatementBody {
+        Statement { statement },
+        Block { colon, statements, endforeach, ending },
+    }
+    struct ForStatement { r#for, left_parenthesis, iterator, right_parenthesis, body }
+    struct ForStatementIterator {
+        initializations, initializations_semicolon, conditions, conditions_semicolon, r#loop,
+    }
+    enum ForStatementBody { Statement { statement }, Block { colon, statements, endfor, ending } }
+    struct DoWhileStatement {
+        r#do, body, r#while, left_parenthesis, condition, right_parenthesis, semicolon,
+    }
+    struct WhileStatement { r#while, left_parenthesis, condition, right_parenthesis, body }
+    enum WhileStatementBody {
+        Statement { statement },
+        Block { colon, statements, endwhile, ending },
+    }
+    enum Level { Literal(value), Parenthesized { left_parenthesis, level, right_parenthesis } }
+    struct BreakStatement { r#break, level, ending }
+    struct ContinueStatement { r#continue, level, ending }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::variables::Variable;
+
+    fn for_statement(code: &str) -> ForStatement {
+        let program = crate::parse(code).unwrap();
+
+        match program.into_iter().nth(1) {
+            Some(Statement::For(statement)) => statement,
+            statement => panic!("expected a for loop, found {:?}", statement),
+        }
+    }
+
+    fn variables(expressions: &CommaSeparated<Expression>) -> Vec<String> {
+        expressions
+            .iter()
+            .map(|expression| match expression {
+                Expression::ComparisonOperation(_) => "comparison".to_string(),
+                Expression::ArithmeticOperation(_) => "arithmetic".to_string(),
+                Expression::Variable(Variable::SimpleVariable(variable)) => {
+                    variable.name.to_string()
+                }
+                expression => panic!("unexpected expression {:?}", expression),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_comma_separated_sections() {
+        let statement = for_statement("<?php for ($i = 0, $j = 10; $i < $j; $i++, $j--) {}");
+        let iterator = &statement.iterator;
+
+        assert_eq!(iterator.initializations.inner.len(), 2);
+        assert_eq!(iterator.initializations.commas.len(), 1);
+        assert_eq!(iterator.conditions.inner.len(), 1);
+        assert_eq!(
+            variables(&iterator.r#loop),
+            vec!["arithmetic", "arithmetic"]
+        );
+        assert_eq!(iterator.r#loop.commas.len(), 1);
+    }
+
+    #[test]
+    fn test_empty_sections() {
+        let statement = for_statement("<?php for (;;): break; endfor;");
+        let iterator = &statement.iterator;
+
+        assert!(iterator.initializations.inner.is_empty());
+        assert!(iterator.conditions.inner.is_empty());
+        assert!(iterator.r#loop.inner.is_empty());
+        assert!(iterator.condition().is_none());
+        assert_eq!(statement.body.body_syntax(), BodySyntax::Alternative);
+    }
+
+    #[test]
+    fn test_last_condition_is_the_test() {
+        let statement = for_statement("<?php for ($i = 0; $a, $b, $i < 10; $i++);");
+        let conditions = &statement.iterator.conditions;
+
+        assert_eq!(variables(conditions), vec!["$a", "$b", "comparison"]);
+        assert_eq!(statement.iterator.condition(), conditions.inner.last());
+        assert!(matches!(
+            statement.iterator.condition(),
+            Some(Expression::ComparisonOperation(_))
+        ));
+    }
+}
@@ -1,16 +1,21 @@
+use std::fmt::Display;
+
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::byte_string::ByteString;
 use crate::lexer::token::Span;
+use crate::lexer::token::Token;
 use crate::lexer::token::TokenKind;
+use crate::node::node_enum;
 use crate::node::Node;
 use crate::parser::ast::arguments::ArgumentPlaceholder;
 use crate::parser::ast::arguments::{ArgumentList, SingleArgument};
 use crate::parser::ast::classes::AnonymousClassExpression;
 use crate::parser::ast::classes::ClassStatement;
 use crate::parser::ast::comments::Comment;
+use crate::parser::ast::comments::CommentGroup;
 use crate::parser::ast::constant::ConstantStatement;
 use crate::parser::ast::control_flow::IfStatement;
 use crate::parser::ast::declares::DeclareStatement;
@@ -41,6 +46,8 @@ use crate::parser::ast::traits::TraitStatement;
 use crate::parser::ast::try_block::TryStatement;
 use crate::parser::ast::utils::CommaSeparated;
 use crate::parser::ast::variables::Variable;
+use crate::parser::error::ParseError;
+use crate::spans::visit_spans;
 
 use self::data_type::Type;
 use self::operators::RangeOperationExpression;
@@ -111,6 +118,22 @@ pub enum Ending {
     CloseTag(Span),
 }
 
+/// Whether the body of a construct uses braces (or a single statement), or the
+/// alternative `:` ... `end*;` syntax.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+pub enum BodySyntax {
+    Brace,
+    Alternative,
+}
+
+/// Whether an array, or a destructuring, is written as `[...]` or with the `array(...)`
+/// and `list(...)` keywords.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ArraySyntax {
+    Short,
+    Long,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type")]
 pub struct HaltCompilerStatement {
@@ -119,6 +142,25 @@ pub struct HaltCompilerStatement {
 
 impl Node for HaltCompilerStatement {}
 
+/// The end of a file after `__halt_compiler();`, parsed as PHP code, see
+/// [`ParserConfig::after_halt`]. PHP never runs it.
+///
+/// [`ParserConfig::after_halt`]: crate::parser::config::ParserConfig::after_halt
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type")]
+pub struct AfterHaltStatement {
+    /// The statements that could be parsed, up to the first error that they couldn't
+    /// be parsed past.
+    pub statements: Vec<Statement>,
+    pub errors: Vec<ParseError>,
+}
+
+impl Node for AfterHaltStatement {
+    fn children(&mut self) -> Vec<&mut dyn Node> {
+        self.statements.children()
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type")]
 pub struct StaticStatement {
@@ -138,6 +180,7 @@ pub struct SwitchStatement {
     pub left_parenthesis: Span,
     pub condition: Expression,
     pub right_parenthesis: Span,
+    pub body_syntax: BodySyntax,
     pub cases: Vec<Case>,
 }
 
@@ -184,8 +227,11 @@ impl Node for ReturnStatement {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type")]
 pub struct UseStatement {
+    pub comments: CommentGroup,
+    pub r#use: Span,
     pub kind: UseKind,
     pub uses: Vec<Use>,
+    pub semicolon: Span,
 }
 
 impl Node for UseStatement {
@@ -197,9 +243,14 @@ impl Node for UseStatement {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type")]
 pub struct GroupUseStatement {
+    pub comments: CommentGroup,
+    pub r#use: Span,
     pub prefix: SimpleIdentifier,
     pub kind: UseKind,
+    pub left_brace: Span,
     pub uses: Vec<Use>,
+    pub right_brace: Span,
+    pub semicolon: Span,
 }
 
 impl Node for GroupUseStatement {
@@ -219,54 +270,124 @@ pub struct TypeAliasStatement {
     pub semicolon: Span, // `;`
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum Statement {
-    FullOpeningTag(FullOpeningTagStatement),
-    ShortOpeningTag(ShortOpeningTagStatement),
-    EchoOpeningTag(EchoOpeningTagStatement),
-    ClosingTag(ClosingTagStatement),
-    InlineHtml(InlineHtmlStatement),
-    Label(LabelStatement),
-    Goto(GotoStatement),
-    HaltCompiler(HaltCompilerStatement),
-    Static(StaticStatement),
-    DoWhile(DoWhileStatement),
-    While(WhileStatement),
-    For(ForStatement),
-    Foreach(ForeachStatement),
-    Break(BreakStatement),
-    Continue(ContinueStatement),
-    Constant(ConstantStatement),
-    Function(FunctionStatement),
-    Class(ClassStatement),
-    Trait(TraitStatement),
-    Interface(InterfaceStatement),
-    If(IfStatement),
-    Switch(SwitchStatement),
-    Echo(EchoStatement),
-    Expression(ExpressionStatement),
-    Return(ReturnStatement),
-    Namespace(NamespaceStatement),
-    Use(UseStatement),
-    GroupUse(GroupUseStatement),
-    Comment(Comment),
-    Try(TryStatement),
-    UnitEnum(UnitEnumStatement),
-    BackedEnum(BackedEnumStatement),
-    Block(BlockStatement),
-    Global(GlobalStatement),
-    Declare(DeclareStatement),
-    Noop(Span),
-    TypeAlias(TypeAliasStatement),
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum Statement {
+        #[leaf]
+        FullOpeningTag(FullOpeningTagStatement),
+        #[leaf]
+        ShortOpeningTag(ShortOpeningTagStatement),
+        #[leaf]
+        EchoOpeningTag(EchoOpeningTagStatement),
+        #[leaf]
+        ClosingTag(ClosingTagStatement),
+        #[leaf]
+        InlineHtml(InlineHtmlStatement),
+        Label(LabelStatement),
+        Goto(GotoStatement),
+        HaltCompiler(HaltCompilerStatement),
+        AfterHalt(AfterHaltStatement),
+        Static(StaticStatement),
+        DoWhile(DoWhileStatement),
+        While(WhileStatement),
+        For(ForStatement),
+        Foreach(ForeachStatement),
+        Break(BreakStatement),
+        Continue(ContinueStatement),
+        Constant(ConstantStatement),
+        Function(FunctionStatement),
+        Class(ClassStatement),
+        Trait(TraitStatement),
+        Interface(InterfaceStatement),
+        If(IfStatement),
+        Switch(SwitchStatement),
+        Echo(EchoStatement),
+        Expression(ExpressionStatement),
+        Return(ReturnStatement),
+        Namespace(NamespaceStatement),
+        Use(UseStatement),
+        GroupUse(GroupUseStatement),
+        Comment(Comment),
+        Try(TryStatement),
+        UnitEnum(UnitEnumStatement),
+        BackedEnum(BackedEnumStatement),
+        Block(BlockStatement),
+        Global(GlobalStatement),
+        Declare(DeclareStatement),
+        #[leaf]
+        Noop(Span),
+        #[leaf]
+        TypeAlias(TypeAliasStatement),
+        Custom(CustomStatement),
+        // A statement that failed to parse, when recovering from errors.
+        #[leaf]
+        Error(Span),
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct InlineHtmlStatement {
-    pub html: ByteString,
+    pub span: Span,
+    pub html: InlineHtml,
 }
 
+/// The content of inline HTML, as much of it as the [`InlineHtmlMode`] of the parser
+/// keeps.
+///
+/// [`InlineHtmlMode`]: crate::parser::config::InlineHtmlMode
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type", content = "value")]
+pub enum InlineHtml {
+    Retained(ByteString),
+    /// The content was dropped, only its length is known.
+    Dropped {
+        length: usize,
+    },
+    /// The content was dropped, keeping its length and FNV-1a hash to tell whether it
+    /// changed.
+    Hashed {
+        hash: u64,
+        length: usize,
+    },
+}
+
+impl InlineHtml {
+    /// The content, unless it was dropped.
+    pub fn bytes(&self) -> Result<&ByteString, InlineHtmlDropped> {
+        match self {
+            InlineHtml::Retained(bytes) => Ok(bytes),
+            _ => Err(InlineHtmlDropped),
+        }
+    }
+
+    /// The length of the content in bytes, whether or not it was dropped.
+    pub fn len(&self) -> usize {
+        match self {
+            InlineHtml::Retained(bytes) => bytes.len(),
+            InlineHtml::Dropped { length } | InlineHtml::Hashed { length, .. } => *length,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The error of code that needs the content of inline HTML that the parser dropped,
+/// such as code that prints the program back, which would otherwise print it wrong.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InlineHtmlDropped;
+
+impl Display for InlineHtmlDropped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("inline HTML was dropped while parsing, keep it with `InlineHtmlMode::Retain`")
+    }
+}
+
+impl std::error::Error for InlineHtmlDropped {}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct FullOpeningTagStatement {
@@ -291,41 +412,30 @@ pub struct ClosingTagStatement {
     pub span: Span,
 }
 
-impl Node for Statement {
+/// A statement parsed by a [`StatementExtension`], for syntax that isn't part of PHP.
+///
+/// The parser doesn't know what the statement means, so it carries whatever the
+/// extension kept of it: the tokens of parts that aren't PHP, and the expressions and
+/// statements of parts that are, which are visited like any other node.
+///
+/// [`StatementExtension`]: crate::parser::extension::StatementExtension
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type")]
+pub struct CustomStatement {
+    /// The name the extension gave the statement, e.g. `unless`.
+    pub tag: ByteString,
+    /// The start of the statement.
+    pub span: Span,
+    pub tokens: Vec<Token<'static>>,
+    pub expressions: Vec<Expression>,
+    pub statements: Vec<Statement>,
+}
+
+impl Node for CustomStatement {
     fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            Statement::Label(statement) => vec![statement],
-            Statement::Goto(statement) => vec![statement],
-            Statement::HaltCompiler(statement) => vec![statement],
-            Statement::Static(statement) => vec![statement],
-            Statement::DoWhile(statement) => vec![statement],
-            Statement::While(statement) => vec![statement],
-            Statement::For(statement) => vec![statement],
-            Statement::Foreach(statement) => vec![statement],
-            Statement::Break(statement) => vec![statement],
-            Statement::Continue(statement) => vec![statement],
-            Statement::Constant(statement) => vec![statement],
-            Statement::Function(statement) => vec![statement],
-            Statement::Class(statement) => vec![statement],
-            Statement::Trait(statement) => vec![statement],
-            Statement::Interface(statement) => vec![statement],
-            Statement::If(statement) => vec![statement],
-            Statement::Switch(statement) => vec![statement],
-            Statement::Echo(statement) => vec![statement],
-            Statement::Expression(statement) => vec![statement],
-            Statement::Return(statement) => vec![statement],
-            Statement::Namespace(statement) => vec![statement],
-            Statement::Use(statement) => vec![statement],
-            Statement::GroupUse(statement) => vec![statement],
-            Statement::Comment(statement) => vec![statement],
-            Statement::Try(statement) => vec![statement],
-            Statement::UnitEnum(statement) => vec![statement],
-            Statement::BackedEnum(statement) => vec![statement],
-            Statement::Block(statement) => vec![statement],
-            Statement::Global(statement) => vec![statement],
-            Statement::Declare(statement) => vec![statement],
-            _ => vec![],
-        }
+        let mut children = self.expressions.children();
+        children.extend(self.statements.children());
+        children
     }
 }
 
@@ -499,6 +609,14 @@ pub struct ConcatExpression {
     pub right: Box<Expression>,
 }
 
+/// A chain of concatenations, e.g. `$a . $b . $c`, flattened into a single node
+/// rather than nested [`ConcatExpression`]s.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ConcatManyExpression {
+    pub parts: Vec<Expression>, // `$a`, `$b`, `$c`
+    pub dots: Vec<Span>,        // `.`, `.`
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct InstanceofExpression {
     pub left: Box<Expression>,
@@ -771,6 +889,21 @@ pub struct ShortArrayExpression {
     pub end: Span, // `]`
 }
 
+impl ShortArrayExpression {
+    pub fn array_syntax(&self) -> ArraySyntax {
+        ArraySyntax::Short
+    }
+
+    pub fn has_trailing_comma(&self) -> bool {
+        self.items.has_trailing_comma()
+    }
+
+    /// Whether the opening and closing brackets are on different lines.
+    pub fn is_multiline(&self) -> bool {
+        self.start.line != self.end.line
+    }
+}
+
 impl Node for ShortArrayExpression {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         vec![&mut self.items]
@@ -788,6 +921,21 @@ pub struct ArrayExpression {
     pub end: Span, // `)`
 }
 
+impl ArrayExpression {
+    pub fn array_syntax(&self) -> ArraySyntax {
+        ArraySyntax::Long
+    }
+
+    pub fn has_trailing_comma(&self) -> bool {
+        self.items.has_trailing_comma()
+    }
+
+    /// Whether the opening and closing parentheses are on different lines.
+    pub fn is_multiline(&self) -> bool {
+        self.start.line != self.end.line
+    }
+}
+
 impl Node for ArrayExpression {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         vec![&mut self.items]
@@ -802,9 +950,26 @@ pub struct ListExpression {
     // `(`
     pub items: Vec<ListEntry>,
     // `$a, $b`
+    pub trailing_comma: Option<Span>,
+    // `,`
     pub end: Span, // `)`
 }
 
+impl ListExpression {
+    pub fn array_syntax(&self) -> ArraySyntax {
+        ArraySyntax::Long
+    }
+
+    pub fn has_trailing_comma(&self) -> bool {
+        self.trailing_comma.is_some()
+    }
+
+    /// Whether the opening and closing parentheses are on different lines.
+    pub fn is_multiline(&self) -> bool {
+        self.start.line != self.end.line
+    }
+}
+
 impl Node for ListExpression {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         self.items.children()
@@ -832,7 +997,9 @@ impl Node for NewExpression {
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct InterpolatedStringExpression {
+    pub start: Span, // `"`
     pub parts: Vec<StringPart>,
+    pub end: Span, // `"`
 }
 
 impl Node for InterpolatedStringExpression {
@@ -843,8 +1010,10 @@ impl Node for InterpolatedStringExpression {
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct HeredocExpression {
+    pub start: Span, // `<<<`
     pub label: ByteString,
     pub parts: Vec<StringPart>,
+    pub end: Span, // the closing label
 }
 
 impl Node for HeredocExpression {
@@ -855,15 +1024,19 @@ impl Node for HeredocExpression {
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct NowdocExpression {
+    pub start: Span, // `<<<`
     pub label: ByteString,
     pub value: ByteString,
+    pub end: Span, // the closing label
 }
 
 impl Node for NowdocExpression {}
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ShellExecExpression {
+    pub start: Span, // `
     pub parts: Vec<StringPart>,
+    pub end: Span, // `
 }
 
 impl Node for ShellExecExpression {
@@ -875,6 +1048,7 @@ impl Node for ShellExecExpression {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct BoolExpression {
     pub value: bool,
+    pub span: Span,
 }
 
 impl Node for BoolExpression {}
@@ -889,7 +1063,7 @@ pub struct ArrayIndexExpression {
 
 impl Node for ArrayIndexExpression {
     fn children(&mut self) -> Vec<&mut dyn Node> {
-        let mut children: Vec<&mut dyn Node> = vec![];
+        let mut children: Vec<&mut dyn Node> = vec![self.array.as_mut()];
         if let Some(index) = &mut self.index {
             children.push(index.as_mut());
         }
@@ -950,6 +1124,7 @@ impl Node for CoalesceExpression {
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct CloneExpression {
+    pub clone: Span,
     pub target: Box<Expression>,
 }
 
@@ -986,6 +1161,7 @@ impl Node for MatchExpression {
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ThrowExpression {
+    pub throw: Span,
     pub value: Box<Expression>,
 }
 
@@ -997,6 +1173,7 @@ impl Node for ThrowExpression {
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct YieldExpression {
+    pub r#yield: Span,
     pub key: Option<Box<Expression>>,
     pub value: Option<Box<Expression>>,
 }
@@ -1016,6 +1193,8 @@ impl Node for YieldExpression {
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct YieldFromExpression {
+    pub r#yield: Span,
+    pub from: Span,
     pub value: Box<Expression>,
 }
 
@@ -1058,136 +1237,148 @@ impl Node for ShortMatchExpression {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum Expression {
-    // eval("$a = 1")
-    Eval(EvalExpression),
-    // empty($a)
-    Empty(EmptyExpression),
-    // die, die(1)
-    Die(DieExpression),
-    // exit, exit(1)
-    Exit(ExitExpression),
-    // isset($a), isset($a, ...)
-    Isset(IssetExpression),
-    // unset($a), isset($a, ...)
-    Unset(UnsetExpression),
-    // print(1), print 1;
-    Print(PrintExpression),
-    Literal(Literal),
-    ArithmeticOperation(ArithmeticOperationExpression),
-    AssignmentOperation(AssignmentOperationExpression),
-    BitwiseOperation(BitwiseOperationExpression),
-    ComparisonOperation(ComparisonOperationExpression),
-    LogicalOperation(LogicalOperationExpression),
-    RangeOperation(RangeOperationExpression),
-    // $a . $b
-    Concat(ConcatExpression),
-    // $foo instanceof Bar
-    Instanceof(InstanceofExpression),
-    // &$foo
-    Reference(ReferenceExpression),
-    // ($a && $b)
-    Parenthesized(ParenthesizedExpression),
-    // @foo()
-    ErrorSuppress(ErrorSuppressExpression),
-    // `foo`, `foo_bar`, etc
-    Identifier(Identifier),
-    // `$foo`, `$foo_bar`, etc
-    Variable(Variable),
-    // include "foo.php"
-    Include(IncludeExpression),
-    // include_once "foo.php"
-    IncludeOnce(IncludeOnceExpression),
-    // require "foo.php"
-    Require(RequireExpression),
-    // require_once "foo.php"
-    RequireOnce(RequireOnceExpression),
-    // `foo(1, 2, 3)`
-    FunctionCall(FunctionCallExpression),
-    // `foo(...)`
-    FunctionClosureCreation(FunctionClosureCreationExpression),
-    // `$foo->bar(1, 2, 3)`
-    MethodCall(MethodCallExpression),
-    // `$foo->bar(...)`
-    MethodClosureCreation(MethodClosureCreationExpression),
-    // `$foo?->bar(1, 2, 3)`
-    NullsafeMethodCall(NullsafeMethodCallExpression),
-    // `Foo::bar(1, 2, 3)`
-    StaticMethodCall(StaticMethodCallExpression),
-    // `Foo::$bar(1, 2, 3)`
-    StaticVariableMethodCall(StaticVariableMethodCallExpression),
-    // `Foo::bar(...)`
-    StaticMethodClosureCreation(StaticMethodClosureCreationExpression),
-    // `Foo::$bar(...)`
-    StaticVariableMethodClosureCreation(StaticVariableMethodClosureCreationExpression),
-    // `foo()->bar`
-    PropertyFetch(PropertyFetchExpression),
-    // `foo()?->bar`
-    NullsafePropertyFetch(NullsafePropertyFetchExpression),
-    // `foo()::$bar`
-    StaticPropertyFetch(StaticPropertyFetchExpression),
-    // `foo()::bar` or `foo()::{$name}`
-    ConstantFetch(ConstantFetchExpression),
-    // `static`
-    Static,
-    // `self`
-    Self_,
-    // `parent`
-    Parent,
-    // `[1, 2, 3]`
-    ShortArray(ShortArrayExpression),
-    // `array(1, 2, 3)`
-    Array(ArrayExpression),
-    // list($a, $b)
-    List(ListExpression),
-    // `function() {}`
-    Closure(ClosureExpression),
-    // `fn() => $foo`
-    ArrowFunction(ArrowFunctionExpression),
-    // `new Foo(1, 2, 3)`
-    New(NewExpression),
-    // `"foo $bar foo"`
-    InterpolatedString(InterpolatedStringExpression),
-    // `<<<"EOT"` / `<<<EOT`
-    Heredoc(HeredocExpression),
-    // `<<<'EOT'`
-    Nowdoc(NowdocExpression),
-    // ``foo``
-    ShellExec(ShellExecExpression),
-    // `new class { ... }`
-    AnonymousClass(AnonymousClassExpression),
-    // `true`, `false`
-    Bool(BoolExpression),
-    // `$foo[0]`
-    ArrayIndex(ArrayIndexExpression),
-    // `null`
-    Null,
-    // `__DIR__`, etc
-    MagicConstant(MagicConstantExpression),
-    // `foo() ?: bar()`
-    ShortTernary(ShortTernaryExpression),
-    // `foo() ? bar() : baz()`
-    Ternary(TernaryExpression),
-    // `foo() ?? bar()`
-    Coalesce(CoalesceExpression),
-    // `clone $foo`
-    Clone(CloneExpression),
-    // `match ($foo) { ... }`
-    Match(MatchExpression),
-    // `match { ... }`
-    ShortMatch(ShortMatchExpression),
-    // `throw new Exception`
-    Throw(ThrowExpression),
-    // `yield $foo`
-    Yield(YieldExpression),
-    // `yield from foo()`
-    YieldFrom(YieldFromExpression),
-    // `(int) "1"`, etc
-    Cast(CastExpression),
-    // ;
-    Noop,
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum Expression {
+        // eval("$a = 1")
+        Eval(EvalExpression),
+        // empty($a)
+        Empty(EmptyExpression),
+        // die, die(1)
+        Die(DieExpression),
+        // exit, exit(1)
+        Exit(ExitExpression),
+        // isset($a), isset($a, ...)
+        Isset(IssetExpression),
+        // unset($a), isset($a, ...)
+        Unset(UnsetExpression),
+        // print(1), print 1;
+        Print(PrintExpression),
+        Literal(Literal),
+        ArithmeticOperation(ArithmeticOperationExpression),
+        AssignmentOperation(AssignmentOperationExpression),
+        BitwiseOperation(BitwiseOperationExpression),
+        ComparisonOperation(ComparisonOperationExpression),
+        LogicalOperation(LogicalOperationExpression),
+        RangeOperation(RangeOperationExpression),
+        // $a . $b
+        Concat(ConcatExpression),
+        // $a . $b . $c
+        ConcatMany(ConcatManyExpression),
+        // $foo instanceof Bar
+        Instanceof(InstanceofExpression),
+        // &$foo
+        Reference(ReferenceExpression),
+        // ($a && $b)
+        Parenthesized(ParenthesizedExpression),
+        // @foo()
+        ErrorSuppress(ErrorSuppressExpression),
+        // `foo`, `foo_bar`, etc
+        Identifier(Identifier),
+        // `$foo`, `$foo_bar`, etc
+        Variable(Variable),
+        // include "foo.php"
+        Include(IncludeExpression),
+        // include_once "foo.php"
+        IncludeOnce(IncludeOnceExpression),
+        // require "foo.php"
+        Require(RequireExpression),
+        // require_once "foo.php"
+        RequireOnce(RequireOnceExpression),
+        // `foo(1, 2, 3)`
+        FunctionCall(FunctionCallExpression),
+        // `foo(...)`
+        FunctionClosureCreation(FunctionClosureCreationExpression),
+        // `$foo->bar(1, 2, 3)`
+        MethodCall(MethodCallExpression),
+        // `$foo->bar(...)`
+        MethodClosureCreation(MethodClosureCreationExpression),
+        // `$foo?->bar(1, 2, 3)`
+        NullsafeMethodCall(NullsafeMethodCallExpression),
+        // `Foo::bar(1, 2, 3)`
+        StaticMethodCall(StaticMethodCallExpression),
+        // `Foo::$bar(1, 2, 3)`
+        StaticVariableMethodCall(StaticVariableMethodCallExpression),
+        // `Foo::bar(...)`
+        StaticMethodClosureCreation(StaticMethodClosureCreationExpression),
+        // `Foo::$bar(...)`
+        StaticVariableMethodClosureCreation(StaticVariableMethodClosureCreationExpression),
+        // `foo()->bar`
+        PropertyFetch(PropertyFetchExpression),
+        // `foo()?->bar`
+        NullsafePropertyFetch(NullsafePropertyFetchExpression),
+        // `foo()::$bar`
+        StaticPropertyFetch(StaticPropertyFetchExpression),
+        // `foo()::bar` or `foo()::{$name}`
+        ConstantFetch(ConstantFetchExpression),
+        // `static`
+        #[leaf]
+        Static(Span),
+        // `self`
+        #[leaf]
+        Self_(Span),
+        // `parent`
+        #[leaf]
+        Parent(Span),
+        // `[1, 2, 3]`
+        ShortArray(ShortArrayExpression),
+        // `array(1, 2, 3)`
+        Array(ArrayExpression),
+        // list($a, $b)
+        List(ListExpression),
+        // `function() {}`
+        Closure(ClosureExpression),
+        // `fn() => $foo`
+        ArrowFunction(ArrowFunctionExpression),
+        // `new Foo(1, 2, 3)`
+        New(NewExpression),
+        // `"foo $bar foo"`
+        InterpolatedString(InterpolatedStringExpression),
+        // `<<<"EOT"` / `<<<EOT`
+        Heredoc(HeredocExpression),
+        // `<<<'EOT'`
+        Nowdoc(NowdocExpression),
+        // ``foo``
+        ShellExec(ShellExecExpression),
+        // `new class { ... }`
+        AnonymousClass(AnonymousClassExpression),
+        // `true`, `false`
+        #[leaf]
+        Bool(BoolExpression),
+        // `$foo[0]`
+        ArrayIndex(ArrayIndexExpression),
+        // `null`
+        #[leaf]
+        Null(Span),
+        // `__DIR__`, etc
+        MagicConstant(MagicConstantExpression),
+        // `foo() ?: bar()`
+        ShortTernary(ShortTernaryExpression),
+        // `foo() ? bar() : baz()`
+        Ternary(TernaryExpression),
+        // `foo() ?? bar()`
+        Coalesce(CoalesceExpression),
+        // `clone $foo`
+        Clone(CloneExpression),
+        // `match ($foo) { ... }`
+        Match(MatchExpression),
+        // `match { ... }`
+        ShortMatch(ShortMatchExpression),
+        // `throw new Exception`
+        Throw(ThrowExpression),
+        // `yield $foo`
+        Yield(YieldExpression),
+        // `yield from foo()`
+        YieldFrom(YieldFromExpression),
+        // `(int) "1"`, etc
+        Cast(CastExpression),
+        // ;
+        Noop,
+        // An expression that failed to parse, when recovering from errors.
+        #[leaf]
+        Error(Span),
+    }
 }
 
 impl Node for EvalExpression {
@@ -1252,6 +1443,12 @@ impl Node for ConcatExpression {
     }
 }
 
+impl Node for ConcatManyExpression {
+    fn children(&mut self) -> Vec<&mut dyn Node> {
+        self.parts.iter_mut().map(|part| part as &mut dyn Node).collect()
+    }
+}
+
 impl Node for InstanceofExpression {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         vec![self.left.as_mut(), self.right.as_mut()]
@@ -1300,80 +1497,6 @@ impl Node for RequireOnceExpression {
     }
 }
 
-impl Node for Expression {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            Expression::Eval(expression) => vec![expression],
-            Expression::Empty(expression) => vec![expression],
-            Expression::Die(expression) => vec![expression],
-            Expression::Exit(expression) => vec![expression],
-            Expression::Isset(expression) => vec![expression],
-            Expression::Unset(expression) => vec![expression],
-            Expression::Print(expression) => vec![expression],
-            Expression::Literal(literal) => vec![literal],
-            Expression::ArithmeticOperation(operation) => vec![operation],
-            Expression::AssignmentOperation(operation) => vec![operation],
-            Expression::BitwiseOperation(operation) => vec![operation],
-            Expression::ComparisonOperation(operation) => vec![operation],
-            Expression::LogicalOperation(operation) => vec![operation],
-            Expression::Concat(expression) => vec![expression],
-            Expression::Instanceof(expression) => vec![expression],
-            Expression::Reference(expression) => vec![expression],
-            Expression::Parenthesized(expression) => vec![expression],
-            Expression::ErrorSuppress(expression) => vec![expression],
-            Expression::Identifier(identifier) => vec![identifier],
-            Expression::Variable(variable) => vec![variable],
-            Expression::Include(expression) => vec![expression],
-            Expression::IncludeOnce(expression) => vec![expression],
-            Expression::Require(expression) => vec![expression],
-            Expression::RequireOnce(expression) => vec![expression],
-            Expression::FunctionCall(expression) => vec![expression],
-            Expression::FunctionClosureCreation(expression) => vec![expression],
-            Expression::MethodCall(expression) => vec![expression],
-            Expression::MethodClosureCreation(expression) => vec![expression],
-            Expression::NullsafeMethodCall(expression) => vec![expression],
-            Expression::StaticMethodCall(expression) => vec![expression],
-            Expression::StaticVariableMethodCall(expression) => vec![expression],
-            Expression::StaticMethodClosureCreation(expression) => vec![expression],
-            Expression::StaticVariableMethodClosureCreation(expression) => vec![expression],
-            Expression::PropertyFetch(expression) => vec![expression],
-            Expression::NullsafePropertyFetch(expression) => vec![expression],
-            Expression::StaticPropertyFetch(expression) => vec![expression],
-            Expression::ConstantFetch(expression) => vec![expression],
-            Expression::Static => vec![],
-            Expression::Self_ => vec![],
-            Expression::Parent => vec![],
-            Expression::ShortArray(expression) => vec![expression],
-            Expression::Array(expression) => vec![expression],
-            Expression::List(expression) => vec![expression],
-            Expression::Closure(expression) => vec![expression],
-            Expression::ArrowFunction(expression) => vec![expression],
-            Expression::New(expression) => vec![expression],
-            Expression::InterpolatedString(expression) => vec![expression],
-            Expression::Heredoc(expression) => vec![expression],
-            Expression::Nowdoc(expression) => vec![expression],
-            Expression::ShellExec(expression) => vec![expression],
-            Expression::AnonymousClass(expression) => vec![expression],
-            Expression::Bool(_) => vec![],
-            Expression::ArrayIndex(expression) => vec![expression],
-            Expression::Null => vec![],
-            Expression::MagicConstant(constant) => vec![constant],
-            Expression::ShortTernary(expression) => vec![expression],
-            Expression::Ternary(expression) => vec![expression],
-            Expression::Coalesce(expression) => vec![expression],
-            Expression::Clone(expression) => vec![expression],
-            Expression::Match(expression) => vec![expression],
-            Expression::ShortMatch(expression) => vec![expression],
-            Expression::Throw(expression) => vec![expression],
-            Expression::Yield(expression) => vec![expression],
-            Expression::YieldFrom(expression) => vec![expression],
-            Expression::Cast(expression) => vec![expression],
-            Expression::RangeOperation(expression) => vec![expression],
-            Expression::Noop => vec![],
-        }
-    }
-}
-
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct DefaultMatchArm {
@@ -1447,11 +1570,13 @@ impl Node for MagicConstantExpression {
     //
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum StringPart {
-    Literal(LiteralStringPart),
-    Expression(ExpressionStringPart),
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum StringPart {
+        Literal(LiteralStringPart),
+        Expression(ExpressionStringPart),
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -1476,15 +1601,6 @@ impl Node for ExpressionStringPart {
     }
 }
 
-impl Node for StringPart {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            StringPart::Literal(part) => vec![part],
-            StringPart::Expression(part) => vec![part],
-        }
-    }
-}
-
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "value")]
 pub enum ArrayItem {
@@ -1545,11 +1661,21 @@ pub enum ListEntry {
     Value {
         value: Expression, // `$foo`
     },
+    ReferencedValue {
+        ampersand: Span,   // `&`
+        value: Expression, // `$foo`
+    },
     KeyValue {
         key: Expression,    // `$foo`
         double_arrow: Span, // `=>`
         value: Expression,  // `$bar`
     },
+    ReferencedKeyValue {
+        key: Expression,    // `$foo`
+        double_arrow: Span, // `=>`
+        ampersand: Span,    // `&`
+        value: Expression,  // `$bar`
+    },
 }
 
 impl Node for ListEntry {
@@ -1557,11 +1683,140 @@ impl Node for ListEntry {
         match self {
             ListEntry::Skipped => vec![],
             ListEntry::Value { value } => vec![value],
+            ListEntry::ReferencedValue {
+                ampersand: _,
+                value,
+            } => vec![value],
             ListEntry::KeyValue {
                 key,
                 double_arrow: _,
                 value,
             } => vec![key, value],
+            ListEntry::ReferencedKeyValue {
+                key,
+                double_arrow: _,
+                ampersand: _,
+                value,
+            } => vec![key, value],
         }
     }
 }
+
+visit_spans! {
+    enum UseKind { Normal, Function, Const }
+    struct StaticVar { var, default }
+    enum Ending { Semicolon(value), CloseTag(value) }
+    enum BodySyntax { Brace, Alternative }
+    struct HaltCompilerStatement { content }
+    struct AfterHaltStatement { statements, errors }
+    struct StaticStatement { vars }
+    struct SwitchStatement {
+        switch, left_parenthesis, condition, right_parenthesis, body_syntax, cases,
+    }
+    struct EchoStatement { echo, values, ending }
+    struct ReturnStatement { r#return, value, ending }
+    struct UseStatement { comments, r#use, kind, uses, semicolon }
+    struct GroupUseStatement {
+        comments, r#use, prefix, kind, left_brace, uses, right_brace, semicolon,
+    }
+    struct TypeAliasStatement { type_keyword, name, equals, r#type, semicolon }
+    struct InlineHtmlStatement { span, html }
+    enum InlineHtml { Retained(value), Dropped { length }, Hashed { hash, length } }
+    struct FullOpeningTagStatement { span }
+    struct ShortOpeningTagStatement { span }
+    struct EchoOpeningTagStatement { span }
+    struct ClosingTagStatement { span }
+    struct CustomStatement { tag, span, tokens, expressions, statements }
+    struct ExpressionStatement { expression, ending }
+    struct GlobalStatement { global, variables }
+    struct BlockStatement { left_brace, statements, right_brace }
+    enum CastKind { Int, Bool, Float, String, Array, Object, Unset }
+    struct Case { condition, body }
+    struct Use { name, alias, kind }
+    struct EvalExpression { eval, argument }
+    struct EmptyExpression { empty, argument }
+    struct DieExpression { die, argument }
+    struct ExitExpression { exit, argument }
+    struct IssetExpression { isset, arguments }
+    struct UnsetExpression { unset, arguments }
+    struct PrintExpression { print, value, argument }
+    struct ConcatExpression { left, dot, right }
+    struct ConcatManyExpression { parts, dots }
+    struct InstanceofExpression { left, instanceof, right }
+    struct ReferenceExpression { ampersand, right }
+    struct ParenthesizedExpression { start, expr, end }
+    struct ErrorSuppressExpression { at, expr }
+    struct IncludeExpression { include, path }
+    struct IncludeOnceExpression { include_once, path }
+    struct RequireExpression { require, path }
+    struct RequireOnceExpression { require_once, path }
+    struct FunctionCallExpression { target, arguments }
+    struct FunctionClosureCreationExpression { target, placeholder }
+    struct MethodCallExpression { target, arrow, method, arguments }
+    struct MethodClosureCreationExpression { target, arrow, method, placeholder }
+    struct NullsafeMethodCallExpression { target, question_arrow, method, arguments }
+    struct StaticMethodCallExpression { target, double_colon, method, arguments }
+    struct StaticVariableMethodCallExpression { target, double_colon, method, arguments }
+    struct StaticMethodClosureCreationExpression { target, double_colon, method, placeholder }
+    struct StaticVariableMethodClosureCreationExpression {
+        target, double_colon, method, placeholder,
+    }
+    struct PropertyFetchExpression { target, arrow, property }
+    struct NullsafePropertyFetchExpression { target, question_arrow, property }
+    struct StaticPropertyFetchExpression { target, double_colon, property }
+    struct ConstantFetchExpression { target, double_colon, constant }
+    struct ShortArrayExpression { start, items, end }
+    struct ArrayExpression { array, start, items, end }
+    struct ListExpression { list, start, items, trailing_comma, end }
+    struct NewExpression { new, target, arguments }
+    struct InterpolatedStringExpression { start, parts, end }
+    struct HeredocExpression { start, label, parts, end }
+    struct NowdocExpression { start, label, value, end }
+    struct ShellExecExpression { start, parts, end }
+    struct BoolExpression { value, span }
+    struct ArrayIndexExpression { array, left_bracket, index, right_bracket }
+    struct ShortTernaryExpression { condition, question_colon, r#else }
+    struct TernaryExpression { condition, question, then, colon, r#else }
+    struct CoalesceExpression { lhs, double_question, rhs }
+    struct CloneExpression { clone, target }
+    struct MatchExpression {
+        keyword, left_parenthesis, condition, right_parenthesis, left_brace, default, arms,
+        right_brace,
+    }
+    struct ThrowExpression { throw, value }
+    struct YieldExpression { r#yield, key, value }
+    struct YieldFromExpression { r#yield, from, value }
+    struct CastExpression { cast, kind, value }
+    struct ShortMatchExpression { keyword, default, arms }
+    struct DefaultMatchArm { keyword, double_arrow, body }
+    struct MatchArm { conditions, arrow, body }
+    enum MatchArmBody { Block { left_brace, statements, right_brace }, Expression { expression } }
+    enum MagicConstantExpression {
+        Directory(value),
+        File(value),
+        Line(value),
+        Class(value),
+        Function(value),
+        Method(value),
+        Namespace(value),
+        Trait(value),
+        CompilerHaltOffset(value),
+    }
+    struct LiteralStringPart { value }
+    struct ExpressionStringPart { expression }
+    enum ArrayItem {
+        Skipped,
+        Value { value },
+        ReferencedValue { ampersand, value },
+        SpreadValue { ellipsis, value },
+        KeyValue { key, double_arrow, value },
+        ReferencedKeyValue { key, double_arrow, ampersand, value },
+    }
+    enum ListEntry {
+        Skipped,
+        Value { value },
+        ReferencedValue { ampersand, value },
+        KeyValue { key, double_arrow, value },
+        ReferencedKeyValue { key, double_arrow, ampersand, value },
+    }
+}
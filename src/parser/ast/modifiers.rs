@@ -3,6 +3,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::token::Span;
+use crate::spans::visit_spans;
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type")]
@@ -12,6 +13,30 @@ pub enum Visibility {
     Private,
 }
 
+/// A modifier that applies to a member, whether or not it was written in the source.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ResolvedModifier<'a, T> {
+    pub modifier: &'a T,
+    /// Whether the modifier is implied, e.g. the `public` of a method without a
+    /// visibility, rather than written.
+    pub synthetic: bool,
+}
+
+impl<'a, T> ResolvedModifier<'a, T> {
+    fn chain(written: &'a [T], synthetic: &'a [T]) -> impl Iterator<Item = Self> {
+        written
+            .iter()
+            .map(|modifier| Self {
+                modifier,
+                synthetic: false,
+            })
+            .chain(synthetic.iter().map(|modifier| Self {
+                modifier,
+                synthetic: true,
+            }))
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "value")]
 pub enum VisibilityModifier {
@@ -110,16 +135,32 @@ impl PropertyModifier {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
-#[repr(transparent)]
 pub struct PropertyModifierGroup {
     pub modifiers: Vec<PropertyModifier>,
+    /// Modifiers that apply without being written, filled in by [`normalize_modifiers`].
+    ///
+    /// [`normalize_modifiers`]: crate::normalize::normalize_modifiers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synthetic: Vec<PropertyModifier>,
 }
 
 impl PropertyModifierGroup {
+    pub fn new(modifiers: Vec<PropertyModifier>) -> Self {
+        Self {
+            modifiers,
+            synthetic: vec![],
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.modifiers.is_empty()
     }
 
+    /// Every modifier that applies, written modifiers first.
+    pub fn resolved(&self) -> impl Iterator<Item = ResolvedModifier<'_, PropertyModifier>> {
+        ResolvedModifier::chain(&self.modifiers, &self.synthetic)
+    }
+
     pub fn get_readonly(&self) -> Option<&PropertyModifier> {
         self.modifiers
             .iter()
@@ -182,16 +223,32 @@ impl MethodModifier {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
-#[repr(transparent)]
 pub struct MethodModifierGroup {
     pub modifiers: Vec<MethodModifier>,
+    /// Modifiers that apply without being written, filled in by [`normalize_modifiers`].
+    ///
+    /// [`normalize_modifiers`]: crate::normalize::normalize_modifiers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synthetic: Vec<MethodModifier>,
 }
 
 impl MethodModifierGroup {
+    pub fn new(modifiers: Vec<MethodModifier>) -> Self {
+        Self {
+            modifiers,
+            synthetic: vec![],
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.modifiers.is_empty()
     }
 
+    /// Every modifier that applies, written modifiers first.
+    pub fn resolved(&self) -> impl Iterator<Item = ResolvedModifier<'_, MethodModifier>> {
+        ResolvedModifier::chain(&self.modifiers, &self.synthetic)
+    }
+
     pub fn has_final(&self) -> bool {
         self.modifiers
             .iter()
@@ -205,9 +262,8 @@ impl MethodModifierGroup {
     }
 
     pub fn has_abstract(&self) -> bool {
-        self.modifiers
-            .iter()
-            .any(|modifier| matches!(modifier, MethodModifier::Abstract { .. }))
+        self.resolved()
+            .any(|resolved| matches!(resolved.modifier, MethodModifier::Abstract { .. }))
     }
 
     pub fn get_abstract(&self) -> Option<&MethodModifier> {
@@ -237,6 +293,16 @@ pub enum ClassModifier {
     Readonly(Span),
 }
 
+impl ClassModifier {
+    pub fn span(&self) -> Span {
+        match self {
+            ClassModifier::Final(span) => *span,
+            ClassModifier::Abstract(span) => *span,
+            ClassModifier::Readonly(span) => *span,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[repr(transparent)]
 pub struct ClassModifierGroup {
@@ -276,17 +342,44 @@ pub enum ConstantModifier {
     Private(Span),
 }
 
+impl ConstantModifier {
+    pub fn span(&self) -> Span {
+        match self {
+            ConstantModifier::Final(span) => *span,
+            ConstantModifier::Public(span) => *span,
+            ConstantModifier::Protected(span) => *span,
+            ConstantModifier::Private(span) => *span,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
-#[repr(transparent)]
 pub struct ConstantModifierGroup {
     pub modifiers: Vec<ConstantModifier>,
+    /// Modifiers that apply without being written, filled in by [`normalize_modifiers`].
+    ///
+    /// [`normalize_modifiers`]: crate::normalize::normalize_modifiers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synthetic: Vec<ConstantModifier>,
 }
 
 impl ConstantModifierGroup {
+    pub fn new(modifiers: Vec<ConstantModifier>) -> Self {
+        Self {
+            modifiers,
+            synthetic: vec![],
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.modifiers.is_empty()
     }
 
+    /// Every modifier that applies, written modifiers first.
+    pub fn resolved(&self) -> impl Iterator<Item = ResolvedModifier<'_, ConstantModifier>> {
+        ResolvedModifier::chain(&self.modifiers, &self.synthetic)
+    }
+
     pub fn has_final(&self) -> bool {
         self.modifiers
             .iter()
@@ -305,3 +398,35 @@ impl ConstantModifierGroup {
             .unwrap_or(Visibility::Public)
     }
 }
+
+visit_spans! {
+    enum VisibilityModifier { Public(value), Protected(value), Private(value) }
+    enum PromotedPropertyModifier {
+        Public(value),
+        Protected(value),
+        Private(value),
+        Readonly(value),
+    }
+    struct PromotedPropertyModifierGroup { modifiers }
+    enum PropertyModifier {
+        Public(value),
+        Protected(value),
+        Private(value),
+        Static(value),
+        Readonly(value),
+    }
+    struct PropertyModifierGroup { modifiers, synthetic }
+    enum MethodModifier {
+        Final(value),
+        Static(value),
+        Abstract(value),
+        Public(value),
+        Protected(value),
+        Private(value),
+    }
+    struct MethodModifierGroup { modifiers, synthetic }
+    enum ClassModifier { Final(value), Abstract(value), Readonly(value) }
+    struct ClassModifierGroup { modifiers }
+    enum ConstantModifier { Final(value), Public(value), Protected(value), Private(value) }
+    struct ConstantModifierGroup { modifiers, synthetic }
+}
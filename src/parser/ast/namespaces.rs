@@ -3,9 +3,11 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::token::Span;
+use crate::node::node_enum;
 use crate::node::Node;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::Statement;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -59,18 +61,17 @@ impl Node for BracedNamespaceBody {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum NamespaceStatement {
-    Unbraced(UnbracedNamespace), // `namespace Foo; *statements*`
-    Braced(BracedNamespace),     // `namespace Foo { *statements* }`
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum NamespaceStatement {
+        Unbraced(UnbracedNamespace), // `namespace Foo; *statements*`
+        Braced(BracedNamespace),     // `namespace Foo { *statements* }`
+    }
 }
 
-impl Node for NamespaceStatement {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            NamespaceStatement::Unbraced(namespace) => vec![namespace],
-            NamespaceStatement::Braced(namespace) => vec![namespace],
-        }
-    }
+visit_spans! {
+    struct UnbracedNamespace { start, name, end, statements }
+    struct BracedNamespace { namespace, name, body }
+    struct BracedNamespaceBody { start, end, statements }
 }
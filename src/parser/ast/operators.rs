@@ -5,6 +5,7 @@ use serde::Serialize;
 use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::Expression;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "value")]
@@ -531,3 +532,69 @@ impl Node for RangeOperationExpression {
         }
     }
 }
+
+visit_spans! {
+    enum ArithmeticOperationExpression {
+        Addition { left, plus, right },
+        Subtraction { left, minus, right },
+        Multiplication { left, asterisk, right },
+        Division { left, slash, right },
+        Modulo { left, percent, right },
+        Exponentiation { left, pow, right },
+        Negative { minus, right },
+        Positive { plus, right },
+        PreIncrement { increment, right },
+        PostIncrement { left, increment },
+        PreDecrement { decrement, right },
+        PostDecrement { left, decrement },
+    }
+    enum AssignmentOperationExpression {
+        Assign { left, equals, right },
+        Addition { left, plus_equals, right },
+        Subtraction { left, minus_equals, right },
+        Multiplication { left, asterisk_equals, right },
+        Division { left, slash_equals, right },
+        Modulo { left, percent_equals, right },
+        Exponentiation { left, pow_equals, right },
+        Concat { left, dot_equals, right },
+        BitwiseAnd { left, ampersand_equals, right },
+        BitwiseOr { left, pipe_equals, right },
+        BitwiseXor { left, caret_equals, right },
+        LeftShift { left, left_shift_equals, right },
+        RightShift { left, right_shift_equals, right },
+        Coalesce { left, coalesce_equals, right },
+    }
+    enum BitwiseOperationExpression {
+        And { left, and, right },
+        Or { left, or, right },
+        Xor { left, xor, right },
+        LeftShift { left, left_shift, right },
+        RightShift { left, right_shift, right },
+        Not { not, right },
+    }
+    enum ComparisonOperationExpression {
+        Equal { left, double_equals, right },
+        Identical { left, triple_equals, right },
+        NotEqual { left, bang_equals, right },
+        AngledNotEqual { left, angled_left_right, right },
+        NotIdentical { left, bang_double_equals, right },
+        LessThan { left, less_than, right },
+        GreaterThan { left, greater_than, right },
+        LessThanOrEqual { left, less_than_equals, right },
+        GreaterThanOrEqual { left, greater_than_equals, right },
+        Spaceship { left, spaceship, right },
+    }
+    enum LogicalOperationExpression {
+        And { left, double_ampersand, right },
+        Or { left, double_pipe, right },
+        Not { bang, right },
+        LogicalAnd { left, and, right },
+        LogicalOr { left, or, right },
+        LogicalXor { left, xor, right },
+    }
+    enum RangeOperationExpression {
+        Exclusive { lower_bound, double_dot, upper_bound },
+        Inclusive { lower_bound, double_dot_equals, upper_bound },
+        Endless { lower_bound, double_dot },
+    }
+}
@@ -5,14 +5,17 @@ use serde::Serialize;
 use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::comments::DocRef;
 use crate::parser::ast::data_type::Type;
 use crate::parser::ast::modifiers::PropertyModifierGroup;
 use crate::parser::ast::variables::SimpleVariable;
 use crate::parser::ast::Expression;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct Property {
+    pub doc: Option<DocRef>,
     pub attributes: Vec<AttributeGroup>,
     #[serde(flatten)]
     pub modifiers: PropertyModifierGroup,
@@ -37,6 +40,7 @@ impl Node for Property {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct VariableProperty {
+    pub doc: Option<DocRef>,
     pub attributes: Vec<AttributeGroup>,
     pub r#type: Option<Type>,
     pub entries: Vec<PropertyEntry>,
@@ -88,3 +92,9 @@ impl PropertyEntry {
         }
     }
 }
+
+visit_spans! {
+    struct Property { doc, attributes, modifiers, r#type, entries, end }
+    struct VariableProperty { doc, attributes, r#type, entries, end }
+    enum PropertyEntry { Uninitialized { variable }, Initialized { variable, equals, value } }
+}
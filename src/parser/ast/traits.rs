@@ -3,8 +3,10 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::lexer::token::Span;
+use crate::node::node_enum;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::comments::DocRef;
 use crate::parser::ast::constant::ClassishConstant;
 use crate::parser::ast::functions::AbstractConstructor;
 use crate::parser::ast::functions::AbstractMethod;
@@ -14,32 +16,20 @@ use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::modifiers::VisibilityModifier;
 use crate::parser::ast::properties::Property;
 use crate::parser::ast::properties::VariableProperty;
+use crate::spans::visit_spans;
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(tag = "type", content = "value")]
-pub enum TraitMember {
-    Constant(ClassishConstant),
-    TraitUsage(TraitUsage),
-    Property(Property),
-    VariableProperty(VariableProperty),
-    AbstractMethod(AbstractMethod),
-    AbstractConstructor(AbstractConstructor),
-    ConcreteMethod(ConcreteMethod),
-    ConcreteConstructor(ConcreteConstructor),
-}
-
-impl Node for TraitMember {
-    fn children(&mut self) -> Vec<&mut dyn Node> {
-        match self {
-            TraitMember::Constant(constant) => vec![constant],
-            TraitMember::TraitUsage(usage) => vec![usage],
-            TraitMember::Property(property) => vec![property],
-            TraitMember::VariableProperty(property) => vec![property],
-            TraitMember::AbstractMethod(method) => vec![method],
-            TraitMember::AbstractConstructor(constructor) => vec![constructor],
-            TraitMember::ConcreteMethod(method) => vec![method],
-            TraitMember::ConcreteConstructor(constructor) => vec![constructor],
-        }
+node_enum! {
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+    #[serde(tag = "type", content = "value")]
+    pub enum TraitMember {
+        Constant(ClassishConstant),
+        TraitUsage(TraitUsage),
+        Property(Property),
+        VariableProperty(VariableProperty),
+        AbstractMethod(AbstractMethod),
+        AbstractConstructor(AbstractConstructor),
+        ConcreteMethod(ConcreteMethod),
+        ConcreteConstructor(ConcreteConstructor),
     }
 }
 
@@ -60,6 +50,7 @@ impl Node for TraitBody {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct TraitStatement {
+    pub doc: Option<DocRef>,
     pub r#trait: Span,
     pub name: SimpleIdentifier,
     pub attributes: Vec<AttributeGroup>,
@@ -77,6 +68,9 @@ impl Node for TraitStatement {
 pub struct TraitUsage {
     pub r#use: Span,
     pub traits: Vec<SimpleIdentifier>,
+    /// Whether the traits are followed by an adaptation block, which may be empty,
+    /// rather than a semicolon.
+    pub braced: bool,
     pub adaptations: Vec<TraitUsageAdaptation>,
 }
 
@@ -106,3 +100,14 @@ pub enum TraitUsageAdaptation {
         insteadof: Vec<SimpleIdentifier>,
     },
 }
+
+visit_spans! {
+    struct TraitBody { left_brace, members, right_brace }
+    struct TraitStatement { doc, r#trait, name, attributes, body }
+    struct TraitUsage { r#use, traits, braced, adaptations }
+    enum TraitUsageAdaptation {
+        Alias { r#trait, method, alias, visibility },
+        Visibility { r#trait, method, visibility },
+        Precedence { r#trait, method, insteadof },
+    }
+}
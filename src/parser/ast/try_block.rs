@@ -6,6 +6,7 @@ use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::Block;
+use crate::spans::visit_spans;
 
 use super::variables::SimpleVariable;
 
@@ -84,3 +85,10 @@ impl Node for FinallyBlock {
         vec![&mut self.body as &mut dyn Node]
     }
 }
+
+visit_spans! {
+    enum CatchType { Identifier { identifier }, Union { identifiers } }
+    struct TryStatement { start, end, body, catches, finally }
+    struct CatchBlock { start, end, types, var, body }
+    struct FinallyBlock { start, end, body }
+}
@@ -7,6 +7,9 @@ use serde::Serialize;
 
 use crate::lexer::token::Span;
 use crate::node::Node;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
@@ -23,6 +26,11 @@ impl<T> CommaSeparated<T> {
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         self.inner.iter_mut()
     }
+
+    /// Whether the last item is followed by a comma.
+    pub fn has_trailing_comma(&self) -> bool {
+        !self.inner.is_empty() && self.commas.len() == self.inner.len()
+    }
 }
 
 impl<T: Node> Node for CommaSeparated<T> {
@@ -31,6 +39,22 @@ impl<T: Node> Node for CommaSeparated<T> {
     }
 }
 
+impl<T: VisitSpans> VisitSpans for CommaSeparated<T> {
+    fn visit_spans<'a>(&'a self, visitor: &mut dyn SpanVisitor<'a>) {
+        visitor.enter(Segment::Field("inner"));
+        self.inner.visit_spans(visitor);
+        visitor.leave();
+        visitor.enter(Segment::Field("commas"));
+        self.commas.visit_spans(visitor);
+        visitor.leave();
+    }
+
+    fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut Span)) {
+        self.inner.visit_spans_mut(f);
+        self.commas.visit_spans_mut(f);
+    }
+}
+
 impl<T> IntoIterator for CommaSeparated<T> {
     type Item = T;
     type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -8,6 +8,7 @@ use crate::lexer::byte_string::ByteString;
 use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::Expression;
+use crate::spans::visit_spans;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type", content = "value")]
@@ -70,3 +71,10 @@ impl Display for SimpleVariable {
         write!(f, "{}", self.name)
     }
 }
+
+visit_spans! {
+    enum Variable { SimpleVariable(value), VariableVariable(value), BracedVariableVariable(value) }
+    struct SimpleVariable { span, name }
+    struct VariableVariable { span, variable }
+    struct BracedVariableVariable { start, variable, end }
+}
@@ -0,0 +1,113 @@
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// The number of tokens the parser moves through between two looks at a
+/// [`CancellationToken`].
+pub(crate) const CANCELLATION_INTERVAL: usize = 1024;
+
+/// A flag that stops a parse, or an analysis, that is running on another thread, e.g.
+/// when a language server gets a newer version of the document being parsed.
+///
+/// Clones share the flag, so a clone can be given to the parser with
+/// [`ParserConfig::cancellation`] and the original kept to cancel it with.
+///
+/// [`ParserConfig::cancellation`]: crate::parser::config::ParserConfig::cancellation
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the work that this token, or any of its clones, was given to. Work that
+    /// is given the token afterwards is cancelled straight away.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Debug for CancellationToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CancellationToken")
+            .field(&self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Tokens are compared by identity, so a token is only equal to its clones.
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::lexer::token::Token;
+    use crate::lexer::Lexer;
+    use crate::parser::config::ParserConfig;
+    use crate::parser::construct_with_config;
+    use crate::parser::error::ErrorCategory;
+
+    #[test]
+    fn test_cancel_from_another_thread() {
+        let mut code = "<?php\n".to_string();
+        for i in 0..100_000 {
+            code.push_str(&format!(
+                "function f{i}($a) {{ return foo($a, [1, 2], \"{{$a}} {i}\") . $a->b; }}\n"
+            ));
+        }
+        let tokens = Lexer::new()
+            .tokenize(&code)
+            .unwrap()
+            .into_iter()
+            .map(Token::into_owned)
+            .collect::<Vec<_>>();
+
+        let token = CancellationToken::new();
+        let config = ParserConfig::new().cancellation(token.clone());
+        let parse = thread::spawn(move || {
+            let result = construct_with_config(&tokens, config);
+
+            (result, Instant::now())
+        });
+
+        thread::sleep(Duration::from_millis(20));
+        let cancelled_at = Instant::now();
+        token.cancel();
+
+        let (result, stopped_at) = parse.join().unwrap();
+        let stack = result.unwrap_err();
+        assert_eq!(stack.errors.len(), 1);
+        assert_eq!(stack.errors[0].category(), ErrorCategory::Cancelled);
+        assert!(!stack.partial.is_empty());
+        assert!(stopped_at.duration_since(cancelled_at) < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_cancelled_before_the_parse() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let config = ParserConfig::new().cancellation(token);
+        let stack = crate::parse_with_config("<?php echo 1;", config).unwrap_err();
+        assert_eq!(stack.errors[0].id, "E093");
+
+        assert!(crate::parse_with_config("<?php echo 1;", ParserConfig::new()).is_ok());
+    }
+}
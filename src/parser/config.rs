@@ -0,0 +1,513 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::parser::ast::Statement;
+use crate::parser::cancellation::CancellationToken;
+use crate::parser::encoding::SourceEncoding;
+use crate::parser::error::ParseResult;
+use crate::parser::extension::StatementExtension;
+use crate::parser::state::State;
+use crate::parser::symbols::SymbolEvent;
+use crate::parser::symbols::SymbolHandler;
+use crate::parser::version::PhpVersion;
+
+/// How much of the content of inline HTML the parser keeps, see
+/// [`ParserConfig::inline_html`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum InlineHtmlMode {
+    /// Keep the content.
+    #[default]
+    Retain,
+    /// Keep the span and the length of the content.
+    SpanOnly,
+    /// Keep the span, the length and a 64-bit hash of the content.
+    Hash,
+}
+
+/// What the parser does with the end of a file after `__halt_compiler();`, see
+/// [`ParserConfig::after_halt`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum AfterHaltMode {
+    /// Keep it as data, like PHP.
+    #[default]
+    Data,
+    /// Keep it as data, and also parse it as PHP code.
+    Parse,
+}
+
+/// Options that change how the parser builds the AST.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParserConfig {
+    pub(crate) skip_bodies: bool,
+    pub(crate) flatten_concat: bool,
+    pub(crate) source_encoding: Option<&'static SourceEncoding>,
+    pub(crate) php_version: PhpVersion,
+    pub(crate) on_symbol: Option<SymbolHandler>,
+    pub(crate) statement_extension: Option<StatementExtension>,
+    pub(crate) inline_html: InlineHtmlMode,
+    pub(crate) after_halt: AfterHaltMode,
+    pub(crate) attribute_token_limit: usize,
+    pub(crate) cancellation: Option<CancellationToken>,
+    pub(crate) source_name: Option<PathBuf>,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            skip_bodies: false,
+            flatten_concat: true,
+            source_encoding: None,
+            php_version: PhpVersion::default(),
+            on_symbol: None,
+            statement_extension: None,
+            inline_html: InlineHtmlMode::default(),
+            after_halt: AfterHaltMode::default(),
+            attribute_token_limit: 10_000,
+            cancellation: None,
+            source_name: None,
+        }
+    }
+}
+
+impl ParserConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip over the bodies of functions, methods, and closures instead of parsing them.
+    ///
+    /// Skipped bodies keep the spans of their braces but contain no statements, so the
+    /// rest of the AST has the same shape as a regular parse. This makes parsing much
+    /// cheaper when only signatures are of interest, e.g. when indexing a project.
+    ///
+    /// Declarations nested inside a skipped body, such as classes or named functions
+    /// declared within a function, are not discovered.
+    pub fn skip_bodies(mut self, skip_bodies: bool) -> Self {
+        self.skip_bodies = skip_bodies;
+
+        self
+    }
+
+    /// Flatten chains of three or more concatenations, such as `$a . $b . $c`, into a
+    /// single [`ConcatManyExpression`] instead of nesting a [`ConcatExpression`] for
+    /// each `.`. Enabled by default.
+    ///
+    /// Generated code can concatenate thousands of pieces, and the nested form is deep
+    /// enough to overflow the stack of anything that walks the AST recursively.
+    ///
+    /// [`ConcatManyExpression`]: crate::parser::ast::ConcatManyExpression
+    /// [`ConcatExpression`]: crate::parser::ast::ConcatExpression
+    pub fn flatten_concat(mut self, flatten_concat: bool) -> Self {
+        self.flatten_concat = flatten_concat;
+
+        self
+    }
+
+    /// The encoding of the source, if known. When the encoding has a `transcode`
+    /// function, the source is transcoded before it is lexed.
+    ///
+    /// Spans in the AST point into the transcoded source, while the spans of errors are
+    /// mapped back to the original source, so they can be shown against the file the
+    /// user sees.
+    pub fn source_encoding_hint(mut self, encoding: Option<&'static SourceEncoding>) -> Self {
+        self.source_encoding = encoding;
+
+        self
+    }
+
+    /// The version of PHP the source is written for, the latest by default.
+    ///
+    /// Names that the targeted version reserves are rejected, while names reserved by a
+    /// later version are accepted. See [`RESERVED_WORDS`] for the words and versions.
+    /// Syntax that the targeted version doesn't support, such as a `match` expression
+    /// before PHP 8.0, is an error. See [`Feature`] for the syntax and versions, and
+    /// `tests/versions/MATRIX.md` for what is covered.
+    ///
+    /// [`RESERVED_WORDS`]: crate::parser::reserved::RESERVED_WORDS
+    /// [`Feature`]: crate::parser::version::Feature
+    pub fn php_version(mut self, version: PhpVersion) -> Self {
+        self.php_version = version;
+
+        self
+    }
+
+    /// Call `handler` with every class, interface, trait, enum, function, method,
+    /// property, constant and enum case as soon as it has been parsed, e.g. to build
+    /// the outline of a document without waiting for the whole AST.
+    ///
+    /// Declarations are reported once they are complete, so the members of a class are
+    /// reported before the class itself. Anything reported before a parse error stays
+    /// reported, even though the parse as a whole fails.
+    pub fn on_symbol<F: Fn(SymbolEvent) + Send + Sync + 'static>(mut self, handler: F) -> Self {
+        self.on_symbol = Some(SymbolHandler(Arc::new(handler)));
+
+        self
+    }
+
+    /// Parse statements that aren't part of PHP with `extension`, e.g. for a superset
+    /// of PHP with statements of its own.
+    ///
+    /// The extension is given every statement before the parser looks at it, with the
+    /// stream at the statement's first token. It returns `None` to leave the statement
+    /// to the parser, or else parses the statement itself, usually into a
+    /// [`CustomStatement`], using the functions in [`extension`] to parse the parts of
+    /// it that are PHP.
+    ///
+    /// [`CustomStatement`]: crate::parser::ast::CustomStatement
+    /// [`extension`]: crate::parser::extension
+    pub fn statement_extension<F>(mut self, extension: F) -> Self
+    where
+        F: Fn(&mut State) -> Option<ParseResult<Statement>> + Send + Sync + 'static,
+    {
+        self.statement_extension = Some(StatementExtension(Arc::new(extension)));
+
+        self
+    }
+
+    /// How much of the content of inline HTML to keep, all of it by default.
+    ///
+    /// Templates can carry a lot more HTML than PHP, which only takes up memory when the
+    /// PHP is all that gets analyzed. Without its content, an [`InlineHtmlStatement`]
+    /// keeps its span, so that tools rewriting the source know that it is there, and
+    /// code that needs the content gets an [`InlineHtmlDropped`] error.
+    ///
+    /// [`InlineHtmlStatement`]: crate::parser::ast::InlineHtmlStatement
+    /// [`InlineHtmlDropped`]: crate::parser::ast::InlineHtmlDropped
+    pub fn inline_html(mut self, mode: InlineHtmlMode) -> Self {
+        self.inline_html = mode;
+
+        self
+    }
+
+    /// What to do with the end of a file after `__halt_compiler();`, which is only
+    /// kept as data by default.
+    ///
+    /// Some files carry more PHP code after `__halt_compiler();`, which PHP never runs
+    /// but tools may still want to look at. When it is parsed, its statements are put
+    /// in an [`AfterHaltStatement`] that follows the `__halt_compiler();`, with the
+    /// errors found in them, which don't fail the parse of the file.
+    ///
+    /// [`AfterHaltStatement`]: crate::parser::ast::AfterHaltStatement
+    pub fn after_halt(mut self, mode: AfterHaltMode) -> Self {
+        self.after_halt = mode;
+
+        self
+    }
+
+    /// The number of tokens an attribute group can span before it is considered
+    /// unclosed, 10,000 by default.
+    ///
+    /// An attribute group whose `]` is missing would otherwise run into the
+    /// declarations that follow it, and be reported at one of them. Instead, the error
+    /// is reported at the `#[` of the group, and the declarations are still parsed.
+    pub fn attribute_token_limit(mut self, limit: usize) -> Self {
+        self.attribute_token_limit = limit;
+
+        self
+    }
+
+    /// Stop parsing with an [`E093`] error once `token` is cancelled, e.g. from another
+    /// thread when the source has changed and the parse is no longer of any use.
+    ///
+    /// The token is looked at every thousand or so tokens, between statements as well as
+    /// within long expressions, argument lists, class bodies and strings, so a parse
+    /// stops soon after the token is cancelled, whatever the source looks like. The
+    /// source is tokenized before it is parsed, which isn't cancelled.
+    ///
+    /// [`E093`]: crate::parser::error::cancelled
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+
+        self
+    }
+
+    /// The name of the file being parsed, as it should be shown to the user, such as a
+    /// path relative to the root of the project. [`parse_file`] sets it to the path it
+    /// is given.
+    ///
+    /// The name is kept in the [`ParseErrorStack`] of a parse that fails, which puts it
+    /// in front of its errors, and is what analyses resolve `__FILE__` and `__DIR__` to.
+    ///
+    /// [`parse_file`]: crate::parser::parse_file
+    /// [`ParseErrorStack`]: crate::parser::error::ParseErrorStack
+    pub fn source_name<P: Into<PathBuf>>(mut self, name: P) -> Self {
+        self.source_name = Some(name.into());
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::functions::FunctionBody;
+    use crate::parser::ast::variables::Variable;
+    use crate::parser::ast::AfterHaltStatement;
+    use crate::parser::ast::Expression;
+    use crate::parser::ast::InlineHtml;
+    use crate::parser::ast::InlineHtmlDropped;
+    use crate::parser::ast::InlineHtmlStatement;
+    use crate::parser::ast::Statement;
+
+    fn function_body(code: &str) -> FunctionBody {
+        let program =
+            crate::parse_with_config(code, ParserConfig::new().skip_bodies(true)).unwrap();
+
+        match program.into_iter().nth(1) {
+            Some(Statement::Function(function)) => function.body,
+            statement => panic!("expected a function, found {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_skip_bodies() {
+        let code = r#"<?php
+function foo(int $a): string {
+    if ($a) {
+        return "}";
+    }
+
+    $b = function () use ($a) { return ${'a'}; };
+
+    return <<<EOT
+    {$a} } {
+    EOT;
+}
+
+function bar() {}
+"#;
+
+        let body = function_body(code);
+        assert!(body.statements.is_empty());
+        assert!(body.skipped);
+        assert_eq!(body.right_brace.line, 12);
+
+        let skipped =
+            crate::parse_with_config(code, ParserConfig::new().skip_bodies(true)).unwrap();
+        let parsed = crate::parse(code).unwrap();
+
+        assert_eq!(skipped.len(), parsed.len());
+        assert!(matches!(&skipped[2], Statement::Function(f) if f.name.value == b"bar"));
+        // An empty body that is parsed isn't marked as skipped.
+        assert!(matches!(&parsed[2], Statement::Function(f) if !f.body.skipped));
+    }
+
+    #[test]
+    fn test_skip_bodies_of_methods() {
+        let code = r#"<?php
+class Foo {
+    public function __construct() { echo '{'; }
+
+    public function bar(): void { $x = "{$this->y}"; }
+}
+"#;
+
+        let program =
+            crate::parse_with_config(code, ParserConfig::new().skip_bodies(true)).unwrap();
+
+        assert_eq!(
+            format!("{:?}", program).matches("statements: []").count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_skip_bodies_unterminated() {
+        assert!(crate::parse_with_config(
+            "<?php function foo() { {",
+            ParserConfig::new().skip_bodies(true)
+        )
+        .is_err());
+    }
+
+    fn expression(code: &str, config: ParserConfig) -> Expression {
+        let program = crate::parse_with_config(code, config).unwrap();
+
+        match program.into_iter().nth(1) {
+            Some(Statement::Expression(statement)) => statement.expression,
+            statement => panic!("expected an expression, found {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_flatten_concat() {
+        let code = "<?php $a . 'b' . ($c . $d) . e();";
+
+        let Expression::ConcatMany(chain) = expression(code, ParserConfig::new()) else {
+            panic!("expected a flattened concatenation");
+        };
+        assert_eq!(chain.parts.len(), 4);
+        assert_eq!(chain.dots.len(), 3);
+        assert!(matches!(chain.parts[2], Expression::Parenthesized(_)));
+
+        assert!(matches!(
+            expression("<?php $a . $b;", ParserConfig::new()),
+            Expression::Concat(_)
+        ));
+
+        let Expression::Concat(outer) = expression(code, ParserConfig::new().flatten_concat(false))
+        else {
+            panic!("expected a binary concatenation");
+        };
+        assert!(matches!(outer.left.as_ref(), Expression::Concat(_)));
+    }
+
+    #[test]
+    fn test_flatten_long_concat() {
+        let mut code = "<?php $a".to_string();
+        for i in 0..9_999 {
+            code.push_str(&format!(" . 'part {}'", i));
+        }
+        code.push(';');
+
+        let program = crate::parse(&code).unwrap();
+        let Statement::Expression(statement) = &program[1] else {
+            unreachable!()
+        };
+        let Expression::ConcatMany(chain) = &statement.expression else {
+            panic!("expected a flattened concatenation");
+        };
+        assert_eq!(chain.parts.len(), 10_000);
+
+        let json = serde_json::to_string(&program).unwrap();
+        assert!(json.contains("part 9998"));
+    }
+
+    fn inline_html(code: &str, mode: InlineHtmlMode) -> Vec<InlineHtmlStatement> {
+        let program =
+            crate::parse_with_config(code, ParserConfig::new().inline_html(mode)).unwrap();
+
+        let html = program
+            .into_iter()
+            .filter_map(|statement| match statement {
+                Statement::InlineHtml(html) => Some(html),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // Retained content doesn't survive the trip, as the line breaks in it are escaped.
+        if mode != InlineHtmlMode::Retain {
+            let json = serde_json::to_string(&html).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Vec<InlineHtmlStatement>>(&json).unwrap(),
+                html
+            );
+        }
+
+        html
+    }
+
+    #[test]
+    fn test_inline_html() {
+        let code = "<h1><?php echo $title; ?></h1>\n<p>Hello</p>";
+
+        let retained = inline_html(code, InlineHtmlMode::Retain);
+        assert_eq!(retained.len(), 2);
+        assert_eq!(retained[1].span.position, 25);
+        assert_eq!(retained[1].html.bytes().unwrap(), b"</h1>\n<p>Hello</p>");
+
+        let dropped = inline_html(code, InlineHtmlMode::SpanOnly);
+        assert_eq!(dropped[1].span, retained[1].span);
+        assert_eq!(dropped[1].html, InlineHtml::Dropped { length: 18 });
+        assert_eq!(dropped[1].html.bytes(), Err(InlineHtmlDropped));
+
+        let hashed = inline_html(code, InlineHtmlMode::Hash);
+        assert_eq!(hashed[0].html.len(), 4);
+        assert!(hashed[0].html.bytes().is_err());
+        assert!(matches!(
+            hashed[1].html,
+            InlineHtml::Hashed { length: 18, .. }
+        ));
+
+        // The hash tells templates apart, and doesn't change between runs.
+        let changed = inline_html("<h1><?php echo $title; ?></h2>", InlineHtmlMode::Hash);
+        assert_eq!(hashed[0].html, changed[0].html);
+        assert_ne!(hashed[1].html, changed[1].html);
+        assert_eq!(
+            inline_html("<br>", InlineHtmlMode::Hash)[0].html,
+            InlineHtml::Hashed {
+                hash: 0x7b01_0e19_b130_decf,
+                length: 4
+            }
+        );
+    }
+
+    fn after_halt(code: &str) -> AfterHaltStatement {
+        let program =
+            crate::parse_with_config(code, ParserConfig::new().after_halt(AfterHaltMode::Parse))
+                .unwrap();
+
+        match program.last() {
+            Some(Statement::AfterHalt(statement)) => statement.clone(),
+            statement => panic!("expected the end of the file, found {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_after_halt() {
+        let code = "<?php\necho 1;\n__halt_compiler(); function stub() {}\n$a = 1;\n";
+
+        let data = crate::parse(code).unwrap();
+        assert_eq!(data.len(), 3);
+        assert!(matches!(
+            &data[2],
+            Statement::HaltCompiler(halt) if halt.content.as_ref().unwrap() == b" function stub() {}\n$a = 1;\n"
+        ));
+
+        let parsed =
+            crate::parse_with_config(code, ParserConfig::new().after_halt(AfterHaltMode::Parse))
+                .unwrap();
+        assert_eq!(parsed.len(), 4);
+        assert_eq!(parsed[2], data[2]);
+
+        let region = after_halt(code);
+        assert!(region.errors.is_empty());
+        assert_eq!(region.statements.len(), 2);
+
+        // Spans are those in the file.
+        let Statement::Function(function) = &region.statements[0] else {
+            panic!("expected a function");
+        };
+        assert_eq!(function.name.span.line, 3);
+        assert_eq!(function.name.span.column, 29);
+        assert_eq!(&code[function.name.span.position..][..4], "stub");
+
+        let Statement::Expression(statement) = &region.statements[1] else {
+            panic!("expected an expression");
+        };
+        let Expression::AssignmentOperation(assignment) = &statement.expression else {
+            panic!("expected an assignment");
+        };
+        let Expression::Variable(Variable::SimpleVariable(variable)) = assignment.left() else {
+            panic!("expected a variable");
+        };
+        assert_eq!(variable.span.line, 4);
+        assert_eq!(variable.span.column, 1);
+        assert_eq!(variable.span.position, code.find("$a").unwrap());
+    }
+
+    #[test]
+    fn test_after_halt_errors() {
+        // Errors after `__halt_compiler();` don't fail the parse.
+        let region = after_halt("<?php __halt_compiler();\necho 1;\nfunction (");
+        assert_eq!(region.statements.len(), 1);
+        assert_eq!(region.errors.len(), 1);
+        assert_eq!(region.errors[0].span.line, 3);
+
+        let region = after_halt("<?php __halt_compiler();\n\n$a = \"");
+        assert!(region.statements.is_empty());
+        assert_eq!(region.errors.len(), 1);
+        assert_eq!(region.errors[0].span.line, 3);
+
+        // Nothing follows it.
+        let program = crate::parse_with_config(
+            "<?php __halt_compiler();",
+            ParserConfig::new().after_halt(AfterHaltMode::Parse),
+        )
+        .unwrap();
+        assert!(matches!(
+            &program[..],
+            [_, Statement::HaltCompiler(halt)] if halt.content.is_none()
+        ));
+    }
+}
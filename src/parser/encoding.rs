@@ -0,0 +1,180 @@
+use crate::lexer::token::Span;
+use crate::parser::error::ParseError;
+
+/// The encoding of a source file, given to the parser with
+/// [`ParserConfig::source_encoding_hint`].
+///
+/// The parser only ever looks at bytes, so a file in an encoding such as ISO-8859-1
+/// can be parsed as it is. Consumers that want the AST in another encoding, usually
+/// UTF-8, provide a `transcode` function; this crate doesn't ship any encoding tables.
+///
+/// ```
+/// use pxp_parser::parser::encoding::{SourceEncoding, TranscodedSource};
+///
+/// fn latin1(source: &[u8]) -> TranscodedSource {
+///     let mut transcoded = TranscodedSource::default();
+///
+///     for (position, &byte) in source.iter().enumerate() {
+///         let mut buffer = [0; 2];
+///
+///         transcoded.push(position, char::from(byte).encode_utf8(&mut buffer).as_bytes());
+///     }
+///
+///     transcoded
+/// }
+///
+/// static LATIN1: SourceEncoding = SourceEncoding {
+///     label: "ISO-8859-1",
+///     transcode: Some(latin1),
+/// };
+/// ```
+///
+/// [`ParserConfig::source_encoding_hint`]: crate::parser::config::ParserConfig::source_encoding_hint
+#[derive(Debug)]
+pub struct SourceEncoding {
+    /// The name of the encoding, e.g. `ISO-8859-1`.
+    pub label: &'static str,
+    /// Converts the source bytes before they are lexed.
+    pub transcode: Option<fn(&[u8]) -> TranscodedSource>,
+}
+
+/// Encodings are compared by their label, as function pointers can't be compared
+/// reliably.
+impl PartialEq for SourceEncoding {
+    fn eq(&self, other: &Self) -> bool {
+        self.label == other.label
+    }
+}
+
+impl Eq for SourceEncoding {}
+
+/// The result of transcoding a source file.
+///
+/// Every byte of the transcoded source remembers the position of the original byte it
+/// came from, so that errors can be reported against the file as the user sees it.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TranscodedSource {
+    pub bytes: Vec<u8>,
+    pub positions: Vec<usize>,
+}
+
+impl TranscodedSource {
+    /// Append the bytes that a character at `position` in the original source was
+    /// transcoded to.
+    pub fn push(&mut self, position: usize, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+        self.positions
+            .resize(self.positions.len() + bytes.len(), position);
+    }
+
+    /// The position in `original` of the byte at `position` in the transcoded source.
+    fn original_position(&self, original: &[u8], position: usize) -> usize {
+        self.positions
+            .get(position)
+            .copied()
+            .unwrap_or(original.len())
+    }
+
+    /// Point an error found in the transcoded source at the original source instead.
+    pub(crate) fn remap(&self, original: &[u8], error: &mut ParseError) {
+        let position = self.original_position(original, error.span.position);
+        let line_start = original[..position]
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |newline| newline + 1);
+
+        error.span = Span::new(
+            original[..position]
+                .iter()
+                .filter(|byte| **byte == b'\n')
+                .count()
+                + 1,
+            position - line_start + 1,
+            position,
+        );
+
+        for annotation in &mut error.annotations {
+            let start = self.original_position(original, annotation.position);
+            let end = self.original_position(original, annotation.position + annotation.length);
+
+            annotation.position = start;
+            annotation.length = end - start;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::Statement;
+    use crate::parser::config::ParserConfig;
+
+    const SOURCE: &[u8] = include_bytes!("../../tests/fixtures/0379-latin1-encoding/code.php");
+
+    fn latin1(source: &[u8]) -> TranscodedSource {
+        let mut transcoded = TranscodedSource::default();
+
+        for (position, &byte) in source.iter().enumerate() {
+            let mut buffer = [0; 2];
+
+            transcoded.push(
+                position,
+                char::from(byte).encode_utf8(&mut buffer).as_bytes(),
+            );
+        }
+
+        transcoded
+    }
+
+    static LATIN1: SourceEncoding = SourceEncoding {
+        label: "ISO-8859-1",
+        transcode: Some(latin1),
+    };
+
+    fn function_name(program: &[Statement]) -> Vec<u8> {
+        program
+            .iter()
+            .find_map(|statement| match statement {
+                Statement::Function(function) => Some(function.name.value.to_vec()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_without_hint() {
+        let program = crate::parse(SOURCE).unwrap();
+
+        assert_eq!(function_name(&program), b"caf\xe9");
+        assert_eq!(
+            crate::analysis::declared_encoding(&program),
+            Some("ISO-8859-1".into())
+        );
+    }
+
+    #[test]
+    fn test_with_hint() {
+        let config = ParserConfig::new().source_encoding_hint(Some(&LATIN1));
+        let program = crate::parse_with_config(SOURCE, config).unwrap();
+
+        assert_eq!(function_name(&program), "café".as_bytes());
+    }
+
+    #[test]
+    fn test_errors_point_at_original_source() {
+        let mut source = SOURCE.to_vec();
+        source.extend_from_slice(b"\n$\xe9\xe9 = ;\n");
+
+        let config = ParserConfig::new().source_encoding_hint(Some(&LATIN1));
+        let error = crate::parse_with_config(&source, config)
+            .unwrap_err()
+            .errors
+            .remove(0);
+
+        let position = source.len() - 2;
+        assert_eq!(error.span.position, position);
+        assert_eq!((error.span.line, error.span.column), (9, 7));
+        assert_eq!(error.annotations[0].position, position);
+        assert_eq!(error.annotations[0].length, 1);
+    }
+}
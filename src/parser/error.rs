@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 
+#[cfg(feature = "report")]
 use ariadne::{CharSet, Color, Config, Label, Report, ReportKind, Source};
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -9,8 +11,17 @@ use crate::lexer::error::SyntaxError;
 use crate::lexer::token::{Span, Token, TokenKind};
 use crate::parser::ast::attributes::AttributeGroup;
 use crate::parser::ast::data_type::Type;
+use crate::parser::ast::enums::BackedEnumType;
 use crate::parser::ast::modifiers::PromotedPropertyModifier;
 use crate::parser::ast::Program;
+use crate::parser::ast::UseKind;
+use crate::parser::reserved::NameKind;
+use crate::parser::version::Feature;
+use crate::parser::version::PhpVersion;
+#[cfg(feature = "report")]
+use crate::utils::preview_range;
+#[cfg(feature = "report")]
+use crate::utils::PREVIEW_LENGTH;
 
 use super::ast::identifiers::SimpleIdentifier;
 use super::ast::variables::SimpleVariable;
@@ -23,6 +34,8 @@ pub type ParseResult<T> = Result<T, ParseError>;
 pub enum ParseErrorAnnotationType {
     Hint,
     Error,
+    /// A replacement for the annotated range that would likely fix the error.
+    Suggestion,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -33,22 +46,80 @@ pub struct ParseErrorAnnotation {
     pub length: usize,
 }
 
+/// A coarse classification of a [`ParseError`], see [`ParseError::category`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default, Deserialize, Serialize, JsonSchema)]
+pub enum ErrorCategory {
+    /// The input is not valid PHP.
+    #[default]
+    Syntax,
+    /// The input is valid PHP, but uses a construct that the version of PHP the parser
+    /// targets does not support, see [`ParserConfig::php_version`].
+    ///
+    /// [`ParserConfig::php_version`]: crate::parser::config::ParserConfig::php_version
+    Unsupported,
+    /// The input exceeds a limit imposed by the parser, such as
+    /// [`ParserConfig::attribute_token_limit`].
+    ///
+    /// [`ParserConfig::attribute_token_limit`]: crate::parser::config::ParserConfig::attribute_token_limit
+    Limit,
+    /// The parser reached a state it should never be in.
+    Internal,
+    /// The input is valid PHP, but uses a construct forbidden by an [`ExpressionPolicy`].
+    ///
+    /// [`ExpressionPolicy`]: crate::parser::policy::ExpressionPolicy
+    Policy,
+    /// The input is valid PHP, but is likely to be a mistake or to break with a later
+    /// version. These are reported as warnings, by [`parse_with_warnings`].
+    ///
+    /// [`parse_with_warnings`]: crate::parser::parse_with_warnings
+    Warning,
+    /// The input was only partly checked, e.g. because it uses a name that isn't known.
+    Info,
+    /// The parse was stopped by a [`CancellationToken`] before it was done.
+    ///
+    /// [`CancellationToken`]: crate::parser::cancellation::CancellationToken
+    Cancelled,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+#[non_exhaustive]
 pub struct ParseError {
     pub id: String,
     pub message: String,
     pub span: Span,
     pub annotations: Vec<ParseErrorAnnotation>,
     pub note: Option<String>,
+    #[serde(default)]
+    pub category: ErrorCategory,
+    /// What the error is about, for the errors that have more to tell than their
+    /// message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<ParseErrorDetail>,
+}
+
+/// The values an error was made from, see [`ParseError::detail`].
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type")]
+#[non_exhaustive]
+pub enum ParseErrorDetail {
+    /// A `break` or `continue` of `requested` levels, with only `available` loops and
+    /// `switch` statements around it.
+    BreakLevelTooHigh { requested: i64, available: usize },
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ParseErrorStack {
     pub partial: Program,
     pub errors: Vec<ParseError>,
+    /// The name of the file that failed to parse, see [`ParserConfig::source_name`].
+    ///
+    /// [`ParserConfig::source_name`]: crate::parser::config::ParserConfig::source_name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_name: Option<PathBuf>,
 }
 
 impl ParseErrorStack {
+    #[cfg(feature = "report")]
     pub fn report<'a>(
         &self,
         source: &'a str,
@@ -56,6 +127,9 @@ impl ParseErrorStack {
         colored: bool,
         ascii: bool,
     ) -> std::io::Result<String> {
+        let source_name = self.source_name.as_ref().map(|name| name.to_string_lossy());
+        let origin = origin.or(source_name.as_deref());
+
         let mut reports = Vec::new();
 
         for error in &self.errors {
@@ -74,9 +148,34 @@ impl ParseError {
             span,
             annotations: Vec::new(),
             note: None,
+            category: ErrorCategory::Syntax,
+            detail: None,
         }
     }
 
+    /// The same error, in `category` rather than [`ErrorCategory::Syntax`].
+    pub fn with_category(mut self, category: ErrorCategory) -> Self {
+        self.category = category;
+
+        self
+    }
+
+    /// The numeric part of the error's id, e.g. `5` for `E005`, or nothing for an id
+    /// that isn't an `E` followed by a number, which the parser never makes.
+    ///
+    /// Codes are stable: once assigned, a code is never reused for a different error.
+    pub fn code(&self) -> Option<u16> {
+        self.id.strip_prefix('E')?.parse().ok()
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        self.category
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
     pub fn highlight(mut self, position: usize, length: usize) -> Self {
         self.annotations.push(ParseErrorAnnotation {
             r#type: ParseErrorAnnotationType::Hint,
@@ -105,6 +204,31 @@ impl ParseError {
         self
     }
 
+    pub fn detail(mut self, detail: ParseErrorDetail) -> Self {
+        self.detail = Some(detail);
+
+        self
+    }
+
+    pub fn suggest<T: ToString>(mut self, message: T, position: usize, length: usize) -> Self {
+        self.annotations.push(ParseErrorAnnotation {
+            r#type: ParseErrorAnnotationType::Suggestion,
+            message: message.to_string(),
+            position,
+            length,
+        });
+
+        self
+    }
+
+    pub fn suggestion(&self) -> Option<&str> {
+        self.annotations
+            .iter()
+            .find(|annotation| annotation.r#type == ParseErrorAnnotationType::Suggestion)
+            .map(|annotation| annotation.message.as_str())
+    }
+
+    #[cfg(feature = "report")]
     pub fn report<'a>(
         &self,
         source: &'a str,
@@ -128,6 +252,12 @@ impl ParseError {
             );
 
         for (order, annotation) in self.annotations.iter().enumerate() {
+            if annotation.r#type == ParseErrorAnnotationType::Suggestion {
+                report = report.with_help(&annotation.message);
+
+                continue;
+            }
+
             let mut label = Label::new((
                 origin,
                 annotation.position..annotation.position + annotation.length,
@@ -136,12 +266,15 @@ impl ParseError {
 
             if !annotation.message.is_empty() {
                 label = label.with_message(&annotation.message);
+            } else if let Some(preview) = multiline_preview(source, annotation) {
+                label = label.with_message(preview);
             }
 
             if colored {
                 label = match annotation.r#type {
                     ParseErrorAnnotationType::Hint => label.with_color(Color::Cyan),
                     ParseErrorAnnotationType::Error => label.with_color(Color::Red),
+                    ParseErrorAnnotationType::Suggestion => unreachable!(),
                 };
             }
 
@@ -167,20 +300,87 @@ impl ParseError {
     }
 }
 
-pub fn unexpected_token(expected: Vec<String>, found: &Token) -> ParseError {
-    let (found_name, eof) = match &found.kind {
-        TokenKind::Eof => ("end of file".to_string(), true),
-        kind => match kind {
-            TokenKind::Identifier
-            | TokenKind::QualifiedIdentifier
-            | TokenKind::FullyQualifiedIdentifier => ("identifier".to_string(), false),
-            TokenKind::Variable => ("variable".to_string(), false),
-            TokenKind::LiteralInteger | TokenKind::LiteralFloat | TokenKind::LiteralString => {
-                ("literal".to_string(), false)
-            }
-            _ => (format!("token `{}`", found.value), false),
-        },
+/// A preview of the code highlighted by an annotation that spans several lines, which
+/// is hard to follow from the arrows alone.
+#[cfg(feature = "report")]
+fn multiline_preview(source: &str, annotation: &ParseErrorAnnotation) -> Option<String> {
+    let range = annotation.position..annotation.position + annotation.length;
+
+    source
+        .as_bytes()
+        .get(range.clone())
+        .is_some_and(|code| code.contains(&b'\n'))
+        .then(|| preview_range(source.as_bytes(), range, PREVIEW_LENGTH))
+}
+
+/// Whether `tokens` end with what looks like the parameters of an arrow function whose
+/// `fn` is missing: a parenthesized list of variables, with their types, where an
+/// expression can start, as in `$f = ($x)` but not `foo($x)` or `[$x`.
+fn ends_with_parameters(tokens: &[&Token]) -> bool {
+    let Some((last, rest)) = tokens.split_last() else {
+        return false;
     };
+    if last.kind != TokenKind::RightParen {
+        return false;
+    }
+
+    let Some(open) = rest
+        .iter()
+        .rposition(|token| token.kind == TokenKind::LeftParen)
+    else {
+        return false;
+    };
+    let parameters = rest[open + 1..].iter().all(|token| {
+        matches!(
+            token.kind,
+            TokenKind::Variable
+                | TokenKind::Comma
+                | TokenKind::Identifier
+                | TokenKind::QualifiedIdentifier
+                | TokenKind::FullyQualifiedIdentifier
+                | TokenKind::Question
+                | TokenKind::Pipe
+                | TokenKind::Ampersand
+                | TokenKind::Ellipsis
+        )
+    });
+
+    parameters
+        && rest[..open].last().is_none_or(|token| {
+            matches!(
+                token.kind,
+                TokenKind::Equals
+                    | TokenKind::Comma
+                    | TokenKind::LeftParen
+                    | TokenKind::LeftBracket
+                    | TokenKind::DoubleArrow
+                    | TokenKind::Return
+                    | TokenKind::Echo
+                    | TokenKind::Question
+                    | TokenKind::Colon
+                    | TokenKind::DoubleQuestion
+            )
+        })
+}
+
+/// How a token is described in errors, e.g. "identifier" or "token `;`".
+fn token_name(found: &Token) -> String {
+    match &found.kind {
+        TokenKind::Eof => "end of file".to_string(),
+        TokenKind::Identifier
+        | TokenKind::QualifiedIdentifier
+        | TokenKind::FullyQualifiedIdentifier => "identifier".to_string(),
+        TokenKind::Variable => "variable".to_string(),
+        TokenKind::LiteralInteger | TokenKind::LiteralFloat | TokenKind::LiteralString => {
+            "literal".to_string()
+        }
+        _ => format!("token `{}`", found.value),
+    }
+}
+
+pub fn unexpected_token(expected: Vec<String>, found: &Token) -> ParseError {
+    let found_name = token_name(found);
+    let eof = found.kind == TokenKind::Eof;
 
     if expected.is_empty() {
         return if eof {
@@ -197,7 +397,7 @@ pub fn unexpected_token(expected: Vec<String>, found: &Token) -> ParseError {
     let expected: Vec<String> = expected
         .iter()
         .map(|s| {
-            if s.starts_with("a ") || s.starts_with("an ") {
+            if s.starts_with("a ") || s.starts_with("an ") || s.starts_with('`') {
                 s.to_string()
             } else {
                 format!("`{}`", s)
@@ -226,6 +426,95 @@ pub fn unexpected_token(expected: Vec<String>, found: &Token) -> ParseError {
     )
 }
 
+/// Like [`unexpected_token`], but suggests the operator the user most likely meant when
+/// `previous` and `found` look like a single operator that has been split apart.
+pub fn unexpected_token_after(previous: &Token, found: &Token) -> ParseError {
+    let error = unexpected_token(vec![], found);
+
+    let suggestion = match (&previous.kind, &found.kind) {
+        (TokenKind::LessThan, TokenKind::LeftShift)
+        | (TokenKind::LeftShift, TokenKind::LessThan) => {
+            "did you mean to start a heredoc with `<<<`?"
+        }
+        (TokenKind::Question, TokenKind::QuestionColon) => "did you mean `?:`?",
+        (TokenKind::Question, TokenKind::Question) => "did you mean `??`?",
+        (TokenKind::DoubleQuestion, TokenKind::Equals) => "did you mean `??=`?",
+        (TokenKind::Equals, TokenKind::GreaterThan) => "did you mean `=>`?",
+        _ => return error,
+    };
+
+    error.suggest(
+        suggestion,
+        previous.span.position,
+        found.span.position + found.value.len() - previous.span.position,
+    )
+}
+
+/// Suggest the PHP for syntax borrowed from another language, when the statement made
+/// of `tokens` fails with `error` at `found`: `elif`, `let`, `var` and `def` at the
+/// start of a statement, an arrow function without `fn`, and a JavaScript template
+/// literal, which PHP reads as a shell command. Errors that already have a suggestion,
+/// or that aren't at `found`, are left as they are.
+pub fn foreign_syntax(error: ParseError, tokens: &[Token], found: &Token) -> ParseError {
+    if error.suggestion().is_some() || error.span != found.span {
+        return error;
+    }
+
+    let tokens: Vec<&Token> = tokens
+        .iter()
+        .filter(|token| {
+            !matches!(
+                token.kind,
+                TokenKind::SingleLineComment
+                    | TokenKind::MultiLineComment
+                    | TokenKind::HashMarkComment
+                    | TokenKind::DocumentComment
+            )
+        })
+        .collect();
+    let first = tokens.first().copied().unwrap_or(found);
+    let word =
+        |word: &[u8]| first.kind == TokenKind::Identifier && first.value.eq_ignore_ascii_case(word);
+
+    // A template literal is still open when the error is in one of its `${`.
+    let backticks = tokens
+        .iter()
+        .filter(|token| token.kind == TokenKind::Backtick)
+        .count();
+    let template = tokens
+        .iter()
+        .rposition(|token| token.kind == TokenKind::Backtick)
+        .filter(|backtick| {
+            backticks % 2 == 1
+                && tokens[*backtick..]
+                    .iter()
+                    .any(|token| token.kind == TokenKind::DollarLeftBrace)
+        });
+
+    let (suggestion, at) = if word(b"elif") {
+        ("use `elseif`", first)
+    } else if word(b"def") && tokens.len() == 1 && found.kind == TokenKind::Identifier {
+        ("use `function` to declare a function", first)
+    } else if word(b"let") && tokens.len() == 1 || first.kind == TokenKind::Var && tokens.is_empty()
+    {
+        (
+            "try removing this, a variable is declared by assigning to it, as in `$x = 1`",
+            first,
+        )
+    } else if found.kind == TokenKind::DoubleArrow && ends_with_parameters(&tokens) {
+        ("use `fn ($x) => ...` for an arrow function", found)
+    } else if let Some(backtick) = template {
+        (
+            "backticks run a shell command, use double quotes for a string, as in `\"{$name}\"`",
+            tokens[backtick],
+        )
+    } else {
+        return error;
+    };
+
+    error.suggest(suggestion, at.span.position, at.value.len())
+}
+
 pub fn unexpected_identifier(expected: Vec<String>, found: String, span: Span) -> ParseError {
     let length = expected.len();
     let expected = if length >= 2 {
@@ -303,7 +592,7 @@ pub fn standalone_type_used_in_union(ty: &Type, span: Span) -> ParseError {
         type_string.len(),
     )
     .highlight(span.position, 1)
-    .note("`never`, `void`, `mixed`, and nullable types cannot be used in a union")
+    .note("`never`, `void`, and `mixed` cannot be used in a union")
 }
 
 pub fn standalone_type_used_in_intersection(ty: &Type, span: Span) -> ParseError {
@@ -324,7 +613,7 @@ pub fn standalone_type_used_in_intersection(ty: &Type, span: Span) -> ParseError
         type_string.len(),
     )
     .highlight(span.position, 1)
-    .note("`never`, `void`, `mixed`, and nullable types cannot be used in an intersection")
+    .note("`never`, `void`, and `mixed` cannot be used in an intersection")
 }
 
 pub fn try_without_catch_or_finally(try_span: Span, last_right_brace: Span) -> ParseError {
@@ -536,6 +825,16 @@ pub fn modifier_cannot_be_used_for_interface_constant(
     .note("only `public`, and `final` modifiers can be used on interface constants")
 }
 
+pub fn modifier_cannot_be_used_for_enum_case(modifier: String, modifier_span: Span) -> ParseError {
+    ParseError::new(
+        "E058",
+        format!("cannot use '{}' as an enum case modifier", modifier),
+        modifier_span,
+    )
+    .error("try removing this", modifier_span.position, modifier.len())
+    .note("enum cases cannot have modifiers")
+}
+
 pub fn modifier_cannot_be_used_for_promoted_property(
     modifier: String,
     modifier_span: Span,
@@ -673,11 +972,9 @@ pub fn final_and_private_modifiers_combined_for_constant(
 }
 
 pub fn reached_unpredictable_state(span: Span) -> ParseError {
-    ParseError::new("E031", "reached unpredictable state", span).error(
-        "please report this as a bug",
-        span.position,
-        1,
-    )
+    ParseError::new("E031", "reached unpredictable state", span)
+        .with_category(ErrorCategory::Internal)
+        .error("please report this as a bug", span.position, 1)
 }
 
 pub fn static_property_cannot_be_readonly(
@@ -759,7 +1056,7 @@ pub fn braced_namespace_declarations_in_unbraced_context(span: Span) -> ParseErr
 }
 
 pub fn nested_namespace_declarations(span: Span) -> ParseError {
-    ParseError::new("E035", "cannot nest namespace declarations", span).error(
+    ParseError::new("E036", "cannot nest namespace declarations", span).error(
         "try closing previous namespace with `}` before declaring a new one",
         span.position,
         1,
@@ -793,7 +1090,7 @@ pub fn forbidden_type_used_in_property(
         type_span.position,
         type_string.len(),
     )
-    .note("`void`, `never`, and `callable` types are not allowed in properties");
+    .note("`void`, `never`, `callable`, and `static` types are not allowed in properties");
 
     // If the class is anonymous, we don't have a span to highlight
     if let Some(class) = class {
@@ -817,25 +1114,32 @@ pub fn missing_item_definition_after_attributes(
     attributes: &Vec<AttributeGroup>,
     current: &Token,
 ) -> ParseError {
+    // When the file ends right after the attributes, there is nothing past the
+    // last attribute group to point at, so that group carries the error instead.
+    let (hints, last) = match (&current.kind, attributes.split_last()) {
+        (TokenKind::Eof, Some((last, rest))) => (rest, Some(last)),
+        _ => (attributes.as_slice(), None),
+    };
+
     let mut annotations = vec![];
 
-    for attribute in attributes {
+    for attribute in hints {
         annotations.push(ParseErrorAnnotation {
             r#type: ParseErrorAnnotationType::Hint,
             message: "".to_string(),
             position: attribute.start.position,
-            length: attribute.end.position - attribute.start.position,
+            length: attribute.end.position + 1 - attribute.start.position,
         });
     }
 
-    annotations.push(match current.kind {
-        TokenKind::Eof => ParseErrorAnnotation {
+    annotations.push(match last {
+        Some(attribute) => ParseErrorAnnotation {
             r#type: ParseErrorAnnotationType::Error,
             message: "reached end of file before an item definition".to_string(),
-            position: current.span.position,
-            length: current.value.len(),
+            position: attribute.start.position,
+            length: attribute.end.position + 1 - attribute.start.position,
         },
-        _ => ParseErrorAnnotation {
+        None => ParseErrorAnnotation {
             r#type: ParseErrorAnnotationType::Error,
             message: format!("expected an item definition, found `{}`", current.value),
             position: current.span.position,
@@ -846,9 +1150,11 @@ pub fn missing_item_definition_after_attributes(
     ParseError {
         id: "E039".to_string(),
         message: "missing item definition after attribute(s)".to_string(),
-        span: current.span,
+        span: last.map_or(current.span, |attribute| attribute.start),
         annotations,
         note: None,
+        category: ErrorCategory::Syntax,
+        detail: None,
     }
 }
 
@@ -890,14 +1196,22 @@ pub fn mixing_keyed_and_unkeyed_list_entries(span: Span) -> ParseError {
 pub fn cannot_use_positional_argument_after_named_argument(
     span: Span,
     current_span: Span,
+    unpacking: bool,
 ) -> ParseError {
-    ParseError::new(
-        "E044".to_string(),
-        "cannot use positional argument after named argument",
-        span,
-    )
-    .error(
-        "try adding a name for this argument",
+    let (message, label) = if unpacking {
+        (
+            "cannot use argument unpacking after named argument",
+            "try unpacking before the named arguments",
+        )
+    } else {
+        (
+            "cannot use positional argument after named argument",
+            "try adding a name for this argument",
+        )
+    };
+
+    ParseError::new("E044".to_string(), message, span).error(
+        label,
         span.position,
         current_span.position - span.position,
     )
@@ -971,40 +1285,1904 @@ pub fn argument_is_required(span: Span, current_span: Span) -> ParseError {
     )
 }
 
-impl From<SyntaxError> for ParseError {
-    fn from(e: SyntaxError) -> Self {
-        Self {
-            id: "E001".to_string(),
-            message: format!("syntax error, {}", e),
-            annotations: vec![],
-            span: e.span(),
-            note: None,
+pub fn invalid_loop_level(keyword: &str, found: &Token) -> ParseError {
+    ParseError::new(
+        "E052".to_string(),
+        format!("`{}` level must be a positive integer literal", keyword),
+        found.span,
+    )
+    .error(
+        "try using a positive integer literal",
+        found.span.position,
+        found.value.len(),
+    )
+}
+
+/// A `break` or `continue` whose level is more than the `available` loops and `switch`
+/// statements around it, which is at least one.
+pub fn break_level_too_high(
+    keyword: &str,
+    requested: i64,
+    available: usize,
+    span: Span,
+) -> ParseError {
+    ParseError::new(
+        "E053".to_string(),
+        format!(
+            "cannot `{}` {} level{}",
+            keyword,
+            requested,
+            if requested == 1 { "" } else { "s" }
+        ),
+        span,
+    )
+    .error(
+        format!("try using a level of at most {}", available),
+        span.position,
+        keyword.len(),
+    )
+    .detail(ParseErrorDetail::BreakLevelTooHigh {
+        requested,
+        available,
+    })
+}
+
+/// A `break` or `continue` that isn't in a loop or a `switch` statement.
+pub fn break_outside_of_loop(keyword: &str, span: Span) -> ParseError {
+    ParseError::new(
+        "E104",
+        format!("cannot use `{}` outside of a loop or `switch`", keyword),
+        span,
+    )
+    .error("try removing this", span.position, keyword.len())
+}
+
+pub fn attributes_on_enum_backing_type(start: Span, end: Span) -> ParseError {
+    ParseError::new(
+        "E054".to_string(),
+        "cannot use attributes on an enum backing type",
+        start,
+    )
+    .error(
+        "try removing this attribute",
+        start.position,
+        end.position + 1 - start.position,
+    )
+}
+
+pub fn enum_backing_type_after_implements(backed_type: &BackedEnumType) -> ParseError {
+    let (colon, identifier, length) = match backed_type {
+        BackedEnumType::String(colon, identifier) => (colon, identifier, "string".len()),
+        BackedEnumType::Int(colon, identifier) => (colon, identifier, "int".len()),
+    };
+
+    ParseError::new(
+        "E055".to_string(),
+        "enum backing type must come before `implements`",
+        *colon,
+    )
+    .error(
+        "try moving this before `implements`",
+        colon.position,
+        identifier.position + length - colon.position,
+    )
+}
+
+pub fn null_default_value_for_non_nullable_property(
+    state: &mut State,
+    class: Option<&SimpleIdentifier>,
+    property: &SimpleVariable,
+    ty: &Type,
+    default_span: Span,
+    default_length: usize,
+) -> ParseError {
+    let note = match ty {
+        Type::Union(_) => {
+            "add `null` to the union type to allow a `null` default value".to_string()
         }
-    }
+        _ => format!(
+            "use the nullable type `?{}` to allow a `null` default value",
+            ty
+        ),
+    };
+
+    ParseError::new(
+        "E056".to_string(),
+        format!(
+            "default value for property `{}::{}` of type `{}` cannot be null",
+            class
+                .map(|c| state.named(c))
+                .unwrap_or_else(|| "anonymous@class".to_string()),
+            property.name,
+            ty
+        ),
+        default_span,
+    )
+    .error(
+        "try changing this value",
+        default_span.position,
+        default_length,
+    )
+    .note(note)
 }
 
-impl Display for ParseError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "[{}] Error: {} on line {} column {}",
-            self.id, self.message, self.span.line, self.span.column
-        )?;
+pub fn default_value_type_mismatch_for_property(
+    state: &mut State,
+    class: Option<&SimpleIdentifier>,
+    property: &SimpleVariable,
+    ty: &Type,
+    value_type: &str,
+    default_span: Span,
+    default_length: usize,
+) -> ParseError {
+    ParseError::new(
+        "E057".to_string(),
+        format!(
+            "cannot use `{}` as default value for property `{}::{}` of type `{}`",
+            value_type,
+            class
+                .map(|c| state.named(c))
+                .unwrap_or_else(|| "anonymous@class".to_string()),
+            property.name,
+            ty
+        ),
+        default_span,
+    )
+    .error(
+        format!("try using a value of type `{}`", ty),
+        default_span.position,
+        default_length,
+    )
+}
 
-        if let Some(note) = &self.note {
-            write!(f, ", Note: {}", note)?;
+pub fn class_name_fetch_used_as_class_reference(double_colon: Span) -> ParseError {
+    ParseError::new(
+        "E059".to_string(),
+        "cannot use the result of `::class` as a class reference",
+        double_colon,
+    )
+    .error("try removing this", double_colon.position, 2)
+    .note("use the class name directly instead, e.g. `Foo::bar()` rather than `Foo::class::bar()`")
+}
+
+pub fn mismatched_body_closer(opener: Span, expected: &TokenKind, found: &Token) -> ParseError {
+    let (opener_value, label) = match expected {
+        TokenKind::RightBrace => ("{", "try replacing this with `}`".to_string()),
+        _ => (":", format!("try adding `{};` before this", expected)),
+    };
+
+    ParseError::new(
+        "E060".to_string(),
+        format!(
+            "cannot close a body opened with `{}` using `{}`",
+            opener_value, found.value
+        ),
+        found.span,
+    )
+    .highlight(opener.position, 1)
+    .error(label, found.span.position, found.value.len())
+    .note("a body must use either braces or the alternative syntax throughout")
+}
+
+pub fn policy_violation<T: Display>(construct: T, span: Span, length: usize) -> ParseError {
+    ParseError::new(
+        "E061".to_string(),
+        format!("{} is not allowed by the expression policy", construct),
+        span,
+    )
+    .with_category(ErrorCategory::Policy)
+    .error("try removing this", span.position, length)
+}
+
+/// An error for `found` where only a literal of one of the `accepted` kinds is allowed,
+/// named after the variants of [`Literal`], e.g. `["Integer", "Float"]`.
+///
+/// [`Literal`]: crate::parser::ast::literals::Literal
+pub fn expected_literal(accepted: &[&str], found: &Token) -> ParseError {
+    let accepted = accepted
+        .iter()
+        .map(|kind| match *kind {
+            "Integer" => "an integer",
+            "Float" => "a float",
+            _ => "a string",
+        })
+        .collect::<Vec<&str>>();
+
+    let expected = match accepted.split_last() {
+        Some((last, rest)) if rest.len() > 1 => format!("{}, or {}", rest.join(", "), last),
+        _ => accepted.join(" or "),
+    };
+
+    let error = ParseError::new(
+        "E077".to_string(),
+        format!("unexpected {}, expecting a literal", token_name(found)),
+        found.span,
+    )
+    .error(
+        format!("expected {}", expected),
+        found.span.position,
+        found.value.len(),
+    );
+
+    match found.kind {
+        TokenKind::Identifier
+        | TokenKind::QualifiedIdentifier
+        | TokenKind::FullyQualifiedIdentifier => {
+            error.note("only a literal is allowed here, not a constant or another expression")
+        }
+        TokenKind::LiteralString if !accepted.contains(&"a string") => {
+            error.note("only a number can be negated here")
         }
+        _ => error,
+    }
+}
 
-        Ok(())
+pub fn declare_encoding_not_a_string(value: Span, length: usize) -> ParseError {
+    ParseError::new(
+        "E062".to_string(),
+        "the value of the `encoding` declaration must be a string",
+        value,
+    )
+    .error("try quoting this", value.position, length)
+}
+
+pub fn strict_types_block_mode(key: &SimpleIdentifier) -> ParseError {
+    ParseError::new(
+        "E074".to_string(),
+        "the `strict_types` declaration must not have a body",
+        key.span,
+    )
+    .error(
+        "try ending this declaration with `;`",
+        key.span.position,
+        key.value.len(),
+    )
+    .note("`strict_types` applies to the whole file")
+}
+
+pub fn cannot_use_in_write_context(target: &str, span: Span, length: usize) -> ParseError {
+    ParseError::new(
+        "E076".to_string(),
+        format!("cannot use {} in write context", target),
+        span,
+    )
+    .error("cannot be assigned to", span.position, length)
+    .note("only variables, properties and array elements can be assigned to")
+}
+
+pub fn assignment_in_case_label(span: Span, length: usize) -> ParseError {
+    ParseError::new("E078".to_string(), "assignment in a case label", span)
+        .with_category(ErrorCategory::Warning)
+        .error(
+            "this assigns when the case is checked",
+            span.position,
+            length,
+        )
+        .note("the value of the subject is compared with the assigned value")
+}
+
+/// A warning for a switch whose case labels mix string and number literals, given
+/// as the span and length of every label.
+pub fn mixed_case_label_types(strings: &[(Span, usize)], numbers: &[(Span, usize)]) -> ParseError {
+    let mut error = ParseError::new(
+        "E079".to_string(),
+        "the case labels of this switch mix strings and numbers",
+        strings[0].0,
+    )
+    .with_category(ErrorCategory::Warning);
+
+    for (span, length) in strings {
+        error = error.error("a string", span.position, *length);
+    }
+
+    for (span, length) in numbers {
+        error = error.highlight(span.position, *length);
     }
+
+    error.note("cases are compared loosely, so `\"1.0\"` matches `1`, consider using `match`")
 }
 
-impl Display for ParseErrorStack {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for error in &self.errors {
-            writeln!(f, "{}", error)?;
-        }
+pub fn yield_in_finally(span: Span, length: usize) -> ParseError {
+    ParseError::new("E080".to_string(), "yield inside a `finally` block", span)
+        .with_category(ErrorCategory::Warning)
+        .error("the generator can be suspended here", span.position, length)
+        .note("destroying the generator while it is suspended in `finally` throws an `Error`")
+}
 
-        Ok(())
+/// An error for the `&` of `yield &$value`, in a function that returns
+/// `by_reference` or not.
+pub fn yield_by_reference(ampersand: Span, by_reference: bool) -> ParseError {
+    let note = if by_reference {
+        "generators declared with `function &` already yield their values by reference"
+    } else {
+        "declare the generator with `function &` to yield its values by reference"
+    };
+
+    ParseError::new(
+        "E081".to_string(),
+        "cannot yield a reference with `&`",
+        ampersand,
+    )
+    .error("try removing this", ampersand.position, 1)
+    .note(note)
+}
+
+/// A warning for the statements from `start` to `end` that follow `terminator`, the
+/// `length` bytes from `terminated` that never hand control back.
+pub fn unreachable_code(
+    terminator: &str,
+    terminated: Span,
+    length: usize,
+    start: Span,
+    end: Span,
+) -> ParseError {
+    ParseError::new("E082".to_string(), "unreachable code", start)
+        .with_category(ErrorCategory::Warning)
+        .error(
+            "this code is never run",
+            start.position,
+            end.end() - start.position,
+        )
+        .highlight(terminated.position, length)
+        .note(format!(
+            "execution never gets past the {} on line {}",
+            terminator, terminated.line
+        ))
+}
+
+pub fn attribute_target_not_allowed(
+    name: &[u8],
+    target: &str,
+    allowed: &[&str],
+    span: Span,
+    length: usize,
+) -> ParseError {
+    let allowed = if allowed.is_empty() {
+        "none".to_string()
+    } else {
+        allowed.join(", ")
+    };
+
+    ParseError::new(
+        "E083".to_string(),
+        format!(
+            "attribute `{}` cannot target a {}",
+            String::from_utf8_lossy(name),
+            target
+        ),
+        span,
+    )
+    .error(format!("this is a {}", target), span.position, length)
+    .note(format!("allowed targets: {}", allowed))
+}
+
+pub fn repeated_attribute(
+    name: &[u8],
+    first: Span,
+    first_length: usize,
+    span: Span,
+    length: usize,
+) -> ParseError {
+    ParseError::new(
+        "E084".to_string(),
+        format!(
+            "attribute `{}` must not be repeated",
+            String::from_utf8_lossy(name)
+        ),
+        span,
+    )
+    .highlight(first.position, first_length)
+    .error("repeated here", span.position, length)
+    .note("only attributes declared with `Attribute::IS_REPEATABLE` can be repeated")
+}
+
+pub fn unknown_attribute(name: &[u8], span: Span, length: usize) -> ParseError {
+    ParseError::new(
+        "E085".to_string(),
+        format!("attribute `{}` is not known", String::from_utf8_lossy(name)),
+        span,
+    )
+    .with_category(ErrorCategory::Info)
+    .highlight(span.position, length)
+    .note("the targets of an attribute can only be checked when its class is known")
+}
+
+pub fn undefined_member(
+    kind: &str,
+    name: &[u8],
+    class: &[u8],
+    suggestion: Option<&[u8]>,
+    span: Span,
+    length: usize,
+) -> ParseError {
+    let error = ParseError::new(
+        "E086".to_string(),
+        format!(
+            "{} `{}` is not declared in `{}`",
+            kind,
+            String::from_utf8_lossy(name),
+            String::from_utf8_lossy(class)
+        ),
+        span,
+    )
+    .with_category(ErrorCategory::Warning)
+    .error(format!("no such {}", kind), span.position, length);
+
+    match suggestion {
+        Some(suggestion) => error.note(format!(
+            "did you mean `{}`?",
+            String::from_utf8_lossy(suggestion)
+        )),
+        None => error,
+    }
+}
+
+pub fn mismatched_delimiter_in_attribute(opener: &Token, closer: &Token) -> ParseError {
+    ParseError::new(
+        "E087".to_string(),
+        format!(
+            "`{}` in an attribute is closed by `{}`",
+            opener.value, closer.value
+        ),
+        opener.span,
+    )
+    .error(
+        "unclosed delimiter",
+        opener.span.position,
+        opener.value.len(),
+    )
+    .error(
+        "mismatched closing delimiter",
+        closer.span.position,
+        closer.value.len(),
+    )
+}
+
+pub fn unclosed_attribute_group(
+    start: Span,
+    innermost: Option<&Token>,
+    limit: Option<usize>,
+) -> ParseError {
+    let (id, message, category) = match limit {
+        Some(limit) => (
+            "E105",
+            format!("attribute group is not closed within {} tokens", limit),
+            ErrorCategory::Limit,
+        ),
+        None => (
+            "E088",
+            "attribute group is never closed".to_string(),
+            ErrorCategory::Syntax,
+        ),
+    };
+
+    let error = ParseError::new(id, message, start)
+        .with_category(category)
+        .error("unclosed attribute group", start.position, 2);
+
+    match innermost {
+        Some(opener) => error.error(
+            "innermost unclosed delimiter",
+            opener.span.position,
+            opener.value.len(),
+        ),
+        None => error,
+    }
+    .note("an attribute group is closed by `]`")
+}
+
+/// An error for a match arm condition that is a `yield`, a `throw` or an assignment,
+/// given as the name of what it is.
+pub fn invalid_match_arm_condition(construct: &str, span: Span, length: usize) -> ParseError {
+    ParseError::new(
+        "E089".to_string(),
+        format!("{} cannot be a match arm condition", construct),
+        span,
+    )
+    .error("this condition", span.position, length)
+    .note("wrap it in parentheses to compare its value with the subject")
+}
+
+pub fn assignment_in_match_subject(span: Span, length: usize) -> ParseError {
+    ParseError::new("E090".to_string(), "assignment in a match subject", span)
+        .with_category(ErrorCategory::Warning)
+        .error(
+            "this assigns before the arms are compared",
+            span.position,
+            length,
+        )
+        .note("the arms are compared with the assigned value")
+}
+
+pub fn nullsafe_closure_creation(span: Span) -> ParseError {
+    ParseError::new(
+        "E091".to_string(),
+        "cannot combine nullsafe operator with first-class callable syntax",
+        span,
+    )
+    .error("use `->` instead", span.position, 3)
+    .note("a closure can't be created from a method of `null`")
+}
+
+/// An `...` with nothing to unpack, which is only a placeholder when it is alone
+/// in the parentheses, as in `strlen(...)`.
+pub fn misplaced_argument_placeholder(span: Span) -> ParseError {
+    ParseError::new(
+        "E092".to_string(),
+        "unexpected `...` in an argument list",
+        span,
+    )
+    .error("expected a value to unpack", span.position, 3)
+    .note(
+        "a closure is created from a function or a method with `(...)` alone, as in `strlen(...)`",
+    )
+}
+
+/// The parse was cancelled at `span`, see [`ParserConfig::cancellation`].
+///
+/// [`ParserConfig::cancellation`]: crate::parser::config::ParserConfig::cancellation
+pub fn cancelled(span: Span) -> ParseError {
+    ParseError::new("E093".to_string(), "parsing was cancelled", span)
+        .with_category(ErrorCategory::Cancelled)
+}
+
+/// A `public`, `protected`, `private` or `readonly` modifier on a parameter of a
+/// function, a closure, or a method other than a constructor with a body.
+pub fn promoted_property_outside_constructor(modifier: &Token) -> ParseError {
+    ParseError::new(
+        "E094".to_string(),
+        "cannot declare promoted property outside a constructor",
+        modifier.span,
+    )
+    .error(
+        "try removing this modifier",
+        modifier.span.position,
+        modifier.value.len(),
+    )
+    .note("only the parameters of a constructor that has a body can be promoted to properties")
+}
+
+/// A `&` on a destructuring pattern, as in `[&[$a]] = $b` or `foreach ($a as &[$b])`.
+pub fn pattern_by_reference(ampersand: Span) -> ParseError {
+    ParseError::new(
+        "E095".to_string(),
+        "cannot assign a destructuring pattern by reference",
+        ampersand,
+    )
+    .error("try removing this", ampersand.position, 1)
+    .note("take the variables inside the pattern by reference instead, as in `[[&$a]] = $b`")
+}
+
+/// An abstract method, or a method of an interface, with a body that starts at `brace`.
+pub fn method_with_body(
+    state: &mut State,
+    interface: bool,
+    class: Option<&SimpleIdentifier>,
+    method: &SimpleIdentifier,
+    brace: Span,
+) -> ParseError {
+    let error = ParseError::new(
+        "E096",
+        format!(
+            "{} method `{}::{}` cannot have a body",
+            if interface { "interface" } else { "abstract" },
+            class
+                .map(|c| state.named(c))
+                .unwrap_or_else(|| "class@anonymous".to_string()),
+            method.value,
+        ),
+        brace,
+    )
+    .error("try replacing the body with `;`", brace.position, 1)
+    .highlight(method.span.position, method.value.len());
+
+    match class {
+        Some(class) => error.highlight(class.span.position, class.value.len()),
+        None => error,
+    }
+}
+
+/// A method that isn't abstract, which ends at `semicolon` instead of having a body.
+pub fn method_without_body(
+    state: &mut State,
+    class: Option<&SimpleIdentifier>,
+    method: &SimpleIdentifier,
+    semicolon: Span,
+) -> ParseError {
+    let error = ParseError::new(
+        "E097",
+        format!(
+            "non-abstract method `{}::{}` must have a body",
+            class
+                .map(|c| state.named(c))
+                .unwrap_or_else(|| "class@anonymous".to_string()),
+            method.value,
+        ),
+        semicolon,
+    )
+    .error("expected a body", semicolon.position, 1)
+    .highlight(method.span.position, method.value.len());
+
+    match class {
+        Some(class) => error.highlight(class.span.position, class.value.len()),
+        None => error,
+    }
+}
+
+/// A nullable type in a union, as in `?A|B`, or in an intersection, as in `A&?B`.
+pub fn nullable_type_used_in_compound_type(ty: &Type, intersection: bool) -> ParseError {
+    let question = ty.first_span();
+    let type_string = ty.to_string();
+
+    let error = ParseError::new(
+        "E098",
+        format!(
+            "nullable type `{}` cannot be used in {}",
+            type_string,
+            if intersection {
+                "an intersection"
+            } else {
+                "a union"
+            }
+        ),
+        question,
+    )
+    .error("try removing this", question.position, 1);
+
+    if intersection {
+        error
+    } else {
+        error.note("add `null` to the union instead, as in `A|B|null`")
+    }
+}
+
+/// A parameter with a type that only a return type can have.
+pub fn forbidden_type_used_in_parameter(parameter: &SimpleVariable, ty: Type) -> ParseError {
+    let type_string = ty.to_string();
+    let type_span = ty.first_span();
+
+    ParseError::new(
+        "E099",
+        format!(
+            "parameter `{}` cannot have type `{}`",
+            parameter.name, type_string
+        ),
+        type_span,
+    )
+    .error(
+        "try using a different type",
+        type_span.position,
+        type_string.len(),
+    )
+    .note("`void`, `never`, and `static` types are not allowed in parameters")
+}
+
+/// A typed class constant, whose first entry is `constant`, with a type that a
+/// constant can't have.
+pub fn forbidden_type_used_in_constant(constant: &SimpleIdentifier, ty: Type) -> ParseError {
+    let type_string = ty.to_string();
+    let type_span = ty.first_span();
+
+    ParseError::new(
+        "E100",
+        format!(
+            "constant `{}` cannot have type `{}`",
+            constant.value, type_string
+        ),
+        type_span,
+    )
+    .error(
+        "try using a different type",
+        type_span.position,
+        type_string.len(),
+    )
+    .note("`void`, `never`, `callable`, and `static` types are not allowed in class constants")
+}
+
+/// A warning for a `static` declaration of `variable`, which replaces the `kind`
+/// declared at `shadowed` for the rest of the function.
+pub fn static_variable_shadows(
+    variable: &SimpleVariable,
+    kind: &str,
+    shadowed: &SimpleVariable,
+) -> ParseError {
+    ParseError::new(
+        "E101",
+        format!("static variable `{}` shadows a {}", variable.name, kind),
+        variable.span,
+    )
+    .with_category(ErrorCategory::Warning)
+    .error(
+        format!("this replaces the {}", kind),
+        variable.span.position,
+        variable.name.len(),
+    )
+    .highlight(shadowed.span.position, shadowed.name.len())
+    .note(format!(
+        "the value of the {} can't be read once the static variable is declared",
+        kind
+    ))
+}
+
+/// A warning for a second `static` declaration of the variable declared at `first` in
+/// the same function.
+pub fn duplicate_static_variable(first: &SimpleVariable, second: &SimpleVariable) -> ParseError {
+    ParseError::new(
+        "E102",
+        format!(
+            "static variable `{}` is declared more than once",
+            second.name
+        ),
+        second.span,
+    )
+    .with_category(ErrorCategory::Warning)
+    .error(
+        "declared again here",
+        second.span.position,
+        second.name.len(),
+    )
+    .highlight(first.span.position, first.name.len())
+    .note("both declarations are the same variable, and PHP 8.3 rejects the second one")
+}
+
+/// A warning for the `${` of a shell command that starts at `backtick`, which looks
+/// like a JavaScript template literal.
+pub fn template_literal_in_shell_exec(backtick: Span, dollar: Span) -> ParseError {
+    ParseError::new(
+        "E103",
+        "`${` in backticks, which run a shell command",
+        dollar,
+    )
+    .with_category(ErrorCategory::Warning)
+    .suggest(
+        "use double quotes for a string, as in `\"{$name}\"`",
+        backtick.position,
+        1,
+    )
+    .error("interpolated here", dollar.position, 2)
+}
+
+pub fn unsupported_feature(
+    feature: Feature,
+    version: PhpVersion,
+    span: Span,
+    length: usize,
+) -> ParseError {
+    ParseError::new(
+        "E075".to_string(),
+        format!("cannot use {} in {}", feature, version),
+        span,
+    )
+    .with_category(ErrorCategory::Unsupported)
+    .error(
+        format!("requires {} or later", feature.since()),
+        span.position,
+        length,
+    )
+}
+
+pub fn cannot_use_reserved_word_as_a_name(
+    span: Span,
+    word: String,
+    kind: NameKind,
+    since: Option<PhpVersion>,
+) -> ParseError {
+    let message = match since {
+        Some(since) => format!(
+            "cannot use `{}` as a {} name, as it is reserved since {}",
+            word,
+            kind.describe(),
+            since
+        ),
+        None => format!(
+            "cannot use reserved keyword `{}` as a {} name",
+            word,
+            kind.describe()
+        ),
+    };
+
+    ParseError::new("E063".to_string(), message, span).error(
+        "try using a different name",
+        span.position,
+        word.len(),
+    )
+}
+
+pub fn soft_reserved_word_used_as_a_name(
+    span: Span,
+    word: String,
+    kind: NameKind,
+    since: PhpVersion,
+) -> ParseError {
+    ParseError::new(
+        "E064".to_string(),
+        format!(
+            "`{}` is soft-reserved since {}, and may not be usable as a {} name in a future version",
+            word,
+            since,
+            kind.describe()
+        ),
+        span,
+    )
+    .with_category(ErrorCategory::Warning)
+    .error("consider using a different name", span.position, word.len())
+}
+
+pub fn array_access_on_class_name_in_new(new: Span, left_bracket: Span) -> ParseError {
+    ParseError::new(
+        "E065".to_string(),
+        "cannot use array access on a class name in a `new` expression",
+        left_bracket,
+    )
+    .highlight(new.position, 3)
+    .error("unexpected `[`", left_bracket.position, 1)
+    .note(
+        "wrap the `new` expression in parentheses to access the new object, e.g. `(new Foo())[0]`",
+    )
+}
+
+pub fn construct_called_by_qualified_name(name: &SimpleIdentifier, construct: &[u8]) -> ParseError {
+    let construct = String::from_utf8_lossy(construct);
+    let prefix = name.value.len() - construct.len();
+
+    ParseError::new(
+        "E066".to_string(),
+        format!(
+            "cannot call `{}` by a qualified name, as it is a language construct",
+            construct
+        ),
+        name.span,
+    )
+    .error("try removing this", name.span.position, prefix)
+    .note(format!(
+        "`{}` isn't a function, so it can only be used by its bare name",
+        construct
+    ))
+}
+
+pub fn construct_imported_as_function(name: &SimpleIdentifier, construct: &[u8]) -> ParseError {
+    let construct = String::from_utf8_lossy(construct);
+
+    ParseError::new(
+        "E067".to_string(),
+        format!(
+            "cannot import `{}` as a function, as it is a language construct",
+            construct
+        ),
+        name.span,
+    )
+    .error(
+        "try removing this import",
+        name.span.position,
+        name.value.len(),
+    )
+}
+
+pub fn conflicting_import(
+    kind: &UseKind,
+    name: &[u8],
+    alias: &SimpleIdentifier,
+    previous: &SimpleIdentifier,
+) -> ParseError {
+    let kind = match kind {
+        UseKind::Normal => "",
+        UseKind::Function => "function ",
+        UseKind::Const => "const ",
+    };
+
+    ParseError::new(
+        "E070".to_string(),
+        format!(
+            "cannot use {}`{}` as `{}`, as the name is already in use",
+            kind,
+            String::from_utf8_lossy(name),
+            alias
+        ),
+        alias.span,
+    )
+    .highlight(previous.span.position, previous.value.len())
+    .error(
+        "try using a different alias",
+        alias.span.position,
+        alias.value.len(),
+    )
+}
+
+pub fn redundant_import_alias(name: &[u8], alias: &SimpleIdentifier) -> ParseError {
+    ParseError::new(
+        "E071".to_string(),
+        format!(
+            "the alias `{}` of `{}` is the name it already has",
+            alias,
+            String::from_utf8_lossy(name)
+        ),
+        alias.span,
+    )
+    .with_category(ErrorCategory::Warning)
+    .error(
+        "consider removing the alias",
+        alias.span.position,
+        alias.value.len(),
+    )
+}
+
+pub fn import_from_current_namespace(
+    name: &[u8],
+    item: &SimpleIdentifier,
+    global: bool,
+) -> ParseError {
+    let namespace = if global { "global" } else { "current" };
+
+    ParseError::new(
+        "E072".to_string(),
+        format!(
+            "`{}` is in the {} namespace, so importing it has no effect",
+            String::from_utf8_lossy(name),
+            namespace
+        ),
+        item.span,
+    )
+    .with_category(ErrorCategory::Warning)
+    .error(
+        "consider removing this import",
+        item.span.position,
+        item.value.len(),
+    )
+}
+
+pub fn duplicate_label(label: &SimpleIdentifier, previous: &SimpleIdentifier) -> ParseError {
+    ParseError::new(
+        "E073".to_string(),
+        format!("label `{}` is already defined", label),
+        label.span,
+    )
+    .highlight(previous.span.position, previous.value.len())
+    .error(
+        "try using a different label",
+        label.span.position,
+        label.value.len(),
+    )
+    .note("labels must be unique within a function, including its nested blocks")
+}
+
+/// The shape of a nested ternary, and the two ways of parenthesizing it.
+fn nested_ternary_shape(
+    outer_short: bool,
+    inner_short: bool,
+) -> (&'static str, &'static str, &'static str) {
+    match (outer_short, inner_short) {
+        (true, _) => ("a ?: b ? c : d", "(a ?: b) ? c : d", "a ?: (b ? c : d)"),
+        (false, true) => ("a ? b : c ?: d", "(a ? b : c) ?: d", "a ? b : (c ?: d)"),
+        (false, false) => (
+            "a ? b : c ? d : e",
+            "(a ? b : c) ? d : e",
+            "a ? b : (c ? d : e)",
+        ),
+    }
+}
+
+pub fn unparenthesized_nested_ternary(
+    outer: Span,
+    outer_short: bool,
+    inner: Span,
+    inner_short: bool,
+) -> ParseError {
+    let (shape, left, right) = nested_ternary_shape(outer_short, inner_short);
+
+    ParseError::new(
+        "E068".to_string(),
+        format!("unparenthesized `{}` is not supported", shape),
+        inner,
+    )
+    .highlight(outer.position, if outer_short { 2 } else { 1 })
+    .error(
+        "try adding parentheses",
+        inner.position,
+        if inner_short { 2 } else { 1 },
+    )
+    .note(format!("use either `{}` or `{}`", left, right))
+}
+
+pub fn unparenthesized_nested_ternary_is_deprecated(
+    outer: Span,
+    outer_short: bool,
+    inner: Span,
+    inner_short: bool,
+) -> ParseError {
+    let (shape, left, right) = nested_ternary_shape(outer_short, inner_short);
+
+    ParseError::new(
+        "E069".to_string(),
+        format!(
+            "unparenthesized `{}` is deprecated, and an error since PHP 8.0",
+            shape
+        ),
+        inner,
+    )
+    .with_category(ErrorCategory::Warning)
+    .highlight(outer.position, if outer_short { 2 } else { 1 })
+    .error(
+        "consider adding parentheses",
+        inner.position,
+        if inner_short { 2 } else { 1 },
+    )
+    .note(format!(
+        "`{}` is evaluated as `{}`, use `{}` if that's what was meant",
+        shape, left, right
+    ))
+}
+
+impl From<SyntaxError> for ParseError {
+    fn from(e: SyntaxError) -> Self {
+        // The lexer reaching a state it should never be in is the same error as the
+        // parser reaching one.
+        let (id, category) = match e {
+            SyntaxError::UnpredictableState(_) => ("E031", ErrorCategory::Internal),
+            _ => ("E001", ErrorCategory::Syntax),
+        };
+
+        let error = Self {
+            id: id.to_string(),
+            message: format!("syntax error, {}", e),
+            annotations: vec![],
+            span: e.span(),
+            note: None,
+            category,
+            detail: None,
+        };
+
+        match e {
+            SyntaxError::UnexpectedLookalikeCharacter(character, span) => {
+                let label = match character.replacement {
+                    "" => "try removing this".to_string(),
+                    " " => "try replacing this with a space".to_string(),
+                    replacement => format!("try replacing this with `{}`", replacement),
+                };
+
+                error.error(label, span.position, character.length())
+            }
+            SyntaxError::UnclosedString(span) => {
+                error.error("this string is never closed", span.position, 1)
+            }
+            SyntaxError::UnfinishedDocStringStart(span) => error.suggest(
+                "did you mean `<<`, or to start a heredoc on the next line?",
+                span.position,
+                3,
+            ),
+            _ => error,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] Error: {} on line {} column {}",
+            self.id, self.message, self.span.line, self.span.column
+        )?;
+
+        if let Some(note) = &self.note {
+            write!(f, ", Note: {}", note)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Why a file couldn't be parsed, see [`parse_file`].
+///
+/// [`parse_file`]: crate::parser::parse_file
+#[derive(Debug)]
+pub enum Error {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The file was read, but doesn't parse, which includes the errors of the lexer.
+    Parse(ParseErrorStack),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::Parse(stack) => write!(f, "{}", stack),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Parse(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<ParseErrorStack> for Error {
+    fn from(stack: ParseErrorStack) -> Self {
+        Error::Parse(stack)
+    }
+}
+
+/// One error per line, each after the name of the file and the line and column of the
+/// error, as in `src/index.php:3:9: `, when the name of the file is known.
+impl Display for ParseErrorStack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for error in &self.errors {
+            if let Some(name) = &self.source_name {
+                write!(
+                    f,
+                    "{}:{}:{}: ",
+                    name.display(),
+                    error.span.line,
+                    error.span.column
+                )?;
+            }
+
+            writeln!(f, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::lexer::stream::TokenStream;
+
+    fn all_errors() -> Vec<(&'static str, ParseError)> {
+        let tokens = [Token::default()];
+        let mut stream = TokenStream::new(&tokens);
+        let mut state = State::new(&mut stream);
+
+        let span = Span::new(1, 1, 0);
+        let token = Token {
+            kind: TokenKind::Identifier,
+            span,
+            value: "foo".into(),
+        };
+        let identifier = SimpleIdentifier {
+            span,
+            value: "Foo".into(),
+        };
+        let variable = SimpleVariable {
+            span,
+            name: "$foo".into(),
+        };
+        let ty = Type::Void(span);
+
+        vec![
+            (
+                "syntax_error",
+                SyntaxError::UnexpectedEndOfFile(span).into(),
+            ),
+            ("syntax_error", SyntaxError::UnclosedString(span).into()),
+            (
+                "syntax_error",
+                SyntaxError::UnfinishedDocStringStart(span).into(),
+            ),
+            (
+                "reached_unpredictable_state",
+                SyntaxError::UnpredictableState(span).into(),
+            ),
+            (
+                "unexpected_token",
+                unexpected_token(vec![], &Token::default()),
+            ),
+            ("unexpected_token", unexpected_token(vec![], &token)),
+            (
+                "unexpected_token",
+                unexpected_token_after(&Token::default(), &token),
+            ),
+            (
+                "unexpected_token",
+                unexpected_token(vec![";".into()], &token),
+            ),
+            (
+                "unexpected_identifier",
+                unexpected_identifier(vec!["bar".into()], "foo".into(), span),
+            ),
+            (
+                "multiple_modifiers",
+                multiple_modifiers("public".into(), span, span),
+            ),
+            (
+                "multiple_visibility_modifiers",
+                multiple_visibility_modifiers(("public".into(), span), ("private".into(), span)),
+            ),
+            (
+                "standalone_type_used_as_nullable",
+                standalone_type_used_as_nullable(&ty, span),
+            ),
+            (
+                "standalone_type_used_in_union",
+                standalone_type_used_in_union(&ty, span),
+            ),
+            (
+                "standalone_type_used_in_intersection",
+                standalone_type_used_in_intersection(&ty, span),
+            ),
+            (
+                "try_without_catch_or_finally",
+                try_without_catch_or_finally(span, span),
+            ),
+            (
+                "variadic_promoted_property",
+                variadic_promoted_property(
+                    &mut state,
+                    None,
+                    &variable,
+                    span,
+                    &PromotedPropertyModifier::Public(span),
+                ),
+            ),
+            (
+                "missing_type_for_readonly_property",
+                missing_type_for_readonly_property(&mut state, None, &variable, span),
+            ),
+            (
+                "abstract_method_on_a_non_abstract_class",
+                abstract_method_on_a_non_abstract_class(
+                    &mut state,
+                    &identifier,
+                    &identifier,
+                    span,
+                    span,
+                ),
+            ),
+            (
+                "constructor_in_enum",
+                constructor_in_enum(&mut state, &identifier, &identifier),
+            ),
+            (
+                "magic_method_in_enum",
+                magic_method_in_enum(&mut state, &identifier, &identifier),
+            ),
+            (
+                "missing_case_value_for_backed_enum",
+                missing_case_value_for_backed_enum(&mut state, &identifier, &identifier, span),
+            ),
+            (
+                "case_value_for_unit_enum",
+                case_value_for_unit_enum(&mut state, &identifier, &identifier, span),
+            ),
+            (
+                "modifier_cannot_be_used_for_constant",
+                modifier_cannot_be_used_for_constant("static".into(), span),
+            ),
+            (
+                "modifier_cannot_be_used_for_interface_constant",
+                modifier_cannot_be_used_for_interface_constant("static".into(), span),
+            ),
+            (
+                "modifier_cannot_be_used_for_enum_case",
+                modifier_cannot_be_used_for_enum_case("final".to_string(), span),
+            ),
+            (
+                "modifier_cannot_be_used_for_promoted_property",
+                modifier_cannot_be_used_for_promoted_property("static".into(), span),
+            ),
+            (
+                "modifier_cannot_be_used_for_property",
+                modifier_cannot_be_used_for_property("final".into(), span),
+            ),
+            (
+                "modifier_cannot_be_used_for_class",
+                modifier_cannot_be_used_for_class("public".into(), span),
+            ),
+            (
+                "modifier_cannot_be_used_for_class_method",
+                modifier_cannot_be_used_for_class_method("readonly".into(), span),
+            ),
+            (
+                "modifier_cannot_be_used_for_enum_method",
+                modifier_cannot_be_used_for_enum_method("abstract".into(), span),
+            ),
+            (
+                "modifier_cannot_be_used_for_interface_method",
+                modifier_cannot_be_used_for_interface_method("final".into(), span),
+            ),
+            (
+                "final_and_abstract_modifiers_combined_for_class",
+                final_and_abstract_modifiers_combined_for_class(span, span),
+            ),
+            (
+                "final_and_abstract_modifiers_combined_for_class_member",
+                final_and_abstract_modifiers_combined_for_class_member(span, span),
+            ),
+            (
+                "final_and_private_modifiers_combined_for_constant",
+                final_and_private_modifiers_combined_for_constant(span, span),
+            ),
+            (
+                "reached_unpredictable_state",
+                reached_unpredictable_state(span),
+            ),
+            (
+                "static_property_cannot_be_readonly",
+                static_property_cannot_be_readonly(&mut state, None, &variable, span, span),
+            ),
+            (
+                "readonly_property_has_default_value",
+                readonly_property_has_default_value(&mut state, None, &variable, span, span),
+            ),
+            (
+                "unbraced_namespace_declarations_in_braced_context",
+                unbraced_namespace_declarations_in_braced_context(span),
+            ),
+            (
+                "braced_namespace_declarations_in_unbraced_context",
+                braced_namespace_declarations_in_unbraced_context(span),
+            ),
+            (
+                "nested_namespace_declarations",
+                nested_namespace_declarations(span),
+            ),
+            (
+                "forbidden_type_used_in_property",
+                forbidden_type_used_in_property(&mut state, None, &variable, ty.clone()),
+            ),
+            (
+                "match_expression_has_multiple_default_arms",
+                match_expression_has_multiple_default_arms(span, span),
+            ),
+            (
+                "missing_item_definition_after_attributes",
+                missing_item_definition_after_attributes(&vec![], &token),
+            ),
+            (
+                "nested_disjunctive_normal_form_types",
+                nested_disjunctive_normal_form_types(span),
+            ),
+            (
+                "illegal_spread_operator_usage",
+                illegal_spread_operator_usage(span),
+            ),
+            (
+                "cannot_assign_reference_to_non_referencable_value",
+                cannot_assign_reference_to_non_referencable_value(span),
+            ),
+            (
+                "mixing_keyed_and_unkeyed_list_entries",
+                mixing_keyed_and_unkeyed_list_entries(span),
+            ),
+            (
+                "cannot_use_positional_argument_after_named_argument",
+                cannot_use_positional_argument_after_named_argument(span, span, false),
+            ),
+            (
+                "cannot_use_reserved_keyword_as_a_type_name",
+                cannot_use_reserved_keyword_as_a_type_name(span, "list".into()),
+            ),
+            (
+                "cannot_use_reserved_keyword_as_a_goto_label",
+                cannot_use_reserved_keyword_as_a_goto_label(span, "list".into()),
+            ),
+            (
+                "cannot_use_reserved_keyword_as_a_constant_name",
+                cannot_use_reserved_keyword_as_a_constant_name(span, "list".into()),
+            ),
+            (
+                "cannot_use_type_in_context",
+                cannot_use_type_in_context(span, "void".into()),
+            ),
+            (
+                "only_positional_arguments_are_accepted",
+                only_positional_arguments_are_accepted(span, span),
+            ),
+            (
+                "only_one_argument_is_accepted",
+                only_one_argument_is_accepted(span, span),
+            ),
+            ("argument_is_required", argument_is_required(span, span)),
+            ("invalid_loop_level", invalid_loop_level("break", &token)),
+            (
+                "break_level_too_high",
+                break_level_too_high("break", 2, 1, span),
+            ),
+            (
+                "break_outside_of_loop",
+                break_outside_of_loop("break", span),
+            ),
+            (
+                "attributes_on_enum_backing_type",
+                attributes_on_enum_backing_type(span, span),
+            ),
+            (
+                "enum_backing_type_after_implements",
+                enum_backing_type_after_implements(&BackedEnumType::Int(span, span)),
+            ),
+            (
+                "null_default_value_for_non_nullable_property",
+                null_default_value_for_non_nullable_property(
+                    &mut state, None, &variable, &ty, span, 4,
+                ),
+            ),
+            (
+                "default_value_type_mismatch_for_property",
+                default_value_type_mismatch_for_property(
+                    &mut state, None, &variable, &ty, "int", span, 1,
+                ),
+            ),
+            (
+                "class_name_fetch_used_as_class_reference",
+                class_name_fetch_used_as_class_reference(span),
+            ),
+            (
+                "mismatched_body_closer",
+                mismatched_body_closer(span, &TokenKind::RightBrace, &token),
+            ),
+            ("policy_violation", policy_violation("`new`", span, 3)),
+            (
+                "declare_encoding_not_a_string",
+                declare_encoding_not_a_string(span, 4),
+            ),
+            (
+                "cannot_use_reserved_word_as_a_name",
+                cannot_use_reserved_word_as_a_name(
+                    span,
+                    "match".to_string(),
+                    NameKind::Type,
+                    Some(PhpVersion::Php80),
+                ),
+            ),
+            (
+                "soft_reserved_word_used_as_a_name",
+                soft_reserved_word_used_as_a_name(
+                    span,
+                    "enum".to_string(),
+                    NameKind::Type,
+                    PhpVersion::Php81,
+                ),
+            ),
+            (
+                "array_access_on_class_name_in_new",
+                array_access_on_class_name_in_new(span, span),
+            ),
+            (
+                "construct_called_by_qualified_name",
+                construct_called_by_qualified_name(
+                    &SimpleIdentifier {
+                        span,
+                        value: "\\isset".into(),
+                    },
+                    b"isset",
+                ),
+            ),
+            (
+                "construct_imported_as_function",
+                construct_imported_as_function(
+                    &SimpleIdentifier {
+                        span,
+                        value: "Foo\\list".into(),
+                    },
+                    b"list",
+                ),
+            ),
+            (
+                "unparenthesized_nested_ternary",
+                unparenthesized_nested_ternary(span, false, span, true),
+            ),
+            (
+                "unparenthesized_nested_ternary_is_deprecated",
+                unparenthesized_nested_ternary_is_deprecated(span, true, span, false),
+            ),
+            (
+                "conflicting_import",
+                conflicting_import(&UseKind::Function, b"Baz\\bar", &identifier, &identifier),
+            ),
+            (
+                "redundant_import_alias",
+                redundant_import_alias(b"App\\Thing", &identifier),
+            ),
+            (
+                "import_from_current_namespace",
+                import_from_current_namespace(b"App\\Thing", &identifier, false),
+            ),
+            ("duplicate_label", duplicate_label(&identifier, &identifier)),
+            (
+                "strict_types_block_mode",
+                strict_types_block_mode(&identifier),
+            ),
+            (
+                "cannot_use_in_write_context",
+                cannot_use_in_write_context("a function's return value", span, 2),
+            ),
+            (
+                "unsupported_feature",
+                unsupported_feature(Feature::Match, PhpVersion::Php74, span, 5),
+            ),
+            (
+                "expected_literal",
+                expected_literal(&["Integer", "Float", "String"], &token),
+            ),
+            (
+                "assignment_in_case_label",
+                assignment_in_case_label(span, 6),
+            ),
+            (
+                "mixed_case_label_types",
+                mixed_case_label_types(&[(span, 3)], &[(span, 1)]),
+            ),
+            ("yield_in_finally", yield_in_finally(span, 5)),
+            ("yield_by_reference", yield_by_reference(span, true)),
+            (
+                "unreachable_code",
+                unreachable_code("`return`", span, 6, span, span),
+            ),
+            (
+                "attribute_target_not_allowed",
+                attribute_target_not_allowed(b"Foo", "class", &["method"], span, 3),
+            ),
+            (
+                "repeated_attribute",
+                repeated_attribute(b"Foo", span, 3, span, 3),
+            ),
+            ("unknown_attribute", unknown_attribute(b"Foo", span, 3)),
+            (
+                "undefined_member",
+                undefined_member("method", b"foo", b"Bar", None, span, 3),
+            ),
+            (
+                "mismatched_delimiter_in_attribute",
+                mismatched_delimiter_in_attribute(&token, &token),
+            ),
+            (
+                "unclosed_attribute_group",
+                unclosed_attribute_group(span, Some(&token), None),
+            ),
+            (
+                "unclosed_attribute_group",
+                unclosed_attribute_group(span, Some(&token), Some(100)),
+            ),
+            (
+                "invalid_match_arm_condition",
+                invalid_match_arm_condition("`yield`", span, 5),
+            ),
+            (
+                "assignment_in_match_subject",
+                assignment_in_match_subject(span, 6),
+            ),
+            ("nullsafe_closure_creation", nullsafe_closure_creation(span)),
+            (
+                "misplaced_argument_placeholder",
+                misplaced_argument_placeholder(span),
+            ),
+            ("cancelled", cancelled(span)),
+            (
+                "promoted_property_outside_constructor",
+                promoted_property_outside_constructor(&token),
+            ),
+            ("pattern_by_reference", pattern_by_reference(span)),
+            (
+                "method_with_body",
+                method_with_body(&mut state, true, Some(&identifier), &identifier, span),
+            ),
+            (
+                "method_without_body",
+                method_without_body(&mut state, None, &identifier, span),
+            ),
+            (
+                "nullable_type_used_in_compound_type",
+                nullable_type_used_in_compound_type(
+                    &Type::Nullable(span, Box::new(ty.clone())),
+                    true,
+                ),
+            ),
+            (
+                "forbidden_type_used_in_parameter",
+                forbidden_type_used_in_parameter(&variable, ty.clone()),
+            ),
+            (
+                "forbidden_type_used_in_constant",
+                forbidden_type_used_in_constant(&identifier, ty.clone()),
+            ),
+            (
+                "static_variable_shadows",
+                static_variable_shadows(&variable, "parameter", &variable),
+            ),
+            (
+                "duplicate_static_variable",
+                duplicate_static_variable(&variable, &variable),
+            ),
+            (
+                "template_literal_in_shell_exec",
+                template_literal_in_shell_exec(span, span),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_error_codes_are_unique() {
+        let mut codes: HashMap<u16, &str> = HashMap::new();
+
+        for (name, error) in all_errors() {
+            let code = error.code();
+            assert!(
+                code.is_some(),
+                "`{}` has an invalid id `{}`",
+                name,
+                error.id
+            );
+
+            if let Some(other) = codes.insert(code.unwrap(), name) {
+                assert_eq!(
+                    other, name,
+                    "`{}` and `{}` share the code {}",
+                    other, name, error.id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_errors_are_listed() {
+        let listed: Vec<String> = all_errors()
+            .into_iter()
+            .map(|(_, error)| error.id)
+            .collect();
+
+        // Every id in the code above the tests, as in `"E001"`.
+        let source = include_str!("error.rs");
+        let source = &source[..source.find("#[cfg(test)]").unwrap()];
+        for (index, _) in source.match_indices("\"E") {
+            let id = &source[index + 1..];
+            let Some(end) = id.find('"') else {
+                continue;
+            };
+            let id = &id[..end];
+
+            if id.len() > 1 && id[1..].bytes().all(|byte| byte.is_ascii_digit()) {
+                assert!(
+                    listed.iter().any(|listed| listed == id),
+                    "`{}` is not in `all_errors`",
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unexpected_token_suggestions() {
+        let suggestion = |code: &str| {
+            let stack = crate::parse(code).unwrap_err();
+
+            stack.errors[0].suggestion().map(str::to_string)
+        };
+
+        assert_eq!(
+            suggestion("<?php $a << <EOT;").as_deref(),
+            Some("did you mean to start a heredoc with `<<<`?")
+        );
+        assert_eq!(
+            suggestion("<?php $a <<<EOT").as_deref(),
+            Some("did you mean `<<`, or to start a heredoc on the next line?")
+        );
+        assert_eq!(
+            suggestion("<?php $a ? ?: $b;").as_deref(),
+            Some("did you mean `?:`?")
+        );
+        assert_eq!(
+            suggestion("<?php $a = [1 = > 2];").as_deref(),
+            Some("did you mean `=>`?")
+        );
+        assert_eq!(suggestion("<?php $a = ];"), None);
+    }
+
+    #[test]
+    fn test_foreign_syntax_suggestions() {
+        let suggestion = |code: &str| {
+            let stack = crate::parse(code).unwrap_err();
+            let error = &stack.errors[0];
+            let annotation = error
+                .annotations
+                .iter()
+                .find(|annotation| annotation.r#type == ParseErrorAnnotationType::Suggestion)?;
+
+            Some(format!(
+                "{} at {}",
+                annotation.message,
+                &code[annotation.position..annotation.position + annotation.length]
+            ))
+        };
+
+        assert_eq!(
+            suggestion("<?php $f = ($x) => $x + 1;").as_deref(),
+            Some("use `fn ($x) => ...` for an arrow function at =>")
+        );
+        assert_eq!(
+            suggestion("<?php usort($a, ($x) => $x);").as_deref(),
+            Some("use `fn ($x) => ...` for an arrow function at =>")
+        );
+        // A `=>` that isn't after the parameters of a function.
+        assert_eq!(suggestion("<?php [&$foo => $bar];"), None);
+        assert_eq!(suggestion("<?php foo($a => 1);"), None);
+        assert_eq!(suggestion("<?php $b = foo($a) => 1;"), None);
+        assert_eq!(
+            suggestion("<?php if ($a) {} elif ($b) {}").as_deref(),
+            Some("use `elseif` at elif")
+        );
+        assert_eq!(
+            suggestion("<?php function f() { if ($a): /* b */ ELIF ($b): endif; }").as_deref(),
+            Some("use `elseif` at ELIF")
+        );
+        assert_eq!(
+            suggestion("<?php $s = `${items.map(item => item.name)}`;").as_deref(),
+            Some("backticks run a shell command, use double quotes for a string, as in `\"{$name}\"` at `")
+        );
+        assert_eq!(
+            suggestion("<?php let $x = 1;").as_deref(),
+            Some("try removing this, a variable is declared by assigning to it, as in `$x = 1` at let")
+        );
+        assert_eq!(
+            suggestion("<?php var $x = 1;").as_deref(),
+            Some("try removing this, a variable is declared by assigning to it, as in `$x = 1` at var")
+        );
+        assert_eq!(
+            suggestion("<?php def main():").as_deref(),
+            Some("use `function` to declare a function at def")
+        );
+
+        // Errors in PHP that only looks like another language are left as they are.
+        assert_eq!(suggestion("<?php $a = [1, 2 => ];"), None);
+        assert_eq!(suggestion("<?php elif($b);\n$c = ;"), None);
+        assert_eq!(suggestion("<?php $s = `${a}` => 1;"), None);
+    }
+
+    #[test]
+    fn test_template_literal_in_shell_exec() {
+        let warnings = |code: &str| {
+            crate::parse_with_warnings(code, crate::ParserConfig::new())
+                .unwrap()
+                .1
+                .into_iter()
+                .map(|warning| (warning.id, warning.span.column))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            warnings("<?php $s = `Hello ${name}, ${user.name}`; $t = `ls $dir`;"),
+            vec![("E103".to_string(), 19)]
+        );
+        assert!(warnings("<?php $s = \"Hello {$name}\"; $t = `ls {$dir}`;").is_empty());
+    }
+
+    #[test]
+    fn test_break_level_detail() {
+        let error = |code: &str| crate::parse(code).unwrap_err().errors.remove(0);
+
+        let too_high = error("<?php while (true) { break 3; }");
+        assert_eq!(too_high.id, "E053");
+        assert_eq!(
+            too_high.detail,
+            Some(ParseErrorDetail::BreakLevelTooHigh {
+                requested: 3,
+                available: 1
+            })
+        );
+
+        let outside = error("<?php function foo() { continue; }");
+        assert_eq!(outside.id, "E104");
+        assert_eq!(outside.detail, None);
+    }
+
+    #[test]
+    fn test_expected_literal() {
+        let error = |code: &str| crate::parse(code).unwrap_err().errors.remove(0);
+
+        let negated = error("<?php declare(ticks=-'1');");
+        assert_eq!(negated.id, "E077");
+        assert_eq!(negated.span.column, 22);
+        assert_eq!(
+            negated.annotations[0].message,
+            "expected an integer or a float"
+        );
+        assert_eq!(
+            negated.note.as_deref(),
+            Some("only a number can be negated here")
+        );
+
+        let constant = error("<?php declare(ticks=\\PHP_INT_MAX);");
+        assert_eq!(
+            constant.message,
+            "unexpected identifier, expecting a literal"
+        );
+        assert_eq!(
+            constant.annotations[0].message,
+            "expected an integer, a float, or a string"
+        );
+        assert!(constant.note.is_some());
+
+        assert!(crate::parse("<?php declare(ticks=-1, precision=-1.5);").is_ok());
+        assert_eq!(error("<?php declare(ticks=--1);").id, "E077");
+        assert_eq!(error("<?php declare(ticks=1 + 1);").id, "E005");
+    }
+
+    #[test]
+    #[cfg(feature = "report")]
+    fn test_multiline_highlights_are_previewed() {
+        let source = "<?php\n$f = function ($x)\n{\n    return $x;\n};\n$g = 1;\n";
+        let start = source.find("function").unwrap();
+        let end = source.find("};").unwrap() + 1;
+        let g = source.find("$g").unwrap();
+
+        let report = |error: ParseError| error.report(source, None, false, true).unwrap();
+
+        let error = ParseError::new("E001", "message", Span::new(6, 1, g))
+            .highlight(start, end - start)
+            .error("here", g, 2);
+        assert!(report(error).contains("function ($x) { return $x; }"));
+
+        let error = ParseError::new("E001", "message", Span::new(6, 1, g))
+            .highlight(start, 8)
+            .error("here", g, 2);
+        assert!(!report(error).contains("function ($x) {"));
+    }
+
+    #[test]
+    fn test_source_name() {
+        let code = "<?php\necho 1 +;\n$a = ;";
+
+        let stack = crate::parse(code).unwrap_err();
+        assert!(stack.to_string().starts_with("[E003] Error:"));
+
+        let config = crate::ParserConfig::new().source_name("app/src/index.php");
+        let stack = crate::parse_with_config(code, config).unwrap_err();
+        assert_eq!(stack.source_name, Some(PathBuf::from("app/src/index.php")));
+        assert!(stack
+            .to_string()
+            .starts_with("app/src/index.php:2:9: [E003] Error:"));
+
+        #[cfg(feature = "report")]
+        assert!(stack
+            .report(code, None, false, true)
+            .unwrap()
+            .contains("app/src/index.php:2:9"));
+    }
+
+    #[test]
+    fn test_error_category() {
+        for (name, error) in all_errors() {
+            let expected = match name {
+                "reached_unpredictable_state" => ErrorCategory::Internal,
+                "policy_violation" => ErrorCategory::Policy,
+                "soft_reserved_word_used_as_a_name"
+                | "unparenthesized_nested_ternary_is_deprecated"
+                | "redundant_import_alias"
+                | "import_from_current_namespace"
+                | "assignment_in_case_label"
+                | "assignment_in_match_subject"
+                | "mixed_case_label_types"
+                | "yield_in_finally"
+                | "unreachable_code"
+                | "undefined_member"
+                | "static_variable_shadows"
+                | "duplicate_static_variable"
+                | "template_literal_in_shell_exec" => ErrorCategory::Warning,
+                "unknown_attribute" => ErrorCategory::Info,
+                "cancelled" => ErrorCategory::Cancelled,
+                "unsupported_feature" => ErrorCategory::Unsupported,
+                // An attribute group that isn't closed within the token limit.
+                "unclosed_attribute_group" if error.id == "E105" => ErrorCategory::Limit,
+                _ => ErrorCategory::Syntax,
+            };
+
+            assert_eq!(
+                error.category(),
+                expected,
+                "`{}` has the wrong category",
+                name
+            );
+        }
     }
 }
@@ -1,7 +1,10 @@
 use crate::expected_token_err;
 use crate::lexer::token::DocStringKind;
+use crate::lexer::token::KindId;
+use crate::lexer::token::Span;
 use crate::lexer::token::TokenKind;
 use crate::parser::ast::arguments::ArgumentPlaceholder;
+use crate::parser::ast::arguments::ArgumentList;
 use crate::parser::ast::identifiers::DynamicIdentifier;
 use crate::parser::ast::identifiers::Identifier;
 use crate::parser::ast::identifiers::SimpleIdentifier;
@@ -15,14 +18,14 @@ use crate::parser::ast::operators::BitwiseOperationExpression;
 use crate::parser::ast::operators::ComparisonOperationExpression;
 use crate::parser::ast::operators::LogicalOperationExpression;
 use crate::parser::ast::{
-    ArrayIndexExpression, CoalesceExpression, ConcatExpression, ConstantFetchExpression,
-    Expression, FunctionCallExpression, FunctionClosureCreationExpression, InstanceofExpression,
-    MagicConstantExpression, MethodCallExpression, MethodClosureCreationExpression,
-    NullsafeMethodCallExpression, NullsafePropertyFetchExpression, PropertyFetchExpression,
-    ReferenceExpression, ShortTernaryExpression, StaticMethodCallExpression,
-    StaticMethodClosureCreationExpression, StaticPropertyFetchExpression,
-    StaticVariableMethodCallExpression, StaticVariableMethodClosureCreationExpression,
-    TernaryExpression,
+    ArrayIndexExpression, CoalesceExpression, ConcatExpression, ConcatManyExpression,
+    ConstantFetchExpression, Expression, FunctionCallExpression, FunctionClosureCreationExpression,
+    InstanceofExpression, MagicConstantExpression, MethodCallExpression,
+    MethodClosureCreationExpression, NullsafeMethodCallExpression, NullsafePropertyFetchExpression,
+    PropertyFetchExpression, ReferenceExpression, ShortTernaryExpression,
+    StaticMethodCallExpression, StaticMethodClosureCreationExpression,
+    StaticPropertyFetchExpression, StaticVariableMethodCallExpression,
+    StaticVariableMethodClosureCreationExpression, TernaryExpression,
 };
 use crate::parser::error;
 use crate::parser::error::ParseResult;
@@ -33,12 +36,16 @@ use crate::parser::internal::control_flow;
 use crate::parser::internal::functions;
 use crate::parser::internal::identifiers;
 use crate::parser::internal::parameters;
+use crate::parser::internal::policy;
 use crate::parser::internal::precedences::Associativity;
 use crate::parser::internal::precedences::Precedence;
+use crate::parser::internal::recovery;
 use crate::parser::internal::strings;
 use crate::parser::internal::utils;
 use crate::parser::internal::variables;
 use crate::parser::state::State;
+use crate::parser::version::Feature;
+use crate::parser::version::PhpVersion;
 
 use super::ast::BoolExpression;
 use super::ast::CastExpression;
@@ -63,26 +70,80 @@ use super::ast::YieldFromExpression;
 use super::ast::operators::RangeOperationExpression;
 
 pub fn create(state: &mut State) -> ParseResult<Expression> {
-    for_precedence(state, Precedence::Lowest)
+    recovery::expression(state, |state| for_precedence(state, Precedence::Lowest))
 }
 
 fn null_coalesce_precedence(state: &mut State) -> ParseResult<Expression> {
     for_precedence(state, Precedence::NullCoalesce)
 }
 
-fn clone_or_new_precedence(state: &mut State) -> ParseResult<Expression> {
-    for_precedence(state, Precedence::CloneOrNew)
+/// The class reference of a `new` expression.
+///
+/// Like PHP, array access, property fetches and static property fetches are part of
+/// the class reference, so `new $a[0]` instantiates the class named by `$a[0]`. Calls
+/// aren't, as the parentheses are the arguments of `new` itself.
+fn new_target(state: &mut State) -> ParseResult<Expression> {
+    let mut target = left(state, &Precedence::CloneOrNew)?;
+
+    loop {
+        let current = state.stream.current();
+        let is_class_name = matches!(
+            target,
+            Expression::Identifier(_) | Expression::Parenthesized(_)
+        );
+
+        target = match current.kind {
+            TokenKind::LeftBracket if !is_class_name => {
+                postfix(state, target, &TokenKind::LeftBracket)?
+            }
+            TokenKind::Arrow if !is_class_name => {
+                state.stream.next();
+
+                Expression::PropertyFetch(PropertyFetchExpression {
+                    target: Box::new(target),
+                    arrow: current.span,
+                    property: Box::new(property_name(state)?),
+                })
+            }
+            TokenKind::QuestionArrow if !is_class_name => {
+                state.stream.next();
+
+                Expression::NullsafePropertyFetch(NullsafePropertyFetchExpression {
+                    target: Box::new(target),
+                    question_arrow: current.span,
+                    property: Box::new(property_name(state)?),
+                })
+            }
+            TokenKind::DoubleColon
+                if matches!(
+                    state.stream.peek().kind,
+                    TokenKind::Variable | TokenKind::Dollar | TokenKind::DollarLeftBrace
+                ) =>
+            {
+                state.stream.next();
+
+                Expression::StaticPropertyFetch(StaticPropertyFetchExpression {
+                    target: Box::new(target),
+                    double_colon: current.span,
+                    property: variables::dynamic_variable(state)?,
+                })
+            }
+            _ => return Ok(target),
+        };
+    }
 }
 
-fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expression> {
+pub fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expression> {
     let mut left = left(state, &precedence)?;
 
     loop {
+        state.check_cancelled()?;
+
         let current = state.stream.current();
         let span = current.span;
         let kind = &current.kind;
 
-        if matches!(current.kind, TokenKind::SemiColon | TokenKind::Eof) {
+        if matches!(current.kind.kind_id(), KindId::SemiColon | KindId::Eof) {
             break;
         }
 
@@ -93,7 +154,11 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                 break;
             }
 
+            policy::token(state, current)?;
+
             left = postfix(state, left, kind)?;
+            policy::call(state, &left)?;
+
             continue;
         }
 
@@ -112,6 +177,8 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                 return Err(error::unexpected_token(vec![], current));
             }
 
+            policy::token(state, current)?;
+
             state.stream.next();
 
             let op = state.stream.current();
@@ -121,10 +188,11 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                     // this happens due to a comment, or whitespaces between the ? and the :
                     // we consider `foo() ? : bar()` a ternary expression, with `then` being a noop
                     // however, this must behave like a short ternary at runtime.
-                    if op.kind == TokenKind::Colon {
+                    if op.kind.kind_id() == KindId::Colon {
                         state.stream.next();
 
                         let r#else = create(state)?;
+                        nested_ternary(state, span, true, &r#else);
 
                         Expression::Ternary(TernaryExpression {
                             condition: Box::new(left),
@@ -137,6 +205,7 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                         let then = create(state)?;
                         let colon = utils::skip_colon(state)?;
                         let r#else = create(state)?;
+                        nested_ternary(state, span, false, &r#else);
 
                         Expression::Ternary(TernaryExpression {
                             condition: Box::new(left),
@@ -149,13 +218,15 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                 }
                 TokenKind::QuestionColon => {
                     let r#else = create(state)?;
+                    nested_ternary(state, span, true, &r#else);
+
                     Expression::ShortTernary(ShortTernaryExpression {
                         condition: Box::new(left),
                         question_colon: span,
                         r#else: Box::new(r#else),
                     })
                 }
-                TokenKind::Equals if op.kind == TokenKind::Ampersand => {
+                TokenKind::Equals if op.kind.kind_id() == KindId::Ampersand => {
                     state.stream.next();
 
                     // FIXME: You should only be allowed to assign a referencable variable,
@@ -171,34 +242,34 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                         })),
                     })
                 }
-                TokenKind::Instanceof if op.kind == TokenKind::Self_ => {
+                TokenKind::Instanceof if op.kind.kind_id() == KindId::Self_ => {
                     state.stream.next();
 
                     Expression::Instanceof(InstanceofExpression {
                         left: Box::new(left),
                         instanceof: span,
-                        right: Box::new(Expression::Self_),
+                        right: Box::new(Expression::Self_(op.span)),
                     })
                 }
-                TokenKind::Instanceof if op.kind == TokenKind::Parent => {
+                TokenKind::Instanceof if op.kind.kind_id() == KindId::Parent => {
                     state.stream.next();
 
                     Expression::Instanceof(InstanceofExpression {
                         left: Box::new(left),
                         instanceof: span,
-                        right: Box::new(Expression::Parent),
+                        right: Box::new(Expression::Parent(op.span)),
                     })
                 }
-                TokenKind::Instanceof if op.kind == TokenKind::Static => {
+                TokenKind::Instanceof if op.kind.kind_id() == KindId::Static => {
                     state.stream.next();
 
                     Expression::Instanceof(InstanceofExpression {
                         left: Box::new(left),
                         instanceof: span,
-                        right: Box::new(Expression::Static),
+                        right: Box::new(Expression::Static(op.span)),
                     })
                 }
-                TokenKind::Instanceof if op.kind == TokenKind::Enum => {
+                TokenKind::Instanceof if op.kind.kind_id() == KindId::Enum => {
                     let enum_span = op.span;
                     state.stream.next();
 
@@ -213,7 +284,7 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                         ))),
                     })
                 }
-                TokenKind::Instanceof if op.kind == TokenKind::From => {
+                TokenKind::Instanceof if op.kind.kind_id() == KindId::From => {
                     let from_span = op.span;
                     state.stream.next();
 
@@ -228,9 +299,9 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                         ))),
                     })
                 }
-                TokenKind::Instanceof if op.kind == TokenKind::Type => {
+                TokenKind::Instanceof if op.kind.kind_id() == KindId::Type => {
                     let from_span = op.span;
-                    let from_value = op.value.clone();
+                    let from_value = op.value.to_byte_string();
                     state.stream.next();
 
                     Expression::Instanceof(InstanceofExpression {
@@ -244,6 +315,11 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                         ))),
                     })
                 }
+                TokenKind::Dot if state.config.flatten_concat => {
+                    let right = for_precedence(state, rpred)?;
+
+                    concat(left, span, right)
+                }
                 _ => {
                     let left = Box::new(left);
                     let right = Box::new(for_precedence(state, rpred)?);
@@ -292,6 +368,8 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                             },
                         ),
                         TokenKind::Equals => {
+                            arrays::destructuring(state, &left);
+
                             Expression::AssignmentOperation(AssignmentOperationExpression::Assign {
                                 left,
                                 equals: span,
@@ -375,13 +453,17 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                                 right,
                             },
                         ),
-                        TokenKind::DoubleQuestionEquals => Expression::AssignmentOperation(
-                            AssignmentOperationExpression::Coalesce {
-                                left,
-                                coalesce_equals: span,
-                                right,
-                            },
-                        ),
+                        TokenKind::DoubleQuestionEquals => {
+                            state.require(Feature::NullCoalescingAssignment, span, 3);
+
+                            Expression::AssignmentOperation(
+                                AssignmentOperationExpression::Coalesce {
+                                    left,
+                                    coalesce_equals: span,
+                                    right,
+                                },
+                            )
+                        }
                         TokenKind::DotEquals => {
                             Expression::AssignmentOperation(AssignmentOperationExpression::Concat {
                                 left,
@@ -554,6 +636,10 @@ fn for_precedence(state: &mut State, precedence: Precedence) -> ParseResult<Expr
                 }
             };
 
+            if let Expression::AssignmentOperation(assignment) = &left {
+                write_context(state, assignment.left());
+            }
+
             continue;
         }
 
@@ -568,7 +654,12 @@ fn left(state: &mut State, precedence: &Precedence) -> ParseResult<Expression> {
         return Err(error::unexpected_token(vec![], state.stream.current()));
     }
 
-    attributes(state, precedence)
+    policy::token(state, state.stream.current())?;
+
+    let left = attributes(state, precedence)?;
+    policy::call(state, &left)?;
+
+    Ok(left)
 }
 
 macro_rules! expressions {
@@ -695,6 +786,11 @@ expressions! {
         let isset = state.stream.current().span;
         state.stream.next();
         let arguments = parameters::argument_list(state)?;
+        if arguments.arguments.is_empty() {
+            let right_parenthesis = arguments.right_parenthesis;
+
+            return Err(error::argument_is_required(right_parenthesis, right_parenthesis));
+        }
 
         Ok(Expression::Isset(IssetExpression { isset, arguments}))
     })
@@ -704,6 +800,11 @@ expressions! {
         let unset = state.stream.current().span;
         state.stream.next();
         let arguments = parameters::argument_list(state)?;
+        if arguments.arguments.is_empty() {
+            let right_parenthesis = arguments.right_parenthesis;
+
+            return Err(error::argument_is_required(right_parenthesis, right_parenthesis));
+        }
 
         Ok(Expression::Unset(UnsetExpression { unset, arguments}))
     })
@@ -757,49 +858,69 @@ expressions! {
 
     #[before(r#yield), current(TokenKind::Throw)]
     throw({
+        let throw = state.stream.current().span;
+        state.require(Feature::ThrowExpressions, throw, 5);
         state.stream.next();
 
         Ok(Expression::Throw(ThrowExpression {
+            throw,
             value: Box::new(for_precedence(state, Precedence::Lowest)?)
         }))
     })
 
     #[before(clone), current(TokenKind::Yield)]
     r#yield({
+        let r#yield = state.stream.current().span;
         state.stream.next();
+
+        if state.finally_depth > 0 {
+            let length = match state.stream.current().kind {
+                TokenKind::From => state.stream.current().span.position + 4 - r#yield.position,
+                _ => 5,
+            };
+
+            state.warn(error::yield_in_finally(r#yield, length));
+        }
+
         if state.stream.current().kind == TokenKind::SemiColon || state.stream.current().kind == TokenKind::RightParen {
             Ok(Expression::Yield(YieldExpression {
+                r#yield,
                 key: None,
                 value: None,
             }))
         } else {
-            let mut from = false;
+            let mut from = None;
 
             if state.stream.current().kind == TokenKind::From {
+                from = Some(state.stream.current().span);
                 state.stream.next();
-                from = true;
             }
 
             let mut key = None;
+            if from.is_none() {
+                yield_ampersand(state);
+            }
             let mut value = Box::new(for_precedence(
                 state,
-                if from {
+                if from.is_some() {
                     Precedence::YieldFrom
                 } else {
                     Precedence::Yield
                 },
             )?);
 
-            if state.stream.current().kind == TokenKind::DoubleArrow && !from {
+            if state.stream.current().kind == TokenKind::DoubleArrow && from.is_none() {
                 state.stream.next();
                 key = Some(value.clone());
+                yield_ampersand(state);
                 value = Box::new(for_precedence(state, Precedence::Yield)?);
             }
 
-            if from {
-                Ok(Expression::YieldFrom(YieldFromExpression { value }))
+            if let Some(from) = from {
+                Ok(Expression::YieldFrom(YieldFromExpression { r#yield, from, value }))
             } else {
                 Ok(Expression::Yield(YieldExpression {
+                    r#yield,
                     key,
                     value: Some(value),
                 }))
@@ -809,34 +930,41 @@ expressions! {
 
     #[before(r#true), current(TokenKind::Clone)]
     clone({
+        let clone = state.stream.current().span;
         state.stream.next();
 
-        let target = for_precedence(state, Precedence::CloneOrNew)?;
+        // calls, array access and member access on the operand bind tighter than `clone`,
+        // so `clone $a->b()` clones the result of the call.
+        let target = for_precedence(state, Precedence::CallDim)?;
 
         Ok(Expression::Clone(CloneExpression {
+            clone,
             target: Box::new(target),
         }))
     })
 
     #[before(r#false), current(TokenKind::True)]
     r#true({
+        let span = state.stream.current().span;
         state.stream.next();
 
-        Ok(Expression::Bool(BoolExpression { value: true }))
+        Ok(Expression::Bool(BoolExpression { value: true, span }))
     })
 
     #[before(null), current(TokenKind::False)]
     r#false({
+        let span = state.stream.current().span;
         state.stream.next();
 
-        Ok(Expression::Bool(BoolExpression { value: false }))
+        Ok(Expression::Bool(BoolExpression { value: false, span }))
     })
 
     #[before(literal_integer), current(TokenKind::Null)]
     null({
+        let span = state.stream.current().span;
         state.stream.next();
 
-        Ok(Expression::Null)
+        Ok(Expression::Null(span))
     })
 
     #[before(literal_float), current(TokenKind::LiteralInteger)]
@@ -844,12 +972,16 @@ expressions! {
         let current = state.stream.current();
 
         if let TokenKind::LiteralInteger = &current.kind {
+            if current.value.len() > 1 && matches!(current.value[1], b'o' | b'O') {
+                state.require(Feature::ExplicitOctalLiterals, current.span, 2);
+            }
+
             state.stream.next();
 
             Ok(Expression::Literal(Literal::Integer(
                 LiteralInteger {
                     span: current.span,
-                    value: current.value.clone()
+                    value: current.value.to_byte_string()
                 }
             )))
         } else {
@@ -867,7 +999,7 @@ expressions! {
             Ok(Expression::Literal(
                 Literal::Float(LiteralFloat {
                     span: current.span,
-                    value: current.value.clone()
+                    value: current.value.to_byte_string()
                 })
             ))
         } else {
@@ -885,7 +1017,7 @@ expressions! {
             Ok(Expression::Literal(
                 Literal::String(LiteralString {
                     span: current.span,
-                    value: current.value.clone()
+                    value: current.value.to_byte_string()
                 })
             ))
         } else {
@@ -915,28 +1047,41 @@ expressions! {
 
     #[before(static_postfix), current(TokenKind::Identifier | TokenKind::QualifiedIdentifier | TokenKind::FullyQualifiedIdentifier)]
     identifier({
-        Ok(Expression::Identifier(Identifier::SimpleIdentifier(identifiers::full_name(state)?)))
+        let name = identifiers::full_name(state)?;
+
+        if state.stream.current().kind == TokenKind::LeftParen {
+            if let Some(construct) = identifiers::qualified_construct(&name) {
+                let error = error::construct_called_by_qualified_name(&name, construct);
+
+                state.record(error);
+            }
+        }
+
+        Ok(Expression::Identifier(Identifier::SimpleIdentifier(name)))
     })
 
     #[before(self_identifier), current(TokenKind::Static)]
     static_postfix({
+        let span = state.stream.current().span;
         state.stream.next();
 
-        postfix(state, Expression::Static, &TokenKind::DoubleColon)
+        postfix(state, Expression::Static(span), &TokenKind::DoubleColon)
     })
 
     #[before(parent_identifier), current(TokenKind::Self_)]
     self_identifier({
+        let span = state.stream.current().span;
         state.stream.next();
 
-        Ok(Expression::Self_)
+        Ok(Expression::Self_(span))
     })
 
     #[before(left_parenthesis), current(TokenKind::Parent)]
     parent_identifier({
+        let span = state.stream.current().span;
         state.stream.next();
 
-        Ok(Expression::Parent)
+        Ok(Expression::Parent(span))
     })
 
     #[before(r#match), current(TokenKind::LeftParen)]
@@ -978,19 +1123,22 @@ expressions! {
 
         let target = match state.stream.current().kind {
             TokenKind::Self_ => {
+                let span = state.stream.current().span;
                 state.stream.next();
 
-                Expression::Self_
+                Expression::Self_(span)
             }
             TokenKind::Static => {
+                let span = state.stream.current().span;
                 state.stream.next();
 
-                Expression::Static
+                Expression::Static(span)
             }
             TokenKind::Parent => {
+                let span = state.stream.current().span;
                 state.stream.next();
 
-                Expression::Parent
+                Expression::Parent(span)
             }
             TokenKind::Enum => {
                 let span = state.stream.current().span;
@@ -1008,13 +1156,13 @@ expressions! {
             }
             TokenKind::Type => {
                 let span = state.stream.current().span;
-                let value = state.stream.current().value.clone();
+                let value = state.stream.current().value.to_byte_string();
 
                 state.stream.next();
 
                 Expression::Identifier(Identifier::SimpleIdentifier(SimpleIdentifier { span, value }))
             }
-            _ => clone_or_new_precedence(state)?,
+            _ => new_target(state)?,
         };
 
         let arguments = if state.stream.current().kind == TokenKind::LeftParen {
@@ -1023,6 +1171,12 @@ expressions! {
             None
         };
 
+        if arguments.is_none() && state.stream.current().kind == TokenKind::LeftBracket {
+            let error = error::array_access_on_class_name_in_new(new, state.stream.current().span);
+
+            state.record(error);
+        }
+
         Ok(Expression::New(NewExpression {
             target: Box::new(target),
             new,
@@ -1212,7 +1366,7 @@ expressions! {
 fn unexpected_token(state: &mut State, _: &Precedence) -> ParseResult<Expression> {
     let current = state.stream.current();
 
-    Err(error::unexpected_token(vec![], current))
+    Err(error::unexpected_token_after(state.stream.previous(), current))
 }
 
 fn postfix(state: &mut State, lhs: Expression, op: &TokenKind) -> ParseResult<Expression> {
@@ -1231,20 +1385,7 @@ fn postfix(state: &mut State, lhs: Expression, op: &TokenKind) -> ParseResult<Ex
         }
         TokenKind::LeftParen => {
             // `(...)` closure creation
-            if state.stream.lookahead(0).kind == TokenKind::Ellipsis
-                && state.stream.lookahead(1).kind == TokenKind::RightParen
-            {
-                let start = utils::skip(state, TokenKind::LeftParen)?;
-                let ellipsis = utils::skip(state, TokenKind::Ellipsis)?;
-                let end = utils::skip(state, TokenKind::RightParen)?;
-
-                let placeholder = ArgumentPlaceholder {
-                    comments: state.stream.comments(),
-                    left_parenthesis: start,
-                    ellipsis,
-                    right_parenthesis: end,
-                };
-
+            if let Some(placeholder) = argument_placeholder(state)? {
                 Expression::FunctionClosureCreation(FunctionClosureCreationExpression {
                     target: Box::new(lhs),
                     placeholder,
@@ -1271,8 +1412,32 @@ fn postfix(state: &mut State, lhs: Expression, op: &TokenKind) -> ParseResult<Ex
         TokenKind::DoubleColon => {
             let span = utils::skip_double_colon(state)?;
 
+            if let Expression::ConstantFetch(ConstantFetchExpression {
+                constant: Identifier::SimpleIdentifier(constant),
+                ..
+            }) = &lhs
+            {
+                if constant.value[..].eq_ignore_ascii_case(b"class") {
+                    let error = error::class_name_fetch_used_as_class_reference(span);
+
+                    state.record(error);
+                }
+            }
+
             let current = state.stream.current();
 
+            if current.value[..].eq_ignore_ascii_case(b"class")
+                && !matches!(
+                    lhs,
+                    Expression::Identifier(_)
+                        | Expression::Self_(_)
+                        | Expression::Static(_)
+                        | Expression::Parent(_)
+                )
+            {
+                state.require(Feature::ClassNameOfObjects, current.span, 5);
+            }
+
             let property = match current.kind {
                 TokenKind::Variable | TokenKind::Dollar | TokenKind::DollarLeftBrace => {
                     Expression::Variable(variables::dynamic_variable(state)?)
@@ -1307,20 +1472,7 @@ fn postfix(state: &mut State, lhs: Expression, op: &TokenKind) -> ParseResult<Ex
             let lhs = Box::new(lhs);
 
             if state.stream.current().kind == TokenKind::LeftParen {
-                if state.stream.lookahead(0).kind == TokenKind::Ellipsis
-                    && state.stream.lookahead(1).kind == TokenKind::RightParen
-                {
-                    let start = utils::skip(state, TokenKind::LeftParen)?;
-                    let ellipsis = utils::skip(state, TokenKind::Ellipsis)?;
-                    let end = utils::skip(state, TokenKind::RightParen)?;
-
-                    let placeholder = ArgumentPlaceholder {
-                        comments: state.stream.comments(),
-                        left_parenthesis: start,
-                        ellipsis,
-                        right_parenthesis: end,
-                    };
-
+                if let Some(placeholder) = argument_placeholder(state)? {
                     match property {
                         Expression::Identifier(identifier) => {
                             Expression::StaticMethodClosureCreation(
@@ -1370,6 +1522,10 @@ fn postfix(state: &mut State, lhs: Expression, op: &TokenKind) -> ParseResult<Ex
             } else {
                 match property {
                     Expression::Identifier(identifier) => {
+                        if let Identifier::DynamicIdentifier(dynamic) = &identifier {
+                            state.require(Feature::DynamicClassConstantFetch, dynamic.start, 1);
+                        }
+
                         Expression::ConstantFetch(ConstantFetchExpression {
                             target: lhs,
                             double_colon: span,
@@ -1389,38 +1545,28 @@ fn postfix(state: &mut State, lhs: Expression, op: &TokenKind) -> ParseResult<Ex
         }
         TokenKind::Arrow | TokenKind::QuestionArrow => {
             let span = state.stream.current().span;
-            state.stream.next();
-
-            let property = match state.stream.current().kind {
-                TokenKind::Variable | TokenKind::Dollar | TokenKind::DollarLeftBrace => {
-                    Expression::Variable(variables::dynamic_variable(state)?)
-                }
-                _ if identifiers::is_identifier_maybe_reserved(&state.stream.current().kind) => {
-                    Expression::Identifier(Identifier::SimpleIdentifier(
-                        identifiers::identifier_maybe_reserved(state)?,
-                    ))
-                }
-                TokenKind::LeftBrace => {
-                    let start = state.stream.current().span;
-                    state.stream.next();
+            if op == &TokenKind::QuestionArrow {
+                state.require(Feature::NullsafeOperator, span, 3);
+            }
 
-                    let name = create(state)?;
+            state.stream.next();
 
-                    let end = utils::skip_right_brace(state)?;
-
-                    Expression::Identifier(Identifier::DynamicIdentifier(DynamicIdentifier {
-                        start,
-                        expr: Box::new(name),
-                        end,
-                    }))
-                }
-                _ => {
-                    return expected_token_err!(["`{`", "`$`", "an identifier"], state);
-                }
-            };
+            let property = property_name(state)?;
 
             if state.stream.current().kind == TokenKind::LeftParen {
-                if op == &TokenKind::QuestionArrow {
+                // `(...)` closure creation
+                if let Some(placeholder) = argument_placeholder(state)? {
+                    if op == &TokenKind::QuestionArrow {
+                        state.record(error::nullsafe_closure_creation(span));
+                    }
+
+                    Expression::MethodClosureCreation(MethodClosureCreationExpression {
+                        target: Box::new(lhs),
+                        method: Box::new(property),
+                        arrow: span,
+                        placeholder,
+                    })
+                } else if op == &TokenKind::QuestionArrow {
                     let arguments = parameters::argument_list(state)?;
 
                     Expression::NullsafeMethodCall(NullsafeMethodCallExpression {
@@ -1430,37 +1576,14 @@ fn postfix(state: &mut State, lhs: Expression, op: &TokenKind) -> ParseResult<Ex
                         arguments,
                     })
                 } else {
-                    // `(...)` closure creation
-                    if state.stream.lookahead(0).kind == TokenKind::Ellipsis
-                        && state.stream.lookahead(1).kind == TokenKind::RightParen
-                    {
-                        let start = utils::skip(state, TokenKind::LeftParen)?;
-                        let ellipsis = utils::skip(state, TokenKind::Ellipsis)?;
-                        let end = utils::skip(state, TokenKind::RightParen)?;
-
-                        let placeholder = ArgumentPlaceholder {
-                            comments: state.stream.comments(),
-                            left_parenthesis: start,
-                            ellipsis,
-                            right_parenthesis: end,
-                        };
-
-                        Expression::MethodClosureCreation(MethodClosureCreationExpression {
-                            target: Box::new(lhs),
-                            method: Box::new(property),
-                            arrow: span,
-                            placeholder,
-                        })
-                    } else {
-                        let arguments = parameters::argument_list(state)?;
+                    let arguments = parameters::argument_list(state)?;
 
-                        Expression::MethodCall(MethodCallExpression {
-                            target: Box::new(lhs),
-                            method: Box::new(property),
-                            arrow: span,
-                            arguments,
-                        })
-                    }
+                    Expression::MethodCall(MethodCallExpression {
+                        target: Box::new(lhs),
+                        method: Box::new(property),
+                        arrow: span,
+                        arguments,
+                    })
                 }
             } else if op == &TokenKind::QuestionArrow {
                 Expression::NullsafePropertyFetch(NullsafePropertyFetchExpression {
@@ -1507,61 +1630,252 @@ fn postfix(state: &mut State, lhs: Expression, op: &TokenKind) -> ParseResult<Ex
     })
 }
 
+/// Report a ternary nested in the `else` branch of another without parentheses, such
+/// as `$a ? $b : $c ? $d : $e`. Chains of short ternaries, `$a ?: $b ?: $c`, are fine.
+///
+/// Check that the target of an assignment can be written to: a variable, a property, a
+/// static property, an element of one of those, or a list to destructure into.
+fn write_context(state: &mut State, target: &Expression) {
+    if let Some((target, span, length)) = unwritable(target) {
+        state.record(error::cannot_use_in_write_context(target, span, length));
+    }
+}
+
+/// What `target` is, and the part of it to highlight, if it can't be written to.
+fn unwritable(target: &Expression) -> Option<(&'static str, Span, usize)> {
+    let call = |arguments: &ArgumentList| {
+        let length = arguments.right_parenthesis.position - arguments.left_parenthesis.position;
+
+        (arguments.left_parenthesis, length + 1)
+    };
+
+    let (target, (span, length)) = match target {
+        Expression::Variable(_)
+        | Expression::PropertyFetch(_)
+        | Expression::StaticPropertyFetch(_)
+        | Expression::List(_)
+        | Expression::ShortArray(_) => return None,
+        Expression::ArrayIndex(index) => {
+            return match index.array.as_ref() {
+                Expression::ShortArray(array) => Some(("a temporary array", array.start, 1)),
+                Expression::Array(array) => Some(("a temporary array", array.array, 5)),
+                array => unwritable(array),
+            };
+        }
+        Expression::FunctionCall(call_expression) => {
+            ("a function's return value", call(&call_expression.arguments))
+        }
+        Expression::MethodCall(MethodCallExpression { arguments, .. })
+        | Expression::NullsafeMethodCall(NullsafeMethodCallExpression { arguments, .. })
+        | Expression::StaticMethodCall(StaticMethodCallExpression { arguments, .. })
+        | Expression::StaticVariableMethodCall(StaticVariableMethodCallExpression {
+            arguments,
+            ..
+        }) => ("a method's return value", call(arguments)),
+        Expression::NullsafePropertyFetch(fetch) => {
+            ("the nullsafe operator", (fetch.question_arrow, 3))
+        }
+        Expression::ConstantFetch(fetch) => (
+            "a class constant",
+            match &fetch.constant {
+                Identifier::SimpleIdentifier(constant) => (constant.span, constant.value.len()),
+                Identifier::DynamicIdentifier(constant) => (constant.start, 1),
+            },
+        ),
+        Expression::Identifier(Identifier::SimpleIdentifier(constant)) => {
+            ("a constant", (constant.span, constant.value.len()))
+        }
+        Expression::New(new) => ("a `new` expression", (new.new, 3)),
+        Expression::Literal(
+            Literal::Integer(LiteralInteger { span, value })
+            | Literal::Float(LiteralFloat { span, value })
+            | Literal::String(LiteralString { span, value }),
+        ) => ("a literal", (*span, value.len())),
+        _ => return None,
+    };
+
+    Some((target, span, length))
+}
+
+/// The nesting was left-associative before PHP 8.0, which is rarely what was meant, so
+/// it is deprecated in PHP 7.4 and an error since.
+fn nested_ternary(state: &mut State, outer: Span, outer_short: bool, r#else: &Expression) {
+    let (inner, inner_short) = match r#else {
+        Expression::Ternary(TernaryExpression { question, then, .. }) => {
+            (*question, matches!(then.as_ref(), Expression::Noop))
+        }
+        Expression::ShortTernary(ShortTernaryExpression { question_colon, .. }) => {
+            (*question_colon, true)
+        }
+        _ => return,
+    };
+
+    if outer_short && inner_short {
+        return;
+    }
+
+    if state.config.php_version >= PhpVersion::Php80 {
+        let error = error::unparenthesized_nested_ternary(outer, outer_short, inner, inner_short);
+
+        state.record(error);
+    } else if state.config.php_version >= PhpVersion::Php74 {
+        let warning = error::unparenthesized_nested_ternary_is_deprecated(
+            outer,
+            outer_short,
+            inner,
+            inner_short,
+        );
+
+        state.warn(warning);
+    }
+}
+
+/// Report and skip the `&` of `yield &$value`, which PHP never accepts, whether or not
+/// the generator yields by reference.
+fn yield_ampersand(state: &mut State) {
+    let current = state.stream.current();
+
+    if current.kind == TokenKind::Ampersand {
+        state.record(error::yield_by_reference(current.span, state.by_reference));
+        state.stream.next();
+    }
+}
+
+/// Parse the `(...)` of a first-class callable, if it is next.
+fn argument_placeholder(state: &mut State) -> ParseResult<Option<ArgumentPlaceholder>> {
+    if state.stream.current().kind != TokenKind::LeftParen
+        || state.stream.lookahead(0).kind != TokenKind::Ellipsis
+        || state.stream.lookahead(1).kind != TokenKind::RightParen
+    {
+        return Ok(None);
+    }
+
+    state.stream.forget_enclosed_comments();
+    let comments = state.stream.comments();
+    let start = utils::skip(state, TokenKind::LeftParen)?;
+    let ellipsis = utils::skip(state, TokenKind::Ellipsis)?;
+    state.require(Feature::FirstClassCallables, ellipsis, 3);
+    let end = utils::skip(state, TokenKind::RightParen)?;
+
+    Ok(Some(ArgumentPlaceholder {
+        comments,
+        left_parenthesis: start,
+        ellipsis,
+        right_parenthesis: end,
+    }))
+}
+
+/// The name of a property or method after `->` or `?->`.
+fn property_name(state: &mut State) -> ParseResult<Expression> {
+    Ok(match state.stream.current().kind {
+        TokenKind::Variable | TokenKind::Dollar | TokenKind::DollarLeftBrace => {
+            Expression::Variable(variables::dynamic_variable(state)?)
+        }
+        _ if identifiers::is_identifier_maybe_reserved(&state.stream.current().kind) => {
+            Expression::Identifier(Identifier::SimpleIdentifier(
+                identifiers::identifier_maybe_reserved(state)?,
+            ))
+        }
+        TokenKind::LeftBrace => {
+            let start = state.stream.current().span;
+            state.stream.next();
+
+            let name = create(state)?;
+
+            let end = utils::skip_right_brace(state)?;
+
+            Expression::Identifier(Identifier::DynamicIdentifier(DynamicIdentifier {
+                start,
+                expr: Box::new(name),
+                end,
+            }))
+        }
+        _ => {
+            return expected_token_err!(["`{`", "`$`", "an identifier"], state);
+        }
+    })
+}
+
 fn is_infix(t: &TokenKind) -> bool {
     matches!(
-        t,
-        TokenKind::Pow
-            | TokenKind::RightShiftEquals
-            | TokenKind::LeftShiftEquals
-            | TokenKind::CaretEquals
-            | TokenKind::AmpersandEquals
-            | TokenKind::PipeEquals
-            | TokenKind::PercentEquals
-            | TokenKind::PowEquals
-            | TokenKind::LogicalAnd
-            | TokenKind::LogicalOr
-            | TokenKind::LogicalXor
-            | TokenKind::Spaceship
-            | TokenKind::LeftShift
-            | TokenKind::RightShift
-            | TokenKind::Ampersand
-            | TokenKind::Pipe
-            | TokenKind::Caret
-            | TokenKind::Percent
-            | TokenKind::Instanceof
-            | TokenKind::Asterisk
-            | TokenKind::Slash
-            | TokenKind::Plus
-            | TokenKind::Minus
-            | TokenKind::Dot
-            | TokenKind::LessThan
-            | TokenKind::GreaterThan
-            | TokenKind::LessThanEquals
-            | TokenKind::GreaterThanEquals
-            | TokenKind::DoubleEquals
-            | TokenKind::TripleEquals
-            | TokenKind::BangEquals
-            | TokenKind::BangDoubleEquals
-            | TokenKind::AngledLeftRight
-            | TokenKind::Question
-            | TokenKind::QuestionColon
-            | TokenKind::BooleanAnd
-            | TokenKind::BooleanOr
-            | TokenKind::Equals
-            | TokenKind::PlusEquals
-            | TokenKind::MinusEquals
-            | TokenKind::DotEquals
-            | TokenKind::DoubleQuestionEquals
-            | TokenKind::AsteriskEquals
-            | TokenKind::SlashEquals
-            // | TokenKind::InclusiveRange
-            // | TokenKind::ExclusiveRange
+        t.kind_id(),
+        KindId::Pow
+            | KindId::RightShiftEquals
+            | KindId::LeftShiftEquals
+            | KindId::CaretEquals
+            | KindId::AmpersandEquals
+            | KindId::PipeEquals
+            | KindId::PercentEquals
+            | KindId::PowEquals
+            | KindId::LogicalAnd
+            | KindId::LogicalOr
+            | KindId::LogicalXor
+            | KindId::Spaceship
+            | KindId::LeftShift
+            | KindId::RightShift
+            | KindId::Ampersand
+            | KindId::Pipe
+            | KindId::Caret
+            | KindId::Percent
+            | KindId::Instanceof
+            | KindId::Asterisk
+            | KindId::Slash
+            | KindId::Plus
+            | KindId::Minus
+            | KindId::Dot
+            | KindId::LessThan
+            | KindId::GreaterThan
+            | KindId::LessThanEquals
+            | KindId::GreaterThanEquals
+            | KindId::DoubleEquals
+            | KindId::TripleEquals
+            | KindId::BangEquals
+            | KindId::BangDoubleEquals
+            | KindId::AngledLeftRight
+            | KindId::Question
+            | KindId::QuestionColon
+            | KindId::BooleanAnd
+            | KindId::BooleanOr
+            | KindId::Equals
+            | KindId::PlusEquals
+            | KindId::MinusEquals
+            | KindId::DotEquals
+            | KindId::DoubleQuestionEquals
+            | KindId::AsteriskEquals
+            | KindId::SlashEquals
+            // | KindId::InclusiveRange
+            // | KindId::ExclusiveRange
     )
 }
 
+/// Append `right` to the chain of concatenations in `left`, if there is one.
+fn concat(left: Expression, dot: Span, right: Expression) -> Expression {
+    match left {
+        Expression::ConcatMany(mut chain) => {
+            chain.parts.push(right);
+            chain.dots.push(dot);
+
+            Expression::ConcatMany(chain)
+        }
+        Expression::Concat(ConcatExpression {
+            left,
+            dot: first,
+            right: second,
+        }) => Expression::ConcatMany(ConcatManyExpression {
+            parts: vec![*left, *second, right],
+            dots: vec![first, dot],
+        }),
+        left => Expression::Concat(ConcatExpression {
+            left: Box::new(left),
+            dot,
+            right: Box::new(right),
+        }),
+    }
+}
+
 #[inline(always)]
 fn is_postfix(state: &State, t: &TokenKind) -> bool {
-    match t {
+    match t.kind_id() {
         // TokenKind::ExclusiveRange => match state.stream.peek().kind {
         //     TokenKind::Comma
         //     | TokenKind::SemiColon
@@ -1572,14 +1886,127 @@ fn is_postfix(state: &State, t: &TokenKind) -> bool {
         //     | TokenKind::As => true,
         //     _ => false,
         // },
-        TokenKind::Increment
-        | TokenKind::Decrement
-        | TokenKind::LeftParen
-        | TokenKind::LeftBracket
-        | TokenKind::Arrow
-        | TokenKind::QuestionArrow
-        | TokenKind::DoubleColon
-        | TokenKind::DoubleQuestion => true,
+        KindId::Increment
+        | KindId::Decrement
+        | KindId::LeftParen
+        | KindId::LeftBracket
+        | KindId::Arrow
+        | KindId::QuestionArrow
+        | KindId::DoubleColon
+        | KindId::DoubleQuestion => true,
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::config::ParserConfig;
+    use crate::parser::version::PhpVersion;
+
+    const CODE: &str = "<?php $x = $a ? $b : $c ? $d : $e;";
+
+    fn parse(code: &str, version: PhpVersion) -> Result<Vec<String>, Vec<String>> {
+        let config = ParserConfig::new().php_version(version);
+
+        match crate::parse_with_warnings(code, config) {
+            Ok((_, warnings)) => Ok(warnings.into_iter().map(|warning| warning.id).collect()),
+            Err(stack) => Err(stack.errors.into_iter().map(|error| error.id).collect()),
+        }
+    }
+
+    #[test]
+    fn test_nested_ternary_by_version() {
+        assert_eq!(parse(CODE, PhpVersion::Php73), Ok(vec![]));
+        assert_eq!(parse(CODE, PhpVersion::Php74), Ok(vec!["E069".to_string()]));
+        assert_eq!(
+            parse(CODE, PhpVersion::Php80),
+            Err(vec!["E068".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_short_ternary_chains_are_allowed() {
+        for version in PhpVersion::ALL {
+            assert_eq!(parse("<?php $x = $a ?: $b ?: $c;", *version), Ok(vec![]));
+        }
+    }
+
+    #[test]
+    fn test_cannot_assign_to_values() {
+        for code in [
+            "<?php foo() = 1;",
+            "<?php foo()[] = 1;",
+            "<?php Foo::BAR = 1;",
+            "<?php $a->b()[0] .= 1;",
+            "<?php $a?->b = 1;",
+            "<?php [1, 2][0] = 3;",
+            "<?php 'a' = 1;",
+        ] {
+            assert_eq!(
+                parse(code, PhpVersion::LATEST),
+                Err(vec!["E076".to_string()]),
+                "{}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn test_can_assign_to_variables_and_properties() {
+        for code in [
+            "<?php foo()->prop = 1;",
+            "<?php (new Foo)->prop = 1;",
+            "<?php static::$a[0] = 1;",
+            "<?php $a[0][] ??= 1;",
+            "<?php [$a, [$b]] = $c;",
+            "<?php list($a, $b) = $c;",
+            "<?php $$a = 1;",
+        ] {
+            assert_eq!(parse(code, PhpVersion::LATEST), Ok(vec![]), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_yield_in_finally() {
+        let warned = Ok(vec!["E080".to_string(), "E080".to_string()]);
+
+        assert_eq!(
+            parse(
+                "<?php function g() { try {} finally { yield 1; yield from g(); } }",
+                PhpVersion::LATEST
+            ),
+            warned
+        );
+        assert_eq!(
+            parse(
+                "<?php function g() { try {} finally { $f = function () { yield 1; }; } yield 2; }",
+                PhpVersion::LATEST
+            ),
+            Ok(vec![])
+        );
+    }
+
+    #[test]
+    fn test_yield_by_reference() {
+        let note = |code: &str| {
+            let stack = crate::parse(code).unwrap_err();
+            assert_eq!(stack.errors[0].id, "E081", "{}", code);
+
+            stack.errors[0].note.clone().unwrap()
+        };
+
+        assert!(note("<?php function g() { yield &$x; }").contains("`function &`"));
+        assert!(note("<?php function &g() { yield $k => &$x; }").contains("already yield"));
+        assert_eq!(
+            parse("<?php function &g() { yield $x; }", PhpVersion::LATEST),
+            Ok(vec![])
+        );
+        assert_eq!(
+            parse(
+                "<?php function g() { yield $a ? $b : $c => $v; }",
+                PhpVersion::LATEST
+            ),
+            Ok(vec![])
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use crate::lexer::token::Span;
+use crate::lexer::token::TokenKind;
+use crate::parser;
+use crate::parser::ast::Expression;
+use crate::parser::ast::Statement;
+use crate::parser::error::ParseResult;
+use crate::parser::expressions;
+use crate::parser::internal::blocks;
+use crate::parser::internal::utils;
+use crate::parser::state::State;
+
+pub use crate::parser::internal::precedences::Precedence;
+
+/// The function given to [`ParserConfig::statement_extension`].
+///
+/// [`ParserConfig::statement_extension`]: crate::parser::config::ParserConfig::statement_extension
+#[derive(Clone)]
+pub struct StatementExtension(pub(crate) Arc<Extension>);
+
+type Extension = dyn Fn(&mut State) -> Option<ParseResult<Statement>> + Send + Sync;
+
+impl Debug for StatementExtension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StatementExtension")
+    }
+}
+
+/// Extensions are compared by identity, as closures can't be compared.
+impl PartialEq for StatementExtension {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for StatementExtension {}
+
+/// Give the statement at the current token to the extension, if there is one.
+pub(crate) fn statement(state: &mut State) -> Option<ParseResult<Statement>> {
+    let extension = state.config.statement_extension.clone()?;
+
+    (extension.0)(state)
+}
+
+/// Parse an expression, e.g. the condition of a custom statement.
+pub fn parse_expression(state: &mut State) -> ParseResult<Expression> {
+    expressions::create(state)
+}
+
+/// Parse an expression made of operators that bind tighter than `precedence`, e.g.
+/// `$a + $b` but not `$a = $b` for [`Precedence::Assignment`].
+pub fn parse_expression_in(state: &mut State, precedence: Precedence) -> ParseResult<Expression> {
+    expressions::for_precedence(state, precedence)
+}
+
+/// Parse a single statement, which may itself be a custom statement.
+pub fn parse_statement(state: &mut State) -> ParseResult<Statement> {
+    parser::statement(state)
+}
+
+/// Parse a `{ ... }` block of statements, returning the spans of its braces.
+pub fn parse_braced_statements(state: &mut State) -> ParseResult<(Span, Vec<Statement>, Span)> {
+    utils::braced(state, &|state: &mut State| {
+        blocks::multiple_statements_until(state, &TokenKind::RightBrace)
+    })
+}
+
+/// Consume a token of the given kind, returning its span, or fail with an
+/// "unexpected token" error.
+pub fn expect(state: &mut State, kind: TokenKind) -> ParseResult<Span> {
+    utils::skip(state, kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::CustomStatement;
+    use crate::parser::config::ParserConfig;
+
+    /// A toy `unless (condition) { ... }` statement.
+    fn unless(state: &mut State) -> Option<ParseResult<Statement>> {
+        let current = state.stream.current();
+        if current.kind != TokenKind::Identifier || current.value != b"unless" {
+            return None;
+        }
+
+        Some(parse_unless(state))
+    }
+
+    fn parse_unless(state: &mut State) -> ParseResult<Statement> {
+        let span = state.stream.current().span;
+        state.stream.next();
+
+        expect(state, TokenKind::LeftParen)?;
+        let condition = parse_expression(state)?;
+        expect(state, TokenKind::RightParen)?;
+        let (_, statements, _) = parse_braced_statements(state)?;
+
+        Ok(Statement::Custom(CustomStatement {
+            tag: "unless".into(),
+            span,
+            tokens: vec![],
+            expressions: vec![condition],
+            statements,
+        }))
+    }
+
+    fn parse(code: &str) -> ParseResult<Vec<Statement>> {
+        let config = ParserConfig::new().statement_extension(unless);
+
+        crate::parse_with_config(code, config).map_err(|mut stack| stack.errors.remove(0))
+    }
+
+    fn custom(statement: &Statement) -> &CustomStatement {
+        match statement {
+            Statement::Custom(statement) => statement,
+            statement => panic!("expected a custom statement, found {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_custom_statement() {
+        let program =
+            parse("<?php unless ($a > 1) { echo 'small'; unless ($b) { exit; } }").unwrap();
+
+        let unless = custom(&program[1]);
+        assert_eq!(unless.tag, b"unless");
+        assert_eq!(unless.span.column, 7);
+        assert!(matches!(
+            unless.expressions[0],
+            Expression::ComparisonOperation(_)
+        ));
+        assert!(matches!(unless.statements[0], Statement::Echo(_)));
+        assert_eq!(custom(&unless.statements[1]).tag, b"unless");
+    }
+
+    #[test]
+    fn test_custom_statement_in_function_body() {
+        let program = parse("<?php function foo() { unless (true) { return; } }").unwrap();
+
+        let Statement::Function(function) = &program[1] else {
+            panic!("expected a function");
+        };
+        assert_eq!(custom(&function.body.statements[0]).tag, b"unless");
+    }
+
+    #[test]
+    fn test_other_statements_fall_through() {
+        let code = "<?php if ($unless) { echo unless(); }";
+
+        assert_eq!(parse(code).unwrap(), crate::parse(code).unwrap());
+    }
+
+    #[test]
+    fn test_custom_statement_errors() {
+        let error = parse("<?php unless ($a) echo 1;").unwrap_err();
+
+        assert_eq!(error.id, "E005");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::lexer::token::Span;
 use crate::lexer::token::TokenKind;
 use crate::parser::ast::Expression;
 use crate::parser::ast::ListEntry;
@@ -7,11 +8,16 @@ use crate::parser::error::ParseResult;
 use crate::parser::expressions;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::version::Feature;
 
 pub fn list_expression(state: &mut State) -> ParseResult<Expression> {
+    let list = utils::skip(state, TokenKind::List)?;
+    let start = utils::skip_left_parenthesis(state)?;
+    let mut trailing_comma = None;
+
     Ok(Expression::List(ListExpression {
-        list: utils::skip(state, TokenKind::List)?,
-        start: utils::skip_left_parenthesis(state)?,
+        list,
+        start,
         items: {
             let mut items = Vec::new();
             let mut has_at_least_one_key = false;
@@ -19,6 +25,7 @@ pub fn list_expression(state: &mut State) -> ParseResult<Expression> {
             let mut current = state.stream.current();
             while current.kind != TokenKind::RightParen {
                 if current.kind == TokenKind::Comma {
+                    trailing_comma = Some(current.span);
                     state.stream.next();
 
                     items.push(ListEntry::Skipped);
@@ -34,22 +41,33 @@ pub fn list_expression(state: &mut State) -> ParseResult<Expression> {
                     state.record(error::illegal_spread_operator_usage(current.span));
                 }
 
-                if current.kind == TokenKind::Ampersand {
+                let mut ampersand = if current.kind == TokenKind::Ampersand {
                     state.stream.next();
 
-                    state.record(error::cannot_assign_reference_to_non_referencable_value(
-                        current.span,
-                    ));
-                }
+                    Some(current.span)
+                } else {
+                    None
+                };
 
                 let mut value = expressions::create(state)?;
+                trailing_comma = None;
                 current = state.stream.current();
                 if current.kind == TokenKind::DoubleArrow {
+                    // The key can't be taken by reference, only the value.
+                    if let Some(ampersand) = ampersand {
+                        state.record(error::cannot_assign_reference_to_non_referencable_value(
+                            ampersand,
+                        ));
+                    }
+
                     if !has_at_least_one_key && !items.is_empty() {
                         state.record(error::mixing_keyed_and_unkeyed_list_entries(current.span));
                     }
 
                     let double_arrow = current.span;
+                    if !has_at_least_one_key {
+                        state.require(Feature::KeyedListAssignments, double_arrow, 2);
+                    }
 
                     state.stream.next();
 
@@ -60,23 +78,35 @@ pub fn list_expression(state: &mut State) -> ParseResult<Expression> {
                         state.record(error::illegal_spread_operator_usage(current.span));
                     }
 
-                    if current.kind == TokenKind::Ampersand {
+                    ampersand = if current.kind == TokenKind::Ampersand {
                         state.stream.next();
 
-                        state.record(error::cannot_assign_reference_to_non_referencable_value(
-                            current.span,
-                        ));
-                    }
+                        Some(current.span)
+                    } else {
+                        None
+                    };
 
                     let mut key = expressions::create(state)?;
                     current = state.stream.current();
 
                     std::mem::swap(&mut key, &mut value);
 
-                    items.push(ListEntry::KeyValue {
-                        key,
-                        double_arrow,
-                        value,
+                    items.push(match ampersand {
+                        Some(ampersand) => {
+                            reference(state, ampersand, &value);
+
+                            ListEntry::ReferencedKeyValue {
+                                key,
+                                double_arrow,
+                                ampersand,
+                                value,
+                            }
+                        }
+                        None => ListEntry::KeyValue {
+                            key,
+                            double_arrow,
+                            value,
+                        },
                     });
 
                     has_at_least_one_key = true;
@@ -85,10 +115,18 @@ pub fn list_expression(state: &mut State) -> ParseResult<Expression> {
                         state.record(error::mixing_keyed_and_unkeyed_list_entries(current.span));
                     }
 
-                    items.push(ListEntry::Value { value });
+                    items.push(match ampersand {
+                        Some(ampersand) => {
+                            reference(state, ampersand, &value);
+
+                            ListEntry::ReferencedValue { ampersand, value }
+                        }
+                        None => ListEntry::Value { value },
+                    });
                 }
 
                 if current.kind == TokenKind::Comma {
+                    trailing_comma = Some(current.span);
                     state.stream.next();
                     current = state.stream.current();
                 } else {
@@ -97,11 +135,13 @@ pub fn list_expression(state: &mut State) -> ParseResult<Expression> {
             }
 
             if current.kind == TokenKind::Comma {
+                trailing_comma = Some(current.span);
                 state.stream.next();
             }
 
             items
         },
+        trailing_comma,
         end: utils::skip_right_parenthesis(state)?,
     }))
 }
@@ -134,6 +174,66 @@ pub fn array_expression(state: &mut State) -> ParseResult<Expression> {
     }))
 }
 
+/// Check the target of an assignment, which destructures the value when it is a short
+/// array, such as `[$a, $b] = $c`.
+pub fn destructuring(state: &mut State, target: &Expression) {
+    if let Expression::ShortArray(array) = target {
+        state.require(Feature::ShortListAssignments, array.start, 1);
+    }
+
+    pattern(state, target);
+}
+
+/// Check the items of the short arrays in a destructuring pattern, which can't be
+/// spread, as in `[...$a] = $b`, nor be patterns taken by reference, as in
+/// `[&[$a]] = $b`. The items of `list()` are checked while it is parsed.
+fn pattern(state: &mut State, target: &Expression) {
+    match target {
+        Expression::ShortArray(array) => {
+            for item in array.items.iter() {
+                match item {
+                    ArrayItem::SpreadValue { ellipsis, .. } => {
+                        state.record(error::illegal_spread_operator_usage(*ellipsis));
+                    }
+                    ArrayItem::ReferencedValue { ampersand, value }
+                    | ArrayItem::ReferencedKeyValue {
+                        ampersand, value, ..
+                    } => {
+                        reference(state, *ampersand, value);
+                        pattern(state, value);
+                    }
+                    ArrayItem::Value { value } | ArrayItem::KeyValue { value, .. } => {
+                        pattern(state, value)
+                    }
+                    ArrayItem::Skipped => {}
+                }
+            }
+        }
+        Expression::List(list) => {
+            for entry in list.items.iter() {
+                match entry {
+                    ListEntry::Value { value }
+                    | ListEntry::ReferencedValue { value, .. }
+                    | ListEntry::KeyValue { value, .. }
+                    | ListEntry::ReferencedKeyValue { value, .. } => pattern(state, value),
+                    ListEntry::Skipped => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check a `value` of a destructuring pattern that is taken by reference with the `&`
+/// at `ampersand`, which has to be a variable rather than a nested pattern.
+fn reference(state: &mut State, ampersand: Span, value: &Expression) {
+    state.require(Feature::ReferenceAssignmentsInLists, ampersand, 1);
+
+    if matches!(value, Expression::ShortArray(_) | Expression::List(_)) {
+        state.record(error::pattern_by_reference(ampersand));
+    }
+}
+
 fn array_pair(state: &mut State) -> ParseResult<ArrayItem> {
     let mut current = state.stream.current();
     let ellipsis = if current.kind == TokenKind::Ellipsis {
@@ -157,6 +257,8 @@ fn array_pair(state: &mut State) -> ParseResult<ArrayItem> {
     let mut value = expressions::create(state)?;
 
     if let Some(ellipsis) = ellipsis {
+        state.require(Feature::SpreadInArrays, ellipsis, 3);
+
         if let Some(ampersand) = ampersand {
             state.record(error::cannot_assign_reference_to_non_referencable_value(
                 ampersand,
@@ -212,3 +314,98 @@ fn array_pair(state: &mut State) -> ParseResult<ArrayItem> {
 
     Ok(ArrayItem::Value { value })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::ArraySyntax;
+    use crate::parser::ast::Expression;
+    use crate::parser::ast::ListEntry;
+    use crate::parser::ast::Statement;
+
+    fn expression(code: &str) -> Expression {
+        let program = crate::parse(&format!("<?php {};", code)).unwrap();
+
+        match program.into_iter().nth(1) {
+            Some(Statement::Expression(statement)) => statement.expression,
+            statement => panic!("expected an expression, found {:?}", statement),
+        }
+    }
+
+    fn flags(code: &str) -> (ArraySyntax, bool, bool) {
+        match expression(code) {
+            Expression::ShortArray(array) => (
+                array.array_syntax(),
+                array.is_multiline(),
+                array.has_trailing_comma(),
+            ),
+            Expression::Array(array) => (
+                array.array_syntax(),
+                array.is_multiline(),
+                array.has_trailing_comma(),
+            ),
+            Expression::AssignmentOperation(assignment) => match assignment.left() {
+                Expression::List(list) => (
+                    list.array_syntax(),
+                    list.is_multiline(),
+                    list.has_trailing_comma(),
+                ),
+                left => panic!("expected a list, found {:?}", left),
+            },
+            expression => panic!("expected an array, found {:?}", expression),
+        }
+    }
+
+    #[test]
+    fn test_array_formatting() {
+        assert_eq!(flags("[1, 2]"), (ArraySyntax::Short, false, false));
+        assert_eq!(flags("[\n  1,\n  2,\n]"), (ArraySyntax::Short, true, true));
+        assert_eq!(flags("array(1, 2,)"), (ArraySyntax::Long, false, true));
+        assert_eq!(
+            flags("array(\n  1,\n  2\n)"),
+            (ArraySyntax::Long, true, false)
+        );
+        assert_eq!(flags("[]"), (ArraySyntax::Short, false, false));
+    }
+
+    #[test]
+    fn test_list_formatting() {
+        assert_eq!(
+            flags("list($a, $b) = $c"),
+            (ArraySyntax::Long, false, false)
+        );
+        assert_eq!(
+            flags("list(\n  $a,\n  $b,\n) = $c"),
+            (ArraySyntax::Long, true, true)
+        );
+        assert_eq!(flags("list(, $b) = $c"), (ArraySyntax::Long, false, false));
+    }
+
+    fn error(code: &str) -> String {
+        crate::parse(&format!("<?php {};", code))
+            .unwrap_err()
+            .errors[0]
+            .id
+            .clone()
+    }
+
+    #[test]
+    fn test_patterns_by_reference() {
+        let Expression::AssignmentOperation(assignment) = expression("list('a' => &$a) = $b")
+        else {
+            panic!("expected an assignment");
+        };
+        let Expression::List(list) = assignment.left() else {
+            panic!("expected a list, found {:?}", assignment.left());
+        };
+        assert!(matches!(
+            list.items[..],
+            [ListEntry::ReferencedKeyValue { .. }]
+        ));
+
+        assert_eq!(error("[&[$a]] = $b"), "E095");
+        assert_eq!(error("list(1 => &list($a)) = $b"), "E095");
+        assert_eq!(error("foreach ($a as &[$b]) {}"), "E095");
+        assert_eq!(error("&[$a] = $b"), "E003");
+        assert_eq!(error("foreach ($a as &$b => $c) {}"), "E042");
+    }
+}
@@ -1,6 +1,9 @@
+use crate::lexer::token::Token;
 use crate::lexer::token::TokenKind;
 use crate::parser::ast::attributes::Attribute;
 use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::error;
+use crate::parser::error::ParseError;
 use crate::parser::error::ParseResult;
 use crate::parser::internal::identifiers;
 use crate::parser::internal::parameters;
@@ -15,6 +18,24 @@ pub fn gather_attributes(state: &mut State) -> ParseResult<bool> {
     let start = state.stream.current().span;
     let mut members = vec![];
 
+    let unclosed = match match_delimiters(state) {
+        GroupEnd::Closed => false,
+        GroupEnd::Mismatched(error, end) => {
+            // Skip the whole group, whose contents can't be trusted.
+            state.record(error);
+            while !state.stream.is_eof() && state.stream.current().span.position <= end {
+                state.stream.next();
+            }
+
+            return gather_attributes(state).map(|_| true);
+        }
+        GroupEnd::Unclosed(error) => {
+            state.record(error);
+
+            true
+        }
+    };
+
     state.stream.next();
 
     loop {
@@ -47,6 +68,16 @@ pub fn gather_attributes(state: &mut State) -> ParseResult<bool> {
         break;
     }
 
+    if unclosed {
+        // Keep the attributes that could be parsed out of the AST, and carry on with
+        // the declaration that follows them.
+        if state.stream.current().kind == TokenKind::RightBracket {
+            state.stream.next();
+        }
+
+        return gather_attributes(state).map(|_| true);
+    }
+
     let end = utils::skip_right_bracket(state)?;
 
     state.attribute(AttributeGroup {
@@ -58,3 +89,153 @@ pub fn gather_attributes(state: &mut State) -> ParseResult<bool> {
     // recursive, looking for multiple attribute brackets after each other.
     gather_attributes(state).map(|_| true)
 }
+
+enum GroupEnd {
+    Closed,
+    /// A delimiter is closed by the wrong one, and the group ends at the `]` at the
+    /// given position.
+    Mismatched(ParseError, usize),
+    /// The group doesn't end within the configured number of tokens.
+    Unclosed(ParseError),
+}
+
+/// Match the delimiters of the attribute group that starts at the current `#[`, to
+/// report a missing or mismatched delimiter where it is rather than at whatever
+/// follows the group.
+fn match_delimiters(state: &State) -> GroupEnd {
+    let tokens = state.stream.upcoming();
+    let limit = state.config.attribute_token_limit;
+
+    let mut open: Vec<&Token> = vec![];
+    let mut mismatch = None;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if index >= limit || token.kind == TokenKind::Eof {
+            let limit = (token.kind != TokenKind::Eof).then_some(limit);
+            let innermost = open
+                .last()
+                .filter(|opener| opener.kind != TokenKind::Attribute);
+            let error = mismatch.unwrap_or_else(|| {
+                error::unclosed_attribute_group(tokens[0].span, innermost.copied(), limit)
+            });
+
+            return GroupEnd::Unclosed(error);
+        }
+
+        match token.kind {
+            TokenKind::Attribute
+            | TokenKind::LeftBracket
+            | TokenKind::LeftParen
+            | TokenKind::LeftBrace
+            | TokenKind::DollarLeftBrace
+            | TokenKind::CurlyOpen => open.push(token),
+            TokenKind::RightBracket | TokenKind::RightParen | TokenKind::RightBrace => {
+                let matching = open.iter().rposition(|opener| closes(opener, token));
+
+                if matching != Some(open.len() - 1) && mismatch.is_none() {
+                    mismatch = Some(error::mismatched_delimiter_in_attribute(
+                        open[open.len() - 1],
+                        token,
+                    ));
+                }
+
+                // A closing delimiter without an opening one is ignored.
+                if let Some(matching) = matching {
+                    open.truncate(matching);
+                }
+
+                if open.is_empty() {
+                    return match mismatch {
+                        Some(error) => GroupEnd::Mismatched(error, token.span.position),
+                        None => GroupEnd::Closed,
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+
+    unreachable!("the stream ends with an end of file")
+}
+
+fn closes(opener: &Token, closer: &Token) -> bool {
+    match closer.kind {
+        TokenKind::RightBracket => {
+            matches!(opener.kind, TokenKind::Attribute | TokenKind::LeftBracket)
+        }
+        TokenKind::RightParen => opener.kind == TokenKind::LeftParen,
+        _ => matches!(
+            opener.kind,
+            TokenKind::LeftBrace | TokenKind::DollarLeftBrace | TokenKind::CurlyOpen
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::Statement;
+    use crate::parser::config::ParserConfig;
+    use crate::parser::error::ParseErrorStack;
+
+    fn parse(code: &str, config: ParserConfig) -> ParseErrorStack {
+        crate::parse_with_config(code, config).unwrap_err()
+    }
+
+    fn classes(stack: &ParseErrorStack) -> Vec<String> {
+        stack
+            .partial
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Class(class) => Some(class.name.value.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mismatched_delimiter() {
+        let stack = parse("<?php\n#[Foo(bar: [1, 2)]\nclass A {}", ParserConfig::new());
+
+        assert_eq!(stack.errors.len(), 1);
+        assert_eq!(stack.errors[0].id, "E087");
+        // The error is at the `[` that isn't closed.
+        assert_eq!(stack.errors[0].span.line, 2);
+        assert_eq!(stack.errors[0].span.column, 12);
+        assert_eq!(classes(&stack), vec!["A"]);
+    }
+
+    #[test]
+    fn test_unclosed_group() {
+        let code = "<?php\n#[Foo(1)\nclass A { public function a() {} }\nclass B {}";
+
+        for (config, id) in [
+            (ParserConfig::new(), "E088"),
+            (ParserConfig::new().attribute_token_limit(4), "E105"),
+        ] {
+            let stack = parse(code, config);
+
+            assert_eq!(stack.errors.len(), 1);
+            assert_eq!(stack.errors[0].id, id);
+            // The error is at the `#[`.
+            assert_eq!(stack.errors[0].span.line, 2);
+            assert_eq!(stack.errors[0].span.column, 1);
+            assert_eq!(classes(&stack), vec!["A", "B"]);
+        }
+    }
+
+    #[test]
+    fn test_token_limit() {
+        let code = "<?php\n#[Foo([1, 2, 3])]\nclass A {}";
+
+        assert!(
+            crate::parse_with_config(code, ParserConfig::new().attribute_token_limit(12)).is_ok()
+        );
+
+        let stack = parse(code, ParserConfig::new().attribute_token_limit(11));
+        assert_eq!(
+            stack.errors[0].message,
+            "attribute group is not closed within 11 tokens"
+        );
+        assert_eq!(classes(&stack), vec!["A"]);
+    }
+}
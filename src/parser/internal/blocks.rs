@@ -1,8 +1,11 @@
 use crate::lexer::token::OpenTagKind;
+use crate::lexer::token::Span;
 use crate::lexer::token::TokenKind;
 use crate::parser;
 use crate::parser::ast::BlockStatement;
+use crate::parser::ast::Ending;
 use crate::parser::ast::Statement;
+use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
@@ -41,6 +44,34 @@ pub fn multiple_statements_until(
     Ok(statements)
 }
 
+/// Parse the statements of a function, method, or closure body up to its closing `}`.
+///
+/// When the parser is configured to skip bodies, the tokens up to the matching `}` are
+/// passed over without being parsed and no statements are returned, and the caller
+/// marks the body as skipped. Braces inside of strings and doc strings are part of
+/// string tokens, so they can't throw the count off.
+pub fn function_statements(state: &mut State) -> ParseResult<Vec<Statement>> {
+    if !state.config.skip_bodies {
+        return multiple_statements_until(state, &TokenKind::RightBrace);
+    }
+
+    let mut depth = 0;
+    loop {
+        match state.stream.current().kind {
+            TokenKind::LeftBrace | TokenKind::DollarLeftBrace => depth += 1,
+            TokenKind::RightBrace if depth == 0 => break,
+            TokenKind::RightBrace => depth -= 1,
+            // The missing `}` is reported by the caller.
+            TokenKind::Eof => break,
+            _ => {}
+        }
+
+        state.stream.next();
+    }
+
+    Ok(vec![])
+}
+
 pub fn multiple_statements_until_any(
     state: &mut State,
     until: &[TokenKind],
@@ -62,3 +93,116 @@ pub fn multiple_statements_until_any(
 
     Ok(statements)
 }
+
+/// Parses the body of a construct that uses brace syntax, i.e. a single statement or
+/// a `{ ... }` block.
+pub fn body_statement(state: &mut State, end: &TokenKind) -> ParseResult<Statement> {
+    if state.stream.current().kind != TokenKind::LeftBrace {
+        return parser::statement(state);
+    }
+
+    let (left_brace, statements, right_brace) = braced_statements(state, end)?;
+
+    Ok(Statement::Block(BlockStatement {
+        left_brace,
+        statements,
+        right_brace,
+    }))
+}
+
+/// Parses a `{ ... }` body of a construct.
+///
+/// A body closed with `end`, the closing keyword of the construct's alternative
+/// syntax, is reported and treated as closed there.
+pub fn braced_statements(
+    state: &mut State,
+    end: &TokenKind,
+) -> ParseResult<(Span, Vec<Statement>, Span)> {
+    let left_brace = utils::skip_left_brace(state)?;
+    let statements = multiple_statements_until_any(state, &[TokenKind::RightBrace, end.clone()])?;
+
+    let current = state.stream.current();
+    if &current.kind != end {
+        return Ok((left_brace, statements, utils::skip_right_brace(state)?));
+    }
+
+    let error = error::mismatched_body_closer(left_brace, &TokenKind::RightBrace, current);
+
+    state.record(error);
+    state.stream.next();
+
+    if matches!(
+        state.stream.current().kind,
+        TokenKind::SemiColon | TokenKind::CloseTag
+    ) {
+        state.stream.next();
+    }
+
+    Ok((left_brace, statements, current.span))
+}
+
+/// The keywords that close a body that uses alternative syntax.
+pub const ALTERNATIVE_ENDS: [TokenKind; 6] = [
+    TokenKind::EndIf,
+    TokenKind::EndWhile,
+    TokenKind::EndFor,
+    TokenKind::EndForeach,
+    TokenKind::EndSwitch,
+    TokenKind::EndDeclare,
+];
+
+/// Parses the statements of a body that uses alternative syntax, up to any of `until`,
+/// or a `}` or the closing keyword of another construct that mistakenly closes the body.
+pub fn alternative_statements(
+    state: &mut State,
+    until: &[TokenKind],
+) -> ParseResult<Vec<Statement>> {
+    let mut stop = until.to_vec();
+    stop.push(TokenKind::RightBrace);
+    stop.extend(ALTERNATIVE_ENDS);
+
+    // `else` and `elseif` are followed by more of the same body, which `end` closes.
+    let end = until.iter().find(|kind| ALTERNATIVE_ENDS.contains(kind));
+    if let Some(end) = end {
+        state.alternative_ends.push(end.clone());
+    }
+    let statements = multiple_statements_until_any(state, &stop);
+    if end.is_some() {
+        state.alternative_ends.pop();
+    }
+
+    statements
+}
+
+/// Skips the closing keyword `end` of a body that uses alternative syntax, along with
+/// the ending that follows it.
+///
+/// A body closed with `}` or with the closing keyword of another construct instead is
+/// reported and treated as closed there. The `}`, or a keyword that closes an enclosing
+/// body, is left in place, as it most likely belongs to the enclosing construct. Any
+/// other keyword is taken as a misspelling of `end`.
+pub fn skip_alternative_end(
+    state: &mut State,
+    colon: Span,
+    end: &TokenKind,
+) -> ParseResult<(Span, Ending)> {
+    let current = state.stream.current();
+
+    if current.kind == TokenKind::RightBrace
+        || (&current.kind != end && ALTERNATIVE_ENDS.contains(&current.kind))
+    {
+        let error = error::mismatched_body_closer(colon, end, current);
+
+        state.record(error);
+
+        if current.kind == TokenKind::RightBrace || state.alternative_ends.contains(&current.kind) {
+            return Ok((current.span, Ending::Semicolon(current.span)));
+        }
+
+        state.stream.next();
+
+        return Ok((current.span, utils::skip_ending(state)?));
+    }
+
+    Ok((utils::skip(state, end.clone())?, utils::skip_ending(state)?))
+}
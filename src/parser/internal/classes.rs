@@ -9,6 +9,7 @@ use crate::parser::ast::classes::ClassImplements;
 use crate::parser::ast::classes::ClassMember;
 use crate::parser::ast::classes::ClassStatement;
 use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::modifiers::ClassModifier;
 use crate::parser::ast::Statement;
 use crate::parser::ast::{Expression, NewExpression};
 use crate::parser::error;
@@ -25,11 +26,20 @@ use crate::parser::internal::properties;
 use crate::parser::internal::traits;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::symbols;
+use crate::parser::symbols::SymbolKind;
+use crate::parser::version::Feature;
 
 pub fn parse(state: &mut State) -> ParseResult<Statement> {
     let attributes = state.get_attributes();
 
     let modifiers = modifiers::class_group(modifiers::collect(state)?)?;
+    for modifier in &modifiers.modifiers {
+        if let ClassModifier::Readonly(span) = modifier {
+            state.require(Feature::ReadonlyClasses, *span, 8);
+        }
+    }
+
     let class = utils::skip(state, TokenKind::Class)?;
     let name = identifiers::type_identifier(state)?;
     let current = state.stream.current();
@@ -67,11 +77,13 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
     };
 
     let has_abstract = modifiers.has_abstract();
+    symbols::enter(state, SymbolKind::Class, &name.value);
     let body = ClassBody {
         left_brace: utils::skip_left_brace(state)?,
         members: {
             let mut members = Vec::new();
-            while state.stream.current().kind != TokenKind::RightBrace {
+            while state.stream.current().kind != TokenKind::RightBrace && !state.stream.is_eof() {
+                state.check_cancelled()?;
                 members.push(member(state, has_abstract, &name)?);
             }
 
@@ -79,8 +91,19 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
         },
         right_brace: utils::skip_right_brace(state)?,
     };
+    symbols::exit(state);
+
+    let start = symbols::start(
+        &attributes,
+        modifiers
+            .modifiers
+            .first()
+            .map_or(class, |modifier| modifier.span()),
+    );
+    symbols::emit(state, SymbolKind::Class, &name.value, name.span, start);
 
     Ok(Statement::Class(ClassStatement {
+        doc: state.stream.doc_comment(start.position),
         class,
         name,
         modifiers,
@@ -139,17 +162,20 @@ pub fn parse_anonymous(state: &mut State, span: Option<Span>) -> ParseResult<Exp
         None
     };
 
+    symbols::enter(state, SymbolKind::Class, &"class@anonymous".into());
     let body = AnonymousClassBody {
         left_brace: utils::skip_left_brace(state)?,
         members: {
             let mut members = Vec::new();
-            while state.stream.current().kind != TokenKind::RightBrace {
+            while state.stream.current().kind != TokenKind::RightBrace && !state.stream.is_eof() {
+                state.check_cancelled()?;
                 members.push(anonymous_member(state)?);
             }
             members
         },
         right_brace: utils::skip_right_brace(state)?,
     };
+    symbols::exit(state);
 
     Ok(Expression::New(NewExpression {
         target: Box::new(Expression::AnonymousClass(AnonymousClassExpression {
@@ -169,6 +195,8 @@ fn member(
     has_abstract: bool,
     name: &SimpleIdentifier,
 ) -> ParseResult<ClassMember> {
+    state.stream.forget_enclosed_comments();
+
     let has_attributes = attributes::gather_attributes(state)?;
 
     if !has_attributes && state.stream.current().kind == TokenKind::Use {
@@ -232,6 +260,8 @@ fn member(
 }
 
 fn anonymous_member(state: &mut State) -> ParseResult<AnonymousClassMember> {
+    state.stream.forget_enclosed_comments();
+
     let has_attributes = attributes::gather_attributes(state)?;
 
     if !has_attributes && state.stream.current().kind == TokenKind::Use {
@@ -2,12 +2,18 @@ use crate::lexer::token::TokenKind;
 use crate::parser::ast::constant::ClassishConstant;
 use crate::parser::ast::constant::ConstantEntry;
 use crate::parser::ast::constant::ConstantStatement;
+use crate::parser::ast::modifiers::ConstantModifier;
 use crate::parser::ast::modifiers::ConstantModifierGroup;
+use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::expressions;
+use crate::parser::internal::data_type;
 use crate::parser::internal::identifiers;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::symbols;
+use crate::parser::symbols::SymbolKind;
+use crate::parser::version::Feature;
 
 pub fn parse(state: &mut State) -> ParseResult<ConstantStatement> {
     let comments = state.stream.comments();
@@ -35,8 +41,19 @@ pub fn parse(state: &mut State) -> ParseResult<ConstantStatement> {
 
     let end = utils::skip_semicolon(state)?;
 
+    for entry in &entries {
+        symbols::emit(
+            state,
+            SymbolKind::Constant,
+            &entry.name.value,
+            entry.name.span,
+            start,
+        );
+    }
+
     Ok(ConstantStatement {
         comments,
+        doc: state.stream.doc_comment(start.position),
         r#const: start,
         entries,
         semicolon: end,
@@ -52,6 +69,34 @@ pub fn classish(
     let comments = state.stream.comments();
     let start = utils::skip(state, TokenKind::Const)?;
 
+    for modifier in &modifiers.modifiers {
+        let (feature, length) = match modifier {
+            ConstantModifier::Final(_) => (Feature::FinalClassConstants, 5),
+            ConstantModifier::Public(_) => (Feature::ClassConstantVisibility, 6),
+            ConstantModifier::Protected(_) => (Feature::ClassConstantVisibility, 9),
+            ConstantModifier::Private(_) => (Feature::ClassConstantVisibility, 7),
+        };
+
+        state.require(feature, modifier.span(), length);
+    }
+
+    // `const int FOO = 1;`, where the name is followed by `=` once the type is parsed.
+    let data_type = if matches!(
+        state.stream.peek().kind,
+        TokenKind::Equals | TokenKind::SemiColon
+    ) {
+        None
+    } else {
+        let data_type = data_type::data_type(state)?;
+        state.require(
+            Feature::TypedClassConstants,
+            data_type.first_span(),
+            data_type.to_string().len(),
+        );
+
+        Some(data_type)
+    };
+
     let mut entries = vec![];
 
     loop {
@@ -74,11 +119,37 @@ pub fn classish(
 
     let end = utils::skip_semicolon(state)?;
 
+    if let Some(data_type) = &data_type {
+        if data_type.includes_callable() || data_type.is_bottom() || data_type.includes_static() {
+            state.record(error::forbidden_type_used_in_constant(
+                &entries[0].name,
+                data_type.clone(),
+            ));
+        }
+    }
+
+    let first = modifiers
+        .modifiers
+        .first()
+        .map_or(start, |modifier| modifier.span());
+    let first = symbols::start(&attributes, first);
+    for entry in &entries {
+        symbols::emit(
+            state,
+            SymbolKind::Constant,
+            &entry.name.value,
+            entry.name.span,
+            first,
+        );
+    }
+
     Ok(ClassishConstant {
         comments,
+        doc: state.stream.doc_comment(first.position),
         attributes,
         modifiers,
         r#const: start,
+        data_type,
         entries,
         semicolon: end,
     })
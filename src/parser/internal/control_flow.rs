@@ -1,21 +1,27 @@
 use crate::expected_token_err;
 
+use crate::lexer::token::Span;
 use crate::lexer::token::TokenKind;
 use crate::parser;
 use crate::parser::ast::ShortMatchExpression;
 use crate::parser::ast::MatchArmBody;
+use crate::parser::ast::BodySyntax;
 use crate::parser::ast::control_flow::IfStatement;
 use crate::parser::ast::control_flow::IfStatementBody;
 use crate::parser::ast::control_flow::IfStatementElse;
 use crate::parser::ast::control_flow::IfStatementElseBlock;
 use crate::parser::ast::control_flow::IfStatementElseIf;
 use crate::parser::ast::control_flow::IfStatementElseIfBlock;
+use crate::parser::ast::literals::Literal;
+use crate::parser::ast::operators::ArithmeticOperationExpression;
 use crate::parser::ast::Case;
 use crate::parser::ast::DefaultMatchArm;
 use crate::parser::ast::Expression;
 use crate::parser::ast::MatchArm;
 use crate::parser::ast::Statement;
 use crate::parser::ast::SwitchStatement;
+use crate::parser::ast::YieldExpression;
+use crate::parser::ast::YieldFromExpression;
 use crate::parser::ast::{Block, MatchExpression};
 use crate::parser::error;
 use crate::parser::error::ParseResult;
@@ -23,9 +29,11 @@ use crate::parser::expressions;
 use crate::parser::internal::blocks;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::version::Feature;
 
 pub fn match_expression(state: &mut State) -> ParseResult<Expression> {
     let keyword = utils::skip(state, TokenKind::Match)?;
+    state.require(Feature::Match, keyword, 5);
 
     match state.stream.current().kind {
         TokenKind::LeftBrace => {
@@ -44,7 +52,15 @@ pub fn match_expression(state: &mut State) -> ParseResult<Expression> {
         _ => {
             let (left_parenthesis, condition, right_parenthesis) =
                 utils::parenthesized(state, &|state: &mut State| {
-                    expressions::create(state).map(Box::new)
+                    let start = state.stream.current().span;
+                    let condition = expressions::create(state)?;
+
+                    if let Expression::AssignmentOperation(_) = condition {
+                        let length = length_from(state, start);
+                        state.warn(error::assignment_in_match_subject(start, length));
+                    }
+
+                    Ok(Box::new(condition))
                 })?;
 
             let left_brace = utils::skip_left_brace(state)?;
@@ -100,7 +116,7 @@ fn match_arms(state: &mut State) -> ParseResult<(Option<Box<DefaultMatchArm>>, V
         } else {
             let mut conditions = Vec::new();
             while state.stream.current().kind != TokenKind::DoubleArrow {
-                conditions.push(expressions::create(state)?);
+                conditions.push(match_arm_condition(state)?);
 
                 if state.stream.current().kind == TokenKind::Comma {
                     state.stream.next();
@@ -134,6 +150,59 @@ fn match_arms(state: &mut State) -> ParseResult<(Option<Box<DefaultMatchArm>>, V
     Ok((default, arms))
 }
 
+/// Parse a condition of a match arm, which is an error when it is a `yield`, a `throw`
+/// or an assignment that isn't in parentheses.
+fn match_arm_condition(state: &mut State) -> ParseResult<Expression> {
+    let start = state.stream.current().span;
+
+    let condition = if state.stream.current().kind == TokenKind::Yield {
+        // The arrow of the arm would be taken for the arrow of a key.
+        let r#yield = state.stream.current().span;
+        state.stream.next();
+
+        if state.stream.current().kind == TokenKind::From {
+            let from = state.stream.current().span;
+            state.stream.next();
+
+            Expression::YieldFrom(YieldFromExpression {
+                r#yield,
+                from,
+                value: Box::new(expressions::create(state)?),
+            })
+        } else {
+            Expression::Yield(YieldExpression {
+                r#yield,
+                key: None,
+                value: Some(Box::new(expressions::create(state)?)),
+            })
+        }
+    } else {
+        expressions::create(state)?
+    };
+
+    let construct = match condition {
+        Expression::Yield(_) => "`yield`",
+        Expression::YieldFrom(_) => "`yield from`",
+        Expression::Throw(_) => "`throw`",
+        Expression::AssignmentOperation(_) => "an assignment",
+        _ => return Ok(condition),
+    };
+
+    let length = length_from(state, start);
+    state.record(error::invalid_match_arm_condition(construct, start, length));
+
+    Ok(condition)
+}
+
+/// The length of the source from `start` to the end of the last consumed token.
+fn length_from(state: &State, start: Span) -> usize {
+    state
+        .stream
+        .last_consumed()
+        .map_or(0, |last| last.span.position + last.value.len())
+        .saturating_sub(start.position)
+}
+
 fn match_arm_body(state: &mut State) -> ParseResult<MatchArmBody> {
     match state.stream.current().kind {
         TokenKind::LeftBrace => {
@@ -151,75 +220,140 @@ fn match_arm_body(state: &mut State) -> ParseResult<MatchArmBody> {
     }
 }
 
+/// Whether a case label is a string literal, or a number, when it is either.
+fn literal_label(label: &Expression) -> Option<bool> {
+    match label {
+        Expression::Literal(Literal::String(_)) => Some(true),
+        Expression::Literal(_) => Some(false),
+        Expression::ArithmeticOperation(
+            ArithmeticOperationExpression::Negative { right, .. }
+            | ArithmeticOperationExpression::Positive { right, .. },
+        ) => literal_label(right).filter(|string| !string),
+        _ => None,
+    }
+}
+
 pub fn switch_statement(state: &mut State) -> ParseResult<Statement> {
     let switch = utils::skip(state, TokenKind::Switch)?;
 
     let (left_parenthesis, condition, right_parenthesis) =
         utils::parenthesized(state, &expressions::create)?;
 
-    let end_token = if state.stream.current().kind == TokenKind::Colon {
-        utils::skip_colon(state)?;
-        TokenKind::EndSwitch
+    let (opener, end_token) = if state.stream.current().kind == TokenKind::Colon {
+        (utils::skip_colon(state)?, TokenKind::EndSwitch)
     } else {
-        utils::skip_left_brace(state)?;
-        TokenKind::RightBrace
+        (utils::skip_left_brace(state)?, TokenKind::RightBrace)
     };
 
-    let mut cases = Vec::new();
-    while state.stream.current().kind != end_token {
-        match state.stream.current().kind {
-            TokenKind::Case => {
-                state.stream.next();
+    // An alternative body also stops at the closing keyword of another construct.
+    let alternative = end_token == TokenKind::EndSwitch;
+    let closes = |kind: &TokenKind| {
+        matches!(kind, TokenKind::RightBrace | TokenKind::EndSwitch)
+            || (alternative && blocks::ALTERNATIVE_ENDS.contains(kind))
+    };
+    let ends_label =
+        |kind: &TokenKind| matches!(kind, TokenKind::Case | TokenKind::Default) || closes(kind);
+
+    if alternative {
+        state.alternative_ends.push(TokenKind::EndSwitch);
+    }
+    let cases = utils::breakable(state, &|state| {
+        let mut cases = Vec::new();
+        // The literal labels, and whether they are strings.
+        let mut labels = Vec::new();
+        while !closes(&state.stream.current().kind) {
+            match state.stream.current().kind {
+                TokenKind::Case => {
+                    state.stream.next();
 
-                let condition = expressions::create(state)?;
+                    let start = state.stream.current().span;
+                    let condition = expressions::create(state)?;
+                    let length = length_from(state, start);
 
-                utils::skip_any_of(state, &[TokenKind::Colon, TokenKind::SemiColon])?;
+                    if let Expression::AssignmentOperation(_) = condition {
+                        state.warn(error::assignment_in_case_label(start, length));
+                    }
 
-                let mut body = Block::new();
+                    if let Some(string) = literal_label(&condition) {
+                        labels.push((string, start, length));
+                    }
 
-                while state.stream.current().kind != TokenKind::Case
-                    && state.stream.current().kind != TokenKind::Default
-                    && state.stream.current().kind != TokenKind::RightBrace
-                    && state.stream.current().kind != end_token
-                {
-                    body.push(parser::statement(state)?);
+                    utils::skip_any_of(state, &[TokenKind::Colon, TokenKind::SemiColon])?;
+
+                    let mut body = Block::new();
+
+                    while !ends_label(&state.stream.current().kind) {
+                        body.push(parser::statement(state)?);
+                    }
+
+                    cases.push(Case {
+                        condition: Some(condition),
+                        body,
+                    });
                 }
+                TokenKind::Default => {
+                    state.stream.next();
 
-                cases.push(Case {
-                    condition: Some(condition),
-                    body,
-                });
-            }
-            TokenKind::Default => {
-                state.stream.next();
+                    utils::skip_any_of(state, &[TokenKind::Colon, TokenKind::SemiColon])?;
 
-                utils::skip_any_of(state, &[TokenKind::Colon, TokenKind::SemiColon])?;
+                    let mut body = Block::new();
 
-                let mut body = Block::new();
+                    while !ends_label(&state.stream.current().kind) {
+                        body.push(parser::statement(state)?);
+                    }
 
-                while state.stream.current().kind != TokenKind::Case
-                    && state.stream.current().kind != TokenKind::Default
-                    && state.stream.current().kind != end_token
+                    cases.push(Case {
+                        condition: None,
+                        body,
+                    });
+                }
+                // Only the newline that follows `?>` may come before a label, as PHP
+                // leaves it out of the output.
+                TokenKind::CloseTag | TokenKind::OpenTag(_) => state.stream.next(),
+                TokenKind::InlineHtml
+                    if matches!(&state.stream.current().value[..], b"\n" | b"\r\n") =>
                 {
-                    body.push(parser::statement(state)?);
+                    state.stream.next()
+                }
+                _ => {
+                    return expected_token_err!(["`case`", "`default`"], state);
                 }
-
-                cases.push(Case {
-                    condition: None,
-                    body,
-                });
-            }
-            _ => {
-                return expected_token_err!(["`case`", "`default`"], state);
             }
         }
+
+        Ok((cases, labels))
+    });
+    if alternative {
+        state.alternative_ends.pop();
     }
+    let (cases, labels) = cases?;
 
-    if end_token == TokenKind::EndSwitch {
-        utils::skip(state, TokenKind::EndSwitch)?;
-        utils::skip_ending(state)?;
+    let (strings, numbers): (Vec<_>, Vec<_>) = labels.into_iter().partition(|label| label.0);
+    if !strings.is_empty() && !numbers.is_empty() {
+        let label = |(_, span, length)| (span, length);
+
+        state.warn(error::mixed_case_label_types(
+            &strings.into_iter().map(label).collect::<Vec<_>>(),
+            &numbers.into_iter().map(label).collect::<Vec<_>>(),
+        ));
+    }
+
+    let current = state.stream.current();
+    if alternative && current.kind != TokenKind::EndSwitch && closes(&current.kind) {
+        blocks::skip_alternative_end(state, opener, &end_token)?;
     } else {
-        utils::skip_right_brace(state)?;
+        if current.kind != end_token {
+            let error = error::mismatched_body_closer(opener, &end_token, current);
+
+            state.record(error);
+        }
+
+        if current.kind == TokenKind::EndSwitch {
+            utils::skip(state, TokenKind::EndSwitch)?;
+            utils::skip_ending(state)?;
+        } else if end_token == TokenKind::RightBrace {
+            utils::skip_right_brace(state)?;
+        }
     }
 
     Ok(Statement::Switch(SwitchStatement {
@@ -227,6 +361,11 @@ pub fn switch_statement(state: &mut State) -> ParseResult<Statement> {
         left_parenthesis,
         condition,
         right_parenthesis,
+        body_syntax: if alternative {
+            BodySyntax::Alternative
+        } else {
+            BodySyntax::Brace
+        },
         cases,
     }))
 }
@@ -251,7 +390,7 @@ pub fn if_statement(state: &mut State) -> ParseResult<Statement> {
 }
 
 fn if_statement_statement_body(state: &mut State) -> ParseResult<IfStatementBody> {
-    let statement = parser::statement(state).map(Box::new)?;
+    let statement = blocks::body_statement(state, &TokenKind::EndIf).map(Box::new)?;
 
     let mut elseifs: Vec<IfStatementElseIf> = vec![];
     let mut current = state.stream.current();
@@ -266,7 +405,7 @@ fn if_statement_statement_body(state: &mut State) -> ParseResult<IfStatementBody
             left_parenthesis,
             condition,
             right_parenthesis,
-            statement: parser::statement(state).map(Box::new)?,
+            statement: blocks::body_statement(state, &TokenKind::EndIf).map(Box::new)?,
         });
 
         current = state.stream.current();
@@ -277,7 +416,7 @@ fn if_statement_statement_body(state: &mut State) -> ParseResult<IfStatementBody
 
         Some(IfStatementElse {
             r#else: current.span,
-            statement: parser::statement(state).map(Box::new)?,
+            statement: blocks::body_statement(state, &TokenKind::EndIf).map(Box::new)?,
         })
     } else {
         None
@@ -292,7 +431,7 @@ fn if_statement_statement_body(state: &mut State) -> ParseResult<IfStatementBody
 
 fn if_statement_block_body(state: &mut State) -> ParseResult<IfStatementBody> {
     let colon = utils::skip(state, TokenKind::Colon)?;
-    let statements = blocks::multiple_statements_until_any(
+    let statements = blocks::alternative_statements(
         state,
         &[TokenKind::Else, TokenKind::ElseIf, TokenKind::EndIf],
     )?;
@@ -311,7 +450,7 @@ fn if_statement_block_body(state: &mut State) -> ParseResult<IfStatementBody> {
             condition,
             right_parenthesis,
             colon: utils::skip(state, TokenKind::Colon)?,
-            statements: blocks::multiple_statements_until_any(
+            statements: blocks::alternative_statements(
                 state,
                 &[TokenKind::Else, TokenKind::ElseIf, TokenKind::EndIf],
             )?,
@@ -326,18 +465,109 @@ fn if_statement_block_body(state: &mut State) -> ParseResult<IfStatementBody> {
         Some(IfStatementElseBlock {
             r#else: current.span,
             colon: utils::skip(state, TokenKind::Colon)?,
-            statements: blocks::multiple_statements_until(state, &TokenKind::EndIf)?,
+            statements: blocks::alternative_statements(state, &[TokenKind::EndIf])?,
         })
     } else {
         None
     };
 
+    let (endif, ending) = blocks::skip_alternative_end(state, colon, &TokenKind::EndIf)?;
+
     Ok(IfStatementBody::Block {
         colon,
         statements,
         elseifs,
         r#else,
-        endif: utils::skip(state, TokenKind::EndIf)?,
-        ending: utils::skip_ending(state)?,
+        endif,
+        ending,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::Statement;
+    use crate::parser::error::ParseError;
+
+    fn warnings(code: &str) -> Vec<ParseError> {
+        crate::parse_with_warnings(code, Default::default())
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn test_assignment_in_case_label() {
+        let warnings = warnings("<?php switch ($a) { case $x = 5: break; case $y .= 'a': }");
+
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].id, "E078");
+        assert_eq!(warnings[0].span.column, 26);
+        assert_eq!(warnings[0].annotations[0].length, 6);
+        assert_eq!(warnings[1].annotations[0].length, 9);
+    }
+
+    #[test]
+    fn test_mixed_case_label_types() {
+        let warnings = warnings(
+            "<?php switch ($a) { case 1: case -2.5: break; case \"0\": default: }
+            switch ($b): case 'a': case 1; endswitch;",
+        );
+
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].id, "E079");
+        assert_eq!(warnings[0].span.column, 52);
+        assert_eq!(warnings[0].annotations.len(), 3);
+        assert_eq!(warnings[1].span.line, 2);
+    }
+
+    #[test]
+    fn test_yield_match_arm_condition() {
+        let stack = crate::parse("<?php $a = match ($x) { yield 1 => 'a', 2, $y = 3 => 'b' };")
+            .unwrap_err();
+
+        assert_eq!(stack.errors.len(), 2);
+        assert_eq!(stack.errors[0].id, "E089");
+        assert_eq!(
+            stack.errors[0].message,
+            "`yield` cannot be a match arm condition"
+        );
+        assert_eq!(stack.errors[0].span.column, 25);
+        assert_eq!(stack.errors[0].annotations[0].length, 7);
+        assert_eq!(
+            stack.errors[1].message,
+            "an assignment cannot be a match arm condition"
+        );
+        assert_eq!(stack.errors[1].annotations[0].length, 6);
+        assert!(matches!(stack.partial[..], [_, Statement::Expression(_)]));
+    }
+
+    #[test]
+    fn test_assignment_in_match_subject() {
+        let warnings = warnings("<?php match ($a = next($b)) { false => 0, default => $a };");
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].id, "E090");
+        assert_eq!(warnings[0].span.column, 14);
+        assert_eq!(warnings[0].annotations[0].length, 13);
+    }
+
+    #[test]
+    fn test_clean_match() {
+        for code in [
+            "<?php match (($a = next($b))) { ($a ? 1 : 2) => 0, self::A, $c->d() => 1 };",
+            "<?php match ($a) { (yield 1) => 0, ($b = 2), 1 + 1 => 1, default => throw $e };",
+        ] {
+            assert!(warnings(code).is_empty(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_clean_switch() {
+        for code in [
+            "<?php switch ($a) { case FOO | BAR: case self::X: case 1 + 1: case 2: default: }",
+            "<?php switch ($a) { case 'a': case \"b\": case A::B: case ($x = 1) > 0: }",
+            "<?php switch ($a) { case 1: switch ($b) { case 'a': } }",
+        ] {
+            assert!(warnings(code).is_empty(), "{}", code);
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::version::Feature;
 use crate::peek_token;
 
 pub fn data_type(state: &mut State) -> ParseResult<Type> {
@@ -23,15 +24,12 @@ pub fn data_type(state: &mut State) -> ParseResult<Type> {
         return union(state, ty, false);
     }
 
-    if state.stream.current().kind == TokenKind::Ampersand
-        && !matches!(
-            state.stream.peek().kind,
-            TokenKind::Variable | TokenKind::Ellipsis | TokenKind::Ampersand
-        )
-    {
+    if intersects(state) {
         return intersection(state, ty, false);
     }
 
+    standalone_literal_type(state, &ty);
+
     Ok(ty)
 }
 
@@ -53,23 +51,41 @@ pub fn optional_data_type(state: &mut State) -> ParseResult<Option<Type>> {
                 return union(state, ty, false).map(Some);
             }
 
-            if state.stream.current().kind == TokenKind::Ampersand
-                && !matches!(
-                    state.stream.peek().kind,
-                    TokenKind::Variable | TokenKind::Ellipsis | TokenKind::Ampersand
-                )
-            {
+            if intersects(state) {
                 return intersection(state, ty, false).map(Some);
             }
 
+            standalone_literal_type(state, &ty);
+
             Ok(Some(ty))
         }
         None => Ok(None),
     }
 }
 
+/// Whether the `&` at the current position joins two types, rather than coming before
+/// a parameter that is taken by reference.
+fn intersects(state: &State) -> bool {
+    state.stream.current().kind == TokenKind::Ampersand
+        && !matches!(
+            state.stream.peek().kind,
+            TokenKind::Variable | TokenKind::Ellipsis | TokenKind::Ampersand
+        )
+}
+
+/// `null` and `false` on their own, while `true` is checked wherever it is used.
+fn standalone_literal_type(state: &mut State, ty: &Type) {
+    match ty {
+        Type::Null(span) => state.require(Feature::LiteralTypes, *span, 4),
+        Type::False(span) => state.require(Feature::LiteralTypes, *span, 5),
+        _ => {}
+    }
+}
+
 fn dnf(state: &mut State) -> ParseResult<Type> {
     // (A|B|..)&C.. or (A&B&..)|C..
+    let left_parenthesis = state.stream.current().span;
+    state.require(Feature::DisjunctiveNormalFormTypes, left_parenthesis, 1);
     state.stream.next();
     let ty = simple_data_type(state)?;
     peek_token!([
@@ -116,6 +132,8 @@ fn optional_simple_data_type(state: &mut State) -> ParseResult<Option<Type>> {
             let span = current.span;
             state.stream.next();
 
+            state.require(Feature::LiteralTypes, span, 4);
+
             Ok(Some(Type::True(span)))
         }
         TokenKind::False => {
@@ -128,6 +146,8 @@ fn optional_simple_data_type(state: &mut State) -> ParseResult<Option<Type>> {
             let span = current.span;
             state.stream.next();
 
+            state.require(Feature::StaticReturnType, span, 6);
+
             Ok(Some(Type::StaticReference(span)))
         }
         TokenKind::Self_ => {
@@ -159,7 +179,11 @@ fn optional_simple_data_type(state: &mut State) -> ParseResult<Option<Type>> {
             let lowered_name = name.to_ascii_lowercase();
             match lowered_name.as_slice() {
                 b"void" => Ok(Some(Type::Void(span))),
-                b"never" => Ok(Some(Type::Never(span))),
+                b"never" => {
+                    state.require(Feature::NeverType, span, 5);
+
+                    Ok(Some(Type::Never(span)))
+                }
                 b"float" => Ok(Some(Type::Float(span))),
                 b"bool" => Ok(Some(Type::Boolean(span))),
                 b"int" => Ok(Some(Type::Integer(span))),
@@ -168,7 +192,11 @@ fn optional_simple_data_type(state: &mut State) -> ParseResult<Option<Type>> {
                 b"mixed" => Ok(Some(Type::Mixed(span))),
                 b"iterable" => Ok(Some(Type::Iterable(span))),
                 b"null" => Ok(Some(Type::Null(span))),
-                b"true" => Ok(Some(Type::True(span))),
+                b"true" => {
+                    state.require(Feature::LiteralTypes, span, 4);
+
+                    Ok(Some(Type::True(span)))
+                }
                 b"false" => Ok(Some(Type::False(span))),
                 b"array" => Ok(Some(Type::Array(span))),
                 b"callable" => Ok(Some(Type::Callable(span))),
@@ -180,7 +208,7 @@ fn optional_simple_data_type(state: &mut State) -> ParseResult<Option<Type>> {
             let span = current.span;
             state.stream.next();
 
-            Ok(Some(Type::Named(span, name)))
+            Ok(Some(Type::Named(span, name.into())))
         }
         _ => Ok(None),
     }
@@ -192,8 +220,24 @@ fn simple_data_type(state: &mut State) -> ParseResult<Type> {
 }
 
 fn nullable(state: &mut State) -> ParseResult<Type> {
+    let ty = nullable_simple_data_type(state)?;
+
+    // `?A|B` or `?A&B`, which are reported by `union` and `intersection`.
+    if state.stream.current().kind == TokenKind::Pipe {
+        return union(state, ty, false);
+    }
+
+    if intersects(state) {
+        return intersection(state, ty, false);
+    }
+
+    Ok(ty)
+}
+
+fn nullable_simple_data_type(state: &mut State) -> ParseResult<Type> {
     let current = state.stream.current();
 
+    state.require(Feature::NullableTypes, current.span, 1);
     state.stream.next();
 
     let ty = simple_data_type(state)?;
@@ -206,7 +250,9 @@ fn nullable(state: &mut State) -> ParseResult<Type> {
 }
 
 fn union(state: &mut State, other: Type, within_dnf: bool) -> ParseResult<Type> {
-    if other.standalone() {
+    if other.nullable() {
+        state.record(error::nullable_type_used_in_compound_type(&other, false));
+    } else if other.standalone() {
         state.record(error::standalone_type_used_in_union(
             &other,
             state.stream.current().span,
@@ -217,6 +263,11 @@ fn union(state: &mut State, other: Type, within_dnf: bool) -> ParseResult<Type>
 
     let mut last_pipe = utils::skip(state, TokenKind::Pipe)?;
 
+    // The types of a disjunctive normal form type are checked as a whole.
+    if !within_dnf {
+        state.require(Feature::UnionTypes, last_pipe, 1);
+    }
+
     loop {
         let current = state.stream.current();
         let ty = if current.kind == TokenKind::LeftParen {
@@ -235,6 +286,8 @@ fn union(state: &mut State, other: Type, within_dnf: bool) -> ParseResult<Type>
                 //        v-- error
                 // F&(A|B|(D&S))
                 state.record(error::nested_disjunctive_normal_form_types(current.span));
+            } else {
+                state.require(Feature::DisjunctiveNormalFormTypes, current.span, 1);
             }
 
             state.stream.next();
@@ -244,6 +297,11 @@ fn union(state: &mut State, other: Type, within_dnf: bool) -> ParseResult<Type>
 
             utils::skip_right_parenthesis(state)?;
 
+            ty
+        } else if current.kind == TokenKind::Question {
+            let ty = nullable_simple_data_type(state)?;
+            state.record(error::nullable_type_used_in_compound_type(&ty, false));
+
             ty
         } else {
             let ty = simple_data_type(state)?;
@@ -267,7 +325,9 @@ fn union(state: &mut State, other: Type, within_dnf: bool) -> ParseResult<Type>
 }
 
 fn intersection(state: &mut State, other: Type, within_dnf: bool) -> ParseResult<Type> {
-    if other.standalone() {
+    if other.nullable() {
+        state.record(error::nullable_type_used_in_compound_type(&other, true));
+    } else if other.standalone() {
         state.record(error::standalone_type_used_in_intersection(
             &other,
             state.stream.current().span,
@@ -278,6 +338,10 @@ fn intersection(state: &mut State, other: Type, within_dnf: bool) -> ParseResult
 
     let mut last_ampersand = utils::skip(state, TokenKind::Ampersand)?;
 
+    if !within_dnf {
+        state.require(Feature::IntersectionTypes, last_ampersand, 1);
+    }
+
     loop {
         let current = state.stream.current();
         let ty = if current.kind == TokenKind::LeftParen {
@@ -296,6 +360,8 @@ fn intersection(state: &mut State, other: Type, within_dnf: bool) -> ParseResult
                 //        v-- error
                 // F|(A&B&(D|S))
                 state.record(error::nested_disjunctive_normal_form_types(current.span));
+            } else {
+                state.require(Feature::DisjunctiveNormalFormTypes, current.span, 1);
             }
 
             state.stream.next();
@@ -305,6 +371,11 @@ fn intersection(state: &mut State, other: Type, within_dnf: bool) -> ParseResult
 
             utils::skip_right_parenthesis(state)?;
 
+            ty
+        } else if current.kind == TokenKind::Question {
+            let ty = nullable_simple_data_type(state)?;
+            state.record(error::nullable_type_used_in_compound_type(&ty, true));
+
             ty
         } else {
             let ty = simple_data_type(state)?;
@@ -320,12 +391,7 @@ fn intersection(state: &mut State, other: Type, within_dnf: bool) -> ParseResult
 
         types.push(ty);
 
-        if state.stream.current().kind == TokenKind::Ampersand
-            && !matches!(
-                state.stream.peek().kind,
-                TokenKind::Variable | TokenKind::Ellipsis | TokenKind::Ampersand
-            )
-        {
+        if intersects(state) {
             last_ampersand = utils::skip(state, TokenKind::Ampersand)?;
         } else {
             break;
@@ -23,23 +23,21 @@ use crate::parser::internal::identifiers;
 use crate::parser::internal::modifiers;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::symbols;
+use crate::parser::symbols::SymbolKind;
+use crate::parser::version::Feature;
 
 use super::traits;
 
 pub fn parse(state: &mut State) -> ParseResult<Statement> {
     let span = utils::skip(state, TokenKind::Enum)?;
+    state.require(Feature::Enums, span, 4);
 
     let name = identifiers::type_identifier(state)?;
 
-    let backed_type: Option<BackedEnumType> = if state.stream.current().kind == TokenKind::Colon {
-        let span = utils::skip_colon(state)?;
-
-        let identifier = identifiers::identifier_of(state, &["string", "int"])?;
-        Some(match &identifier.value[..] {
-            b"string" => BackedEnumType::String(span, identifier.span),
-            b"int" => BackedEnumType::Int(span, identifier.span),
-            _ => unreachable!(),
-        })
+    let mut backed_type: Option<BackedEnumType> = if state.stream.current().kind == TokenKind::Colon
+    {
+        Some(backing_type(state)?)
     } else {
         None
     };
@@ -57,15 +55,30 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
                 break;
             }
         }
+
+        // The backing type can't be declared after the implemented interfaces,
+        // but we can still parse it and carry on as if it was in the right place.
+        if backed_type.is_none() && state.stream.current().kind == TokenKind::Colon {
+            let misplaced = backing_type(state)?;
+
+            state.record(error::enum_backing_type_after_implements(&misplaced));
+
+            backed_type = Some(misplaced);
+        }
     }
 
     let attributes = state.get_attributes();
+    let start = symbols::start(&attributes, span);
+
+    symbols::enter(state, SymbolKind::Enum, &name.value);
     if let Some(backed_type) = backed_type {
         let body = BackedEnumBody {
             left_brace: utils::skip_left_brace(state)?,
             members: {
                 let mut members = Vec::new();
-                while state.stream.current().kind != TokenKind::RightBrace {
+                while state.stream.current().kind != TokenKind::RightBrace && !state.stream.is_eof()
+                {
+                    state.check_cancelled()?;
                     if let Some(member) = backed_member(state, &name)? {
                         members.push(member);
                     }
@@ -75,8 +88,12 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
             },
             right_brace: utils::skip_right_brace(state)?,
         };
+        symbols::exit(state);
+
+        symbols::emit(state, SymbolKind::Enum, &name.value, name.span, start);
 
         Ok(Statement::BackedEnum(BackedEnumStatement {
+            doc: state.stream.doc_comment(start.position),
             r#enum: span,
             name,
             backed_type,
@@ -89,7 +106,9 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
             left_brace: utils::skip_left_brace(state)?,
             members: {
                 let mut members = Vec::new();
-                while state.stream.current().kind != TokenKind::RightBrace {
+                while state.stream.current().kind != TokenKind::RightBrace && !state.stream.is_eof()
+                {
+                    state.check_cancelled()?;
                     if let Some(member) = unit_member(state, &name)? {
                         members.push(member);
                     }
@@ -98,8 +117,12 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
             },
             right_brace: utils::skip_right_brace(state)?,
         };
+        symbols::exit(state);
+
+        symbols::emit(state, SymbolKind::Enum, &name.value, name.span, start);
 
         Ok(Statement::UnitEnum(UnitEnumStatement {
+            doc: state.stream.doc_comment(start.position),
             r#enum: span,
             name,
             attributes,
@@ -109,10 +132,39 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
     }
 }
 
+fn backing_type(state: &mut State) -> ParseResult<BackedEnumType> {
+    let span = utils::skip_colon(state)?;
+
+    // Attributes can't be attached to the backing type, so we parse them
+    // to be able to report the whole group and continue with the type.
+    let current = state.stream.current();
+    if current.kind == TokenKind::Attribute {
+        let count = state.attributes.len();
+        attributes::gather_attributes(state)?;
+        let misplaced = state.attributes.split_off(count);
+
+        if let Some(last) = misplaced.last() {
+            state.record(error::attributes_on_enum_backing_type(
+                current.span,
+                last.end,
+            ));
+        }
+    }
+
+    let identifier = identifiers::identifier_of(state, &["string", "int"])?;
+    Ok(match &identifier.value[..] {
+        b"string" => BackedEnumType::String(span, identifier.span),
+        b"int" => BackedEnumType::Int(span, identifier.span),
+        _ => unreachable!(),
+    })
+}
+
 fn unit_member(
     state: &mut State,
     enum_name: &SimpleIdentifier,
 ) -> ParseResult<Option<UnitEnumMember>> {
+    state.stream.forget_enclosed_comments();
+
     let has_attributes = attributes::gather_attributes(state)?;
 
     if !has_attributes && state.stream.current().kind == TokenKind::Use {
@@ -121,8 +173,16 @@ fn unit_member(
             .map(Some);
     }
 
+    let modifiers = modifiers::collect(state)?;
+
     let current = state.stream.current();
     if current.kind == TokenKind::Case {
+        for (span, modifier) in modifiers {
+            let error = error::modifier_cannot_be_used_for_enum_case(modifier.to_string(), span);
+
+            state.record(error);
+        }
+
         let attributes = state.get_attributes();
 
         let start = current.span;
@@ -146,7 +206,11 @@ fn unit_member(
 
         let end = utils::skip_semicolon(state)?;
 
+        let first = symbols::start(&attributes, start);
+        symbols::emit(state, SymbolKind::EnumCase, &name.value, name.span, first);
+
         return Ok(Some(UnitEnumMember::Case(UnitEnumCase {
+            doc: state.stream.doc_comment(first.position),
             start,
             end,
             name,
@@ -154,8 +218,6 @@ fn unit_member(
         })));
     }
 
-    let modifiers = modifiers::collect(state)?;
-
     if state.stream.current().kind == TokenKind::Const {
         return constants::classish(state, modifiers::constant_group(modifiers)?)
             .map(UnitEnumMember::Constant)
@@ -169,6 +231,8 @@ fn backed_member(
     state: &mut State,
     enum_name: &SimpleIdentifier,
 ) -> ParseResult<Option<BackedEnumMember>> {
+    state.stream.forget_enclosed_comments();
+
     let has_attributes = attributes::gather_attributes(state)?;
 
     if !has_attributes && state.stream.current().kind == TokenKind::Use {
@@ -177,8 +241,16 @@ fn backed_member(
             .map(Some);
     }
 
+    let modifiers = modifiers::collect(state)?;
+
     let current = state.stream.current();
     if current.kind == TokenKind::Case {
+        for (span, modifier) in modifiers {
+            let error = error::modifier_cannot_be_used_for_enum_case(modifier.to_string(), span);
+
+            state.record(error);
+        }
+
         let attributes = state.get_attributes();
 
         let case = current.span;
@@ -205,7 +277,11 @@ fn backed_member(
 
         let semicolon = utils::skip_semicolon(state)?;
 
+        let start = symbols::start(&attributes, case);
+        symbols::emit(state, SymbolKind::EnumCase, &name.value, name.span, start);
+
         return Ok(Some(BackedEnumMember::Case(BackedEnumCase {
+            doc: state.stream.doc_comment(start.position),
             attributes,
             case,
             name,
@@ -215,8 +291,6 @@ fn backed_member(
         })));
     }
 
-    let modifiers = modifiers::collect(state)?;
-
     if state.stream.current().kind == TokenKind::Const {
         return constants::classish(state, modifiers::constant_group(modifiers)?)
             .map(BackedEnumMember::Constant)
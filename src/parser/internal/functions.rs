@@ -18,6 +18,7 @@ use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::modifiers::MethodModifierGroup;
 use crate::parser::ast::Expression;
 use crate::parser::ast::Statement;
+use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::expressions;
 use crate::parser::internal::blocks;
@@ -27,6 +28,9 @@ use crate::parser::internal::parameters;
 use crate::parser::internal::utils;
 use crate::parser::internal::variables;
 use crate::parser::state::State;
+use crate::parser::symbols;
+use crate::parser::symbols::SymbolKind;
+use crate::parser::version::Feature;
 
 pub enum MethodType {
     Abstract,
@@ -42,6 +46,9 @@ pub enum Method {
 }
 
 pub fn anonymous_function(state: &mut State) -> ParseResult<Expression> {
+    // Closures are expressions, so the comments before the tokens of the statement
+    // they are in aren't theirs.
+    state.stream.forget_enclosed_comments();
     let comments = state.stream.comments();
     let attributes = state.get_attributes();
     let current = state.stream.current();
@@ -103,22 +110,23 @@ pub fn anonymous_function(state: &mut State) -> ParseResult<Expression> {
         None
     };
 
-    let return_type = if state.stream.current().kind == TokenKind::Colon {
-        Some(ReturnType {
-            colon: utils::skip_colon(state)?,
-            data_type: data_type::data_type(state)?,
-        })
-    } else {
-        None
-    };
+    if let Some(uses) = &uses {
+        parameters::trailing_comma(state, &uses.variables);
+    }
+
+    let return_type = return_type(state)?;
 
     let body = FunctionBody {
         comments: state.stream.comments(),
         left_brace: utils::skip_left_brace(state)?,
-        statements: blocks::multiple_statements_until(state, &TokenKind::RightBrace)?,
+        statements: utils::function_body(state, ampersand.is_some(), &blocks::function_statements)?,
+        skipped: state.config.skip_bodies,
         right_brace: utils::skip_right_brace(state)?,
     };
 
+    let start = symbols::start(&attributes, r#static.unwrap_or(function));
+    symbols::emit_closure(state, function, 8, start);
+
     Ok(Expression::Closure(ClosureExpression {
         comments,
         function,
@@ -133,6 +141,7 @@ pub fn anonymous_function(state: &mut State) -> ParseResult<Expression> {
 }
 
 pub fn arrow_function(state: &mut State) -> ParseResult<Expression> {
+    state.stream.forget_enclosed_comments();
     let comments = state.stream.comments();
     let current = state.stream.current();
     let r#static = if current.kind == TokenKind::Static {
@@ -144,6 +153,7 @@ pub fn arrow_function(state: &mut State) -> ParseResult<Expression> {
     };
 
     let r#fn = utils::skip(state, TokenKind::Fn)?;
+    state.require(Feature::ArrowFunctions, r#fn, 2);
 
     let current = state.stream.current();
     let ampersand = if state.stream.current().kind == TokenKind::Ampersand {
@@ -156,16 +166,12 @@ pub fn arrow_function(state: &mut State) -> ParseResult<Expression> {
 
     let attributes = state.get_attributes();
     let parameters = parameters::function_parameter_list(state)?;
-    let return_type = if state.stream.current().kind == TokenKind::Colon {
-        Some(ReturnType {
-            colon: utils::skip_colon(state)?,
-            data_type: data_type::data_type(state)?,
-        })
-    } else {
-        None
-    };
+    let return_type = return_type(state)?;
+
+    let body = arrow_function_body(state, ampersand.is_some())?;
 
-    let body = arrow_function_body(state)?;
+    let start = symbols::start(&attributes, r#static.unwrap_or(r#fn));
+    symbols::emit_closure(state, r#fn, 2, start);
 
     Ok(Expression::ArrowFunction(ArrowFunctionExpression {
         comments,
@@ -179,25 +185,42 @@ pub fn arrow_function(state: &mut State) -> ParseResult<Expression> {
     }))
 }
 
-fn arrow_function_body(state: &mut State) -> ParseResult<ArrowFunctionBody> {
+fn arrow_function_body(state: &mut State, by_reference: bool) -> ParseResult<ArrowFunctionBody> {
     if state.stream.current().kind == TokenKind::LeftBrace {
         let left_brace = utils::skip_left_brace(state)?;
-        let statements = blocks::multiple_statements_until(state, &TokenKind::RightBrace)?;
+        let statements = utils::function_body(state, by_reference, &blocks::function_statements)?;
         let right_brace = utils::skip_right_brace(state)?;
 
         Ok(ArrowFunctionBody::Block(ArrowFunctionBlockBody {
             left_brace,
             statements,
+            skipped: state.config.skip_bodies,
             right_brace,
         }))
     } else {
         let double_arrow = utils::skip(state, TokenKind::DoubleArrow)?;
-        let expression = Box::new(expressions::create(state)?);
+        let expression = Box::new(utils::function_body(
+            state,
+            by_reference,
+            &expressions::create,
+        )?);
 
         Ok(ArrowFunctionBody::Expression(ArrowFunctionExpressionBody { double_arrow, expression }))
     }
 }
 
+/// The return type of any function-like, after its parameters and a closure's `use`.
+fn return_type(state: &mut State) -> ParseResult<Option<ReturnType>> {
+    if state.stream.current().kind != TokenKind::Colon {
+        return Ok(None);
+    }
+
+    Ok(Some(ReturnType {
+        colon: utils::skip_colon(state)?,
+        data_type: data_type::data_type(state)?,
+    }))
+}
+
 pub fn function(state: &mut State) -> ParseResult<Statement> {
     let comments = state.stream.comments();
 
@@ -212,31 +235,29 @@ pub fn function(state: &mut State) -> ParseResult<Statement> {
         None
     };
 
-    let name = identifiers::identifier_maybe_soft_reserved(state)?;
+    let name = identifiers::function_identifier(state)?;
 
     // get attributes before processing parameters, otherwise
     // parameters will steal attributes of this function.
     let attributes = state.get_attributes();
 
     let parameters = parameters::function_parameter_list(state)?;
-    let return_type = if state.stream.current().kind == TokenKind::Colon {
-        Some(ReturnType {
-            colon: utils::skip_colon(state)?,
-            data_type: data_type::data_type(state)?,
-        })
-    } else {
-        None
-    };
+    let return_type = return_type(state)?;
 
     let body = FunctionBody {
         comments: state.stream.comments(),
         left_brace: utils::skip_left_brace(state)?,
-        statements: blocks::multiple_statements_until(state, &TokenKind::RightBrace)?,
+        statements: utils::function_body(state, ampersand.is_some(), &blocks::function_statements)?,
+        skipped: state.config.skip_bodies,
         right_brace: utils::skip_right_brace(state)?,
     };
 
+    let start = symbols::start(&attributes, function);
+    symbols::emit(state, SymbolKind::Function, &name.value, name.span, start);
+
     Ok(Statement::Function(FunctionStatement {
         comments,
+        doc: state.stream.doc_comment(start.position),
         function,
         name,
         attributes,
@@ -267,24 +288,43 @@ pub fn method(
     };
 
     let name = identifiers::identifier_maybe_reserved(state)?;
+    let start = symbols::start(
+        &attributes,
+        modifiers
+            .modifiers
+            .first()
+            .map_or(function, |modifier| modifier.span()),
+    );
+    let doc = state.stream.doc_comment(start.position);
     let has_body = match r#type {
         MethodType::Abstract => false,
         MethodType::Concrete => true,
         MethodType::DependingOnModifiers => !modifiers.has_abstract(),
     };
 
+    let interface = matches!(r#type, MethodType::Abstract);
+
     if name.to_string().to_lowercase() == "__construct" {
         return if has_body {
             let parameters = parameters::constructor_parameter_list(state, class)?;
+            body(state, has_body, interface, class, &name)?;
             let body = MethodBody {
                 comments: state.stream.comments(),
                 left_brace: utils::skip_left_brace(state)?,
-                statements: blocks::multiple_statements_until(state, &TokenKind::RightBrace)?,
+                statements: utils::function_body(
+                    state,
+                    ampersand.is_some(),
+                    &blocks::function_statements,
+                )?,
+                skipped: state.config.skip_bodies,
                 right_brace: utils::skip_right_brace(state)?,
             };
 
+            symbols::emit(state, SymbolKind::Method, &name.value, name.span, start);
+
             Ok(Method::ConcreteConstructor(ConcreteConstructor {
                 comments,
+                doc,
                 attributes,
                 modifiers,
                 function,
@@ -295,10 +335,14 @@ pub fn method(
             }))
         } else {
             let parameters = parameters::function_parameter_list(state)?;
+            body(state, has_body, interface, class, &name)?;
             let semicolon = utils::skip_semicolon(state)?;
 
+            symbols::emit(state, SymbolKind::Method, &name.value, name.span, start);
+
             Ok(Method::AbstractConstructor(AbstractConstructor {
                 comments,
+                doc,
                 attributes,
                 modifiers,
                 function,
@@ -311,18 +355,28 @@ pub fn method(
     }
 
     let parameters = parameters::function_parameter_list(state)?;
-    let return_type = if state.stream.current().kind == TokenKind::Colon {
-        Some(ReturnType {
-            colon: utils::skip_colon(state)?,
-            data_type: data_type::data_type(state)?,
-        })
-    } else {
-        None
-    };
+    let return_type = return_type(state)?;
+
+    body(state, has_body, interface, class, &name)?;
 
     if has_body {
+        let body = MethodBody {
+            comments: state.stream.comments(),
+            left_brace: utils::skip_left_brace(state)?,
+            statements: utils::function_body(
+                state,
+                ampersand.is_some(),
+                &blocks::function_statements,
+            )?,
+            skipped: state.config.skip_bodies,
+            right_brace: utils::skip_right_brace(state)?,
+        };
+
+        symbols::emit(state, SymbolKind::Method, &name.value, name.span, start);
+
         Ok(Method::Concrete(ConcreteMethod {
             comments,
+            doc,
             attributes,
             modifiers,
             function,
@@ -330,16 +384,16 @@ pub fn method(
             name,
             parameters,
             return_type,
-            body: MethodBody {
-                comments: state.stream.comments(),
-                left_brace: utils::skip_left_brace(state)?,
-                statements: blocks::multiple_statements_until(state, &TokenKind::RightBrace)?,
-                right_brace: utils::skip_right_brace(state)?,
-            },
+            body,
         }))
     } else {
+        let semicolon = utils::skip_semicolon(state)?;
+
+        symbols::emit(state, SymbolKind::Method, &name.value, name.span, start);
+
         Ok(Method::Abstract(AbstractMethod {
             comments,
+            doc,
             attributes,
             modifiers,
             function,
@@ -347,7 +401,36 @@ pub fn method(
             name,
             parameters,
             return_type,
-            semicolon: utils::skip_semicolon(state)?,
+            semicolon,
         }))
     }
 }
+
+/// Fail when a method has a body it can't have, or lacks one it needs, with an error
+/// that says so rather than that the `{` or `;` is unexpected.
+fn body(
+    state: &mut State,
+    has_body: bool,
+    interface: bool,
+    class: Option<&SimpleIdentifier>,
+    name: &SimpleIdentifier,
+) -> ParseResult<()> {
+    let current = state.stream.current();
+
+    match current.kind {
+        TokenKind::LeftBrace if !has_body => Err(error::method_with_body(
+            state,
+            interface,
+            class,
+            name,
+            current.span,
+        )),
+        TokenKind::SemiColon if has_body => Err(error::method_without_body(
+            state,
+            class,
+            name,
+            current.span,
+        )),
+        _ => Ok(()),
+    }
+}
@@ -2,6 +2,7 @@ use crate::lexer::token::TokenKind;
 use crate::parser::ast::goto::GotoStatement;
 use crate::parser::ast::goto::LabelStatement;
 use crate::parser::ast::Statement;
+use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::internal::identifiers;
 use crate::parser::internal::utils;
@@ -12,6 +13,18 @@ pub fn label_statement(state: &mut State) -> ParseResult<Statement> {
     let label = identifiers::label_identifier(state)?;
     let colon = utils::skip_colon(state)?;
 
+    // Labels are unique within a function, however deeply they are nested in its
+    // blocks, loops and alternative syntax bodies.
+    if let Some(previous) = state
+        .labels
+        .iter()
+        .find(|previous| previous.value == label.value)
+    {
+        state.record(error::duplicate_label(&label, previous));
+    } else {
+        state.labels.push(label.clone());
+    }
+
     Ok(Statement::Label(LabelStatement {
         comments,
         label,
@@ -32,3 +45,57 @@ pub fn goto_statement(state: &mut State) -> ParseResult<Statement> {
         semicolon,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    fn errors(code: &str) -> Vec<String> {
+        match crate::parse(code) {
+            Ok(_) => vec![],
+            Err(stack) => stack
+                .errors
+                .iter()
+                .map(|error| format!("{} {}", error.id, error.message))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_labels_in_nested_bodies() {
+        let duplicate = vec!["E073 label `a` is already defined".to_string()];
+
+        for code in [
+            "<?php a: if (true): a: endif;",
+            "<?php a: while (true) { a: }",
+            "<?php a: foreach ($b as $c): a: endforeach;",
+            "<?php declare(ticks=1) { a: } a:",
+            "<?php try { a: } catch (E $e) { a: }",
+            "<?php try {} finally { a: } a:",
+            "<?php switch ($b) { case 1: a: break; default: a: }",
+            "<?php function f() { { a: } if (true): a: endif; }",
+        ] {
+            assert_eq!(errors(code), duplicate, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_function_likes_have_their_own_labels() {
+        for code in [
+            "<?php a: function f() { a: } a2:",
+            "<?php a: $f = function () { a: };",
+            "<?php function f() { a: $f = static function () { a: }; }",
+            "<?php class C { function f() { a: } function g() { a: } }",
+            "<?php a: new class { function f() { a: } };",
+            "<?php a: A: b:",
+        ] {
+            assert_eq!(errors(code), Vec::<String>::new(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_labels_are_restored_after_a_function() {
+        assert_eq!(
+            errors("<?php a: function f() { b: } a:"),
+            vec!["E073 label `a` is already defined".to_string()]
+        );
+    }
+}
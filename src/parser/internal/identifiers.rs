@@ -2,9 +2,11 @@ use crate::lexer::token::TokenKind;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::error;
 use crate::parser::error::ParseResult;
+use crate::parser::reserved;
+use crate::parser::reserved::NameKind;
+use crate::parser::reserved::Reservation;
 use crate::parser::state::State;
-
-use crate::peek_token;
+use crate::parser::version::PhpVersion;
 
 pub fn identifier_of(state: &mut State, kinds: &[&str]) -> ParseResult<SimpleIdentifier> {
     let ident = identifier(state)?;
@@ -25,56 +27,27 @@ pub fn identifier_of(state: &mut State, kinds: &[&str]) -> ParseResult<SimpleIde
 /// Expect an unqualified identifier such as Foo or Bar for a class, interface, trait, or an enum name.
 pub fn type_identifier(state: &mut State) -> ParseResult<SimpleIdentifier> {
     let current = state.stream.current();
-    match &current.kind {
-        TokenKind::Identifier | TokenKind::Type => {
-            let span = current.span;
-
-            state.stream.next();
-
-            Ok(SimpleIdentifier {
-                span,
-                value: current.value.clone(),
-            })
-        }
-        TokenKind::Enum | TokenKind::From => {
-            let span = current.span;
-            let name = current.to_string().into();
-
-            state.stream.next();
-
-            Ok(SimpleIdentifier { span, value: name })
-        }
-        TokenKind::Self_ | TokenKind::Static | TokenKind::Parent => {
-            state.record(error::cannot_use_reserved_keyword_as_a_type_name(
-                current.span,
-                current.to_string(),
-            ));
-
-            let span = current.span;
-            let name = current.to_string().into();
-
-            state.stream.next();
-
-            Ok(SimpleIdentifier { span, value: name })
+    let name = match &current.kind {
+        TokenKind::Identifier | TokenKind::Type => SimpleIdentifier {
+            span: current.span,
+            value: current.value.to_byte_string(),
+        },
+        t if is_reserved_identifier(t) => SimpleIdentifier {
+            span: current.span,
+            value: current.to_string().into(),
+        },
+        _ => {
+            return Err(error::unexpected_token(
+                vec!["an identifier".to_owned()],
+                current,
+            ))
         }
-        t if is_reserved_identifier(t) => {
-            state.record(error::cannot_use_reserved_keyword_as_a_type_name(
-                current.span,
-                current.to_string(),
-            ));
-
-            let span = current.span;
-            let name = current.to_string().into();
+    };
 
-            state.stream.next();
+    state.stream.next();
+    check_reserved(state, &name, NameKind::Type);
 
-            Ok(SimpleIdentifier { span, value: name })
-        }
-        _ => Err(error::unexpected_token(
-            vec!["an identifier".to_owned()],
-            current,
-        )),
-    }
+    Ok(name)
 }
 
 /// Expect an unqualified identifier such as foo or bar for a goto label name.
@@ -88,7 +61,7 @@ pub fn label_identifier(state: &mut State) -> ParseResult<SimpleIdentifier> {
 
             Ok(SimpleIdentifier {
                 span,
-                value: current.value.clone(),
+                value: current.value.to_byte_string(),
             })
         }
         TokenKind::Enum | TokenKind::From => {
@@ -136,41 +109,71 @@ pub fn label_identifier(state: &mut State) -> ParseResult<SimpleIdentifier> {
 /// Expect an unqualified identifier such as FOO or BAR for a constant name.
 pub fn constant_identifier(state: &mut State) -> ParseResult<SimpleIdentifier> {
     let current = state.stream.current();
-    match &current.kind {
-        TokenKind::Identifier | TokenKind::Type => {
-            let span = current.span;
-
-            state.stream.next();
-
-            Ok(SimpleIdentifier {
-                span,
-                value: current.value.clone(),
-            })
+    let name = match &current.kind {
+        TokenKind::Identifier | TokenKind::Type => SimpleIdentifier {
+            span: current.span,
+            value: current.value.to_byte_string(),
+        },
+        t if is_reserved_identifier(t) => SimpleIdentifier {
+            span: current.span,
+            value: current.to_string().into(),
+        },
+        _ => {
+            return Err(error::unexpected_token(
+                vec!["an identifier".to_owned()],
+                current,
+            ))
         }
-        TokenKind::Enum | TokenKind::From | TokenKind::Self_ | TokenKind::Parent => {
-            let span = current.span;
-            let name = current.to_string().into();
+    };
 
-            state.stream.next();
+    state.stream.next();
+    check_reserved(state, &name, NameKind::Constant);
 
-            Ok(SimpleIdentifier { span, value: name })
-        }
-        t if is_reserved_identifier(t) => {
-            state.record(error::cannot_use_reserved_keyword_as_a_constant_name(
-                current.span,
-                current.to_string(),
-            ));
+    Ok(name)
+}
 
-            let span = current.span;
-            let name = current.to_string().into();
+/// Expect an unqualified identifier such as foo or bar for a function name.
+pub fn function_identifier(state: &mut State) -> ParseResult<SimpleIdentifier> {
+    let name = identifier_maybe_soft_reserved(state)?;
 
-            state.stream.next();
+    check_reserved(state, &name, NameKind::Function);
 
-            Ok(SimpleIdentifier { span, value: name })
-        }
-        _ => Err(error::unexpected_token(
-            vec!["an identifier".to_owned()],
-            current,
+    Ok(name)
+}
+
+/// Report a name that the targeted version of PHP reserves, according to [`RESERVED_WORDS`].
+///
+/// [`RESERVED_WORDS`]: crate::parser::reserved::RESERVED_WORDS
+fn check_reserved(state: &mut State, name: &SimpleIdentifier, kind: NameKind) {
+    let reserved = match reserved::reservation(&name.value, kind, state.config.php_version) {
+        Some(reserved) => reserved,
+        None => return,
+    };
+
+    let word = name.value.to_string();
+
+    match reserved.reservation {
+        Reservation::Soft => state.warn(error::soft_reserved_word_used_as_a_name(
+            name.span,
+            word,
+            kind,
+            reserved.since,
+        )),
+        // Keywords that every targeted version reserves keep their own errors.
+        Reservation::Keyword if reserved.since == PhpVersion::OLDEST => state.record(match kind {
+            NameKind::Type => error::cannot_use_reserved_keyword_as_a_type_name(name.span, word),
+            NameKind::Constant => {
+                error::cannot_use_reserved_keyword_as_a_constant_name(name.span, word)
+            }
+            NameKind::Function => {
+                error::cannot_use_reserved_word_as_a_name(name.span, word, kind, None)
+            }
+        }),
+        _ => state.record(error::cannot_use_reserved_word_as_a_name(
+            name.span,
+            word,
+            kind,
+            Some(reserved.since),
         )),
     }
 }
@@ -185,7 +188,7 @@ pub fn identifier(state: &mut State) -> ParseResult<SimpleIdentifier> {
 
         Ok(SimpleIdentifier {
             span,
-            value: current.value.clone(),
+            value: current.value.to_byte_string(),
         })
     } else {
         Err(error::unexpected_token(
@@ -195,20 +198,6 @@ pub fn identifier(state: &mut State) -> ParseResult<SimpleIdentifier> {
     }
 }
 
-/// Expect an unqualified or qualified identifier such as Foo, Bar or Foo\Bar.
-pub fn name(state: &mut State) -> ParseResult<SimpleIdentifier> {
-    let name = peek_token!([
-        TokenKind::Identifier | TokenKind::QualifiedIdentifier | TokenKind::Type => {
-            state.stream.current().value.clone()
-        },
-    ], state, "an identifier");
-
-    let span = state.stream.current().span;
-    state.stream.next();
-
-    Ok(SimpleIdentifier { span, value: name })
-}
-
 /// Expect an optional unqualified or qualified identifier such as Foo, Bar or Foo\Bar.
 pub fn optional_name(state: &mut State) -> Option<SimpleIdentifier> {
     let current = state.stream.current();
@@ -219,7 +208,7 @@ pub fn optional_name(state: &mut State) -> Option<SimpleIdentifier> {
 
             Some(SimpleIdentifier {
                 span: current.span,
-                value: current.value.clone(),
+                value: current.value.to_byte_string(),
             })
         }
         t if is_reserved_identifier(t) => {
@@ -227,7 +216,7 @@ pub fn optional_name(state: &mut State) -> Option<SimpleIdentifier> {
 
             Some(SimpleIdentifier {
                 span: current.span,
-                value: current.value.clone(),
+                value: current.value.to_byte_string(),
             })
         }
         _ => None,
@@ -249,7 +238,7 @@ pub fn full_name(state: &mut State) -> ParseResult<SimpleIdentifier> {
 
             Ok(SimpleIdentifier {
                 span,
-                value: current.value.clone(),
+                value: current.value.to_byte_string(),
             })
         }
         _ => Err(error::unexpected_token(
@@ -259,6 +248,23 @@ pub fn full_name(state: &mut State) -> ParseResult<SimpleIdentifier> {
     }
 }
 
+/// The language constructs that are used like functions, e.g. `isset($a)`.
+const CONSTRUCTS: &[&[u8]] = &[
+    b"array", b"list", b"isset", b"empty", b"exit", b"die", b"eval", b"unset", b"print",
+];
+
+/// The language construct named by the last segment of a qualified name, e.g. `isset`
+/// in `\isset` or `namespace\isset`.
+pub fn qualified_construct(name: &SimpleIdentifier) -> Option<&[u8]> {
+    let separator = name.value.iter().rposition(|byte| *byte == b'\\')?;
+    let last = &name.value[separator + 1..];
+
+    CONSTRUCTS
+        .iter()
+        .any(|construct| construct.eq_ignore_ascii_case(last))
+        .then_some(last)
+}
+
 /// Expect an unqualified, qualified or fully qualified identifier such as Foo, Foo\Bar or \Foo\Bar.
 pub fn full_type_name(state: &mut State) -> ParseResult<SimpleIdentifier> {
     let current = state.stream.current();
@@ -274,7 +280,7 @@ pub fn full_type_name(state: &mut State) -> ParseResult<SimpleIdentifier> {
 
             Ok(SimpleIdentifier {
                 span,
-                value: current.value.clone(),
+                value: current.value.to_byte_string(),
             })
         }
         TokenKind::Enum | TokenKind::From => {
@@ -333,7 +339,7 @@ pub fn full_type_name_including_self(state: &mut State) -> ParseResult<SimpleIde
 
             Ok(SimpleIdentifier {
                 span,
-                value: current.value.clone(),
+                value: current.value.to_byte_string(),
             })
         }
         TokenKind::Enum
@@ -386,7 +392,7 @@ pub fn identifier_maybe_soft_reserved(state: &mut State) -> ParseResult<SimpleId
     let current = state.stream.current();
 
     if is_soft_reserved_identifier(&current.kind) {
-        let name = current.value.clone().into();
+        let name = current.value.to_byte_string();
         let span = current.span;
         state.stream.next();
 
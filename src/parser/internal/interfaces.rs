@@ -15,6 +15,8 @@ use crate::parser::internal::identifiers;
 use crate::parser::internal::modifiers;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::symbols;
+use crate::parser::symbols::SymbolKind;
 
 pub fn parse(state: &mut State) -> ParseResult<Statement> {
     let span = utils::skip(state, TokenKind::Interface)?;
@@ -42,11 +44,13 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
 
     let attributes = state.get_attributes();
 
+    symbols::enter(state, SymbolKind::Interface, &name.value);
     let body = InterfaceBody {
         left_brace: utils::skip_left_brace(state)?,
         members: {
             let mut members = Vec::new();
-            while state.stream.current().kind != TokenKind::RightBrace {
+            while state.stream.current().kind != TokenKind::RightBrace && !state.stream.is_eof() {
+                state.check_cancelled()?;
                 members.push(member(state, &name)?);
             }
 
@@ -54,8 +58,13 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
         },
         right_brace: utils::skip_right_brace(state)?,
     };
+    symbols::exit(state);
+
+    let start = symbols::start(&attributes, span);
+    symbols::emit(state, SymbolKind::Interface, &name.value, name.span, start);
 
     Ok(Statement::Interface(InterfaceStatement {
+        doc: state.stream.doc_comment(start.position),
         interface: span,
         name,
         attributes,
@@ -65,6 +74,8 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
 }
 
 fn member(state: &mut State, interface_name: &SimpleIdentifier) -> ParseResult<InterfaceMember> {
+    state.stream.forget_enclosed_comments();
+
     attributes::gather_attributes(state)?;
 
     let modifiers = modifiers::collect(state)?;
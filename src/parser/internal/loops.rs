@@ -1,3 +1,4 @@
+use crate::lexer::token::Span;
 use crate::lexer::token::Token;
 use crate::lexer::token::TokenKind;
 use crate::parser;
@@ -14,9 +15,12 @@ use crate::parser::ast::loops::ForeachStatementIterator;
 use crate::parser::ast::loops::Level;
 use crate::parser::ast::loops::WhileStatement;
 use crate::parser::ast::loops::WhileStatementBody;
+use crate::parser::ast::Expression;
 use crate::parser::ast::Statement;
+use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::expressions;
+use crate::parser::internal::arrays;
 use crate::parser::internal::blocks;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
@@ -42,6 +46,13 @@ pub fn foreach_statement(state: &mut State) -> ParseResult<Statement> {
 
             let current = state.stream.current();
             if current.kind == TokenKind::DoubleArrow {
+                // The key can't be taken by reference, only the value.
+                if let Some(ampersand) = ampersand {
+                    state.record(error::cannot_assign_reference_to_non_referencable_value(
+                        ampersand,
+                    ));
+                }
+
                 state.stream.next();
                 let arrow = current.span;
 
@@ -56,6 +67,7 @@ pub fn foreach_statement(state: &mut State) -> ParseResult<Statement> {
                 let mut key = expressions::create(state)?;
 
                 std::mem::swap(&mut value, &mut key);
+                target(state, ampersand, &value);
 
                 Ok(ForeachStatementIterator::KeyAndValue {
                     expression,
@@ -66,6 +78,8 @@ pub fn foreach_statement(state: &mut State) -> ParseResult<Statement> {
                     value,
                 })
             } else {
+                target(state, ampersand, &value);
+
                 Ok(ForeachStatementIterator::Value {
                     expression,
                     r#as,
@@ -75,18 +89,25 @@ pub fn foreach_statement(state: &mut State) -> ParseResult<Statement> {
             }
         })?;
 
-    let body = if state.stream.current().kind == TokenKind::Colon {
-        ForeachStatementBody::Block {
-            colon: utils::skip_colon(state)?,
-            statements: blocks::multiple_statements_until(state, &TokenKind::EndForeach)?,
-            endforeach: utils::skip(state, TokenKind::EndForeach)?,
-            ending: utils::skip_ending(state)?,
-        }
-    } else {
-        ForeachStatementBody::Statement {
-            statement: parser::statement(state).map(Box::new)?,
-        }
-    };
+    let body = utils::breakable(state, &|state| {
+        Ok(if state.stream.current().kind == TokenKind::Colon {
+            let colon = utils::skip_colon(state)?;
+            let statements = blocks::alternative_statements(state, &[TokenKind::EndForeach])?;
+            let (endforeach, ending) =
+                blocks::skip_alternative_end(state, colon, &TokenKind::EndForeach)?;
+
+            ForeachStatementBody::Block {
+                colon,
+                statements,
+                endforeach,
+                ending,
+            }
+        } else {
+            ForeachStatementBody::Statement {
+                statement: blocks::body_statement(state, &TokenKind::EndForeach).map(Box::new)?,
+            }
+        })
+    })?;
 
     Ok(Statement::Foreach(ForeachStatement {
         foreach,
@@ -97,6 +118,18 @@ pub fn foreach_statement(state: &mut State) -> ParseResult<Statement> {
     }))
 }
 
+/// Check the value that a `foreach` assigns to, which is destructured like the target of
+/// an assignment when it is a pattern, and can only be taken by reference when it isn't.
+fn target(state: &mut State, ampersand: Option<Span>, value: &Expression) {
+    if let Some(ampersand) = ampersand {
+        if matches!(value, Expression::ShortArray(_) | Expression::List(_)) {
+            state.record(error::pattern_by_reference(ampersand));
+        }
+    }
+
+    arrays::destructuring(state, value);
+}
+
 pub fn for_statement(state: &mut State) -> ParseResult<Statement> {
     let r#for = utils::skip(state, TokenKind::For)?;
 
@@ -127,18 +160,24 @@ pub fn for_statement(state: &mut State) -> ParseResult<Statement> {
         })
     })?;
 
-    let body = if state.stream.current().kind == TokenKind::Colon {
-        ForStatementBody::Block {
-            colon: utils::skip_colon(state)?,
-            statements: blocks::multiple_statements_until(state, &TokenKind::EndFor)?,
-            endfor: utils::skip(state, TokenKind::EndFor)?,
-            ending: utils::skip_ending(state)?,
-        }
-    } else {
-        ForStatementBody::Statement {
-            statement: parser::statement(state).map(Box::new)?,
-        }
-    };
+    let body = utils::breakable(state, &|state| {
+        Ok(if state.stream.current().kind == TokenKind::Colon {
+            let colon = utils::skip_colon(state)?;
+            let statements = blocks::alternative_statements(state, &[TokenKind::EndFor])?;
+            let (endfor, ending) = blocks::skip_alternative_end(state, colon, &TokenKind::EndFor)?;
+
+            ForStatementBody::Block {
+                colon,
+                statements,
+                endfor,
+                ending,
+            }
+        } else {
+            ForStatementBody::Statement {
+                statement: blocks::body_statement(state, &TokenKind::EndFor).map(Box::new)?,
+            }
+        })
+    })?;
 
     Ok(Statement::For(ForStatement {
         r#for,
@@ -152,7 +191,7 @@ pub fn for_statement(state: &mut State) -> ParseResult<Statement> {
 pub fn do_while_statement(state: &mut State) -> ParseResult<Statement> {
     let r#do = utils::skip(state, TokenKind::Do)?;
 
-    let body = parser::statement(state).map(Box::new)?;
+    let body = utils::breakable(state, &|state| parser::statement(state).map(Box::new))?;
 
     let r#while = utils::skip(state, TokenKind::While)?;
 
@@ -178,18 +217,25 @@ pub fn while_statement(state: &mut State) -> ParseResult<Statement> {
     let (left_parenthesis, condition, right_parenthesis) =
         utils::parenthesized(state, &expressions::create)?;
 
-    let body = if state.stream.current().kind == TokenKind::Colon {
-        WhileStatementBody::Block {
-            colon: utils::skip_colon(state)?,
-            statements: blocks::multiple_statements_until(state, &TokenKind::EndWhile)?,
-            endwhile: utils::skip(state, TokenKind::EndWhile)?,
-            ending: utils::skip_ending(state)?,
-        }
-    } else {
-        WhileStatementBody::Statement {
-            statement: parser::statement(state).map(Box::new)?,
-        }
-    };
+    let body = utils::breakable(state, &|state| {
+        Ok(if state.stream.current().kind == TokenKind::Colon {
+            let colon = utils::skip_colon(state)?;
+            let statements = blocks::alternative_statements(state, &[TokenKind::EndWhile])?;
+            let (endwhile, ending) =
+                blocks::skip_alternative_end(state, colon, &TokenKind::EndWhile)?;
+
+            WhileStatementBody::Block {
+                colon,
+                statements,
+                endwhile,
+                ending,
+            }
+        } else {
+            WhileStatementBody::Statement {
+                statement: blocks::body_statement(state, &TokenKind::EndWhile).map(Box::new)?,
+            }
+        })
+    })?;
 
     Ok(Statement::While(WhileStatement {
         r#while,
@@ -201,50 +247,85 @@ pub fn while_statement(state: &mut State) -> ParseResult<Statement> {
 }
 
 pub fn continue_statement(state: &mut State) -> ParseResult<Statement> {
+    let r#continue = utils::skip(state, TokenKind::Continue)?;
+
     Ok(Statement::Continue(ContinueStatement {
-        r#continue: utils::skip(state, TokenKind::Continue)?,
-        level: maybe_loop_level(state)?,
+        r#continue,
+        level: maybe_loop_level(state, "continue", r#continue)?,
         ending: utils::skip_ending(state)?,
     }))
 }
 
 pub fn break_statement(state: &mut State) -> ParseResult<Statement> {
+    let r#break = utils::skip(state, TokenKind::Break)?;
+
     Ok(Statement::Break(BreakStatement {
-        r#break: utils::skip(state, TokenKind::Break)?,
-        level: maybe_loop_level(state)?,
+        r#break,
+        level: maybe_loop_level(state, "break", r#break)?,
         ending: utils::skip_ending(state)?,
     }))
 }
 
-fn maybe_loop_level(state: &mut State) -> ParseResult<Option<Level>> {
+fn maybe_loop_level(state: &mut State, keyword: &str, span: Span) -> ParseResult<Option<Level>> {
     let current = &state.stream.current().kind;
 
-    Ok(
-        if current == &TokenKind::SemiColon || current == &TokenKind::CloseTag {
-            None
-        } else {
-            Some(loop_level(state)?)
-        },
-    )
+    let level = if current == &TokenKind::SemiColon || current == &TokenKind::CloseTag {
+        None
+    } else {
+        Some(loop_level(state, keyword)?)
+    };
+
+    // `break` and `continue` without a level target the innermost loop or `switch`.
+    let requested = match &level {
+        Some(level) => level.value().unwrap_or(i64::MAX),
+        None => 1,
+    };
+
+    if state.loop_depth == 0 {
+        return Err(error::break_outside_of_loop(keyword, span));
+    }
+
+    if requested > state.loop_depth as i64 {
+        return Err(error::break_level_too_high(
+            keyword,
+            requested,
+            state.loop_depth,
+            span,
+        ));
+    }
+
+    Ok(level)
 }
 
-fn loop_level(state: &mut State) -> ParseResult<Level> {
+fn loop_level(state: &mut State, keyword: &str) -> ParseResult<Level> {
+    let current = state.stream.current();
+
     if let Token {
         kind: TokenKind::LiteralInteger,
         span,
         value,
-    } = state.stream.current()
+    } = current
     {
+        let literal = LiteralInteger {
+            value: value.clone().into(),
+            span: *span,
+        };
+
+        if literal.to_i64() == Some(0) {
+            return Err(error::invalid_loop_level(keyword, current));
+        }
+
         state.stream.next();
 
-        return Ok(Level::Literal(LiteralInteger {
-            value: value.clone(),
-            span: *span,
-        }));
+        return Ok(Level::Literal(literal));
+    }
+
+    if current.kind != TokenKind::LeftParen {
+        return Err(error::invalid_loop_level(keyword, current));
     }
 
     let (left_parenthesis, level, right_parenthesis) =
-        utils::parenthesized(state, &|state| loop_level(state).map(Box::new))?;
+        utils::parenthesized(state, &|state| loop_level(state, keyword).map(Box::new))?;
 
     Ok(Level::Parenthesized {
         left_parenthesis,
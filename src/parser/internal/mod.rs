@@ -14,8 +14,10 @@ pub(in crate::parser) mod loops;
 pub(in crate::parser) mod modifiers;
 pub(in crate::parser) mod namespaces;
 pub(in crate::parser) mod parameters;
+pub(in crate::parser) mod policy;
 pub(in crate::parser) mod precedences;
 pub(in crate::parser) mod properties;
+pub(in crate::parser) mod recovery;
 pub(in crate::parser) mod strings;
 pub(in crate::parser) mod traits;
 pub(in crate::parser) mod try_block;
@@ -98,7 +98,7 @@ pub fn method_group(input: Vec<(Span, TokenKind)>) -> ParseResult<MethodModifier
         })
         .collect::<ParseResult<Vec<MethodModifier>>>()?;
 
-    Ok(MethodModifierGroup { modifiers })
+    Ok(MethodModifierGroup::new(modifiers))
 }
 
 #[inline(always)]
@@ -115,7 +115,7 @@ pub fn interface_method_group(input: Vec<(Span, TokenKind)>) -> ParseResult<Meth
         })
         .collect::<ParseResult<Vec<MethodModifier>>>()?;
 
-    Ok(MethodModifierGroup { modifiers })
+    Ok(MethodModifierGroup::new(modifiers))
 }
 
 #[inline(always)]
@@ -135,7 +135,7 @@ pub fn enum_method_group(input: Vec<(Span, TokenKind)>) -> ParseResult<MethodMod
         })
         .collect::<ParseResult<Vec<MethodModifier>>>()?;
 
-    Ok(MethodModifierGroup { modifiers })
+    Ok(MethodModifierGroup::new(modifiers))
 }
 
 #[inline(always)]
@@ -155,7 +155,7 @@ pub fn property_group(input: Vec<(Span, TokenKind)>) -> ParseResult<PropertyModi
         })
         .collect::<ParseResult<Vec<PropertyModifier>>>()?;
 
-    Ok(PropertyModifierGroup { modifiers })
+    Ok(PropertyModifierGroup::new(modifiers))
 }
 
 #[inline(always)]
@@ -195,7 +195,7 @@ pub fn constant_group(input: Vec<(Span, TokenKind)>) -> ParseResult<ConstantModi
                         final_span, *span,
                     ))
                 } else {
-                    Ok(ConstantModifier::Final(*span))
+                    Ok(ConstantModifier::Private(*span))
                 }
             }
             TokenKind::Final => {
@@ -216,7 +216,7 @@ pub fn constant_group(input: Vec<(Span, TokenKind)>) -> ParseResult<ConstantModi
         })
         .collect::<ParseResult<Vec<ConstantModifier>>>()?;
 
-    Ok(ConstantModifierGroup { modifiers })
+    Ok(ConstantModifierGroup::new(modifiers))
 }
 
 pub fn interface_constant_group(
@@ -234,7 +234,7 @@ pub fn interface_constant_group(
         })
         .collect::<ParseResult<Vec<ConstantModifier>>>()?;
 
-    Ok(ConstantModifierGroup { modifiers })
+    Ok(ConstantModifierGroup::new(modifiers))
 }
 
 pub fn collect(state: &mut State) -> ParseResult<Vec<(Span, TokenKind)>> {
@@ -1,11 +1,16 @@
+use crate::lexer::token::KindId;
 use crate::lexer::token::TokenKind;
 use crate::parser::ast::arguments::{Argument, SingleArgument};
 use crate::parser::ast::arguments::{ArgumentList, NamedArgument, PositionalArgument};
+use crate::parser::ast::data_type::Type;
 use crate::parser::ast::functions::ConstructorParameter;
 use crate::parser::ast::functions::ConstructorParameterList;
 use crate::parser::ast::functions::FunctionParameter;
 use crate::parser::ast::functions::FunctionParameterList;
 use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::utils::CommaSeparated;
+use crate::parser::ast::variables::SimpleVariable;
+use crate::parser::ast::Expression;
 use crate::parser::error;
 use crate::parser::error::ParseError;
 use crate::parser::error::ParseResult;
@@ -17,6 +22,7 @@ use crate::parser::internal::modifiers;
 use crate::parser::internal::utils;
 use crate::parser::internal::variables;
 use crate::parser::state::State;
+use crate::parser::version::Feature;
 
 pub fn function_parameter_list(state: &mut State) -> Result<FunctionParameterList, ParseError> {
     let comments = state.stream.comments();
@@ -26,10 +32,18 @@ pub fn function_parameter_list(state: &mut State) -> Result<FunctionParameterLis
         &|state| {
             attributes::gather_attributes(state)?;
 
+            let current = state.stream.current();
+            if matches!(
+                current.kind,
+                TokenKind::Public | TokenKind::Protected | TokenKind::Private | TokenKind::Readonly
+            ) {
+                return Err(error::promoted_property_outside_constructor(current));
+            }
+
             let ty = data_type::optional_data_type(state)?;
 
             let mut current = state.stream.current();
-            let ampersand = if current.kind == TokenKind::Ampersand {
+            let ampersand = if current.kind.kind_id() == KindId::Ampersand {
                 state.stream.next();
                 current = state.stream.current();
                 Some(current.span)
@@ -37,7 +51,7 @@ pub fn function_parameter_list(state: &mut State) -> Result<FunctionParameterLis
                 None
             };
 
-            let ellipsis = if current.kind == TokenKind::Ellipsis {
+            let ellipsis = if current.kind.kind_id() == KindId::Ellipsis {
                 state.stream.next();
 
                 Some(current.span)
@@ -48,10 +62,14 @@ pub fn function_parameter_list(state: &mut State) -> Result<FunctionParameterLis
             // 2. Then expect a variable.
             let var = variables::simple_variable(state)?;
 
+            if let Some(ty) = &ty {
+                forbidden_type(state, &var, ty);
+            }
+
             let mut default = None;
-            if state.stream.current().kind == TokenKind::Equals {
+            if state.stream.current().kind.kind_id() == KindId::Equals {
                 state.stream.next();
-                default = Some(expressions::create(state)?);
+                default = Some(default_value(state)?);
             }
 
             Ok(FunctionParameter {
@@ -67,6 +85,8 @@ pub fn function_parameter_list(state: &mut State) -> Result<FunctionParameterLis
         TokenKind::RightParen,
     )?;
 
+    trailing_comma(state, &parameters);
+
     let right_parenthesis = utils::skip_right_parenthesis(state)?;
 
     Ok(FunctionParameterList {
@@ -77,6 +97,16 @@ pub fn function_parameter_list(state: &mut State) -> Result<FunctionParameterLis
     })
 }
 
+/// `void`, `never` and `static` are only allowed as return types.
+fn forbidden_type(state: &mut State, parameter: &SimpleVariable, ty: &Type) {
+    if ty.is_bottom() || ty.includes_static() {
+        state.record(error::forbidden_type_used_in_parameter(
+            parameter,
+            ty.clone(),
+        ));
+    }
+}
+
 pub fn constructor_parameter_list(
     state: &mut State,
     class: Option<&SimpleIdentifier>,
@@ -91,6 +121,18 @@ pub fn constructor_parameter_list(
 
             let modifiers = modifiers::promoted_property_group(modifiers::collect(state)?)?;
 
+            if let Some(modifier) = modifiers.modifiers.first() {
+                state.require(
+                    Feature::ConstructorPromotion,
+                    modifier.span(),
+                    modifier.to_string().len(),
+                );
+            }
+
+            if let Some(modifier) = modifiers.get_readonly() {
+                state.require(Feature::ReadonlyProperties, modifier.span(), 8);
+            }
+
             let ty = data_type::optional_data_type(state)?;
 
             let mut current = state.stream.current();
@@ -124,10 +166,14 @@ pub fn constructor_parameter_list(
 
             // 2. Then expect a variable.
 
-            if !modifiers.is_empty() {
+            if modifiers.is_empty() {
+                if let Some(ty) = &ty {
+                    forbidden_type(state, &var, ty);
+                }
+            } else {
                 match &ty {
                     Some(ty) => {
-                        if ty.includes_callable() || ty.is_bottom() {
+                        if ty.includes_callable() || ty.is_bottom() || ty.includes_static() {
                             return Err(error::forbidden_type_used_in_property(
                                 state,
                                 class,
@@ -150,9 +196,9 @@ pub fn constructor_parameter_list(
             }
 
             let mut default = None;
-            if state.stream.current().kind == TokenKind::Equals {
+            if state.stream.current().kind.kind_id() == KindId::Equals {
                 state.stream.next();
-                default = Some(expressions::create(state)?);
+                default = Some(default_value(state)?);
             }
 
             Ok(ConstructorParameter {
@@ -169,6 +215,8 @@ pub fn constructor_parameter_list(
         TokenKind::RightParen,
     )?;
 
+    trailing_comma(state, &parameters);
+
     let right_parenthesis = utils::skip_right_parenthesis(state)?;
 
     Ok(ConstructorParameterList {
@@ -180,28 +228,46 @@ pub fn constructor_parameter_list(
 }
 
 pub fn argument_list(state: &mut State) -> ParseResult<ArgumentList> {
+    // Only the comments between the callee and the `(` belong to the arguments.
+    state.stream.forget_enclosed_comments();
     let comments = state.stream.comments();
     let start = utils::skip_left_parenthesis(state)?;
 
     let mut arguments = Vec::new();
     let mut has_used_named_arguments = false;
 
-    while !state.stream.is_eof() && state.stream.current().kind != TokenKind::RightParen {
+    while !state.stream.is_eof() && state.stream.current().kind.kind_id() != KindId::RightParen {
+        state.check_cancelled()?;
+
         let span = state.stream.current().span;
         let (named, argument) = argument(state)?;
         if named {
             has_used_named_arguments = true;
         } else if has_used_named_arguments {
+            let unpacking = matches!(
+                argument,
+                Argument::Positional(PositionalArgument {
+                    ellipsis: Some(_),
+                    ..
+                })
+            );
+
             return Err(error::cannot_use_positional_argument_after_named_argument(
                 span,
                 state.stream.current().span,
+                unpacking,
             ));
         }
 
         arguments.push(argument);
 
-        if state.stream.current().kind == TokenKind::Comma {
+        if state.stream.current().kind.kind_id() == KindId::Comma {
+            let comma = state.stream.current().span;
             state.stream.next();
+
+            if state.stream.current().kind.kind_id() == KindId::RightParen {
+                state.require(Feature::TrailingCommaInArguments, comma, 1);
+            }
         } else {
             break;
         }
@@ -222,12 +288,13 @@ pub fn single_argument(
     required: bool,
     only_positional: bool,
 ) -> Option<ParseResult<SingleArgument>> {
+    state.stream.forget_enclosed_comments();
     let comments = state.stream.comments();
     let start = utils::skip_left_parenthesis(state).ok()?;
 
     let mut first_argument = None;
 
-    while !state.stream.is_eof() && state.stream.current().kind != TokenKind::RightParen {
+    while !state.stream.is_eof() && state.stream.current().kind.kind_id() != KindId::RightParen {
         let span = state.stream.current().span;
         let (named, argument) = argument(state).ok()?;
         if only_positional && named {
@@ -246,7 +313,7 @@ pub fn single_argument(
 
         first_argument = Some(argument);
 
-        if state.stream.current().kind == TokenKind::Comma {
+        if state.stream.current().kind.kind_id() == KindId::Comma {
             state.stream.next();
         } else {
             break;
@@ -272,13 +339,35 @@ pub fn single_argument(
     }))
 }
 
+/// The default value of a parameter.
+fn default_value(state: &mut State) -> ParseResult<Expression> {
+    let value = expressions::create(state)?;
+
+    if let Expression::New(new) = &value {
+        state.require(Feature::NewInInitializers, new.new, 3);
+    }
+
+    Ok(value)
+}
+
+/// Check the trailing comma of a parameter list, or of the `use` list of a closure.
+pub fn trailing_comma<T>(state: &mut State, parameters: &CommaSeparated<T>) {
+    if parameters.has_trailing_comma() {
+        if let Some(comma) = parameters.commas.last() {
+            state.require(Feature::TrailingCommaInParameters, *comma, 1);
+        }
+    }
+}
+
 fn argument(state: &mut State) -> ParseResult<(bool, Argument)> {
     if identifiers::is_identifier_maybe_reserved(&state.stream.current().kind)
-        && state.stream.peek().kind == TokenKind::Colon
+        && state.stream.peek().kind.kind_id() == KindId::Colon
     {
         let name = identifiers::identifier_maybe_reserved(state)?;
+        state.require(Feature::NamedArguments, name.span, name.value.len());
+
         let colon = utils::skip(state, TokenKind::Colon)?;
-        let ellipsis = if state.stream.current().kind == TokenKind::Ellipsis {
+        let ellipsis = if state.stream.current().kind.kind_id() == KindId::Ellipsis {
             Some(utils::skip(state, TokenKind::Ellipsis)?)
         } else {
             None
@@ -297,8 +386,16 @@ fn argument(state: &mut State) -> ParseResult<(bool, Argument)> {
         ));
     }
 
-    let ellipsis = if state.stream.current().kind == TokenKind::Ellipsis {
-        Some(utils::skip(state, TokenKind::Ellipsis)?)
+    let ellipsis = if state.stream.current().kind.kind_id() == KindId::Ellipsis {
+        let ellipsis = utils::skip(state, TokenKind::Ellipsis)?;
+        if matches!(
+            state.stream.current().kind,
+            TokenKind::Comma | TokenKind::RightParen
+        ) {
+            return Err(error::misplaced_argument_placeholder(ellipsis));
+        }
+
+        Some(ellipsis)
     } else {
         None
     };
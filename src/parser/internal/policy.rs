@@ -0,0 +1,120 @@
+use crate::lexer::token::Span;
+use crate::lexer::token::Token;
+use crate::lexer::token::TokenKind;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::Expression;
+use crate::parser::error;
+use crate::parser::error::ParseResult;
+use crate::parser::policy::CallPolicy;
+use crate::parser::state::State;
+
+/// Check a token that starts, or joins, a construct the expression policy may forbid.
+pub fn token(state: &State, token: &Token) -> ParseResult<()> {
+    let policy = match &state.policy {
+        Some(policy) => policy,
+        None => return Ok(()),
+    };
+
+    let (allowed, construct) = match &token.kind {
+        TokenKind::Backtick => (policy.shell_exec, "shell exec".to_string()),
+        TokenKind::Include
+        | TokenKind::IncludeOnce
+        | TokenKind::Require
+        | TokenKind::RequireOnce => (policy.include, format!("`{}`", token.value)),
+        TokenKind::New => (policy.new, "`new`".to_string()),
+        TokenKind::Eval | TokenKind::Die | TokenKind::Exit | TokenKind::Print => (
+            policy.allows_call(&token.value),
+            format!("calling `{}`", token.value),
+        ),
+        TokenKind::Increment
+        | TokenKind::Decrement
+        | TokenKind::Equals
+        | TokenKind::PlusEquals
+        | TokenKind::MinusEquals
+        | TokenKind::AsteriskEquals
+        | TokenKind::PowEquals
+        | TokenKind::SlashEquals
+        | TokenKind::DotEquals
+        | TokenKind::AndEquals
+        | TokenKind::DoubleQuestionEquals
+        | TokenKind::PercentEquals
+        | TokenKind::AmpersandEquals
+        | TokenKind::PipeEquals
+        | TokenKind::CaretEquals
+        | TokenKind::LeftShiftEquals
+        | TokenKind::RightShiftEquals => (policy.assignment, "assignment".to_string()),
+        _ => return Ok(()),
+    };
+
+    if allowed {
+        return Ok(());
+    }
+
+    Err(error::policy_violation(
+        construct,
+        token.span,
+        token.value.len(),
+    ))
+}
+
+/// Check a call expression, once its target is known.
+pub fn call(state: &State, expression: &Expression) -> ParseResult<()> {
+    let policy = match &state.policy {
+        Some(policy) => policy,
+        None => return Ok(()),
+    };
+
+    let (name, span, length) = match expression {
+        Expression::FunctionCall(call) => match call.target.as_ref() {
+            Expression::Identifier(Identifier::SimpleIdentifier(name)) => {
+                (Some(&name.value), name.span, name.value.len())
+            }
+            _ => (None, call.arguments.left_parenthesis, 1),
+        },
+        Expression::FunctionClosureCreation(creation) => match creation.target.as_ref() {
+            Expression::Identifier(Identifier::SimpleIdentifier(name)) => {
+                (Some(&name.value), name.span, name.value.len())
+            }
+            _ => (None, creation.placeholder.left_parenthesis, 1),
+        },
+        Expression::MethodCall(call) => (None, call.arrow, 2),
+        Expression::MethodClosureCreation(creation) => (None, creation.arrow, 2),
+        Expression::NullsafeMethodCall(call) => (None, call.question_arrow, 3),
+        Expression::StaticMethodCall(call) => (None, call.double_colon, 2),
+        Expression::StaticMethodClosureCreation(creation) => (None, creation.double_colon, 2),
+        Expression::StaticVariableMethodCall(call) => (None, call.double_colon, 2),
+        Expression::StaticVariableMethodClosureCreation(creation) => {
+            (None, creation.double_colon, 2)
+        }
+        _ => return Ok(()),
+    };
+
+    let (allowed, construct) = match name {
+        Some(name) => (policy.allows_call(name), format!("calling `{}`", name)),
+        None => (
+            matches!(policy.calls, CallPolicy::All),
+            match expression {
+                Expression::FunctionCall(_) | Expression::FunctionClosureCreation(_) => {
+                    "calling a dynamic function name".to_string()
+                }
+                _ => "calling a method".to_string(),
+            },
+        ),
+    };
+
+    if allowed {
+        return Ok(());
+    }
+
+    Err(error::policy_violation(construct, span, length))
+}
+
+/// Check a variable, given the span of its first token.
+pub fn variable(state: &State, span: Span, length: usize) -> ParseResult<()> {
+    match &state.policy {
+        Some(policy) if !policy.variables => {
+            Err(error::policy_violation("using a variable", span, length))
+        }
+        _ => Ok(()),
+    }
+}
@@ -1,9 +1,17 @@
+use crate::lexer::token::Span;
 use crate::lexer::token::TokenKind;
+use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::data_type::Type;
 use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::literals::Literal;
 use crate::parser::ast::modifiers::PropertyModifierGroup;
+use crate::parser::ast::operators::ArithmeticOperationExpression;
 use crate::parser::ast::properties::Property;
 use crate::parser::ast::properties::PropertyEntry;
 use crate::parser::ast::properties::VariableProperty;
+use crate::parser::ast::variables::SimpleVariable;
+use crate::parser::ast::BoolExpression;
+use crate::parser::ast::Expression;
 use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::expressions;
@@ -11,14 +19,29 @@ use crate::parser::internal::data_type;
 use crate::parser::internal::utils;
 use crate::parser::internal::variables;
 use crate::parser::state::State;
+use crate::parser::symbols;
+use crate::parser::symbols::SymbolKind;
+use crate::parser::version::Feature;
 
 pub fn parse(
     state: &mut State,
     class_name: Option<&SimpleIdentifier>,
     modifiers: PropertyModifierGroup,
 ) -> ParseResult<Property> {
+    if let Some(modifier) = modifiers.get_readonly() {
+        state.require(Feature::ReadonlyProperties, modifier.span(), 8);
+    }
+
     let ty = data_type::optional_data_type(state)?;
 
+    if let Some(ty) = &ty {
+        state.require(
+            Feature::TypedProperties,
+            ty.first_span(),
+            ty.to_string().len(),
+        );
+    }
+
     let mut entries = vec![];
     let mut type_checked = false;
     loop {
@@ -40,7 +63,7 @@ pub fn parse(
 
             match &ty {
                 Some(ty) => {
-                    if ty.includes_callable() || ty.is_bottom() {
+                    if ty.includes_callable() || ty.is_bottom() || ty.includes_static() {
                         let error = error::forbidden_type_used_in_property(
                             state,
                             class_name,
@@ -81,8 +104,13 @@ pub fn parse(
             }
 
             state.stream.next();
+            let start = state.stream.current().span;
             let value = expressions::create(state)?;
 
+            if let Some(ty) = &ty {
+                default_value(state, class_name, &variable, ty, &value, start);
+            }
+
             entries.push(PropertyEntry::Initialized {
                 variable,
                 equals: current.span,
@@ -100,11 +128,22 @@ pub fn parse(
     }
 
     let end = utils::skip_semicolon(state)?;
+    let attributes = state.get_attributes();
+
+    let first = match (modifiers.modifiers.first(), &ty) {
+        (Some(modifier), _) => modifier.span(),
+        (None, Some(ty)) => ty.first_span(),
+        (None, None) => entries[0].variable().span,
+    };
+    emit(state, &attributes, first, &entries);
 
     Ok(Property {
+        doc: state
+            .stream
+            .doc_comment(symbols::start(&attributes, first).position),
         r#type: ty,
         modifiers,
-        attributes: state.get_attributes(),
+        attributes,
         entries,
         end,
     })
@@ -114,10 +153,18 @@ pub fn parse_var(
     state: &mut State,
     class_name: Option<&SimpleIdentifier>,
 ) -> ParseResult<VariableProperty> {
-    utils::skip(state, TokenKind::Var)?;
+    let var = utils::skip(state, TokenKind::Var)?;
 
     let ty = data_type::optional_data_type(state)?;
 
+    if let Some(ty) = &ty {
+        state.require(
+            Feature::TypedProperties,
+            ty.first_span(),
+            ty.to_string().len(),
+        );
+    }
+
     let mut entries = vec![];
     let mut type_checked = false;
     loop {
@@ -127,7 +174,7 @@ pub fn parse_var(
             type_checked = true;
 
             if let Some(ty) = &ty {
-                if ty.includes_callable() || ty.is_bottom() {
+                if ty.includes_callable() || ty.is_bottom() || ty.includes_static() {
                     let error = error::forbidden_type_used_in_property(
                         state,
                         class_name,
@@ -144,8 +191,13 @@ pub fn parse_var(
         if current.kind == TokenKind::Equals {
             let span = current.span;
             state.stream.next();
+            let start = state.stream.current().span;
             let value = expressions::create(state)?;
 
+            if let Some(ty) = &ty {
+                default_value(state, class_name, &variable, ty, &value, start);
+            }
+
             entries.push(PropertyEntry::Initialized {
                 variable,
                 equals: span,
@@ -163,11 +215,125 @@ pub fn parse_var(
     }
 
     let end = utils::skip_semicolon(state)?;
+    let attributes = state.get_attributes();
+
+    emit(state, &attributes, var, &entries);
 
     Ok(VariableProperty {
+        doc: state
+            .stream
+            .doc_comment(symbols::start(&attributes, var).position),
         r#type: ty,
-        attributes: state.get_attributes(),
+        attributes,
         entries,
         end,
     })
 }
+
+/// Report each property of a declaration that starts with `first`, the span of its
+/// first modifier, type or variable.
+fn emit(state: &State, attributes: &[AttributeGroup], first: Span, entries: &[PropertyEntry]) {
+    let start = symbols::start(attributes, first);
+
+    for entry in entries {
+        let variable = entry.variable();
+
+        symbols::emit(
+            state,
+            SymbolKind::Property,
+            &variable.name,
+            variable.span,
+            start,
+        );
+    }
+}
+
+/// Check that a literal default value is compatible with the type of the property.
+fn default_value(
+    state: &mut State,
+    class_name: Option<&SimpleIdentifier>,
+    variable: &SimpleVariable,
+    ty: &Type,
+    value: &Expression,
+    start: Span,
+) {
+    let value_type = match literal_type(value) {
+        Some(value_type) => value_type,
+        None => return,
+    };
+
+    if accepts(ty, value_type) != Some(false) {
+        return;
+    }
+
+    let end = state.stream.previous();
+    let length = end.span.position + end.value.len() - start.position;
+
+    let error = if value_type == "null" {
+        error::null_default_value_for_non_nullable_property(
+            state, class_name, variable, ty, start, length,
+        )
+    } else {
+        let value_type = match value_type {
+            "true" | "false" => "bool",
+            value_type => value_type,
+        };
+
+        error::default_value_type_mismatch_for_property(
+            state, class_name, variable, ty, value_type, start, length,
+        )
+    };
+
+    state.record(error);
+}
+
+/// The type of a literal value, with `true` and `false` kept separate from each other.
+fn literal_type(value: &Expression) -> Option<&'static str> {
+    Some(match value {
+        Expression::Null(_) => "null",
+        Expression::Bool(BoolExpression { value: true, .. }) => "true",
+        Expression::Bool(BoolExpression { value: false, .. }) => "false",
+        Expression::Literal(Literal::Integer(_)) => "int",
+        Expression::Literal(Literal::Float(_)) => "float",
+        Expression::Literal(Literal::String(_)) => "string",
+        Expression::ShortArray(_) | Expression::Array(_) => "array",
+        Expression::ArithmeticOperation(
+            ArithmeticOperationExpression::Negative { right, .. }
+            | ArithmeticOperationExpression::Positive { right, .. },
+        ) => match literal_type(right)? {
+            "int" => "int",
+            "float" => "float",
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// Whether the type accepts a literal of the given type, or `None` if that
+/// can't be known without looking at the rest of the program.
+fn accepts(ty: &Type, value_type: &str) -> Option<bool> {
+    Some(match ty {
+        Type::Nullable(_, inner) => value_type == "null" || accepts(inner, value_type)?,
+        Type::Union(types) => {
+            let mut accepted = false;
+            for ty in types {
+                accepted |= accepts(ty, value_type)?;
+            }
+
+            accepted
+        }
+        Type::Mixed(_) => true,
+        Type::Null(_) => value_type == "null",
+        Type::True(_) => value_type == "true",
+        Type::False(_) => value_type == "false",
+        Type::Boolean(_) => matches!(value_type, "true" | "false"),
+        Type::Integer(_) => value_type == "int",
+        // Integers are implicitly converted to floats.
+        Type::Float(_) => matches!(value_type, "int" | "float"),
+        Type::String(_) => value_type == "string",
+        Type::Array(_) | Type::Iterable(_) => value_type == "array",
+        // Whether a type is nullable is always known, even for class types.
+        _ if value_type == "null" => false,
+        _ => return None,
+    })
+}
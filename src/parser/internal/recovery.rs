@@ -0,0 +1,372 @@
+use crate::lexer::token::Span;
+use crate::lexer::token::TokenKind;
+use crate::parser::ast::Expression;
+use crate::parser::ast::Statement;
+use crate::parser::error;
+use crate::parser::error::ParseError;
+use crate::parser::error::ParseResult;
+use crate::parser::state::State;
+
+/// Where the tokens that follow an error are skipped to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Boundary {
+    /// The `;` that ends the statement, which is skipped too, the `}` of the block
+    /// that contains it, or the keyword that starts the next statement.
+    Statement,
+    /// The `;`, `,` or closing delimiter that follows the expression, which isn't
+    /// skipped, so that the construct that contains it can carry on.
+    Expression,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Delimiter {
+    Parenthesis,
+    Bracket,
+    Brace,
+}
+
+/// Parse a statement with `parse`, or when recovering from errors, record the error it
+/// fails with and skip to the end of the statement, which is replaced by a
+/// [`Statement::Error`].
+pub fn statement(
+    state: &mut State,
+    parse: fn(&mut State) -> ParseResult<Statement>,
+) -> ParseResult<Statement> {
+    state.check_cancelled()?;
+
+    let start = state.stream.current().span;
+    let foreign_syntax = |state: &State, error: ParseError| {
+        let tokens = state.stream.consumed_since(start.position);
+
+        error::foreign_syntax(error, tokens, state.stream.current())
+    };
+
+    if !state.recover {
+        return parse(state).map_err(|error| foreign_syntax(state, error));
+    }
+
+    let context = Context::save(state);
+
+    parse(state).or_else(|error| {
+        let error = foreign_syntax(state, error);
+        state.record(error);
+        context.restore(state);
+        synchronize(state, start, Boundary::Statement);
+
+        Ok(Statement::Error(start))
+    })
+}
+
+/// Parse an expression with `parse`, or when recovering from errors, record the error
+/// it fails with and skip to the end of the expression, which is replaced by an
+/// [`Expression::Error`].
+///
+/// An expression that fails at its first token isn't an expression at all, as in
+/// `else;`, and the error is left to the construct that contains it.
+pub fn expression(
+    state: &mut State,
+    parse: impl FnOnce(&mut State) -> ParseResult<Expression>,
+) -> ParseResult<Expression> {
+    if !state.recover {
+        return parse(state);
+    }
+
+    let start = state.stream.current().span;
+    let context = Context::save(state);
+
+    parse(state).or_else(|error| {
+        if state.stream.current().span == start {
+            return Err(error);
+        }
+
+        state.record(error);
+        context.restore(state);
+        synchronize(state, start, Boundary::Expression);
+
+        Ok(Expression::Error(start))
+    })
+}
+
+/// The parts of the state that a construct changes while it is parsed and puts back
+/// once it is done, which an error leaves changed.
+struct Context {
+    loop_depth: usize,
+    finally_depth: usize,
+    by_reference: bool,
+    scopes: usize,
+    containers: usize,
+}
+
+impl Context {
+    fn save(state: &State) -> Self {
+        Self {
+            loop_depth: state.loop_depth,
+            finally_depth: state.finally_depth,
+            by_reference: state.by_reference,
+            scopes: state.stack.len(),
+            containers: state.containers.len(),
+        }
+    }
+
+    fn restore(self, state: &mut State) {
+        state.loop_depth = self.loop_depth;
+        state.finally_depth = self.finally_depth;
+        state.by_reference = self.by_reference;
+        state.stack.truncate(self.scopes);
+        state.containers.truncate(self.containers);
+        state.attributes.clear();
+    }
+}
+
+/// Skip the tokens up to the `boundary` of a construct that starts at `start` and
+/// failed to parse.
+///
+/// The delimiters opened since `start` are closed first, though a `;` still ends a
+/// statement or an expression when only parentheses and brackets are open. At least
+/// one token is skipped when a statement fails at its first token, so that the
+/// parser always moves on.
+fn synchronize(state: &mut State, start: Span, boundary: Boundary) {
+    let mut open = Vec::new();
+    for token in state.stream.consumed_since(start.position) {
+        close(&mut open, &token.kind);
+    }
+
+    while !state.stream.is_eof() {
+        let kind = &state.stream.current().kind;
+        let in_block = open.contains(&Delimiter::Brace);
+
+        match kind {
+            TokenKind::SemiColon if !in_block => {
+                if boundary == Boundary::Statement {
+                    state.stream.next();
+                }
+
+                return;
+            }
+            TokenKind::CloseTag if !in_block => return,
+            TokenKind::Comma if open.is_empty() && boundary == Boundary::Expression => return,
+            _ if open.is_empty() && boundary == Boundary::Statement && starts_statement(kind) => {
+                break;
+            }
+            _ => {}
+        }
+
+        match closing(kind) {
+            Some(delimiter) if !open.contains(&delimiter) => {
+                // The delimiter closes a construct that contains this one, unless a
+                // statement failed at it, in which case it is stray.
+                if boundary == Boundary::Expression || delimiter == Delimiter::Brace {
+                    break;
+                }
+            }
+            _ => {
+                close(&mut open, kind);
+                state.stream.next();
+
+                // The block of the statement, as in `if ($a { ... }`, is over.
+                if boundary == Boundary::Statement
+                    && kind == &TokenKind::RightBrace
+                    && !open.contains(&Delimiter::Brace)
+                {
+                    return;
+                }
+
+                continue;
+            }
+        }
+
+        state.stream.next();
+    }
+
+    if boundary == Boundary::Statement && state.stream.current().span == start {
+        state.stream.next();
+    }
+}
+
+/// Open or close the delimiter that `kind` is, if any.
+fn close(open: &mut Vec<Delimiter>, kind: &TokenKind) {
+    if let Some(delimiter) = opening(kind) {
+        open.push(delimiter);
+    } else if let Some(delimiter) = closing(kind) {
+        if let Some(position) = open.iter().rposition(|open| *open == delimiter) {
+            open.truncate(position);
+        }
+    }
+}
+
+fn opening(kind: &TokenKind) -> Option<Delimiter> {
+    match kind {
+        TokenKind::LeftParen => Some(Delimiter::Parenthesis),
+        TokenKind::LeftBracket | TokenKind::Attribute => Some(Delimiter::Bracket),
+        TokenKind::LeftBrace | TokenKind::CurlyOpen | TokenKind::DollarLeftBrace => {
+            Some(Delimiter::Brace)
+        }
+        _ => None,
+    }
+}
+
+fn closing(kind: &TokenKind) -> Option<Delimiter> {
+    match kind {
+        TokenKind::RightParen => Some(Delimiter::Parenthesis),
+        TokenKind::RightBracket => Some(Delimiter::Bracket),
+        TokenKind::RightBrace => Some(Delimiter::Brace),
+        _ => None,
+    }
+}
+
+/// Whether a keyword can only start a statement, so that the statement before it
+/// must have ended.
+fn starts_statement(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Namespace
+            | TokenKind::Use
+            | TokenKind::Const
+            | TokenKind::Class
+            | TokenKind::Interface
+            | TokenKind::Trait
+            | TokenKind::Enum
+            | TokenKind::Abstract
+            | TokenKind::Final
+            | TokenKind::If
+            | TokenKind::Switch
+            | TokenKind::For
+            | TokenKind::Foreach
+            | TokenKind::While
+            | TokenKind::Do
+            | TokenKind::Try
+            | TokenKind::Return
+            | TokenKind::Echo
+            | TokenKind::Global
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::arguments::Argument;
+    use crate::parser::ast::Expression;
+    use crate::parser::ast::ExpressionStatement;
+    use crate::parser::ast::Statement;
+    use crate::parser::parse_with_recovery;
+
+    /// The kinds of the statements of `code` after its opening tag, and the positions
+    /// of its errors.
+    fn recover(code: &str) -> (Vec<String>, Vec<String>) {
+        let (program, errors) = parse_with_recovery(code);
+
+        let kinds = program[1..]
+            .iter()
+            .map(|statement| {
+                let debug = format!("{:?}", statement);
+                debug[..debug.find('(').unwrap_or(debug.len())].to_string()
+            })
+            .collect();
+        let errors = errors
+            .iter()
+            .map(|error| format!("{}:{}", error.span.line, error.span.column))
+            .collect();
+
+        (kinds, errors)
+    }
+
+    #[test]
+    fn test_broken_statements() {
+        let code = "<?php
+$a = 1;
+if ($b { foo(); }
+$c = 2;
+class { }
+function d() { return 3; }
+while ($e) { $f = [1, 2; }
+echo $a;";
+
+        assert_eq!(
+            recover(code),
+            (
+                vec![
+                    "Expression".to_string(),
+                    "Error".to_string(),
+                    "Expression".to_string(),
+                    "Error".to_string(),
+                    "Function".to_string(),
+                    "While".to_string(),
+                    "Echo".to_string(),
+                ],
+                vec!["3:8".to_string(), "5:7".to_string(), "7:24".to_string()]
+            )
+        );
+
+        // The other statements are the same as with the broken ones blanked out.
+        let blanked = code
+            .lines()
+            .enumerate()
+            .map(|(index, line)| match index {
+                2 | 4 | 6 => " ".repeat(line.len()),
+                _ => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (program, _) = parse_with_recovery(code);
+        let valid = crate::parse(&blanked).unwrap();
+        assert_eq!(
+            [&program[1], &program[3], &program[5], &program[7]],
+            [&valid[1], &valid[2], &valid[3], &valid[4]]
+        );
+
+        assert!(crate::parse(code).is_err());
+    }
+
+    #[test]
+    fn test_broken_expressions() {
+        let (program, errors) = parse_with_recovery("<?php foo(1, +, 3); $a = ;");
+        assert_eq!(errors.len(), 2);
+
+        let Statement::Expression(ExpressionStatement {
+            expression: Expression::FunctionCall(call),
+            ..
+        }) = &program[1]
+        else {
+            panic!("expected a function call, found {:?}", program[1]);
+        };
+        let values: Vec<_> = call
+            .arguments
+            .arguments
+            .iter()
+            .map(|argument| match argument {
+                Argument::Positional(argument) => &argument.value,
+                Argument::Named(argument) => &argument.value,
+            })
+            .collect();
+        assert!(matches!(
+            values[..],
+            [
+                Expression::Literal(_),
+                Expression::Error(_),
+                Expression::Literal(_)
+            ]
+        ));
+
+        assert!(matches!(
+            &program[2],
+            Statement::Expression(ExpressionStatement {
+                expression: Expression::Error(span),
+                ..
+            }) if span.column == 21
+        ));
+    }
+
+    #[test]
+    fn test_stray_keyword() {
+        assert_eq!(
+            recover("<?php else { $a = 1; } $b = 2; )"),
+            (
+                vec![
+                    "Error".to_string(),
+                    "Expression".to_string(),
+                    "Error".to_string()
+                ],
+                vec!["1:7".to_string(), "1:32".to_string()]
+            )
+        );
+    }
+}
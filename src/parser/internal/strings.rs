@@ -1,5 +1,5 @@
-use crate::expect_token;
 use crate::expected_token_err;
+use crate::lexer::byte_string::ByteString;
 use crate::lexer::error::SyntaxError;
 use crate::lexer::token::DocStringIndentationKind;
 use crate::lexer::token::TokenKind;
@@ -17,6 +17,7 @@ use crate::parser::ast::{
     NowdocExpression, NullsafePropertyFetchExpression, PropertyFetchExpression,
     ShellExecExpression,
 };
+use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::expressions::create;
 use crate::parser::internal::identifiers;
@@ -26,47 +27,68 @@ use crate::parser::state::State;
 
 #[inline(always)]
 pub fn interpolated(state: &mut State) -> ParseResult<Expression> {
+    let start = state.stream.current().span;
     let mut parts = Vec::new();
 
     while state.stream.current().kind != TokenKind::DoubleQuote {
+        state.check_cancelled()?;
+
         if let Some(part) = part(state)? {
             parts.push(part);
         }
     }
 
+    let end = state.stream.current().span;
     state.stream.next();
 
     Ok(Expression::InterpolatedString(
-        InterpolatedStringExpression { parts },
+        InterpolatedStringExpression { start, parts, end },
     ))
 }
 
 #[inline(always)]
 pub fn shell_exec(state: &mut State) -> ParseResult<Expression> {
+    let start = state.stream.current().span;
     state.stream.next();
 
     let mut parts = Vec::new();
+    let mut warned = false;
 
     while state.stream.current().kind != TokenKind::Backtick {
+        state.check_cancelled()?;
+
+        let current = state.stream.current();
+        if current.kind == TokenKind::DollarLeftBrace && !warned {
+            state.warn(error::template_literal_in_shell_exec(start, current.span));
+            warned = true;
+        }
+
         if let Some(part) = part(state)? {
             parts.push(part);
         }
     }
 
+    let end = state.stream.current().span;
     state.stream.next();
 
-    Ok(Expression::ShellExec(ShellExecExpression { parts }))
+    Ok(Expression::ShellExec(ShellExecExpression {
+        start,
+        parts,
+        end,
+    }))
 }
 
 #[inline(always)]
 pub fn heredoc(state: &mut State) -> ParseResult<Expression> {
     let span = state.stream.current().span;
-    let label = state.stream.current().value.clone();
+    let label = state.stream.current().value.to_byte_string();
     state.stream.next();
 
     let mut parts = Vec::new();
 
     while !matches!(state.stream.current().kind, TokenKind::EndDocString(_, _)) {
+        state.check_cancelled()?;
+
         if let Some(part) = part(state)? {
             parts.push(part);
         }
@@ -79,6 +101,7 @@ pub fn heredoc(state: &mut State) -> ParseResult<Expression> {
         _ => unreachable!(),
     };
 
+    let end = state.stream.current().span;
     state.stream.next();
 
     let mut new_line = true;
@@ -141,18 +164,29 @@ pub fn heredoc(state: &mut State) -> ParseResult<Expression> {
         }
     }
 
-    Ok(Expression::Heredoc(HeredocExpression { label, parts }))
+    Ok(Expression::Heredoc(HeredocExpression {
+        start: span,
+        label,
+        parts,
+        end,
+    }))
 }
 
 #[inline(always)]
 pub fn nowdoc(state: &mut State) -> ParseResult<Expression> {
     let span = state.stream.current().span;
-    let label = state.stream.current().value.clone();
+    let label = state.stream.current().value.to_byte_string();
 
     state.stream.next();
 
-    let mut string_part = state.stream.current().value.clone();
-    expect_token!([TokenKind::StringPart => ()], state, "constant string");
+    // An empty nowdoc doesn't produce a string part, only the closing label.
+    let mut string_part = if state.stream.current().kind == TokenKind::StringPart {
+        let value = state.stream.current().value.to_byte_string();
+        state.stream.next();
+        value
+    } else {
+        ByteString::default()
+    };
 
     let (indentation_type, indentation_amount) = match &state.stream.current().kind {
         TokenKind::EndDocString(indentation_type, indentation_amount) => {
@@ -161,6 +195,7 @@ pub fn nowdoc(state: &mut State) -> ParseResult<Expression> {
         _ => unreachable!(),
     };
 
+    let end = state.stream.current().span;
     state.stream.next();
 
     if indentation_type != DocStringIndentationKind::None {
@@ -223,13 +258,18 @@ pub fn nowdoc(state: &mut State) -> ParseResult<Expression> {
         string_part = bytes.into();
     }
 
-    Ok(Expression::Nowdoc(NowdocExpression { label, value: string_part }))
+    Ok(Expression::Nowdoc(NowdocExpression {
+        start: span,
+        label,
+        value: string_part,
+        end,
+    }))
 }
 
 fn part(state: &mut State) -> ParseResult<Option<StringPart>> {
     Ok(match &state.stream.current().kind {
         TokenKind::StringPart => {
-            let s = state.stream.current().value.clone();
+            let s = state.stream.current().value.to_byte_string();
             let part = if s.len() > 0 {
                 Some(StringPart::Literal(LiteralStringPart { value: s }))
             } else {
@@ -272,7 +312,7 @@ fn part(state: &mut State) -> ParseResult<Option<StringPart>> {
 
                             Expression::Literal(Literal::Integer(LiteralInteger {
                                 span: current.span,
-                                value: current.value.clone(),
+                                value: current.value.to_byte_string(),
                             }))
                         }
                         TokenKind::Minus => {
@@ -288,7 +328,7 @@ fn part(state: &mut State) -> ParseResult<Option<StringPart>> {
                                         right: Box::new(Expression::Literal(Literal::Integer(
                                             LiteralInteger {
                                                 span: literal.span,
-                                                value: literal.value.clone(),
+                                                value: literal.value.to_byte_string(),
                                             },
                                         ))),
                                     },
@@ -302,7 +342,7 @@ fn part(state: &mut State) -> ParseResult<Option<StringPart>> {
 
                             Expression::Literal(Literal::String(LiteralString {
                                 span: current.span,
-                                value: current.value.clone(),
+                                value: current.value.to_byte_string(),
                             }))
                         }
                         TokenKind::Variable => Expression::Variable(Variable::SimpleVariable(
@@ -354,7 +394,70 @@ fn part(state: &mut State) -> ParseResult<Option<StringPart>> {
             }))
         }
         _ => {
-            return expected_token_err!(["`${`", "`{$", "`\"`", "a variable"], state);
+            return expected_token_err!(["`${`", "`{$`", "`\"`", "a variable"], state);
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::Statement;
+
+    fn parts(code: &str) -> Vec<StringPart> {
+        let program = crate::parse(code).unwrap();
+
+        match &program[1] {
+            Statement::Echo(echo) => match &echo.values[0] {
+                Expression::InterpolatedString(string) => string.parts.clone(),
+                Expression::Heredoc(heredoc) => heredoc.parts.clone(),
+                value => panic!("expected a string, found {:?}", value),
+            },
+            statement => panic!("expected an echo statement, found {:?}", statement),
+        }
+    }
+
+    fn literal(part: &StringPart) -> Option<&ByteString> {
+        match part {
+            StringPart::Literal(literal) => Some(&literal.value),
+            StringPart::Expression(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_bare_words_are_text() {
+        for code in [
+            "<?php echo \"__LINE__ PHP_EOL true self::X foo() {$a}\";",
+            "<?php echo <<<EOT\n__LINE__ PHP_EOL true self::X foo() {$a}\nEOT;",
+        ] {
+            let parts = parts(code);
+
+            assert_eq!(parts.len(), 2, "{}", code);
+            assert!(literal(&parts[0])
+                .unwrap()
+                .ends_with(b"__LINE__ PHP_EOL true self::X foo() "));
+            assert!(literal(&parts[1]).is_none());
+        }
+    }
+
+    #[test]
+    fn test_braced_expressions() {
+        let parts = parts("<?php echo \"{$arr['key']}{$obj->method()}{${$name}}\";");
+        let expressions: Vec<&Expression> = parts
+            .iter()
+            .filter_map(|part| match part {
+                StringPart::Expression(part) => Some(part.expression.as_ref()),
+                StringPart::Literal(_) => None,
+            })
+            .collect();
+
+        assert!(matches!(
+            expressions[..],
+            [
+                Expression::ArrayIndex(_),
+                Expression::MethodCall(_),
+                Expression::Variable(Variable::BracedVariableVariable(_)),
+            ]
+        ));
+    }
+}
@@ -20,6 +20,9 @@ use crate::parser::internal::modifiers;
 use crate::parser::internal::properties;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::symbols;
+use crate::parser::symbols::SymbolKind;
+use crate::parser::version::Feature;
 use crate::peek_token;
 
 pub fn usage(state: &mut State) -> ParseResult<TraitUsage> {
@@ -51,7 +54,8 @@ pub fn usage(state: &mut State) -> ParseResult<TraitUsage> {
     }
 
     let mut adaptations = Vec::new();
-    if state.stream.current().kind == TokenKind::LeftBrace {
+    let braced = state.stream.current().kind == TokenKind::LeftBrace;
+    if braced {
         utils::skip_left_brace(state)?;
 
         while state.stream.current().kind != TokenKind::RightBrace {
@@ -60,10 +64,10 @@ pub fn usage(state: &mut State) -> ParseResult<TraitUsage> {
                     TokenKind::DoubleColon => {
                         let r#trait = identifiers::full_type_name(state)?;
                         state.stream.next();
-                        let method = identifiers::identifier(state)?;
+                        let method = identifiers::identifier_maybe_reserved(state)?;
                         (Some(r#trait), method)
                     }
-                    _ => (None, identifiers::identifier(state)?),
+                    _ => (None, identifiers::identifier_maybe_reserved(state)?),
                 };
 
             expect_token!([
@@ -85,7 +89,7 @@ pub fn usage(state: &mut State) -> ParseResult<TraitUsage> {
                                         visibility,
                                     });
                                 } else {
-                                    let alias: SimpleIdentifier = identifiers::name(state)?;
+                                    let alias: SimpleIdentifier = identifiers::identifier_maybe_reserved(state)?;
                                     adaptations.push(TraitUsageAdaptation::Alias {
                                         r#trait,
                                         method,
@@ -95,7 +99,7 @@ pub fn usage(state: &mut State) -> ParseResult<TraitUsage> {
                                 }
                             }
                             _ => {
-                                let alias: SimpleIdentifier = identifiers::name(state)?;
+                                let alias: SimpleIdentifier = identifiers::identifier_maybe_reserved(state)?;
                                 adaptations.push(TraitUsageAdaptation::Alias {
                                     r#trait,
                                     method,
@@ -155,6 +159,7 @@ pub fn usage(state: &mut State) -> ParseResult<TraitUsage> {
     Ok(TraitUsage {
         r#use: span,
         traits,
+        braced,
         adaptations,
     })
 }
@@ -164,19 +169,26 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
     let name = identifiers::type_identifier(state)?;
     let attributes = state.get_attributes();
 
+    symbols::enter(state, SymbolKind::Trait, &name.value);
     let body = TraitBody {
         left_brace: utils::skip_left_brace(state)?,
         members: {
             let mut members = Vec::new();
             while state.stream.current().kind != TokenKind::RightBrace && !state.stream.is_eof() {
+                state.check_cancelled()?;
                 members.push(member(state, &name)?);
             }
             members
         },
         right_brace: utils::skip_right_brace(state)?,
     };
+    symbols::exit(state);
+
+    let start = symbols::start(&attributes, span);
+    symbols::emit(state, SymbolKind::Trait, &name.value, name.span, start);
 
     Ok(Statement::Trait(TraitStatement {
+        doc: state.stream.doc_comment(start.position),
         r#trait: span,
         name,
         attributes,
@@ -185,6 +197,8 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
 }
 
 fn member(state: &mut State, class_name: &SimpleIdentifier) -> ParseResult<TraitMember> {
+    state.stream.forget_enclosed_comments();
+
     let has_attributes = attributes::gather_attributes(state)?;
 
     if !has_attributes && state.stream.current().kind == TokenKind::Use {
@@ -198,6 +212,8 @@ fn member(state: &mut State, class_name: &SimpleIdentifier) -> ParseResult<Trait
     let modifiers = modifiers::collect(state)?;
 
     if state.stream.current().kind == TokenKind::Const {
+        state.require(Feature::ConstantsInTraits, state.stream.current().span, 5);
+
         return constants::classish(state, modifiers::constant_group(modifiers)?)
             .map(TraitMember::Constant);
     }
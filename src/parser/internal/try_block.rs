@@ -10,6 +10,7 @@ use crate::parser::internal::blocks;
 use crate::parser::internal::identifiers;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
+use crate::parser::version::Feature;
 
 use super::variables;
 
@@ -36,6 +37,9 @@ pub fn try_block(state: &mut State) -> ParseResult<Statement> {
 
         let types = catch_type(state)?;
         let var = if state.stream.current().kind == TokenKind::RightParen {
+            let right_parenthesis = state.stream.current().span;
+            state.require(Feature::CatchWithoutVariable, right_parenthesis, 1);
+
             None
         } else {
             Some(variables::simple_variable(state)?)
@@ -46,9 +50,7 @@ pub fn try_block(state: &mut State) -> ParseResult<Statement> {
 
         let catch_body = blocks::multiple_statements_until(state, &TokenKind::RightBrace)?;
 
-        utils::skip_right_brace(state)?;
-
-        let catch_end = state.stream.current().span;
+        let catch_end = utils::skip_right_brace(state)?;
 
         catches.push(CatchBlock {
             start: catch_start,
@@ -65,10 +67,12 @@ pub fn try_block(state: &mut State) -> ParseResult<Statement> {
         state.stream.next();
         utils::skip_left_brace(state)?;
 
-        let finally_body = blocks::multiple_statements_until(state, &TokenKind::RightBrace)?;
+        state.finally_depth += 1;
+        let finally_body = blocks::multiple_statements_until(state, &TokenKind::RightBrace);
+        state.finally_depth -= 1;
+        let finally_body = finally_body?;
 
-        utils::skip_right_brace(state)?;
-        let finally_end = state.stream.current().span;
+        let finally_end = utils::skip_right_brace(state)?;
 
         finally = Some(FinallyBlock {
             start: finally_start,
@@ -81,7 +85,11 @@ pub fn try_block(state: &mut State) -> ParseResult<Statement> {
         return Err(error::try_without_catch_or_finally(start, last_right_brace));
     }
 
-    let end = state.stream.current().span;
+    let end = match (&finally, catches.last()) {
+        (Some(finally), _) => finally.end,
+        (None, Some(catch)) => catch.end,
+        (None, None) => last_right_brace,
+    };
 
     Ok(Statement::Try(TryStatement {
         start,
@@ -97,6 +105,7 @@ fn catch_type(state: &mut State) -> ParseResult<CatchType> {
     let id = identifiers::full_name(state)?;
 
     if state.stream.current().kind == TokenKind::Pipe {
+        state.require(Feature::MultiCatch, state.stream.current().span, 1);
         state.stream.next();
 
         let mut types = vec![id];
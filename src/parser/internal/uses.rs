@@ -1,4 +1,7 @@
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
 use crate::lexer::token::TokenKind;
+use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::GroupUseStatement;
 use crate::parser::ast::Statement;
 use crate::parser::ast::Use;
@@ -8,9 +11,14 @@ use crate::parser::error;
 use crate::parser::error::ParseResult;
 use crate::parser::internal::identifiers;
 use crate::parser::internal::utils;
+use crate::parser::state::Import;
+use crate::parser::state::Scope;
 use crate::parser::state::State;
+use crate::parser::version::Feature;
 
 pub fn use_statement(state: &mut State) -> ParseResult<Statement> {
+    let comments = state.stream.comments();
+    let r#use = state.stream.current().span;
     state.stream.next();
 
     let kind = match state.stream.current().kind {
@@ -27,6 +35,7 @@ pub fn use_statement(state: &mut State) -> ParseResult<Statement> {
 
     if state.stream.peek().kind == TokenKind::LeftBrace {
         let prefix = identifiers::full_name(state)?;
+        let left_brace = state.stream.current().span;
         state.stream.next();
         let mut uses = Vec::new();
         while state.stream.current().kind != TokenKind::RightBrace {
@@ -63,6 +72,13 @@ pub fn use_statement(state: &mut State) -> ParseResult<Statement> {
                 alias = Some(identifiers::type_identifier(state)?);
             }
 
+            if use_kind.as_ref().unwrap_or(&kind) == &UseKind::Function && alias.is_none() {
+                imported_construct(state, &name);
+            }
+
+            let import_kind = use_kind.clone().unwrap_or_else(|| kind.clone());
+            import(state, import_kind, Some(&prefix), &name, alias.as_ref());
+
             uses.push(Use {
                 name,
                 kind: use_kind,
@@ -70,22 +86,35 @@ pub fn use_statement(state: &mut State) -> ParseResult<Statement> {
             });
 
             if state.stream.current().kind == TokenKind::Comma {
+                let comma = state.stream.current().span;
                 state.stream.next();
+
+                if state.stream.current().kind == TokenKind::RightBrace {
+                    state.require(Feature::TrailingCommaInGroupedUses, comma, 1);
+                }
+
                 continue;
             }
+
+            break;
         }
 
-        utils::skip_right_brace(state)?;
-        utils::skip_semicolon(state)?;
+        let right_brace = utils::skip_right_brace(state)?;
+        let semicolon = utils::skip_semicolon(state)?;
 
         Ok(Statement::GroupUse(GroupUseStatement {
+            comments,
+            r#use,
             prefix,
             kind,
+            left_brace,
             uses,
+            right_brace,
+            semicolon,
         }))
     } else {
         let mut uses = Vec::new();
-        while !state.stream.is_eof() {
+        let semicolon = loop {
             let name = identifiers::full_type_name(state)?;
             let mut alias = None;
             if state.stream.current().kind == TokenKind::As {
@@ -93,6 +122,12 @@ pub fn use_statement(state: &mut State) -> ParseResult<Statement> {
                 alias = Some(identifiers::type_identifier(state)?);
             }
 
+            if kind == UseKind::Function && alias.is_none() {
+                imported_construct(state, &name);
+            }
+
+            import(state, kind.clone(), None, &name, alias.as_ref());
+
             uses.push(Use {
                 name,
                 kind: None,
@@ -104,10 +139,214 @@ pub fn use_statement(state: &mut State) -> ParseResult<Statement> {
                 continue;
             }
 
-            utils::skip_semicolon(state)?;
-            break;
+            break utils::skip_semicolon(state)?;
+        };
+
+        Ok(Statement::Use(UseStatement {
+            comments,
+            r#use,
+            kind,
+            uses,
+            semicolon,
+        }))
+    }
+}
+
+/// Functions can't be named after a language construct, so importing one would shadow
+/// the construct, e.g. `use function Foo\list;`.
+fn imported_construct(state: &mut State, name: &SimpleIdentifier) {
+    if let Some(construct) = identifiers::qualified_construct(name) {
+        let error = error::construct_imported_as_function(name, construct);
+
+        state.record(error);
+    }
+}
+
+/// Check an import against the earlier imports of the namespace, then remember it.
+///
+/// Importing two names as the same alias is an error, while an alias that repeats the
+/// imported name, or an import of a name from the current namespace, only has no
+/// effect. Functions, constants and classes are imported separately, so e.g. a function
+/// and a class can share an alias.
+fn import(
+    state: &mut State,
+    kind: UseKind,
+    prefix: Option<&SimpleIdentifier>,
+    name: &SimpleIdentifier,
+    alias: Option<&SimpleIdentifier>,
+) {
+    let full = match prefix {
+        // The prefix of a group use keeps its trailing `\`, e.g. `Foo\` in `use Foo\{Bar};`.
+        Some(prefix) => [&prefix.value[..], &name.value[..]].concat(),
+        None => name.value.to_vec(),
+    };
+    let full = ByteString::from(full.strip_prefix(b"\\").unwrap_or(&full).to_vec());
+
+    // Constants are case-sensitive, unlike functions and classes.
+    let same = |a: &[u8], b: &[u8]| match kind {
+        UseKind::Const => a == b,
+        _ => a.eq_ignore_ascii_case(b),
+    };
+
+    let last = last_part(name);
+    let alias = alias.cloned().unwrap_or_else(|| last.clone());
+
+    let previous = state
+        .imports
+        .iter()
+        .find(|import| import.kind == kind && same(&import.alias.value, &alias.value));
+    if let Some(previous) = previous {
+        let error = error::conflicting_import(&kind, &full, &alias, &previous.alias);
+
+        state.record(error);
+    } else if same(&alias.value, &last.value) {
+        let namespace = match state.namespace() {
+            Some(Scope::Namespace(namespace) | Scope::BracedNamespace(Some(namespace))) => {
+                &namespace.value[..]
+            }
+            _ => b"",
+        };
+        let parent = match full.iter().rposition(|byte| *byte == b'\\') {
+            Some(separator) => &full[..separator],
+            None => b"",
+        };
+
+        if parent.eq_ignore_ascii_case(namespace) {
+            let warning = error::import_from_current_namespace(&full, name, namespace.is_empty());
+
+            state.warn(warning);
+        } else if alias.span != last.span {
+            let warning = error::redundant_import_alias(&full, &alias);
+
+            state.warn(warning);
+        }
+    }
+
+    state.imports.push(Import {
+        kind,
+        alias,
+        name: full,
+    });
+}
+
+/// The last part of a name, e.g. `Bar` in `Foo\Bar`.
+fn last_part(name: &SimpleIdentifier) -> SimpleIdentifier {
+    let start = name
+        .value
+        .iter()
+        .rposition(|byte| *byte == b'\\')
+        .map_or(0, |separator| separator + 1);
+
+    SimpleIdentifier {
+        span: Span::new(
+            name.span.line,
+            name.span.column + start,
+            name.span.position + start,
+        ),
+        value: name.value[start..].into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn parse(code: &str) -> Result<Vec<String>, Vec<String>> {
+        match crate::parse_with_warnings(code, Default::default()) {
+            Ok((_, warnings)) => Ok(warnings.into_iter().map(|warning| warning.id).collect()),
+            Err(stack) => Err(stack.errors.into_iter().map(|error| error.id).collect()),
         }
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_conflicting_imports() {
+        assert_eq!(
+            parse("<?php use Foo\\Bar; use Baz\\Bar;"),
+            Err(ids(&["E070"]))
+        );
+        assert_eq!(parse("<?php use Foo\\Bar, Baz\\BAR;"), Err(ids(&["E070"])));
+        assert_eq!(
+            parse("<?php use Foo\\Bar; use Foo\\Bar;"),
+            Err(ids(&["E070"]))
+        );
+        assert_eq!(
+            parse("<?php use Foo\\Bar; use Baz\\Qux as Bar;"),
+            Err(ids(&["E070"]))
+        );
+        assert_eq!(
+            parse("<?php use function A\\foo; use function B\\FOO;"),
+            Err(ids(&["E070"]))
+        );
+        assert_eq!(
+            parse("<?php use const A\\FOO; use const B\\FOO;"),
+            Err(ids(&["E070"]))
+        );
+    }
+
+    #[test]
+    fn test_conflicting_group_imports() {
+        assert_eq!(
+            parse("<?php use Foo\\{Bar, function baz}; use function Qux\\baz;"),
+            Err(ids(&["E070"]))
+        );
+        assert_eq!(
+            parse("<?php use Foo\\{Bar}; use Baz\\{Bar as Qux, Bar};"),
+            Err(ids(&["E070"]))
+        );
+    }
+
+    #[test]
+    fn test_imports_that_do_not_conflict() {
+        assert_eq!(
+            parse("<?php use Foo\\bar; use function Baz\\bar; use const Qux\\bar;"),
+            Ok(vec![])
+        );
+        assert_eq!(
+            parse("<?php use const A\\FOO; use const B\\foo;"),
+            Ok(vec![])
+        );
+        assert_eq!(
+            parse("<?php namespace A; use X\\Bar; namespace B; use Y\\Bar;"),
+            Ok(vec![])
+        );
+        assert_eq!(
+            parse("<?php namespace A { use X\\Bar; } namespace B { use Y\\Bar; }"),
+            Ok(vec![])
+        );
+    }
+
+    #[test]
+    fn test_redundant_alias() {
+        assert_eq!(parse("<?php use App\\Thing as Thing;"), Ok(ids(&["E071"])));
+        assert_eq!(
+            parse("<?php use App\\{Thing as Thing};"),
+            Ok(ids(&["E071"]))
+        );
+        assert_eq!(parse("<?php use App\\Thing as Other;"), Ok(vec![]));
+    }
 
-        Ok(Statement::Use(UseStatement { uses, kind }))
+    #[test]
+    fn test_import_from_current_namespace() {
+        assert_eq!(
+            parse("<?php namespace App; use App\\Thing;"),
+            Ok(ids(&["E072"]))
+        );
+        assert_eq!(
+            parse("<?php namespace App; use app\\{Thing, Models\\User};"),
+            Ok(ids(&["E072"]))
+        );
+        assert_eq!(
+            parse("<?php namespace App; use App\\Thing as Thing;"),
+            Ok(ids(&["E072"]))
+        );
+        assert_eq!(parse("<?php use Thing;"), Ok(ids(&["E072"])));
+        assert_eq!(parse("<?php use \\Thing;"), Ok(ids(&["E072"])));
+        assert_eq!(
+            parse("<?php namespace App; use App\\Thing as Other;"),
+            Ok(vec![])
+        );
+        assert_eq!(parse("<?php namespace App; use Thing;"), Ok(vec![]));
     }
 }
@@ -1,3 +1,4 @@
+use crate::lexer::token::KindId;
 use crate::lexer::token::Span;
 use crate::lexer::token::TokenKind;
 use crate::parser::ast::utils::CommaSeparated;
@@ -9,11 +10,11 @@ use crate::parser::state::State;
 pub fn skip_ending(state: &mut State) -> ParseResult<Ending> {
     let current = state.stream.current();
 
-    if current.kind == TokenKind::CloseTag {
+    if current.kind.kind_id() == KindId::CloseTag {
         state.stream.next();
 
         Ok(Ending::CloseTag(current.span))
-    } else if current.kind == TokenKind::SemiColon {
+    } else if current.kind.kind_id() == KindId::SemiColon {
         state.stream.next();
 
         Ok(Ending::Semicolon(current.span))
@@ -25,7 +26,7 @@ pub fn skip_ending(state: &mut State) -> ParseResult<Ending> {
 pub fn skip_semicolon(state: &mut State) -> ParseResult<Span> {
     let current = state.stream.current();
 
-    if current.kind == TokenKind::SemiColon {
+    if current.kind.kind_id() == KindId::SemiColon {
         state.stream.next();
 
         Ok(current.span)
@@ -73,7 +74,7 @@ pub fn skip_colon(state: &mut State) -> ParseResult<Span> {
 pub fn skip(state: &mut State, kind: TokenKind) -> ParseResult<Span> {
     let current = state.stream.current();
 
-    if current.kind == kind {
+    if current.kind.kind_id() == kind.kind_id() {
         let end = current.span;
 
         state.stream.next();
@@ -87,7 +88,8 @@ pub fn skip(state: &mut State, kind: TokenKind) -> ParseResult<Span> {
 pub fn skip_any_of(state: &mut State, kinds: &[TokenKind]) -> ParseResult<Span> {
     let current = state.stream.current();
 
-    if kinds.contains(&current.kind) {
+    let id = current.kind.kind_id();
+    if kinds.iter().any(|kind| kind.kind_id() == id) {
         let end = current.span;
 
         state.stream.next();
@@ -131,6 +133,46 @@ pub fn braced<T>(
     Ok((left_brace, inner, right_brace))
 }
 
+/// Parse the body of a loop or `switch` statement.
+///
+/// This function will increase the loop depth for the duration of the given
+/// function, allowing `break` and `continue` statements to target the construct.
+pub fn breakable<T>(
+    state: &mut State,
+    func: &dyn Fn(&mut State) -> ParseResult<T>,
+) -> ParseResult<T> {
+    state.loop_depth += 1;
+    let inner = func(state);
+    state.loop_depth -= 1;
+
+    inner
+}
+
+/// Parse the body of a function, method, or closure.
+///
+/// Loops and `switch` statements surrounding a function body cannot be targeted
+/// by `break` or `continue` statements inside of it, so the loop depth is reset
+/// for the duration of the given function. Likewise, the body has its own `goto`
+/// labels, isn't inside any `finally` block, and yields by reference when the
+/// function returns `by_reference`.
+pub fn function_body<T>(
+    state: &mut State,
+    by_reference: bool,
+    func: &dyn Fn(&mut State) -> ParseResult<T>,
+) -> ParseResult<T> {
+    let depth = std::mem::take(&mut state.loop_depth);
+    let labels = std::mem::take(&mut state.labels);
+    let finally_depth = std::mem::take(&mut state.finally_depth);
+    let outer = std::mem::replace(&mut state.by_reference, by_reference);
+    let inner = func(state);
+    state.loop_depth = depth;
+    state.labels = labels;
+    state.finally_depth = finally_depth;
+    state.by_reference = outer;
+
+    inner
+}
+
 pub fn semicolon_terminated<T>(
     state: &mut State,
     func: &(dyn Fn(&mut State) -> ParseResult<T>),
@@ -150,11 +192,11 @@ pub fn comma_separated<T>(
     let mut commas: Vec<Span> = vec![];
     let mut current = state.stream.current();
 
-    while current.kind != until {
+    while current.kind.kind_id() != until.kind_id() {
         inner.push(func(state)?);
 
         current = state.stream.current();
-        if current.kind != TokenKind::Comma {
+        if current.kind.kind_id() != KindId::Comma {
             break;
         }
 
@@ -178,17 +220,17 @@ pub fn comma_separated_no_trailing<T>(
     let mut commas: Vec<Span> = vec![];
     let mut current = state.stream.current();
 
-    while current.kind != until {
+    while current.kind.kind_id() != until.kind_id() {
         inner.push(func(state)?);
 
         current = state.stream.current();
-        if current.kind != TokenKind::Comma {
+        if current.kind.kind_id() != KindId::Comma {
             break;
         }
 
         // If the next token is the until token, we don't want to consume the comma.
         // This ensures that trailing commas are not allowed.
-        if state.stream.peek().kind == until {
+        if state.stream.peek().kind.kind_id() == until.kind_id() {
             break;
         }
 
@@ -214,7 +256,7 @@ pub fn at_least_one_comma_separated_no_trailing<T>(
         inner.push(func(state)?);
 
         let current = state.stream.current();
-        if current.kind != TokenKind::Comma {
+        if current.kind.kind_id() != KindId::Comma {
             break;
         }
 
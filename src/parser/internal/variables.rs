@@ -6,14 +6,17 @@ use crate::parser::ast::variables::Variable;
 use crate::parser::ast::variables::VariableVariable;
 use crate::parser::error::ParseResult;
 use crate::parser::expressions;
+use crate::parser::internal::policy;
 use crate::parser::internal::utils;
 use crate::parser::state::State;
 
 pub fn simple_variable(state: &mut State) -> ParseResult<SimpleVariable> {
     let current = state.stream.current();
     if let TokenKind::Variable = &current.kind {
+        policy::variable(state, current.span, current.value.len())?;
+
         let span = current.span;
-        let name = current.value.clone();
+        let name = current.value.to_byte_string();
         state.stream.next();
 
         return Ok(SimpleVariable { span, name });
@@ -24,10 +27,17 @@ pub fn simple_variable(state: &mut State) -> ParseResult<SimpleVariable> {
 
 pub fn dynamic_variable(state: &mut State) -> ParseResult<Variable> {
     let current = state.stream.current();
+    if matches!(
+        current.kind,
+        TokenKind::Variable | TokenKind::DollarLeftBrace | TokenKind::Dollar
+    ) {
+        policy::variable(state, current.span, current.value.len())?;
+    }
+
     match &current.kind {
         TokenKind::Variable => {
             let span = current.span;
-            let name = current.value.clone();
+            let name = current.value.to_byte_string();
             state.stream.next();
 
             Ok(Variable::SimpleVariable(SimpleVariable { span, name }))
@@ -0,0 +1,272 @@
+//! Diagnostics in the shape of the Language Server Protocol, for editors and CI, see
+//! [`diagnostics_to_json`].
+
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::parser::error::ErrorCategory;
+use crate::parser::error::ParseError;
+use crate::parser::error::ParseErrorAnnotationType;
+use crate::parser::source_map::PositionEncoding;
+use crate::parser::source_map::SourceMap;
+
+/// A line and a column, both counted from 0.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+impl Position {
+    /// The position of the byte at `offset`, with the column in `encoding`.
+    pub fn new(map: &SourceMap, offset: usize, encoding: PositionEncoding) -> Self {
+        let (line, column) = map.line_col_in(offset, encoding);
+
+        Self {
+            line: line - 1,
+            character: column - 1,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Range {
+    /// The range of the `length` bytes from `offset`.
+    pub fn new(map: &SourceMap, offset: usize, length: usize, encoding: PositionEncoding) -> Self {
+        Self {
+            start: Position::new(map, offset, encoding),
+            end: Position::new(map, offset + length, encoding),
+        }
+    }
+}
+
+/// How serious a [`Diagnostic`] is, serialized as the number the protocol uses.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DiagnosticSeverity {
+    Error = 1,
+    Warning = 2,
+    Information = 3,
+    Hint = 4,
+}
+
+impl Serialize for DiagnosticSeverity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct DiagnosticRelatedInformation {
+    pub location: Location,
+    pub message: String,
+}
+
+/// A [`ParseError`] as a diagnostic of the protocol.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: DiagnosticSeverity,
+    pub code: String,
+    /// Always `pxp-parser`.
+    pub source: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<DiagnosticRelatedInformation>,
+}
+
+/// The diagnostics of a file, in the shape of the parameters of
+/// `textDocument/publishDiagnostics`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct FileDiagnostics {
+    pub uri: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ParseError {
+    /// The error as a diagnostic of the protocol, in the file at `uri` that `map` is
+    /// of.
+    ///
+    /// The range is that of the first error annotation, or the empty range at the span
+    /// of an error without one. The other annotations, hints and suggestions, are its
+    /// related information, and the note follows the message on a line of its own.
+    pub fn to_lsp_diagnostic(
+        &self,
+        uri: &str,
+        map: &SourceMap,
+        encoding: PositionEncoding,
+    ) -> Diagnostic {
+        let primary = self
+            .annotations
+            .iter()
+            .find(|annotation| annotation.r#type == ParseErrorAnnotationType::Error);
+        let range = match primary {
+            Some(annotation) => Range::new(map, annotation.position, annotation.length, encoding),
+            None => Range::new(map, self.span.position, 0, encoding),
+        };
+
+        let severity = match self.category() {
+            ErrorCategory::Warning => DiagnosticSeverity::Warning,
+            ErrorCategory::Info => DiagnosticSeverity::Information,
+            _ => DiagnosticSeverity::Error,
+        };
+
+        let message = match &self.note {
+            Some(note) => format!("{}\n{}", self.message, note),
+            None => self.message.clone(),
+        };
+
+        let related_information = self
+            .annotations
+            .iter()
+            .filter(|annotation| annotation.r#type != ParseErrorAnnotationType::Error)
+            .map(|annotation| DiagnosticRelatedInformation {
+                location: Location {
+                    uri: uri.to_string(),
+                    range: Range::new(map, annotation.position, annotation.length, encoding),
+                },
+                message: annotation.message.clone(),
+            })
+            .collect();
+
+        Diagnostic {
+            range,
+            severity,
+            code: self.id.clone(),
+            source: "pxp-parser".to_string(),
+            message,
+            related_information,
+        }
+    }
+}
+
+/// The diagnostics of the file at `uri`, whose content is `source`, such as the errors
+/// of a [`ParseErrorStack`] or the warnings of [`parse_with_warnings`], in the order
+/// they are given.
+///
+/// [`ParseErrorStack`]: crate::parser::error::ParseErrorStack
+/// [`parse_with_warnings`]: crate::parser::parse_with_warnings
+pub fn file_diagnostics(
+    uri: &str,
+    source: &[u8],
+    diagnostics: &[ParseError],
+    encoding: PositionEncoding,
+) -> FileDiagnostics {
+    let map = SourceMap::new(source);
+
+    FileDiagnostics {
+        uri: uri.to_string(),
+        diagnostics: diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_lsp_diagnostic(uri, &map, encoding))
+            .collect(),
+    }
+}
+
+/// The [`file_diagnostics`] of a file as JSON, on a single line, so that the output for
+/// several files is a line for each. The same diagnostics always give the same JSON.
+pub fn diagnostics_to_json(
+    uri: &str,
+    source: &[u8],
+    diagnostics: &[ParseError],
+    encoding: PositionEncoding,
+) -> String {
+    // Nothing in the diagnostics can fail to serialize.
+    serde_json::to_string(&file_diagnostics(uri, source, diagnostics, encoding)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "<?php\n$s = \"😀é\"; $a = ;\n";
+
+    #[test]
+    fn test_position() {
+        let map = SourceMap::new(CODE.as_bytes());
+        let semicolon = CODE.rfind(';').unwrap();
+
+        assert_eq!(
+            Position::new(&map, semicolon, PositionEncoding::Utf8),
+            Position {
+                line: 1,
+                character: 20
+            }
+        );
+        // The emoji is two code units, and `é` one.
+        assert_eq!(
+            Position::new(&map, semicolon, PositionEncoding::Utf16),
+            Position {
+                line: 1,
+                character: 17
+            }
+        );
+        assert_eq!(
+            Position::new(&map, CODE.len(), PositionEncoding::Utf16),
+            Position {
+                line: 2,
+                character: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_to_json() {
+        let stack = crate::parse(CODE).unwrap_err();
+
+        let json = |encoding| {
+            diagnostics_to_json("file:///a.php", CODE.as_bytes(), &stack.errors, encoding)
+        };
+
+        assert_eq!(
+            json(PositionEncoding::Utf16),
+            r#"{"uri":"file:///a.php","diagnostics":[{"range":{"start":{"line":1,"character":17},"end":{"line":1,"character":18}},"severity":1,"code":"E003","source":"pxp-parser","message":"unexpected token `;`"}]}"#
+        );
+        assert!(json(PositionEncoding::Utf8).contains(
+            r#""range":{"start":{"line":1,"character":20},"end":{"line":1,"character":21}}"#
+        ));
+    }
+
+    #[test]
+    fn test_related_information() {
+        let code = "<?php\nfunction f() {\n    static $x, $x;\n}\n";
+        let warnings = crate::analysis::static_var_issues(&crate::parse(code).unwrap());
+
+        let map = SourceMap::new(code.as_bytes());
+        let diagnostic =
+            warnings[0].to_lsp_diagnostic("file:///b.php", &map, PositionEncoding::Utf16);
+
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Warning);
+        assert_eq!(diagnostic.code, "E102");
+        assert_eq!(
+            diagnostic.range.start,
+            Position {
+                line: 2,
+                character: 15
+            }
+        );
+        assert_eq!(
+            diagnostic.message,
+            "static variable `$x` is declared more than once\nboth declarations are the same variable, and PHP 8.3 rejects the second one"
+        );
+        assert_eq!(diagnostic.related_information.len(), 1);
+        assert_eq!(
+            diagnostic.related_information[0].location,
+            Location {
+                uri: "file:///b.php".to_string(),
+                range: Range::new(&map, code.find("$x").unwrap(), 2, PositionEncoding::Utf16),
+            }
+        );
+    }
+}
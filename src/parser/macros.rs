@@ -47,47 +47,48 @@ macro_rules! expect_token {
     };
 }
 
+/// Parse a literal of one of the given kinds, named after the variants of [`Literal`],
+/// or of any kind when none are given.
+///
+/// [`Literal`]: crate::parser::ast::literals::Literal
 #[macro_export]
 macro_rules! expect_literal {
-    ($state:expr) => {{
+    ($state:expr, [ $($kind:ident),+ $(,)? ]) => {{
+        use $crate::parser::ast::literals::Literal;
+        use $crate::parser::ast::literals::LiteralFloat;
+        use $crate::parser::ast::literals::LiteralInteger;
+        use $crate::parser::ast::literals::LiteralString;
+
         let current = $state.stream.current();
+        let (span, value) = (current.span, current.value.to_byte_string());
 
-        match &current.kind {
-            TokenKind::LiteralInteger => {
-                $state.stream.next();
+        let literal = match &current.kind {
+            TokenKind::LiteralInteger => Some(Literal::Integer(LiteralInteger { span, value })),
+            TokenKind::LiteralFloat => Some(Literal::Float(LiteralFloat { span, value })),
+            TokenKind::LiteralString => Some(Literal::String(LiteralString { span, value })),
+            _ => None,
+        };
 
-                $crate::parser::ast::literals::Literal::Integer(
-                    $crate::parser::ast::literals::LiteralInteger {
-                        span: current.span,
-                        value: current.value.clone(),
-                    },
-                )
-            }
-            TokenKind::LiteralFloat => {
-                $state.stream.next();
-
-                $crate::parser::ast::literals::Literal::Float(
-                    $crate::parser::ast::literals::LiteralFloat {
-                        span: current.span,
-                        value: current.value.clone(),
-                    },
-                )
-            }
-            TokenKind::LiteralString => {
-                $state.stream.next();
-
-                $crate::parser::ast::literals::Literal::String(
-                    $crate::parser::ast::literals::LiteralString {
-                        span: current.span,
-                        value: current.value.clone(),
-                    },
-                )
-            }
+        match literal {
+            $(
+                Some(literal @ Literal::$kind(_)) => {
+                    $state.stream.next();
+
+                    literal
+                }
+            )+
             _ => {
-                return $crate::expected_token_err!(["a literal"], $state);
+                return Err($crate::parser::error::expected_literal(
+                    &[$(stringify!($kind)),+],
+                    current,
+                ));
             }
         }
     }};
+
+    ($state:expr) => {
+        $crate::expect_literal!($state, [Integer, Float, String])
+    };
 }
 
 #[macro_export]
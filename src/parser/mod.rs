@@ -1,4 +1,7 @@
+use std::path::Path;
+
 use crate::expect_literal;
+use crate::lexer::token::KindId;
 use crate::lexer::token::OpenTagKind;
 use crate::lexer::token::Token;
 use crate::lexer::token::TokenKind;
@@ -7,8 +10,16 @@ use crate::parser::ast::declares::DeclareBody;
 use crate::parser::ast::declares::DeclareEntry;
 use crate::parser::ast::declares::DeclareEntryGroup;
 use crate::parser::ast::declares::DeclareStatement;
+use crate::parser::ast::literals::Literal;
 use crate::parser::ast::variables::Variable;
-use crate::parser::ast::{Program, Statement, StaticVar};
+use crate::parser::ast::{Ending, Expression, Program, Statement, StaticVar};
+use crate::parser::config::AfterHaltMode;
+use crate::parser::config::InlineHtmlMode;
+use crate::parser::config::ParserConfig;
+use crate::parser::encoding::TranscodedSource;
+use crate::parser::error::Error;
+use crate::parser::error::ErrorCategory;
+use crate::parser::error::ParseError;
 use crate::parser::error::ParseErrorStack;
 use crate::parser::error::ParseResult;
 use crate::parser::internal::attributes;
@@ -23,15 +34,18 @@ use crate::parser::internal::identifiers;
 use crate::parser::internal::interfaces;
 use crate::parser::internal::loops;
 use crate::parser::internal::namespaces;
+use crate::parser::internal::recovery;
 use crate::parser::internal::traits;
 use crate::parser::internal::try_block;
 use crate::parser::internal::uses;
 use crate::parser::internal::utils;
 use crate::parser::internal::variables;
+use crate::parser::policy::ExpressionPolicy;
 use crate::parser::state::State;
 
 pub use crate::lexer::stream::TokenStream;
 
+use self::ast::AfterHaltStatement;
 use self::ast::ClosingTagStatement;
 use self::ast::EchoOpeningTagStatement;
 use self::ast::EchoStatement;
@@ -39,10 +53,12 @@ use self::ast::ExpressionStatement;
 use self::ast::FullOpeningTagStatement;
 use self::ast::GlobalStatement;
 use self::ast::HaltCompilerStatement;
+use self::ast::InlineHtml;
 use self::ast::InlineHtmlStatement;
 use self::ast::ReturnStatement;
 use self::ast::ShortOpeningTagStatement;
 use self::ast::StaticStatement;
+use self::ast::ThrowExpression;
 use self::ast::TypeAliasStatement;
 use self::internal::data_type::data_type;
 use self::internal::identifiers::identifier;
@@ -50,14 +66,147 @@ use self::internal::precedences::Precedence;
 use self::internal::utils::skip_semicolon;
 
 pub mod ast;
+pub mod cancellation;
+pub mod config;
+pub mod encoding;
 pub mod error;
+pub mod extension;
+pub mod lsp;
+pub mod policy;
+pub mod report;
+pub mod reserved;
+pub mod source_map;
+pub mod state;
+pub mod symbols;
+pub mod version;
 
 mod expressions;
 mod internal;
 mod macros;
-mod state;
 
 pub fn parse<B: ?Sized + AsRef<[u8]>>(input: &B) -> Result<Program, ParseErrorStack> {
+    parse_with_config(input, ParserConfig::default())
+}
+
+/// Parse the input, also returning the tokens it was parsed from, for analyses that
+/// need the layout of the source, which isn't kept in the AST.
+pub fn parse_with_tokens<'a, B: ?Sized + AsRef<[u8]>>(
+    input: &'a B,
+) -> Result<(Program, Vec<Token<'a>>), ParseErrorStack> {
+    let lexer = Lexer::new();
+    let tokens = match lexer.tokenize(input) {
+        Ok(tokens) => tokens,
+        Err(error) => {
+            return Err(ParseErrorStack {
+                errors: vec![error.into()],
+                partial: Vec::new(),
+                source_name: None,
+            })
+        }
+    };
+
+    let program = construct(&tokens)?;
+
+    Ok((program, tokens))
+}
+
+pub fn parse_with_config<B: ?Sized + AsRef<[u8]>>(
+    input: &B,
+    config: ParserConfig,
+) -> Result<Program, ParseErrorStack> {
+    parse_with_warnings(input, config).map(|(program, _)| program)
+}
+
+/// Read and parse the file at `path`, with the path as the
+/// [`ParserConfig::source_name`], so that the errors it fails with are shown with it.
+///
+/// A UTF-8 byte order mark and a `#!` line at the start of the file, as in
+/// `#!/usr/bin/env php`, are skipped, the way the PHP CLI skips them, so the AST is
+/// that of the rest of the file. Spans in the AST start after them, while the spans of
+/// errors are those in the file.
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Program, Error> {
+    parse_file_with_config(path, ParserConfig::default())
+}
+
+/// Read and parse the file at `path` like [`parse_file`], with the options of `config`.
+pub fn parse_file_with_config<P: AsRef<Path>>(
+    path: P,
+    config: ParserConfig,
+) -> Result<Program, Error> {
+    let path = path.as_ref();
+    let source = std::fs::read(path)?;
+    let start = script_start(&source);
+
+    parse_with_config(&source[start..], config.source_name(path)).map_err(|mut stack| {
+        let skipped = TranscodedSource {
+            bytes: vec![],
+            positions: (start..source.len()).collect(),
+        };
+        for error in &mut stack.errors {
+            skipped.remap(&source, error);
+        }
+
+        Error::Parse(stack)
+    })
+}
+
+/// The position of the script in a file, after its byte order mark and `#!` line.
+fn script_start(source: &[u8]) -> usize {
+    let start = match source.strip_prefix(b"\xEF\xBB\xBF") {
+        Some(_) => 3,
+        None => 0,
+    };
+
+    if !source[start..].starts_with(b"#!") {
+        return start;
+    }
+
+    match source[start..].iter().position(|byte| *byte == b'\n') {
+        Some(newline) => start + newline + 1,
+        None => source.len(),
+    }
+}
+
+/// Parse the input, also returning the warnings found along the way, such as a name
+/// that is soft-reserved by the targeted version of PHP. Warnings never stop the input
+/// from parsing, and are dropped when it doesn't parse.
+pub fn parse_with_warnings<B: ?Sized + AsRef<[u8]>>(
+    input: &B,
+    config: ParserConfig,
+) -> Result<(Program, Vec<ParseError>), ParseErrorStack> {
+    let transcode = match config
+        .source_encoding
+        .and_then(|encoding| encoding.transcode)
+    {
+        Some(transcode) => transcode,
+        None => return parse_source(input.as_ref(), config),
+    };
+
+    let source = input.as_ref();
+    let transcoded = transcode(source);
+
+    match parse_source(&transcoded.bytes, config) {
+        Ok((program, mut warnings)) => {
+            for warning in &mut warnings {
+                transcoded.remap(source, warning);
+            }
+
+            Ok((program, warnings))
+        }
+        Err(mut stack) => {
+            for error in &mut stack.errors {
+                transcoded.remap(source, error);
+            }
+
+            Err(stack)
+        }
+    }
+}
+
+fn parse_source(
+    input: &[u8],
+    config: ParserConfig,
+) -> Result<(Program, Vec<ParseError>), ParseErrorStack> {
     let lexer = Lexer::new();
     let tokens = match lexer.tokenize(input) {
         Ok(tokens) => tokens,
@@ -65,16 +214,100 @@ pub fn parse<B: ?Sized + AsRef<[u8]>>(input: &B) -> Result<Program, ParseErrorSt
             return Err(ParseErrorStack {
                 errors: vec![error.into()],
                 partial: Vec::new(),
+                source_name: config.source_name,
             })
         }
     };
 
-    construct(&tokens)
+    construct_program(&tokens, config)
+}
+
+/// Parse a single expression, written without an opening tag or a trailing semicolon,
+/// rejecting any construct that `policy` doesn't allow.
+///
+/// This is meant for expressions written by end users, e.g. in a template engine.
+pub fn parse_restricted_expression<B: ?Sized + AsRef<[u8]>>(
+    input: &B,
+    policy: &ExpressionPolicy,
+) -> ParseResult<Expression> {
+    let lexer = Lexer::new();
+    let tokens = lexer.tokenize_scripting(input)?;
+
+    let mut stream = TokenStream::new(&tokens);
+    let mut state = State::new(&mut stream);
+    state.policy = Some(policy.clone());
+
+    let expression = expressions::create(&mut state)?;
+
+    if !state.stream.is_eof() {
+        return Err(error::unexpected_token(vec![], state.stream.current()));
+    }
+
+    if let Some(error) = state.errors.into_iter().next() {
+        return Err(error);
+    }
+
+    Ok(expression)
 }
 
 pub fn construct(tokens: &[Token]) -> Result<Program, ParseErrorStack> {
+    construct_with_config(tokens, ParserConfig::default())
+}
+
+pub fn construct_with_config(
+    tokens: &[Token],
+    config: ParserConfig,
+) -> Result<Program, ParseErrorStack> {
+    construct_program(tokens, config).map(|(program, _)| program)
+}
+
+/// Parse the input like [`construct_with_recovery`], returning the program along with
+/// every error found in it, which is empty when the input parses.
+pub fn parse_with_recovery<B: ?Sized + AsRef<[u8]>>(input: &B) -> (Program, Vec<ParseError>) {
+    match Lexer::new().tokenize(input) {
+        Ok(tokens) => construct_with_recovery(&tokens),
+        Err(error) => (Program::new(), vec![error.into()]),
+    }
+}
+
+/// Parse the tokens, carrying on after errors instead of stopping at the first one,
+/// e.g. for a file that is being edited.
+///
+/// A statement that fails to parse is replaced by a [`Statement::Error`], and the
+/// tokens up to its `;`, the `}` of the block it is in, or the keyword that starts
+/// the next statement are skipped. An expression that fails to parse is replaced by
+/// an [`Expression::Error`] in the same way, up to the `;`, `,` or closing delimiter
+/// that follows it, so that the statement or the arguments around it are kept.
+///
+/// The errors are returned in the order they were found, along with the errors that
+/// never stop the input from parsing, such as a feature the targeted version of PHP
+/// doesn't support.
+pub fn construct_with_recovery(tokens: &[Token]) -> (Program, Vec<ParseError>) {
     let mut stream = TokenStream::new(tokens);
     let mut state = State::new(&mut stream);
+    state.recover = true;
+
+    let mut program = Program::new();
+    while !state.stream.is_eof() {
+        match top_level_statement(&mut state) {
+            Ok(statement) => program.push(statement),
+            Err(error) => {
+                state.record(error);
+                break;
+            }
+        }
+    }
+
+    (program, state.errors)
+}
+
+fn construct_program(
+    tokens: &[Token],
+    config: ParserConfig,
+) -> Result<(Program, Vec<ParseError>), ParseErrorStack> {
+    let mut stream = TokenStream::new(tokens);
+    let mut state = State::new(&mut stream);
+    state.config = config;
 
     let mut program = Program::new();
 
@@ -88,6 +321,7 @@ pub fn construct(tokens: &[Token]) -> Result<Program, ParseErrorStack> {
                 return Err(ParseErrorStack {
                     errors: previous,
                     partial: program,
+                    source_name: state.config.source_name.clone(),
                 });
             }
         };
@@ -100,13 +334,58 @@ pub fn construct(tokens: &[Token]) -> Result<Program, ParseErrorStack> {
         return Err(ParseErrorStack {
             errors,
             partial: program,
+            source_name: state.config.source_name.clone(),
         });
     }
 
-    Ok(program.to_vec())
+    #[cfg(debug_assertions)]
+    if let Err(violation) = crate::spans::SpanInvariants::check_span_invariants(&program) {
+        panic!("the spans of the program are inconsistent: {}", violation);
+    }
+
+    Ok((program.to_vec(), state.warnings))
+}
+
+/// Parse the data after `__halt_compiler();` as PHP code. Errors are kept with the
+/// statements, as PHP never runs them, except for a cancellation, which stops the
+/// parse of the file too.
+fn after_halt(state: &mut State) -> ParseResult<Statement> {
+    let data = state.stream.current();
+    state.stream.next();
+
+    let tokens = Lexer::new().tokenize_scripting_at(&data.value[..], data.span);
+    let (statements, errors) = match tokens {
+        Ok(tokens) => match construct_program(&tokens, state.config.clone()) {
+            Ok((statements, warnings)) => {
+                state.warnings.extend(warnings);
+
+                (statements, vec![])
+            }
+            Err(stack) => (stack.partial, stack.errors),
+        },
+        Err(error) => (vec![], vec![error.into()]),
+    };
+
+    if let Some(cancelled) = errors
+        .iter()
+        .find(|error| error.category() == ErrorCategory::Cancelled)
+    {
+        return Err(cancelled.clone());
+    }
+
+    Ok(Statement::AfterHalt(AfterHaltStatement {
+        statements,
+        errors,
+    }))
 }
 
 fn top_level_statement(state: &mut State) -> ParseResult<Statement> {
+    recovery::statement(state, parse_top_level_statement)
+}
+
+fn parse_top_level_statement(state: &mut State) -> ParseResult<Statement> {
+    state.stream.forget_enclosed_comments();
+
     let statement = match &state.stream.current().kind {
         TokenKind::Namespace => namespaces::namespace(state)?,
         TokenKind::Use => uses::use_statement(state)?,
@@ -115,8 +394,11 @@ fn top_level_statement(state: &mut State) -> ParseResult<Statement> {
             state.stream.next();
 
             let content = if let TokenKind::InlineHtml = state.stream.current().kind.clone() {
-                let content = state.stream.current().value.clone();
-                state.stream.next();
+                let content = state.stream.current().value.to_byte_string();
+                // The data is parsed as the next statement.
+                if state.config.after_halt == AfterHaltMode::Data {
+                    state.stream.next();
+                }
                 Some(content)
             } else {
                 None
@@ -124,6 +406,14 @@ fn top_level_statement(state: &mut State) -> ParseResult<Statement> {
 
             Statement::HaltCompiler(HaltCompilerStatement { content })
         }
+        TokenKind::InlineHtml
+            if state
+                .stream
+                .last_consumed()
+                .is_some_and(|token| token.kind == TokenKind::HaltCompiler) =>
+        {
+            after_halt(state)?
+        }
         TokenKind::Type if state.stream.peek().kind == TokenKind::Identifier => {
             let type_keyword = utils::skip(state, TokenKind::Type)?;
             let name = identifier(state)?;
@@ -146,6 +436,16 @@ fn top_level_statement(state: &mut State) -> ParseResult<Statement> {
 }
 
 fn statement(state: &mut State) -> ParseResult<Statement> {
+    recovery::statement(state, parse_statement)
+}
+
+fn parse_statement(state: &mut State) -> ParseResult<Statement> {
+    state.stream.forget_enclosed_comments();
+
+    if let Some(statement) = extension::statement(state) {
+        return statement;
+    }
+
     let has_attributes = attributes::gather_attributes(state)?;
 
     let current = state.stream.current();
@@ -168,22 +468,15 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
             }
             TokenKind::Function
                 if identifiers::is_identifier_maybe_soft_reserved(&peek.kind)
-                    || peek.kind == TokenKind::Ampersand =>
+                    || peek.kind.kind_id() == KindId::Ampersand =>
             {
-                if peek.kind == TokenKind::Ampersand {
-                    if !identifiers::is_identifier_maybe_soft_reserved(
-                        &state.stream.lookahead(1).kind,
-                    ) {
-                        return Ok(Statement::Expression(ExpressionStatement {
-                            expression: expressions::attributes(state, &Precedence::Lowest)?,
-                            ending: utils::skip_ending(state)?,
-                        }));
-                    }
-
-                    functions::function(state)?
-                } else {
-                    functions::function(state)?
-                }
+                function_or_closure(state)?
+            }
+            TokenKind::Eof => {
+                return Err(error::missing_item_definition_after_attributes(
+                    &state.attributes,
+                    current,
+                ))
             }
             _ => Statement::Expression(ExpressionStatement {
                 expression: expressions::attributes(state, &Precedence::Lowest)?,
@@ -232,27 +525,14 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
             }
             TokenKind::Function
                 if identifiers::is_identifier_maybe_soft_reserved(&peek.kind)
-                    || peek.kind == TokenKind::Ampersand =>
+                    || peek.kind.kind_id() == KindId::Ampersand =>
             {
-                if peek.kind == TokenKind::Ampersand {
-                    if !identifiers::is_identifier_maybe_soft_reserved(
-                        &state.stream.lookahead(1).kind,
-                    ) {
-                        return Ok(Statement::Expression(ExpressionStatement {
-                            expression: expressions::attributes(state, &Precedence::Lowest)?,
-                            ending: utils::skip_ending(state)?,
-                        }));
-                    }
-
-                    functions::function(state)?
-                } else {
-                    functions::function(state)?
-                }
+                function_or_closure(state)?
             }
             TokenKind::Goto => goto::goto_statement(state)?,
             token
                 if identifiers::is_identifier_maybe_reserved(token)
-                    && peek.kind == TokenKind::Colon =>
+                    && peek.kind.kind_id() == KindId::Colon =>
             {
                 goto::label_statement(state)?
             }
@@ -265,15 +545,42 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                     loop {
                         let key = identifiers::identifier(state)?;
                         let span = utils::skip(state, TokenKind::Equals)?;
-                        let value = expect_literal!(state);
+
+                        // Values must be literals, but numbers can be negative.
+                        let minus = if state.stream.current().kind == TokenKind::Minus {
+                            let minus = state.stream.current().span;
+                            state.stream.next();
+
+                            Some(minus)
+                        } else {
+                            None
+                        };
+
+                        let current = state.stream.current();
+                        let (value_span, value_length) = (current.span, current.value.len());
+                        let value = if minus.is_some() {
+                            expect_literal!(state, [Integer, Float])
+                        } else {
+                            expect_literal!(state)
+                        };
+
+                        if key.value[..].eq_ignore_ascii_case(b"encoding")
+                            && !matches!(value, Literal::String(_))
+                        {
+                            state.record(error::declare_encoding_not_a_string(
+                                value_span,
+                                value_length,
+                            ));
+                        }
 
                         entries.push(DeclareEntry {
                             key,
                             equals: span,
+                            minus,
                             value,
                         });
 
-                        if state.stream.current().kind == TokenKind::Comma {
+                        if state.stream.current().kind.kind_id() == KindId::Comma {
                             state.stream.next();
                         } else {
                             break;
@@ -295,10 +602,8 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                         DeclareBody::Noop { semicolon: span }
                     }
                     TokenKind::LeftBrace => {
-                        let start = utils::skip_left_brace(state)?;
-                        let statements =
-                            blocks::multiple_statements_until(state, &TokenKind::RightBrace)?;
-                        let end = utils::skip_right_brace(state)?;
+                        let (start, statements, end) =
+                            blocks::braced_statements(state, &TokenKind::EndDeclare)?;
 
                         DeclareBody::Braced {
                             left_brace: start,
@@ -309,11 +614,16 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                     TokenKind::Colon => {
                         let start = utils::skip_colon(state)?;
                         let statements =
-                            blocks::multiple_statements_until(state, &TokenKind::EndDeclare)?;
-                        let end = (
-                            utils::skip(state, TokenKind::EndDeclare)?,
-                            utils::skip_semicolon(state)?,
-                        );
+                            blocks::alternative_statements(state, &[TokenKind::EndDeclare])?;
+                        let end = match blocks::skip_alternative_end(
+                            state,
+                            start,
+                            &TokenKind::EndDeclare,
+                        )? {
+                            (end, Ending::Semicolon(semicolon) | Ending::CloseTag(semicolon)) => {
+                                (end, semicolon)
+                            }
+                        };
 
                         DeclareBody::Block {
                             colon: start,
@@ -332,6 +642,16 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                     }
                 };
 
+                if !body.is_file_scoped() {
+                    if let Some(entry) = entries
+                        .entries
+                        .iter()
+                        .find(|entry| entry.key.value[..].eq_ignore_ascii_case(b"strict_types"))
+                    {
+                        state.record(error::strict_types_block_mode(&entry.key));
+                    }
+                }
+
                 Statement::Declare(DeclareStatement {
                     declare: span,
                     entries,
@@ -347,7 +667,7 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                 loop {
                     variables.push(variables::dynamic_variable(state)?);
 
-                    if state.stream.current().kind == TokenKind::Comma {
+                    if state.stream.current().kind.kind_id() == KindId::Comma {
                         state.stream.next();
                     } else {
                         break;
@@ -370,7 +690,7 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                     let var = variables::simple_variable(state)?;
                     let mut default = None;
 
-                    if state.stream.current().kind == TokenKind::Equals {
+                    if state.stream.current().kind.kind_id() == KindId::Equals {
                         state.stream.next();
 
                         default = Some(expressions::create(state)?);
@@ -381,7 +701,7 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                         default,
                     });
 
-                    if state.stream.current().kind == TokenKind::Comma {
+                    if state.stream.current().kind.kind_id() == KindId::Comma {
                         state.stream.next();
                     } else {
                         break;
@@ -393,10 +713,24 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                 Statement::Static(StaticStatement { vars })
             }
             TokenKind::InlineHtml => {
-                let html = state.stream.current().value.clone();
+                let current = state.stream.current();
                 state.stream.next();
 
-                Statement::InlineHtml(InlineHtmlStatement { html })
+                let html = match state.config.inline_html {
+                    InlineHtmlMode::Retain => InlineHtml::Retained(current.value.to_byte_string()),
+                    InlineHtmlMode::SpanOnly => InlineHtml::Dropped {
+                        length: current.value.len(),
+                    },
+                    InlineHtmlMode::Hash => InlineHtml::Hashed {
+                        hash: fnv1a(&current.value),
+                        length: current.value.len(),
+                    },
+                };
+
+                Statement::InlineHtml(InlineHtmlStatement {
+                    span: current.span,
+                    html,
+                })
             }
             TokenKind::Do => loops::do_while_statement(state)?,
             TokenKind::While => loops::while_statement(state)?,
@@ -422,7 +756,7 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                 loop {
                     values.push(expressions::create(state)?);
 
-                    if state.stream.current().kind == TokenKind::Comma {
+                    if state.stream.current().kind.kind_id() == KindId::Comma {
                         state.stream.next();
                     } else {
                         break;
@@ -453,6 +787,18 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
                     ending: utils::skip_ending(state)?,
                 })
             }
+            // A `throw` statement, as opposed to a `throw` expression.
+            TokenKind::Throw => {
+                state.stream.next();
+
+                Statement::Expression(ExpressionStatement {
+                    expression: Expression::Throw(ThrowExpression {
+                        throw: current.span,
+                        value: Box::new(expressions::create(state)?),
+                    }),
+                    ending: utils::skip_ending(state)?,
+                })
+            }
             _ => Statement::Expression(ExpressionStatement {
                 expression: expressions::create(state)?,
                 ending: utils::skip_ending(state)?,
@@ -462,3 +808,83 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
 
     Ok(statement)
 }
+
+/// Parse a statement that starts with `function`, which declares a function when the
+/// keyword is followed by a name, with or without a `&` in between, and is otherwise
+/// an expression starting with a closure, as in `function &() {};`.
+fn function_or_closure(state: &mut State) -> ParseResult<Statement> {
+    let name = match state.stream.peek() {
+        token if token.kind.kind_id() == KindId::Ampersand => state.stream.lookahead(1),
+        token => token,
+    };
+
+    if identifiers::is_identifier_maybe_soft_reserved(&name.kind) {
+        return functions::function(state);
+    }
+
+    Ok(Statement::Expression(ExpressionStatement {
+        expression: expressions::attributes(state, &Precedence::Lowest)?,
+        ending: utils::skip_ending(state)?,
+    }))
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, which unlike the hashers of the standard library
+/// is the same on every platform and with every version of Rust.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Write `code` to a file of its own, named after `name`.
+    fn file(name: &str, code: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("pxp-parser-{}.php", name));
+        std::fs::write(&path, code).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let code = "<?php\nrequire __DIR__ . '/vendor/autoload.php';\n\n(new App)->run();\n";
+
+        for (name, prefix) in [
+            ("plain", ""),
+            ("bom", "\u{feff}"),
+            ("shebang", "#!/usr/bin/env php\n"),
+            ("bom-shebang", "\u{feff}#!/usr/bin/env php\r\n"),
+        ] {
+            let path = file(name, format!("{}{}", prefix, code).as_bytes());
+            let program = parse_file(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(program.unwrap(), parse(code).unwrap(), "{}", name);
+        }
+
+        let path = file("missing", b"");
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(parse_file(&path), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn test_parse_file_errors() {
+        let code = b"\xEF\xBB\xBF#!/usr/bin/env php\n<?php\necho 1 +;";
+        let path = file("errors", code);
+        let result = parse_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let Err(Error::Parse(stack)) = result else {
+            panic!("expected the file not to parse");
+        };
+        assert_eq!(stack.source_name, Some(path));
+        assert_eq!(stack.errors[0].span.line, 3);
+        assert_eq!(stack.errors[0].span.column, 9);
+        assert_eq!(stack.errors[0].span.position, 36);
+    }
+}
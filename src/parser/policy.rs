@@ -0,0 +1,191 @@
+use crate::lexer::byte_string::ByteString;
+
+/// The functions and methods that a restricted expression is allowed to call.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CallPolicy {
+    /// Any function or method may be called.
+    All,
+    /// Nothing may be called.
+    None,
+    /// Only functions with one of the given names may be called.
+    ///
+    /// Names are compared case-insensitively and without a leading `\`. Method calls
+    /// and calls to a dynamic name, such as `$foo()`, are never allowed.
+    Allowlist(Vec<ByteString>),
+}
+
+/// The constructs allowed in an expression parsed by [`crate::parse_restricted_expression`].
+///
+/// The default policy allows variables and operators that have no side effects;
+/// anything else has to be allowed explicitly.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExpressionPolicy {
+    pub(crate) calls: CallPolicy,
+    pub(crate) new: bool,
+    pub(crate) assignment: bool,
+    pub(crate) shell_exec: bool,
+    pub(crate) include: bool,
+    pub(crate) variables: bool,
+}
+
+impl Default for ExpressionPolicy {
+    fn default() -> Self {
+        Self {
+            calls: CallPolicy::None,
+            new: false,
+            assignment: false,
+            shell_exec: false,
+            include: false,
+            variables: true,
+        }
+    }
+}
+
+impl ExpressionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Which functions and methods may be called, including the `eval`, `exit`, `die`
+    /// and `print` language constructs.
+    pub fn allow_calls(mut self, calls: CallPolicy) -> Self {
+        self.calls = calls;
+
+        self
+    }
+
+    /// Allow `new Foo()` and anonymous classes.
+    pub fn allow_new(mut self, allow: bool) -> Self {
+        self.new = allow;
+
+        self
+    }
+
+    /// Allow assignments, including compound assignments such as `.=` and increments
+    /// such as `$a++`.
+    pub fn allow_assignment(mut self, allow: bool) -> Self {
+        self.assignment = allow;
+
+        self
+    }
+
+    /// Allow backtick shell execution.
+    pub fn allow_shell_exec(mut self, allow: bool) -> Self {
+        self.shell_exec = allow;
+
+        self
+    }
+
+    /// Allow `include`, `include_once`, `require` and `require_once`.
+    pub fn allow_include(mut self, allow: bool) -> Self {
+        self.include = allow;
+
+        self
+    }
+
+    /// Allow variables, including variables interpolated into strings.
+    pub fn allow_variables(mut self, allow: bool) -> Self {
+        self.variables = allow;
+
+        self
+    }
+
+    pub(crate) fn allows_call(&self, name: &[u8]) -> bool {
+        match &self.calls {
+            CallPolicy::All => true,
+            CallPolicy::None => false,
+            CallPolicy::Allowlist(names) => {
+                let name = name.strip_prefix(b"\\").unwrap_or(name);
+
+                names.iter().any(|allowed| {
+                    let allowed = allowed.strip_prefix(b"\\").unwrap_or(allowed);
+
+                    allowed.eq_ignore_ascii_case(name)
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::Expression;
+    use crate::parser::error::ParseResult;
+
+    fn parse(code: &str, policy: &ExpressionPolicy) -> ParseResult<Expression> {
+        crate::parse_restricted_expression(code, policy)
+    }
+
+    #[test]
+    fn test_allowlisted_calls() {
+        let policy =
+            ExpressionPolicy::new().allow_calls(CallPolicy::Allowlist(vec!["count".into()]));
+
+        assert!(parse("count($items) > 0 ? $user->name ?? 'guest' : ''", &policy).is_ok());
+        assert!(parse("\\COUNT($items)", &policy).is_ok());
+
+        let error = parse("system('x')", &policy).unwrap_err();
+        assert_eq!(error.id, "E061");
+        assert_eq!(
+            error.message,
+            "calling `system` is not allowed by the expression policy"
+        );
+        assert_eq!((error.span.position, error.annotations[0].length), (0, 6));
+
+        let error = parse("$user->delete()", &policy).unwrap_err();
+        assert_eq!(error.span.position, 5);
+
+        assert!(parse("$name()", &policy).is_err());
+        assert!(parse("exit(1)", &policy).is_err());
+        assert!(parse("system('x')", &policy.allow_calls(CallPolicy::All)).is_ok());
+    }
+
+    #[test]
+    fn test_shell_exec() {
+        let error = parse("$a . `rm -rf /`", &ExpressionPolicy::new()).unwrap_err();
+
+        assert_eq!(error.id, "E061");
+        assert_eq!(
+            error.message,
+            "shell exec is not allowed by the expression policy"
+        );
+        assert_eq!((error.span.line, error.span.column), (1, 6));
+
+        assert!(parse("`ls`", &ExpressionPolicy::new().allow_shell_exec(true)).is_ok());
+    }
+
+    #[test]
+    fn test_side_effects() {
+        let policy = ExpressionPolicy::new();
+
+        assert!(parse("$a = 1", &policy).is_err());
+        assert!(parse("$a .= 'b'", &policy).is_err());
+        assert!(parse("$a++", &policy).is_err());
+        assert!(parse("new Foo()", &policy).is_err());
+        assert!(parse("new class {}", &policy).is_err());
+        assert!(parse("include 'foo.php'", &policy).is_err());
+        assert!(parse(
+            "$a = new Foo()",
+            &policy.allow_assignment(true).allow_new(true)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_variables() {
+        let policy = ExpressionPolicy::new().allow_variables(false);
+
+        assert!(parse("1 + 2 * 3", &policy).is_ok());
+        assert!(parse("'a' . $b", &policy).is_err());
+        assert!(parse("\"hello {$name}\"", &policy).is_err());
+    }
+
+    #[test]
+    fn test_single_expression() {
+        let policy = ExpressionPolicy::new();
+
+        assert!(parse("1; 2", &policy).is_err());
+        assert!(parse("", &policy).is_err());
+    }
+}
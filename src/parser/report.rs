@@ -0,0 +1,763 @@
+//! Reports of several diagnostics with the source they are about, see [`render_report`].
+
+use crate::parser::error::ErrorCategory;
+use crate::parser::error::ParseError;
+use crate::parser::error::ParseErrorAnnotationType;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const CYAN: &str = "\x1b[1;36m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// Options that change how [`render_report`] shows diagnostics.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RenderOptions {
+    pub(crate) colored: bool,
+    pub(crate) tab_width: usize,
+    pub(crate) context_lines: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            colored: false,
+            tab_width: 4,
+            context_lines: 2,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Color the report with ANSI escape codes, for a terminal.
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+
+        self
+    }
+
+    /// Show tabs as `tab_width` spaces, so that the markers under the code line up with
+    /// it whatever the tab stops of the terminal are.
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+
+        self
+    }
+
+    /// Show `lines` lines of the source before and after each line with a marker.
+    pub fn context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+
+        self
+    }
+}
+
+/// Render diagnostics, like the errors of a [`ParseErrorStack`] or the warnings of
+/// [`parse_with_warnings`], as a report in the style of `rustc`: each diagnostic with
+/// its code and message, followed by the lines of the source it marks, some lines
+/// around them, and its note.
+///
+/// The primary annotations of a diagnostic are marked with `^` and the others with
+/// `-`. An annotation over several lines is marked where it starts and where it ends,
+/// with a line joining the two in the margin.
+///
+/// [`ParseErrorStack`]: crate::parser::error::ParseErrorStack
+/// [`parse_with_warnings`]: crate::parser::parse_with_warnings
+pub fn render_report(
+    source: &[u8],
+    file_name: &str,
+    diagnostics: &[ParseError],
+    options: RenderOptions,
+) -> String {
+    let renderer = Renderer {
+        source: Source::new(source),
+        file_name,
+        options,
+    };
+
+    diagnostics
+        .iter()
+        .map(|diagnostic| renderer.diagnostic(diagnostic))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The lines of a source.
+struct Source<'a> {
+    lines: Vec<&'a [u8]>,
+    starts: Vec<usize>,
+}
+
+impl<'a> Source<'a> {
+    fn new(source: &'a [u8]) -> Self {
+        let mut lines = Vec::new();
+        let mut starts = Vec::new();
+
+        let mut start = 0;
+        for line in source.split(|byte| *byte == b'\n') {
+            starts.push(start);
+            start += line.len() + 1;
+
+            lines.push(line.strip_suffix(b"\r").unwrap_or(line));
+        }
+
+        Self { lines, starts }
+    }
+
+    /// The line of a position, and its byte offset in that line.
+    fn locate(&self, position: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|start| *start <= position) - 1;
+
+        (
+            line,
+            (position - self.starts[line]).min(self.lines[line].len()),
+        )
+    }
+
+    /// The number of columns taken by the first `bytes` bytes of a line.
+    fn width(&self, line: usize, bytes: usize, tab_width: usize) -> usize {
+        String::from_utf8_lossy(&self.lines[line][..bytes])
+            .chars()
+            .map(|char| if char == '\t' { tab_width } else { 1 })
+            .sum()
+    }
+
+    fn text(&self, line: usize, tab_width: usize) -> String {
+        String::from_utf8_lossy(self.lines[line]).replace('\t', &" ".repeat(tab_width))
+    }
+}
+
+/// An annotation of a diagnostic, placed on the lines of the source.
+struct Marker {
+    primary: bool,
+    message: String,
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    /// The column after the end of an annotation on a single line, or the column of
+    /// the last character of one over several lines.
+    end_column: usize,
+}
+
+impl Marker {
+    fn multiline(&self) -> bool {
+        self.end_line > self.start_line
+    }
+}
+
+struct Renderer<'a> {
+    source: Source<'a>,
+    file_name: &'a str,
+    options: RenderOptions,
+}
+
+impl Renderer<'_> {
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.options.colored && !text.is_empty() {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn diagnostic(&self, diagnostic: &ParseError) -> String {
+        let (level, color) = match diagnostic.category() {
+            ErrorCategory::Warning => ("warning", YELLOW),
+            ErrorCategory::Info => ("info", CYAN),
+            _ => ("error", RED),
+        };
+
+        let markers = self.markers(diagnostic);
+        let multiline = markers
+            .iter()
+            .filter(|marker| marker.multiline())
+            .collect::<Vec<&Marker>>();
+
+        // The empty line after a final newline is only shown when it is marked.
+        let last_line = match self.source.lines.len() - 1 {
+            last if last > 0 && self.source.lines[last].is_empty() => last - 1,
+            last => last,
+        };
+
+        let mut shown = Vec::new();
+        for marker in &markers {
+            for line in [marker.start_line, marker.end_line] {
+                let first = line.saturating_sub(self.options.context_lines);
+                let last = (line + self.options.context_lines).min(last_line.max(line));
+
+                shown.extend(first..=last);
+            }
+        }
+        shown.sort_unstable();
+        shown.dedup();
+
+        let gutter = (shown.last().copied().unwrap_or_default() + 1)
+            .to_string()
+            .len();
+        let margin = " ".repeat(gutter);
+        let bar = self.paint(BLUE, "|");
+
+        let mut report = vec![
+            format!(
+                "{}{}",
+                self.paint(color, &format!("{}[{}]", level, diagnostic.id)),
+                self.paint(BOLD, &format!(": {}", diagnostic.message))
+            ),
+            format!(
+                "{}{} {}:{}:{}",
+                margin,
+                self.paint(BLUE, "-->"),
+                self.file_name,
+                diagnostic.span.line,
+                diagnostic.span.column
+            ),
+            format!("{} {}", margin, bar),
+        ];
+
+        let mut previous: Option<usize> = None;
+        for line in shown {
+            if previous.is_some_and(|previous| line > previous + 1) {
+                report.push(self.paint(BLUE, "..."));
+            }
+            previous = Some(line);
+
+            let mut row = Row::new(multiline.len() * 2);
+            for (depth, marker) in multiline.iter().enumerate() {
+                if marker.start_line < line && line <= marker.end_line {
+                    row.put(depth * 2, '|', Some(color_of(marker, color)));
+                }
+            }
+
+            report.push(format!(
+                "{} {} {}{}",
+                self.paint(BLUE, &format!("{:>width$}", line + 1, width = gutter)),
+                bar,
+                self.cells(&row),
+                self.source.text(line, self.options.tab_width)
+            ));
+
+            for row in self.marker_rows(line, &markers, &multiline, color) {
+                report.push(format!("{} {} {}", margin, bar, self.cells(&row)));
+            }
+        }
+
+        let mut footer = Vec::new();
+        if let Some(note) = &diagnostic.note {
+            footer.push(format!(
+                "{} {} {}: {}",
+                margin,
+                self.paint(BLUE, "="),
+                self.paint(BOLD, "note"),
+                note
+            ));
+        }
+
+        for annotation in &diagnostic.annotations {
+            if annotation.r#type == ParseErrorAnnotationType::Suggestion {
+                footer.push(format!(
+                    "{} {} {}: {}",
+                    margin,
+                    self.paint(BLUE, "="),
+                    self.paint(BOLD, "help"),
+                    annotation.message
+                ));
+            }
+        }
+
+        if !footer.is_empty() {
+            report.push(format!("{} {}", margin, bar));
+            report.extend(footer);
+        }
+
+        let mut report = report
+            .iter()
+            .map(|row| row.trim_end())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        report.push('\n');
+
+        report
+    }
+
+    fn markers(&self, diagnostic: &ParseError) -> Vec<Marker> {
+        let tab_width = self.options.tab_width;
+        let length = self.source.starts.last().unwrap() + self.source.lines.last().unwrap().len();
+
+        let mut annotations = diagnostic
+            .annotations
+            .iter()
+            .filter(|annotation| annotation.r#type != ParseErrorAnnotationType::Suggestion)
+            .map(|annotation| {
+                (
+                    annotation.r#type == ParseErrorAnnotationType::Error,
+                    annotation.message.clone(),
+                    annotation.position,
+                    annotation.length,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if annotations.is_empty() {
+            annotations.push((true, String::new(), diagnostic.span.position, 0));
+        }
+
+        annotations
+            .into_iter()
+            .map(|(primary, message, position, length_)| {
+                let start = position.min(length);
+                let end = (position + length_).min(length).max(start);
+
+                let (start_line, start_byte) = self.source.locate(start);
+                let (end_line, end_byte) = self.source.locate(end.saturating_sub(1).max(start));
+
+                if end_line > start_line {
+                    return Marker {
+                        primary,
+                        message,
+                        start_line,
+                        start_column: self.source.width(start_line, start_byte, tab_width),
+                        end_line,
+                        end_column: self.source.width(end_line, end_byte, tab_width),
+                    };
+                }
+
+                let start_column = self.source.width(start_line, start_byte, tab_width);
+                let end_byte = (start_byte + end - start).min(self.source.lines[start_line].len());
+
+                Marker {
+                    primary,
+                    message,
+                    start_line,
+                    start_column,
+                    end_line: start_line,
+                    end_column: self
+                        .source
+                        .width(start_line, end_byte, tab_width)
+                        .max(start_column + 1),
+                }
+            })
+            .collect()
+    }
+
+    /// The rows under a line of the source, which mark the annotations on it.
+    fn marker_rows(
+        &self,
+        line: usize,
+        markers: &[Marker],
+        multiline: &[&Marker],
+        color: &'static str,
+    ) -> Vec<Row> {
+        let margin = multiline.len() * 2;
+        let bars = |row: &mut Row, active: &dyn Fn(usize, &Marker) -> bool| {
+            for (depth, marker) in multiline.iter().enumerate() {
+                if active(depth, marker) {
+                    row.put(depth * 2, '|', Some(color_of(marker, color)));
+                }
+            }
+        };
+
+        let mut rows = Vec::new();
+
+        let mut single = markers
+            .iter()
+            .filter(|marker| !marker.multiline() && marker.start_line == line)
+            .collect::<Vec<&Marker>>();
+        single.sort_by_key(|marker| marker.start_column);
+
+        if let Some(last) = single.last() {
+            let active =
+                |_: usize, marker: &Marker| marker.start_line < line && line <= marker.end_line;
+
+            let mut row = Row::new(margin);
+            bars(&mut row, &active);
+            for marker in &single {
+                let underline = if marker.primary { '^' } else { '-' };
+                for column in marker.start_column..marker.end_column {
+                    row.put(margin + column, underline, Some(color_of(marker, color)));
+                }
+            }
+
+            if !last.message.is_empty() {
+                let end = row.cells.len();
+                row.write(end + 1, &last.message, color_of(last, color));
+            }
+            rows.push(row);
+
+            let pending = single[..single.len() - 1]
+                .iter()
+                .filter(|marker| !marker.message.is_empty())
+                .collect::<Vec<_>>();
+
+            if !pending.is_empty() {
+                let mut row = Row::new(margin);
+                bars(&mut row, &active);
+                for marker in &pending {
+                    row.put(
+                        margin + marker.start_column,
+                        '|',
+                        Some(color_of(marker, color)),
+                    );
+                }
+                rows.push(row);
+            }
+
+            for (index, marker) in pending.iter().enumerate().rev() {
+                let mut row = Row::new(margin);
+                bars(&mut row, &active);
+                for left in &pending[..index] {
+                    row.put(margin + left.start_column, '|', Some(color_of(left, color)));
+                }
+                row.write(
+                    margin + marker.start_column,
+                    &marker.message,
+                    color_of(marker, color),
+                );
+                rows.push(row);
+            }
+        }
+
+        for (depth, marker) in multiline.iter().enumerate() {
+            if marker.start_line != line {
+                continue;
+            }
+
+            let mut row = Row::new(margin);
+            bars(&mut row, &|other, other_marker: &Marker| {
+                (other_marker.start_line < line
+                    || (other_marker.start_line == line && other < depth))
+                    && line <= other_marker.end_line
+            });
+
+            let color = color_of(marker, color);
+            for column in depth * 2 + 1..margin + marker.start_column {
+                row.put(column, '_', Some(color));
+            }
+            row.put(
+                margin + marker.start_column,
+                if marker.primary { '^' } else { '-' },
+                Some(color),
+            );
+            rows.push(row);
+        }
+
+        for (depth, marker) in multiline.iter().enumerate() {
+            if marker.end_line != line {
+                continue;
+            }
+
+            let mut row = Row::new(margin);
+            bars(&mut row, &|other, other_marker: &Marker| {
+                other != depth
+                    && other_marker.start_line <= line
+                    && (line < other_marker.end_line
+                        || (line == other_marker.end_line && other > depth))
+            });
+
+            let color = color_of(marker, color);
+            row.put(depth * 2, '|', Some(color));
+            for column in depth * 2 + 1..margin + marker.end_column {
+                row.put(column, '_', Some(color));
+            }
+            row.put(
+                margin + marker.end_column,
+                if marker.primary { '^' } else { '-' },
+                Some(color),
+            );
+
+            if !marker.message.is_empty() {
+                let end = row.cells.len();
+                row.write(end + 1, &marker.message, color);
+            }
+            rows.push(row);
+        }
+
+        rows
+    }
+
+    fn cells(&self, row: &Row) -> String {
+        let mut text = String::new();
+
+        let mut run = String::new();
+        let mut run_color = None;
+        for (char, color) in &row.cells {
+            if *color != run_color {
+                self.flush(&mut text, &mut run, run_color);
+                run_color = *color;
+            }
+
+            run.push(*char);
+        }
+        self.flush(&mut text, &mut run, run_color);
+
+        text
+    }
+
+    fn flush(&self, text: &mut String, run: &mut String, color: Option<&str>) {
+        match color {
+            Some(color) => text.push_str(&self.paint(color, run)),
+            None => text.push_str(run),
+        }
+
+        run.clear();
+    }
+}
+
+/// The color of the markers of an annotation of a diagnostic of the given color.
+fn color_of(marker: &Marker, color: &'static str) -> &'static str {
+    if marker.primary {
+        color
+    } else {
+        BLUE
+    }
+}
+
+/// A row of the report under a line of the source, made of colored characters.
+struct Row {
+    cells: Vec<(char, Option<&'static str>)>,
+}
+
+impl Row {
+    fn new(width: usize) -> Self {
+        Self {
+            cells: vec![(' ', None); width],
+        }
+    }
+
+    fn put(&mut self, column: usize, char: char, color: Option<&'static str>) {
+        if self.cells.len() <= column {
+            self.cells.resize(column + 1, (' ', None));
+        }
+
+        self.cells[column] = (char, color);
+    }
+
+    fn write(&mut self, column: usize, text: &str, color: &'static str) {
+        for (offset, char) in text.chars().enumerate() {
+            self.put(column + offset, char, Some(color));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::Span;
+    use crate::lexer::token::Token;
+    use crate::lexer::token::TokenKind;
+    use crate::parser::error;
+
+    const SOURCE: &str = "<?php
+
+function run() {
+\treturn 1;
+\techo 'never';
+\techo 'nor this';
+}
+
+if ($a) {
+    echo 1;
+endif;
+
+foo(name: 1, 3);
+";
+
+    /// The span of the first occurrence of `needle` in the source.
+    fn span(needle: &str) -> Span {
+        let position = SOURCE.find(needle).unwrap();
+        let line = SOURCE[..position].matches('\n').count() + 1;
+        let column = position - SOURCE[..position].rfind('\n').map_or(0, |end| end + 1) + 1;
+
+        Span::new(line, column, position)
+    }
+
+    fn diagnostics() -> Vec<ParseError> {
+        let endif = Token {
+            kind: TokenKind::EndIf,
+            span: span("endif"),
+            value: "endif".into(),
+        };
+
+        vec![
+            error::unreachable_code(
+                "`return`",
+                span("return"),
+                9,
+                span("echo 'never'"),
+                span(";\n}").with_length(1),
+            ),
+            error::mismatched_body_closer(span("{\n    echo"), &TokenKind::RightBrace, &endif),
+            error::cannot_use_positional_argument_after_named_argument(
+                span("3)"),
+                span(");"),
+                false,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_report() {
+        let report = render_report(
+            SOURCE.as_bytes(),
+            "run.php",
+            &diagnostics(),
+            RenderOptions::new(),
+        );
+
+        assert_eq!(
+            report,
+            "warning[E082]: unreachable code
+ --> run.php:5:2
+  |
+2 |
+3 |   function run() {
+4 |       return 1;
+  |       ---------
+5 |       echo 'never';
+  |  _____^
+6 | |     echo 'nor this';
+  | |____________________^ this code is never run
+7 |   }
+8 |
+  |
+  = note: execution never gets past the `return` on line 4
+
+error[E060]: cannot close a body opened with `{` using `endif`
+  --> run.php:11:1
+   |
+ 7 | }
+ 8 |
+ 9 | if ($a) {
+   |         -
+10 |     echo 1;
+11 | endif;
+   | ^^^^^ try replacing this with `}`
+12 |
+13 | foo(name: 1, 3);
+   |
+   = note: a body must use either braces or the alternative syntax throughout
+
+error[E044]: cannot use positional argument after named argument
+  --> run.php:13:14
+   |
+11 | endif;
+12 |
+13 | foo(name: 1, 3);
+   |              ^ try adding a name for this argument
+"
+        );
+    }
+
+    #[test]
+    fn test_colored_report() {
+        let colored = render_report(
+            SOURCE.as_bytes(),
+            "run.php",
+            &diagnostics(),
+            RenderOptions::new().colored(true),
+        );
+
+        let mut plain = String::new();
+        let mut rest = colored.as_str();
+        while let Some(start) = rest.find('\x1b') {
+            plain.push_str(&rest[..start]);
+            rest = &rest[start + rest[start..].find('m').unwrap() + 1..];
+        }
+        plain.push_str(rest);
+
+        assert_eq!(
+            plain,
+            render_report(
+                SOURCE.as_bytes(),
+                "run.php",
+                &diagnostics(),
+                RenderOptions::new()
+            )
+        );
+
+        let lines = colored.lines().collect::<Vec<&str>>();
+        assert_eq!(
+            lines[0],
+            "\x1b[1;33mwarning[E082]\x1b[0m\x1b[1m: unreachable code\x1b[0m"
+        );
+        assert_eq!(
+            lines[10],
+            "  \x1b[1;34m|\x1b[0m \x1b[1;33m|____________________^\x1b[0m \x1b[1;33mthis code is never run\x1b[0m"
+        );
+        assert_eq!(
+            lines[22],
+            "   \x1b[1;34m|\x1b[0m         \x1b[1;34m-\x1b[0m"
+        );
+        assert_eq!(
+            lines.last().unwrap(),
+            &"   \x1b[1;34m|\x1b[0m              \x1b[1;31m^\x1b[0m \x1b[1;31mtry adding a name for this argument\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_tab_width() {
+        let report = render_report(
+            SOURCE.as_bytes(),
+            "run.php",
+            &diagnostics()[..1],
+            RenderOptions::new().tab_width(2).context_lines(0),
+        );
+
+        assert_eq!(
+            report,
+            "warning[E082]: unreachable code
+ --> run.php:5:2
+  |
+4 |     return 1;
+  |     ---------
+5 |     echo 'never';
+  |  ___^
+6 | |   echo 'nor this';
+  | |__________________^ this code is never run
+  |
+  = note: execution never gets past the `return` on line 4
+"
+        );
+    }
+
+    #[test]
+    fn test_long_file() {
+        let source = "<?php\n".to_string() + &"echo 1;\n".repeat(1200);
+        let start = |line: usize| 6 + (line - 2) * 8;
+
+        let diagnostic =
+            ParseError::new("E005", "unexpected `echo`", Span::new(1000, 1, start(1000)))
+                .highlight(start(3), 4)
+                .error("unexpected `echo`", start(1000), 4);
+
+        assert_eq!(
+            render_report(
+                source.as_bytes(),
+                "long.php",
+                &[diagnostic],
+                RenderOptions::new()
+            ),
+            "error[E005]: unexpected `echo`
+    --> long.php:1000:1
+     |
+   1 | <?php
+   2 | echo 1;
+   3 | echo 1;
+     | ----
+   4 | echo 1;
+   5 | echo 1;
+...
+ 998 | echo 1;
+ 999 | echo 1;
+1000 | echo 1;
+     | ^^^^ unexpected `echo`
+1001 | echo 1;
+1002 | echo 1;
+"
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use crate::parser::version::PhpVersion;
+
+/// How a word is reserved.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Reservation {
+    /// A keyword of the language.
+    Keyword,
+    /// A name the type system uses, such as `int` or `never`. These can't name a
+    /// class-like, but most can still name a function or a constant.
+    Type,
+    /// Reserved for future use. Class-likes can still be named after these, but the
+    /// parser warns about it.
+    Soft,
+}
+
+/// The position a name is declared in, which decides which reserved words it may use.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NameKind {
+    /// The name of a class, interface, trait or enum, or of a `use` alias.
+    Type,
+    Function,
+    Constant,
+}
+
+impl NameKind {
+    pub(crate) fn describe(&self) -> &'static str {
+        match self {
+            NameKind::Type => "type",
+            NameKind::Function => "function",
+            NameKind::Constant => "constant",
+        }
+    }
+}
+
+/// A row of [`RESERVED_WORDS`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ReservedWord {
+    /// The word, in lowercase; names are compared case-insensitively.
+    pub word: &'static str,
+    /// The first version that reserves the word this way. Words reserved since
+    /// [`PhpVersion::OLDEST`] are reserved in every version the parser targets.
+    pub since: PhpVersion,
+    pub reservation: Reservation,
+    /// Whether the word can't name a function.
+    pub functions: bool,
+    /// Whether the word can't name a global constant.
+    pub constants: bool,
+}
+
+impl ReservedWord {
+    /// Whether the word can't, or shouldn't, be used for a name of the given kind.
+    pub fn applies_to(&self, kind: NameKind) -> bool {
+        match kind {
+            NameKind::Type => true,
+            NameKind::Function => self.functions,
+            NameKind::Constant => self.constants,
+        }
+    }
+}
+
+const fn keyword(word: &'static str, since: PhpVersion) -> ReservedWord {
+    ReservedWord {
+        word,
+        since,
+        reservation: Reservation::Keyword,
+        functions: true,
+        constants: true,
+    }
+}
+
+const fn type_name(word: &'static str, since: PhpVersion) -> ReservedWord {
+    ReservedWord {
+        word,
+        since,
+        reservation: Reservation::Type,
+        functions: false,
+        constants: false,
+    }
+}
+
+const fn soft(word: &'static str, since: PhpVersion) -> ReservedWord {
+    ReservedWord {
+        word,
+        since,
+        reservation: Reservation::Soft,
+        functions: false,
+        constants: false,
+    }
+}
+
+/// The words that can't, or shouldn't, be used to name a class-like, a function or a
+/// constant, and the version of PHP that reserved them.
+///
+/// A word can appear more than once, e.g. `object` was soft-reserved in PHP 7.0 and
+/// became a type in PHP 7.2; the latest row that the targeted version has reached wins.
+/// Method names, class constant names and property names can use any of these.
+pub static RESERVED_WORDS: &[ReservedWord] = &[
+    keyword("__halt_compiler", PhpVersion::Php70),
+    keyword("abstract", PhpVersion::Php70),
+    keyword("and", PhpVersion::Php70),
+    keyword("array", PhpVersion::Php70),
+    keyword("as", PhpVersion::Php70),
+    keyword("break", PhpVersion::Php70),
+    keyword("callable", PhpVersion::Php70),
+    keyword("case", PhpVersion::Php70),
+    keyword("catch", PhpVersion::Php70),
+    keyword("class", PhpVersion::Php70),
+    keyword("clone", PhpVersion::Php70),
+    keyword("const", PhpVersion::Php70),
+    keyword("continue", PhpVersion::Php70),
+    keyword("declare", PhpVersion::Php70),
+    keyword("default", PhpVersion::Php70),
+    keyword("die", PhpVersion::Php70),
+    keyword("do", PhpVersion::Php70),
+    keyword("echo", PhpVersion::Php70),
+    keyword("else", PhpVersion::Php70),
+    keyword("elseif", PhpVersion::Php70),
+    keyword("empty", PhpVersion::Php70),
+    keyword("enddeclare", PhpVersion::Php70),
+    keyword("endfor", PhpVersion::Php70),
+    keyword("endforeach", PhpVersion::Php70),
+    keyword("endif", PhpVersion::Php70),
+    keyword("endswitch", PhpVersion::Php70),
+    keyword("endwhile", PhpVersion::Php70),
+    keyword("eval", PhpVersion::Php70),
+    keyword("exit", PhpVersion::Php70),
+    keyword("extends", PhpVersion::Php70),
+    keyword("final", PhpVersion::Php70),
+    keyword("finally", PhpVersion::Php70),
+    keyword("for", PhpVersion::Php70),
+    keyword("foreach", PhpVersion::Php70),
+    keyword("function", PhpVersion::Php70),
+    keyword("global", PhpVersion::Php70),
+    keyword("goto", PhpVersion::Php70),
+    keyword("if", PhpVersion::Php70),
+    keyword("implements", PhpVersion::Php70),
+    keyword("include", PhpVersion::Php70),
+    keyword("include_once", PhpVersion::Php70),
+    keyword("instanceof", PhpVersion::Php70),
+    keyword("insteadof", PhpVersion::Php70),
+    keyword("interface", PhpVersion::Php70),
+    keyword("isset", PhpVersion::Php70),
+    keyword("list", PhpVersion::Php70),
+    keyword("namespace", PhpVersion::Php70),
+    keyword("new", PhpVersion::Php70),
+    keyword("or", PhpVersion::Php70),
+    keyword("print", PhpVersion::Php70),
+    keyword("private", PhpVersion::Php70),
+    keyword("protected", PhpVersion::Php70),
+    keyword("public", PhpVersion::Php70),
+    keyword("require", PhpVersion::Php70),
+    keyword("require_once", PhpVersion::Php70),
+    keyword("return", PhpVersion::Php70),
+    keyword("static", PhpVersion::Php70),
+    keyword("switch", PhpVersion::Php70),
+    keyword("throw", PhpVersion::Php70),
+    keyword("trait", PhpVersion::Php70),
+    keyword("try", PhpVersion::Php70),
+    keyword("unset", PhpVersion::Php70),
+    keyword("use", PhpVersion::Php70),
+    keyword("var", PhpVersion::Php70),
+    keyword("while", PhpVersion::Php70),
+    keyword("xor", PhpVersion::Php70),
+    keyword("yield", PhpVersion::Php70),
+    keyword("__class__", PhpVersion::Php70),
+    keyword("__dir__", PhpVersion::Php70),
+    keyword("__file__", PhpVersion::Php70),
+    keyword("__function__", PhpVersion::Php70),
+    keyword("__line__", PhpVersion::Php70),
+    keyword("__method__", PhpVersion::Php70),
+    keyword("__namespace__", PhpVersion::Php70),
+    keyword("__trait__", PhpVersion::Php70),
+    keyword("fn", PhpVersion::Php74),
+    keyword("match", PhpVersion::Php80),
+    // `readonly` became a keyword in PHP 8.1, but `readonly()` is still parsed as a
+    // function call, so functions can be named after it.
+    ReservedWord {
+        functions: false,
+        ..keyword("readonly", PhpVersion::Php81)
+    },
+    // `self` and `parent` refer to a class, so only class-likes can't be named after them.
+    ReservedWord {
+        functions: false,
+        constants: false,
+        ..keyword("self", PhpVersion::Php70)
+    },
+    ReservedWord {
+        functions: false,
+        constants: false,
+        ..keyword("parent", PhpVersion::Php70)
+    },
+    type_name("bool", PhpVersion::Php70),
+    // `true`, `false` and `null` are constants themselves, so they can't be redeclared.
+    ReservedWord {
+        constants: true,
+        ..type_name("false", PhpVersion::Php70)
+    },
+    type_name("float", PhpVersion::Php70),
+    type_name("int", PhpVersion::Php70),
+    ReservedWord {
+        constants: true,
+        ..type_name("null", PhpVersion::Php70)
+    },
+    type_name("string", PhpVersion::Php70),
+    ReservedWord {
+        constants: true,
+        ..type_name("true", PhpVersion::Php70)
+    },
+    type_name("iterable", PhpVersion::Php71),
+    type_name("void", PhpVersion::Php71),
+    soft("object", PhpVersion::Php70),
+    type_name("object", PhpVersion::Php72),
+    soft("mixed", PhpVersion::Php70),
+    type_name("mixed", PhpVersion::Php80),
+    type_name("never", PhpVersion::Php81),
+    soft("numeric", PhpVersion::Php70),
+    soft("resource", PhpVersion::Php70),
+    // `enum` is only a keyword when it is followed by a name, so `class Enum` still works.
+    soft("enum", PhpVersion::Php81),
+];
+
+/// Look up how `word` is reserved for a name of the given kind, when targeting `version`.
+pub fn reservation(
+    word: &[u8],
+    kind: NameKind,
+    version: PhpVersion,
+) -> Option<&'static ReservedWord> {
+    RESERVED_WORDS
+        .iter()
+        .rev()
+        .find(|reserved| {
+            reserved.since <= version && reserved.word.as_bytes().eq_ignore_ascii_case(word)
+        })
+        .filter(|reserved| reserved.applies_to(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::config::ParserConfig;
+    use crate::parser::error::ParseError;
+
+    fn parse(code: &str, version: PhpVersion) -> Result<Vec<String>, Vec<ParseError>> {
+        let config = ParserConfig::new().php_version(version);
+
+        match crate::parse_with_warnings(code, config) {
+            Ok((_, warnings)) => Ok(warnings.into_iter().map(|warning| warning.id).collect()),
+            Err(stack) => Err(stack.errors),
+        }
+    }
+
+    fn reserved(word: &str, kind: NameKind, version: PhpVersion) -> Option<Reservation> {
+        reservation(word.as_bytes(), kind, version).map(|reserved| reserved.reservation)
+    }
+
+    #[test]
+    fn test_reservation_by_version() {
+        assert_eq!(reserved("Match", NameKind::Type, PhpVersion::Php74), None);
+        assert_eq!(
+            reserved("Match", NameKind::Type, PhpVersion::Php80),
+            Some(Reservation::Keyword)
+        );
+        assert_eq!(
+            reserved("object", NameKind::Type, PhpVersion::Php71),
+            Some(Reservation::Soft)
+        );
+        assert_eq!(
+            reserved("object", NameKind::Type, PhpVersion::Php72),
+            Some(Reservation::Type)
+        );
+        assert_eq!(reserved("Enum", NameKind::Type, PhpVersion::Php80), None);
+        assert_eq!(
+            reserved("Enum", NameKind::Type, PhpVersion::Php81),
+            Some(Reservation::Soft)
+        );
+    }
+
+    #[test]
+    fn test_reservation_by_kind() {
+        assert_eq!(
+            reserved("int", NameKind::Function, PhpVersion::LATEST),
+            None
+        );
+        assert_eq!(
+            reserved("readonly", NameKind::Function, PhpVersion::LATEST),
+            None
+        );
+        assert_eq!(
+            reserved("readonly", NameKind::Constant, PhpVersion::LATEST),
+            Some(Reservation::Keyword)
+        );
+        assert_eq!(
+            reserved("list", NameKind::Function, PhpVersion::OLDEST),
+            Some(Reservation::Keyword)
+        );
+        assert_eq!(
+            reserved("self", NameKind::Constant, PhpVersion::LATEST),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rows_are_lowercase_and_ordered() {
+        for (index, reserved) in RESERVED_WORDS.iter().enumerate() {
+            assert_eq!(reserved.word, reserved.word.to_lowercase());
+
+            for earlier in &RESERVED_WORDS[..index] {
+                assert!(
+                    earlier.word != reserved.word || earlier.since < reserved.since,
+                    "`{}` is listed out of order",
+                    reserved.word
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_class_names_by_version() {
+        assert_eq!(parse("<?php class Match {}", PhpVersion::Php74), Ok(vec![]));
+
+        let errors = parse("<?php class Match {}", PhpVersion::Php80).unwrap_err();
+        assert_eq!(errors[0].id, "E063");
+        assert_eq!(
+            errors[0].message,
+            "cannot use `Match` as a type name, as it is reserved since PHP 8.0"
+        );
+
+        assert_eq!(parse("<?php class Enum {}", PhpVersion::Php80), Ok(vec![]));
+        assert_eq!(
+            parse("<?php class Enum {}", PhpVersion::Php81),
+            Ok(vec!["E064".to_string()])
+        );
+
+        assert_eq!(
+            parse("<?php interface Readonly {}", PhpVersion::Php80),
+            Ok(vec![])
+        );
+        assert!(parse("<?php interface Readonly {}", PhpVersion::Php81).is_err());
+
+        assert!(parse("<?php trait Mixed {}", PhpVersion::Php74)
+            .is_ok_and(|warnings| warnings == ["E064"]));
+        assert!(parse("<?php trait Mixed {}", PhpVersion::Php80).is_err());
+
+        for version in PhpVersion::ALL {
+            let errors = parse("<?php class List {}", *version).unwrap_err();
+            assert_eq!(errors[0].id, "E045");
+        }
+    }
+
+    #[test]
+    fn test_function_and_constant_names() {
+        assert_eq!(
+            parse("<?php function int() {}", PhpVersion::LATEST),
+            Ok(vec![])
+        );
+        assert_eq!(
+            parse("<?php function readonly() {}", PhpVersion::LATEST),
+            Ok(vec![])
+        );
+
+        let errors = parse("<?php function list() {}", PhpVersion::LATEST).unwrap_err();
+        assert_eq!(
+            errors[0].message,
+            "cannot use reserved keyword `list` as a function name"
+        );
+
+        assert_eq!(
+            parse("<?php const SELF = 1;", PhpVersion::LATEST),
+            Ok(vec![])
+        );
+        assert_eq!(
+            parse("<?php const READONLY = 1;", PhpVersion::Php80),
+            Ok(vec![])
+        );
+
+        let errors = parse("<?php const READONLY = 1;", PhpVersion::Php81).unwrap_err();
+        assert_eq!(
+            errors[0].message,
+            "cannot use `READONLY` as a constant name, as it is reserved since PHP 8.1"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Lines and columns of a source, see [`SourceMap`].
+
+use crate::parser::error::ParseError;
+
+/// What columns are counted in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum PositionEncoding {
+    /// UTF-16 code units, the default of the Language Server Protocol.
+    #[default]
+    Utf16,
+    /// Bytes, as in the columns of a [`Span`].
+    ///
+    /// [`Span`]: crate::lexer::token::Span
+    Utf8,
+    /// Characters.
+    Utf32,
+}
+
+/// The positions where the lines of a source start, built once, to turn the byte
+/// positions of spans into lines and columns and back.
+///
+/// Lines end at `\n`, `\r\n` or a lone `\r`, as they do for PHP. The lexer only counts
+/// `\n`, so in a source with lone `\r`s the lines of a map are further along than those
+/// of spans.
+#[derive(Debug, Clone)]
+pub struct SourceMap<'a> {
+    source: &'a [u8],
+    /// The position of the first byte of each line.
+    starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(source: &'a [u8]) -> Self {
+        let mut starts = vec![0];
+        for (position, byte) in source.iter().enumerate() {
+            match byte {
+                b'\n' => starts.push(position + 1),
+                b'\r' if source.get(position + 1) != Some(&b'\n') => starts.push(position + 1),
+                _ => {}
+            }
+        }
+
+        Self { source, starts }
+    }
+
+    /// The source that the map is of.
+    pub fn source(&self) -> &'a [u8] {
+        self.source
+    }
+
+    /// The number of lines, where a source that ends with a newline ends with an empty
+    /// line.
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// The line and column of the byte at `offset`, both counted from 1, with the
+    /// column in bytes. An offset past the end is that of the end.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        self.line_col_in(offset, PositionEncoding::Utf8)
+    }
+
+    /// The line and column of the byte at `offset`, both counted from 1, with the
+    /// column in `encoding`.
+    ///
+    /// Invalid UTF-8 counts as one character for each sequence of invalid bytes, the way
+    /// it is shown in place of them.
+    pub fn line_col_in(&self, offset: usize, encoding: PositionEncoding) -> (usize, usize) {
+        let offset = offset.min(self.source.len());
+        let line = self.starts.partition_point(|start| *start <= offset);
+        let text = &self.source[self.starts[line - 1]..offset];
+
+        let column = match encoding {
+            PositionEncoding::Utf8 => text.len(),
+            PositionEncoding::Utf16 => String::from_utf8_lossy(text).encode_utf16().count(),
+            PositionEncoding::Utf32 => String::from_utf8_lossy(text).chars().count(),
+        };
+
+        (line, column + 1)
+    }
+
+    /// The position of the byte at a line and a column in bytes, both counted from 1.
+    /// The column can be one past the end of the line, where its newline is.
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        if line == 0 || column == 0 || line > self.starts.len() {
+            return None;
+        }
+
+        if column > self.line_text(line).len() + 1 {
+            return None;
+        }
+
+        Some(self.starts[line - 1] + column - 1)
+    }
+
+    /// The text of a line, counted from 1, without its newline, or nothing for a line
+    /// past the end.
+    pub fn line_text(&self, line: usize) -> &'a [u8] {
+        if line == 0 || line > self.starts.len() {
+            return &[];
+        }
+
+        let start = self.starts[line - 1];
+        let mut end = self.starts.get(line).copied().unwrap_or(self.source.len());
+
+        while end > start && matches!(self.source[end - 1], b'\n' | b'\r') {
+            end -= 1;
+        }
+
+        &self.source[start..end]
+    }
+}
+
+impl ParseError {
+    /// The line and column of the error, both counted from 1, with the column in bytes.
+    pub fn position(&self, map: &SourceMap) -> (usize, usize) {
+        map.line_col(self.span.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newlines() {
+        let map = SourceMap::new(b"<?php\r\necho 1;\rmac\n\nlast");
+
+        assert_eq!(map.line_count(), 5);
+        assert_eq!(map.line_text(1), b"<?php");
+        assert_eq!(map.line_text(2), b"echo 1;");
+        assert_eq!(map.line_text(3), b"mac");
+        assert_eq!(map.line_text(4), b"");
+        assert_eq!(map.line_text(5), b"last");
+        assert_eq!(map.line_text(6), b"");
+
+        assert_eq!(map.line_col(0), (1, 1));
+        assert_eq!(map.line_col(5), (1, 6));
+        assert_eq!(map.line_col(7), (2, 1));
+        assert_eq!(map.line_col(15), (3, 1));
+        assert_eq!(map.line_col(24), (5, 5));
+        assert_eq!(map.line_col(100), (5, 5));
+
+        assert_eq!(map.offset(2, 1), Some(7));
+        assert_eq!(map.offset(5, 5), Some(24));
+        assert_eq!(map.offset(5, 6), None);
+        assert_eq!(map.offset(6, 1), None);
+        assert_eq!(map.offset(0, 1), None);
+
+        let ends_with_newline = SourceMap::new(b"<?php\n");
+        assert_eq!(ends_with_newline.line_count(), 2);
+        assert_eq!(ends_with_newline.line_col(6), (2, 1));
+    }
+
+    #[test]
+    fn test_multi_byte_columns() {
+        let code = "<?php\n$s = \"😀é\"; $a = ;\n";
+        let map = SourceMap::new(code.as_bytes());
+        let semicolon = code.rfind(';').unwrap();
+
+        assert_eq!(map.line_col(semicolon), (2, 21));
+        assert_eq!(map.line_col_in(semicolon, PositionEncoding::Utf16), (2, 18));
+        assert_eq!(map.line_col_in(semicolon, PositionEncoding::Utf32), (2, 17));
+        assert_eq!(map.offset(2, 21), Some(semicolon));
+
+        let error = &crate::parse(code).unwrap_err().errors[0];
+        assert_eq!(error.position(&map), (error.span.line, error.span.column));
+        assert_eq!(error.position(&map), (2, 21));
+    }
+}
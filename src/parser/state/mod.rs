@@ -1,10 +1,21 @@
 use std::collections::VecDeque;
 use std::fmt::Display;
 
+use crate::lexer::byte_string::ByteString;
 use crate::lexer::stream::TokenStream;
+use crate::lexer::token::Span;
+use crate::lexer::token::TokenKind;
 use crate::parser::ast::attributes::AttributeGroup;
 use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::UseKind;
+use crate::parser::cancellation::CANCELLATION_INTERVAL;
+use crate::parser::config::ParserConfig;
+use crate::parser::error;
 use crate::parser::error::ParseError;
+use crate::parser::error::ParseResult;
+use crate::parser::policy::ExpressionPolicy;
+use crate::parser::symbols::SymbolContainer;
+use crate::parser::version::Feature;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum NamespaceType {
@@ -18,6 +29,16 @@ pub enum Scope {
     BracedNamespace(Option<SimpleIdentifier>),
 }
 
+/// A name imported by a `use` statement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Import {
+    pub kind: UseKind,
+    /// The name the import is known by: its alias, or else the last part of its name.
+    pub alias: SimpleIdentifier,
+    /// The imported name, without a leading `\`.
+    pub name: ByteString,
+}
+
 #[derive(Debug)]
 pub struct State<'a> {
     pub stack: VecDeque<Scope>,
@@ -25,6 +46,36 @@ pub struct State<'a> {
     pub attributes: Vec<AttributeGroup>,
     pub namespace_type: Option<NamespaceType>,
     pub errors: Vec<ParseError>,
+    pub warnings: Vec<ParseError>,
+    /// The number of enclosing loop and `switch` constructs that a
+    /// `break` or `continue` statement is able to target.
+    pub loop_depth: usize,
+    pub config: ParserConfig,
+    /// The constructs allowed while parsing a restricted expression.
+    pub policy: Option<ExpressionPolicy>,
+    /// The classes, interfaces, traits and enums being parsed, outermost first. Only
+    /// tracked when symbols are reported.
+    pub containers: Vec<SymbolContainer>,
+    /// The names imported in the current namespace.
+    pub imports: Vec<Import>,
+    /// The `goto` labels declared in the current function, method or closure, or
+    /// else outside of any of them.
+    pub labels: Vec<SimpleIdentifier>,
+    /// The number of enclosing `finally` blocks in the current function.
+    pub finally_depth: usize,
+    /// Whether the current function returns by reference, which makes a generator
+    /// yield by reference.
+    pub by_reference: bool,
+    /// Whether a statement or an expression that fails to parse is skipped and parsing
+    /// carries on, see [`construct_with_recovery`].
+    ///
+    /// [`construct_with_recovery`]: crate::parser::construct_with_recovery
+    pub recover: bool,
+    /// The closing keywords of the enclosing bodies that use the alternative syntax,
+    /// innermost last, e.g. `endif` and `endforeach`.
+    pub alternative_ends: Vec<TokenKind>,
+    /// The number of tokens left when the cancellation token was last looked at.
+    cancellation_checked_at: usize,
 }
 
 impl<'a> State<'a> {
@@ -35,6 +86,18 @@ impl<'a> State<'a> {
             namespace_type: None,
             attributes: vec![],
             errors: vec![],
+            warnings: vec![],
+            loop_depth: 0,
+            config: ParserConfig::default(),
+            policy: None,
+            containers: vec![],
+            imports: vec![],
+            labels: vec![],
+            finally_depth: 0,
+            by_reference: false,
+            recover: false,
+            alternative_ends: vec![],
+            cancellation_checked_at: usize::MAX,
         }
     }
 
@@ -54,6 +117,44 @@ impl<'a> State<'a> {
         self.errors.push(error);
     }
 
+    pub fn warn(&mut self, warning: ParseError) {
+        self.warnings.push(warning);
+    }
+
+    /// Record an error if the targeted version of PHP doesn't support `feature`, used
+    /// at the `length` bytes from `span`.
+    pub fn require(&mut self, feature: Feature, span: Span, length: usize) {
+        let version = self.config.php_version;
+
+        if version < feature.since() {
+            self.record(error::unsupported_feature(feature, version, span, length));
+        }
+    }
+
+    /// Fail if the parse has been cancelled, see [`ParserConfig::cancellation`].
+    ///
+    /// The token is only looked at once the parser has moved through a number of tokens
+    /// since it was last looked at, so this is cheap enough to call in any loop.
+    ///
+    /// [`ParserConfig::cancellation`]: crate::parser::config::ParserConfig::cancellation
+    pub fn check_cancelled(&mut self) -> ParseResult<()> {
+        let Some(token) = &self.config.cancellation else {
+            return Ok(());
+        };
+
+        let left = self.stream.upcoming().len();
+        if self.cancellation_checked_at.abs_diff(left) < CANCELLATION_INTERVAL {
+            return Ok(());
+        }
+
+        self.cancellation_checked_at = left;
+        if token.is_cancelled() {
+            return Err(error::cancelled(self.stream.current().span));
+        }
+
+        Ok(())
+    }
+
     /// Return the namespace type used in the current state
     ///
     /// The namespace type is retrieve from the last entered
@@ -78,7 +179,10 @@ impl<'a> State<'a> {
         }
     }
 
+    /// Enter a namespace, which starts without any imports.
     pub fn enter(&mut self, scope: Scope) {
+        self.imports.clear();
+
         match &scope {
             Scope::Namespace(_) => {
                 self.namespace_type = Some(NamespaceType::Unbraced);
@@ -0,0 +1,321 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::lexer::token::SpanRange;
+use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::state::Scope;
+use crate::parser::state::State;
+use crate::utils::preview_tokens;
+use crate::utils::PREVIEW_LENGTH;
+
+/// The kind of a declaration reported by [`ParserConfig::on_symbol`].
+///
+/// [`ParserConfig::on_symbol`]: crate::parser::config::ParserConfig::on_symbol
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SymbolKind {
+    /// Only appears as a [`SymbolContainer`], namespaces aren't reported themselves.
+    Namespace,
+    Class,
+    Interface,
+    Trait,
+    Enum,
+    Function,
+    Method,
+    Property,
+    /// A global constant, or a constant of a class, interface, trait or enum.
+    Constant,
+    EnumCase,
+    /// A closure or an arrow function, named after a preview of its code, e.g.
+    /// `function ($x) use ($y) { …`.
+    Closure,
+}
+
+/// A declaration that encloses a symbol.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SymbolContainer {
+    pub kind: SymbolKind,
+    /// The name as written, e.g. `App\Models` for a namespace. Anonymous classes are
+    /// named `class@anonymous`.
+    pub name: ByteString,
+}
+
+/// A declaration that the parser has just finished parsing.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SymbolEvent {
+    pub kind: SymbolKind,
+    pub name: ByteString,
+    /// The range of the name, e.g. `Foo` in `class Foo {}` or `$bar` in `public $bar;`.
+    pub selection: SpanRange,
+    /// The range of the whole declaration, including its attributes and modifiers.
+    /// Properties and constants declared together, e.g. `const A = 1, B = 2;`, share
+    /// the range of the declaration.
+    pub span: SpanRange,
+    /// The enclosing declarations, outermost first, e.g. the namespace and the class
+    /// of a method.
+    pub container: Vec<SymbolContainer>,
+}
+
+/// The function given to [`ParserConfig::on_symbol`].
+///
+/// [`ParserConfig::on_symbol`]: crate::parser::config::ParserConfig::on_symbol
+#[derive(Clone)]
+pub struct SymbolHandler(pub(crate) Arc<dyn Fn(SymbolEvent) + Send + Sync>);
+
+impl Debug for SymbolHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SymbolHandler")
+    }
+}
+
+/// Handlers are compared by identity, as closures can't be compared.
+impl PartialEq for SymbolHandler {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SymbolHandler {}
+
+/// Make `name` the container of the symbols parsed until the matching [`exit`].
+pub(crate) fn enter(state: &mut State, kind: SymbolKind, name: &ByteString) {
+    if state.config.on_symbol.is_some() {
+        state.containers.push(SymbolContainer {
+            kind,
+            name: name.clone(),
+        });
+    }
+}
+
+pub(crate) fn exit(state: &mut State) {
+    state.containers.pop();
+}
+
+/// The start of a declaration: its first attribute, or else `first`, the span of its
+/// first modifier or keyword.
+pub(crate) fn start(attributes: &[AttributeGroup], first: Span) -> Span {
+    attributes.first().map_or(first, |group| group.start)
+}
+
+/// Report a declaration that starts at `start` and ends with the last consumed token.
+pub(crate) fn emit(
+    state: &State,
+    kind: SymbolKind,
+    name: &ByteString,
+    name_span: Span,
+    start: Span,
+) {
+    let selection = name_span.to(after(name_span, name.len()));
+
+    report(state, kind, name, selection, start);
+}
+
+/// Report a closure or an arrow function that starts at `start`, selecting its
+/// `function` or `fn` keyword of `length` bytes.
+pub(crate) fn emit_closure(state: &State, keyword: Span, length: usize, start: Span) {
+    if state.config.on_symbol.is_none() {
+        return;
+    }
+
+    let tokens = state.stream.consumed_since(start.position);
+    let name = ByteString::from(preview_tokens(tokens, PREVIEW_LENGTH));
+
+    report(
+        state,
+        SymbolKind::Closure,
+        &name,
+        keyword.to(after(keyword, length)),
+        start,
+    );
+}
+
+fn report(state: &State, kind: SymbolKind, name: &ByteString, selection: SpanRange, start: Span) {
+    let Some(handler) = &state.config.on_symbol else {
+        return;
+    };
+
+    let end = state
+        .stream
+        .last_consumed()
+        .map_or(start, |last| after(last.span, last.value.len()));
+
+    let mut container = Vec::with_capacity(state.containers.len() + 1);
+    if let Some(Scope::Namespace(namespace) | Scope::BracedNamespace(Some(namespace))) =
+        state.namespace()
+    {
+        container.push(SymbolContainer {
+            kind: SymbolKind::Namespace,
+            name: namespace.value.clone(),
+        });
+    }
+    container.extend(state.containers.iter().cloned());
+
+    (handler.0)(SymbolEvent {
+        kind,
+        name: name.clone(),
+        selection,
+        span: start.to(end),
+        container,
+    });
+}
+
+/// The point just after a token of `length` bytes that starts at `span`.
+fn after(span: Span, length: usize) -> Span {
+    Span::new(span.line, span.column + length, span.position + length)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::parser::config::ParserConfig;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0388-document-symbols/code.php");
+
+    fn symbols(code: &str) -> (bool, Vec<SymbolEvent>) {
+        let events = Arc::new(Mutex::new(vec![]));
+        let collected = events.clone();
+
+        let config = ParserConfig::new().on_symbol(move |event| {
+            collected.lock().unwrap().push(event);
+        });
+        let parsed = crate::parse_with_config(code, config).is_ok();
+
+        let events = events.lock().unwrap().clone();
+
+        (parsed, events)
+    }
+
+    fn outline(events: &[SymbolEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| {
+                let container = event
+                    .container
+                    .iter()
+                    .map(|container| container.name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" > ");
+
+                format!("{:?} {} in [{}]", event.kind, event.name, container)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_symbols_are_reported_in_order() {
+        let (parsed, events) = symbols(SOURCE);
+
+        assert!(parsed);
+        assert_eq!(
+            outline(&events),
+            vec![
+                "Constant VERSION in [App\\Models]",
+                "Constant BUILD in [App\\Models]",
+                "Function helper in [App\\Models]",
+                "Constant PREFIX in [App\\Models > HasName]",
+                "Method name in [App\\Models > HasName]",
+                "Interface HasName in [App\\Models]",
+                "Property $greeting in [App\\Models > Greets]",
+                "Method greet in [App\\Models > Greets]",
+                "Trait Greets in [App\\Models]",
+                "Constant TABLE in [App\\Models > User]",
+                "Property $first in [App\\Models > User]",
+                "Property $last in [App\\Models > User]",
+                "Method __construct in [App\\Models > User]",
+                "Property $anonymous in [App\\Models > User > class@anonymous]",
+                "Method name in [App\\Models > User]",
+                "Class User in [App\\Models]",
+                "EnumCase Active in [App\\Models > Status]",
+                "EnumCase Inactive in [App\\Models > Status]",
+                "Method label in [App\\Models > Status]",
+                "Enum Status in [App\\Models]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_symbol_ranges() {
+        let (_, events) = symbols(SOURCE);
+        let text = |range: SpanRange| &SOURCE[range.start.position..range.end.position];
+
+        let class = events.iter().find(|e| e.kind == SymbolKind::Class).unwrap();
+        assert_eq!(text(class.selection), "User");
+        assert!(text(class.span).starts_with("#[Entity]\nfinal class User"));
+        assert!(text(class.span).ends_with("    }\n}"));
+        assert_eq!((class.span.start.line, class.span.end.line), (26, 43));
+
+        let last = events.iter().find(|e| e.name == b"$last").unwrap();
+        assert_eq!(text(last.selection), "$last");
+        assert_eq!(
+            text(last.span),
+            "private ?string $first = null, $last = null;"
+        );
+
+        let build = events.iter().find(|e| e.name == b"BUILD").unwrap();
+        assert_eq!(text(build.span), "const VERSION = 1, BUILD = 2;");
+
+        let greeting = events.iter().find(|e| e.name == b"$greeting").unwrap();
+        assert_eq!(text(greeting.span), "var $greeting;");
+
+        let case = events
+            .iter()
+            .find(|e| e.kind == SymbolKind::EnumCase)
+            .unwrap();
+        assert_eq!(text(case.span), "case Active = 'active';");
+    }
+
+    #[test]
+    fn test_symbols_before_an_error_are_reported() {
+        let (parsed, events) = symbols(
+            "<?php class Foo { public function bar() {} } function baz() {} $x = ; function qux() {}",
+        );
+
+        assert!(!parsed);
+        assert_eq!(
+            outline(&events),
+            vec![
+                "Method bar in [Foo]",
+                "Class Foo in []",
+                "Function baz in []"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_closures_are_named_after_their_code() {
+        let code = "<?php class A { function b() {\n    return [static fn ($x) => $x * 2, function ($y) use ($z) {\n        // The sum.\n        return $y + $z + strlen('a string that makes the closure too long');\n    }];\n} }";
+        let (_, events) = symbols(code);
+
+        assert_eq!(
+            outline(&events[2..]),
+            vec!["Method b in [A]", "Class A in []"]
+        );
+        assert_eq!(events[0].kind, SymbolKind::Closure);
+        assert_eq!(
+            String::from(events[0].name.clone()),
+            "static fn ($x) => $x * 2"
+        );
+        assert_eq!(events[1].kind, SymbolKind::Closure);
+        assert_eq!(
+            String::from(events[1].name.clone()),
+            "function ($y) use ($z) { return $y + $z + strlen(…"
+        );
+        assert_eq!(events[1].container[0].name, b"A");
+
+        let text = |range: SpanRange| &code[range.start.position..range.end.position];
+        assert_eq!(text(events[0].selection), "fn");
+        assert_eq!(text(events[0].span), "static fn ($x) => $x * 2");
+        assert_eq!(text(events[1].selection), "function");
+        assert!(text(events[1].span).ends_with("too long');\n    }"));
+    }
+
+    #[test]
+    fn test_braced_namespaces() {
+        let (_, events) = symbols("<?php namespace A { class B {} } namespace { function c() {} }");
+
+        assert_eq!(outline(&events), vec!["Class B in [A]", "Function c in []"]);
+    }
+}
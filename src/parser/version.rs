@@ -0,0 +1,227 @@
+use std::fmt::{Display, Formatter};
+
+/// A version of PHP that the parser can target, given to the parser with
+/// [`ParserConfig::php_version`].
+///
+/// Versions are ordered, so `version >= PhpVersion::Php80` reads as "PHP 8.0 or later".
+/// The parser targets the latest version by default.
+///
+/// [`ParserConfig::php_version`]: crate::parser::config::ParserConfig::php_version
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub enum PhpVersion {
+    Php70,
+    Php71,
+    Php72,
+    Php73,
+    Php74,
+    Php80,
+    Php81,
+    Php82,
+    #[default]
+    Php83,
+}
+
+impl PhpVersion {
+    /// Every version the parser can target, from the oldest to the latest.
+    pub const ALL: &'static [PhpVersion] = &[
+        PhpVersion::Php70,
+        PhpVersion::Php71,
+        PhpVersion::Php72,
+        PhpVersion::Php73,
+        PhpVersion::Php74,
+        PhpVersion::Php80,
+        PhpVersion::Php81,
+        PhpVersion::Php82,
+        PhpVersion::Php83,
+    ];
+
+    pub const OLDEST: PhpVersion = PhpVersion::Php70;
+    pub const LATEST: PhpVersion = PhpVersion::Php83;
+}
+
+impl Display for PhpVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let version = match self {
+            PhpVersion::Php70 => "7.0",
+            PhpVersion::Php71 => "7.1",
+            PhpVersion::Php72 => "7.2",
+            PhpVersion::Php73 => "7.3",
+            PhpVersion::Php74 => "7.4",
+            PhpVersion::Php80 => "8.0",
+            PhpVersion::Php81 => "8.1",
+            PhpVersion::Php82 => "8.2",
+            PhpVersion::Php83 => "8.3",
+        };
+
+        write!(f, "PHP {}", version)
+    }
+}
+
+/// A syntax feature that the oldest versions the parser targets don't support.
+///
+/// When the targeted version is older than [`Feature::since`], using the feature is an
+/// error, but the parser carries on as if it was supported.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Feature {
+    /// `?int`
+    NullableTypes,
+    /// `['a' => $a] = $b` or `list('a' => $a) = $b`
+    KeyedListAssignments,
+    /// `[$a, $b] = $c`
+    ShortListAssignments,
+    /// `public const FOO = 1;`
+    ClassConstantVisibility,
+    /// `catch (A | B $e)`
+    MultiCatch,
+    /// `use Foo\{A, B,};`
+    TrailingCommaInGroupedUses,
+    /// `foo($a, $b,)`
+    TrailingCommaInArguments,
+    /// `[&$a, $b] = $c` or `list('a' => &$a) = $b`
+    ReferenceAssignmentsInLists,
+    /// `fn ($a) => $a`
+    ArrowFunctions,
+    /// `$a ??= $b`
+    NullCoalescingAssignment,
+    /// `[...$a]`
+    SpreadInArrays,
+    /// `public int $foo;`
+    TypedProperties,
+    /// `match ($a) { ... }`
+    Match,
+    /// `$a?->b`
+    NullsafeOperator,
+    /// `foo(a: 1)`
+    NamedArguments,
+    /// `int|string`
+    UnionTypes,
+    /// `public function __construct(private int $a) {}`
+    ConstructorPromotion,
+    /// `function foo(): static`
+    StaticReturnType,
+    /// `$a ?? throw new Exception()`
+    ThrowExpressions,
+    /// `catch (Exception)`
+    CatchWithoutVariable,
+    /// `function foo($a, $b,) {}` or `function () use ($a, $b,) {}`
+    TrailingCommaInParameters,
+    /// `$a::class`
+    ClassNameOfObjects,
+    /// `enum Foo {}`
+    Enums,
+    /// `public readonly int $foo;`
+    ReadonlyProperties,
+    /// `A&B`
+    IntersectionTypes,
+    /// `function foo($a = new Foo()) {}`
+    NewInInitializers,
+    /// `foo(...)`
+    FirstClassCallables,
+    /// `final public const FOO = 1;`
+    FinalClassConstants,
+    /// `0o17`
+    ExplicitOctalLiterals,
+    /// `function foo(): never`
+    NeverType,
+    /// `readonly class Foo {}`
+    ReadonlyClasses,
+    /// `(A&B)|null`
+    DisjunctiveNormalFormTypes,
+    /// `true`, and `null` or `false` on their own.
+    LiteralTypes,
+    /// `trait Foo { const BAR = 1; }`
+    ConstantsInTraits,
+    /// `Foo::{$bar}`
+    DynamicClassConstantFetch,
+    /// `const int FOO = 1;`
+    TypedClassConstants,
+}
+
+impl Feature {
+    /// The first version of PHP that supports the feature.
+    pub const fn since(&self) -> PhpVersion {
+        match self {
+            Feature::NullableTypes
+            | Feature::KeyedListAssignments
+            | Feature::ShortListAssignments
+            | Feature::ClassConstantVisibility
+            | Feature::MultiCatch => PhpVersion::Php71,
+            Feature::TrailingCommaInGroupedUses => PhpVersion::Php72,
+            Feature::TrailingCommaInArguments | Feature::ReferenceAssignmentsInLists => {
+                PhpVersion::Php73
+            }
+            Feature::ArrowFunctions
+            | Feature::NullCoalescingAssignment
+            | Feature::SpreadInArrays
+            | Feature::TypedProperties => PhpVersion::Php74,
+            Feature::Match
+            | Feature::NullsafeOperator
+            | Feature::NamedArguments
+            | Feature::UnionTypes
+            | Feature::ConstructorPromotion
+            | Feature::StaticReturnType
+            | Feature::ThrowExpressions
+            | Feature::CatchWithoutVariable
+            | Feature::TrailingCommaInParameters
+            | Feature::ClassNameOfObjects => PhpVersion::Php80,
+            Feature::Enums
+            | Feature::ReadonlyProperties
+            | Feature::IntersectionTypes
+            | Feature::NewInInitializers
+            | Feature::FirstClassCallables
+            | Feature::FinalClassConstants
+            | Feature::ExplicitOctalLiterals
+            | Feature::NeverType => PhpVersion::Php81,
+            Feature::ReadonlyClasses
+            | Feature::DisjunctiveNormalFormTypes
+            | Feature::LiteralTypes
+            | Feature::ConstantsInTraits => PhpVersion::Php82,
+            Feature::DynamicClassConstantFetch | Feature::TypedClassConstants => PhpVersion::Php83,
+        }
+    }
+}
+
+impl Display for Feature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let feature = match self {
+            Feature::NullableTypes => "nullable types",
+            Feature::KeyedListAssignments => "keys in list assignments",
+            Feature::ShortListAssignments => "short list assignments",
+            Feature::ClassConstantVisibility => "class constant visibility modifiers",
+            Feature::MultiCatch => "catching multiple exception types",
+            Feature::TrailingCommaInGroupedUses => "trailing commas in grouped `use` statements",
+            Feature::TrailingCommaInArguments => "trailing commas in argument lists",
+            Feature::ReferenceAssignmentsInLists => "references in list assignments",
+            Feature::ArrowFunctions => "arrow functions",
+            Feature::NullCoalescingAssignment => "the `??=` operator",
+            Feature::SpreadInArrays => "unpacking inside arrays",
+            Feature::TypedProperties => "typed properties",
+            Feature::Match => "`match` expressions",
+            Feature::NullsafeOperator => "the `?->` operator",
+            Feature::NamedArguments => "named arguments",
+            Feature::UnionTypes => "union types",
+            Feature::ConstructorPromotion => "constructor property promotion",
+            Feature::StaticReturnType => "the `static` return type",
+            Feature::ThrowExpressions => "`throw` expressions",
+            Feature::CatchWithoutVariable => "catching exceptions without a variable",
+            Feature::TrailingCommaInParameters => "trailing commas in parameter lists",
+            Feature::ClassNameOfObjects => "`::class` on objects",
+            Feature::Enums => "enums",
+            Feature::ReadonlyProperties => "readonly properties",
+            Feature::IntersectionTypes => "intersection types",
+            Feature::NewInInitializers => "`new` in initializers",
+            Feature::FirstClassCallables => "first-class callable syntax",
+            Feature::FinalClassConstants => "final class constants",
+            Feature::ExplicitOctalLiterals => "the `0o` octal prefix",
+            Feature::NeverType => "the `never` type",
+            Feature::ReadonlyClasses => "readonly classes",
+            Feature::DisjunctiveNormalFormTypes => "disjunctive normal form types",
+            Feature::LiteralTypes => "the `true` type, and standalone `null` and `false` types",
+            Feature::ConstantsInTraits => "constants in traits",
+            Feature::DynamicClassConstantFetch => "dynamic class constant fetches",
+            Feature::TypedClassConstants => "typed class constants",
+        };
+
+        write!(f, "{}", feature)
+    }
+}
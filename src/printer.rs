@@ -1,8 +1,27 @@
 use std::cmp;
 use std::collections::HashMap;
+use std::fmt::Display;
 
+use crate::downcast::downcast_mut;
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
 use crate::lexer::token::Token;
 use crate::lexer::token::TokenKind;
+use crate::node::Node;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::literals::LiteralFloat;
+use crate::parser::ast::literals::LiteralInteger;
+use crate::parser::ast::literals::LiteralString;
+use crate::parser::ast::variables::SimpleVariable;
+use crate::parser::ast::variables::Variable;
+use crate::parser::ast::Program;
+use crate::parser::error::ParseError;
+use crate::rewrite::RewriteError;
+use crate::rewrite::Rewriter;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+use crate::traverser::Visitor;
 
 /// Prints the tokens as a string
 ///
@@ -77,3 +96,413 @@ pub fn print(tokens: &[Token]) -> String {
 
     output.join("\n")
 }
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PrintError {
+    /// The source that is preserved doesn't parse.
+    InvalidSource(ParseError),
+    /// The program differs from the source in more than the values of its names,
+    /// variables and literals, e.g. a statement was added or removed. There is no text
+    /// to generate for such a change, see [`Printer`].
+    StructureChanged,
+    /// The changed values couldn't be put in the source, e.g. as two of them are at
+    /// the same position.
+    Rewrite(RewriteError),
+}
+
+impl Display for PrintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSource(error) => write!(
+                f,
+                "Print Error: the source doesn't parse, {} on line {} column {}",
+                error.message, error.span.line, error.span.column
+            ),
+            Self::StructureChanged => write!(
+                f,
+                "Print Error: the structure of the program differs from the source"
+            ),
+            Self::Rewrite(error) => write!(f, "Print Error: {}", error),
+        }
+    }
+}
+
+/// Prints a program that was parsed from `source` and then modified, copying the
+/// source byte for byte where the program didn't change.
+///
+/// The program is compared with the one parsed from the source, so there is nothing to
+/// track while modifying it: a name, variable or literal whose value differs from the
+/// one at the same position in the source is printed anew, and everything else is
+/// copied, including whitespace and comments. A value that was changed and changed
+/// back again is copied too. Strings are quoted the way they were, and a space is put
+/// between a new value and a neighbouring word that it would otherwise run into, as
+/// in `echo.5` when `.5` is changed to `0.5`.
+///
+/// Only those values are printed anew: there is no printer that generates the text of
+/// a statement or an expression, so any other change, such as a statement that was
+/// added, removed or replaced, is reported as a [`PrintError::StructureChanged`]
+/// rather than printed. The source is parsed with the default configuration.
+///
+/// ```
+/// use pxp_parser::printer::Printer;
+///
+/// let source = b"<?php $a = 'a'; // one\n";
+/// let program = pxp_parser::parse(source).unwrap();
+///
+/// assert_eq!(Printer::preserving(source).print(&program).unwrap(), source);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Printer<'a> {
+    source: &'a [u8],
+}
+
+impl<'a> Printer<'a> {
+    pub fn preserving(source: &'a [u8]) -> Self {
+        Self { source }
+    }
+
+    pub fn print(&self, program: &Program) -> Result<Vec<u8>, PrintError> {
+        let (mut original, tokens) = crate::parse_with_tokens(self.source)
+            .map_err(|stack| PrintError::InvalidSource(stack.errors[0].clone()))?;
+
+        let mut values = Values::default();
+        program.visit_spans(&mut values);
+
+        // The original with the values of the program, and the values that differ.
+        let mut leaves = Leaves {
+            values: values.0,
+            changes: vec![],
+            unknown: false,
+        };
+        for statement in original.iter_mut() {
+            leaves.visit_node(statement).unwrap();
+        }
+
+        if leaves.unknown || original != *program {
+            return Err(PrintError::StructureChanged);
+        }
+
+        let mut rewriter = Rewriter::new(self.source);
+        for (span, text) in leaves.changes {
+            let length = self.length(&tokens, span.position);
+            let mut replacement = vec![];
+
+            if span.position > 0
+                && is_word(self.source[span.position - 1])
+                && text.first().is_some_and(|byte| is_word(*byte))
+            {
+                replacement.push(b' ');
+            }
+            replacement.extend_from_slice(&text);
+            if self
+                .source
+                .get(span.position + length)
+                .is_some_and(|byte| is_word(*byte))
+                && text.last().is_some_and(|byte| is_word(*byte))
+            {
+                replacement.push(b' ');
+            }
+
+            let end = Span::new(span.line, span.column + length, span.position + length);
+
+            rewriter.replace_span(span.to(end), replacement);
+        }
+
+        rewriter.apply().map_err(PrintError::Rewrite)
+    }
+
+    /// The length of the token at `position` in the source, up to the next token.
+    fn length(&self, tokens: &[Token], position: usize) -> usize {
+        let index = tokens.partition_point(|token| token.span.position <= position);
+        let end = tokens
+            .get(index)
+            .map_or(self.source.len(), |token| token.span.position);
+
+        self.source[position..end]
+            .iter()
+            .rposition(|byte| !byte.is_ascii_whitespace())
+            .map_or(0, |last| last + 1)
+    }
+}
+
+/// The values of the names, variables and literals of a program, by position.
+#[derive(Default)]
+struct Values(HashMap<usize, ByteString>);
+
+impl<'a> SpanVisitor<'a> for Values {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        let (span, value) = if let Some(identifier) = node.downcast_ref::<SimpleIdentifier>() {
+            (identifier.span, &identifier.value)
+        } else if let Some(variable) = node.downcast_ref::<SimpleVariable>() {
+            (variable.span, &variable.name)
+        } else if let Some(string) = node.downcast_ref::<LiteralString>() {
+            (string.span, &string.value)
+        } else if let Some(integer) = node.downcast_ref::<LiteralInteger>() {
+            (integer.span, &integer.value)
+        } else if let Some(float) = node.downcast_ref::<LiteralFloat>() {
+            (float.span, &float.value)
+        } else {
+            return;
+        };
+
+        self.0.insert(span.position, value.clone());
+    }
+}
+
+/// Replaces the values of the original program with those of the modified one, and
+/// keeps those that differ as changes.
+struct Leaves {
+    values: HashMap<usize, ByteString>,
+    changes: Vec<(Span, Vec<u8>)>,
+    /// Whether the original has a value at a position where the program has none.
+    unknown: bool,
+}
+
+impl Leaves {
+    fn leaf(&mut self, span: Span, original: &mut ByteString, string: bool) {
+        let Some(value) = self.values.get(&span.position) else {
+            self.unknown = true;
+
+            return;
+        };
+
+        if value != original {
+            let text = if string {
+                quote(&original[..], &value[..])
+            } else {
+                value.to_vec()
+            };
+
+            self.changes.push((span, text));
+            *original = value.clone();
+        }
+    }
+}
+
+impl Visitor<()> for Leaves {
+    fn visit(&mut self, node: &mut dyn Node) -> Result<(), ()> {
+        if let Some(identifier) = downcast_mut::<SimpleIdentifier>(node) {
+            self.leaf(identifier.span, &mut identifier.value, false);
+        } else if let Some(variable) = downcast_mut::<SimpleVariable>(node) {
+            self.leaf(variable.span, &mut variable.name, false);
+        } else if let Some(Variable::SimpleVariable(variable)) = downcast_mut::<Variable>(node) {
+            // The variables of expressions aren't children.
+            self.leaf(variable.span, &mut variable.name, false);
+        } else if let Some(string) = downcast_mut::<LiteralString>(node) {
+            self.leaf(string.span, &mut string.value, true);
+        } else if let Some(integer) = downcast_mut::<LiteralInteger>(node) {
+            self.leaf(integer.span, &mut integer.value, false);
+        } else if let Some(float) = downcast_mut::<LiteralFloat>(node) {
+            self.leaf(float.span, &mut float.value, false);
+        }
+
+        Ok(())
+    }
+}
+
+/// The source of a string with the quotes of `original`, whose value is `value`,
+/// quotes included, as in the AST.
+fn quote(original: &[u8], value: &[u8]) -> Vec<u8> {
+    let quote = original[0];
+    let content = match value {
+        [first, content @ .., last] if first == last && matches!(first, b'\'' | b'"') => content,
+        content => content,
+    };
+
+    let mut text = vec![quote];
+    for byte in content {
+        let escaped = match quote {
+            b'\'' => matches!(byte, b'\\' | b'\''),
+            _ => matches!(byte, b'\\' | b'"' | b'$'),
+        };
+        if escaped {
+            text.push(b'\\');
+        }
+        text.push(*byte);
+    }
+    text.push(quote);
+
+    text
+}
+
+fn is_word(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte >= 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::functions::ConcreteMethod;
+    use crate::parser::ast::Expression;
+    use crate::parser::ast::Statement;
+
+    /// Renames the methods called `from`, and the variables, strings, floats and
+    /// identifiers in `values`.
+    struct Renamer<'a> {
+        from: &'a str,
+        to: &'a str,
+        values: &'a [(&'a str, &'a str)],
+    }
+
+    impl Visitor<()> for Renamer<'_> {
+        fn visit(&mut self, node: &mut dyn Node) -> Result<(), ()> {
+            if let Some(method) = downcast_mut::<ConcreteMethod>(node) {
+                if method.name.value[..] == *self.from.as_bytes() {
+                    method.name.value = self.to.into();
+                }
+            } else if let Some(Variable::SimpleVariable(variable)) = downcast_mut::<Variable>(node)
+            {
+                self.rename(&mut variable.name);
+            } else if let Some(string) = downcast_mut::<LiteralString>(node) {
+                self.rename(&mut string.value);
+            } else if let Some(float) = downcast_mut::<LiteralFloat>(node) {
+                self.rename(&mut float.value);
+            } else if let Some(identifier) = downcast_mut::<SimpleIdentifier>(node) {
+                self.rename(&mut identifier.value);
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Renamer<'_> {
+        fn rename(&self, value: &mut ByteString) {
+            for (from, to) in self.values {
+                if value[..] == *from.as_bytes() {
+                    *value = (*to).into();
+                }
+            }
+        }
+    }
+
+    fn rename(source: &str, from: &str, to: &str, values: &[(&str, &str)]) -> String {
+        let mut program = crate::parse(source).unwrap();
+        let mut renamer = Renamer { from, to, values };
+        for statement in program.iter_mut() {
+            renamer.visit_node(statement).unwrap();
+        }
+
+        let output = Printer::preserving(source.as_bytes())
+            .print(&program)
+            .unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    /// A long source, with a method called `handle3` in the fourth class.
+    fn source() -> String {
+        let mut source = "<?php\n\ndeclare(strict_types=1);\n\nnamespace App;\n".to_string();
+
+        for class in 0..50 {
+            source.push_str(&format!(
+                "
+/**
+ * Class number {class}.
+ */
+final class Handler{class} extends Base   implements \\Countable
+{{
+    private array $items = [ 'a' => 1,   'b' => 2.50 ];
+
+    public function handle{class}(int $value,  string $name = \"x\\$\"): ?string
+    {{
+        // Keep   the spacing.
+        $total = $value * 0x1F + count( $this->items );
+        if ($total > 10) {{
+            return sprintf('%s: %d', $name, $total); # trailing
+        }}
+
+        return <<<EOT
+        Total: {{$total}}
+        EOT;
+    }}
+}}
+"
+            ));
+        }
+
+        source
+    }
+
+    #[test]
+    fn test_unchanged_program() {
+        let source = source();
+        assert!(source.lines().count() > 1000);
+
+        assert_eq!(rename(&source, "", "", &[]), source);
+    }
+
+    #[test]
+    fn test_renamed_method() {
+        let source = source();
+        let output = rename(&source, "handle3", "process", &[]);
+
+        let changed = source
+            .lines()
+            .zip(output.lines())
+            .filter(|(before, after)| before != after)
+            .collect::<Vec<_>>();
+
+        assert_eq!(source.lines().count(), output.lines().count());
+        assert_eq!(
+            changed,
+            vec![(
+                "    public function handle3(int $value,  string $name = \"x\\$\"): ?string",
+                "    public function process(int $value,  string $name = \"x\\$\"): ?string",
+            )]
+        );
+    }
+
+    #[test]
+    fn test_changed_and_restored() {
+        let source = "<?php echo 'it\\'s', $a, \"$\";";
+
+        assert_eq!(
+            rename(source, "", "", &[("'it's'", "'it's'"), ("$a", "$b")]),
+            "<?php echo 'it\\'s', $b, \"$\";"
+        );
+        assert_eq!(
+            rename(source, "", "", &[("'it's'", "'a\\b'"), ("\"$\"", "\"a$\"")]),
+            "<?php echo 'a\\\\b', $a, \"a\\$\";"
+        );
+    }
+
+    #[test]
+    fn test_separators() {
+        assert_eq!(
+            rename("<?php echo.5;", "", "", &[(".5", "1.5")]),
+            "<?php echo 1.5;"
+        );
+        assert_eq!(
+            rename("<?php echo.5.'a';", "", "", &[(".5", "1.0")]),
+            "<?php echo 1.0.'a';"
+        );
+    }
+
+    #[test]
+    fn test_structure_changed() {
+        let source = "<?php echo 1;";
+        let mut program = crate::parse(source).unwrap();
+        program.push(program[1].clone());
+
+        assert_eq!(
+            Printer::preserving(source.as_bytes()).print(&program),
+            Err(PrintError::StructureChanged)
+        );
+
+        let Statement::Echo(echo) = &mut program[1] else {
+            panic!("expected an echo");
+        };
+        echo.values[0] = Expression::Null(Span::dummy());
+
+        assert_eq!(
+            Printer::preserving(source.as_bytes()).print(&program),
+            Err(PrintError::StructureChanged)
+        );
+    }
+}
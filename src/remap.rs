@@ -0,0 +1,198 @@
+//! Moves the spans of an AST or of diagnostics, for tools that parse code other than
+//! the one they were given, see [`RemapSpans`] and [`OffsetMap`].
+//!
+//! A tool that adds a `<?php` before a snippet, wraps it in a namespace or strips the
+//! header of a template parses a source of its own, and remaps the spans of what it
+//! parsed to point into the code of the user.
+
+use crate::lexer::token::Span;
+use crate::parser::ast::Program;
+use crate::parser::error::ParseError;
+use crate::parser::error::ParseErrorStack;
+use crate::parser::source_map::SourceMap;
+use crate::spans::VisitSpans;
+
+pub trait RemapSpans {
+    /// Replace every span with what `mapper` gives for it.
+    fn remap_spans(&mut self, mapper: &impl Fn(Span) -> Span);
+}
+
+impl RemapSpans for Program {
+    fn remap_spans(&mut self, mapper: &impl Fn(Span) -> Span) {
+        self.visit_spans_mut(&mut |span| *span = mapper(*span));
+    }
+}
+
+impl RemapSpans for ParseError {
+    /// The annotations only have a position and a length, so their start and end are
+    /// each mapped as the position of a span.
+    fn remap_spans(&mut self, mapper: &impl Fn(Span) -> Span) {
+        self.visit_spans_mut(&mut |span| *span = mapper(*span));
+    }
+}
+
+impl RemapSpans for ParseErrorStack {
+    fn remap_spans(&mut self, mapper: &impl Fn(Span) -> Span) {
+        self.partial.remap_spans(mapper);
+
+        for error in &mut self.errors {
+            error.remap_spans(mapper);
+        }
+    }
+}
+
+/// The positions of a parsed source in the source it was made from, by adding or
+/// stripping code.
+///
+/// Each edit is a span of the parsed source and the number of bytes to move the
+/// positions at or after it by. A negative number is code that was added there, such
+/// as a prefix, whose spans have nothing to point to in the original and map to
+/// [`Span::dummy`]. A positive number is code of the original that was stripped
+/// there, such as the header of a template.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct OffsetMap {
+    /// The position of each edit and the number of bytes, in source order.
+    edits: Vec<(usize, isize)>,
+}
+
+impl OffsetMap {
+    pub fn from_edits(edits: &[(Span, isize)]) -> Self {
+        let mut edits: Vec<(usize, isize)> = edits
+            .iter()
+            .map(|(span, delta)| (span.position, *delta))
+            .collect();
+        edits.sort_by_key(|(position, _)| *position);
+
+        Self { edits }
+    }
+
+    /// The position in the original of the byte at `offset` of the parsed source, or
+    /// nothing for a byte of added code.
+    pub fn map_offset(&self, offset: usize) -> Option<usize> {
+        let mut delta = 0;
+
+        for (position, bytes) in &self.edits {
+            if *position > offset {
+                break;
+            }
+
+            if *bytes < 0 && offset < position + bytes.unsigned_abs() {
+                return None;
+            }
+
+            delta += bytes;
+        }
+
+        offset.checked_add_signed(delta)
+    }
+
+    /// The span in the original of a span of the parsed source, with its line and column
+    /// found in the [`SourceMap`] of the original, or a [`Span::dummy`] for a span of
+    /// added code.
+    ///
+    /// A source map starts a new line after a `\r` that isn't followed by a `\n`, which
+    /// the lexer doesn't, so a span after one of those has a different line than if the
+    /// original had been parsed.
+    pub fn map(&self, span: Span, original: &SourceMap) -> Span {
+        if span.is_dummy() {
+            return span;
+        }
+
+        let Some(position) = self.map_offset(span.position) else {
+            return Span::dummy();
+        };
+        let position = position.min(original.source().len());
+        let (line, column) = original.line_col(position);
+
+        Span::new(line, column, position).with_length(span.length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::identifiers::SimpleIdentifier;
+    use crate::spans::Segment;
+    use crate::spans::SpanVisitor;
+
+    struct Identifiers<'a>(Vec<&'a SimpleIdentifier>);
+
+    impl<'a> SpanVisitor<'a> for Identifiers<'a> {
+        fn span(&mut self, _: &'a Span) {}
+
+        fn enter(&mut self, segment: Segment<'a>) {
+            if let Segment::Node(node) = segment {
+                if let Some(identifier) = node.downcast_ref::<SimpleIdentifier>() {
+                    self.0.push(identifier);
+                }
+            }
+        }
+    }
+
+    fn identifiers(program: &Program) -> Vec<&SimpleIdentifier> {
+        let mut identifiers = Identifiers(vec![]);
+        program.visit_spans(&mut identifiers);
+
+        identifiers.0
+    }
+
+    #[test]
+    fn test_remap_prefix() {
+        let snippet = "class Foo {\n    const BAR = 1;\n\n    public function baz() {}\n}\n";
+        let prefix = "<?php\n// user\n";
+        assert_eq!(prefix.len(), 14);
+
+        let mut program = crate::parse(&format!("{}{}", prefix, snippet)).unwrap();
+        let offsets = OffsetMap::from_edits(&[(Span::new(1, 1, 0), -14)]);
+        let source = SourceMap::new(snippet.as_bytes());
+        program.remap_spans(&|span| offsets.map(span, &source));
+
+        let identifiers = identifiers(&program);
+        assert_eq!(identifiers.len(), 3);
+        for identifier in identifiers {
+            let position = identifier.span.position;
+
+            assert_eq!(
+                &snippet.as_bytes()[position..position + identifier.value.len()],
+                &identifier.value[..]
+            );
+            assert_eq!(
+                (identifier.span.line, identifier.span.column),
+                source.line_col(position)
+            );
+        }
+    }
+
+    #[test]
+    fn test_offset_map() {
+        // `<?php ` was added before the code, and a header of 10 bytes stripped after
+        // the first 4 bytes of it.
+        let offsets = OffsetMap::from_edits(&[(Span::new(1, 11, 10), 10), (Span::dummy(), -6)]);
+
+        assert_eq!(offsets.map_offset(0), None);
+        assert_eq!(offsets.map_offset(5), None);
+        assert_eq!(offsets.map_offset(6), Some(0));
+        assert_eq!(offsets.map_offset(9), Some(3));
+        assert_eq!(offsets.map_offset(10), Some(14));
+
+        let original = SourceMap::new(b"ab\ncHEADER....\nd");
+        assert_eq!(
+            offsets.map(Span::new(1, 12, 11), &original),
+            Span::new(3, 1, 15)
+        );
+        assert_eq!(offsets.map(Span::new(1, 3, 2), &original), Span::dummy());
+    }
+
+    #[test]
+    fn test_remap_diagnostics() {
+        let mut stack = crate::parse("<?php\n$a = ;").unwrap_err();
+        let offsets = OffsetMap::from_edits(&[(Span::new(1, 1, 0), -6)]);
+        let source = SourceMap::new(b"$a = ;");
+        stack.remap_spans(&|span| offsets.map(span, &source));
+
+        let error = &stack.errors[0];
+        assert_eq!(error.span, Span::new(1, 6, 5).with_length(1));
+        assert_eq!(error.annotations[0].position, 5);
+        assert_eq!(error.annotations[0].length, 1);
+    }
+}
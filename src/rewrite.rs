@@ -0,0 +1,248 @@
+use std::fmt::Display;
+
+use crate::lexer::token::Span;
+use crate::lexer::token::SpanRange;
+
+pub type RewriteResult<T> = Result<T, RewriteError>;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RewriteError {
+    OverlappingEdits(Span, Span),
+    OutOfBounds(Span),
+}
+
+impl RewriteError {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::OverlappingEdits(_, span) => *span,
+            Self::OutOfBounds(span) => *span,
+        }
+    }
+}
+
+impl Display for RewriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OverlappingEdits(first, second) => write!(
+                f,
+                "Rewrite Error: edit on line {} column {} overlaps edit on line {} column {}",
+                second.line, second.column, first.line, first.column
+            ),
+            Self::OutOfBounds(span) => write!(
+                f,
+                "Rewrite Error: edit on line {} column {} is out of bounds",
+                span.line, span.column
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Edit {
+    range: SpanRange,
+    text: Vec<u8>,
+}
+
+impl Edit {
+    fn start(&self) -> usize {
+        self.range.start.position
+    }
+
+    fn end(&self) -> usize {
+        self.range.end.position
+    }
+}
+
+/// Splices replacement text into the original source at the given ranges, e.g. the
+/// [`range`] of a node, leaving everything else byte-for-byte untouched.
+///
+/// ```
+/// use pxp_parser::lexer::token::Span;
+/// use pxp_parser::rewrite::Rewriter;
+///
+/// let mut rewriter = Rewriter::new(b"<?php echo foo();");
+/// rewriter.replace_span(Span::new(1, 12, 11).to(Span::new(1, 15, 14)), "bar");
+///
+/// assert_eq!(rewriter.apply().unwrap(), b"<?php echo bar();");
+/// ```
+///
+/// [`range`]: crate::spans::range
+#[derive(Debug, Clone)]
+pub struct Rewriter<'a> {
+    source: &'a [u8],
+    edits: Vec<Edit>,
+}
+
+impl<'a> Rewriter<'a> {
+    pub fn new(source: &'a [u8]) -> Self {
+        Self {
+            source,
+            edits: vec![],
+        }
+    }
+
+    pub fn replace_span<T: AsRef<[u8]>>(&mut self, range: SpanRange, text: T) {
+        self.edits.push(Edit {
+            range,
+            text: text.as_ref().to_vec(),
+        });
+    }
+
+    pub fn insert_before<T: AsRef<[u8]>>(&mut self, range: SpanRange, text: T) {
+        self.replace_span(range.start.to(range.start), text);
+    }
+
+    pub fn insert_after<T: AsRef<[u8]>>(&mut self, range: SpanRange, text: T) {
+        self.replace_span(range.end.to(range.end), text);
+    }
+
+    pub fn delete(&mut self, range: SpanRange) {
+        self.replace_span(range, "");
+    }
+
+    pub fn apply(mut self) -> RewriteResult<Vec<u8>> {
+        // Insertions sort ahead of a replacement starting at the same position,
+        // and the sort is stable so insertions keep the order they were queued in.
+        self.edits
+            .sort_by_key(|edit| (edit.start(), !edit.range.is_empty()));
+
+        for pair in self.edits.windows(2) {
+            if pair[1].start() < pair[0].end() {
+                return Err(RewriteError::OverlappingEdits(
+                    pair[0].range.start,
+                    pair[1].range.start,
+                ));
+            }
+        }
+
+        if let Some(edit) = self
+            .edits
+            .iter()
+            .find(|edit| edit.end() > self.source.len())
+        {
+            return Err(RewriteError::OutOfBounds(edit.range.start));
+        }
+
+        let mut output = self.source.to_vec();
+
+        for edit in self.edits.iter().rev() {
+            output.splice(edit.start()..edit.end(), edit.text.iter().copied());
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::downcast::downcast;
+    use crate::node::Node;
+    use crate::parser::ast::functions::FunctionStatement;
+    use crate::parser::ast::identifiers::Identifier;
+    use crate::parser::ast::{Expression, FunctionCallExpression};
+    use crate::traverser::Visitor;
+
+    struct FunctionRenamer<'a, 'b> {
+        from: &'b [u8],
+        to: &'b str,
+        rewriter: &'b mut Rewriter<'a>,
+    }
+
+    impl<'a, 'b> Visitor<()> for FunctionRenamer<'a, 'b> {
+        fn visit(&mut self, node: &mut dyn Node) -> Result<(), ()> {
+            let name = if let Some(function) = downcast::<FunctionStatement>(node) {
+                &function.name
+            } else if let Some(call) = downcast::<FunctionCallExpression>(node) {
+                match call.target.as_ref() {
+                    Expression::Identifier(Identifier::SimpleIdentifier(name)) => name,
+                    _ => return Ok(()),
+                }
+            } else {
+                return Ok(());
+            };
+
+            if name.value[..].eq_ignore_ascii_case(self.from) {
+                self.rewriter
+                    .replace_span(crate::spans::range(name).unwrap(), self.to);
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rename_function_and_call_sites() {
+        let source = b"<?php
+
+// foo() is called twice below.
+function   foo( $a ) { return $a   *2; }
+
+/* keep this */ echo foo(1),    FOO(2);
+echo food(3);
+";
+
+        let mut program = crate::parse(source).unwrap();
+        let mut rewriter = Rewriter::new(source);
+
+        FunctionRenamer {
+            from: b"foo",
+            to: "bar",
+            rewriter: &mut rewriter,
+        }
+        .visit_node(&mut program)
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(rewriter.apply().unwrap()).unwrap(),
+            "<?php
+
+// foo() is called twice below.
+function   bar( $a ) { return $a   *2; }
+
+/* keep this */ echo bar(1),    bar(2);
+echo food(3);
+"
+        );
+    }
+
+    #[test]
+    fn test_insert_and_delete() {
+        let source = b"<?php echo 1;";
+        let range = Span::new(1, 12, 11).to(Span::new(1, 13, 12));
+
+        let mut rewriter = Rewriter::new(source);
+        rewriter.insert_after(range, " + 2");
+        rewriter.insert_before(range, "(");
+        rewriter.insert_before(range, "0 + ");
+        rewriter.delete(Span::new(1, 13, 12).to(Span::new(1, 14, 13)));
+
+        assert_eq!(rewriter.apply().unwrap(), b"<?php echo (0 + 1 + 2");
+    }
+
+    #[test]
+    fn test_overlapping_edits() {
+        let source = b"<?php echo 123;";
+
+        let mut rewriter = Rewriter::new(source);
+        rewriter.replace_span(Span::new(1, 12, 11).to(Span::new(1, 15, 14)), "4");
+        let span = Span::new(1, 13, 12);
+        rewriter.insert_before(span.to(span), "5");
+
+        assert_eq!(
+            rewriter.apply(),
+            Err(RewriteError::OverlappingEdits(
+                Span::new(1, 12, 11),
+                Span::new(1, 13, 12)
+            ))
+        );
+
+        let mut rewriter = Rewriter::new(source);
+        rewriter.delete(Span::new(1, 15, 14).to(Span::new(1, 17, 16)));
+
+        assert_eq!(
+            rewriter.apply(),
+            Err(RewriteError::OutOfBounds(Span::new(1, 15, 14)))
+        );
+    }
+}
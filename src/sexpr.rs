@@ -0,0 +1,705 @@
+//! Compact s-expression dumps of the AST.
+//!
+//! The output is deterministic and indentation-formatted, which makes it a lot
+//! easier to review in diffs than the `Debug` or JSON representations.
+//!
+//! Node names are converted to `snake_case`, struct fields are written as
+//! `:field value` pairs and lists are wrapped in square brackets. Enum variants are
+//! folded into the node they wrap whenever the node's name already implies them.
+//! Empty lists, empty nodes and missing optional values are omitted from fields,
+//! as are spans unless they are requested.
+
+use std::fmt::Display;
+
+use serde::ser;
+use serde::Serialize;
+
+use crate::parser::ast::Expression;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+
+/// The maximum width of a line before an expression is split over multiple lines.
+const WIDTH: usize = 80;
+
+pub trait ToSExpr {
+    /// Dump the node as an s-expression, omitting spans.
+    fn to_sexpr(&self) -> String;
+
+    /// Dump the node as an s-expression, including spans as `line:column` atoms.
+    fn to_sexpr_with_spans(&self) -> String;
+}
+
+impl ToSExpr for Program {
+    fn to_sexpr(&self) -> String {
+        print(self, false)
+    }
+
+    fn to_sexpr_with_spans(&self) -> String {
+        print(self, true)
+    }
+}
+
+impl ToSExpr for Statement {
+    fn to_sexpr(&self) -> String {
+        print(self, false)
+    }
+
+    fn to_sexpr_with_spans(&self) -> String {
+        print(self, true)
+    }
+}
+
+impl ToSExpr for Expression {
+    fn to_sexpr(&self) -> String {
+        print(self, false)
+    }
+
+    fn to_sexpr_with_spans(&self) -> String {
+        print(self, true)
+    }
+}
+
+fn print<T: Serialize + ?Sized>(value: &T, spans: bool) -> String {
+    let mut output = String::new();
+
+    // The AST doesn't contain any values that can fail to serialize.
+    if let Ok(Some(expr)) = value.serialize(Serializer { spans }) {
+        expr.write(0, 0, &mut output);
+    }
+
+    output
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum SExpr {
+    Atom(String),
+    /// A node without any fields worth printing.
+    Empty(String),
+    List(String, Vec<SExpr>),
+    Seq(Vec<SExpr>),
+    /// The fields of a node that has been flattened into a map by `serde`.
+    Map(Vec<SExpr>),
+    Field(String, Box<SExpr>),
+}
+
+impl SExpr {
+    fn flat(&self) -> String {
+        match self {
+            SExpr::Atom(atom) | SExpr::Empty(atom) => atom.clone(),
+            SExpr::List(head, items) => {
+                let mut flat = format!("({}", head);
+                for item in items {
+                    flat.push(' ');
+                    flat.push_str(&item.flat());
+                }
+                flat.push(')');
+                flat
+            }
+            SExpr::Seq(items) => {
+                let items: Vec<String> = items.iter().map(|item| item.flat()).collect();
+
+                format!("[{}]", items.join(" "))
+            }
+            SExpr::Map(fields) => {
+                let fields: Vec<String> = fields.iter().map(|field| field.flat()).collect();
+
+                format!("{{{}}}", fields.join(" "))
+            }
+            SExpr::Field(name, value) => format!(":{} {}", name, value.flat()),
+        }
+    }
+
+    /// Write the expression starting at the given column, indenting any
+    /// continuation lines relative to the given indentation.
+    fn write(&self, column: usize, indent: usize, output: &mut String) {
+        let flat = self.flat();
+        if column + flat.len() <= WIDTH {
+            output.push_str(&flat);
+            return;
+        }
+
+        match self {
+            SExpr::Atom(atom) | SExpr::Empty(atom) => output.push_str(atom),
+            SExpr::List(head, items) => {
+                output.push('(');
+                output.push_str(head);
+                write_items(items, indent + 2, output);
+                output.push(')');
+            }
+            SExpr::Seq(items) => {
+                output.push('[');
+                write_items(items, indent + 2, output);
+                output.push(']');
+            }
+            SExpr::Map(fields) => {
+                output.push('{');
+                write_items(fields, indent + 2, output);
+                output.push('}');
+            }
+            SExpr::Field(name, value) => {
+                output.push(':');
+                output.push_str(name);
+                output.push(' ');
+                value.write(column + name.len() + 2, indent, output);
+            }
+        }
+    }
+
+    /// Whether the expression can be left out of a field without losing any information.
+    fn is_omittable(&self) -> bool {
+        match self {
+            SExpr::Empty(_) => true,
+            SExpr::Seq(items) | SExpr::Map(items) => items.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+/// Wrap the serialized contents of an enum variant.
+fn wrap_variant(variant: &str, inner: Option<SExpr>) -> SExpr {
+    let variant = snake_case(variant);
+    // Most variants wrap a node with a more specific name, e.g. `Statement::Echo(EchoStatement)`,
+    // in which case the variant name is redundant.
+    let implied = |head: &str| format!("_{}_", head).contains(&format!("_{}_", variant));
+
+    match inner {
+        None => SExpr::Atom(variant),
+        Some(SExpr::Empty(head)) if implied(&head) => SExpr::Atom(head),
+        Some(SExpr::Empty(_)) => SExpr::Atom(variant),
+        Some(SExpr::List(head, items)) if implied(&head) => SExpr::List(head, items),
+        Some(SExpr::Map(fields)) if fields.is_empty() => SExpr::Atom(variant),
+        Some(SExpr::Map(fields)) => SExpr::List(variant, fields),
+        Some(inner) => SExpr::List(variant, vec![inner]),
+    }
+}
+
+/// Create a node from its fields.
+fn node(head: String, mut fields: Vec<(String, SExpr)>) -> SExpr {
+    match fields.len() {
+        0 => SExpr::Empty(head),
+        // A single field doesn't need a name to be understood.
+        1 => SExpr::List(head, vec![fields.remove(0).1]),
+        _ => SExpr::List(
+            head,
+            fields
+                .into_iter()
+                .map(|(key, value)| SExpr::Field(key, Box::new(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn write_items(items: &[SExpr], indent: usize, output: &mut String) {
+    for item in items {
+        output.push('\n');
+        output.push_str(&" ".repeat(indent));
+        item.write(indent, indent, output);
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut output = String::with_capacity(name.len() + 4);
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next = chars.get(i + 1);
+
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next.is_some_and(|n| n.is_ascii_lowercase()))
+            {
+                output.push('_');
+            }
+        }
+
+        output.push(c.to_ascii_lowercase());
+    }
+
+    output
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\\\""))
+}
+
+#[derive(Debug)]
+struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Copy)]
+struct Serializer {
+    spans: bool,
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Option<SExpr>;
+    type Error = Error;
+
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = StructSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Atom(v.to_string())))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Atom(v.to_string())))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Atom(v.to_string())))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Atom(v.to_string())))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Atom(quote(v))))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
+        self.serialize_str(&v.escape_ascii().to_string())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok> {
+        // Optional spans mark the presence of a token, e.g. `&` or `...`, so we still
+        // need to print something when spans are omitted.
+        Ok(Some(
+            value
+                .serialize(self)?
+                .unwrap_or_else(|| SExpr::Atom("true".to_string())),
+        ))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Atom("nil".to_string())))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Atom(snake_case(name))))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Atom(snake_case(variant))))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        Ok(Some(wrap_variant(variant, value.serialize(self)?)))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(SeqSerializer::new(self, None))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Ok(SeqSerializer::new(self, None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Ok(SeqSerializer::new(self, Some(snake_case(name))))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Ok(SeqSerializer::new(self, Some(snake_case(variant))))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(MapSerializer {
+            serializer: self,
+            fields: Vec::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        Ok(StructSerializer::new(self, name, len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(StructSerializer::new(self, variant, len))
+    }
+}
+
+struct SeqSerializer {
+    serializer: Serializer,
+    head: Option<String>,
+    items: Vec<SExpr>,
+}
+
+impl SeqSerializer {
+    fn new(serializer: Serializer, head: Option<String>) -> Self {
+        Self {
+            serializer,
+            head,
+            items: Vec::new(),
+        }
+    }
+
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        if let Some(item) = value.serialize(self.serializer)? {
+            self.items.push(item);
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<SExpr>> {
+        Ok(Some(match self.head {
+            Some(head) if self.items.is_empty() => SExpr::Atom(head),
+            Some(head) => SExpr::List(head, self.items),
+            None => SExpr::Seq(self.items),
+        }))
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Option<SExpr>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Option<SExpr>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Option<SExpr>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = Option<SExpr>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+/// Nodes containing a `#[serde(flatten)]` field are serialized as maps, which
+/// means their name isn't available and only their fields can be printed.
+struct MapSerializer {
+    serializer: Serializer,
+    fields: Vec<SExpr>,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Option<SExpr>;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        self.key = key.serialize(self.serializer)?.map(|key| {
+            let key = key.flat();
+
+            key.trim_matches('"').to_string()
+        });
+
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take().unwrap_or_default();
+
+        match value.serialize(self.serializer)? {
+            Some(value) if !value.is_omittable() => {
+                self.fields.push(SExpr::Field(key, Box::new(value)))
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(Some(SExpr::Map(self.fields)))
+    }
+}
+
+struct StructSerializer {
+    serializer: Serializer,
+    name: &'static str,
+    len: usize,
+    fields: Vec<(String, SExpr)>,
+}
+
+impl StructSerializer {
+    fn new(serializer: Serializer, name: &'static str, len: usize) -> Self {
+        Self {
+            serializer,
+            name,
+            len,
+            fields: Vec::new(),
+        }
+    }
+
+    fn push<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        match value.serialize(self.serializer)? {
+            Some(value) if !value.is_omittable() => self.fields.push((key.to_string(), value)),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn field(&self, name: &str) -> Option<&SExpr> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    fn finish(mut self) -> Result<Option<SExpr>> {
+        if self.name == "Span" {
+            if !self.serializer.spans {
+                return Ok(None);
+            }
+
+            let field = |name| {
+                self.field(name)
+                    .map(|value| value.flat())
+                    .unwrap_or_default()
+            };
+
+            return Ok(Some(SExpr::Atom(format!(
+                "{}:{}",
+                field("line"),
+                field("column")
+            ))));
+        }
+
+        let tag = match self.field("type") {
+            Some(SExpr::Atom(tag)) => Some(tag.clone()),
+            _ => None,
+        };
+
+        let tag = match tag {
+            // Adjacently tagged enums are serialized as a `type` and `value` pair,
+            // or just a `type` for unit variants.
+            Some(tag)
+                if self.len <= 2
+                    && !tag.starts_with('"')
+                    && self
+                        .fields
+                        .iter()
+                        .all(|(key, _)| key == "type" || key == "value") =>
+            {
+                let value = self
+                    .fields
+                    .into_iter()
+                    .find(|(key, _)| key == "value")
+                    .map(|(_, value)| value);
+
+                return Ok(Some(wrap_variant(&tag, value)));
+            }
+            Some(tag) if tag.starts_with('"') => tag.trim_matches('"').to_string(),
+            _ => return Ok(Some(node(snake_case(self.name), self.fields))),
+        };
+
+        // Internally tagged structs and enums have their name in a `type` field.
+        self.fields.retain(|(key, _)| key != "type");
+
+        let inner = node(snake_case(self.name), self.fields);
+        if tag == self.name {
+            Ok(Some(inner))
+        } else {
+            Ok(Some(wrap_variant(&tag, Some(inner))))
+        }
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = Option<SExpr>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for StructSerializer {
+    type Ok = Option<SExpr>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("ClassStatement"), "class_statement");
+        assert_eq!(snake_case("DNFType"), "dnf_type");
+        assert_eq!(snake_case("Int"), "int");
+        assert_eq!(snake_case("U8Value"), "u8_value");
+    }
+
+    #[test]
+    fn test_dump_is_deterministic() {
+        let code = r#"<?php
+
+        #[Attr(1)]
+        final class Foo extends Bar implements Baz {
+            public function __construct(private readonly int $x = 1) {}
+
+            public function bar(string ...$args): ?string {
+                return match ($this->x) {
+                    1, 2 => fn ($y) => $y . <<<EOT
+                        {$args[0]} and $this->x
+                        EOT,
+                    default => null,
+                };
+            }
+        }
+        "#;
+
+        let first = crate::parse(code).unwrap();
+        let second = crate::parse(code).unwrap();
+
+        assert_eq!(first.to_sexpr(), second.to_sexpr());
+        assert_eq!(first.to_sexpr_with_spans(), second.to_sexpr_with_spans());
+        assert_ne!(first.to_sexpr(), first.to_sexpr_with_spans());
+    }
+}
@@ -0,0 +1,648 @@
+//! Walks the spans of an AST, see [`VisitSpans`], and checks that they are consistent
+//! with the source, see [`SpanInvariants`].
+//!
+//! Each span is the start and length of a token, so a node covers the range from the
+//! start of the first to the end of the last of the spans within it. The fields of a
+//! node aren't always declared in source order, e.g. the commas of a list are kept
+//! apart from its items, but the elements of a list are: each element has to end
+//! before the next one starts. A node contains its children too: none of the tokens of
+//! the node itself lies inside one of them.
+
+use std::any::Any;
+use std::fmt::Display;
+
+use crate::lexer::byte_string::ByteString;
+use crate::lexer::token::Span;
+use crate::lexer::token::SpanRange;
+use crate::lexer::token::Token;
+use crate::parser::ast::Program;
+use crate::parser::error::ParseError;
+
+/// A value of the AST whose spans can be visited, in the order its fields are
+/// declared in.
+pub trait VisitSpans {
+    /// Visit every span, along with the nodes, fields and list elements around it.
+    fn visit_spans<'a>(&'a self, _visitor: &mut dyn SpanVisitor<'a>) {}
+
+    /// Visit every span, to change it.
+    fn visit_spans_mut(&mut self, _f: &mut dyn FnMut(&mut Span)) {}
+}
+
+/// What a [`VisitSpans`] traversal calls. Every [`SpanVisitor::enter`] is followed by
+/// a [`SpanVisitor::leave`] once what is in the segment has been visited.
+pub trait SpanVisitor<'a> {
+    fn span(&mut self, span: &'a Span);
+
+    fn enter(&mut self, _segment: Segment<'a>) {}
+
+    fn leave(&mut self) {}
+}
+
+impl<'a, F: FnMut(&'a Span)> SpanVisitor<'a> for F {
+    fn span(&mut self, span: &'a Span) {
+        self(span)
+    }
+}
+
+/// Where a [`SpanVisitor`] is in the AST.
+#[derive(Clone, Copy)]
+pub enum Segment<'a> {
+    /// A struct or enum of the AST, which can be downcast to its type.
+    Node(&'a dyn Any),
+    /// The variant of the enum that was entered last.
+    Variant(&'static str),
+    Field(&'static str),
+    /// An element of a list.
+    Element(usize),
+}
+
+impl Segment<'_> {
+    fn write(&self, path: &mut String) {
+        match self {
+            Segment::Node(_) => {}
+            Segment::Variant(name) | Segment::Field(name) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+
+                path.push_str(name.trim_start_matches("r#"));
+            }
+            Segment::Element(index) => path.push_str(&format!("[{}]", index)),
+        }
+    }
+}
+
+/// Implement [`VisitSpans`] for structs and enums of the AST by listing their fields
+/// and variants. The implementations destructure the values, so that adding a field
+/// or a variant can't leave it out of the traversal.
+///
+/// A struct can end its fields with `..` to skip the rest, for fields that aren't
+/// part of the source, like a cache. Tuple variants name each of their values.
+///
+/// ```ignore
+/// visit_spans! {
+///     struct ArgumentList { comments, left_parenthesis, arguments, right_parenthesis }
+///     enum Argument { Positional(argument), Named(argument) }
+/// }
+/// ```
+macro_rules! visit_spans {
+    () => {};
+    (struct $name:ident { $($field:ident,)* .. } $($rest:tt)*) => {
+        $crate::spans::visit_spans!(@struct $name [$($field)*] [..]);
+        $crate::spans::visit_spans!($($rest)*);
+    };
+    (struct $name:ident { $($field:ident),* $(,)? } $($rest:tt)*) => {
+        $crate::spans::visit_spans!(@struct $name [$($field)*] []);
+        $crate::spans::visit_spans!($($rest)*);
+    };
+    (enum $name:ident {
+        $($variant:ident $(($($value:ident),*))? $({ $($field:ident),* $(,)? })?),* $(,)?
+    } $($rest:tt)*) => {
+        impl $crate::spans::VisitSpans for $name {
+            fn visit_spans<'a>(&'a self, visitor: &mut dyn $crate::spans::SpanVisitor<'a>) {
+                visitor.enter($crate::spans::Segment::Node(self));
+                match self {
+                    $($name::$variant $(($($value),*))? $({ $($field),* })? => {
+                        visitor.enter($crate::spans::Segment::Variant(stringify!($variant)));
+                        $($($crate::spans::VisitSpans::visit_spans($value, visitor);)*)?
+                        $($(
+                            visitor.enter($crate::spans::Segment::Field(stringify!($field)));
+                            $crate::spans::VisitSpans::visit_spans($field, visitor);
+                            visitor.leave();
+                        )*)?
+                        visitor.leave();
+                    })*
+                }
+                visitor.leave();
+            }
+
+            #[allow(unused_variables)]
+            fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut $crate::lexer::token::Span)) {
+                match self {
+                    $($name::$variant $(($($value),*))? $({ $($field),* })? => {
+                        $($($crate::spans::VisitSpans::visit_spans_mut($value, f);)*)?
+                        $($($crate::spans::VisitSpans::visit_spans_mut($field, f);)*)?
+                    })*
+                }
+            }
+        }
+
+        $crate::spans::visit_spans!($($rest)*);
+    };
+    (@struct $name:ident [$($field:ident)*] [$($rest:tt)*]) => {
+        impl $crate::spans::VisitSpans for $name {
+            fn visit_spans<'a>(&'a self, visitor: &mut dyn $crate::spans::SpanVisitor<'a>) {
+                let $name { $($field,)* $($rest)* } = self;
+
+                visitor.enter($crate::spans::Segment::Node(self));
+                $(
+                    visitor.enter($crate::spans::Segment::Field(stringify!($field)));
+                    $crate::spans::VisitSpans::visit_spans($field, visitor);
+                    visitor.leave();
+                )*
+                visitor.leave();
+            }
+
+            #[allow(unused_variables)]
+            fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut $crate::lexer::token::Span)) {
+                let $name { $($field,)* $($rest)* } = self;
+
+                $($crate::spans::VisitSpans::visit_spans_mut($field, f);)*
+            }
+        }
+    };
+}
+
+pub(crate) use visit_spans;
+
+impl VisitSpans for Span {
+    fn visit_spans<'a>(&'a self, visitor: &mut dyn SpanVisitor<'a>) {
+        visitor.span(self);
+    }
+
+    fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut Span)) {
+        f(self);
+    }
+}
+
+impl VisitSpans for bool {}
+
+impl VisitSpans for usize {}
+
+impl VisitSpans for u64 {}
+
+impl VisitSpans for ByteString {}
+
+impl<T: VisitSpans> VisitSpans for Vec<T> {
+    fn visit_spans<'a>(&'a self, visitor: &mut dyn SpanVisitor<'a>) {
+        for (index, element) in self.iter().enumerate() {
+            visitor.enter(Segment::Element(index));
+            element.visit_spans(visitor);
+            visitor.leave();
+        }
+    }
+
+    fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut Span)) {
+        for element in self {
+            element.visit_spans_mut(f);
+        }
+    }
+}
+
+impl<T: VisitSpans> VisitSpans for Option<T> {
+    fn visit_spans<'a>(&'a self, visitor: &mut dyn SpanVisitor<'a>) {
+        if let Some(value) = self {
+            value.visit_spans(visitor);
+        }
+    }
+
+    fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut Span)) {
+        if let Some(value) = self {
+            value.visit_spans_mut(f);
+        }
+    }
+}
+
+impl<T: VisitSpans + ?Sized> VisitSpans for Box<T> {
+    fn visit_spans<'a>(&'a self, visitor: &mut dyn SpanVisitor<'a>) {
+        (**self).visit_spans(visitor);
+    }
+
+    fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut Span)) {
+        (**self).visit_spans_mut(f);
+    }
+}
+
+impl<A: VisitSpans, B: VisitSpans> VisitSpans for (A, B) {
+    fn visit_spans<'a>(&'a self, visitor: &mut dyn SpanVisitor<'a>) {
+        self.0.visit_spans(visitor);
+        self.1.visit_spans(visitor);
+    }
+
+    fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut Span)) {
+        self.0.visit_spans_mut(f);
+        self.1.visit_spans_mut(f);
+    }
+}
+
+/// The tokens kept by a custom statement.
+impl VisitSpans for Token<'_> {
+    fn visit_spans<'a>(&'a self, visitor: &mut dyn SpanVisitor<'a>) {
+        visitor.span(&self.span);
+    }
+
+    fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut Span)) {
+        f(&mut self.span);
+    }
+}
+
+/// The errors kept with the statements after `__halt_compiler();`, and the errors of a
+/// parse. Annotations only have a position and a length, so they aren't visited,
+/// except to change them: their start and end are each changed as the position of a
+/// span.
+impl VisitSpans for ParseError {
+    fn visit_spans<'a>(&'a self, visitor: &mut dyn SpanVisitor<'a>) {
+        visitor.enter(Segment::Node(self));
+        visitor.enter(Segment::Field("span"));
+        visitor.span(&self.span);
+        visitor.leave();
+        visitor.leave();
+    }
+
+    fn visit_spans_mut(&mut self, f: &mut dyn FnMut(&mut Span)) {
+        f(&mut self.span);
+
+        for annotation in &mut self.annotations {
+            let mut offset = |offset: usize| {
+                let mut span = Span::new(1, offset + 1, offset);
+                f(&mut span);
+
+                span.position
+            };
+
+            let start = offset(annotation.position);
+            let end = offset(annotation.position + annotation.length);
+
+            annotation.position = start;
+            annotation.length = end.saturating_sub(start);
+        }
+    }
+}
+
+/// The span that starts first among those within `nodes` and the one that ends last,
+/// or two [`Span::dummy`] if there are none.
+pub fn bounds<T: VisitSpans + ?Sized>(nodes: &[&T]) -> (Span, Span) {
+    let mut bounds: Option<(Span, Span)> = None;
+
+    for node in nodes {
+        node.visit_spans(&mut |span: &Span| {
+            let (first, last) = bounds.get_or_insert((*span, *span));
+
+            if span.position < first.position {
+                *first = *span;
+            }
+            if span.end() >= last.end() {
+                *last = *span;
+            }
+        });
+    }
+
+    bounds.unwrap_or((Span::dummy(), Span::dummy()))
+}
+
+/// The positions where a node starts and ends, or `None` if it has no span at all,
+/// like the missing middle of `$a ?: $b`. The end of a node is the end of its last
+/// token.
+pub fn extent<T: VisitSpans + ?Sized>(node: &T) -> Option<(usize, usize)> {
+    let (start, end) = bounds(&[node]);
+
+    (!start.is_dummy()).then(|| (start.position, end.end()))
+}
+
+/// The range of the source that `node` covers, as with [`extent`].
+pub fn range<T: VisitSpans + ?Sized>(node: &T) -> Option<SpanRange> {
+    let (start, end) = bounds(&[node]);
+    if start.is_dummy() {
+        return None;
+    }
+
+    let end = Span::new(end.line, end.column + end.length, end.end());
+
+    Some(start.to(end))
+}
+
+pub trait SpanInvariants {
+    /// Check that the elements of every list in the node are in source order without
+    /// overlapping, that every node contains its children, and that no span is a
+    /// [`Span::dummy`].
+    fn check_span_invariants(&self) -> std::result::Result<(), SpanViolation>;
+}
+
+impl SpanInvariants for Program {
+    fn check_span_invariants(&self) -> std::result::Result<(), SpanViolation> {
+        check(self)
+    }
+}
+
+/// A span that is out of place.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SpanViolation {
+    pub kind: SpanViolationKind,
+    /// The path to the span, e.g. `[1].Function.body.statements[0].Echo.echo`.
+    pub path: String,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SpanViolationKind {
+    /// The span is the first of an element of a list, and starts before the previous
+    /// element ends, with its last span.
+    Order {
+        previous_path: String,
+        previous: Span,
+    },
+    /// The span is a token of a node, and lies inside one of its children, which
+    /// starts with its first span.
+    Containment { child_path: String, child: Span },
+    /// The span is a [`Span::dummy`].
+    Dummy,
+}
+
+impl Display for SpanViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` at {}:{}",
+            self.path, self.span.line, self.span.column
+        )?;
+
+        match &self.kind {
+            SpanViolationKind::Order {
+                previous_path,
+                previous,
+            } => write!(
+                f,
+                " comes before `{}` at {}:{} ends",
+                previous_path, previous.line, previous.column
+            ),
+            SpanViolationKind::Containment { child_path, child } => write!(
+                f,
+                " is inside the child starting with `{}` at {}:{}",
+                child_path, child.line, child.column
+            ),
+            SpanViolationKind::Dummy => write!(f, " is a dummy span"),
+        }
+    }
+}
+
+fn check<T: VisitSpans + ?Sized>(value: &T) -> std::result::Result<(), SpanViolation> {
+    let mut checker = Checker {
+        frames: vec![Frame::default()],
+        found: None,
+    };
+    value.visit_spans(&mut checker);
+
+    let Some(found) = checker.found else {
+        return Ok(());
+    };
+
+    // The spans are found again to tell where they are, which is only worth doing
+    // once something is wrong.
+    let path = |span: &Span| {
+        let mut finder = PathFinder {
+            target: span,
+            segments: vec![],
+            path: None,
+        };
+        value.visit_spans(&mut finder);
+
+        finder.path.unwrap_or_default()
+    };
+
+    let (span, kind) = match found {
+        Found::Order(span, previous) => (
+            span,
+            SpanViolationKind::Order {
+                previous_path: path(previous),
+                previous: *previous,
+            },
+        ),
+        Found::Containment(span, child) => (
+            span,
+            SpanViolationKind::Containment {
+                child_path: path(child),
+                child: *child,
+            },
+        ),
+        Found::Dummy(span) => (span, SpanViolationKind::Dummy),
+    };
+
+    Err(SpanViolation {
+        kind,
+        path: path(span),
+        span: *span,
+    })
+}
+
+/// The first violation found, with the spans it is about.
+enum Found<'a> {
+    Order(&'a Span, &'a Span),
+    Containment(&'a Span, &'a Span),
+    Dummy(&'a Span),
+}
+
+struct Checker<'a> {
+    /// A frame for each segment that was entered, below the frame of the value that
+    /// is checked.
+    frames: Vec<Frame<'a>>,
+    found: Option<Found<'a>>,
+}
+
+#[derive(Default)]
+struct Frame<'a> {
+    node: bool,
+    element: bool,
+    /// The span that starts first within the segment, and the one that ends last.
+    first: Option<&'a Span>,
+    last: Option<&'a Span>,
+    /// The last span of the previous element of a list in the segment.
+    previous: Option<&'a Span>,
+    /// The tokens of a node, which aren't within one of its children.
+    tokens: Vec<&'a Span>,
+    /// The first and last spans of each of the children of a node.
+    children: Vec<(&'a Span, &'a Span)>,
+}
+
+impl<'a> Frame<'a> {
+    fn add(&mut self, first: &'a Span, last: &'a Span) {
+        if self.first.is_none_or(|span| first.position < span.position) {
+            self.first = Some(first);
+        }
+
+        if self.last.is_none_or(|span| last.end() >= span.end()) {
+            self.last = Some(last);
+        }
+    }
+}
+
+impl<'a> Checker<'a> {
+    fn found(&mut self, found: Found<'a>) {
+        self.found.get_or_insert(found);
+    }
+}
+
+impl<'a> SpanVisitor<'a> for Checker<'a> {
+    fn span(&mut self, span: &'a Span) {
+        if span.is_dummy() {
+            return self.found(Found::Dummy(span));
+        }
+
+        if let Some(frame) = self.frames.last_mut() {
+            frame.add(span, span);
+        }
+
+        if let Some(node) = self.frames.iter_mut().rev().find(|frame| frame.node) {
+            node.tokens.push(span);
+        }
+    }
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        self.frames.push(Frame {
+            node: matches!(segment, Segment::Node(_)),
+            element: matches!(segment, Segment::Element(_)),
+            ..Frame::default()
+        });
+    }
+
+    fn leave(&mut self) {
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        let (Some(first), Some(last)) = (frame.first, frame.last) else {
+            return;
+        };
+
+        if frame.node {
+            // A node can keep the first or last token of a child too, like the `}` that
+            // ends both a `try` statement and its last `catch` block.
+            for token in &frame.tokens {
+                let child = frame.children.iter().find(|(start, end)| {
+                    start.position < token.position && token.end() < end.end()
+                });
+
+                if let Some((start, _)) = child {
+                    self.found(Found::Containment(token, start));
+                }
+            }
+
+            if let Some(parent) = self.frames.iter_mut().rev().find(|frame| frame.node) {
+                parent.children.push((first, last));
+            }
+        }
+
+        let Some(parent) = self.frames.last_mut() else {
+            return;
+        };
+
+        if frame.element {
+            if let Some(previous) = parent.previous {
+                if first.position < previous.end() {
+                    self.found.get_or_insert(Found::Order(first, previous));
+                }
+            }
+
+            parent.previous = Some(last);
+        }
+
+        parent.add(first, last);
+    }
+}
+
+/// Finds the path to a span, from the segments it is in.
+struct PathFinder<'a, 'b> {
+    target: &'b Span,
+    segments: Vec<Segment<'a>>,
+    path: Option<String>,
+}
+
+impl<'a> SpanVisitor<'a> for PathFinder<'a, '_> {
+    fn span(&mut self, span: &'a Span) {
+        if self.path.is_none() && std::ptr::eq(span, self.target) {
+            let mut path = String::new();
+            for segment in &self.segments {
+                segment.write(&mut path);
+            }
+
+            self.path = Some(path);
+        }
+    }
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        self.segments.push(segment);
+    }
+
+    fn leave(&mut self) {
+        self.segments.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::Statement;
+
+    #[test]
+    fn test_fixture_like_program_is_valid() {
+        let program = crate::parse(
+            "<?php\n\n// a\nuse Foo; // b\n\n/** c */\nfunction foo($a, $b) {\n    try {} catch (E $e) {} finally {}\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(program.check_span_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_out_of_order_statements() {
+        let mut program = crate::parse("<?php echo 1;\necho 2;").unwrap();
+        program.swap(1, 2);
+
+        let violation = program.check_span_invariants().unwrap_err();
+        assert_eq!(violation.path, "[2].Echo.echo");
+        assert_eq!(violation.span.line, 1);
+        assert!(matches!(
+            violation.kind,
+            SpanViolationKind::Order { previous, .. } if previous.line == 2
+        ));
+    }
+
+    #[test]
+    fn test_token_outside_of_node() {
+        let mut program = crate::parse("<?php echo $a + $b;").unwrap();
+        let Statement::Echo(echo) = &mut program[1] else {
+            panic!("expected an echo statement");
+        };
+        // The `echo` keyword moved to where the `+` is.
+        echo.echo = Span::new(1, 15, 14).with_length(1);
+
+        let violation = program.check_span_invariants().unwrap_err();
+        assert_eq!(violation.path, "[1].Echo.echo");
+        assert_eq!(
+            violation.kind,
+            SpanViolationKind::Containment {
+                child_path: "[1].Echo.values[0].ArithmeticOperation.Addition.left.Variable.SimpleVariable.span".to_string(),
+                child: Span::new(1, 12, 11).with_length(2),
+            }
+        );
+    }
+
+    #[test]
+    fn test_visit_spans_in_order() {
+        let mut program = crate::parse("<?php foo(1, $b);").unwrap();
+
+        let mut positions = vec![];
+        program.visit_spans(&mut |span: &Span| positions.push(span.position));
+        assert_eq!(positions, [0, 6, 9, 10, 13, 15, 16]);
+
+        program.visit_spans_mut(&mut |span| span.position += 1);
+        let mut moved = vec![];
+        program.visit_spans(&mut |span: &Span| moved.push(span.position));
+        assert_eq!(moved, [1, 7, 10, 11, 14, 16, 17]);
+    }
+
+    #[test]
+    fn test_span_range() {
+        let range = Span::new(1, 7, 6).to(Span::new(1, 13, 12));
+
+        assert_eq!(range.len(), 6);
+        assert!(range.contains_offset(6));
+        assert!(!range.contains_offset(12));
+
+        let other = Span::new(1, 10, 9).to(Span::new(2, 1, 20));
+        let joined = range.join(&other);
+
+        assert_eq!((joined.start, joined.end), (range.start, other.end));
+        assert!(joined.contains(&range) && joined.contains(&other));
+        assert!(!range.contains(&other));
+        assert!(Span::dummy().is_dummy());
+    }
+}
@@ -2,6 +2,8 @@ use std::fmt::Debug;
 
 use crate::node::Node;
 
+pub mod walk;
+
 pub trait Visitor<E: Debug> {
     fn visit_node(&mut self, node: &mut dyn Node) -> Result<(), E> {
         self.visit(node)?;
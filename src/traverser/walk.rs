@@ -0,0 +1,237 @@
+//! Walking a program with a hook for each kind of node, see [`walk`] and [`walk_mut`].
+//!
+//! Unlike [`super::Visitor`], which is handed every node as a `dyn Node` to downcast,
+//! these visitors have a method for each kind of node they are interested in, and are
+//! handed nodes of that type.
+
+use crate::downcast::downcast_mut;
+use crate::lexer::token::Span;
+use crate::node::Node;
+use crate::parser::ast::classes::ClassStatement;
+use crate::parser::ast::functions::AbstractConstructor;
+use crate::parser::ast::functions::AbstractMethod;
+use crate::parser::ast::functions::ConcreteConstructor;
+use crate::parser::ast::functions::ConcreteMethod;
+use crate::parser::ast::functions::FunctionStatement;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::variables::Variable;
+use crate::parser::ast::Expression;
+use crate::parser::ast::Program;
+use crate::parser::ast::Statement;
+use crate::spans::Segment;
+use crate::spans::SpanVisitor;
+use crate::spans::VisitSpans;
+
+/// The hooks that [`walk`] calls, which do nothing unless overridden.
+///
+/// A node is visited before its children, and children in the order their fields are
+/// declared in, as in [`VisitSpans`].
+#[allow(unused_variables)]
+pub trait Visitor {
+    fn visit_statement(&mut self, statement: &Statement) {}
+
+    fn visit_expression(&mut self, expression: &Expression) {}
+
+    fn visit_class(&mut self, class: &ClassStatement) {}
+
+    fn visit_function(&mut self, function: &FunctionStatement) {}
+
+    fn visit_method(&mut self, method: &ConcreteMethod) {}
+
+    fn visit_abstract_method(&mut self, method: &AbstractMethod) {}
+
+    fn visit_constructor(&mut self, constructor: &ConcreteConstructor) {}
+
+    fn visit_abstract_constructor(&mut self, constructor: &AbstractConstructor) {}
+
+    /// Every name, whether it is declared, as the name of a class, or used, as the
+    /// name of a function that is called.
+    fn visit_identifier(&mut self, identifier: &SimpleIdentifier) {}
+
+    fn visit_variable(&mut self, variable: &Variable) {}
+}
+
+/// The hooks that [`walk_mut`] calls, which do nothing unless overridden.
+///
+/// A node is visited before its children, so that the children of a node that a hook
+/// replaces are those of the new node.
+#[allow(unused_variables)]
+pub trait VisitorMut {
+    fn visit_statement(&mut self, statement: &mut Statement) {}
+
+    fn visit_expression(&mut self, expression: &mut Expression) {}
+
+    fn visit_class(&mut self, class: &mut ClassStatement) {}
+
+    fn visit_function(&mut self, function: &mut FunctionStatement) {}
+
+    fn visit_method(&mut self, method: &mut ConcreteMethod) {}
+
+    fn visit_abstract_method(&mut self, method: &mut AbstractMethod) {}
+
+    fn visit_constructor(&mut self, constructor: &mut ConcreteConstructor) {}
+
+    fn visit_abstract_constructor(&mut self, constructor: &mut AbstractConstructor) {}
+
+    /// Every name, whether it is declared, as the name of a class, or used, as the
+    /// name of a function that is called.
+    fn visit_identifier(&mut self, identifier: &mut SimpleIdentifier) {}
+
+    fn visit_variable(&mut self, variable: &mut Variable) {}
+}
+
+/// Visit every node of `program` with `visitor`.
+pub fn walk<V: Visitor + ?Sized>(program: &Program, visitor: &mut V) {
+    program.visit_spans(&mut Walker(visitor));
+}
+
+/// Visit every node of `program` with `visitor`, which can change them.
+pub fn walk_mut<V: VisitorMut + ?Sized>(program: &mut Program, visitor: &mut V) {
+    visit_mut(program, visitor);
+}
+
+/// Calls the hooks of a [`Visitor`] as the nodes are entered, which borrows them from
+/// the program.
+struct Walker<'v, V: ?Sized>(&'v mut V);
+
+impl<'a, V: Visitor + ?Sized> SpanVisitor<'a> for Walker<'_, V> {
+    fn span(&mut self, _: &'a Span) {}
+
+    fn enter(&mut self, segment: Segment<'a>) {
+        let Segment::Node(node) = segment else {
+            return;
+        };
+
+        let visitor = &mut *self.0;
+        if let Some(statement) = node.downcast_ref::<Statement>() {
+            visitor.visit_statement(statement);
+        } else if let Some(expression) = node.downcast_ref::<Expression>() {
+            visitor.visit_expression(expression);
+        } else if let Some(class) = node.downcast_ref::<ClassStatement>() {
+            visitor.visit_class(class);
+        } else if let Some(function) = node.downcast_ref::<FunctionStatement>() {
+            visitor.visit_function(function);
+        } else if let Some(method) = node.downcast_ref::<ConcreteMethod>() {
+            visitor.visit_method(method);
+        } else if let Some(method) = node.downcast_ref::<AbstractMethod>() {
+            visitor.visit_abstract_method(method);
+        } else if let Some(constructor) = node.downcast_ref::<ConcreteConstructor>() {
+            visitor.visit_constructor(constructor);
+        } else if let Some(constructor) = node.downcast_ref::<AbstractConstructor>() {
+            visitor.visit_abstract_constructor(constructor);
+        } else if let Some(identifier) = node.downcast_ref::<SimpleIdentifier>() {
+            visitor.visit_identifier(identifier);
+        } else if let Some(variable) = node.downcast_ref::<Variable>() {
+            visitor.visit_variable(variable);
+        }
+    }
+}
+
+fn visit_mut<V: VisitorMut + ?Sized>(node: &mut dyn Node, visitor: &mut V) {
+    if let Some(statement) = downcast_mut::<Statement>(node) {
+        visitor.visit_statement(statement);
+    } else if let Some(expression) = downcast_mut::<Expression>(node) {
+        visitor.visit_expression(expression);
+    } else if let Some(class) = downcast_mut::<ClassStatement>(node) {
+        visitor.visit_class(class);
+    } else if let Some(function) = downcast_mut::<FunctionStatement>(node) {
+        visitor.visit_function(function);
+    } else if let Some(method) = downcast_mut::<ConcreteMethod>(node) {
+        visitor.visit_method(method);
+    } else if let Some(method) = downcast_mut::<AbstractMethod>(node) {
+        visitor.visit_abstract_method(method);
+    } else if let Some(constructor) = downcast_mut::<ConcreteConstructor>(node) {
+        visitor.visit_constructor(constructor);
+    } else if let Some(constructor) = downcast_mut::<AbstractConstructor>(node) {
+        visitor.visit_abstract_constructor(constructor);
+    } else if let Some(identifier) = downcast_mut::<SimpleIdentifier>(node) {
+        visitor.visit_identifier(identifier);
+    } else if let Some(variable) = downcast_mut::<Variable>(node) {
+        visitor.visit_variable(variable);
+    }
+
+    for child in node.children() {
+        visit_mut(child, visitor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::variables::SimpleVariable;
+
+    const SOURCE: &str = include_str!("../../tests/fixtures/0425-walk-variables/code.php");
+
+    #[derive(Default)]
+    struct Counter {
+        variables: usize,
+        functions: Vec<String>,
+        catches: usize,
+    }
+
+    impl Visitor for Counter {
+        fn visit_statement(&mut self, statement: &Statement) {
+            if let Statement::Try(r#try) = statement {
+                self.catches += r#try.catches.len();
+            }
+        }
+
+        fn visit_expression(&mut self, expression: &Expression) {
+            if let Expression::Variable(_) = expression {
+                self.variables += 1;
+            }
+        }
+
+        fn visit_function(&mut self, function: &FunctionStatement) {
+            self.functions.push(function.name.value.to_string());
+        }
+    }
+
+    #[test]
+    fn test_count_variables() {
+        let program = crate::parse(SOURCE).unwrap();
+        let mut counter = Counter::default();
+        walk(&program, &mut counter);
+
+        // Those in the array items, the match arms, the interpolated strings and the
+        // catch block, but not the parameters or the variable that is caught.
+        assert_eq!(counter.variables, 13);
+        assert_eq!(counter.functions, vec!["describe"]);
+        assert_eq!(counter.catches, 1);
+    }
+
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn visit_variable(&mut self, variable: &mut Variable) {
+            if let Variable::SimpleVariable(SimpleVariable { name, .. }) = variable {
+                if name.to_string() == "$label" {
+                    *name = "$title".into();
+                }
+            }
+        }
+    }
+
+    /// The names of the variables that aren't variable variables.
+    #[derive(Default)]
+    struct Names(Vec<String>);
+
+    impl Visitor for Names {
+        fn visit_variable(&mut self, variable: &Variable) {
+            if let Variable::SimpleVariable(variable) = variable {
+                self.0.push(variable.name.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rename_variables() {
+        let mut program = crate::parse(SOURCE).unwrap();
+        walk_mut(&mut program, &mut Rename);
+
+        let mut names = Names::default();
+        walk(&program, &mut names);
+        assert_eq!(names.0.iter().filter(|name| *name == "$title").count(), 4);
+        assert!(!names.0.contains(&"$label".to_string()));
+    }
+}
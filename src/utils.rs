@@ -0,0 +1,251 @@
+//! Helpers for showing code to users, e.g. in diagnostics and document outlines.
+
+use std::ops::Range;
+
+use crate::lexer::token::SpanRange;
+use crate::lexer::token::Token;
+use crate::lexer::token::TokenKind;
+use crate::lexer::Lexer;
+
+/// The length of the previews made by the crate itself, in characters.
+pub const PREVIEW_LENGTH: usize = 80;
+
+/// A one-line preview of the code in `span`, at most `max_len` characters long.
+///
+/// Runs of whitespace, including line breaks, are collapsed to a single space, and
+/// comments are left out. Code that doesn't fit is cut after the last token that does
+/// and followed by `…`, so that a string isn't cut in the middle, unless not even the
+/// first token fits. The code is lexed on its own, and is cut between words instead
+/// when it can't be, e.g. because it starts inside a string. Bytes that aren't valid
+/// UTF-8 are replaced with `�`.
+pub fn preview(source: &[u8], span: SpanRange, max_len: usize) -> String {
+    preview_range(source, span.start.position..span.end.position, max_len)
+}
+
+/// Like [`preview`], for code that has already been lexed. Tokens are separated by a
+/// space when there is a gap between them.
+pub fn preview_tokens(tokens: &[Token], max_len: usize) -> String {
+    let tokens = tokens
+        .iter()
+        .filter(|token| !is_comment(&token.kind) && token.kind != TokenKind::Eof)
+        .collect::<Vec<&Token>>();
+
+    let pieces = tokens
+        .iter()
+        .enumerate()
+        .map(|(index, token)| {
+            let mut piece = String::from_utf8_lossy(&token.value).to_string();
+            let end = token.span.position + token.value.len();
+
+            if tokens
+                .get(index + 1)
+                .is_some_and(|next| next.span.position > end)
+            {
+                piece.push(' ');
+            }
+
+            piece
+        })
+        .collect::<Vec<String>>();
+
+    truncate(&pieces, max_len)
+}
+
+/// Like [`preview`], for a range of byte offsets.
+pub(crate) fn preview_range(source: &[u8], range: Range<usize>, max_len: usize) -> String {
+    let end = range.end.min(source.len());
+    let code = &source[range.start.min(end)..end];
+
+    let pieces = match Lexer::new().tokenize_scripting(code) {
+        Ok(tokens) => {
+            let tokens = tokens
+                .iter()
+                .filter(|token| token.kind != TokenKind::Eof)
+                .collect::<Vec<&Token>>();
+
+            let mut pieces = vec![];
+            let mut start = 0;
+            for (index, token) in tokens.iter().enumerate() {
+                let position = token.span.position.max(start);
+                pieces.push(String::from_utf8_lossy(&code[start..position]).to_string());
+
+                start = position;
+                if is_comment(&token.kind) {
+                    let end = tokens
+                        .get(index + 1)
+                        .map_or(code.len(), |next| next.span.position);
+
+                    pieces.push(" ".to_string());
+                    start = end;
+                }
+            }
+            pieces.push(String::from_utf8_lossy(&code[start..]).to_string());
+
+            pieces
+        }
+        Err(_) => words(code),
+    };
+
+    truncate(&pieces, max_len)
+}
+
+/// Split code at the start of every word.
+fn words(code: &[u8]) -> Vec<String> {
+    let mut pieces = vec![];
+    let mut start = 0;
+    for index in 1..code.len() {
+        if code[index - 1].is_ascii_whitespace() && !code[index].is_ascii_whitespace() {
+            pieces.push(String::from_utf8_lossy(&code[start..index]).to_string());
+            start = index;
+        }
+    }
+    pieces.push(String::from_utf8_lossy(&code[start..]).to_string());
+
+    pieces
+}
+
+/// Join the pieces, collapsing whitespace, and cut the result between two pieces when
+/// it is longer than `max_len`.
+fn truncate(pieces: &[String], max_len: usize) -> String {
+    if max_len == 0 {
+        return String::new();
+    }
+
+    let mut text = String::new();
+    let mut length = 0;
+    // The end of every piece, in bytes, and the length of the text up to it.
+    let mut boundaries = vec![];
+    let mut space = false;
+
+    'pieces: for piece in pieces {
+        for character in piece.chars() {
+            if character.is_whitespace() {
+                space = true;
+
+                continue;
+            }
+
+            if space && !text.is_empty() {
+                text.push(' ');
+                length += 1;
+            }
+
+            space = false;
+            text.push(character);
+            length += 1;
+
+            // The rest won't fit anyway.
+            if length > max_len {
+                break 'pieces;
+            }
+        }
+
+        boundaries.push((text.len(), length));
+    }
+
+    if length <= max_len {
+        return text;
+    }
+
+    // Leave room for the ellipsis, which is separated by a space like the token that
+    // it stands for.
+    let spaced = |end: usize| text[end..].starts_with(' ');
+    let end = boundaries
+        .iter()
+        .rev()
+        .find(|(end, length)| *end > 0 && length + usize::from(spaced(*end)) < max_len)
+        .map_or_else(
+            || {
+                text.char_indices()
+                    .nth(max_len - 1)
+                    .map_or(text.len(), |(end, _)| end)
+            },
+            |(end, _)| *end,
+        );
+
+    format!("{}{}…", &text[..end], if spaced(end) { " " } else { "" })
+}
+
+fn is_comment(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::SingleLineComment
+            | TokenKind::MultiLineComment
+            | TokenKind::HashMarkComment
+            | TokenKind::DocumentComment
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::Span;
+
+    fn preview_of(code: &str, max_len: usize) -> String {
+        let end = Span::new(1, code.len() + 1, code.len());
+
+        preview(code.as_bytes(), Span::new(1, 1, 0).to(end), max_len)
+    }
+
+    #[test]
+    fn test_whitespace_and_comments_are_collapsed() {
+        assert_eq!(
+            preview_of("function ($x)\n    use ($y) // why\n{\n\treturn $x;\n}", 80),
+            "function ($x) use ($y) { return $x; }"
+        );
+    }
+
+    #[test]
+    fn test_truncation_at_a_token_boundary() {
+        assert_eq!(
+            preview_of("function ($x) use ($y) { return $x + $y; }", 30),
+            "function ($x) use ($y) { …"
+        );
+        assert_eq!(
+            preview_of("$a = 'a long string that does not fit';", 20),
+            "$a = …"
+        );
+    }
+
+    #[test]
+    fn test_truncation_inside_a_long_heredoc() {
+        let code = "$sql = <<<SQL\n    SELECT *\n    FROM users\n    WHERE id = 1\n    SQL;";
+
+        assert_eq!(preview_of(code, 30), "$sql = <<<SQL …");
+        assert_eq!(
+            preview_of(code, 80),
+            "$sql = <<<SQL SELECT * FROM users WHERE id = 1 SQL;"
+        );
+    }
+
+    #[test]
+    fn test_truncation_at_a_multibyte_character() {
+        let code = "'ééééééééééééééé'";
+
+        assert_eq!(preview_of(code, 6), "'éééé…");
+        assert_eq!(preview_of(code, 6).chars().count(), 6);
+        assert_eq!(preview_of("$café = 1;", 7), "$café …");
+    }
+
+    #[test]
+    fn test_code_that_cant_be_lexed_is_cut_between_words() {
+        assert_eq!(preview_of("it's a long sentence", 12), "it's a …");
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let span = Span::new(1, 1, 0).to(Span::new(1, 6, 5));
+
+        assert_eq!(preview(b"'a\xffb'", span, 80), "'a\u{fffd}b'");
+    }
+
+    #[test]
+    fn test_preview_tokens() {
+        let tokens = Lexer::new()
+            .tokenize(b"<?php fn ($x) => /* twice */ $x * 2;")
+            .unwrap();
+
+        assert_eq!(preview_tokens(&tokens[1..], 80), "fn ($x) => $x * 2;");
+        assert_eq!(preview_tokens(&tokens[1..], 10), "fn ($x) …");
+    }
+}
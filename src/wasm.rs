@@ -0,0 +1,79 @@
+use wasm_bindgen::prelude::*;
+
+use crate::parser::error::ParseErrorStack;
+
+/// The first error found while parsing, as handed to JavaScript.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct JsParseError {
+    pub code: String,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<ParseErrorStack> for JsParseError {
+    fn from(stack: ParseErrorStack) -> Self {
+        match stack.errors.into_iter().next() {
+            Some(error) => Self {
+                code: error.id,
+                message: error.message,
+                line: error.span.line,
+                column: error.span.column,
+            },
+            None => Self {
+                code: String::new(),
+                message: "failed to parse the given source".to_string(),
+                line: 0,
+                column: 0,
+            },
+        }
+    }
+}
+
+/// Parse the given source, returning the program serialized as JSON, or a [`JsParseError`]
+/// for the first error found.
+///
+/// The JSON has the same shape as `serde_json::to_string(&program)`, so it is described
+/// by the `schema.json` at the root of the repository.
+#[wasm_bindgen]
+pub fn parse_to_json(source: &str) -> Result<String, JsValue> {
+    to_json(source).map_err(JsValue::from)
+}
+
+fn to_json(source: &str) -> Result<String, JsParseError> {
+    let program = crate::parse(source)?;
+
+    serde_json::to_string(&program).map_err(|error| JsParseError {
+        code: String::new(),
+        message: error.to_string(),
+        line: 0,
+        column: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_to_json() {
+        let json = to_json("<?php echo 1;").unwrap();
+        let program: crate::parser::ast::Program = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(program, crate::parse("<?php echo 1;").unwrap());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            to_json("<?php\n\necho 1 +;").unwrap_err(),
+            JsParseError {
+                code: "E003".to_string(),
+                message: "unexpected token `;`".to_string(),
+                line: 3,
+                column: 9,
+            }
+        );
+    }
+}
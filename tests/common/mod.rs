@@ -0,0 +1,31 @@
+use std::env;
+use std::fs::read_dir;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The directories of the fixtures, in order.
+pub fn fixtures() -> Vec<PathBuf> {
+    let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+
+    let mut entries = read_dir(manifest.join("tests/fixtures"))
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|entry| entry.is_dir())
+        .collect::<Vec<PathBuf>>();
+
+    entries.sort();
+
+    entries
+}
+
+/// The code of a fixture, which is empty for a fixture without any.
+pub fn code(fixture: &Path) -> Vec<u8> {
+    let code_file = if fixture.join("code.pxp").exists() {
+        fixture.join("code.pxp")
+    } else {
+        fixture.join("code.php")
+    };
+
+    std::fs::read(code_file).unwrap_or_default()
+}
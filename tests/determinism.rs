@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::thread;
+
+use pxp_parser::analysis::find_clones;
+use pxp_parser::sexpr::ToSExpr;
+use pxp_parser::ParserConfig;
+
+mod common;
+
+use common::code;
+use common::fixtures;
+
+/// Everything the crate outputs for a fixture: the JSON and s-expression of the program
+/// with its warnings and clones, or else the errors and their report.
+fn output(code: &[u8]) -> String {
+    match pxp_parser::parse_with_warnings(code, ParserConfig::default()) {
+        Ok((program, warnings)) => format!(
+            "{}\n{}\n{}\n{:?}",
+            serde_json::to_string(&program).unwrap(),
+            program.to_sexpr(),
+            serde_json::to_string(&warnings).unwrap(),
+            find_clones(&program, 2, 10),
+        ),
+        Err(stack) => {
+            let source = String::from_utf8_lossy(code);
+
+            format!(
+                "{}\n{}",
+                serde_json::to_string(&stack.errors).unwrap(),
+                stack
+                    .report(&source, Some("code.php"), false, true)
+                    .unwrap(),
+            )
+        }
+    }
+}
+
+fn outputs(fixtures: &[PathBuf]) -> Vec<(PathBuf, String)> {
+    fixtures
+        .iter()
+        .map(|fixture| (fixture.clone(), output(&code(fixture))))
+        .collect()
+}
+
+/// Every hash map gets its own random seed, and every thread its own random keys, so
+/// parsing the fixtures a second time on another thread, in the opposite order, would
+/// expose any output that depends on the iteration order of a hash map.
+#[test]
+fn test_output_is_deterministic() {
+    let fixtures = fixtures();
+    let first = outputs(&fixtures);
+
+    let mut reversed = fixtures.clone();
+    reversed.reverse();
+    let mut second = thread::spawn(move || outputs(&reversed)).join().unwrap();
+    second.reverse();
+
+    assert_eq!(first.len(), second.len());
+    for ((fixture, first), (_, second)) in first.iter().zip(&second) {
+        assert!(
+            first == second,
+            "output of fixture `{}` differs between runs",
+            fixture.display()
+        );
+    }
+}
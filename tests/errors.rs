@@ -0,0 +1,148 @@
+use std::env;
+use std::fs::read_dir;
+use std::panic;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The error that a snippet of `tests/errors` should fail with, described by the
+/// `.expect` file next to it:
+///
+/// ```text
+/// code: E005
+/// span: 3:9
+/// message: expecting `)`
+/// ```
+///
+/// The code and the line and column of the span are those of the first error, and the
+/// message only has to be found in the message of that error, so it can be trimmed to
+/// the part that matters.
+#[derive(Debug, PartialEq)]
+struct Expectation {
+    code: String,
+    span: String,
+    message: String,
+}
+
+impl Expectation {
+    fn read(path: &Path) -> Option<Self> {
+        let expect = std::fs::read_to_string(path).ok()?;
+
+        let mut code = None;
+        let mut span = None;
+        let mut message = None;
+
+        for line in expect.lines() {
+            match line.split_once(": ") {
+                Some(("code", value)) => code = Some(value.to_string()),
+                Some(("span", value)) => span = Some(value.to_string()),
+                Some(("message", value)) => message = Some(value.to_string()),
+                _ => panic!("unknown line `{}` in `{}`", line, path.display()),
+            }
+        }
+
+        Some(Self {
+            code: code.unwrap_or_default(),
+            span: span.unwrap_or_default(),
+            message: message.unwrap_or_default(),
+        })
+    }
+
+    fn write(&self, path: &Path) {
+        std::fs::write(
+            path,
+            format!(
+                "code: {}\nspan: {}\nmessage: {}\n",
+                self.code, self.span, self.message
+            ),
+        )
+        .unwrap();
+    }
+
+    fn matches(&self, found: &Self) -> bool {
+        self.code == found.code && self.span == found.span && found.message.contains(&self.message)
+    }
+}
+
+/// The first error that `code` fails with, or why there is none.
+fn first_error(code: &[u8]) -> Result<Expectation, String> {
+    let result = panic::catch_unwind(|| pxp_parser::parse(code));
+
+    match result {
+        Ok(Ok(_)) => Err("parses without errors".to_string()),
+        Ok(Err(stack)) => {
+            let error = &stack.errors[0];
+
+            Ok(Expectation {
+                code: error.id.clone(),
+                span: format!("{}:{}", error.span.line, error.span.column),
+                message: error.message.clone(),
+            })
+        }
+        Err(_) => Err("panics".to_string()),
+    }
+}
+
+#[test]
+fn test_error_snippets() {
+    let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let update = env::var_os("UPDATE_EXPECT").is_some();
+
+    let mut snippets = read_dir(manifest.join("tests/errors"))
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|entry| {
+            entry
+                .extension()
+                .is_some_and(|extension| extension == "php")
+        })
+        .collect::<Vec<PathBuf>>();
+
+    snippets.sort();
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut failures = vec![];
+    for snippet in &snippets {
+        let name = snippet.file_stem().unwrap().to_string_lossy();
+        let expect_file = snippet.with_extension("expect");
+        let expected = Expectation::read(&expect_file);
+
+        let found = match first_error(&std::fs::read(snippet).unwrap()) {
+            Ok(found) => found,
+            Err(reason) => {
+                failures.push(format!("snippet `{}` {}, but should fail", name, reason));
+                continue;
+            }
+        };
+
+        match expected {
+            Some(expected) if expected.matches(&found) => {}
+            Some(expected) if !update => failures.push(format!(
+                "snippet `{}` fails with the wrong error\n   want [{}] at {}: ...{}...\n   got  [{}] at {}: {}",
+                name,
+                expected.code,
+                expected.span,
+                expected.message,
+                found.code,
+                found.span,
+                found.message
+            )),
+            None if !update => failures.push(format!(
+                "snippet `{}` has no `{}` file",
+                name,
+                expect_file.file_name().unwrap().to_string_lossy()
+            )),
+            _ => found.write(&expect_file),
+        }
+    }
+
+    panic::set_hook(hook);
+
+    assert!(
+        failures.is_empty(),
+        "{}\n\nrun with `UPDATE_EXPECT=1` to accept the errors that are found",
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,3 @@
+<?php
+
+abstract class A { abstract function f() {} }
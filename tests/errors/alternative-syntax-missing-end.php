@@ -0,0 +1,6 @@
+<?php
+
+if ($items):
+    foreach ($items as $item):
+        echo $item;
+endif;
@@ -0,0 +1,5 @@
+<?php
+
+if ($user->isAdmin()):
+    echo "admin";
+endwhile;
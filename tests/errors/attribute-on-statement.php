@@ -0,0 +1,3 @@
+<?php
+
+#[Foo] echo 1;
@@ -0,0 +1,3 @@
+<?php
+
+#[Foo(1] function f() {}
@@ -0,0 +1,3 @@
+<?php
+
+class A { const callable B = 1; }
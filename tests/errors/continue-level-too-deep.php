@@ -0,0 +1,3 @@
+<?php
+
+while (1) { continue 2; }
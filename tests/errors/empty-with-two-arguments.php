@@ -0,0 +1,4 @@
+<?php
+
+if (empty($a, $b)) {
+}
@@ -0,0 +1,3 @@
+<?php
+
+enum E: float { case A = 1.0; }
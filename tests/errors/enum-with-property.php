@@ -0,0 +1,3 @@
+<?php
+
+enum E { public $a; }
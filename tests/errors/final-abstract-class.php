@@ -0,0 +1,3 @@
+<?php
+
+final abstract class A {}
@@ -0,0 +1,3 @@
+<?php
+
+class A { final private const A = 1; }
@@ -0,0 +1,3 @@
+<?php
+
+interface I { final function f(); }
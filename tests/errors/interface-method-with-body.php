@@ -0,0 +1,3 @@
+<?php
+
+interface I { function f() {} }
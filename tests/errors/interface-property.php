@@ -0,0 +1,3 @@
+<?php
+
+interface I { public $a; }
@@ -0,0 +1,5 @@
+<?php
+
+if (isset()) {
+    echo 1;
+}
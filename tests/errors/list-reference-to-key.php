@@ -0,0 +1,3 @@
+<?php
+
+list(&$a => $b) = $c;
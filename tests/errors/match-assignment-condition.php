@@ -0,0 +1,3 @@
+<?php
+
+$a = match ($b) { $c = 1 => 2 };
@@ -0,0 +1,3 @@
+<?php
+
+$a = match ($b) { default => 1, default => 2 };
@@ -0,0 +1,3 @@
+<?php
+
+$a = match ($b) { 1 2 };
@@ -0,0 +1,3 @@
+<?php
+
+function f(A&?B $a) {}
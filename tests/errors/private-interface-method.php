@@ -0,0 +1,3 @@
+<?php
+
+interface I { private function f(); }
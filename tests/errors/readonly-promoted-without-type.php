@@ -0,0 +1,3 @@
+<?php
+
+class A { function __construct(public readonly $a) {} }
@@ -0,0 +1,3 @@
+<?php
+
+class A { static const A = 1; }
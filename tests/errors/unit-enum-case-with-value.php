@@ -0,0 +1,3 @@
+<?php
+
+enum E { case A = 1; }
@@ -0,0 +1,14 @@
+<?php
+
+foreach ($a as $b) {
+    while (true) {
+        break 2;
+    }
+}
+
+for (;;):
+    switch ($a) {
+        case 1:
+            continue 2;
+    }
+endfor;
@@ -0,0 +1,7 @@
+<?php
+
+foreach ($a as $b) {
+    $c = function () {
+        break;
+    };
+}
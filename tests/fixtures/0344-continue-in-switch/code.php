@@ -0,0 +1,6 @@
+<?php
+
+switch ($a) {
+    default:
+        continue;
+}
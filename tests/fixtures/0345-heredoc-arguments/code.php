@@ -0,0 +1,18 @@
+<?php
+
+foo(<<<SQL
+    SELECT * FROM {$table}
+    SQL, $a, $b);
+
+foo($a, <<<SQL
+    SELECT $column
+    SQL, $b);
+
+foo($a, $b, <<<SQL
+    SELECT 1
+    SQL);
+
+foo($a, <<<SQL
+    SELECT 1
+    SQL,
+);
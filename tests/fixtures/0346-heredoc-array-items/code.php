@@ -0,0 +1,13 @@
+<?php
+
+$a = [
+    <<<EOT
+    first {$b}
+    EOT,
+    'key' => <<<EOT
+    second
+    EOT,
+    <<<EOT
+    third
+    EOT => $c,
+];
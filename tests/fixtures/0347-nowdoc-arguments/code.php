@@ -0,0 +1,14 @@
+<?php
+
+foo(<<<'SQL'
+    SELECT $a
+    SQL, $b);
+
+foo($a, <<<'SQL'
+    SELECT {$b}
+    SQL,
+);
+
+$a = [<<<'EOT'
+    value
+    EOT, 1];
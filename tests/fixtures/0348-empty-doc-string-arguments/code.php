@@ -0,0 +1,11 @@
+<?php
+
+foo(<<<SQL
+    SQL, 1);
+
+foo(<<<'SQL'
+    SQL, 1);
+
+$a = [<<<EOT
+EOT, <<<'EOT'
+EOT];
@@ -0,0 +1,5 @@
+<?php
+
+enum Suit implements HasLabel : string {
+    case Hearts = "H";
+}
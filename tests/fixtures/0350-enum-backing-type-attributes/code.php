@@ -0,0 +1,5 @@
+<?php
+
+enum Suit : #[Attr] string implements HasLabel {
+    case Hearts = "H";
+}
@@ -0,0 +1,8 @@
+<?php
+
+use App\Contracts\HasLabel;
+
+enum Suit: string implements HasLabel, \App\Contracts\HasColor, Contracts\HasIcon
+{
+    case Hearts = 'H';
+}
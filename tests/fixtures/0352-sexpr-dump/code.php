@@ -0,0 +1,25 @@
+<?php
+
+#[Entity(table: 'users')]
+final class User extends Model implements HasName
+{
+    public function __construct(private readonly string $name) {}
+
+    public function greeting(int $hour): string
+    {
+        $format = fn (string $name): string => "Hello, {$name}!";
+
+        return match (true) {
+            $hour < 12 => <<<EOT
+                Good morning, {$this->name}.
+                EOT,
+            default => $format($this->name),
+        };
+    }
+}
+
+$callback = function ($user) use (&$count) {
+    $count++;
+
+    return $user->greeting(9);
+};
@@ -0,0 +1,17 @@
+<?php
+
+class Foo extends Bar
+{
+    private array $items = [];
+    public int $x = self::DEFAULT;
+    public static $y = parent::VALUE;
+    public ?int $z = null;
+    public int|string|null $a = null;
+    public float $b = 1;
+    public float $c = -1.5;
+    public bool $d = false;
+    public iterable $e = [1, 2, 3];
+    public mixed $f = null;
+    public ?Baz $g = null;
+    public Baz $h = self::BAZ;
+}
@@ -0,0 +1,6 @@
+<?php
+
+class Foo
+{
+    public Bar $bar = null;
+}
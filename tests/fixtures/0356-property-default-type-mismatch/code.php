@@ -0,0 +1,6 @@
+<?php
+
+class Foo
+{
+    public int $x = 'str';
+}
@@ -0,0 +1,5 @@
+<?php
+
+echo 1;
+
+// trailing comment
\ No newline at end of file
@@ -0,0 +1,5 @@
+<?php
+
+echo 1;
+
+#[Attr]
\ No newline at end of file
@@ -0,0 +1,5 @@
+<?php
+
+echo 1;
+
+#[Foo] #[Bar]
@@ -0,0 +1,5 @@
+<?php
+
+enum Foo {
+    final case Bar;
+}
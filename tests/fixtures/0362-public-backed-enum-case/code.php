@@ -0,0 +1,5 @@
+<?php
+
+enum Foo: string {
+    public case Bar = 'bar';
+}
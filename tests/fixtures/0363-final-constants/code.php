@@ -0,0 +1,14 @@
+<?php
+
+class Foo {
+    final public const BAR = 1;
+    private const BAZ = 2;
+}
+
+interface Qux {
+    final const QUUX = 3;
+}
+
+enum Corge {
+    final protected const GRAULT = 4;
+}
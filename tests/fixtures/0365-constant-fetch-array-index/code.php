@@ -0,0 +1,4 @@
+<?php
+
+Foo::BAR[0][1];
+self::BAZ[0];
@@ -0,0 +1,3 @@
+<?php
+
+while ($x) { foo(); endwhile;
@@ -0,0 +1,3 @@
+<?php
+
+if ($a) { foreach ($x as $y): foo(); }
@@ -0,0 +1,18 @@
+<?php
+
+while ($a) {
+    foreach ($b as $c):
+        if ($c) {
+            foo();
+        }
+    endforeach;
+}
+
+if ($a):
+    switch ($b) {
+        case 1:
+            for (;;):
+                break;
+            endfor;
+    }
+endif;
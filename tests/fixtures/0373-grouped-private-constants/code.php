@@ -0,0 +1,7 @@
+<?php
+
+class Foo {
+    private const A = 1, B = 2;
+    public const C = 1;
+    protected const D = 2;
+}
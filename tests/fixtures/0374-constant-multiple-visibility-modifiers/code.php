@@ -0,0 +1,5 @@
+<?php
+
+class Foo {
+    private public const E = 1;
+}
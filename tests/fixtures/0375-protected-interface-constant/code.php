@@ -0,0 +1,5 @@
+<?php
+
+interface Foo {
+    protected const A = 1, B = 2;
+}
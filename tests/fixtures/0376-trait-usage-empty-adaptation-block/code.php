@@ -0,0 +1,6 @@
+<?php
+
+class Foo {
+    use A, B;
+    use C, D {}
+}
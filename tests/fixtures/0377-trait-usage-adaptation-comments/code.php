@@ -0,0 +1,13 @@
+<?php
+
+class Foo {
+    use A, B {
+        // Prefer the implementation from `A`.
+        A::foo insteadof B;
+        /* keep `B`'s as well */
+        B::foo as protected bar;
+
+        # and make `baz` private
+        baz as private;
+    }
+}
@@ -0,0 +1,6 @@
+<?php
+
+class Foo {
+    use A { A::foo as bar; }
+    use B { B /* trait */ :: /* method */ foo as baz; }
+}
@@ -0,0 +1,7 @@
+<?php
+
+declare(encoding='ISO-8859-1');
+
+function caf() {
+    return ' la carte';
+}
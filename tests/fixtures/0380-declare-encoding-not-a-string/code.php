@@ -0,0 +1,5 @@
+<?php
+
+declare(encoding=8859);
+
+echo 'hello';
@@ -0,0 +1,4 @@
+<?php
+
+list($a, $b,) = $c;
+list(, $d) = $e;
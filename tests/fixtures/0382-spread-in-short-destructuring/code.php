@@ -0,0 +1,3 @@
+<?php
+
+[$a, ...$rest] = $items;
@@ -0,0 +1,7 @@
+<?php
+
+class Match {}
+
+function list() {}
+
+const NULL = 1;
@@ -0,0 +1,9 @@
+<?php
+
+class Enum {}
+
+interface Numeric {}
+
+function int() {}
+
+const SELF = 1;
@@ -0,0 +1,10 @@
+<?php
+
+clone $obj->prop;
+clone $obj->prop();
+new $a[0];
+clone $a ?? $b;
+clone new Foo();
+new $this->class();
+new $a['b']->c::$d($e);
+new Foo::$bar;
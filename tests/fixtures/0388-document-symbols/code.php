@@ -0,0 +1,55 @@
+<?php
+
+namespace App\Models;
+
+const VERSION = 1, BUILD = 2;
+
+function helper(): void {}
+
+interface HasName
+{
+    const PREFIX = 'name';
+
+    public function name(): string;
+}
+
+trait Greets
+{
+    var $greeting;
+
+    public function greet(): string
+    {
+        return $this->greeting;
+    }
+}
+
+#[Entity]
+final class User implements HasName
+{
+    use Greets;
+
+    public const TABLE = 'users';
+
+    private ?string $first = null, $last = null;
+
+    public function __construct(public int $id) {}
+
+    public function name(): string
+    {
+        return new class {
+            public $anonymous;
+        };
+    }
+}
+
+enum Status: string
+{
+    case Active = 'active';
+
+    case Inactive = 'inactive';
+
+    public function label(): string
+    {
+        return ucfirst($this->value);
+    }
+}
@@ -0,0 +1,5 @@
+<?php
+
+if (\isset($x)) {
+    echo $x;
+}
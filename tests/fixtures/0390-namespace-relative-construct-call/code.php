@@ -0,0 +1,5 @@
+<?php
+
+namespace Foo;
+
+$a = namespace\array(1, 2);
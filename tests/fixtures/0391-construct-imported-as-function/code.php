@@ -0,0 +1,5 @@
+<?php
+
+use function Foo\list;
+
+list($a, $b) = [1, 2];
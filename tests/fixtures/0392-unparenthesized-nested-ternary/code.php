@@ -0,0 +1,3 @@
+<?php
+
+$x = $a ? $b : $c ? $d : $e;
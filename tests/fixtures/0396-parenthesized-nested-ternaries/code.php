@@ -0,0 +1,9 @@
+<?php
+
+$x = $a ? ($b ? $c : $d) : $e;
+$x = $a ? $b ? $c : $d : $e;
+$x = $a ? $b : ($c ? $d : $e);
+$x = ($a ? $b : $c) ? $d : $e;
+$x = $a ?: $b ?: $c;
+$x = $a ?: ($b ? $c : $d);
+$x = $a ? $b : $y = $c ? $d : $e;
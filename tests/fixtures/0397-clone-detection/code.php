@@ -0,0 +1,49 @@
+<?php
+
+function total(array $items): int
+{
+    $sum = 0;
+    foreach ($items as $item) {
+        $sum += $item['price'] * $item['quantity'];
+    }
+    log_total('items', $sum);
+
+    return $sum;
+}
+
+function weight(array $parcels): int
+{
+    // Copied from total(), with the names changed.
+    $weight = 0;
+    foreach ($parcels as $parcel) {
+        $weight += $parcel['mass'] * $parcel['count'];
+    }
+    log_total('parcels', $weight);
+
+    return $weight;
+}
+
+class Invoice
+{
+    public function amount(array $lines): int
+    {
+        $amount = 100;
+        foreach ($lines as $line) {
+            $amount += $line['cost'] * $line['units'];
+        }
+        log_total('lines', $amount);
+
+        return $amount;
+    }
+}
+
+function near_miss(array $rows): int
+{
+    $count = 0;
+    foreach ($rows as $row) {
+        $count += $rows['size'] * $row['weight'];
+    }
+    log_total('rows', $count);
+
+    return $count;
+}
@@ -0,0 +1,4 @@
+<?php
+
+use Foo\Bar;
+use Baz\Bar;
@@ -0,0 +1,6 @@
+<?php
+
+namespace App;
+
+use Foo\Bar;
+use Baz\{Qux, Bar};
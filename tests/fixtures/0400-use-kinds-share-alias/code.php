@@ -0,0 +1,10 @@
+<?php
+
+use Foo\bar;
+use function Baz\bar;
+use const Qux\bar;
+use const Qux\BAR;
+
+namespace Other;
+
+use Baz\Bar;
@@ -0,0 +1,10 @@
+<?php
+
+function retry($attempts) {
+    start:
+    if ($attempts > 0):
+        $attempts--;
+        start:
+        goto start;
+    endif;
+}
@@ -0,0 +1,15 @@
+<?php
+
+start:
+$retry = function () {
+    start:
+    goto start;
+};
+
+while (true) {
+    declare(ticks=1) {
+        end:
+    }
+}
+
+goto start;
@@ -0,0 +1,11 @@
+<?php echo '<ul>'; ?>
+<?php foreach ($users as $user): ?>
+    <li><?php echo "{$user->name} <{$user->email}>"; ?></li>
+<?php endforeach; ?>
+</ul>
+<h1><?= $title ?></h1>
+<?php
+print $footer;
+printf('%d users', $count);
+?>
+<p><?= 'Done' ?></p>
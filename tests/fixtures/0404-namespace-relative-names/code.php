@@ -0,0 +1,18 @@
+<?php
+
+namespace App;
+
+#[namespace\Attributes\Route('/')]
+function handle(namespace\Http\Request $request): namespace\Http\Response
+{
+    try {
+        return new namespace\Http\Response($request instanceof namespace\Http\JsonRequest);
+    } catch (namespace\Http\NotFound | namespace\Http\Gone $e) {
+        return namespace\Http\Response::empty();
+    }
+}
+
+class Controller extends namespace\Base
+{
+    use namespace\Concerns\Validates;
+}
@@ -0,0 +1,20 @@
+<?php
+
+declare(strict_types=1);
+
+declare(ticks=1);
+
+function work() {}
+
+declare(ticks=5, encoding='UTF-8') {
+    work();
+    declare(ticks=10);
+    work();
+}
+
+work();
+
+// The rest of the file ticks every other statement.
+declare(ticks=2);
+
+declare(ticks=3) work();
@@ -0,0 +1,7 @@
+<?php
+
+echo "line __LINE__ of __FILE__: {$arr['key']} {$obj->method()} {${$name}} $plain";
+
+echo <<<EOT
+    line __LINE__ of __FILE__: {$arr['key']} {$obj->method()} {${$name}} $plain
+    EOT;
@@ -0,0 +1,20 @@
+<?php
+
+declare(strict_types=1);
+
+function connect(float $timeout = 30, int $retries = '1', ?string $host = null, array $ports = [1, 2]) {}
+
+class Client
+{
+    public string $name = 'client';
+
+    public ?string $region = null;
+
+    public function __construct(
+        private int|bool $limit = 1.5,
+        private string $label = null,
+        string $prefix = null,
+    ) {}
+
+    public function call(callable $callback = 'strlen', float ...$weights) {}
+}
@@ -0,0 +1,41 @@
+<?php
+
+namespace App\Cards {
+    enum Suit: string
+    {
+        case Hearts = 'H';
+        case Spades = 'S';
+
+        public static function fromChar(string $char): self
+        {
+            return self::from($char);
+        }
+    }
+}
+
+namespace App\Legacy {
+    class Suit
+    {
+        const Hearts = 'H';
+    }
+}
+
+namespace App\Game {
+    use App\Cards\Suit;
+    use App\Legacy;
+
+    function play(Suit $suit, string $value): ?Suit
+    {
+        if ($suit instanceof Suit && $suit === Suit::Hearts) {
+            return Suit::tryFrom($value);
+        }
+
+        $legacy = Legacy\Suit::Hearts;
+
+        foreach (Suit::cases() as $case) {
+            echo Deck::Hearts, $case::class;
+        }
+
+        return Suit::from($legacy);
+    }
+}
@@ -0,0 +1,11 @@
+<?php
+
+declare(ticks=-1);
+declare(precision = - 1.5);
+
+enum Direction: int
+{
+    case Up = -1;
+    case Down = -Direction::Up->value;
+    case Still = -PHP_INT_MAX;
+}
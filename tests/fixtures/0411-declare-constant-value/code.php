@@ -0,0 +1,3 @@
+<?php
+
+declare(ticks=PHP_INT_MAX);
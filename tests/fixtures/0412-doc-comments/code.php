@@ -0,0 +1,45 @@
+<?php
+
+/** The version. */
+const VERSION = '1.0';
+
+/**
+ * @template T of object
+ * @param class-string<T> $class
+ * @return T
+ * @throws \RuntimeException when it can't be found, see {@link Container}
+ * @phpstan-assert !null $class
+ */
+function make(string $class): object {}
+
+/*
+ * @phpstan-type Options array{retries: int}
+ */
+#[Attribute]
+final class Client
+{
+    /** @var int */
+    public const RETRIES = 3;
+
+    // @deprecated is on the next line.
+    /** @deprecated */
+    public ?string $name = null;
+
+    public int $port = 80;
+
+    /**
+     ** @param Options $options
+     */
+    public function __construct(array $options) {}
+
+    #[Override]
+    /** Not the doc comment, it comes after an attribute. */
+    public function send(): void {}
+}
+
+/** @psalm-immutable */
+enum Status: string
+{
+    /** @since 2.0 */
+    case Active = 'active';
+}
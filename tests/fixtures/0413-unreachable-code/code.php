@@ -0,0 +1,55 @@
+<?php
+
+function greet(string $name): string
+{
+    return "Hello, {$name}!";
+    echo 'never printed';
+    $name = strtoupper($name);
+}
+
+function retry(int $attempts): void
+{
+    start:
+    if ($attempts-- > 0) {
+        goto start;
+        // Reached again through the label below.
+        again:
+        echo 'retrying';
+    }
+
+    throw new RuntimeException('Out of attempts');
+    $attempts = 0;
+}
+
+foreach ([1, 2, 3] as $number) {
+    if ($number === 2) {
+        continue;
+        echo 'skipped';
+    }
+
+    switch ($number) {
+        case 1:
+            break;
+            echo 'after break';
+        default:
+            echo $number;
+    }
+}
+
+$handler = function () {
+    exit(1);
+    echo 'closure';
+};
+
+return helper();
+
+echo 'dead';
+
+function helper(): int
+{
+    return 1;
+}
+
+class Helper
+{
+}
@@ -0,0 +1,48 @@
+<?php
+
+namespace App\Routing;
+
+use Attribute;
+
+#[Attribute(Attribute::TARGET_METHOD)]
+final class Route
+{
+    public function __construct(public string $path)
+    {
+    }
+}
+
+#[Attribute(Attribute::TARGET_PARAMETER | Attribute::IS_REPEATABLE)]
+final class Inject
+{
+}
+
+#[Attribute]
+final class Marker
+{
+}
+
+#[Route('/users')]
+final class UserController
+{
+    #[Route('/users'), Route('/people')]
+    public function index(): array
+    {
+        return [];
+    }
+
+    public function __construct(
+        #[Inject, Inject] private Repository $users,
+        #[Route('/nowhere')] private Logger $logger,
+    ) {
+    }
+
+    #[Marker]
+    #[Marker]
+    public const VERSION = 2;
+
+    #[Cache]
+    public function show(#[Marker] int $id): void
+    {
+    }
+}
@@ -0,0 +1,6 @@
+<?php
+
+#[Foo(1)
+class A {}
+
+class B {}
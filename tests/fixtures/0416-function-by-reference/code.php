@@ -0,0 +1,13 @@
+<?php
+
+function &foo() {}
+
+function &() {};
+
+$x = function &() {};
+
+#[A]
+function &bar() {}
+
+#[A]
+function &() {};
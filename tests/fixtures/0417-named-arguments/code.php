@@ -0,0 +1,4 @@
+<?php
+
+new Foo(...$options, timeout: 5);
+foo(1, fn: 1, array: [], class: Bar::class,);
@@ -0,0 +1,3 @@
+<?php
+
+new Foo(timeout: 5, ...$rest);
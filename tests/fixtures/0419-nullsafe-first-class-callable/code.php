@@ -0,0 +1,3 @@
+<?php
+
+$format = $formatter?->format(...);
@@ -0,0 +1,3 @@
+<?php
+
+$log = sprintf(..., $message);
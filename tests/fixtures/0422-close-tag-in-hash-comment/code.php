@@ -0,0 +1,5 @@
+<?php
+
+# render the header ?><h1>Title</h1>
+<?php
+function footer() {}
@@ -0,0 +1,4 @@
+<?php
+
+/* render the header ?><h1>Title</h1> */
+function footer() {}
@@ -0,0 +1,20 @@
+<?php
+
+declare(strict_types=1);
+
+namespace App\Http;
+
+// Framework
+use Illuminate\Support\Str;
+use Illuminate\Http\{Request, Response as HttpResponse};
+use App\Models\User; // the current user
+
+/* Helpers */
+use function App\Support\version10;
+use function App\Support\version2;
+use const App\Support\DEBUG;
+use App\Models\{Team, function team_name};
+
+final class Controller
+{
+}
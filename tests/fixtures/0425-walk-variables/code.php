@@ -0,0 +1,17 @@
+<?php
+
+function describe(array $items, ?string $label): string
+{
+    $parts = [$label, 'count' => count($items), ...$items];
+
+    try {
+        $kind = match ($items[0]) {
+            $label => "same as {$label}",
+            default => "item {$items[0]} of $label",
+        };
+    } catch (Exception $e) {
+        return $e->getMessage();
+    }
+
+    return implode(', ', $parts) . $kind;
+}
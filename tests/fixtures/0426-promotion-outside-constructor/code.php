@@ -0,0 +1,7 @@
+<?php
+
+class Point {
+    public function move(
+        #[SensitiveParameter] private int $x = 0,
+    ): void {}
+}
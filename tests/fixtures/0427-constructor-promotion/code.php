@@ -0,0 +1,10 @@
+<?php
+
+class Point {
+    public function __CONSTRUCT(
+        #[SensitiveParameter] private readonly int $x,
+        protected string $y = 'a',
+        public ?Point $origin = null,
+        int ...$rest,
+    ) {}
+}
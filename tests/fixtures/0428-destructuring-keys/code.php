@@ -0,0 +1,8 @@
+<?php
+
+['a' . 'b' => $x] = $arr;
+[CONST_KEY => &$ref] = $arr;
+[$obj->prop => $v] = $arr;
+list('a' => &$a, 'b' => [&$b]) = $arr;
+
+foreach ($rows as $key => list(1 + 1 => &$value)) {}
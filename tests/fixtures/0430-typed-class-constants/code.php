@@ -0,0 +1,21 @@
+<?php
+
+interface HasVersion
+{
+    const string VERSION = '1.0';
+}
+
+class Config implements HasVersion
+{
+    final public const ?int LIMIT = null;
+    protected const int|string KEY = 'key', OTHER = 1;
+    const (Countable&Traversable)|array ITEMS = [];
+    const self DEFAULT = new self();
+}
+
+enum Mode
+{
+    const Mode FALLBACK = self::Fast;
+
+    case Fast;
+}
@@ -0,0 +1,3 @@
+<?php
+
+function format(?string|int $value): string {}
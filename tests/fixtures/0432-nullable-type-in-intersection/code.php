@@ -0,0 +1,3 @@
+<?php
+
+function count_all(Countable&?Traversable $items): int {}
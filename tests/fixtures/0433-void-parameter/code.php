@@ -0,0 +1,3 @@
+<?php
+
+function nothing(void $value) {}
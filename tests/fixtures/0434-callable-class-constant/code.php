@@ -0,0 +1,6 @@
+<?php
+
+class Handlers
+{
+    const callable DEFAULT = 'strlen';
+}
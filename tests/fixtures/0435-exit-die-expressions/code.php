@@ -0,0 +1,12 @@
+<?php
+
+exit;
+exit();
+exit(1);
+die;
+die();
+die("message");
+
+$x ?: exit(1);
+$f = fn () => die('no');
+$handle = fopen($file, 'r') or die;
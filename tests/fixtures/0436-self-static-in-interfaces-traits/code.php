@@ -0,0 +1,31 @@
+<?php
+
+interface Factory extends Base
+{
+    const DEFAULT = self::NAME . '.default';
+
+    public function make(): static;
+
+    public function base(): parent;
+}
+
+trait Counts
+{
+    const STEP = 1;
+
+    public function next(): int
+    {
+        return self::STEP + static::$count;
+    }
+}
+
+$counter = new class {
+    public static int $count = 0;
+
+    public function reset(): static
+    {
+        static::$count = 0;
+
+        return $this;
+    }
+};
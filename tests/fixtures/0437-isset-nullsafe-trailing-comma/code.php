@@ -0,0 +1,5 @@
+<?php
+
+isset($user?->address?->city, $options['k'],);
+empty($user?->profile?->name,);
+unset($cache[$key], $this->items,);
@@ -0,0 +1,23 @@
+<?php
+
+namespace App;
+
+trait Logs
+{
+    public function log(string $method = __METHOD__, int $line = __LINE__, string $trait = __TRAIT__): array
+    {
+        echo __METHOD__;
+        $x = __LINE__ + 1;
+
+        return [
+            __CLASS__ => __FUNCTION__,
+            __NAMESPACE__ => __DIR__ . '/' . basename(__FILE__),
+            __LINE__ => $x,
+        ];
+    }
+}
+
+function where($file = __FILE__, $dir = __DIR__, $lines = [__LINE__ => __FUNCTION__])
+{
+    return __line__ * 2;
+}
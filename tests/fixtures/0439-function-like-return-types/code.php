@@ -0,0 +1,15 @@
+<?php
+
+use App\Models\User as Account;
+
+$a = fn (): (A&B)|null => $x;
+$b = function (): ?static { };
+$c = function &(): array { };
+$d = static fn &(int $id): Account|int|null => $id;
+
+function &named(): (A&B)|null {}
+
+interface Repository
+{
+    public function &find(): ?static;
+}
@@ -0,0 +1,14 @@
+<?php
+
+use Vendor\SomeTrait as ST;
+
+class A
+{
+    use \Vendor\Package\SomeTrait, ST, Other {
+        \Vendor\Package\SomeTrait::method insteadof Other, ST;
+        ST::method as renamed;
+        Other::list as protected print;
+        Package\Helper::default as public;
+        new as private construct;
+    }
+}
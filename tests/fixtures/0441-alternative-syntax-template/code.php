@@ -0,0 +1,20 @@
+<?php if ($items): ?>
+<ul>
+    <?php foreach ($items as $item): ?>
+        <?php switch ($item->type): ?>
+<?php case 'link': ?>
+            <li><a href="<?= $item->url ?>"><?= $item->title ?></a></li>
+            <?php break; ?>
+<?php default: ?>
+            <li><?= $item->title ?></li>
+        <?php endswitch; ?>
+    <?php endforeach; ?>
+</ul>
+<?php elseif ($loading): ?>
+    <?php for ($i = 0; $i < 3; $i++): ?>
+        <?php while (false): endwhile; ?>
+        <p>Loading</p>
+    <?php endfor; ?>
+<?php else: ?>
+    <p>Nothing here.</p>
+<?php endif; ?>
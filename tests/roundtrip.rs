@@ -0,0 +1,38 @@
+use pxp_parser::parser::ast::Program;
+use pxp_parser::remap::RemapSpans;
+
+mod common;
+
+use common::code;
+use common::fixtures;
+
+/// Every fixture that parses deserializes from its JSON to the program it was
+/// serialized from.
+#[test]
+fn test_json_round_trip() {
+    let mut failures = vec![];
+
+    for fixture in fixtures() {
+        let Ok(program) = pxp_parser::parse(&code(&fixture)) else {
+            continue;
+        };
+
+        let json = serde_json::to_string(&program).unwrap();
+        match serde_json::from_str::<Program>(&json) {
+            Ok(deserialized) if deserialized == program => {}
+            Ok(_) => failures.push(format!("{}: the program changed", fixture.display())),
+            Err(error) => failures.push(format!("{}: {}", fixture.display(), error)),
+        }
+
+        let mut remapped = program.clone();
+        remapped.remap_spans(&|span| span);
+        if remapped != program {
+            failures.push(format!(
+                "{}: remapping changed the program",
+                fixture.display()
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
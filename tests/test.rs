@@ -4,16 +4,20 @@ use std::io;
 use std::path::PathBuf;
 
 use pretty_assertions::assert_str_eq;
+use pxp_parser::sexpr::ToSExpr;
+use pxp_parser::spans::SpanInvariants;
 
 struct TestFixture {
     fixture: String,
     code_file: PathBuf,
     ast_file: PathBuf,
+    sexpr_file: PathBuf,
     error_file: PathBuf,
 }
 
 struct ExpectedTestResult {
     ast: String,
+    sexpr: String,
     error: String,
 }
 
@@ -29,12 +33,13 @@ impl TestFixture {
             fixture: entry.to_string_lossy().to_string(),
             code_file,
             ast_file: entry.join("ast.txt"),
+            sexpr_file: entry.join("ast.sexpr"),
             error_file: entry.join("error.txt"),
         }
     }
 
-    fn code(&self) -> String {
-        std::fs::read_to_string(&self.code_file).unwrap_or_default()
+    fn code(&self) -> Vec<u8> {
+        std::fs::read(&self.code_file).unwrap_or_default()
     }
 
     fn validate(&self) -> io::Result<()> {
@@ -48,11 +53,11 @@ impl TestFixture {
             ));
         }
 
-        if !self.ast_file.exists() && !self.error_file.exists() {
+        if !self.ast_file.exists() && !self.sexpr_file.exists() && !self.error_file.exists() {
             return Err(io::Error::new(
                 io::ErrorKind::NotFound,
                 format!(
-                    "Test fixture {} must have either an ast.txt or error.txt file",
+                    "Test fixture {} must have either an ast.txt, ast.sexpr or error.txt file",
                     self.fixture
                 ),
             ));
@@ -63,9 +68,10 @@ impl TestFixture {
 
     fn expected(&self) -> ExpectedTestResult {
         let ast = std::fs::read_to_string(&self.ast_file).unwrap_or_default();
+        let sexpr = std::fs::read_to_string(&self.sexpr_file).unwrap_or_default();
         let error = std::fs::read_to_string(&self.error_file).unwrap_or_default();
 
-        ExpectedTestResult { ast, error }
+        ExpectedTestResult { ast, sexpr, error }
     }
 }
 
@@ -107,14 +113,38 @@ fn run_test(test_fixture: &TestFixture) -> io::Result<()> {
         );
     }
 
+    if !expected.sexpr.is_empty() {
+        let ast = pxp_parser::parse(&code).unwrap();
+        assert_str_eq!(
+            expected.sexpr.trim(),
+            ast.to_sexpr(),
+            "ast mismatch for fixture `{}`",
+            test_fixture.fixture
+        );
+    }
+
+    if let Ok(ast) = pxp_parser::parse(&code) {
+        if let Err(violation) = ast.check_span_invariants() {
+            panic!(
+                "span violation in fixture `{}`: {}",
+                test_fixture.fixture, violation
+            );
+        }
+    }
+
     if !expected.error.is_empty() {
         let error = pxp_parser::parse(&code).err().unwrap();
 
         assert_str_eq!(
             expected.error.trim(),
-            (error.report(&code, Some("code.php"), false, true)?)
-                .to_string()
-                .trim(),
+            (error.report(
+                &String::from_utf8_lossy(&code),
+                Some("code.php"),
+                false,
+                true
+            )?)
+            .to_string()
+            .trim(),
             "error mismatch for fixture `{}`",
             test_fixture.fixture
         );
@@ -0,0 +1,315 @@
+use std::env;
+use std::fs::read_dir;
+use std::path::Path;
+use std::path::PathBuf;
+
+use pretty_assertions::assert_str_eq;
+use pxp_parser::ParserConfig;
+use pxp_parser::PhpVersion;
+
+/// A fixture of `tests/versions`, described by the comments at the top of the file:
+///
+/// ```php
+/// // feature: arrow functions
+/// // since: 7.4
+/// // until: 7.4
+/// // known-failing: why the parser doesn't get it right yet
+/// ```
+///
+/// The fixture should parse with every version from `since` to `until`, or to the
+/// latest version, and fail with every other. A `known-failing` fixture documents a
+/// gap in the parser, and has to disagree with its expectation for at least one
+/// version, so the comment is removed once the gap is closed.
+struct VersionFixture {
+    name: String,
+    feature: String,
+    since: PhpVersion,
+    until: PhpVersion,
+    known_failing: Option<String>,
+    code: Vec<u8>,
+}
+
+impl VersionFixture {
+    fn new(path: &Path) -> Self {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let code = std::fs::read(path).unwrap();
+
+        let mut feature = None;
+        let mut since = None;
+        let mut until = PhpVersion::LATEST;
+        let mut known_failing = None;
+
+        for line in String::from_utf8_lossy(&code).lines() {
+            let Some((key, value)) = line
+                .strip_prefix("// ")
+                .and_then(|comment| comment.split_once(": "))
+            else {
+                continue;
+            };
+
+            match key {
+                "feature" => feature = Some(value.to_string()),
+                "since" => since = Some(version(&name, value)),
+                "until" => until = version(&name, value),
+                "known-failing" => known_failing = Some(value.to_string()),
+                _ => panic!("unknown header `{}` in fixture `{}`", key, name),
+            }
+        }
+
+        Self {
+            feature: feature.unwrap_or_else(|| panic!("fixture `{}` has no feature", name)),
+            since: since.unwrap_or_else(|| panic!("fixture `{}` has no minimum version", name)),
+            until,
+            known_failing,
+            code,
+            name,
+        }
+    }
+
+    fn expected(&self, version: PhpVersion) -> bool {
+        self.since <= version && version <= self.until
+    }
+
+    fn parses(&self, version: PhpVersion) -> bool {
+        let config = ParserConfig::new().php_version(version);
+
+        pxp_parser::parse_with_warnings(&self.code, config).is_ok()
+    }
+}
+
+fn version(fixture: &str, value: &str) -> PhpVersion {
+    *PhpVersion::ALL
+        .iter()
+        .find(|version| version.to_string() == format!("PHP {}", value))
+        .unwrap_or_else(|| panic!("unknown version `{}` in fixture `{}`", value, fixture))
+}
+
+fn fixtures(manifest: &Path) -> Vec<VersionFixture> {
+    let mut entries = read_dir(manifest.join("tests/versions"))
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|entry| {
+            entry
+                .extension()
+                .is_some_and(|extension| extension == "php")
+        })
+        .collect::<Vec<PathBuf>>();
+
+    entries.sort();
+
+    entries
+        .iter()
+        .map(|entry| VersionFixture::new(entry))
+        .collect()
+}
+
+/// The outcome of every fixture under every version, as a markdown table: `✓` when
+/// the fixture parses and `✗` when it fails, followed by `!` when that is wrong.
+fn matrix(fixtures: &[VersionFixture], outcomes: &[Vec<bool>]) -> String {
+    let mut matrix = String::from(
+        "# PHP version matrix\n\n\
+         Generated by `tests/versions.rs` from the fixtures in this directory: `✓` when a\n\
+         fixture parses when targeting a version and `✗` when it fails, followed by `!` when\n\
+         PHP would do otherwise. Run `UPDATE_MATRIX=1 cargo test --test versions` to update it.\n\n",
+    );
+
+    matrix.push_str("| Fixture | Feature |");
+    for version in PhpVersion::ALL {
+        matrix.push_str(&format!(
+            " {} |",
+            version.to_string().trim_start_matches("PHP ")
+        ));
+    }
+    matrix.push_str("\n| --- | --- |");
+    matrix.push_str(&" :---: |".repeat(PhpVersion::ALL.len()));
+    matrix.push('\n');
+
+    for (fixture, outcomes) in fixtures.iter().zip(outcomes) {
+        matrix.push_str(&format!("| `{}` | {} |", fixture.name, fixture.feature));
+
+        for (version, parses) in PhpVersion::ALL.iter().zip(outcomes) {
+            let cell = if *parses { "✓" } else { "✗" };
+            let wrong = if fixture.expected(*version) == *parses {
+                ""
+            } else {
+                "!"
+            };
+
+            matrix.push_str(&format!(" {}{} |", cell, wrong));
+        }
+
+        matrix.push('\n');
+    }
+
+    let supported = PhpVersion::ALL
+        .iter()
+        .enumerate()
+        .filter(|(index, version)| {
+            fixtures
+                .iter()
+                .zip(outcomes)
+                .all(|(fixture, outcomes)| fixture.expected(**version) == outcomes[*index])
+        })
+        .map(|(_, version)| version.to_string())
+        .collect::<Vec<String>>();
+
+    matrix.push_str(&format!(
+        "\nFully supported: {}.\n",
+        if supported.is_empty() {
+            "none".to_string()
+        } else {
+            supported.join(", ")
+        }
+    ));
+
+    let known_failing = fixtures
+        .iter()
+        .filter_map(|fixture| {
+            fixture
+                .known_failing
+                .as_ref()
+                .map(|reason| format!("- `{}`: {}\n", fixture.name, reason))
+        })
+        .collect::<String>();
+
+    if !known_failing.is_empty() {
+        matrix.push_str(&format!("\nKnown failures:\n\n{}", known_failing));
+    }
+
+    matrix
+}
+
+#[test]
+fn test_version_fixtures() {
+    let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let fixtures = fixtures(&manifest);
+
+    let outcomes = fixtures
+        .iter()
+        .map(|fixture| {
+            PhpVersion::ALL
+                .iter()
+                .map(|version| fixture.parses(*version))
+                .collect::<Vec<bool>>()
+        })
+        .collect::<Vec<Vec<bool>>>();
+
+    let mut failures = vec![];
+    for (fixture, outcomes) in fixtures.iter().zip(&outcomes) {
+        let wrong = PhpVersion::ALL
+            .iter()
+            .zip(outcomes)
+            .filter(|(version, parses)| fixture.expected(**version) != **parses)
+            .map(|(version, _)| version.to_string())
+            .collect::<Vec<String>>();
+
+        match &fixture.known_failing {
+            None if !wrong.is_empty() => failures.push(format!(
+                "fixture `{}` has the wrong outcome with {}",
+                fixture.name,
+                wrong.join(", ")
+            )),
+            Some(_) if wrong.is_empty() => failures.push(format!(
+                "fixture `{}` is marked as known-failing, but has the right outcome with every version",
+                fixture.name
+            )),
+            _ => {}
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+
+    let matrix = matrix(&fixtures, &outcomes);
+    let matrix_file = manifest.join("tests/versions/MATRIX.md");
+
+    if env::var_os("UPDATE_MATRIX").is_some() {
+        std::fs::write(&matrix_file, &matrix).unwrap();
+    }
+
+    assert_str_eq!(
+        std::fs::read_to_string(&matrix_file).unwrap_or_default(),
+        matrix,
+        "`tests/versions/MATRIX.md` is out of date, run with `UPDATE_MATRIX=1` to update it"
+    );
+}
+
+/// The version a fixture needs, found from its syntax, should be the oldest version it
+/// parses with, except for the trailing commas that a program doesn't keep.
+#[test]
+fn test_required_versions() {
+    let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let unkept = [
+        "7.2-trailing-comma-in-grouped-uses",
+        "7.3-trailing-comma-in-arguments",
+    ];
+
+    let mut failures = vec![];
+    for fixture in fixtures(&manifest) {
+        if fixture.known_failing.is_some()
+            || fixture.until != PhpVersion::LATEST
+            || unkept.contains(&fixture.name.as_str())
+        {
+            continue;
+        }
+
+        let program = pxp_parser::parse(&fixture.code).unwrap();
+        let required = pxp_parser::analysis::required_version(&program).version;
+
+        if required != fixture.since {
+            failures.push(format!(
+                "fixture `{}` requires {}, but is found to require {}",
+                fixture.name, fixture.since, required
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// A fixture should fail with the version before the one it needs because it uses a
+/// feature that the version doesn't support, with an error that names the feature and
+/// the version.
+#[test]
+fn test_unsupported_feature_errors() {
+    let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+
+    let mut failures = vec![];
+    for fixture in fixtures(&manifest) {
+        if fixture.known_failing.is_some() || fixture.since == PhpVersion::OLDEST {
+            continue;
+        }
+
+        let index = PhpVersion::ALL
+            .iter()
+            .position(|version| *version == fixture.since)
+            .unwrap();
+        let version = PhpVersion::ALL[index - 1];
+        let config = ParserConfig::new().php_version(version);
+
+        let Err(stack) = pxp_parser::parse_with_warnings(&fixture.code, config) else {
+            continue;
+        };
+
+        let suffix = format!(" in {}", version);
+        if !stack
+            .errors
+            .iter()
+            .any(|error| error.id == "E075" && error.message.ends_with(&suffix))
+        {
+            failures.push(format!(
+                "fixture `{}` fails with {} without an unsupported feature: {}",
+                fixture.name,
+                version,
+                stack
+                    .errors
+                    .iter()
+                    .map(|error| error.message.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,10 @@
+<?php
+
+// feature: anonymous classes
+// since: 7.0
+
+$logger = new class {
+    public function log($message) {
+        echo $message;
+    }
+};
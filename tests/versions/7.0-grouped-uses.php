@@ -0,0 +1,6 @@
+<?php
+
+// feature: grouped `use` statements
+// since: 7.0
+
+use App\Models\{User, Post as Article};
@@ -0,0 +1,6 @@
+<?php
+
+// feature: the `??` operator
+// since: 7.0
+
+$name = $_GET['name'] ?? 'anonymous';
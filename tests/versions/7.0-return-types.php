@@ -0,0 +1,8 @@
+<?php
+
+// feature: return types
+// since: 7.0
+
+function name(): string {
+    return 'name';
+}
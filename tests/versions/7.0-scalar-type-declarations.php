@@ -0,0 +1,8 @@
+<?php
+
+// feature: scalar type declarations
+// since: 7.0
+
+function add(int $a, float $b, string $c, bool $d) {
+    return $a + $b;
+}
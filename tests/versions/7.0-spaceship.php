@@ -0,0 +1,8 @@
+<?php
+
+// feature: the `<=>` operator
+// since: 7.0
+
+usort($items, function ($a, $b) {
+    return $a <=> $b;
+});
@@ -0,0 +1,10 @@
+<?php
+
+// feature: `throw` statements
+// since: 7.0
+
+if (!$user) {
+    throw new Exception('No user');
+}
+
+foreach ($errors as $error) throw $error;
@@ -0,0 +1,7 @@
+<?php
+
+// feature: unparenthesized nested ternaries
+// since: 7.0
+// until: 7.4
+
+$result = $a ? $b : $c ? $d : $e;
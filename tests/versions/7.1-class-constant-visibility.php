@@ -0,0 +1,9 @@
+<?php
+
+// feature: class constant visibility modifiers
+// since: 7.1
+
+class Config {
+    private const SECRET = 'hunter2';
+    public const VERSION = '1.0';
+}
@@ -0,0 +1,6 @@
+<?php
+
+// feature: keys in list assignments
+// since: 7.1
+
+list('id' => $id, 'name' => $name) = $row;
@@ -0,0 +1,10 @@
+<?php
+
+// feature: catching multiple exception types
+// since: 7.1
+
+try {
+    run();
+} catch (InvalidArgumentException | RuntimeException $e) {
+    report($e);
+}
@@ -0,0 +1,8 @@
+<?php
+
+// feature: nullable types
+// since: 7.1
+
+function find(?int $id): ?User {
+    return null;
+}
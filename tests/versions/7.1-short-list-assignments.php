@@ -0,0 +1,6 @@
+<?php
+
+// feature: short list assignments
+// since: 7.1
+
+[$first, $second] = $pair;
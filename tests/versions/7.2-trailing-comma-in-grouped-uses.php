@@ -0,0 +1,9 @@
+<?php
+
+// feature: trailing commas in grouped `use` statements
+// since: 7.2
+
+use App\Models\{
+    User,
+    Post,
+};
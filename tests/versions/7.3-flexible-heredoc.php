@@ -0,0 +1,11 @@
+<?php
+
+// feature: indented heredoc closing markers
+// since: 7.3
+// known-failing: the lexer accepts indented closing markers in every version
+
+function template() {
+    return <<<HTML
+        <p>Hello</p>
+        HTML;
+}
@@ -0,0 +1,7 @@
+<?php
+
+// feature: references in list assignments
+// since: 7.3
+
+[$first, &$second] = $pair;
+list('total' => &$total) = $totals;
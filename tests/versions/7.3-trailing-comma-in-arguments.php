@@ -0,0 +1,9 @@
+<?php
+
+// feature: trailing commas in argument lists
+// since: 7.3
+
+$total = sum(
+    $subtotal,
+    $tax,
+);
@@ -0,0 +1,7 @@
+<?php
+
+// feature: arrow functions
+// since: 7.4
+
+$double = fn ($x) => $x * 2;
+$triple = static fn (int $x): int => $x * 3;
@@ -0,0 +1,6 @@
+<?php
+
+// feature: the `??=` operator
+// since: 7.4
+
+$options['timeout'] ??= 30;
@@ -0,0 +1,7 @@
+<?php
+
+// feature: numeric literal separators
+// since: 7.4
+// known-failing: the lexer removes the separators before the parser sees the literal
+
+$million = 1_000_000;
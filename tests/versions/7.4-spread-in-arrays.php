@@ -0,0 +1,6 @@
+<?php
+
+// feature: unpacking inside arrays
+// since: 7.4
+
+$all = [...$defaults, 'extra', ...$overrides];
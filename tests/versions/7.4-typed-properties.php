@@ -0,0 +1,9 @@
+<?php
+
+// feature: typed properties
+// since: 7.4
+
+class User {
+    public int $id;
+    protected ?string $name = null;
+}
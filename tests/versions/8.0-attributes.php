@@ -0,0 +1,10 @@
+<?php
+
+// feature: attributes
+// since: 8.0
+// known-failing: attributes are always lexed as attributes, while older versions read `#` as the start of a comment
+
+#[
+    Route('/home')
+]
+function home() {}
@@ -0,0 +1,10 @@
+<?php
+
+// feature: catching exceptions without a variable
+// since: 8.0
+
+try {
+    connect();
+} catch (ConnectionException) {
+    retry();
+}
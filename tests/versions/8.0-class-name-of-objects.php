@@ -0,0 +1,6 @@
+<?php
+
+// feature: `::class` on objects
+// since: 8.0
+
+$class = $object::class;
@@ -0,0 +1,11 @@
+<?php
+
+// feature: constructor property promotion
+// since: 8.0
+
+class Point {
+    public function __construct(
+        public int $x = 0,
+        private int $y = 0
+    ) {}
+}
@@ -0,0 +1,10 @@
+<?php
+
+// feature: `match` expressions
+// since: 8.0
+
+$label = match ($status) {
+    200, 201 => 'ok',
+    404 => 'not found',
+    default => 'error',
+};
@@ -0,0 +1,6 @@
+<?php
+
+// feature: named arguments
+// since: 8.0
+
+$text = htmlspecialchars($value, double_encode: false);
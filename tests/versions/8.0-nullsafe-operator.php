@@ -0,0 +1,6 @@
+<?php
+
+// feature: the `?->` operator
+// since: 8.0
+
+$country = $session?->user?->getAddress()?->country;
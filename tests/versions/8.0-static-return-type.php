@@ -0,0 +1,10 @@
+<?php
+
+// feature: the `static` return type
+// since: 8.0
+
+class Model {
+    public static function create(): static {
+        return new static();
+    }
+}
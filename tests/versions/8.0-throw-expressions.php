@@ -0,0 +1,6 @@
+<?php
+
+// feature: `throw` expressions
+// since: 8.0
+
+$user = $repository->find($id) ?? throw new NotFoundException();
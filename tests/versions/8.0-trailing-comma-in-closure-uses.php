@@ -0,0 +1,9 @@
+<?php
+
+// feature: trailing commas in closure `use` lists
+// since: 8.0
+
+$callback = function () use (
+    $first,
+    $second,
+) {};
@@ -0,0 +1,9 @@
+<?php
+
+// feature: trailing commas in parameter lists
+// since: 8.0
+
+function connect(
+    string $host,
+    int $port,
+) {}
@@ -0,0 +1,8 @@
+<?php
+
+// feature: union types
+// since: 8.0
+
+function parse(int|string $value): int|float {
+    return $value;
+}
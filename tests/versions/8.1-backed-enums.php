@@ -0,0 +1,9 @@
+<?php
+
+// feature: backed enums
+// since: 8.1
+
+enum Status: string {
+    case Active = 'active';
+    case Inactive = 'inactive';
+}
@@ -0,0 +1,9 @@
+<?php
+
+// feature: enums
+// since: 8.1
+
+enum Suit {
+    case Hearts;
+    case Spades;
+}
@@ -0,0 +1,6 @@
+<?php
+
+// feature: the `0o` octal prefix
+// since: 8.1
+
+$permissions = 0o755;
@@ -0,0 +1,8 @@
+<?php
+
+// feature: final class constants
+// since: 8.1
+
+class Config {
+    final public const VERSION = '1.0';
+}
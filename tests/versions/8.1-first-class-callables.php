@@ -0,0 +1,8 @@
+<?php
+
+// feature: first-class callable syntax
+// since: 8.1
+
+$strlen = strlen(...);
+$method = $object->method(...);
+$static = Foo::bar(...);
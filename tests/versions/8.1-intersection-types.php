@@ -0,0 +1,8 @@
+<?php
+
+// feature: intersection types
+// since: 8.1
+
+function count_items(Iterator&Countable $items): int {
+    return count($items);
+}
@@ -0,0 +1,8 @@
+<?php
+
+// feature: the `never` type
+// since: 8.1
+
+function fail(string $message): never {
+    throw new Exception($message);
+}
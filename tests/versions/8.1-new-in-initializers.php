@@ -0,0 +1,6 @@
+<?php
+
+// feature: `new` in initializers
+// since: 8.1
+
+function log($message, $logger = new NullLogger()) {}
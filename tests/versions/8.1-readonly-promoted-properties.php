@@ -0,0 +1,8 @@
+<?php
+
+// feature: readonly promoted properties
+// since: 8.1
+
+class Money {
+    public function __construct(public readonly int $amount) {}
+}
@@ -0,0 +1,8 @@
+<?php
+
+// feature: readonly properties
+// since: 8.1
+
+class Money {
+    public readonly int $amount;
+}
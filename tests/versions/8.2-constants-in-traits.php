@@ -0,0 +1,8 @@
+<?php
+
+// feature: constants in traits
+// since: 8.2
+
+trait HasVersion {
+    public const VERSION = 2;
+}
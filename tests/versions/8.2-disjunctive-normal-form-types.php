@@ -0,0 +1,6 @@
+<?php
+
+// feature: disjunctive normal form types
+// since: 8.2
+
+function render((Stringable&Countable)|string $value) {}
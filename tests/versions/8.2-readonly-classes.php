@@ -0,0 +1,8 @@
+<?php
+
+// feature: readonly classes
+// since: 8.2
+
+readonly class Point {
+    public function __construct(public int $x, public int $y) {}
+}
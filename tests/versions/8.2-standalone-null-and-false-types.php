@@ -0,0 +1,12 @@
+<?php
+
+// feature: standalone `null` and `false` types
+// since: 8.2
+
+function nothing(): null {
+    return null;
+}
+
+function failure(): false {
+    return false;
+}
@@ -0,0 +1,8 @@
+<?php
+
+// feature: the `true` type
+// since: 8.2
+
+function success(): true {
+    return true;
+}
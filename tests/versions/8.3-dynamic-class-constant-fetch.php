@@ -0,0 +1,6 @@
+<?php
+
+// feature: dynamic class constant fetches
+// since: 8.3
+
+$value = Config::{$name};
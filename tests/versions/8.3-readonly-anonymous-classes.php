@@ -0,0 +1,9 @@
+<?php
+
+// feature: readonly anonymous classes
+// since: 8.3
+// known-failing: readonly anonymous classes aren't supported yet
+
+$point = new readonly class {
+    public int $x = 0;
+};
@@ -0,0 +1,8 @@
+<?php
+
+// feature: typed class constants
+// since: 8.3
+
+class Config {
+    const string VERSION = '1.0';
+}